@@ -0,0 +1,35 @@
+//! A 10,000-line batch where 90% of the lines repeat earlier CIDRs, as in
+//! log-derived lists: computing every entry against reusing the first result.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use ipcalc::batch::process_batch_with_options;
+
+fn cidrs() -> Vec<String> {
+    // 1,000 distinct CIDRs, each seen ten times; IPv6 repeats alternate
+    // their hex digit case, which still matches
+    (0..10_000u32)
+        .map(|i| {
+            let n = i % 1000;
+            match (n % 2, (i / 1000) % 2) {
+                (0, _) => format!("10.{}.{}.0/24", n / 256, n % 256),
+                (_, 0) => format!("2001:db8:{:x}::/48", n),
+                _ => format!("2001:DB8:{:X}::/48", n),
+            }
+        })
+        .collect()
+}
+
+fn bench_batch_cache(c: &mut Criterion) {
+    let cidrs = cidrs();
+
+    c.bench_function("batch, every entry computed", |b| {
+        b.iter(|| black_box(process_batch_with_options(&cidrs, cidrs.len(), false, false).unwrap()))
+    });
+
+    c.bench_function("batch, repeats reused", |b| {
+        b.iter(|| black_box(process_batch_with_options(&cidrs, cidrs.len(), false, true).unwrap()))
+    });
+}
+
+criterion_group!(benches, bench_batch_cache);
+criterion_main!(benches);
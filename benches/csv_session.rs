@@ -0,0 +1,49 @@
+//! Many single-subnet results as one CSV table: concatenating each result's
+//! own CSV (a writer and header per subnet) against one shared `CsvSession`.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use ipcalc::ipv4::Ipv4Subnet;
+use ipcalc::output::{CsvOutput, CsvSession};
+use std::net::Ipv4Addr;
+
+fn subnets() -> Vec<Ipv4Subnet> {
+    (0..1000u32)
+        .map(|i| {
+            let cidr = format!("{}/30", Ipv4Addr::from(0x0a00_0000 + (i << 2)));
+            Ipv4Subnet::from_cidr(&cidr).unwrap()
+        })
+        .collect()
+}
+
+fn bench_csv(c: &mut Criterion) {
+    let subnets = subnets();
+
+    c.bench_function("to_csv per subnet, headers stripped", |b| {
+        b.iter(|| {
+            let mut out = String::new();
+            for (i, subnet) in subnets.iter().enumerate() {
+                let csv = subnet.to_csv().unwrap();
+                let body = if i == 0 {
+                    &csv[..]
+                } else {
+                    csv.split_once('\n').map_or("", |(_, rows)| rows)
+                };
+                out.push_str(body);
+            }
+            black_box(out)
+        })
+    });
+
+    c.bench_function("one CsvSession", |b| {
+        b.iter(|| {
+            let mut session = CsvSession::new(Vec::new());
+            for subnet in &subnets {
+                session.append(subnet).unwrap();
+            }
+            black_box(session.finish().unwrap())
+        })
+    });
+}
+
+criterion_group!(benches, bench_csv);
+criterion_main!(benches);
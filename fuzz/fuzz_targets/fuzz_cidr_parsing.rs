@@ -6,5 +6,20 @@ fuzz_target!(|data: &[u8]| {
     if let Ok(s) = std::str::from_utf8(data) {
         let _ = ipcalc::Ipv4Subnet::from_cidr(s);
         let _ = ipcalc::Ipv6Subnet::from_cidr(s);
+        let _ = ipcalc::validation::validate_cidr(s);
+        let _ = ipcalc::validation::parse_cidr_parts(s.trim());
+        let _ = ipcalc::process_batch(&[s.to_string()]);
+
+        // Whitespace and sign mutations around the prefix separator
+        for variant in [
+            format!(" {s}\n"),
+            s.replacen('/', "/ ", 1),
+            s.replacen('/', "/+", 1),
+            s.replacen('/', "/-", 1),
+        ] {
+            let _ = ipcalc::Ipv4Subnet::from_cidr(&variant);
+            let _ = ipcalc::Ipv6Subnet::from_cidr(&variant);
+            let _ = ipcalc::validation::validate_cidr(&variant);
+        }
     }
 });
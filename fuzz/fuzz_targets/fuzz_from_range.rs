@@ -7,8 +7,12 @@ fuzz_target!(|data: &[u8]| {
         if let Some(idx) = s.find('\0') {
             let start = &s[..idx];
             let end = &s[idx + 1..];
-            let _ = ipcalc::from_range::from_range_ipv4(start, end);
-            let _ = ipcalc::from_range::from_range_ipv6(start, end);
+            if let Ok(result) = ipcalc::from_range::from_range_ipv4(start, end) {
+                ipcalc::verify::verify_from_range_ipv4(&result).unwrap();
+            }
+            if let Ok(result) = ipcalc::from_range::from_range_ipv6(start, end) {
+                ipcalc::verify::verify_from_range_ipv6(&result).unwrap();
+            }
         }
     }
 });
@@ -0,0 +1,74 @@
+use crate::error::Result;
+use crate::ipv4::Ipv4Subnet;
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// The base address and wildcard mask that match an IPv4 CIDR in a Cisco
+/// ACL, with a ready-to-paste `permit` line.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AclWildcardResult {
+    pub cidr: String,
+    pub base: String,
+    pub wildcard: String,
+    pub snippet: String,
+}
+
+// ---------------------------------------------------------------------------
+// Public entry point
+// ---------------------------------------------------------------------------
+
+/// ACL base and wildcard for an IPv4 CIDR (or bare address, taken as a /32).
+/// A /32 uses the `host` keyword instead of an all-zero wildcard.
+pub fn acl_wildcard(cidr: &str) -> Result<AclWildcardResult> {
+    let subnet = Ipv4Subnet::from_addr_or_cidr(cidr)?;
+    let snippet = if subnet.prefix_length == 32 {
+        format!("permit ip host {}", subnet.network)
+    } else {
+        format!("permit ip {} {}", subnet.network, subnet.wildcard)
+    };
+    Ok(AclWildcardResult {
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        base: subnet.network.to_string(),
+        wildcard: subnet.wildcard.to_string(),
+        snippet,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_octet_wildcard() {
+        let result = acl_wildcard("10.0.0.0/16").unwrap();
+        assert_eq!(result.base, "10.0.0.0");
+        assert_eq!(result.wildcard, "0.0.255.255");
+        assert_eq!(result.snippet, "permit ip 10.0.0.0 0.0.255.255");
+    }
+
+    #[test]
+    fn test_partial_octet_wildcard() {
+        // Host bits in the input are cleared from the base
+        let result = acl_wildcard("192.168.1.77/26").unwrap();
+        assert_eq!(result.cidr, "192.168.1.64/26");
+        assert_eq!(result.wildcard, "0.0.0.63");
+        assert_eq!(result.snippet, "permit ip 192.168.1.64 0.0.0.63");
+    }
+
+    #[test]
+    fn test_host_and_invalid() {
+        let result = acl_wildcard("10.1.2.3").unwrap();
+        assert_eq!(result.wildcard, "0.0.0.0");
+        assert_eq!(result.snippet, "permit ip host 10.1.2.3");
+        assert!(acl_wildcard("2001:db8::/32").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::summarize::{summarize_ipv4_with_limit, summarize_ipv6_with_limit};
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// Count of distinct addresses covered by a list of CIDRs.
+///
+/// `address_count` is a decimal string so IPv6 magnitudes are not truncated.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AddressCountResult {
+    pub input_count: usize,
+    pub block_count: usize,
+    pub address_count: String,
+    pub blocks: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Counting
+// ---------------------------------------------------------------------------
+
+/// Format `2^128 + rest` as a decimal string.
+///
+/// 2^128 does not fit in a u128; `rest` is at most the IPv4 total (2^32), so
+/// adding it to the low 20 digits never carries into the high part.
+pub(crate) fn format_full_ipv6_plus(rest: u128) -> String {
+    const HIGH: u128 = 3_402_823_669_209_384_634;
+    const LOW: u128 = 63_374_607_431_768_211_456;
+    format!("{}{:020}", HIGH, LOW + rest)
+}
+
+pub fn count_addresses(cidrs: &[String]) -> Result<AddressCountResult> {
+    count_addresses_with_limit(cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+/// Count distinct addresses across `cidrs` (IPv4 and/or IPv6).
+///
+/// Each family is summarized first, which removes duplicates and overlaps,
+/// then the sizes of the resulting disjoint blocks are summed.
+pub fn count_addresses_with_limit(
+    cidrs: &[String],
+    max_inputs: usize,
+) -> Result<AddressCountResult> {
+    if cidrs.is_empty() {
+        return Err(IpCalcError::EmptyCidrList);
+    }
+    if cidrs.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: cidrs.len(),
+            limit: max_inputs,
+        });
+    }
+
+    let (v6, v4): (Vec<String>, Vec<String>) = cidrs
+        .iter()
+        .map(|c| c.trim().to_string())
+        .partition(|c| c.contains(':'));
+
+    let mut blocks = Vec::new();
+    let mut v4_total: u128 = 0;
+    let mut v6_total: u128 = 0;
+    let mut full_ipv6 = false;
+
+    if !v4.is_empty() {
+        for subnet in summarize_ipv4_with_limit(&v4, max_inputs)?.cidrs {
+            v4_total += 1u128 << (32 - subnet.prefix_length);
+            blocks.push(format!("{}/{}", subnet.network, subnet.prefix_length));
+        }
+    }
+
+    if !v6.is_empty() {
+        for subnet in summarize_ipv6_with_limit(&v6, max_inputs)?.cidrs {
+            if subnet.prefix_length == 0 {
+                // ::/0 covers everything, so it is the only IPv6 block
+                full_ipv6 = true;
+            } else {
+                // Disjoint blocks short of ::/0 sum to at most 2^128 - 1
+                v6_total += 1u128 << (128 - subnet.prefix_length);
+            }
+            blocks.push(format!("{}/{}", subnet.network, subnet.prefix_length));
+        }
+    }
+
+    let address_count = if full_ipv6 {
+        format_full_ipv6_plus(v4_total)
+    } else {
+        match v6_total.checked_add(v4_total) {
+            Some(total) => total.to_string(),
+            // v6_total + v4_total - 2^128, without leaving u128
+            None => format_full_ipv6_plus(v4_total - (u128::MAX - v6_total) - 1),
+        }
+    };
+
+    Ok(AddressCountResult {
+        input_count: cidrs.len(),
+        block_count: blocks.len(),
+        address_count,
+        blocks,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_overlapping_inputs_count_union() {
+        let result = count_addresses(&strings(&[
+            "10.0.0.0/24",
+            "10.0.0.128/25",
+            "192.168.0.0/24",
+        ]))
+        .unwrap();
+        // 256 + 256, not 256 + 128 + 256
+        assert_eq!(result.address_count, "512");
+        assert_eq!(result.input_count, 3);
+        assert_eq!(result.blocks, ["10.0.0.0/24", "192.168.0.0/24"]);
+    }
+
+    #[test]
+    fn test_ipv6_magnitude() {
+        let result = count_addresses(&strings(&["2001:db8::/32", "2001:db8:1::/48"])).unwrap();
+        assert_eq!(result.address_count, (1u128 << 96).to_string());
+        assert_eq!(result.block_count, 1);
+    }
+
+    #[test]
+    fn test_mixed_families_and_full_ipv6() {
+        let result = count_addresses(&strings(&["::/0", "10.0.0.0/30"])).unwrap();
+        assert_eq!(
+            result.address_count,
+            "340282366920938463463374607431768211460"
+        );
+    }
+
+    #[test]
+    fn test_ipv6_just_short_of_full_plus_ipv4_does_not_overflow() {
+        // ::1 through ffff:...:ffff covers 2^128 - 1 addresses in 128 blocks
+        let mut cidrs: Vec<String> = crate::from_range::from_range_ipv6(
+            "::1",
+            "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+            crate::ipv6::Ipv6Format::default(),
+        )
+        .unwrap()
+        .cidrs
+        .iter()
+        .map(|s| format!("{}/{}", s.network, s.prefix_length))
+        .collect();
+        assert_eq!(cidrs.len(), 128);
+        cidrs.push("0.0.0.0/0".to_string());
+
+        let result = count_addresses(&cidrs).unwrap();
+        // 2^128 - 1 + 2^32
+        assert_eq!(
+            result.address_count,
+            "340282366920938463463374607436063178751"
+        );
+        assert_eq!(result.block_count, 129);
+    }
+
+    #[test]
+    fn test_empty_and_invalid() {
+        assert!(matches!(
+            count_addresses(&[]),
+            Err(IpCalcError::EmptyCidrList)
+        ));
+        assert!(count_addresses(&strings(&["not-a-cidr"])).is_err());
+    }
+}
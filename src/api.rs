@@ -3,8 +3,10 @@ use std::time::Duration;
 
 use axum::{
     Extension, Router,
-    extract::Query,
+    body::{Body, to_bytes},
+    extract::{Query, Request, State},
     http::{HeaderValue, StatusCode, header},
+    middleware::{self, Next},
     response::{IntoResponse, Json, Response},
     routing::{get, post},
 };
@@ -21,25 +23,111 @@ use utoipa::{IntoParams, OpenApi, ToSchema};
 use utoipa_swagger_ui::SwaggerUi;
 
 #[cfg(feature = "swagger")]
-use crate::batch::BatchResult;
-use crate::batch::process_batch_with_limit;
-use crate::config::ServerConfig;
+use crate::addresses::AddressCountResult;
+use crate::addresses::count_addresses_with_limit;
 #[cfg(feature = "swagger")]
-use crate::contains::ContainsResult;
-use crate::contains::{check_ipv4_contains, check_ipv6_contains};
+use crate::arithmetic::IpAddResult;
+use crate::arithmetic::add_ipv4;
+#[cfg(feature = "swagger")]
+use crate::available::{AllocateResult, AvailableResult};
+use crate::available::{
+    allocate_v4_with_limit, allocate_v6_with_limit, find_available_v4_with_limit,
+    find_available_v6_with_limit,
+};
+#[cfg(feature = "swagger")]
+use crate::batch::{AggregateSummary, BatchResult};
+use crate::batch::{BatchFilter, process_batch_with_options};
+use crate::client_ip::{ClientIp, ClientIpResolver, client_ip_middleware};
+#[cfg(feature = "swagger")]
+use crate::complement::{Ipv4ComplementResult, Ipv6ComplementResult};
+use crate::complement::{complement_ipv4, complement_ipv6};
+use crate::config::{Limits, ServerConfig};
+#[cfg(feature = "swagger")]
+use crate::contains::{
+    AddressTranslation, ContainmentRelationship, ContainsResult, SubnetContainsResult,
+};
+use crate::contains::{
+    check_contains_translated, check_ipv4_contains, check_ipv4_subnet_contains,
+    check_ipv6_contains, check_ipv6_subnet_contains,
+};
+#[cfg(feature = "swagger")]
+use crate::covers::CoverageResult;
+use crate::covers::check_coverage_with_limit;
+use crate::dns::resolve_contains;
 use crate::error::IpCalcError;
 #[cfg(feature = "swagger")]
+use crate::eui64::Eui64Result;
+use crate::eui64::derive_eui64;
+#[cfg(feature = "swagger")]
+use crate::exclude::{Ipv4ExcludeResult, Ipv6ExcludeResult};
+use crate::exclude::{exclude_ipv4_with_limit, exclude_ipv6_with_limit};
+#[cfg(feature = "swagger")]
 use crate::from_range::{Ipv4FromRangeResult, Ipv6FromRangeResult};
 use crate::from_range::{from_range_ipv4_with_limit, from_range_ipv6_with_limit};
+#[cfg(feature = "swagger")]
+use crate::gaps::{FreeRange, GapsResult};
+use crate::gaps::{find_gaps_v4_with_limit, find_gaps_v6_with_limit};
+use crate::grouped_summary::summarize_grouped_with_limit;
+#[cfg(feature = "swagger")]
+use crate::grouped_summary::{GroupedSummaryResult, TagGroup};
+#[cfg(feature = "swagger")]
+use crate::hosts::HostsResult;
+use crate::hosts::{list_hosts_v4, list_hosts_v6};
+#[cfg(feature = "swagger")]
+use crate::ifid::InterfaceIdResult;
+use crate::ifid::split_interface_id;
 use crate::ipv4::Ipv4Subnet;
-use crate::ipv6::Ipv6Subnet;
-use crate::output::{CsvOutput, OutputFormat, TextOutput};
 #[cfg(feature = "swagger")]
-use crate::subnet_generator::{Ipv4SubnetList, Ipv6SubnetList, SplitSummary};
-use crate::subnet_generator::{count_subnets, generate_ipv4_subnets, generate_ipv6_subnets};
+use crate::ipv6::Ipv6FormatResult;
+use crate::ipv6::{Ipv6Case, Ipv6Format, Ipv6Style, Ipv6Subnet, expand_ipv6};
+#[cfg(feature = "swagger")]
+use crate::lpm::LpmResult;
+use crate::lpm::{lpm_ipv4_with_limit, lpm_ipv6_with_limit, lpm_with_limit};
+use crate::output::{
+    CsvOutput, JsonCase, Kinded, OutputFormat, ResultKind, TextOutput, camel_case_json, to_yaml,
+};
+#[cfg(feature = "csv")]
+use crate::output::{ipv4_split_csv_chunks, ipv6_split_csv_chunks};
+#[cfg(feature = "swagger")]
+use crate::overlap::{OverlapKind, OverlapPair};
+use crate::overlap::{
+    OverlapResult, find_overlapping_pairs_v4_with_limit, find_overlaps_with_limit,
+};
+#[cfg(feature = "swagger")]
+use crate::reverse_dns::{PtrStub, ReverseDnsResult};
+use crate::reverse_dns::{reverse_dns_ipv4, reverse_dns_ipv6};
+use crate::special_purpose::special_purpose_blocks;
+#[cfg(feature = "swagger")]
+use crate::special_purpose::{AllocationAttributes, SpecialPurposeEntry};
+#[cfg(feature = "swagger")]
+use crate::subnet_generator::{
+    DualStackPair, DualStackPlan, Ipv4SubnetList, Ipv6SubnetList, SplitSummary,
+};
+use crate::subnet_generator::{
+    count_subnets, generate_dualstack_subnets, generate_ipv4_subnets_from,
+    generate_ipv6_subnets_from, nth_ipv4_subnet, nth_ipv6_subnet, page_count, random_seed,
+};
+#[cfg(feature = "csv")]
+use crate::subnet_generator::{iter_ipv4_subnets_from, iter_ipv6_subnets_from};
 #[cfg(feature = "swagger")]
 use crate::summarize::{Ipv4SummaryResult, Ipv6SummaryResult};
 use crate::summarize::{summarize_ipv4_with_limit, summarize_ipv6_with_limit};
+#[cfg(feature = "swagger")]
+use crate::supernet::SupernetResult;
+use crate::supernet::{
+    find_common_supernet_v4, find_common_supernet_v6, find_supernet_v4_with_limit,
+    find_supernet_v6_with_limit,
+};
+#[cfg(feature = "swagger")]
+use crate::usable::{AddressRole, Ipv4NthHostResult, UsabilityResult};
+use crate::usable::{check_ipv4_host, check_ipv4_usable, check_ipv6_host, nth_host_v4};
+use crate::validation::with_host_prefix;
+use crate::vlsm::allocate_vlsm;
+#[cfg(feature = "swagger")]
+use crate::vlsm::{VlsmAllocation, VlsmResult};
+#[cfg(feature = "swagger")]
+use crate::walk::Ipv4WalkResult;
+use crate::walk::walk_ipv4;
 
 #[cfg(feature = "swagger")]
 use crate::ipam::models::{
@@ -55,17 +143,59 @@ use crate::ipam_api::{AllocateSpecificRequest, AutoAllocateBody, IpamErrorRespon
     paths(
         health,
         version,
+        limits,
         calculate_ipv4,
         calculate_ipv6,
+        next_subnet_ipv4,
+        prev_subnet_ipv4,
         split_ipv4,
         split_ipv6,
+        dualstack_split_handler,
         contains_ipv4,
         contains_ipv6,
+        subnet_contains_ipv4,
+        subnet_contains_ipv6,
+        is_usable_ipv4,
+        host_check_ipv4,
+        host_check_ipv6,
         summarize_ipv4_handler,
         summarize_ipv6_handler,
+        overlaps_ipv4_handler,
+        supernet_ipv4_handler,
+        supernet_ipv6_handler,
+        common_supernet_ipv4,
+        common_supernet_ipv6,
+        reverse_dns_ipv4_handler,
+        reverse_dns_ipv6_handler,
         from_range_ipv4_handler,
         from_range_ipv6_handler,
+        walk_ipv4_handler,
+        add_ipv4_handler,
+        hosts_ipv4_handler,
+        hosts_ipv6_handler,
+        nth_host_ipv4_handler,
+        complement_ipv4_handler,
+        complement_ipv6_handler,
+        exclude_ipv4_handler,
+        exclude_ipv6_handler,
+        gaps_ipv4_handler,
+        gaps_ipv6_handler,
+        allocate_ipv4_handler,
+        allocate_ipv6_handler,
+        lpm_ipv4_handler,
+        lpm_ipv6_handler,
+        eui64_handler,
+        ifid_handler,
+        ipv6_format_handler,
         batch_handler,
+        addresses_handler,
+        overlaps_handler,
+        summarize_grouped_handler,
+        covers_handler,
+        match_handler,
+        available_ipv4_handler,
+        available_ipv6_handler,
+        vlsm_ipv4_handler,
         crate::ipam_api::ipam_create_supernet,
         crate::ipam_api::ipam_list_supernets,
         crate::ipam_api::ipam_get_supernet,
@@ -85,10 +215,11 @@ use crate::ipam_api::{AllocateSpecificRequest, AutoAllocateBody, IpamErrorRespon
     ),
     components(
         schemas(
-            Ipv4Subnet, Ipv6Subnet, Ipv4SubnetList, Ipv6SubnetList, SplitSummary,
-            ContainsResult, Ipv4SummaryResult, Ipv6SummaryResult, Ipv4FromRangeResult,
-            Ipv6FromRangeResult, SubnetQuery, SplitQuery, ContainsQuery, SummarizeQuery,
-            FromRangeQuery, BatchRequest, BatchResult, ErrorResponse, VersionResponse,
+            Ipv4Subnet, SpecialPurposeEntry, AllocationAttributes, Ipv6Subnet, Ipv4SubnetList, Ipv6SubnetList, SplitSummary,
+            DualStackPlan, DualStackPair, DualStackQuery,
+            ContainsResult, AddressTranslation, SubnetContainsQuery, SubnetContainsResult, ContainmentRelationship, UsabilityResult, AddressRole, HostCheckQuery, Ipv4SummaryResult, Ipv6SummaryResult, Ipv4FromRangeResult,
+            Ipv6FromRangeResult, SubnetQuery, CommonSupernetQuery, SplitQuery, ContainsQuery, SummarizeQuery,
+            FromRangeQuery, WalkQuery, Ipv4WalkResult, AddQuery, IpAddResult, HostsQuery, HostsResult, NthHostQuery, Ipv4NthHostResult, Ipv4ComplementResult, Ipv6ComplementResult, ComplementQuery, Ipv4ExcludeResult, Ipv6ExcludeResult, ExcludeQuery, GapsQuery, GapsResult, FreeRange, AllocateQuery, AllocateResult, LpmQuery, LpmResult, Eui64Query, Eui64Result, IfidQuery, InterfaceIdResult, Ipv6FormatQuery, Ipv6FormatResult, BatchRequest, BatchResult, BatchFilter, AggregateSummary, AddressesRequest, AddressCountResult, OverlapsRequest, SummarizeGroupedRequest, GroupedSummaryResult, TagGroup, OverlapResult, OverlapPair, OverlapKind, SupernetResult, ReverseDnsQuery, ReverseDnsResult, PtrStub, CoversRequest, CoverageResult, MatchRequest, AvailableRequest, AvailableResult, VlsmRequest, VlsmResult, VlsmAllocation, ErrorResponse, PayloadTooLargeResponse, VersionResponse, Limits,
             Supernet, SupernetList, CreateSupernet, Allocation, AllocationList,
             AllocationStatus, Tag, UpdateAllocation, AllocateSpecificRequest,
             AutoAllocateBody, TagsBody, AuditEntry, AuditList, UtilizationReport,
@@ -103,13 +234,431 @@ use crate::ipam_api::{AllocateSpecificRequest, AutoAllocateBody, IpamErrorRespon
         title = "ipcalc API",
         version = env!("CARGO_PKG_VERSION"),
         description = "A fast IPv4 and IPv6 subnet calculator API with IP address management",
-    )
+    ),
+    modifiers(&ResultKindAddon)
 )]
 pub struct ApiDoc;
 
+/// Documents the `kind` field that calculation results start with when the
+/// `result_kind` config switch is on.
+#[cfg(feature = "swagger")]
+struct ResultKindAddon;
+
+#[cfg(feature = "swagger")]
+impl utoipa::Modify for ResultKindAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        use utoipa::openapi::RefOr;
+        use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+
+        let kinds = [
+            ("Ipv4Subnet", Ipv4Subnet::KIND),
+            ("Ipv6Subnet", Ipv6Subnet::KIND),
+            ("Ipv4SubnetList", Ipv4SubnetList::KIND),
+            ("Ipv6SubnetList", Ipv6SubnetList::KIND),
+            ("SplitSummary", SplitSummary::KIND),
+            ("DualStackPlan", DualStackPlan::KIND),
+            ("ContainsResult", ContainsResult::KIND),
+            ("UsabilityResult", UsabilityResult::KIND),
+            ("Ipv4SummaryResult", Ipv4SummaryResult::KIND),
+            ("Ipv6SummaryResult", Ipv6SummaryResult::KIND),
+            ("OverlapResult", OverlapResult::KIND),
+            ("GroupedSummaryResult", GroupedSummaryResult::KIND),
+            ("SupernetResult", SupernetResult::KIND),
+            ("ReverseDnsResult", ReverseDnsResult::KIND),
+            ("Ipv4FromRangeResult", Ipv4FromRangeResult::KIND),
+            ("Ipv6FromRangeResult", Ipv6FromRangeResult::KIND),
+            ("Ipv4WalkResult", Ipv4WalkResult::KIND),
+            ("IpAddResult", IpAddResult::KIND),
+            ("HostsResult", HostsResult::KIND),
+            ("Ipv4NthHostResult", Ipv4NthHostResult::KIND),
+            ("Ipv4ComplementResult", Ipv4ComplementResult::KIND),
+            ("Ipv6ComplementResult", Ipv6ComplementResult::KIND),
+            ("Ipv4ExcludeResult", Ipv4ExcludeResult::KIND),
+            ("Ipv6ExcludeResult", Ipv6ExcludeResult::KIND),
+            ("GapsResult", GapsResult::KIND),
+            ("AllocateResult", AllocateResult::KIND),
+            ("LpmResult", LpmResult::KIND),
+            ("Ipv6FormatResult", Ipv6FormatResult::KIND),
+            ("Eui64Result", Eui64Result::KIND),
+            ("InterfaceIdResult", InterfaceIdResult::KIND),
+            ("BatchResult", BatchResult::KIND),
+            ("AddressCountResult", AddressCountResult::KIND),
+            ("CoverageResult", CoverageResult::KIND),
+            ("AvailableResult", AvailableResult::KIND),
+            ("VlsmResult", VlsmResult::KIND),
+        ];
+        let Some(components) = openapi.components.as_mut() else {
+            return;
+        };
+        for (name, kind) in kinds {
+            let (Some(RefOr::T(Schema::Object(object))), Some(kind)) =
+                (components.schemas.get_mut(name), kind)
+            else {
+                continue;
+            };
+            let property = ObjectBuilder::new()
+                .schema_type(Type::String)
+                .enum_values(Some([kind]))
+                .description(Some(
+                    "Result type, present when the server sets result_kind = true",
+                ));
+            object
+                .properties
+                .insert("kind".to_string(), property.into());
+        }
+    }
+}
+
+/// A core calculation endpoint, as listed in the `serve` banner and the
+/// landing page.
+pub struct EndpointInfo {
+    pub method: &'static str,
+    /// Path with its query parameters as placeholders
+    pub usage: &'static str,
+    pub description: &'static str,
+    /// A concrete request (query string for GET, JSON body for POST)
+    pub example: &'static str,
+}
+
+/// Registry of the core calculation endpoints.
+pub const ENDPOINTS: &[EndpointInfo] = &[
+    EndpointInfo {
+        method: "GET",
+        usage: "/health",
+        description: "Health check",
+        example: "/health",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/version",
+        description: "Version information",
+        example: "/version",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/limits",
+        description: "Effective request limits",
+        example: "/limits",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4?cidr=<cidr>",
+        description: "Calculate IPv4 subnet",
+        example: "/v4?cidr=192.168.1.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6?cidr=<cidr>",
+        description: "Calculate IPv6 subnet",
+        example: "/v6?cidr=2001:db8::/32",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/next-subnet?cidr=<cidr>",
+        description: "Adjacent IPv4 block after a CIDR",
+        example: "/v4/next-subnet?cidr=192.168.1.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/prev-subnet?cidr=<cidr>",
+        description: "Adjacent IPv4 block before a CIDR",
+        example: "/v4/prev-subnet?cidr=192.168.1.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/split?cidr=<cidr>&prefix=<n>&count=<n>",
+        description: "Split IPv4 supernet",
+        example: "/v4/split?cidr=10.0.0.0/8&prefix=16&count=5",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/split?cidr=<cidr>&prefix=<n>&count=<n>",
+        description: "Split IPv6 supernet",
+        example: "/v6/split?cidr=2001:db8::/32&prefix=48&count=10",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/dualstack/split?v4=<cidr>&v6=<cidr>&v4_prefix=<n>&v6_prefix=<n>&count=<n>",
+        description: "Paired IPv4/IPv6 split",
+        example: "/dualstack/split?v4=10.20.0.0/16&v6=2001:db8:20::/48&v4_prefix=24&v6_prefix=64&count=10",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/contains?cidr=<cidr>&address=<ip>",
+        description: "Check IPv4 containment",
+        example: "/v4/contains?cidr=192.168.1.0/24&address=192.168.1.100",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/contains?cidr=<cidr>&address=<ip>",
+        description: "Check IPv6 containment",
+        example: "/v6/contains?cidr=2001:db8::/32&address=2001:db8::1",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/subnet-contains?outer=<cidr>&inner=<cidr>",
+        description: "Check IPv4 subnet containment",
+        example: "/v4/subnet-contains?outer=10.0.0.0/8&inner=10.1.0.0/16",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/subnet-contains?outer=<cidr>&inner=<cidr>",
+        description: "Check IPv6 subnet containment",
+        example: "/v6/subnet-contains?outer=2001:db8::/32&inner=2001:db8:1::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/is-usable?cidr=<addr/prefix>",
+        description: "Check IPv4 address usability",
+        example: "/v4/is-usable?cidr=192.168.1.255/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/host-check?address=<ip>&cidr=<cidr>",
+        description: "Check an IPv4 address is a usable host of a subnet",
+        example: "/v4/host-check?address=192.168.1.0&cidr=192.168.1.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/host-check?address=<ip>&cidr=<cidr>",
+        description: "Check an IPv6 address is a usable host of a subnet",
+        example: "/v6/host-check?address=2001:db8::&cidr=2001:db8::/64",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/summarize?cidrs=<cidr,cidr,...>",
+        description: "Summarize IPv4 CIDRs",
+        example: "/v4/summarize?cidrs=192.168.0.0/24,192.168.1.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/summarize?cidrs=<cidr,cidr,...>",
+        description: "Summarize IPv6 CIDRs",
+        example: "/v6/summarize?cidrs=2001:db8::/48,2001:db8:1::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/overlaps?cidrs=<cidr,cidr,...>",
+        description: "Find overlapping IPv4 CIDRs",
+        example: "/v4/overlaps?cidrs=10.0.0.0/8,10.1.0.0/16,192.168.0.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/supernet?cidrs=<cidr,cidr,...>",
+        description: "Smallest IPv4 CIDR covering all inputs",
+        example: "/v4/supernet?cidrs=10.1.4.0/24,10.1.7.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/supernet?cidrs=<cidr,cidr,...>",
+        description: "Smallest IPv6 CIDR covering all inputs",
+        example: "/v6/supernet?cidrs=2001:db8::/48,2001:db8:3::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/common-supernet?a=<cidr>&b=<cidr>",
+        description: "Tightest IPv4 CIDR covering two blocks",
+        example: "/v4/common-supernet?a=192.168.1.0/24&b=192.168.3.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/common-supernet?a=<cidr>&b=<cidr>",
+        description: "Tightest IPv6 CIDR covering two blocks",
+        example: "/v6/common-supernet?a=2001:db8:1::/48&b=2001:db8:2::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/reverse-dns?cidr=<cidr>",
+        description: "Reverse DNS zone and PTR stubs for an IPv4 CIDR",
+        example: "/v4/reverse-dns?cidr=192.0.2.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/reverse-dns?cidr=<cidr>",
+        description: "Nibble-reversed ip6.arpa zone for an IPv6 prefix",
+        example: "/v6/reverse-dns?cidr=2001:db8:abcd::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/from-range?start=<ip>&end=<ip>",
+        description: "IPv4 range to CIDRs",
+        example: "/v4/from-range?start=192.168.1.10&end=192.168.1.20",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/from-range?start=<ip>&end=<ip>",
+        description: "IPv6 range to CIDRs",
+        example: "/v6/from-range?start=2001:db8::1&end=2001:db8::ff",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/walk?start=<cidr>&end=<cidr>",
+        description: "Every same-size IPv4 subnet from start to end",
+        example: "/v4/walk?start=10.0.0.0/24&end=10.0.5.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/add?address=<ip>&offset=<n>",
+        description: "IPv4 address moved by an integer offset",
+        example: "/v4/add?address=10.0.0.0&offset=256",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/hosts?cidr=<cidr>[&max=<n>]",
+        description: "Usable IPv4 host addresses of a subnet",
+        example: "/v4/hosts?cidr=192.168.1.0/29&max=6",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/hosts?cidr=<cidr>[&max=<n>]",
+        description: "IPv6 host addresses of a subnet",
+        example: "/v6/hosts?cidr=2001:db8::/124",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/nth-host?cidr=<cidr>&n=<n>",
+        description: "The nth usable IPv4 host of a subnet, counting from 0",
+        example: "/v4/nth-host?cidr=10.0.0.0/24&n=5",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/complement?cidr=<cidr>&within=<cidr>",
+        description: "IPv4 complement within parent block",
+        example: "/v4/complement?cidr=10.0.1.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/complement?cidr=<cidr>&within=<cidr>",
+        description: "IPv6 complement within parent block",
+        example: "/v6/complement?cidr=2001:db8:1::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/exclude?supernet=<cidr>&cidrs=<cidr,cidr,...>",
+        description: "IPv4 supernet minus the excluded blocks",
+        example: "/v4/exclude?supernet=10.0.0.0/8&cidrs=10.1.0.0/16,10.2.0.0/16",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/exclude?supernet=<cidr>&cidrs=<cidr,cidr,...>",
+        description: "IPv6 supernet minus the excluded blocks",
+        example: "/v6/exclude?supernet=2001:db8::/46&cidrs=2001:db8:1::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/gaps?supernet=<cidr>&used=<cidr,cidr,...>",
+        description: "Free IPv4 space left by allocated subnets",
+        example: "/v4/gaps?supernet=10.0.0.0/16&used=10.0.0.0/24,10.0.4.0/22",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/gaps?supernet=<cidr>&used=<cidr,cidr,...>",
+        description: "Free IPv6 space left by allocated subnets",
+        example: "/v6/gaps?supernet=2001:db8::/46&used=2001:db8:1::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/allocate?supernet=<cidr>&prefix=<len>&count=<n>&used=<cidr,cidr,...>",
+        description: "Next free IPv4 block(s) of one size in a supernet",
+        example: "/v4/allocate?supernet=10.0.0.0/16&prefix=24&used=10.0.0.0/24,10.0.1.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/allocate?supernet=<cidr>&prefix=<len>&count=<n>&used=<cidr,cidr,...>",
+        description: "Next free IPv6 block(s) of one size in a supernet",
+        example: "/v6/allocate?supernet=2001:db8::/48&prefix=64&count=2&used=2001:db8::/64",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v4/lpm?address=<ip>&cidrs=<cidr,cidr,...>",
+        description: "Longest-prefix match for an IPv4 address",
+        example: "/v4/lpm?address=10.1.2.3&cidrs=10.0.0.0/8,10.1.0.0/16,10.1.2.0/24",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/lpm?address=<ip>&cidrs=<cidr,cidr,...>",
+        description: "Longest-prefix match for an IPv6 address",
+        example: "/v6/lpm?address=2001:db8:1::5&cidrs=2001:db8::/32,2001:db8:1::/48",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/eui64?prefix=<cidr>&mac=<mac>",
+        description: "SLAAC (EUI-64) address of a MAC in a prefix",
+        example: "/v6/eui64?prefix=2001:db8::/64&mac=00:1a:2b:3c:4d:5e",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/ifid?cidr=<cidr>",
+        description: "Network and interface identifier of an IPv6 address",
+        example: "/v6/ifid?cidr=2001:db8::1234:5678:9abc:def0/64",
+    },
+    EndpointInfo {
+        method: "GET",
+        usage: "/v6/format?address=<ip>",
+        description: "Compressed and fully expanded forms of an IPv6 address",
+        example: "/v6/format?address=2001:db8::1",
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/batch",
+        description: "Batch CIDR processing",
+        example: r#"{"cidrs":["192.168.1.0/24","10.0.0.0/8"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/addresses",
+        description: "Count distinct addresses",
+        example: r#"{"cidrs":["10.0.0.0/24","10.0.0.128/25"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/overlaps",
+        description: "Find overlapping CIDRs (IPv4 and/or IPv6)",
+        example: r#"{"cidrs":["10.0.0.0/8","10.1.0.0/16","2001:db8::/32"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/summarize-grouped",
+        description: "Summarize tagged CIDRs separately per tag value",
+        example: r#"{"tag":"role","cidrs":["10.0.0.0/24 # role=web","10.0.1.0/24 # role=web"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/covers",
+        description: "Check a CIDR list exactly covers a target",
+        example: r#"{"target":"10.0.0.0/16","cidrs":["10.0.0.0/17","10.0.128.0/17"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/match",
+        description: "Longest-prefix match for an address among CIDRs",
+        example: r#"{"address":"10.1.2.3","cidrs":["10.0.0.0/8","10.1.0.0/16","10.1.2.0/24"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/v4/available",
+        description: "Free IPv4 blocks of one size in a supernet",
+        example: r#"{"supernet":"192.168.1.0/24","prefix":26,"used":["192.168.1.0/26"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/v6/available",
+        description: "Free IPv6 blocks of one size in a supernet",
+        example: r#"{"supernet":"2001:db8::/46","prefix":48,"used":["2001:db8:1::/48"]}"#,
+    },
+    EndpointInfo {
+        method: "POST",
+        usage: "/v4/vlsm",
+        description: "Carve an IPv4 supernet into variable-length subnets",
+        example: r#"{"supernet":"10.0.0.0/24","requirements":[100,50,20,10]}"#,
+    },
+];
+
 #[derive(Default)]
 pub struct RouterConfig {
+    /// Server settings shared by every stage (limits, timeout, CORS, client address)
     pub server: ServerConfig,
+    /// IPAM operations; the `/ipam` routes are only mounted when set
     pub ipam_ops: Option<Arc<crate::ipam::operations::IpamOps>>,
 }
 
@@ -118,6 +667,31 @@ pub struct RouterConfig {
 pub struct SubnetQuery {
     /// IP address in CIDR notation (e.g., 192.168.1.0/24 or 2001:db8::/48)
     cidr: String,
+    /// Reject IPv6 input that is not in RFC 5952 canonical form
+    #[serde(default)]
+    require_canonical: bool,
+    /// Add the IANA special-purpose registry entry (RFC 6890) to IPv4 results
+    #[serde(default)]
+    rfc6890: bool,
+    /// Hex digit case for IPv6 results (lower, upper)
+    #[serde(default)]
+    v6_case: Ipv6Case,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct ReverseDnsQuery {
+    /// Network in CIDR notation (e.g., 192.0.2.0/24 or 2001:db8::/48)
+    cidr: String,
+    /// Only return the zone name, without per-host records
+    #[serde(default)]
+    zone_only: bool,
     /// Pretty print JSON output
     #[serde(default)]
     pretty: bool,
@@ -141,6 +715,56 @@ pub struct SplitQuery {
     /// Show only the number of available subnets (no generation)
     #[serde(default, alias = "count-only")]
     count_only: bool,
+    /// Return the generated subnets in a reproducible random order
+    #[serde(default)]
+    shuffle: bool,
+    /// Seed for shuffle (random if omitted; reported in the response)
+    seed: Option<u64>,
+    /// 0-based index of the first subnet to generate, for paging with count or limit
+    #[serde(default)]
+    offset: u64,
+    /// Generate at most this many subnets from offset on; the last page may
+    /// be shorter (where count would be an error)
+    limit: Option<u64>,
+    /// Return only the subnet with this 0-based index, computed directly
+    /// (ignores count, max, shuffle, and offset)
+    index: Option<u64>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct DualStackQuery {
+    /// IPv4 supernet in CIDR notation (e.g., 10.20.0.0/16)
+    v4: String,
+    /// IPv6 supernet in CIDR notation (e.g., 2001:db8:20::/48)
+    v6: String,
+    /// New prefix length for the IPv4 subnets
+    v4_prefix: u8,
+    /// New prefix length for the IPv6 subnets
+    v6_prefix: u8,
+    /// Number of subnet pairs to generate
+    count: u64,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct HostCheckQuery {
+    /// IP address to check (e.g., 192.168.1.0)
+    address: String,
+    /// Subnet in CIDR notation (e.g., 192.168.1.0/24)
+    cidr: String,
     /// Pretty print JSON output
     #[serde(default)]
     pretty: bool,
@@ -154,8 +778,30 @@ pub struct SplitQuery {
 pub struct ContainsQuery {
     /// Network in CIDR notation (e.g., 192.168.1.0/24)
     cidr: String,
-    /// IP address to check (e.g., 192.168.1.100)
+    /// IP address to check (e.g., 192.168.1.100), or a range to check every
+    /// address in it (e.g., 192.168.1.10-192.168.1.20)
     address: String,
+    /// Translate between IPv4 and IPv6 (IPv4-mapped, IPv4-compatible, 6to4, NAT64) when families differ
+    #[serde(default)]
+    translate: bool,
+    /// Look up the address's reverse DNS (PTR) name; requires `allow_dns_lookups` in the server config
+    #[serde(default)]
+    resolve: bool,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct SubnetContainsQuery {
+    /// Enclosing network in CIDR notation (e.g., 10.0.0.0/8)
+    outer: String,
+    /// Network to check in CIDR notation (e.g., 10.1.0.0/16)
+    inner: String,
     /// Pretty print JSON output
     #[serde(default)]
     pretty: bool,
@@ -177,6 +823,205 @@ pub struct SummarizeQuery {
     format: ApiOutputFormat,
 }
 
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct CommonSupernetQuery {
+    /// First CIDR (e.g., 192.168.1.0/24)
+    a: String,
+    /// Second CIDR of the same IP version (e.g., 192.168.3.0/24)
+    b: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct ComplementQuery {
+    /// Network in CIDR notation (e.g., 10.0.1.0/24)
+    cidr: String,
+    /// Block to take the complement within (default: the one-bit-shorter parent)
+    within: Option<String>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct ExcludeQuery {
+    /// Block to subtract from (e.g., 10.0.0.0/8); also accepted as `cidr`
+    #[serde(alias = "cidr")]
+    supernet: String,
+    /// Comma-separated CIDRs to remove (e.g., 10.1.0.0/16,10.2.0.0/16); also accepted as `exclude`
+    #[serde(alias = "exclude")]
+    cidrs: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct GapsQuery {
+    /// Parent block (e.g., 10.0.0.0/16)
+    supernet: String,
+    /// Comma-separated allocated subnets (e.g., 10.0.0.0/24,10.0.4.0/22)
+    #[serde(default)]
+    used: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct AllocateQuery {
+    /// Parent block (e.g., 10.0.0.0/16)
+    supernet: String,
+    /// Prefix length of the blocks to allocate (e.g., 24)
+    prefix: u8,
+    /// Number of blocks to allocate (default 1)
+    count: Option<u64>,
+    /// Comma-separated subnets already in use (e.g., 10.0.0.0/24,10.0.1.0/24)
+    #[serde(default)]
+    used: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct LpmQuery {
+    /// IP address to match (e.g., 10.1.2.3)
+    address: String,
+    /// Comma-separated candidate CIDRs (e.g., 10.0.0.0/8,10.1.0.0/16,10.1.2.0/24)
+    cidrs: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct Ipv6FormatQuery {
+    /// IPv6 address in any valid spelling (e.g., 2001:0db8:0:0::1)
+    address: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct Eui64Query {
+    /// IPv6 prefix of /64 or shorter (e.g., 2001:db8::/64)
+    prefix: String,
+    /// MAC address with colon or dash separators (e.g., 00:1a:2b:3c:4d:5e)
+    mac: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct IfidQuery {
+    /// IPv6 address with prefix length (e.g., 2001:db8::1234:5678:9abc:def0/64)
+    cidr: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct WalkQuery {
+    /// First subnet (e.g., 10.0.0.0/24)
+    start: String,
+    /// Last subnet, with the same prefix length (e.g., 10.0.5.0/24)
+    end: String,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct AddQuery {
+    /// IPv4 address (e.g., 10.0.0.0)
+    address: String,
+    /// Number of addresses to move by, negative to go back (e.g., 256 or -1)
+    offset: i64,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct HostsQuery {
+    /// CIDR notation (e.g., 192.168.1.0/29)
+    cidr: String,
+    /// Stop after this many hosts (capped at the subnet generation limit)
+    max: Option<usize>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
+pub struct NthHostQuery {
+    /// CIDR notation (e.g., 10.0.0.0/24)
+    cidr: String,
+    /// 0-based index of the host, where 0 is the first usable host (e.g., 5)
+    n: u64,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    format: ApiOutputFormat,
+}
+
 #[derive(Deserialize)]
 #[cfg_attr(feature = "swagger", derive(ToSchema, IntoParams))]
 pub struct FromRangeQuery {
@@ -197,6 +1042,23 @@ pub struct FromRangeQuery {
 pub struct BatchRequest {
     /// List of CIDRs to process (IPv4 and/or IPv6)
     pub cidrs: Vec<String>,
+    /// Record IPv6 entries that are not in RFC 5952 canonical form as errors
+    #[serde(default)]
+    pub require_canonical: bool,
+    /// Also summarize all valid entries into `aggregate_summary`
+    #[serde(default)]
+    pub with_summary: bool,
+    /// Keep only failed (`errors`) or parsed (`ok`) entries in `results`
+    pub filter: Option<BatchFilter>,
+    /// Skip entries equal to or inside any of these CIDRs
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Skip entries inside any IANA special-purpose block
+    #[serde(default)]
+    pub ignore_special: bool,
+    /// Hex digit case for IPv6 entries (lower, upper)
+    #[serde(default)]
+    pub v6_case: Ipv6Case,
     /// Pretty print JSON output
     #[serde(default)]
     pub pretty: bool,
@@ -205,11 +1067,133 @@ pub struct BatchRequest {
     pub format: ApiOutputFormat,
 }
 
-#[derive(Serialize)]
+#[derive(Deserialize)]
 #[cfg_attr(feature = "swagger", derive(ToSchema))]
-struct ErrorResponse {
-    /// Error message
-    error: String,
+pub struct AddressesRequest {
+    /// List of CIDRs whose distinct addresses are counted (IPv4 and/or IPv6)
+    pub cidrs: Vec<String>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pub pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    pub format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+pub struct OverlapsRequest {
+    /// List of CIDRs to check for overlaps (IPv4 and/or IPv6)
+    pub cidrs: Vec<String>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pub pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    pub format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+pub struct SummarizeGroupedRequest {
+    /// Tag key to group by (e.g., role)
+    pub tag: String,
+    /// CIDRs with trailing `# key=value` tags, as in batch input
+    /// (e.g., "10.0.0.0/24 # role=web")
+    pub cidrs: Vec<String>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pub pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    pub format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+pub struct CoversRequest {
+    /// Block that should be covered (e.g., 10.0.0.0/16)
+    pub target: String,
+    /// CIDRs expected to cover the target exactly
+    pub cidrs: Vec<String>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pub pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    pub format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+pub struct MatchRequest {
+    /// IP address to match (e.g., 10.1.2.3)
+    pub address: String,
+    /// Candidate CIDRs, all of the address's family
+    pub cidrs: Vec<String>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pub pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    pub format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+pub struct AvailableRequest {
+    /// Block to search (e.g., 192.168.1.0/24)
+    pub supernet: String,
+    /// Size of the blocks to return (e.g., 26)
+    pub prefix: u8,
+    /// CIDRs already in use
+    #[serde(default)]
+    pub used: Vec<String>,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pub pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    pub format: ApiOutputFormat,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+pub struct VlsmRequest {
+    /// IPv4 block to carve (e.g., 10.0.0.0/24)
+    #[serde(alias = "cidr")]
+    pub supernet: String,
+    /// Required host count of each subnet (e.g., [100, 50, 20, 10])
+    #[serde(alias = "hosts")]
+    pub requirements: Vec<u64>,
+    /// Usable hosts of a /31: 0 (default) or 2 (RFC 3021)
+    #[serde(default)]
+    pub p2p_usable: u64,
+    /// Pretty print JSON output
+    #[serde(default)]
+    pub pretty: bool,
+    /// Output format (json, text, csv, yaml)
+    #[serde(default)]
+    pub format: ApiOutputFormat,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+struct ErrorResponse {
+    /// Error message
+    error: String,
+}
+
+/// Error body for a request rejected by `max_body_size`.
+#[derive(Serialize)]
+#[cfg_attr(feature = "swagger", derive(ToSchema))]
+struct PayloadTooLargeResponse {
+    /// Error message
+    error: String,
+    /// Configured request body limit in bytes
+    max_body_size: usize,
+    /// Content-Length sent by the client, if any
+    content_length: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -257,18 +1241,31 @@ fn build_response(status: StatusCode, content_type: &str, body: String) -> Respo
     }
 }
 
-fn format_response<T: Serialize + TextOutput + CsvOutput>(
+/// Render a result in the requested format. With `kind` set (the
+/// `result_kind` config switch), JSON and YAML start with the result's `kind`.
+fn format_response<T: Serialize + TextOutput + CsvOutput + ResultKind>(
     value: T,
     format: ApiOutputFormat,
     pretty: bool,
+    kind: bool,
     status: StatusCode,
 ) -> Response {
+    if let Err(e) = OutputFormat::from(format).ensure_available() {
+        return json_response(
+            ErrorResponse {
+                error: e.to_string(),
+            },
+            pretty,
+            StatusCode::BAD_REQUEST,
+        );
+    }
+    let tagged = Kinded::new(&value, kind);
     match format {
         ApiOutputFormat::Json => {
             let body = if pretty {
-                serde_json::to_string_pretty(&value)
+                serde_json::to_string_pretty(&tagged)
             } else {
-                serde_json::to_string(&value)
+                serde_json::to_string(&tagged)
             };
             match body {
                 Ok(b) => build_response(status, "application/json", b),
@@ -295,11 +1292,11 @@ fn format_response<T: Serialize + TextOutput + CsvOutput>(
                 StatusCode::INTERNAL_SERVER_ERROR,
             ),
         },
-        ApiOutputFormat::Yaml => match serde_saphyr::to_string(&value) {
+        ApiOutputFormat::Yaml => match to_yaml(&tagged) {
             Ok(body) => build_response(status, "application/yaml", body),
             Err(e) => json_response(
                 ErrorResponse {
-                    error: IpCalcError::Yaml(e.to_string()).to_string(),
+                    error: e.to_string(),
                 },
                 false,
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -309,87 +1306,334 @@ fn format_response<T: Serialize + TextOutput + CsvOutput>(
 }
 
 pub fn create_router(config: RouterConfig) -> Router {
-    let config_ext = Arc::new(config.server.clone());
-
-    let router = Router::new()
-        .route("/health", get(health))
-        .route("/version", get(version))
-        .route("/v4", get(calculate_ipv4))
-        .route("/v6", get(calculate_ipv6))
-        .route("/v4/split", get(split_ipv4))
-        .route("/v6/split", get(split_ipv6))
-        .route("/v4/contains", get(contains_ipv4))
-        .route("/v6/contains", get(contains_ipv6))
-        .route("/v4/summarize", get(summarize_ipv4_handler))
-        .route("/v6/summarize", get(summarize_ipv6_handler))
-        .route("/v4/from-range", get(from_range_ipv4_handler))
-        .route("/v6/from-range", get(from_range_ipv6_handler))
-        .route("/batch", post(batch_handler));
-
-    // Dashboard is always available (serves the SPA for all tools)
-    let ipam_enabled = config.ipam_ops.is_some();
-    let router = router
-        .route("/dashboard", get(dashboard))
-        .route("/", get(dashboard));
-
-    // Conditionally mount IPAM routes
-    let router = if let Some(ops) = config.ipam_ops {
-        let ipam_router = crate::ipam_api::create_ipam_router().layer(Extension(ops));
-        router.nest("/ipam", ipam_router)
-    } else {
+    RouterBuilder::new(config).build()
+}
+
+/// Assembles the API router: the routes, then middleware in fixed stages.
+///
+/// Each stage wraps everything before it, so it sees the requests before and
+/// the responses after the inner stages, including the errors they return
+/// themselves. From the inside out:
+///
+/// 1. routes, with the IPAM, swagger, and UI routes the config enables
+/// 2. extensions: the server config for handlers, and the `case=camel`
+///    rewrite of JSON responses
+/// 3. tracing, whose span records the client address set in stage 5
+/// 4. limits: the body size limit, then the JSON diagnostics for its 413s
+/// 5. client address resolution, honouring trusted proxies
+/// 6. timeout, covering everything above so a slow body read also times out
+/// 7. CORS, which answers preflight requests itself
+/// 8. security headers, outermost so every response carries them
+///
+/// A new middleware belongs in the stage whose responses it must see, with a
+/// test in `tests/api_tests.rs` for the behavior that depends on its place.
+pub struct RouterBuilder {
+    config: RouterConfig,
+}
+
+impl RouterBuilder {
+    pub fn new(config: RouterConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn build(self) -> Router {
+        let router = self.routes();
+        self.wrap(router)
+    }
+
+    /// Apply every middleware stage to `router`.
+    fn wrap(&self, router: Router) -> Router {
+        let router = self.extensions(router);
+        let router = self.tracing(router);
+        let router = self.limits(router);
+        let router = self.client_address(router);
+        let router = self.timeout(router);
+        let router = self.cors(router);
+        self.security_headers(router)
+    }
+
+    fn routes(&self) -> Router {
+        let router = Router::new()
+            .route("/health", get(health))
+            .route("/version", get(version))
+            .route("/limits", get(limits))
+            .route("/v4", get(calculate_ipv4))
+            .route("/v6", get(calculate_ipv6))
+            .route("/v4/next-subnet", get(next_subnet_ipv4))
+            .route("/v4/prev-subnet", get(prev_subnet_ipv4))
+            .route("/v4/split", get(split_ipv4))
+            .route("/v6/split", get(split_ipv6))
+            .route("/dualstack/split", get(dualstack_split_handler))
+            .route("/v4/contains", get(contains_ipv4))
+            .route("/v6/contains", get(contains_ipv6))
+            .route("/v4/subnet-contains", get(subnet_contains_ipv4))
+            .route("/v6/subnet-contains", get(subnet_contains_ipv6))
+            .route("/v4/is-usable", get(is_usable_ipv4))
+            .route("/v4/host-check", get(host_check_ipv4))
+            .route("/v6/host-check", get(host_check_ipv6))
+            .route("/v4/summarize", get(summarize_ipv4_handler))
+            .route("/v6/summarize", get(summarize_ipv6_handler))
+            .route("/v4/overlaps", get(overlaps_ipv4_handler))
+            .route("/v4/supernet", get(supernet_ipv4_handler))
+            .route("/v6/supernet", get(supernet_ipv6_handler))
+            .route("/v4/common-supernet", get(common_supernet_ipv4))
+            .route("/v6/common-supernet", get(common_supernet_ipv6))
+            .route("/v4/reverse-dns", get(reverse_dns_ipv4_handler))
+            .route("/v6/reverse-dns", get(reverse_dns_ipv6_handler))
+            .route("/v4/from-range", get(from_range_ipv4_handler))
+            .route("/v6/from-range", get(from_range_ipv6_handler))
+            .route("/v4/walk", get(walk_ipv4_handler))
+            .route("/v4/add", get(add_ipv4_handler))
+            .route("/v4/hosts", get(hosts_ipv4_handler))
+            .route("/v6/hosts", get(hosts_ipv6_handler))
+            .route("/v4/nth-host", get(nth_host_ipv4_handler))
+            .route("/v4/complement", get(complement_ipv4_handler))
+            .route("/v6/complement", get(complement_ipv6_handler))
+            .route("/v4/exclude", get(exclude_ipv4_handler))
+            .route("/v6/exclude", get(exclude_ipv6_handler))
+            .route("/v4/gaps", get(gaps_ipv4_handler))
+            .route("/v6/gaps", get(gaps_ipv6_handler))
+            .route("/v4/allocate", get(allocate_ipv4_handler))
+            .route("/v6/allocate", get(allocate_ipv6_handler))
+            .route("/v4/lpm", get(lpm_ipv4_handler))
+            .route("/v6/lpm", get(lpm_ipv6_handler))
+            .route("/v6/eui64", get(eui64_handler))
+            .route("/v6/ifid", get(ifid_handler))
+            .route("/v6/format", get(ipv6_format_handler))
+            .route("/batch", post(batch_handler))
+            .route("/addresses", post(addresses_handler))
+            .route("/overlaps", post(overlaps_handler))
+            .route("/summarize-grouped", post(summarize_grouped_handler))
+            .route("/covers", post(covers_handler))
+            .route("/match", post(match_handler))
+            .route("/v4/available", post(available_ipv4_handler))
+            .route("/v6/available", post(available_ipv6_handler))
+            .route("/v4/vlsm", post(vlsm_ipv4_handler))
+            .route("/vlsm", post(vlsm_ipv4_handler));
+
+        // Dashboard is always available (serves the SPA for all tools); the
+        // landing page takes over `/` when enabled
+        let ipam_enabled = self.config.ipam_ops.is_some();
+        let router = router.route("/dashboard", get(dashboard));
+        let router = if self.config.server.enable_landing_page {
+            router.route("/", get(landing_page))
+        } else {
+            router.route("/", get(dashboard))
+        };
+
+        // Self-contained mini UI, opt-in via config and the webui feature
+        #[cfg(feature = "webui")]
+        let router = if self.config.server.enable_ui {
+            router.route("/ui", get(web_ui))
+        } else {
+            router
+        };
+
+        // Conditionally mount IPAM routes
+        let router = if let Some(ops) = self.config.ipam_ops.clone() {
+            let ipam_router = crate::ipam_api::create_ipam_router().layer(Extension(ops));
+            router.nest("/ipam", ipam_router)
+        } else {
+            router
+        };
+
+        // Features endpoint
+        #[cfg(feature = "swagger")]
+        let swagger_enabled = self.config.server.enable_swagger;
+        #[cfg(not(feature = "swagger"))]
+        let swagger_enabled = false;
+
+        let features = FeaturesResponse {
+            ipam: ipam_enabled,
+            swagger: swagger_enabled,
+        };
+        let router = router.route(
+            "/features",
+            get(move || async move { Json(features.clone()) }),
+        );
+
+        #[cfg(feature = "swagger")]
+        let router = if self.config.server.enable_swagger {
+            router.merge(
+                SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()),
+            )
+        } else {
+            router
+        };
+
         router
-    };
+    }
 
-    // Features endpoint
-    #[cfg(feature = "swagger")]
-    let swagger_enabled = config.server.enable_swagger;
-    #[cfg(not(feature = "swagger"))]
-    let swagger_enabled = false;
+    fn extensions(&self, router: Router) -> Router {
+        router
+            .layer(middleware::from_fn(json_case_middleware))
+            .layer(Extension(Arc::new(self.config.server.clone())))
+    }
 
-    let features = FeaturesResponse {
-        ipam: ipam_enabled,
-        swagger: swagger_enabled,
-    };
-    let router = router.route(
-        "/features",
-        get(move || async move { Json(features.clone()) }),
-    );
+    fn tracing(&self, router: Router) -> Router {
+        router.layer(TraceLayer::new_for_http().make_span_with(request_span))
+    }
 
-    #[cfg(feature = "swagger")]
-    let router = if config.server.enable_swagger {
-        router.merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
-    } else {
+    fn limits(&self, router: Router) -> Router {
+        let max_body_size = self.config.server.max_body_size;
         router
-    };
+            .layer(RequestBodyLimitLayer::new(max_body_size))
+            // Outside the limit layer so it also sees the 413s the layer
+            // returns itself for an oversized Content-Length
+            .layer(middleware::from_fn_with_state(
+                max_body_size,
+                body_limit_diagnostics,
+            ))
+    }
 
-    let cors = CorsLayer::new()
-        .allow_origin(tower_http::cors::AllowOrigin::list(
-            Vec::<HeaderValue>::new(),
+    fn client_address(&self, router: Router) -> Router {
+        // Invalid proxy entries are rejected at startup; fall back to the
+        // socket peer if a caller builds a router from an unchecked config
+        let resolver = ClientIpResolver::from_config(&self.config.server).unwrap_or_else(|e| {
+            warn!("{}; ignoring X-Forwarded-For", e);
+            ClientIpResolver::default()
+        });
+        router.layer(middleware::from_fn_with_state(
+            Arc::new(resolver),
+            client_ip_middleware,
         ))
-        .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
-        .allow_headers([header::CONTENT_TYPE]);
-
-    router
-        .layer(Extension(config_ext))
-        .layer(TraceLayer::new_for_http())
-        .layer(RequestBodyLimitLayer::new(config.server.max_body_size))
-        .layer(TimeoutLayer::with_status_code(
+    }
+
+    fn timeout(&self, router: Router) -> Router {
+        router.layer(TimeoutLayer::with_status_code(
             StatusCode::REQUEST_TIMEOUT,
-            Duration::from_secs(config.server.timeout_seconds),
-        ))
-        .layer(cors)
-        .layer(SetResponseHeaderLayer::overriding(
-            header::X_CONTENT_TYPE_OPTIONS,
-            HeaderValue::from_static("nosniff"),
-        ))
-        .layer(SetResponseHeaderLayer::overriding(
-            header::X_FRAME_OPTIONS,
-            HeaderValue::from_static("DENY"),
-        ))
-        .layer(SetResponseHeaderLayer::overriding(
-            header::CACHE_CONTROL,
-            HeaderValue::from_static("no-store"),
+            Duration::from_secs(self.config.server.timeout_seconds),
         ))
+    }
+
+    fn cors(&self, router: Router) -> Router {
+        router.layer(
+            CorsLayer::new()
+                .allow_origin(tower_http::cors::AllowOrigin::list(
+                    Vec::<HeaderValue>::new(),
+                ))
+                .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
+                .allow_headers([header::CONTENT_TYPE]),
+        )
+    }
+
+    fn security_headers(&self, router: Router) -> Router {
+        router
+            .layer(SetResponseHeaderLayer::overriding(
+                header::X_CONTENT_TYPE_OPTIONS,
+                HeaderValue::from_static("nosniff"),
+            ))
+            .layer(SetResponseHeaderLayer::overriding(
+                header::X_FRAME_OPTIONS,
+                HeaderValue::from_static("DENY"),
+            ))
+            .layer(SetResponseHeaderLayer::overriding(
+                header::CACHE_CONTROL,
+                HeaderValue::from_static("no-store"),
+            ))
+    }
+}
+
+/// Request span for the trace layer, tagged with the resolved client address.
+fn request_span<B>(request: &axum::http::Request<B>) -> tracing::Span {
+    let client_ip = request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| ip.to_string());
+    tracing::debug_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        client_ip = client_ip.as_deref().unwrap_or("-"),
+    )
+}
+
+/// Replace the bare 413 of an oversized request body with a JSON body that
+/// states the configured limit and the Content-Length the client sent.
+async fn body_limit_diagnostics(
+    State(max_body_size): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    let client_ip = request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| *ip);
+    let uri = request.uri().clone();
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return response;
+    }
+
+    warn!(
+        uri = %uri,
+        client_ip = client_ip.map(|ip| ip.to_string()).as_deref().unwrap_or("-"),
+        max_body_size,
+        content_length,
+        "Request body exceeds max_body_size"
+    );
+    json_response(
+        PayloadTooLargeResponse {
+            error: format!("Request body exceeds the maximum size of {max_body_size} bytes"),
+            max_body_size,
+            content_length,
+        },
+        false,
+        StatusCode::PAYLOAD_TOO_LARGE,
+    )
+}
+
+/// The `case` query parameter, accepted by every endpoint alongside its own.
+#[derive(Deserialize)]
+struct JsonCaseQuery {
+    #[serde(default)]
+    case: JsonCase,
+}
+
+/// Rewrite the keys of a JSON response to camelCase when the request asks
+/// for `case=camel`, keeping the compact or pretty layout it had.
+async fn json_case_middleware(request: Request, next: Next) -> Response {
+    let case = match Query::<JsonCaseQuery>::try_from_uri(request.uri()) {
+        Ok(Query(query)) => query.case,
+        Err(_) => {
+            return json_response(
+                ErrorResponse {
+                    error: "case must be 'snake' or 'camel'".to_string(),
+                },
+                false,
+                StatusCode::BAD_REQUEST,
+            );
+        }
+    };
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if case == JsonCase::Snake || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    // Compact JSON has no raw newlines; pretty JSON always does
+    match camel_case_json(&text, text.contains('\n')) {
+        Ok(body) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(body))
+        }
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
@@ -419,6 +1663,47 @@ async fn version() -> Json<VersionResponse> {
     })
 }
 
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/limits",
+    responses(
+        (status = 200, description = "Effective request limits for this server", body = Limits)
+    ),
+    tag = "ipcalc"
+))]
+async fn limits(Extension(config): Extension<Arc<ServerConfig>>) -> Json<Limits> {
+    Json(config.limits())
+}
+
+/// The API renders IPv6 addresses compressed, in the requested case.
+fn v6_format(case: Ipv6Case) -> Ipv6Format {
+    Ipv6Format {
+        style: Ipv6Style::Compressed,
+        case,
+    }
+}
+
+/// A CSV body sent chunk by chunk as `chunks` produces it, with no
+/// content-length. Dropping the body, as a disconnecting client does, drops
+/// `chunks` and whatever is generating its rows.
+#[cfg(feature = "csv")]
+fn csv_stream_response(
+    chunks: impl Iterator<Item = crate::error::Result<Vec<u8>>> + Send + 'static,
+) -> Response {
+    let body = axum::body::Body::from_stream(futures_util::stream::iter(chunks));
+    match Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/csv")
+        .body(body)
+    {
+        Ok(resp) => resp,
+        Err(_) => Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body("Internal Server Error".into())
+            .expect("fallback response must be valid"),
+    }
+}
+
 /// Helper function to format JSON responses with optional pretty printing
 fn json_response<T: Serialize>(value: T, pretty: bool, status: StatusCode) -> Response {
     let json_string = if pretty {
@@ -437,6 +1722,18 @@ fn json_response<T: Serialize>(value: T, pretty: bool, status: StatusCode) -> Re
     }
 }
 
+fn dns_lookups_disabled(pretty: bool) -> Response {
+    warn!("PTR lookup requested but allow_dns_lookups is off");
+    json_response(
+        ErrorResponse {
+            error: "DNS lookups are disabled on this server (set allow_dns_lookups = true)"
+                .to_string(),
+        },
+        pretty,
+        StatusCode::FORBIDDEN,
+    )
+}
+
 #[cfg_attr(feature = "swagger", utoipa::path(
     get,
     path = "/v4",
@@ -450,12 +1747,24 @@ fn json_response<T: Serialize>(value: T, pretty: bool, status: StatusCode) -> Re
     tag = "ipcalc"
 ))]
 #[instrument(skip_all, fields(cidr = %params.cidr))]
-async fn calculate_ipv4(Query(params): Query<SubnetQuery>) -> impl IntoResponse {
+async fn calculate_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SubnetQuery>,
+) -> impl IntoResponse {
     info!("Calculating IPv4 subnet");
-    match Ipv4Subnet::from_cidr(&params.cidr) {
-        Ok(subnet) => {
+    match Ipv4Subnet::from_addr_or_cidr(&params.cidr) {
+        Ok(mut subnet) => {
+            if params.rfc6890 {
+                subnet.apply_rfc6890();
+            }
             info!(network = %subnet.network, "IPv4 calculation successful");
-            format_response(subnet, params.format, params.pretty, StatusCode::OK)
+            format_response(
+                subnet,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
             warn!(error = %e, "IPv4 calculation failed");
@@ -472,26 +1781,43 @@ async fn calculate_ipv4(Query(params): Query<SubnetQuery>) -> impl IntoResponse
 
 #[cfg_attr(feature = "swagger", utoipa::path(
     get,
-    path = "/v6",
+    path = "/v4/next-subnet",
     params(
         SubnetQuery
     ),
     responses(
-        (status = 200, description = "IPv6 subnet information", body = Ipv6Subnet),
-        (status = 400, description = "Invalid CIDR notation", body = ErrorResponse)
+        (status = 200, description = "The following IPv4 block of the same prefix length", body = Ipv4Subnet),
+        (status = 400, description = "Invalid CIDR notation, or no next block in the address space", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
 #[instrument(skip_all, fields(cidr = %params.cidr))]
-async fn calculate_ipv6(Query(params): Query<SubnetQuery>) -> impl IntoResponse {
-    info!("Calculating IPv6 subnet");
-    match Ipv6Subnet::from_cidr(&params.cidr) {
+async fn next_subnet_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SubnetQuery>,
+) -> impl IntoResponse {
+    info!("Finding next IPv4 subnet");
+    let result = Ipv4Subnet::from_addr_or_cidr(&params.cidr).and_then(|subnet| {
+        subnet
+            .next_subnet()
+            .ok_or_else(|| IpCalcError::NoAdjacentSubnet {
+                cidr: subnet.input.clone(),
+                direction: "next".to_string(),
+            })
+    });
+    match result {
         Ok(subnet) => {
-            info!(network = %subnet.network, "IPv6 calculation successful");
-            format_response(subnet, params.format, params.pretty, StatusCode::OK)
+            info!(network = %subnet.network, "Next IPv4 subnet found");
+            format_response(
+                subnet,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv6 calculation failed");
+            warn!(error = %e, "Next IPv4 subnet lookup failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -505,68 +1831,2226 @@ async fn calculate_ipv6(Query(params): Query<SubnetQuery>) -> impl IntoResponse
 
 #[cfg_attr(feature = "swagger", utoipa::path(
     get,
-    path = "/v4/split",
+    path = "/v4/prev-subnet",
     params(
-        SplitQuery
+        SubnetQuery
     ),
     responses(
-        (status = 200, description = "Generated IPv4 subnets", body = Ipv4SubnetList),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "The preceding IPv4 block of the same prefix length", body = Ipv4Subnet),
+        (status = 400, description = "Invalid CIDR notation, or no previous block in the address space", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(cidr = %params.cidr, prefix = params.prefix, count = ?params.count, max = params.max))]
-async fn split_ipv4(Query(params): Query<SplitQuery>) -> impl IntoResponse {
-    info!("Splitting IPv4 supernet");
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn prev_subnet_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SubnetQuery>,
+) -> impl IntoResponse {
+    info!("Finding previous IPv4 subnet");
+    let result = Ipv4Subnet::from_addr_or_cidr(&params.cidr).and_then(|subnet| {
+        subnet
+            .prev_subnet()
+            .ok_or_else(|| IpCalcError::NoAdjacentSubnet {
+                cidr: subnet.input.clone(),
+                direction: "previous".to_string(),
+            })
+    });
+    match result {
+        Ok(subnet) => {
+            info!(network = %subnet.network, "Previous IPv4 subnet found");
+            format_response(
+                subnet,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "Previous IPv4 subnet lookup failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
 
-    if params.count_only {
-        return match count_subnets(&params.cidr, params.prefix) {
-            Ok(summary) => {
-                info!(available = %summary.available_subnets, "IPv4 count-only successful");
-                format_response(summary, params.format, params.pretty, StatusCode::OK)
-            }
-            Err(e) => {
-                warn!(error = %e, "IPv4 count-only failed");
-                json_response(
-                    ErrorResponse {
-                        error: e.to_string(),
-                    },
-                    params.pretty,
-                    StatusCode::BAD_REQUEST,
-                )
-            }
-        };
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6",
+    params(
+        SubnetQuery
+    ),
+    responses(
+        (status = 200, description = "IPv6 subnet information", body = Ipv6Subnet),
+        (status = 400, description = "Invalid CIDR notation", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn calculate_ipv6(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SubnetQuery>,
+) -> impl IntoResponse {
+    info!("Calculating IPv6 subnet");
+    let parsed = if params.require_canonical {
+        Ipv6Subnet::from_cidr_canonical(&with_host_prefix(&params.cidr))
+    } else {
+        Ipv6Subnet::from_addr_or_cidr(&params.cidr)
+    };
+    match parsed {
+        Ok(mut subnet) => {
+            subnet.apply_style(v6_format(params.v6_case));
+            info!(network = %subnet.network, "IPv6 calculation successful");
+            format_response(
+                subnet,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 calculation failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+/// The subnet count a split request asks for: `None` for `max`, the page
+/// size for `limit`, or `count`.
+fn split_count(params: &SplitQuery) -> Result<Option<u64>, IpCalcError> {
+    if params.max {
+        return Ok(None);
+    }
+    match (params.limit, params.count) {
+        (Some(_), Some(_)) => Err(IpCalcError::InvalidInput(
+            "'count' and 'limit' are mutually exclusive".to_string(),
+        )),
+        (Some(limit), None) => {
+            page_count(&params.cidr, params.prefix, params.offset, limit).map(Some)
+        }
+        (None, Some(count)) => Ok(Some(count)),
+        (None, None) => Err(IpCalcError::InvalidInput(
+            "Either 'count', 'limit', or 'max=true' must be specified".to_string(),
+        )),
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/split",
+    params(
+        SplitQuery
+    ),
+    responses(
+        (status = 200, description = "Generated IPv4 subnets", body = Ipv4SubnetList),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr, prefix = params.prefix, count = ?params.count, max = params.max))]
+async fn split_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SplitQuery>,
+) -> impl IntoResponse {
+    info!("Splitting IPv4 supernet");
+
+    if params.count_only {
+        return match count_subnets(&params.cidr, params.prefix) {
+            Ok(summary) => {
+                info!(available = %summary.available_subnets, "IPv4 count-only successful");
+                format_response(
+                    summary,
+                    params.format,
+                    params.pretty,
+                    config.result_kind,
+                    StatusCode::OK,
+                )
+            }
+            Err(e) => {
+                warn!(error = %e, "IPv4 count-only failed");
+                json_response(
+                    ErrorResponse {
+                        error: e.to_string(),
+                    },
+                    params.pretty,
+                    StatusCode::BAD_REQUEST,
+                )
+            }
+        };
+    }
+
+    if let Some(index) = params.index {
+        return match nth_ipv4_subnet(&params.cidr, params.prefix, index) {
+            Ok(subnet) => {
+                info!(index, network = %subnet.network, "IPv4 subnet lookup successful");
+                format_response(
+                    subnet,
+                    params.format,
+                    params.pretty,
+                    config.result_kind,
+                    StatusCode::OK,
+                )
+            }
+            Err(e) => {
+                warn!(error = %e, "IPv4 subnet lookup failed");
+                json_response(
+                    ErrorResponse {
+                        error: e.to_string(),
+                    },
+                    params.pretty,
+                    StatusCode::BAD_REQUEST,
+                )
+            }
+        };
+    }
+
+    // Determine the actual count: None means generate max
+    let actual_count = match split_count(&params) {
+        Ok(count) => count,
+        Err(e) => {
+            warn!(error = %e, "Invalid split count");
+            return json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            );
+        }
+    };
+
+    // Unshuffled CSV streams rows as they are generated, so memory stays flat
+    // however large the split
+    #[cfg(feature = "csv")]
+    if matches!(params.format, ApiOutputFormat::Csv) && !params.shuffle {
+        let iter = iter_ipv4_subnets_from(&params.cidr, params.prefix, params.offset, actual_count)
+            .and_then(|iter| {
+                iter.check_generation_limit()?;
+                Ok(iter)
+            });
+        return match iter {
+            Ok(iter) => {
+                let (supernet, count) = (iter.supernet().input.clone(), iter.requested_count());
+                info!(count, "Streaming IPv4 split as CSV");
+                csv_stream_response(ipv4_split_csv_chunks(
+                    &supernet,
+                    iter.new_prefix(),
+                    count,
+                    iter.offset(),
+                    iter,
+                ))
+            }
+            Err(e) => {
+                warn!(error = %e, "IPv4 split failed");
+                json_response(
+                    ErrorResponse {
+                        error: e.to_string(),
+                    },
+                    params.pretty,
+                    StatusCode::BAD_REQUEST,
+                )
+            }
+        };
+    }
+
+    match generate_ipv4_subnets_from(&params.cidr, params.prefix, params.offset, actual_count) {
+        Ok(mut result) => {
+            if params.shuffle {
+                result.shuffle(params.seed.unwrap_or_else(random_seed));
+            }
+            info!(
+                subnets_generated = result.subnets.len(),
+                "IPv4 split successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 split failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/split",
+    params(
+        SplitQuery
+    ),
+    responses(
+        (status = 200, description = "Generated IPv6 subnets", body = Ipv6SubnetList),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr, prefix = params.prefix, count = ?params.count, max = params.max))]
+async fn split_ipv6(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SplitQuery>,
+) -> impl IntoResponse {
+    info!("Splitting IPv6 supernet");
+
+    if params.count_only {
+        return match count_subnets(&params.cidr, params.prefix) {
+            Ok(summary) => {
+                info!(available = %summary.available_subnets, "IPv6 count-only successful");
+                format_response(
+                    summary,
+                    params.format,
+                    params.pretty,
+                    config.result_kind,
+                    StatusCode::OK,
+                )
+            }
+            Err(e) => {
+                warn!(error = %e, "IPv6 count-only failed");
+                json_response(
+                    ErrorResponse {
+                        error: e.to_string(),
+                    },
+                    params.pretty,
+                    StatusCode::BAD_REQUEST,
+                )
+            }
+        };
+    }
+
+    if let Some(index) = params.index {
+        return match nth_ipv6_subnet(&params.cidr, params.prefix, index) {
+            Ok(subnet) => {
+                info!(index, network = %subnet.network, "IPv6 subnet lookup successful");
+                format_response(
+                    subnet,
+                    params.format,
+                    params.pretty,
+                    config.result_kind,
+                    StatusCode::OK,
+                )
+            }
+            Err(e) => {
+                warn!(error = %e, "IPv6 subnet lookup failed");
+                json_response(
+                    ErrorResponse {
+                        error: e.to_string(),
+                    },
+                    params.pretty,
+                    StatusCode::BAD_REQUEST,
+                )
+            }
+        };
+    }
+
+    // Determine the actual count: None means generate max
+    let actual_count = match split_count(&params) {
+        Ok(count) => count,
+        Err(e) => {
+            warn!(error = %e, "Invalid split count");
+            return json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            );
+        }
+    };
+
+    // Unshuffled CSV streams rows as they are generated, so memory stays flat
+    // however large the split
+    #[cfg(feature = "csv")]
+    if matches!(params.format, ApiOutputFormat::Csv) && !params.shuffle {
+        let iter = iter_ipv6_subnets_from(&params.cidr, params.prefix, params.offset, actual_count)
+            .and_then(|iter| {
+                iter.check_generation_limit()?;
+                Ok(iter)
+            });
+        return match iter {
+            Ok(iter) => {
+                let (supernet, count) = (iter.supernet().input.clone(), iter.requested_count());
+                info!(count, "Streaming IPv6 split as CSV");
+                csv_stream_response(ipv6_split_csv_chunks(
+                    &supernet,
+                    iter.new_prefix(),
+                    count,
+                    iter.offset(),
+                    iter,
+                ))
+            }
+            Err(e) => {
+                warn!(error = %e, "IPv6 split failed");
+                json_response(
+                    ErrorResponse {
+                        error: e.to_string(),
+                    },
+                    params.pretty,
+                    StatusCode::BAD_REQUEST,
+                )
+            }
+        };
+    }
+
+    match generate_ipv6_subnets_from(&params.cidr, params.prefix, params.offset, actual_count) {
+        Ok(mut result) => {
+            if params.shuffle {
+                result.shuffle(params.seed.unwrap_or_else(random_seed));
+            }
+            info!(
+                subnets_generated = result.subnets.len(),
+                "IPv6 split successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 split failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/dualstack/split",
+    params(
+        DualStackQuery
+    ),
+    responses(
+        (status = 200, description = "IPv4 and IPv6 subnets paired by index", body = DualStackPlan),
+        (status = 400, description = "Invalid parameters, or one family ran out of subnets", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(v4 = %params.v4, v6 = %params.v6, count = params.count))]
+async fn dualstack_split_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<DualStackQuery>,
+) -> impl IntoResponse {
+    info!("Splitting dual-stack supernets");
+    match generate_dualstack_subnets(
+        &params.v4,
+        &params.v6,
+        params.v4_prefix,
+        params.v6_prefix,
+        params.count,
+        Ipv6Format::default(),
+    ) {
+        Ok(result) => {
+            info!(pairs = result.pairs.len(), "Dual-stack split successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "Dual-stack split failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/contains",
+    params(
+        ContainsQuery
+    ),
+    responses(
+        (status = 200, description = "IPv4 containment check result", body = ContainsResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 403, description = "DNS lookups are disabled on this server", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr, address = %params.address))]
+async fn contains_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ContainsQuery>,
+) -> impl IntoResponse {
+    info!("Checking IPv4 address containment");
+    if params.resolve && !config.allow_dns_lookups {
+        return dns_lookups_disabled(params.pretty);
+    }
+    let result = if params.translate {
+        check_contains_translated(&params.cidr, &params.address)
+    } else {
+        check_ipv4_contains(&params.cidr, &params.address)
+    };
+    match result {
+        Ok(mut result) => {
+            if params.resolve {
+                resolve_contains(&mut result).await;
+            }
+            info!(
+                contained = result.contained,
+                "IPv4 containment check successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 containment check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/contains",
+    params(
+        ContainsQuery
+    ),
+    responses(
+        (status = 200, description = "IPv6 containment check result", body = ContainsResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse),
+        (status = 403, description = "DNS lookups are disabled on this server", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr, address = %params.address))]
+async fn contains_ipv6(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ContainsQuery>,
+) -> impl IntoResponse {
+    info!("Checking IPv6 address containment");
+    if params.resolve && !config.allow_dns_lookups {
+        return dns_lookups_disabled(params.pretty);
+    }
+    let result = if params.translate {
+        check_contains_translated(&params.cidr, &params.address)
+    } else {
+        check_ipv6_contains(&params.cidr, &params.address)
+    };
+    match result {
+        Ok(mut result) => {
+            if params.resolve {
+                resolve_contains(&mut result).await;
+            }
+            info!(
+                contained = result.contained,
+                "IPv6 containment check successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 containment check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/subnet-contains",
+    params(
+        SubnetContainsQuery
+    ),
+    responses(
+        (status = 200, description = "IPv4 subnet containment check result", body = SubnetContainsResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(outer = %params.outer, inner = %params.inner))]
+async fn subnet_contains_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SubnetContainsQuery>,
+) -> impl IntoResponse {
+    info!("Checking IPv4 subnet containment");
+    match check_ipv4_subnet_contains(&params.outer, &params.inner) {
+        Ok(result) => {
+            info!(
+                relationship = result.relationship.as_str(),
+                "IPv4 subnet containment check successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 subnet containment check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/subnet-contains",
+    params(
+        SubnetContainsQuery
+    ),
+    responses(
+        (status = 200, description = "IPv6 subnet containment check result", body = SubnetContainsResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(outer = %params.outer, inner = %params.inner))]
+async fn subnet_contains_ipv6(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SubnetContainsQuery>,
+) -> impl IntoResponse {
+    info!("Checking IPv6 subnet containment");
+    match check_ipv6_subnet_contains(&params.outer, &params.inner) {
+        Ok(result) => {
+            info!(
+                relationship = result.relationship.as_str(),
+                "IPv6 subnet containment check successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 subnet containment check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/is-usable",
+    params(
+        SubnetQuery
+    ),
+    responses(
+        (status = 200, description = "Whether the address is the network, broadcast, or a usable host", body = UsabilityResult),
+        (status = 400, description = "Invalid CIDR notation", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn is_usable_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SubnetQuery>,
+) -> impl IntoResponse {
+    info!("Checking IPv4 address usability");
+    match check_ipv4_usable(&params.cidr) {
+        Ok(result) => {
+            info!(
+                role = result.role.as_str(),
+                "IPv4 usability check successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 usability check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/host-check",
+    params(
+        HostCheckQuery
+    ),
+    responses(
+        (status = 200, description = "Whether the IPv4 address is a usable host, the network or broadcast address, or outside the subnet", body = UsabilityResult),
+        (status = 400, description = "Invalid address or CIDR notation", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(address = %params.address, cidr = %params.cidr))]
+async fn host_check_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<HostCheckQuery>,
+) -> impl IntoResponse {
+    info!("Checking IPv4 host address");
+    match check_ipv4_host(&params.address, &params.cidr) {
+        Ok(result) => {
+            info!(role = result.role.as_str(), "IPv4 host check successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 host check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/host-check",
+    params(
+        HostCheckQuery
+    ),
+    responses(
+        (status = 200, description = "Whether the IPv6 address is a usable host, the Subnet-Router anycast address, or outside the subnet", body = UsabilityResult),
+        (status = 400, description = "Invalid address or CIDR notation", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(address = %params.address, cidr = %params.cidr))]
+async fn host_check_ipv6(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<HostCheckQuery>,
+) -> impl IntoResponse {
+    info!("Checking IPv6 host address");
+    match check_ipv6_host(&params.address, &params.cidr) {
+        Ok(result) => {
+            info!(role = result.role.as_str(), "IPv6 host check successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 host check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/summarize",
+    params(
+        SummarizeQuery
+    ),
+    responses(
+        (status = 200, description = "Summarized IPv4 CIDRs", body = Ipv4SummaryResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidrs = %params.cidrs))]
+async fn summarize_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SummarizeQuery>,
+) -> impl IntoResponse {
+    info!("Summarizing IPv4 CIDRs");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match summarize_ipv4_with_limit(&cidrs, config.max_summarize_inputs) {
+        Ok(result) => {
+            info!(
+                input = result.input_count,
+                output = result.output_count,
+                "IPv4 summarization successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 summarization failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/summarize",
+    params(
+        SummarizeQuery
+    ),
+    responses(
+        (status = 200, description = "Summarized IPv6 CIDRs", body = Ipv6SummaryResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidrs = %params.cidrs))]
+async fn summarize_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SummarizeQuery>,
+) -> impl IntoResponse {
+    info!("Summarizing IPv6 CIDRs");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match summarize_ipv6_with_limit(&cidrs, config.max_summarize_inputs) {
+        Ok(result) => {
+            info!(
+                input = result.input_count,
+                output = result.output_count,
+                "IPv6 summarization successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 summarization failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/overlaps",
+    params(
+        SummarizeQuery
+    ),
+    responses(
+        (status = 200, description = "Overlapping pairs of IPv4 CIDRs", body = OverlapResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidrs = %params.cidrs))]
+async fn overlaps_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SummarizeQuery>,
+) -> impl IntoResponse {
+    info!("Finding overlapping IPv4 CIDRs");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match find_overlapping_pairs_v4_with_limit(&cidrs, config.max_summarize_inputs) {
+        Ok(pairs) => {
+            let result = OverlapResult::new(cidrs.len(), pairs);
+            info!(
+                input = result.input_count,
+                overlaps = result.overlap_count,
+                "IPv4 overlap check successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 overlap check failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/supernet",
+    params(
+        SummarizeQuery
+    ),
+    responses(
+        (status = 200, description = "Smallest IPv4 CIDR containing every input", body = SupernetResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidrs = %params.cidrs))]
+async fn supernet_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SummarizeQuery>,
+) -> impl IntoResponse {
+    info!("Finding IPv4 supernet");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match find_supernet_v4_with_limit(&cidrs, config.max_summarize_inputs) {
+        Ok(result) => {
+            info!(supernet = %result.supernet, "IPv4 supernet successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 supernet failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/supernet",
+    params(
+        SummarizeQuery
+    ),
+    responses(
+        (status = 200, description = "Smallest IPv6 CIDR containing every input", body = SupernetResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidrs = %params.cidrs))]
+async fn supernet_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<SummarizeQuery>,
+) -> impl IntoResponse {
+    info!("Finding IPv6 supernet");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match find_supernet_v6_with_limit(&cidrs, config.max_summarize_inputs, Ipv6Format::default()) {
+        Ok(result) => {
+            info!(supernet = %result.supernet, "IPv6 supernet successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 supernet failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/common-supernet",
+    params(
+        CommonSupernetQuery
+    ),
+    responses(
+        (status = 200, description = "The tightest IPv4 block covering both inputs", body = Ipv4Subnet),
+        (status = 400, description = "Invalid CIDR notation or mixed IP versions", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(a = %params.a, b = %params.b))]
+async fn common_supernet_ipv4(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<CommonSupernetQuery>,
+) -> impl IntoResponse {
+    info!("Finding common IPv4 supernet");
+    match find_common_supernet_v4(&params.a, &params.b) {
+        Ok(subnet) => {
+            info!(network = %subnet.network, prefix = subnet.prefix_length, "Common IPv4 supernet found");
+            format_response(
+                subnet,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "Common IPv4 supernet lookup failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/common-supernet",
+    params(
+        CommonSupernetQuery
+    ),
+    responses(
+        (status = 200, description = "The tightest IPv6 block covering both inputs", body = Ipv6Subnet),
+        (status = 400, description = "Invalid CIDR notation or mixed IP versions", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(a = %params.a, b = %params.b))]
+async fn common_supernet_ipv6(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<CommonSupernetQuery>,
+) -> impl IntoResponse {
+    info!("Finding common IPv6 supernet");
+    match find_common_supernet_v6(&params.a, &params.b) {
+        Ok(subnet) => {
+            info!(network = %subnet.network, prefix = subnet.prefix_length, "Common IPv6 supernet found");
+            format_response(
+                subnet,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "Common IPv6 supernet lookup failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/reverse-dns",
+    params(
+        ReverseDnsQuery
+    ),
+    responses(
+        (status = 200, description = "Reverse zone and a PTR stub per usable host", body = ReverseDnsResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn reverse_dns_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ReverseDnsQuery>,
+) -> impl IntoResponse {
+    info!("Building IPv4 reverse DNS zone");
+    match reverse_dns_ipv4(&params.cidr, params.zone_only) {
+        Ok(result) => {
+            info!(zones = result.zones.len(), "IPv4 reverse DNS successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 reverse DNS failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/reverse-dns",
+    params(
+        ReverseDnsQuery
+    ),
+    responses(
+        (status = 200, description = "Nibble-reversed ip6.arpa zone (no records)", body = ReverseDnsResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn reverse_dns_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ReverseDnsQuery>,
+) -> impl IntoResponse {
+    info!("Building IPv6 reverse DNS zone");
+    match reverse_dns_ipv6(&params.cidr) {
+        Ok(result) => {
+            info!(zones = result.zones.len(), "IPv6 reverse DNS successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 reverse DNS failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/from-range",
+    params(
+        FromRangeQuery
+    ),
+    responses(
+        (status = 200, description = "CIDR blocks covering the IPv4 range", body = Ipv4FromRangeResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(start = %params.start, end = %params.end))]
+async fn from_range_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<FromRangeQuery>,
+) -> impl IntoResponse {
+    info!("Converting IPv4 range to CIDRs");
+    match from_range_ipv4_with_limit(&params.start, &params.end, config.max_generated_cidrs) {
+        Ok(result) => {
+            info!(cidr_count = result.cidr_count, "IPv4 from-range successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 from-range failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/from-range",
+    params(
+        FromRangeQuery
+    ),
+    responses(
+        (status = 200, description = "CIDR blocks covering the IPv6 range", body = Ipv6FromRangeResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(start = %params.start, end = %params.end))]
+async fn from_range_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<FromRangeQuery>,
+) -> impl IntoResponse {
+    info!("Converting IPv6 range to CIDRs");
+    match from_range_ipv6_with_limit(
+        &params.start,
+        &params.end,
+        config.max_generated_cidrs,
+        Ipv6Format::default(),
+    ) {
+        Ok(result) => {
+            info!(cidr_count = result.cidr_count, "IPv6 from-range successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 from-range failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/walk",
+    params(
+        WalkQuery
+    ),
+    responses(
+        (status = 200, description = "Every same-size IPv4 subnet from start to end, inclusive", body = Ipv4WalkResult),
+        (status = 400, description = "Invalid CIDRs, mismatched prefix lengths, or start after end", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(start = %params.start, end = %params.end))]
+async fn walk_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<WalkQuery>,
+) -> impl IntoResponse {
+    info!("Walking IPv4 subnets");
+    match walk_ipv4(&params.start, &params.end) {
+        Ok(result) => {
+            info!(count = result.count, "IPv4 walk successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 walk failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/add",
+    params(
+        AddQuery
+    ),
+    responses(
+        (status = 200, description = "The address moved by the offset", body = IpAddResult),
+        (status = 400, description = "Invalid address, or the result is outside the address space", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(address = %params.address, offset = params.offset))]
+async fn add_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<AddQuery>,
+) -> impl IntoResponse {
+    info!("Adding offset to IPv4 address");
+    match add_ipv4(&params.address, params.offset) {
+        Ok(result) => {
+            info!(result = %result.result, "IPv4 add successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 add failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/hosts",
+    params(
+        HostsQuery
+    ),
+    responses(
+        (status = 200, description = "Host addresses in order; `truncated` is true when the subnet has more", body = HostsResult),
+        (status = 400, description = "Invalid CIDR", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr, max = ?params.max))]
+async fn hosts_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<HostsQuery>,
+) -> impl IntoResponse {
+    info!("Listing IPv4 hosts");
+    match list_hosts_v4(&params.cidr, params.max.unwrap_or(usize::MAX)) {
+        Ok(result) => {
+            info!(
+                count = result.count,
+                truncated = result.truncated,
+                "IPv4 hosts successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 hosts failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/hosts",
+    params(
+        HostsQuery
+    ),
+    responses(
+        (status = 200, description = "Host addresses in order; `truncated` is true when the subnet has more", body = HostsResult),
+        (status = 400, description = "Invalid CIDR", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr, max = ?params.max))]
+async fn hosts_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<HostsQuery>,
+) -> impl IntoResponse {
+    info!("Listing IPv6 hosts");
+    match list_hosts_v6(&params.cidr, params.max.unwrap_or(usize::MAX)) {
+        Ok(result) => {
+            info!(
+                count = result.count,
+                truncated = result.truncated,
+                "IPv6 hosts successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 hosts failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/nth-host",
+    params(
+        NthHostQuery
+    ),
+    responses(
+        (status = 200, description = "The host at the given index", body = Ipv4NthHostResult),
+        (status = 400, description = "Invalid CIDR, or the index is past the last host", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr, n = params.n))]
+async fn nth_host_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<NthHostQuery>,
+) -> impl IntoResponse {
+    info!("Finding nth IPv4 host");
+    match nth_host_v4(&params.cidr, params.n.into()) {
+        Ok(result) => {
+            info!(address = %result.address, "IPv4 nth host successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 nth host failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/complement",
+    params(
+        ComplementQuery
+    ),
+    responses(
+        (status = 200, description = "Rest of the parent block once the IPv4 CIDR is removed", body = Ipv4ComplementResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn complement_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ComplementQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv4 complement");
+    match complement_ipv4(&params.cidr, params.within.as_deref()) {
+        Ok(result) => {
+            info!(cidr_count = result.cidr_count, "IPv4 complement successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 complement failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/complement",
+    params(
+        ComplementQuery
+    ),
+    responses(
+        (status = 200, description = "Rest of the parent block once the IPv6 CIDR is removed", body = Ipv6ComplementResult),
+        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn complement_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ComplementQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv6 complement");
+    match complement_ipv6(
+        &params.cidr,
+        params.within.as_deref(),
+        Ipv6Format::default(),
+    ) {
+        Ok(result) => {
+            info!(cidr_count = result.cidr_count, "IPv6 complement successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 complement failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/exclude",
+    params(
+        ExcludeQuery
+    ),
+    responses(
+        (status = 200, description = "Fewest CIDRs covering the IPv4 supernet minus the excluded blocks", body = Ipv4ExcludeResult),
+        (status = 400, description = "Invalid parameters or an excluded block larger than the supernet", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(supernet = %params.supernet, cidrs = %params.cidrs))]
+async fn exclude_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ExcludeQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv4 exclusion");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match exclude_ipv4_with_limit(&params.supernet, &cidrs, config.max_summarize_inputs) {
+        Ok(result) => {
+            info!(cidr_count = result.cidr_count, "IPv4 exclusion successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 exclusion failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/exclude",
+    params(
+        ExcludeQuery
+    ),
+    responses(
+        (status = 200, description = "Fewest CIDRs covering the IPv6 supernet minus the excluded blocks", body = Ipv6ExcludeResult),
+        (status = 400, description = "Invalid parameters or an excluded block larger than the supernet", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(supernet = %params.supernet, cidrs = %params.cidrs))]
+async fn exclude_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<ExcludeQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv6 exclusion");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match exclude_ipv6_with_limit(
+        &params.supernet,
+        &cidrs,
+        config.max_summarize_inputs,
+        Ipv6Format::default(),
+    ) {
+        Ok(result) => {
+            info!(cidr_count = result.cidr_count, "IPv6 exclusion successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 exclusion failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/gaps",
+    params(
+        GapsQuery
+    ),
+    responses(
+        (status = 200, description = "Free IPv4 space in the supernet and its largest contiguous run", body = GapsResult),
+        (status = 400, description = "Invalid parameters or an allocation outside the supernet", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(supernet = %params.supernet, used = %params.used))]
+async fn gaps_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<GapsQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv4 free space");
+    let used: Vec<String> = params
+        .used
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match find_gaps_v4_with_limit(&params.supernet, &used, config.max_summarize_inputs) {
+        Ok(result) => {
+            info!(
+                free_cidr_count = result.free_cidr_count,
+                "IPv4 gap analysis successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 gap analysis failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/gaps",
+    params(
+        GapsQuery
+    ),
+    responses(
+        (status = 200, description = "Free IPv6 space in the supernet and its largest contiguous run", body = GapsResult),
+        (status = 400, description = "Invalid parameters or an allocation outside the supernet", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(supernet = %params.supernet, used = %params.used))]
+async fn gaps_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<GapsQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv6 free space");
+    let used: Vec<String> = params
+        .used
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match find_gaps_v6_with_limit(
+        &params.supernet,
+        &used,
+        config.max_summarize_inputs,
+        Ipv6Format::default(),
+    ) {
+        Ok(result) => {
+            info!(
+                free_cidr_count = result.free_cidr_count,
+                "IPv6 gap analysis successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 gap analysis failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/allocate",
+    params(
+        AllocateQuery
+    ),
+    responses(
+        (status = 200, description = "The lowest free IPv4 blocks of the requested size", body = AllocateResult),
+        (status = 400, description = "Invalid parameters or not enough aligned free space", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(supernet = %params.supernet, prefix = params.prefix, count = params.count))]
+async fn allocate_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<AllocateQuery>,
+) -> impl IntoResponse {
+    info!("Allocating IPv4 blocks");
+    let used: Vec<String> = params
+        .used
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match allocate_v4_with_limit(
+        &params.supernet,
+        params.prefix,
+        params.count.unwrap_or(1),
+        &used,
+        config.max_summarize_inputs,
+    ) {
+        Ok(result) => {
+            info!(
+                remaining = result.remaining_count,
+                "IPv4 allocation successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 allocation failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/allocate",
+    params(
+        AllocateQuery
+    ),
+    responses(
+        (status = 200, description = "The lowest free IPv6 blocks of the requested size", body = AllocateResult),
+        (status = 400, description = "Invalid parameters or not enough aligned free space", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(supernet = %params.supernet, prefix = params.prefix, count = params.count))]
+async fn allocate_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<AllocateQuery>,
+) -> impl IntoResponse {
+    info!("Allocating IPv6 blocks");
+    let used: Vec<String> = params
+        .used
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match allocate_v6_with_limit(
+        &params.supernet,
+        params.prefix,
+        params.count.unwrap_or(1),
+        &used,
+        config.max_summarize_inputs,
+        Ipv6Format::default(),
+    ) {
+        Ok(result) => {
+            info!(
+                remaining = result.remaining_count,
+                "IPv6 allocation successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 allocation failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v4/lpm",
+    params(
+        LpmQuery
+    ),
+    responses(
+        (status = 200, description = "Most specific CIDR containing the IPv4 address (matched is null when none does)", body = LpmResult),
+        (status = 400, description = "Invalid address or CIDR list", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(address = %params.address, cidrs = %params.cidrs))]
+async fn lpm_ipv4_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<LpmQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv4 longest-prefix match");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match lpm_ipv4_with_limit(&params.address, &cidrs, config.max_summarize_inputs) {
+        Ok(result) => {
+            info!(matched = ?result.matched, "IPv4 longest-prefix match successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv4 longest-prefix match failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/lpm",
+    params(
+        LpmQuery
+    ),
+    responses(
+        (status = 200, description = "Most specific CIDR containing the IPv6 address (matched is null when none does)", body = LpmResult),
+        (status = 400, description = "Invalid address or CIDR list", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(address = %params.address, cidrs = %params.cidrs))]
+async fn lpm_ipv6_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<LpmQuery>,
+) -> impl IntoResponse {
+    info!("Computing IPv6 longest-prefix match");
+    let cidrs: Vec<String> = params
+        .cidrs
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match lpm_ipv6_with_limit(&params.address, &cidrs, config.max_summarize_inputs) {
+        Ok(result) => {
+            info!(matched = ?result.matched, "IPv6 longest-prefix match successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 longest-prefix match failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/eui64",
+    params(
+        Eui64Query
+    ),
+    responses(
+        (status = 200, description = "SLAAC address derived from the MAC", body = Eui64Result),
+        (status = 400, description = "Invalid prefix or MAC address", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(prefix = %params.prefix, mac = %params.mac))]
+async fn eui64_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<Eui64Query>,
+) -> impl IntoResponse {
+    info!("Deriving EUI-64 address");
+    match derive_eui64(&params.prefix, &params.mac) {
+        Ok(result) => {
+            info!(address = %result.derived_address, "EUI-64 derivation successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "EUI-64 derivation failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/ifid",
+    params(
+        IfidQuery
+    ),
+    responses(
+        (status = 200, description = "Network and interface identifier of the address", body = InterfaceIdResult),
+        (status = 400, description = "Invalid IPv6 CIDR", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(cidr = %params.cidr))]
+async fn ifid_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<IfidQuery>,
+) -> impl IntoResponse {
+    info!("Splitting interface identifier");
+    match split_interface_id(&params.cidr) {
+        Ok(result) => {
+            info!(interface_id = %result.interface_id, "Interface identifier split successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "Interface identifier split failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    }
+}
+
+#[cfg_attr(feature = "swagger", utoipa::path(
+    get,
+    path = "/v6/format",
+    params(
+        Ipv6FormatQuery
+    ),
+    responses(
+        (status = 200, description = "RFC 5952 compressed and fully expanded forms of the address", body = Ipv6FormatResult),
+        (status = 400, description = "Invalid IPv6 address", body = ErrorResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(address = %params.address))]
+async fn ipv6_format_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Query(params): Query<Ipv6FormatQuery>,
+) -> impl IntoResponse {
+    info!("Formatting IPv6 address");
+    match expand_ipv6(&params.address) {
+        Ok(result) => {
+            info!(compressed = %result.compressed, "IPv6 formatting successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            warn!(error = %e, "IPv6 formatting failed");
+            json_response(
+                ErrorResponse {
+                    error: e.to_string(),
+                },
+                params.pretty,
+                StatusCode::BAD_REQUEST,
+            )
+        }
     }
+}
 
-    // Determine the actual count: None means generate max
-    let actual_count = if params.max {
-        None
-    } else {
-        match params.count {
-            Some(c) => Some(c),
-            None => {
-                warn!("Neither count nor max specified");
-                return json_response(
-                    ErrorResponse {
-                        error: "Either 'count' or 'max=true' must be specified".to_string(),
-                    },
-                    params.pretty,
-                    StatusCode::BAD_REQUEST,
-                );
+#[cfg_attr(feature = "swagger", utoipa::path(
+    post,
+    path = "/batch",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Batch CIDR processing results", body = BatchResult),
+        (status = 400, description = "Invalid request (e.g., empty CIDR list)", body = ErrorResponse),
+        (status = 413, description = "Request body exceeds max_body_size", body = PayloadTooLargeResponse)
+    ),
+    tag = "ipcalc"
+))]
+#[instrument(skip_all, fields(count = params.cidrs.len()))]
+async fn batch_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Json(params): Json<BatchRequest>,
+) -> impl IntoResponse {
+    info!("Processing batch CIDRs");
+    match process_batch_with_options(
+        &params.cidrs,
+        config.max_batch_size,
+        params.require_canonical,
+        true,
+    )
+    .and_then(|mut result| {
+        if !params.ignore.is_empty() || params.ignore_special {
+            let mut ignore = params.ignore;
+            if params.ignore_special {
+                ignore.extend(special_purpose_blocks());
             }
+            result.apply_ignore(&ignore)?;
         }
-    };
-
-    match generate_ipv4_subnets(&params.cidr, params.prefix, actual_count) {
+        if params.with_summary {
+            result.add_aggregate_summary(v6_format(params.v6_case))?;
+        }
+        if let Some(filter) = params.filter {
+            result.apply_filter(filter);
+        }
+        result.apply_ipv6_style(v6_format(params.v6_case));
+        Ok(result)
+    }) {
         Ok(result) => {
-            info!(
-                subnets_generated = result.subnets.len(),
-                "IPv4 split successful"
-            );
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            info!(count = result.count, "Batch processing successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv4 split failed");
+            warn!(error = %e, "Batch processing failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -579,69 +4063,38 @@ async fn split_ipv4(Query(params): Query<SplitQuery>) -> impl IntoResponse {
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
-    path = "/v6/split",
-    params(
-        SplitQuery
-    ),
+    post,
+    path = "/addresses",
+    request_body = AddressesRequest,
     responses(
-        (status = 200, description = "Generated IPv6 subnets", body = Ipv6SubnetList),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "Distinct address count across the CIDRs", body = AddressCountResult),
+        (status = 400, description = "Invalid request (e.g., empty or invalid CIDR list)", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(cidr = %params.cidr, prefix = params.prefix, count = ?params.count, max = params.max))]
-async fn split_ipv6(Query(params): Query<SplitQuery>) -> impl IntoResponse {
-    info!("Splitting IPv6 supernet");
-
-    if params.count_only {
-        return match count_subnets(&params.cidr, params.prefix) {
-            Ok(summary) => {
-                info!(available = %summary.available_subnets, "IPv6 count-only successful");
-                format_response(summary, params.format, params.pretty, StatusCode::OK)
-            }
-            Err(e) => {
-                warn!(error = %e, "IPv6 count-only failed");
-                json_response(
-                    ErrorResponse {
-                        error: e.to_string(),
-                    },
-                    params.pretty,
-                    StatusCode::BAD_REQUEST,
-                )
-            }
-        };
-    }
-
-    // Determine the actual count: None means generate max
-    let actual_count = if params.max {
-        None
-    } else {
-        match params.count {
-            Some(c) => Some(c),
-            None => {
-                warn!("Neither count nor max specified");
-                return json_response(
-                    ErrorResponse {
-                        error: "Either 'count' or 'max=true' must be specified".to_string(),
-                    },
-                    params.pretty,
-                    StatusCode::BAD_REQUEST,
-                );
-            }
-        }
-    };
-
-    match generate_ipv6_subnets(&params.cidr, params.prefix, actual_count) {
+#[instrument(skip_all, fields(count = params.cidrs.len()))]
+async fn addresses_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Json(params): Json<AddressesRequest>,
+) -> impl IntoResponse {
+    info!("Counting distinct addresses");
+    match count_addresses_with_limit(&params.cidrs, config.max_summarize_inputs) {
         Ok(result) => {
             info!(
-                subnets_generated = result.subnets.len(),
-                "IPv6 split successful"
+                blocks = result.block_count,
+                addresses = %result.address_count,
+                "Address count successful"
             );
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv6 split failed");
+            warn!(error = %e, "Address count failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -654,30 +4107,38 @@ async fn split_ipv6(Query(params): Query<SplitQuery>) -> impl IntoResponse {
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
-    path = "/v4/contains",
-    params(
-        ContainsQuery
-    ),
+    post,
+    path = "/overlaps",
+    request_body = OverlapsRequest,
     responses(
-        (status = 200, description = "IPv4 containment check result", body = ContainsResult),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "Overlapping pairs of CIDRs, each family checked on its own", body = OverlapResult),
+        (status = 400, description = "Invalid request (e.g., invalid CIDR)", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(cidr = %params.cidr, address = %params.address))]
-async fn contains_ipv4(Query(params): Query<ContainsQuery>) -> impl IntoResponse {
-    info!("Checking IPv4 address containment");
-    match check_ipv4_contains(&params.cidr, &params.address) {
+#[instrument(skip_all, fields(count = params.cidrs.len()))]
+async fn overlaps_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Json(params): Json<OverlapsRequest>,
+) -> impl IntoResponse {
+    info!("Finding overlapping CIDRs");
+    match find_overlaps_with_limit(&params.cidrs, config.max_summarize_inputs) {
         Ok(result) => {
             info!(
-                contained = result.contained,
-                "IPv4 containment check successful"
+                input = result.input_count,
+                overlaps = result.overlap_count,
+                "Overlap check successful"
             );
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv4 containment check failed");
+            warn!(error = %e, "Overlap check failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -690,30 +4151,34 @@ async fn contains_ipv4(Query(params): Query<ContainsQuery>) -> impl IntoResponse
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
-    path = "/v6/contains",
-    params(
-        ContainsQuery
-    ),
+    post,
+    path = "/summarize-grouped",
+    request_body = SummarizeGroupedRequest,
     responses(
-        (status = 200, description = "IPv6 containment check result", body = ContainsResult),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "One summarized set per tag value", body = GroupedSummaryResult),
+        (status = 400, description = "Invalid request (e.g., missing tag or invalid CIDR)", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(cidr = %params.cidr, address = %params.address))]
-async fn contains_ipv6(Query(params): Query<ContainsQuery>) -> impl IntoResponse {
-    info!("Checking IPv6 address containment");
-    match check_ipv6_contains(&params.cidr, &params.address) {
+#[instrument(skip_all, fields(tag = %params.tag, count = params.cidrs.len()))]
+async fn summarize_grouped_handler(
+    Extension(config): Extension<Arc<ServerConfig>>,
+    Json(params): Json<SummarizeGroupedRequest>,
+) -> impl IntoResponse {
+    info!("Summarizing CIDRs grouped by tag");
+    match summarize_grouped_with_limit(&params.cidrs, &params.tag, config.max_summarize_inputs) {
         Ok(result) => {
-            info!(
-                contained = result.contained,
-                "IPv6 containment check successful"
-            );
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            info!(groups = result.groups.len(), "Grouped summarize successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv6 containment check failed");
+            warn!(error = %e, "Grouped summarize failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -726,41 +4191,39 @@ async fn contains_ipv6(Query(params): Query<ContainsQuery>) -> impl IntoResponse
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
-    path = "/v4/summarize",
-    params(
-        SummarizeQuery
-    ),
+    post,
+    path = "/covers",
+    request_body = CoversRequest,
     responses(
-        (status = 200, description = "Summarized IPv4 CIDRs", body = Ipv4SummaryResult),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "Coverage report; `exact` is false when there are gaps or excess", body = CoverageResult),
+        (status = 400, description = "Invalid request (e.g., empty or invalid CIDR list)", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(cidrs = %params.cidrs))]
-async fn summarize_ipv4_handler(
+#[instrument(skip_all, fields(target = %params.target, count = params.cidrs.len()))]
+async fn covers_handler(
     Extension(config): Extension<Arc<ServerConfig>>,
-    Query(params): Query<SummarizeQuery>,
+    Json(params): Json<CoversRequest>,
 ) -> impl IntoResponse {
-    info!("Summarizing IPv4 CIDRs");
-    let cidrs: Vec<String> = params
-        .cidrs
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    match summarize_ipv4_with_limit(&cidrs, config.max_summarize_inputs) {
+    info!("Checking coverage");
+    match check_coverage_with_limit(&params.target, &params.cidrs, config.max_summarize_inputs) {
         Ok(result) => {
             info!(
-                input = result.input_count,
-                output = result.output_count,
-                "IPv4 summarization successful"
+                exact = result.exact,
+                gaps = result.gaps.len(),
+                excess = result.excess.len(),
+                "Coverage check successful"
             );
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv4 summarization failed");
+            warn!(error = %e, "Coverage check failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -773,41 +4236,34 @@ async fn summarize_ipv4_handler(
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
-    path = "/v6/summarize",
-    params(
-        SummarizeQuery
-    ),
+    post,
+    path = "/match",
+    request_body = MatchRequest,
     responses(
-        (status = 200, description = "Summarized IPv6 CIDRs", body = Ipv6SummaryResult),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "Most specific CIDR containing the address (matched is null when none does)", body = LpmResult),
+        (status = 400, description = "Invalid request or mixed IPv4 and IPv6 inputs", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(cidrs = %params.cidrs))]
-async fn summarize_ipv6_handler(
+#[instrument(skip_all, fields(address = %params.address, count = params.cidrs.len()))]
+async fn match_handler(
     Extension(config): Extension<Arc<ServerConfig>>,
-    Query(params): Query<SummarizeQuery>,
+    Json(params): Json<MatchRequest>,
 ) -> impl IntoResponse {
-    info!("Summarizing IPv6 CIDRs");
-    let cidrs: Vec<String> = params
-        .cidrs
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    match summarize_ipv6_with_limit(&cidrs, config.max_summarize_inputs) {
+    info!("Computing longest-prefix match");
+    match lpm_with_limit(&params.address, &params.cidrs, config.max_summarize_inputs) {
         Ok(result) => {
-            info!(
-                input = result.input_count,
-                output = result.output_count,
-                "IPv6 summarization successful"
-            );
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            info!(matched = ?result.matched, "Longest-prefix match successful");
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv6 summarization failed");
+            warn!(error = %e, "Longest-prefix match failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -820,30 +4276,42 @@ async fn summarize_ipv6_handler(
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
-    path = "/v4/from-range",
-    params(
-        FromRangeQuery
-    ),
+    post,
+    path = "/v4/available",
+    request_body = AvailableRequest,
     responses(
-        (status = 200, description = "CIDR blocks covering the IPv4 range", body = Ipv4FromRangeResult),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "Free IPv4 blocks of the requested size", body = AvailableResult),
+        (status = 400, description = "Invalid request or too many blocks", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(start = %params.start, end = %params.end))]
-async fn from_range_ipv4_handler(
+#[instrument(skip_all, fields(supernet = %params.supernet, prefix = params.prefix, used = params.used.len()))]
+async fn available_ipv4_handler(
     Extension(config): Extension<Arc<ServerConfig>>,
-    Query(params): Query<FromRangeQuery>,
+    Json(params): Json<AvailableRequest>,
 ) -> impl IntoResponse {
-    info!("Converting IPv4 range to CIDRs");
-    match from_range_ipv4_with_limit(&params.start, &params.end, config.max_generated_cidrs) {
+    info!("Finding available IPv4 blocks");
+    match find_available_v4_with_limit(
+        &params.supernet,
+        params.prefix,
+        &params.used,
+        config.max_summarize_inputs,
+    ) {
         Ok(result) => {
-            info!(cidr_count = result.cidr_count, "IPv4 from-range successful");
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            info!(
+                available = result.available_count,
+                "IPv4 availability successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv4 from-range failed");
+            warn!(error = %e, "IPv4 availability failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -856,30 +4324,42 @@ async fn from_range_ipv4_handler(
 }
 
 #[cfg_attr(feature = "swagger", utoipa::path(
-    get,
-    path = "/v6/from-range",
-    params(
-        FromRangeQuery
-    ),
+    post,
+    path = "/v6/available",
+    request_body = AvailableRequest,
     responses(
-        (status = 200, description = "CIDR blocks covering the IPv6 range", body = Ipv6FromRangeResult),
-        (status = 400, description = "Invalid parameters", body = ErrorResponse)
+        (status = 200, description = "Free IPv6 blocks of the requested size", body = AvailableResult),
+        (status = 400, description = "Invalid request or too many blocks", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(start = %params.start, end = %params.end))]
-async fn from_range_ipv6_handler(
+#[instrument(skip_all, fields(supernet = %params.supernet, prefix = params.prefix, used = params.used.len()))]
+async fn available_ipv6_handler(
     Extension(config): Extension<Arc<ServerConfig>>,
-    Query(params): Query<FromRangeQuery>,
+    Json(params): Json<AvailableRequest>,
 ) -> impl IntoResponse {
-    info!("Converting IPv6 range to CIDRs");
-    match from_range_ipv6_with_limit(&params.start, &params.end, config.max_generated_cidrs) {
+    info!("Finding available IPv6 blocks");
+    match find_available_v6_with_limit(
+        &params.supernet,
+        params.prefix,
+        &params.used,
+        config.max_summarize_inputs,
+    ) {
         Ok(result) => {
-            info!(cidr_count = result.cidr_count, "IPv6 from-range successful");
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            info!(
+                available = result.available_count,
+                "IPv6 availability successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "IPv6 from-range failed");
+            warn!(error = %e, "IPv6 availability failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -893,27 +4373,43 @@ async fn from_range_ipv6_handler(
 
 #[cfg_attr(feature = "swagger", utoipa::path(
     post,
-    path = "/batch",
-    request_body = BatchRequest,
+    path = "/v4/vlsm",
+    request_body = VlsmRequest,
     responses(
-        (status = 200, description = "Batch CIDR processing results", body = BatchResult),
-        (status = 400, description = "Invalid request (e.g., empty CIDR list)", body = ErrorResponse)
+        (status = 200, description = "One subnet per host requirement, largest first", body = VlsmResult),
+        (status = 400, description = "Invalid request or requirements do not fit", body = ErrorResponse)
     ),
     tag = "ipcalc"
 ))]
-#[instrument(skip_all, fields(count = params.cidrs.len()))]
-async fn batch_handler(
+#[instrument(skip_all, fields(supernet = %params.supernet, requirements = params.requirements.len()))]
+async fn vlsm_ipv4_handler(
     Extension(config): Extension<Arc<ServerConfig>>,
-    Json(params): Json<BatchRequest>,
+    Json(params): Json<VlsmRequest>,
 ) -> impl IntoResponse {
-    info!("Processing batch CIDRs");
-    match process_batch_with_limit(&params.cidrs, config.max_batch_size) {
+    info!("Planning IPv4 VLSM allocation");
+    let result = match params.p2p_usable {
+        0 | 2 => allocate_vlsm(&params.supernet, &params.requirements, params.p2p_usable),
+        n => Err(IpCalcError::InvalidInput(format!(
+            "p2p_usable must be 0 or 2, got {}",
+            n
+        ))),
+    };
+    match result {
         Ok(result) => {
-            info!(count = result.count, "Batch processing successful");
-            format_response(result, params.format, params.pretty, StatusCode::OK)
+            info!(
+                remaining = result.remaining_addresses,
+                "IPv4 VLSM allocation successful"
+            );
+            format_response(
+                result,
+                params.format,
+                params.pretty,
+                config.result_kind,
+                StatusCode::OK,
+            )
         }
         Err(e) => {
-            warn!(error = %e, "Batch processing failed");
+            warn!(error = %e, "IPv4 VLSM allocation failed");
             json_response(
                 ErrorResponse {
                     error: e.to_string(),
@@ -931,6 +4427,56 @@ struct FeaturesResponse {
     swagger: bool,
 }
 
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render the landing page from [`ENDPOINTS`].
+fn render_landing_page() -> String {
+    let mut rows = String::new();
+    for ep in ENDPOINTS {
+        let example = if ep.method == "GET" {
+            format!(r#"<a href="{0}">{0}</a>"#, html_escape(ep.example))
+        } else {
+            format!("<code>{}</code>", html_escape(ep.example))
+        };
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+            ep.method,
+            html_escape(ep.usage),
+            html_escape(ep.description),
+            example
+        ));
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head><meta charset="utf-8"><title>ipcalc API</title></head>
+<body>
+<h1>ipcalc API {version}</h1>
+<p>Subnet calculator endpoints. GET endpoints accept <code>format=json|text|csv|yaml</code> and <code>pretty=true</code>.</p>
+<table border="1" cellpadding="4">
+<tr><th>Method</th><th>Endpoint</th><th>Description</th><th>Example</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+        version = env!("CARGO_PKG_VERSION"),
+        rows = rows
+    )
+}
+
+async fn landing_page() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        render_landing_page(),
+    )
+}
+
 async fn dashboard() -> impl IntoResponse {
     (
         StatusCode::OK,
@@ -938,3 +4484,95 @@ async fn dashboard() -> impl IntoResponse {
         include_str!("../dashboard.html"),
     )
 }
+
+#[cfg(feature = "webui")]
+async fn web_ui() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        include_str!("../webui.html"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tower::ServiceExt;
+
+    fn builder(server: ServerConfig) -> RouterBuilder {
+        RouterBuilder::new(RouterConfig {
+            server,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_timeout_wraps_handlers() {
+        let app = builder(ServerConfig {
+            timeout_seconds: 1,
+            ..Default::default()
+        })
+        .wrap(Router::new().route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                "done"
+            }),
+        ));
+        let req = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::REQUEST_TIMEOUT);
+        // The security headers wrap the timeout's own response
+        assert_eq!(resp.headers()[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_rejects_before_handler() {
+        let reached = Arc::new(AtomicBool::new(false));
+        let flag = reached.clone();
+        let app = builder(ServerConfig {
+            max_body_size: 16,
+            ..Default::default()
+        })
+        .wrap(Router::new().route(
+            "/echo",
+            post(move |body: String| async move {
+                flag.store(true, Ordering::SeqCst);
+                body
+            }),
+        ));
+        let req = Request::builder()
+            .method("POST")
+            .uri("/echo")
+            .header(header::CONTENT_LENGTH, 64)
+            .body(Body::from("x".repeat(64)))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(resp.headers()[header::CACHE_CONTROL], "no-store");
+        assert!(!reached.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_client_address_visible_to_inner_stages() {
+        let app = builder(ServerConfig::default()).wrap(Router::new().route(
+            "/ip",
+            get(|Extension(ClientIp(ip)): Extension<ClientIp>| async move { ip.to_string() }),
+        ));
+        let mut req = Request::builder().uri("/ip").body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(axum::extract::ConnectInfo(std::net::SocketAddr::from((
+                [192, 0, 2, 7],
+                4000,
+            ))));
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"192.0.2.7");
+    }
+}
@@ -0,0 +1,149 @@
+use crate::error::{IpCalcError, Result};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// An address moved by an integer offset.
+///
+/// The offset is a decimal string since an IPv6 offset can exceed i64.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IpAddResult {
+    pub address: String,
+    pub offset: String,
+    pub result: String,
+}
+
+fn out_of_range(address: &str, offset: impl ToString) -> IpCalcError {
+    IpCalcError::AddressOutOfRange {
+        address: address.to_string(),
+        offset: offset.to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// The IPv4 address `offset` addresses after `address`, or before it when
+/// `offset` is negative. Stepping outside 0.0.0.0-255.255.255.255 is an error.
+pub fn ip_add_v4(address: &str, offset: i64) -> Result<String> {
+    let addr: Ipv4Addr = address
+        .trim()
+        .parse()
+        .map_err(|_| IpCalcError::InvalidIpv4Address(address.to_string()))?;
+    let sum = i64::from(u32::from(addr))
+        .checked_add(offset)
+        .and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| out_of_range(address, offset))?;
+    Ok(Ipv4Addr::from(sum).to_string())
+}
+
+/// IPv6 counterpart of [`ip_add_v4`].
+pub fn ip_add_v6(address: &str, offset: i128) -> Result<String> {
+    let addr: Ipv6Addr = address
+        .trim()
+        .parse()
+        .map_err(|_| IpCalcError::InvalidIpv6Address(address.to_string()))?;
+    let base = u128::from(addr);
+    let sum = if offset < 0 {
+        base.checked_sub(offset.unsigned_abs())
+    } else {
+        base.checked_add(offset as u128)
+    }
+    .ok_or_else(|| out_of_range(address, offset))?;
+    Ok(Ipv6Addr::from(sum).to_string())
+}
+
+/// [`ip_add_v4`] with the address and offset it was given.
+pub fn add_ipv4(address: &str, offset: i64) -> Result<IpAddResult> {
+    Ok(IpAddResult {
+        address: address.trim().to_string(),
+        offset: offset.to_string(),
+        result: ip_add_v4(address, offset)?,
+    })
+}
+
+/// Add `offset` to an IPv4 or IPv6 address, picking the family from the
+/// address.
+pub fn add_ip(address: &str, offset: i128) -> Result<IpAddResult> {
+    let result = if address.contains(':') {
+        ip_add_v6(address, offset)?
+    } else {
+        let offset = i64::try_from(offset).map_err(|_| out_of_range(address, offset))?;
+        ip_add_v4(address, offset)?
+    };
+    Ok(IpAddResult {
+        address: address.trim().to_string(),
+        offset: offset.to_string(),
+        result,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_v4() {
+        assert_eq!(ip_add_v4("10.0.0.0", 256).unwrap(), "10.0.1.0");
+        assert_eq!(ip_add_v4("10.0.0.0", -1).unwrap(), "9.255.255.255");
+        assert_eq!(ip_add_v4("10.0.0.5", 0).unwrap(), "10.0.0.5");
+        assert_eq!(ip_add_v4("0.0.0.1", -1).unwrap(), "0.0.0.0");
+        assert_eq!(ip_add_v4("255.255.255.254", 1).unwrap(), "255.255.255.255");
+    }
+
+    #[test]
+    fn test_add_v4_out_of_range() {
+        let err = ip_add_v4("0.0.0.0", -1).unwrap_err();
+        assert!(matches!(err, IpCalcError::AddressOutOfRange { .. }));
+        assert!(ip_add_v4("255.255.255.255", 1).is_err());
+        assert!(ip_add_v4("10.0.0.0", i64::MAX).is_err());
+        assert!(ip_add_v4("10.0.0.0", i64::MIN).is_err());
+        assert!(matches!(
+            ip_add_v4("2001:db8::", 1),
+            Err(IpCalcError::InvalidIpv4Address(_))
+        ));
+    }
+
+    #[test]
+    fn test_add_v6() {
+        assert_eq!(ip_add_v6("2001:db8::", 256).unwrap(), "2001:db8::100");
+        assert_eq!(
+            ip_add_v6("2001:db8::", -1).unwrap(),
+            "2001:db7:ffff:ffff:ffff:ffff:ffff:ffff"
+        );
+        // Jumping a whole /64
+        assert_eq!(ip_add_v6("2001:db8::", 1 << 64).unwrap(), "2001:db8:0:1::");
+        assert_eq!(
+            ip_add_v6("::", i128::MAX).unwrap(),
+            "7fff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+        );
+        assert_eq!(ip_add_v6("8000::", i128::MIN).unwrap(), "::");
+    }
+
+    #[test]
+    fn test_add_v6_out_of_range() {
+        assert!(ip_add_v6("::", -1).is_err());
+        assert!(ip_add_v6("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff", 1).is_err());
+        assert!(ip_add_v6("7fff::", i128::MIN).is_err());
+    }
+
+    #[test]
+    fn test_add_ip_dispatches_on_family() {
+        let result = add_ip("10.0.0.0", 256).unwrap();
+        assert_eq!(result.result, "10.0.1.0");
+        assert_eq!(result.offset, "256");
+        assert_eq!(add_ip("2001:db8::1", -1).unwrap().result, "2001:db8::");
+        // An IPv4 offset beyond i64 can only overflow
+        assert!(add_ip("10.0.0.0", 1 << 64).is_err());
+    }
+}
@@ -0,0 +1,406 @@
+use crate::config::DEFAULTS;
+use crate::covers::{Block, exclude, format_block, format_block_as};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use crate::subnet_generator::check_generation_limit;
+use crate::summarize::{summarize_ipv4_with_limit, summarize_ipv6_with_limit};
+use crate::validation::{Family, validate_prefix};
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// Every block of one size inside a supernet that overlaps no used CIDR.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AvailableResult {
+    pub supernet: String,
+    pub prefix: u8,
+    pub used_count: usize,
+    pub available_count: usize,
+    pub available: Vec<String>,
+}
+
+/// The lowest free blocks of one size in a supernet, as an allocator would
+/// hand them out. `remaining_count` is how many free blocks of that size are
+/// left afterwards (saturating at u64::MAX).
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AllocateResult {
+    pub supernet: String,
+    pub prefix: u8,
+    pub used_count: usize,
+    pub requested: u64,
+    pub allocated: Vec<String>,
+    pub remaining_count: u64,
+}
+
+// ---------------------------------------------------------------------------
+// Deaggregation over (network, prefix) pairs
+// ---------------------------------------------------------------------------
+
+/// Blocks of `prefix` in `free`; free blocks smaller than `prefix` hold none.
+fn block_count(free: &[Block], prefix: u8) -> u64 {
+    free.iter()
+        .filter(|&&(_, p)| p <= prefix)
+        .map(|&(_, p)| 1u64.checked_shl(u32::from(prefix - p)).unwrap_or(u64::MAX))
+        .fold(0u64, u64::saturating_add)
+}
+
+/// The free space around `holes`, after checking `prefix` fits the supernet.
+fn free_space(supernet: Block, prefix: u8, holes: &[Block], bits: u8) -> Result<Vec<Block>> {
+    validate_prefix(prefix, if bits == 32 { Family::V4 } else { Family::V6 })?;
+    if prefix < supernet.1 {
+        return Err(IpCalcError::InvalidSubnetSplit {
+            new_prefix: prefix,
+            original_prefix: supernet.1,
+        });
+    }
+    let mut free = Vec::new();
+    exclude(supernet, holes, bits, &mut free);
+    Ok(free)
+}
+
+/// Every `prefix`-sized block of the free blocks, lazily and in ascending
+/// order. Free blocks smaller than `prefix` are skipped, so space that is
+/// free but not aligned to `prefix` yields nothing.
+fn deaggregate(free: Vec<Block>, prefix: u8, bits: u8) -> impl Iterator<Item = Block> {
+    let step = 1u128.checked_shl(u32::from(bits - prefix)).unwrap_or(0);
+    free.into_iter()
+        .filter(move |&(_, p)| p <= prefix)
+        .flat_map(move |(network, p)| {
+            let n = 1u128
+                .checked_shl(u32::from(prefix - p))
+                .unwrap_or(u128::MAX);
+            (0..n).map(move |i| (network + i * step, prefix))
+        })
+}
+
+/// Split the free space around `holes` into `prefix`-sized blocks, in
+/// ascending order.
+fn available_blocks(supernet: Block, prefix: u8, holes: &[Block], bits: u8) -> Result<Vec<Block>> {
+    let free = free_space(supernet, prefix, holes, bits)?;
+    check_generation_limit(block_count(&free, prefix))?;
+    Ok(deaggregate(free, prefix, bits).collect())
+}
+
+fn available_result(
+    supernet: Block,
+    prefix: u8,
+    used_count: usize,
+    holes: &[Block],
+    bits: u8,
+) -> Result<AvailableResult> {
+    let available: Vec<String> = available_blocks(supernet, prefix, holes, bits)?
+        .into_iter()
+        .map(|b| format_block(b, bits))
+        .collect();
+    Ok(AvailableResult {
+        supernet: format_block(supernet, bits),
+        prefix,
+        used_count,
+        available_count: available.len(),
+        available,
+    })
+}
+
+/// Allocate `count` blocks, with IPv6 blocks in the result rendered in
+/// `format`'s case.
+fn allocate_result(
+    supernet: Block,
+    prefix: u8,
+    count: u64,
+    used_count: usize,
+    holes: &[Block],
+    bits: u8,
+    format: Ipv6Format,
+) -> Result<AllocateResult> {
+    if count == 0 {
+        return Err(IpCalcError::InvalidInput(
+            "count must be at least 1".to_string(),
+        ));
+    }
+    check_generation_limit(count)?;
+    let free = free_space(supernet, prefix, holes, bits)?;
+    let fits = block_count(&free, prefix);
+    if fits == 0 {
+        return Err(IpCalcError::NoFreeSpace {
+            supernet: format_block(supernet, bits),
+            prefix,
+        });
+    }
+    if fits < count {
+        return Err(IpCalcError::InsufficientFreeSpace {
+            supernet: format_block(supernet, bits),
+            prefix,
+            requested: count,
+            available: fits,
+        });
+    }
+
+    Ok(AllocateResult {
+        supernet: format_block_as(supernet, bits, format),
+        prefix,
+        used_count,
+        requested: count,
+        allocated: deaggregate(free, prefix, bits)
+            .take(count as usize)
+            .map(|b| format_block_as(b, bits, format))
+            .collect(),
+        remaining_count: fits - count,
+    })
+}
+
+fn holes_v4(used: &[String], max_inputs: usize) -> Result<Vec<Block>> {
+    if used.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(summarize_ipv4_with_limit(used, max_inputs)?
+        .cidrs
+        .iter()
+        .map(|s| (u32::from(s.network) as u128, s.prefix_length))
+        .collect())
+}
+
+fn holes_v6(used: &[String], max_inputs: usize) -> Result<Vec<Block>> {
+    if used.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(summarize_ipv6_with_limit(used, max_inputs)?
+        .cidrs
+        .iter()
+        .map(|s| (u128::from(s.network), s.prefix_length))
+        .collect())
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+pub fn find_available_v4(supernet: &str, prefix: u8, used: &[String]) -> Result<AvailableResult> {
+    find_available_v4_with_limit(supernet, prefix, used, DEFAULTS.max_summarize_inputs)
+}
+
+/// Every /`prefix` block in `supernet` that overlaps none of `used`.
+///
+/// Used CIDRs outside the supernet are ignored. The output is capped by the
+/// subnet generation limit.
+pub fn find_available_v4_with_limit(
+    supernet: &str,
+    prefix: u8,
+    used: &[String],
+    max_inputs: usize,
+) -> Result<AvailableResult> {
+    let s = Ipv4Subnet::from_cidr(supernet)?;
+    let holes = holes_v4(used, max_inputs)?;
+    let block = (u32::from(s.network) as u128, s.prefix_length);
+    available_result(block, prefix, used.len(), &holes, 32)
+}
+
+pub fn find_available_v6(supernet: &str, prefix: u8, used: &[String]) -> Result<AvailableResult> {
+    find_available_v6_with_limit(supernet, prefix, used, DEFAULTS.max_summarize_inputs)
+}
+
+/// IPv6 counterpart of [`find_available_v4_with_limit`].
+pub fn find_available_v6_with_limit(
+    supernet: &str,
+    prefix: u8,
+    used: &[String],
+    max_inputs: usize,
+) -> Result<AvailableResult> {
+    let s = Ipv6Subnet::from_cidr(supernet)?;
+    let holes = holes_v6(used, max_inputs)?;
+    let block = (u128::from(s.network), s.prefix_length);
+    available_result(block, prefix, used.len(), &holes, 128)
+}
+
+pub fn allocate_v4(
+    supernet: &str,
+    prefix: u8,
+    count: u64,
+    used: &[String],
+) -> Result<AllocateResult> {
+    allocate_v4_with_limit(supernet, prefix, count, used, DEFAULTS.max_summarize_inputs)
+}
+
+/// The first `count` /`prefix` blocks in `supernet`, lowest first, that
+/// overlap none of `used`. Fails unless all of them fit.
+///
+/// Used CIDRs outside the supernet are ignored, as in
+/// [`find_available_v4_with_limit`].
+pub fn allocate_v4_with_limit(
+    supernet: &str,
+    prefix: u8,
+    count: u64,
+    used: &[String],
+    max_inputs: usize,
+) -> Result<AllocateResult> {
+    let s = Ipv4Subnet::from_cidr(supernet)?;
+    let holes = holes_v4(used, max_inputs)?;
+    let block = (u32::from(s.network) as u128, s.prefix_length);
+    let format = Ipv6Format::default();
+    allocate_result(block, prefix, count, used.len(), &holes, 32, format)
+}
+
+pub fn allocate_v6(
+    supernet: &str,
+    prefix: u8,
+    count: u64,
+    used: &[String],
+    format: Ipv6Format,
+) -> Result<AllocateResult> {
+    let max_inputs = DEFAULTS.max_summarize_inputs;
+    allocate_v6_with_limit(supernet, prefix, count, used, max_inputs, format)
+}
+
+/// IPv6 counterpart of [`allocate_v4_with_limit`], with the supernet and
+/// allocated blocks rendered in `format`'s case.
+pub fn allocate_v6_with_limit(
+    supernet: &str,
+    prefix: u8,
+    count: u64,
+    used: &[String],
+    max_inputs: usize,
+    format: Ipv6Format,
+) -> Result<AllocateResult> {
+    let s = Ipv6Subnet::from_cidr(supernet)?;
+    let holes = holes_v6(used, max_inputs)?;
+    let block = (u128::from(s.network), s.prefix_length);
+    allocate_result(block, prefix, count, used.len(), &holes, 128, format)
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_partially_used_ipv4() {
+        let result = find_available_v4(
+            "192.168.1.0/24",
+            26,
+            &strings(&["192.168.1.0/26", "192.168.1.200/29"]),
+        )
+        .unwrap();
+        assert_eq!(result.available, ["192.168.1.64/26", "192.168.1.128/26"]);
+        assert_eq!(result.available_count, 2);
+        assert_eq!(result.used_count, 2);
+
+        // Nothing used: the whole supernet, deaggregated
+        let result = find_available_v4("10.0.0.0/22", 24, &[]).unwrap();
+        assert_eq!(
+            result.available,
+            ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/24", "10.0.3.0/24"]
+        );
+
+        // Used space outside the supernet is ignored; a covering block leaves nothing
+        let result = find_available_v4("10.0.0.0/24", 25, &strings(&["172.16.0.0/12"])).unwrap();
+        assert_eq!(result.available_count, 2);
+        let result = find_available_v4("10.0.0.0/24", 25, &strings(&["10.0.0.0/8"])).unwrap();
+        assert!(result.available.is_empty());
+    }
+
+    #[test]
+    fn test_partially_used_ipv6() {
+        let result =
+            find_available_v6("2001:db8::/46", 48, &strings(&["2001:db8:1::/48"])).unwrap();
+        assert_eq!(
+            result.available,
+            ["2001:db8::/48", "2001:db8:2::/48", "2001:db8:3::/48"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        assert!(matches!(
+            find_available_v4("10.0.0.0/24", 16, &[]),
+            Err(IpCalcError::InvalidSubnetSplit { .. })
+        ));
+        assert!(matches!(
+            find_available_v4("10.0.0.0/24", 33, &[]),
+            Err(IpCalcError::InvalidPrefixLength(33))
+        ));
+        assert!(matches!(
+            find_available_v6("2001:db8::/32", 64, &[]),
+            Err(IpCalcError::SubnetLimitExceeded { .. })
+        ));
+        assert!(find_available_v4("10.0.0.0/24", 26, &strings(&["2001:db8::/32"])).is_err());
+    }
+
+    #[test]
+    fn test_allocate_first_free() {
+        let used = strings(&["10.0.0.0/24", "10.0.1.0/24"]);
+        let result = allocate_v4("10.0.0.0/16", 24, 1, &used).unwrap();
+        assert_eq!(result.allocated, ["10.0.2.0/24"]);
+        assert_eq!(result.remaining_count, 253);
+
+        let result = allocate_v4("10.0.0.0/16", 24, 3, &used).unwrap();
+        assert_eq!(
+            result.allocated,
+            ["10.0.2.0/24", "10.0.3.0/24", "10.0.4.0/24"]
+        );
+
+        // /64s in a /48 never enumerate the whole free space
+        let used = strings(&["2001:db8::/64"]);
+        let result = allocate_v6("2001:db8::/48", 64, 2, &used, Ipv6Format::default()).unwrap();
+        assert_eq!(result.allocated, ["2001:db8:0:1::/64", "2001:db8:0:2::/64"]);
+        assert_eq!(result.remaining_count, 65533);
+
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let result = allocate_v6("2001:db8:ab::/48", 64, 1, &[], upper).unwrap();
+        assert_eq!(result.supernet, "2001:DB8:AB::/48");
+        assert_eq!(result.allocated, ["2001:DB8:AB::/64"]);
+    }
+
+    #[test]
+    fn test_allocate_skips_unaligned_space() {
+        // 128 addresses are free, but as two /26s in different /25s
+        let used = strings(&["10.0.0.64/26", "10.0.0.192/26"]);
+        assert!(matches!(
+            allocate_v4("10.0.0.0/24", 25, 1, &used),
+            Err(IpCalcError::NoFreeSpace { prefix: 25, .. })
+        ));
+        let result = allocate_v4("10.0.0.0/24", 26, 2, &used).unwrap();
+        assert_eq!(result.allocated, ["10.0.0.0/26", "10.0.0.128/26"]);
+
+        // A /28 hole in the first /24 pushes a /24 past it
+        let result = allocate_v4("10.0.0.0/22", 24, 1, &strings(&["10.0.0.16/28"])).unwrap();
+        assert_eq!(result.allocated, ["10.0.1.0/24"]);
+
+        // Smaller fragments come before a later aligned block
+        let used = strings(&["10.0.0.0/27", "10.0.0.64/26", "10.0.1.0/25"]);
+        let result = allocate_v4("10.0.0.0/23", 27, 3, &used).unwrap();
+        assert_eq!(
+            result.allocated,
+            ["10.0.0.32/27", "10.0.0.128/27", "10.0.0.160/27"]
+        );
+    }
+
+    #[test]
+    fn test_allocate_short_or_invalid() {
+        let used = strings(&["10.0.0.0/25"]);
+        let err = allocate_v4("10.0.0.0/24", 26, 3, &used).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "10.0.0.0/24 has room for only 2 free /26 blocks, 3 requested"
+        );
+        assert!(matches!(
+            allocate_v4("10.0.0.0/24", 24, 1, &strings(&["10.0.0.7/32"])),
+            Err(IpCalcError::NoFreeSpace { .. })
+        ));
+        assert!(allocate_v4("10.0.0.0/24", 26, 0, &[]).is_err());
+        assert!(allocate_v4("10.0.0.0/24", 16, 1, &[]).is_err());
+        assert!(allocate_v6("::/0", 128, u64::MAX, &[], Ipv6Format::default()).is_err());
+    }
+}
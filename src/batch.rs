@@ -1,12 +1,20 @@
+use crate::config::DEFAULTS;
+use crate::covers::{Block, contains};
 use crate::error::{IpCalcError, Result};
 use crate::ipv4::Ipv4Subnet;
-use crate::ipv6::Ipv6Subnet;
-use serde::Serialize;
+use crate::ipv6::{Ipv6Format, Ipv6Style, Ipv6Subnet, format_ipv6};
+use crate::summarize::{summarize_ipv4_with_limit, summarize_ipv6_with_options};
+use crate::validation::{parse_cidr_parts, with_host_prefix};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
 
 /// A subnet calculation result that can be either IPv4 or IPv6.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "version")]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum SubnetResult {
     #[serde(rename = "v4")]
     V4(Ipv4Subnet),
@@ -15,43 +23,323 @@ pub enum SubnetResult {
 }
 
 /// The result for a single CIDR entry in a batch — either a subnet or an error message.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum BatchEntryResult {
     Ok { subnet: Box<SubnetResult> },
     Err { error: String },
 }
 
 /// A single entry in a batch result, pairing the input CIDR with its result.
-#[derive(Debug, Clone, Serialize)]
+///
+/// `tags` holds `key=value` pairs parsed from a trailing `#` comment on the
+/// input line; `warnings` lists any comment tokens that were ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BatchEntry {
     pub cidr: String,
     #[serde(flatten)]
     pub result: BatchEntryResult,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// A batch input line split into its CIDR and any trailing comment tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchLine {
+    pub cidr: String,
+    pub tags: BTreeMap<String, String>,
+    pub warnings: Vec<String>,
 }
 
 /// The top-level result of processing a batch of CIDRs.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BatchResult {
     pub count: usize,
+    /// IPv6 entries not written in RFC 5952 canonical form
+    #[serde(default)]
+    pub non_canonical: usize,
+    /// Entries answered from an earlier identical CIDR instead of recomputed
+    #[serde(default)]
+    pub cache_hits: usize,
+    /// Which entries `results` was narrowed to by [`BatchResult::apply_filter`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BatchFilter>,
+    /// Entries left in `results` after filtering; `count` still covers the
+    /// whole batch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shown_count: Option<usize>,
+    /// Entries dropped by [`BatchResult::apply_ignore`]; `count` leaves them out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignored_count: Option<usize>,
+    /// The first [`MAX_LISTED_IGNORED`] dropped entries, as given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored: Vec<String>,
     pub results: Vec<BatchEntry>,
+    /// Minimal covering set of the valid entries, present when requested
+    /// with `--with-summary`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate_summary: Option<AggregateSummary>,
+}
+
+/// How many ignored entries [`BatchResult::apply_ignore`] lists by name.
+pub const MAX_LISTED_IGNORED: usize = 100;
+
+/// Which batch entries to keep in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BatchFilter {
+    /// Only entries that failed to parse
+    Errors,
+    /// Only entries that parsed
+    Ok,
+}
+
+impl BatchFilter {
+    fn keeps(self, entry: &BatchEntry) -> bool {
+        let ok = matches!(entry.result, BatchEntryResult::Ok { .. });
+        match self {
+            BatchFilter::Errors => !ok,
+            BatchFilter::Ok => ok,
+        }
+    }
+}
+
+/// The summarized valid entries of a batch, one covering set per family.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AggregateSummary {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ipv4: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ipv6: Vec<String>,
+}
+
+impl AggregateSummary {
+    /// Summarize each family's CIDRs on its own, rendering IPv6 blocks in
+    /// `format`'s case. A family with no CIDRs is left empty.
+    pub fn summarize(v4: &[String], v6: &[String], format: Ipv6Format) -> Result<Self> {
+        let mut summary = Self::default();
+        if !v4.is_empty() {
+            summary.ipv4 = summarize_ipv4_with_limit(v4, v4.len())?
+                .cidrs
+                .iter()
+                .map(|s| format!("{}/{}", s.network, s.prefix_length))
+                .collect();
+        }
+        if !v6.is_empty() {
+            summary.ipv6 = summarize_ipv6_with_options(v6, v6.len(), false, format)?
+                .cidrs
+                .iter()
+                .map(Ipv6Subnet::cidr)
+                .collect();
+        }
+        Ok(summary)
+    }
+}
+
+impl BatchResult {
+    /// Summarize the successfully parsed entries into `aggregate_summary`,
+    /// with IPv6 blocks in `format`'s case. Entries that failed to parse are
+    /// left out.
+    pub fn add_aggregate_summary(&mut self, format: Ipv6Format) -> Result<()> {
+        let (mut v4, mut v6) = (Vec::new(), Vec::new());
+        for entry in &self.results {
+            if let BatchEntryResult::Ok { subnet } = &entry.result {
+                match subnet.as_ref() {
+                    SubnetResult::V4(s) => v4.push(format!("{}/{}", s.network, s.prefix_length)),
+                    SubnetResult::V6(s) => v6.push(format!("{}/{}", s.network, s.prefix_length)),
+                }
+            }
+        }
+
+        // The batch size limit already bounds the inputs
+        self.aggregate_summary = Some(AggregateSummary::summarize(&v4, &v6, format)?);
+        Ok(())
+    }
+
+    /// Keep only the entries `filter` selects, recording it and the number
+    /// kept. `count` and the other totals still describe the whole batch.
+    pub fn apply_filter(&mut self, filter: BatchFilter) {
+        self.results.retain(|entry| filter.keeps(entry));
+        self.filter = Some(filter);
+        self.shown_count = Some(self.results.len());
+    }
+
+    /// Drop the parsed entries equal to or inside one of the `ignore` CIDRs,
+    /// counting them in `ignored_count` and listing the first
+    /// [`MAX_LISTED_IGNORED`] in `ignored`. Entries that failed to parse are
+    /// kept. `count` and `non_canonical` then cover the remaining entries.
+    pub fn apply_ignore(&mut self, ignore: &[String]) -> Result<()> {
+        let (mut v4, mut v6): (Vec<Block>, Vec<Block>) = (Vec::new(), Vec::new());
+        for cidr in ignore {
+            if cidr.contains(':') {
+                let s = Ipv6Subnet::from_cidr(cidr.trim())?;
+                v6.push((u128::from(s.network), s.prefix_length));
+            } else {
+                let s = Ipv4Subnet::from_cidr(cidr.trim())?;
+                v4.push((u32::from(s.network) as u128, s.prefix_length));
+            }
+        }
+
+        let mut ignored_count = 0;
+        let mut ignored = Vec::new();
+        self.results.retain(|entry| {
+            let BatchEntryResult::Ok { subnet } = &entry.result else {
+                return true;
+            };
+            let (blocks, block, bits) = match subnet.as_ref() {
+                SubnetResult::V4(s) => (&v4, (u32::from(s.network) as u128, s.prefix_length), 32),
+                SubnetResult::V6(s) => (&v6, (u128::from(s.network), s.prefix_length), 128),
+            };
+            let skip = blocks.iter().any(|&outer| contains(outer, block, bits));
+            if skip {
+                ignored_count += 1;
+                if ignored.len() < MAX_LISTED_IGNORED {
+                    ignored.push(entry.cidr.clone());
+                }
+            }
+            !skip
+        });
+        self.count = self.results.len();
+        self.non_canonical = count_non_canonical(&self.results);
+        self.ignored_count = Some(ignored_count);
+        self.ignored = ignored;
+        Ok(())
+    }
+
+    /// Override the usable host count reported for /31 entries.
+    pub fn apply_p2p_usable(&mut self, usable: u64) {
+        for entry in &mut self.results {
+            if let BatchEntryResult::Ok { subnet } = &mut entry.result
+                && let SubnetResult::V4(s) = subnet.as_mut()
+            {
+                s.apply_p2p_usable(usable);
+            }
+        }
+    }
+
+    /// Attach the special-purpose registry entry to every IPv4 entry.
+    pub fn apply_rfc6890(&mut self) {
+        for entry in &mut self.results {
+            if let BatchEntryResult::Ok { subnet } = &mut entry.result
+                && let SubnetResult::V4(s) = subnet.as_mut()
+            {
+                s.apply_rfc6890();
+            }
+        }
+    }
+
+    /// Render every successfully parsed IPv6 entry in `style`.
+    pub fn apply_ipv6_style(&mut self, style: impl Into<Ipv6Format>) {
+        let style = style.into();
+        for entry in &mut self.results {
+            if let BatchEntryResult::Ok { subnet } = &mut entry.result
+                && let SubnetResult::V6(s) = subnet.as_mut()
+            {
+                s.apply_style(style);
+            }
+        }
+    }
+
+    /// Report `total_addresses` of every IPv6 entry as an exact decimal.
+    pub fn apply_decimal_totals(&mut self) {
+        for entry in &mut self.results {
+            if let BatchEntryResult::Ok { subnet } = &mut entry.result
+                && let SubnetResult::V6(s) = subnet.as_mut()
+            {
+                s.apply_decimal_total();
+            }
+        }
+    }
 }
 
-pub const DEFAULT_MAX_BATCH_SIZE: usize = 10_000;
+/// Returns true if `key` is a valid tag key: ASCII alphanumerics, `_`, `-`, or `.`.
+fn is_valid_tag_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.'))
+}
+
+/// Split a batch input line into its CIDR and trailing comment tags.
+///
+/// Everything after the first `#` is a comment holding whitespace-separated
+/// `key=value` tags, e.g. `10.0.0.0/24 # role=web env=prod`. Tokens without
+/// `=`, with an empty or invalid key, or with an empty value are ignored and
+/// reported as warnings. If a key repeats, the last value wins.
+pub fn parse_batch_line(raw: &str) -> BatchLine {
+    let (cidr, comment) = match raw.split_once('#') {
+        Some((cidr, comment)) => (cidr, Some(comment)),
+        None => (raw, None),
+    };
+
+    let mut line = BatchLine {
+        cidr: cidr.trim().to_string(),
+        ..BatchLine::default()
+    };
+
+    for token in comment.into_iter().flat_map(str::split_whitespace) {
+        match token.split_once('=') {
+            Some((key, value)) if is_valid_tag_key(key) && !value.is_empty() => {
+                line.tags.insert(key.to_string(), value.to_string());
+            }
+            _ => {
+                line.warnings.push(format!(
+                    "ignored malformed tag '{}' (expected key=value)",
+                    token
+                ));
+            }
+        }
+    }
+
+    line
+}
 
 /// Process a batch of CIDR strings, auto-detecting IPv4 vs IPv6 per entry.
 ///
 /// Returns `EmptyCidrList` if the input slice is empty. Individual parsing
 /// errors are captured per-entry rather than aborting the entire batch.
+///
+/// Ordering guarantee: `results[i]` always corresponds to `cidrs[i]`, with or
+/// without the `parallel` feature. Callers may rely on this to zip results
+/// back to their inputs.
 pub fn process_batch(cidrs: &[String]) -> Result<BatchResult> {
-    process_batch_with_limit(cidrs, DEFAULT_MAX_BATCH_SIZE)
+    process_batch_with_limit(cidrs, DEFAULTS.max_batch_size)
 }
 
 /// Process a batch of CIDR strings with a configurable size limit.
+///
+/// With the `parallel` feature, entries are computed on the rayon thread pool;
+/// results are still returned in input order (see [`process_batch`]).
 pub fn process_batch_with_limit(cidrs: &[String], max_batch_size: usize) -> Result<BatchResult> {
+    process_batch_with_options(cidrs, max_batch_size, false, true)
+}
+
+/// Process a batch of CIDR strings with a size limit, optionally rejecting
+/// IPv6 entries that are not in canonical form (recorded as entry errors).
+///
+/// With `memoize`, each distinct CIDR is computed once and repeats get a
+/// clone of its result, counted in `cache_hits`. Entries are matched on the
+/// parsed CIDR (see [`cache_key`]), so `2001:DB8::/32` reuses the result of
+/// `2001:db8::/32` with its own `canonical_input`. The output is otherwise
+/// identical to computing every entry.
+pub fn process_batch_with_options(
+    cidrs: &[String],
+    max_batch_size: usize,
+    require_canonical: bool,
+    memoize: bool,
+) -> Result<BatchResult> {
     if cidrs.is_empty() {
         return Err(IpCalcError::EmptyCidrList);
     }
@@ -62,44 +350,195 @@ pub fn process_batch_with_limit(cidrs: &[String], max_batch_size: usize) -> Resu
         });
     }
 
-    let results: Vec<BatchEntry> = cidrs
-        .iter()
-        .map(|raw| {
-            let cidr = raw.trim().to_string();
-            let is_ipv6 = cidr.contains(':');
-            let result = if is_ipv6 {
-                match Ipv6Subnet::from_cidr(&cidr) {
-                    Ok(subnet) => BatchEntryResult::Ok {
-                        subnet: Box::new(SubnetResult::V6(subnet)),
-                    },
-                    Err(e) => BatchEntryResult::Err {
-                        error: e.to_string(),
-                    },
-                }
-            } else {
-                match Ipv4Subnet::from_cidr(&cidr) {
-                    Ok(subnet) => BatchEntryResult::Ok {
-                        subnet: Box::new(SubnetResult::V4(subnet)),
-                    },
-                    Err(e) => BatchEntryResult::Err {
-                        error: e.to_string(),
-                    },
+    let lines: Vec<BatchLine> = cidrs.iter().map(|raw| parse_batch_line(raw)).collect();
+    let (computed, slots) = if memoize {
+        // Dedup up front so the parallel path needs no shared cache, then
+        // expand back to one slot per line. Exact repeats of a line's text
+        // skip normalizing it again.
+        let mut seen_text: HashMap<&str, (usize, bool)> = HashMap::new();
+        let mut first_seen: HashMap<Cow<'_, str>, usize> = HashMap::new();
+        let mut unique: Vec<&str> = Vec::new();
+        let slots: Vec<(usize, bool)> = lines
+            .iter()
+            .map(|line| {
+                *seen_text.entry(&line.cidr).or_insert_with(|| {
+                    let (key, canonical) = cache_key(&line.cidr, require_canonical);
+                    let slot = *first_seen.entry(key).or_insert_with(|| {
+                        unique.push(&line.cidr);
+                        unique.len() - 1
+                    });
+                    (slot, canonical)
+                })
+            })
+            .collect();
+        (compute_results(&unique, require_canonical), Some(slots))
+    } else {
+        let all: Vec<&str> = lines.iter().map(|line| line.cidr.as_str()).collect();
+        (compute_results(&all, require_canonical), None)
+    };
+    let cache_hits = lines.len() - computed.len();
+
+    let results: Vec<BatchEntry> = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let result = match &slots {
+                Some(slots) => {
+                    let (slot, canonical) = slots[i];
+                    let mut result = computed[slot].clone();
+                    // The shared result carries the first spelling's flag
+                    if let BatchEntryResult::Ok { subnet } = &mut result
+                        && let SubnetResult::V6(s) = subnet.as_mut()
+                    {
+                        s.canonical_input = canonical;
+                    }
+                    result
                 }
+                None => computed[i].clone(),
             };
-            BatchEntry { cidr, result }
+            BatchEntry {
+                cidr: line.cidr,
+                result,
+                tags: line.tags,
+                warnings: line.warnings,
+            }
         })
         .collect();
 
     Ok(BatchResult {
         count: results.len(),
+        non_canonical: count_non_canonical(&results),
+        cache_hits,
+        filter: None,
+        shown_count: None,
+        ignored_count: None,
+        ignored: Vec::new(),
         results,
+        aggregate_summary: None,
     })
 }
 
+/// The memoization key of a batch CIDR, and whether its address is spelled
+/// canonically.
+///
+/// Entries that parse are keyed on their address in canonical form plus the
+/// prefix, so every spelling of a CIDR shares one result; only
+/// `canonical_input` depends on the spelling, and the caller restores it per
+/// entry. Entries that do not parse are keyed on their own text, since their
+/// errors echo it, and so is non-canonical IPv6 in strict mode.
+fn cache_key(cidr: &str, require_canonical: bool) -> (Cow<'_, str>, bool) {
+    let full = with_host_prefix(cidr);
+    let parsed = parse_cidr_parts(full.trim())
+        .ok()
+        .and_then(|(addr, prefix)| {
+            // The same canonical form `Ipv6Subnet::from_cidr` checks against
+            let canonical = match addr.parse::<IpAddr>().ok()? {
+                IpAddr::V4(v4) => v4.to_string(),
+                IpAddr::V6(v6) => format_ipv6(&v6, Ipv6Style::Compressed),
+            };
+            Some((format!("{}/{}", canonical, prefix), addr == canonical))
+        });
+    match parsed {
+        Some((key, canonical)) if canonical || !require_canonical => (Cow::Owned(key), canonical),
+        _ => (Cow::Borrowed(cidr), true),
+    }
+}
+
+/// Compute the result of each CIDR, in input order.
+///
+/// With the `parallel` feature, entries are computed on the rayon thread pool.
+fn compute_results(cidrs: &[&str], require_canonical: bool) -> Vec<BatchEntryResult> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        // Indexed parallel iterators collect in input order.
+        cidrs
+            .par_iter()
+            .map(|cidr| entry_result(cidr, require_canonical))
+            .collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    cidrs
+        .iter()
+        .map(|cidr| entry_result(cidr, require_canonical))
+        .collect()
+}
+
+/// Count IPv6 entries that were not in canonical form, whether they were
+/// accepted (`canonical_input: false`) or rejected in strict mode.
+pub fn count_non_canonical(results: &[BatchEntry]) -> usize {
+    results
+        .iter()
+        .filter(|entry| match &entry.result {
+            BatchEntryResult::Ok { subnet } => {
+                matches!(subnet.as_ref(), SubnetResult::V6(s) if !s.canonical_input)
+            }
+            BatchEntryResult::Err { .. } => {
+                Ipv6Subnet::from_addr_or_cidr(&entry.cidr).is_ok_and(|s| !s.canonical_input)
+            }
+        })
+        .count()
+}
+
+fn entry_result(cidr: &str, require_canonical: bool) -> BatchEntryResult {
+    if cidr.contains(':') {
+        let parsed = if require_canonical {
+            Ipv6Subnet::from_cidr_canonical(&with_host_prefix(cidr))
+        } else {
+            Ipv6Subnet::from_addr_or_cidr(cidr)
+        };
+        match parsed {
+            Ok(subnet) => BatchEntryResult::Ok {
+                subnet: Box::new(SubnetResult::V6(subnet)),
+            },
+            Err(e) => BatchEntryResult::Err {
+                error: e.to_string(),
+            },
+        }
+    } else {
+        match Ipv4Subnet::from_addr_or_cidr(cidr) {
+            Ok(subnet) => BatchEntryResult::Ok {
+                subnet: Box::new(SubnetResult::V4(subnet)),
+            },
+            Err(e) => BatchEntryResult::Err {
+                error: e.to_string(),
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_aggregate_summary_merges_adjacent() {
+        let cidrs: Vec<String> = [
+            "10.0.1.0/24",
+            "2001:db8:1::/48",
+            "10.0.0.0/24",
+            "bogus",
+            "2001:db8::/48",
+            "192.168.0.0/24",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let mut result = process_batch(&cidrs).unwrap();
+        assert!(result.aggregate_summary.is_none());
+
+        result.add_aggregate_summary(Ipv6Format::default()).unwrap();
+        let summary = result.aggregate_summary.take().unwrap();
+        assert_eq!(summary.ipv4, ["10.0.0.0/23", "192.168.0.0/24"]);
+        assert_eq!(summary.ipv6, ["2001:db8::/47"]);
+
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        result.add_aggregate_summary(upper).unwrap();
+        let summary = result.aggregate_summary.unwrap();
+        assert_eq!(summary.ipv4, ["10.0.0.0/23", "192.168.0.0/24"]);
+        assert_eq!(summary.ipv6, ["2001:DB8::/47"]);
+    }
+
     #[test]
     fn test_batch_single_v4() {
         let cidrs = vec!["192.168.1.0/24".to_string()];
@@ -136,6 +575,104 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_batch_filters() {
+        let cidrs: Vec<String> = ["10.0.0.0/24", "bogus", "2001:db8::/32", "10.0.0.0/33"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let unfiltered = process_batch(&cidrs).unwrap();
+        assert_eq!(unfiltered.results.len(), 4);
+        assert_eq!(unfiltered.filter, None);
+        assert_eq!(unfiltered.shown_count, None);
+
+        let mut errors = unfiltered.clone();
+        errors.apply_filter(BatchFilter::Errors);
+        assert_eq!(errors.count, 4);
+        assert_eq!(errors.shown_count, Some(2));
+        let cidrs: Vec<&str> = errors.results.iter().map(|e| e.cidr.as_str()).collect();
+        assert_eq!(cidrs, ["bogus", "10.0.0.0/33"]);
+
+        let mut ok = unfiltered;
+        ok.apply_filter(BatchFilter::Ok);
+        assert_eq!(ok.count, 4);
+        assert_eq!(ok.shown_count, Some(2));
+        assert_eq!(ok.filter, Some(BatchFilter::Ok));
+        let cidrs: Vec<&str> = ok.results.iter().map(|e| e.cidr.as_str()).collect();
+        assert_eq!(cidrs, ["10.0.0.0/24", "2001:db8::/32"]);
+    }
+
+    fn ignoring(cidrs: &[&str], ignore: &[String]) -> BatchResult {
+        let cidrs: Vec<String> = cidrs.iter().map(|s| s.to_string()).collect();
+        let mut result = process_batch(&cidrs).unwrap();
+        result.apply_ignore(ignore).unwrap();
+        result
+    }
+
+    fn kept(result: &BatchResult) -> Vec<&str> {
+        result.results.iter().map(|e| e.cidr.as_str()).collect()
+    }
+
+    #[test]
+    fn test_batch_ignore_exact_and_contained() {
+        let ignore = vec!["10.0.0.0/8".to_string(), "2001:db8::/32".to_string()];
+        let result = ignoring(
+            &[
+                "10.0.0.0/8",
+                "10.1.2.3",
+                "11.0.0.0/8",
+                "2001:db8:1::/48",
+                "bogus",
+            ],
+            &ignore,
+        );
+        // Errors are never ignored
+        assert_eq!(kept(&result), ["11.0.0.0/8", "bogus"]);
+        assert_eq!(result.count, 2);
+        assert_eq!(result.ignored_count, Some(3));
+        assert_eq!(
+            result.ignored,
+            ["10.0.0.0/8", "10.1.2.3", "2001:db8:1::/48"]
+        );
+
+        // A larger block is not inside a smaller ignore entry
+        let result = ignoring(&["10.0.0.0/7"], &ignore);
+        assert_eq!(result.ignored_count, Some(0));
+
+        let mut result = process_batch(&["10.0.0.0/8".to_string()]).unwrap();
+        assert!(result.apply_ignore(&["bogus".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_batch_ignore_special_purpose() {
+        let result = ignoring(
+            &[
+                "0.0.0.0/0",
+                "169.254.7.0/24",
+                "192.0.2.5",
+                "8.8.8.0/24",
+                "fe80::1/64",
+                "::1",
+            ],
+            &crate::special_purpose::special_purpose_blocks(),
+        );
+        assert_eq!(kept(&result), ["0.0.0.0/0", "8.8.8.0/24"]);
+        assert_eq!(result.ignored_count, Some(4));
+    }
+
+    #[test]
+    fn test_batch_ignore_listing_is_capped() {
+        let cidrs: Vec<String> = (0..MAX_LISTED_IGNORED + 5)
+            .map(|i| format!("10.0.{}.{}", i / 256, i % 256))
+            .collect();
+        let mut result = process_batch(&cidrs).unwrap();
+        result.apply_ignore(&["10.0.0.0/16".to_string()]).unwrap();
+        assert_eq!(result.ignored_count, Some(MAX_LISTED_IGNORED + 5));
+        assert_eq!(result.ignored.len(), MAX_LISTED_IGNORED);
+        assert_eq!(result.count, 0);
+    }
+
     #[test]
     fn test_batch_mixed() {
         let cidrs = vec!["192.168.1.0/24".to_string(), "2001:db8::/32".to_string()];
@@ -213,4 +750,161 @@ mod tests {
             BatchEntryResult::Ok { .. }
         ));
     }
+
+    #[test]
+    fn test_batch_multiple_tags() {
+        let cidrs = vec!["10.0.0.0/24 # role=web env=prod".to_string()];
+        let result = process_batch(&cidrs).unwrap();
+        let entry = &result.results[0];
+        assert_eq!(entry.cidr, "10.0.0.0/24");
+        assert!(matches!(&entry.result, BatchEntryResult::Ok { .. }));
+        assert_eq!(entry.tags.len(), 2);
+        assert_eq!(entry.tags["role"], "web");
+        assert_eq!(entry.tags["env"], "prod");
+        assert!(entry.warnings.is_empty());
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["results"][0]["tags"]["role"], "web");
+        assert_eq!(json["results"][0]["tags"]["env"], "prod");
+        assert!(json["results"][0].get("warnings").is_none());
+    }
+
+    #[test]
+    fn test_batch_malformed_tag_ignored_with_warning() {
+        let line = parse_batch_line("10.0.0.0/24 # role=web bogus =x env=");
+        assert_eq!(line.cidr, "10.0.0.0/24");
+        assert_eq!(line.tags.len(), 1);
+        assert_eq!(line.tags["role"], "web");
+        assert_eq!(line.warnings.len(), 3);
+        assert!(line.warnings[0].contains("'bogus'"));
+    }
+
+    #[test]
+    fn test_batch_untagged_entry_omits_tags() {
+        let cidrs = vec!["10.0.0.0/24".to_string()];
+        let result = process_batch(&cidrs).unwrap();
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json["results"][0].get("tags").is_none());
+    }
+
+    #[test]
+    fn test_batch_preserves_input_order() {
+        // Large enough to be split across several rayon workers when the
+        // `parallel` feature is enabled; families and errors are interleaved
+        // so per-entry cost varies.
+        let cidrs: Vec<String> = (0..50_000u32)
+            .map(|i| match (i.wrapping_mul(2_654_435_761)) % 3 {
+                0 => format!("10.{}.{}.0/24", (i >> 8) & 0xff, i & 0xff),
+                1 => format!("2001:db8:{:x}::/48", i),
+                _ => format!("bogus-{}", i),
+            })
+            .collect();
+        let result = process_batch_with_limit(&cidrs, cidrs.len()).unwrap();
+        assert_eq!(result.count, cidrs.len());
+        for (i, entry) in result.results.iter().enumerate() {
+            assert_eq!(entry.cidr, cidrs[i], "entry {} out of order", i);
+        }
+    }
+
+    #[test]
+    fn test_batch_counts_non_canonical() {
+        let cidrs = vec![
+            "2001:DB8::/32".to_string(),
+            "2001:db8::/32".to_string(),
+            "2001:0db8:0:0:0:0:0:0/32".to_string(),
+            "10.0.0.0/8".to_string(),
+        ];
+        let result = process_batch(&cidrs).unwrap();
+        assert_eq!(result.non_canonical, 2);
+        assert!(
+            result
+                .results
+                .iter()
+                .all(|e| matches!(e.result, BatchEntryResult::Ok { .. }))
+        );
+
+        let strict =
+            process_batch_with_options(&cidrs, DEFAULTS.max_batch_size, true, true).unwrap();
+        assert_eq!(strict.non_canonical, 2);
+        let errors: Vec<&str> = strict
+            .results
+            .iter()
+            .filter_map(|e| match &e.result {
+                BatchEntryResult::Err { error } => Some(error.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("use 2001:db8::/32"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn test_batch_memoization_matches_uncached() {
+        // 90% repeats, including invalid and non-canonical entries and tagged lines
+        let distinct = [
+            "10.0.0.0/24",
+            "192.168.1.77/26",
+            "2001:DB8::/32",
+            "2001:db8::1",
+            "bogus",
+            "10.0.0.0/24 # role=web",
+            "172.16.0.0/12",
+            "fe80::/10",
+            "10.1.2.3",
+            "300.0.0.0/8",
+        ];
+        let cidrs: Vec<String> = (0..1000)
+            .map(|i| distinct[if i % 10 == 0 { (i / 10) % 10 } else { 0 }].to_string())
+            .collect();
+
+        let cached = process_batch_with_options(&cidrs, cidrs.len(), false, true).unwrap();
+        let uncached = process_batch_with_options(&cidrs, cidrs.len(), false, false).unwrap();
+        assert_eq!(uncached.cache_hits, 0);
+        // "10.0.0.0/24 # role=web" shares its CIDR with "10.0.0.0/24"
+        assert_eq!(cached.cache_hits, 1000 - 9);
+        assert_eq!(
+            serde_json::to_string(&cached.results).unwrap(),
+            serde_json::to_string(&uncached.results).unwrap()
+        );
+        assert_eq!(cached.non_canonical, uncached.non_canonical);
+        assert_eq!(cached.results[50].tags["role"], "web");
+        assert!(cached.results[40].tags.is_empty());
+    }
+
+    #[test]
+    fn test_batch_memoization_keys_on_parsed_cidr() {
+        let cidrs: Vec<String> = [
+            "2001:db8::/32",
+            "2001:DB8::/32",
+            "2001:0db8::/32",
+            "10.1.2.3",
+            "10.1.2.3/32",
+            "10.1.2.0/24",
+            "bogus",
+            "bogus ",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let cached = process_batch_with_options(&cidrs, cidrs.len(), false, true).unwrap();
+        let uncached = process_batch_with_options(&cidrs, cidrs.len(), false, false).unwrap();
+        // Batch lines are trimmed, so the two "bogus" lines match too
+        assert_eq!(cached.cache_hits, 4);
+        assert_eq!(
+            serde_json::to_string(&cached.results).unwrap(),
+            serde_json::to_string(&uncached.results).unwrap()
+        );
+        assert_eq!(cached.non_canonical, 2);
+        assert_eq!(cached.results[1].cidr, "2001:DB8::/32");
+
+        // Strict mode errors echo the spelling, so those are not shared
+        let cached = process_batch_with_options(&cidrs, cidrs.len(), true, true).unwrap();
+        let uncached = process_batch_with_options(&cidrs, cidrs.len(), true, false).unwrap();
+        assert_eq!(cached.cache_hits, 2);
+        assert_eq!(
+            serde_json::to_string(&cached.results).unwrap(),
+            serde_json::to_string(&uncached.results).unwrap()
+        );
+    }
 }
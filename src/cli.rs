@@ -13,10 +13,41 @@ pub struct Cli {
     #[arg(long)]
     pub stdin: bool,
 
+    /// In batch mode, also summarize all valid entries into `aggregate_summary`
+    #[arg(long)]
+    pub with_summary: bool,
+
+    /// In batch mode, recompute repeated CIDRs instead of reusing the first result
+    #[arg(long)]
+    pub no_batch_cache: bool,
+
+    /// In batch mode, show only the entries that failed (text output is one
+    /// `CIDR: error` line each)
+    #[arg(long, conflicts_with = "only_ok")]
+    pub only_errors: bool,
+
+    /// In batch mode, show only the entries that parsed
+    #[arg(long)]
+    pub only_ok: bool,
+
+    /// In batch mode, skip entries equal to or inside this CIDR (repeatable)
+    #[arg(long, value_name = "CIDR")]
+    pub ignore: Vec<String>,
+
+    /// In batch mode, skip entries inside the CIDRs listed in a file (one per
+    /// line, blank lines and # comments skipped)
+    #[arg(long, value_name = "FILE")]
+    pub ignore_file: Option<String>,
+
+    /// In batch mode, skip entries inside any IANA special-purpose block
+    /// (private, loopback, link-local, documentation, ...)
+    #[arg(long)]
+    pub ignore_special: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 
-    /// Output format (json, text, csv, or yaml)
+    /// Output format (json, text, csv, yaml, or json-patch for diff)
     #[arg(short, long, default_value = "json", global = true)]
     pub format: OutputFormatArg,
 
@@ -24,6 +55,51 @@ pub struct Cli {
     #[arg(short = 'o', long, global = true)]
     pub output: Option<String>,
 
+    /// Report only the CIDRs added or removed since the snapshot in FILE, then
+    /// save the current list there (for commands that list CIDRs)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub since: Option<String>,
+
+    /// Explain errors with the expected format and an example
+    #[arg(long, global = true)]
+    pub explain_errors: bool,
+
+    /// Usable hosts to report for an IPv4 /31 point-to-point subnet (RFC 3021)
+    #[arg(long, value_enum, default_value = "2", global = true)]
+    pub p2p_usable: P2pUsableArg,
+
+    /// Additional rendering style for IPv6 addresses (compressed, expanded, full, nibble)
+    #[arg(long, value_enum, default_value = "compressed", global = true)]
+    pub ipv6_style: Ipv6StyleArg,
+
+    /// Hex digit case for IPv6 addresses (lower, upper)
+    #[arg(long, value_enum, default_value = "lower", global = true)]
+    pub v6_case: Ipv6CaseArg,
+
+    /// Reject IPv6 addresses that are not in RFC 5952 canonical form
+    #[arg(long, global = true)]
+    pub require_canonical: bool,
+
+    /// Add the IANA special-purpose registry entry (RFC 6890) to IPv4 results
+    #[arg(long, global = true)]
+    pub rfc6890: bool,
+
+    /// Report IPv6 total_addresses as exact decimals instead of 2^N above 2^64
+    #[arg(long, global = true)]
+    pub decimal_totals: bool,
+
+    /// Leave out the leading `kind` field of JSON and YAML output
+    #[arg(long, global = true)]
+    pub no_kind: bool,
+
+    /// Key naming of JSON output (snake, camel)
+    #[arg(long, value_enum, default_value = "snake", global = true)]
+    pub json_case: JsonCaseArg,
+
+    /// Check JSON, YAML, and NDJSON output against the result's JSON Schema before writing it (needs the `schema` feature)
+    #[arg(long, global = true)]
+    pub validate_output: bool,
+
     /// Launch interactive TUI mode
     #[cfg(feature = "tui")]
     #[arg(long)]
@@ -38,8 +114,13 @@ pub enum Commands {
         cidr: String,
 
         /// New prefix length for subnets
-        #[arg(short = 'p', long)]
-        prefix: u8,
+        #[arg(short = 'p', long, required_unless_present = "hosts")]
+        prefix: Option<u8>,
+
+        /// Pick the longest prefix whose subnets hold at least this many usable
+        /// hosts (IPv4 excludes network and broadcast below /31)
+        #[arg(long, conflicts_with = "prefix")]
+        hosts: Option<u64>,
 
         /// Number of subnets to generate (mutually exclusive with --max)
         #[arg(short = 'n', long, conflicts_with = "max")]
@@ -52,31 +133,450 @@ pub enum Commands {
         /// Show only the number of available subnets (no generation)
         #[arg(long, conflicts_with_all = ["count", "max"])]
         count_only: bool,
+
+        /// Report how many subnets would be generated and the approximate memory, without generating
+        #[arg(long, conflicts_with = "count_only")]
+        estimate: bool,
+
+        /// Return the generated subnets in a reproducible random order.
+        /// Selection (-n) happens first, so only the selected subnets are shuffled
+        #[arg(long, conflicts_with = "count_only")]
+        shuffle: bool,
+
+        /// Seed for --shuffle (random if omitted; the seed used is reported in the output)
+        #[arg(long, requires = "shuffle")]
+        seed: Option<u64>,
+
+        /// Start at the subnet with this 0-based index, to page through a large
+        /// split with -n (e.g. --offset 5000 -n 100 gives subnets 5000-5099)
+        #[arg(long, default_value_t = 0, conflicts_with_all = ["count_only", "estimate"])]
+        offset: u64,
+
+        /// Generate at most this many subnets from --offset on, stopping early at
+        /// the end of the split (where -n would be an error)
+        #[arg(long, conflicts_with_all = ["count", "max", "count_only", "estimate"])]
+        limit: Option<u64>,
+
+        /// Print only the subnet with this 0-based index, computed directly
+        /// (e.g. --index 1000 gives the 1001st subnet)
+        #[arg(long, conflicts_with_all = ["count", "max", "count_only", "estimate", "shuffle", "offset", "limit"])]
+        index: Option<u64>,
+
+        /// With --max, check that the subnets summarize back to the supernet (exit code 70 if not)
+        #[arg(long, conflicts_with_all = ["count", "count_only", "estimate", "offset", "limit", "index"])]
+        verify: bool,
+    },
+
+    /// Split an IPv4 and an IPv6 supernet in parallel, pairing the subnets row by row
+    Dualstack {
+        /// IPv4 supernet (e.g., 10.20.0.0/16)
+        #[arg(long)]
+        v4: String,
+
+        /// IPv6 supernet (e.g., 2001:db8:20::/48)
+        #[arg(long)]
+        v6: String,
+
+        /// New prefix length for the IPv4 subnets
+        #[arg(long)]
+        split_prefix_v4: u8,
+
+        /// New prefix length for the IPv6 subnets
+        #[arg(long)]
+        split_prefix_v6: u8,
+
+        /// Number of subnet pairs to generate
+        #[arg(short = 'n', long)]
+        count: u64,
+    },
+
+    /// Find the subnet size that fits N subnets of at least M hosts each in a supernet
+    PlanSplit {
+        /// Supernet in CIDR notation (e.g., 10.0.0.0/8)
+        cidr: String,
+
+        /// Minimum number of subnets needed
+        #[arg(long)]
+        subnets: u64,
+
+        /// Minimum usable hosts per subnet
+        #[arg(long)]
+        hosts: u64,
     },
 
-    /// Check if an IP address is contained in a subnet
+    /// Split a supernet in levels (e.g., sites, then VLANs) and show the nested tree
+    Tree {
+        /// Supernet in CIDR notation (e.g., 10.0.0.0/16)
+        cidr: String,
+
+        /// Prefix length of one level, outermost first; repeat per level (e.g., -p 20 -p 24)
+        #[arg(short = 'p', long = "prefix", required = true)]
+        prefixes: Vec<u8>,
+    },
+
+    /// Check if an IP address (or a whole CIDR) is contained in a subnet
     Contains {
         /// Network in CIDR notation (e.g., 192.168.1.0/24)
         cidr: String,
-        /// IP address to check (e.g., 192.168.1.100)
+        /// IP address to check (e.g., 192.168.1.100), a range to check every
+        /// address in it (192.168.1.10-192.168.1.20 or 192.168.1.10-20), or a
+        /// CIDR to check the whole block
+        address: String,
+        /// Translate between IPv4 and IPv6 (IPv4-mapped, IPv4-compatible, 6to4, NAT64) when families differ
+        #[arg(long)]
+        translate: bool,
+        /// Look up the address's reverse DNS (PTR) name (requires the dns feature)
+        #[arg(long)]
+        resolve: bool,
+    },
+
+    /// Check whether one CIDR lies inside another, and how the two relate
+    SubnetContains {
+        /// Enclosing network in CIDR notation (e.g., 10.0.0.0/8)
+        outer: String,
+        /// Network to check in CIDR notation (e.g., 10.1.0.0/16)
+        inner: String,
+    },
+
+    /// List the CIDRs in a list that contain an address (or overlap a block),
+    /// most specific first
+    Lookup {
+        /// Address or CIDR to look up (e.g., 10.1.2.3 or 10.1.0.0/16)
+        query: String,
+        /// CIDRs to search (IPv4 and/or IPv6)
+        #[arg(required_unless_present = "file", num_args = 1..)]
+        cidrs: Vec<String>,
+        /// Read CIDRs from a file (one per line, blank lines and # comments skipped)
+        #[arg(long, conflicts_with = "cidrs")]
+        file: Option<String>,
+        /// Report at most this many matches (the total is still counted)
+        #[arg(long)]
+        max_results: Option<usize>,
+        /// Least specific match first instead of most specific first
+        #[arg(long)]
+        shortest_first: bool,
+    },
+
+    /// Find the longest-prefix match for an address among a list of CIDRs
+    #[command(visible_alias = "match")]
+    Lpm {
+        /// IP address to match (e.g., 10.1.2.3)
+        address: String,
+        /// Candidate CIDRs, all of the address's family
+        #[arg(required = true, num_args = 1..)]
+        cidrs: Vec<String>,
+    },
+
+    /// Check whether an IPv4 address is its subnet's network or broadcast address
+    #[command(name = "is-usable")]
+    IsUsable {
+        /// Address with its prefix length (e.g., 192.168.1.255/24)
+        cidr: String,
+    },
+
+    /// Check that an address is a usable host of a subnet (not its network
+    /// or broadcast address, and inside it)
+    #[command(name = "host-check")]
+    HostCheck {
+        /// IPv4 or IPv6 address to check (e.g., 192.168.1.0)
+        address: String,
+        /// Subnet in CIDR notation (e.g., 192.168.1.0/24)
+        cidr: String,
+    },
+
+    /// Show the Cisco ACL base address and wildcard mask for an IPv4 CIDR
+    #[command(name = "acl-wildcard")]
+    AclWildcard {
+        /// Network in CIDR notation (e.g., 10.0.0.0/16)
+        cidr: String,
+    },
+
+    /// Show the adjacent block of the same size after a CIDR
+    #[command(name = "next-subnet")]
+    NextSubnet {
+        /// Network in CIDR notation (e.g., 192.168.1.0/24 or 2001:db8::/48)
+        cidr: String,
+    },
+
+    /// Show the adjacent block of the same size before a CIDR
+    #[command(name = "prev-subnet")]
+    PrevSubnet {
+        /// Network in CIDR notation (e.g., 192.168.1.0/24 or 2001:db8::/48)
+        cidr: String,
+    },
+
+    /// List the rest of a CIDR's parent block (the sibling space)
+    Complement {
+        /// Network in CIDR notation (e.g., 10.0.1.0/24)
+        cidr: String,
+        /// Block to take the complement within (default: the one-bit-shorter parent)
+        #[arg(long)]
+        within: Option<String>,
+    },
+
+    /// List the fewest CIDRs covering a supernet minus the excluded blocks
+    Exclude {
+        /// Block to subtract from (e.g., 10.0.0.0/8)
+        supernet: String,
+        /// CIDRs to remove (e.g., 10.1.0.0/16 10.2.0.0/16)
+        #[arg(required = true, num_args = 1..)]
+        cidrs: Vec<String>,
+    },
+
+    /// List the free space left in a supernet by its allocated subnets
+    Gaps {
+        /// Parent block (e.g., 10.0.0.0/16)
+        supernet: String,
+        /// Allocated subnets, comma-separated (e.g., 10.0.0.0/24,10.0.4.0/22)
+        #[arg(long, value_delimiter = ',')]
+        used: Vec<String>,
+    },
+
+    /// List the CIDRs of a parent block not claimed by any allocated child
+    FreeSpace {
+        /// Parent block (e.g., 10.0.0.0/16)
+        parent: String,
+        /// Allocated child blocks (e.g., 10.0.1.0/24 10.0.5.0/24)
+        allocated: Vec<String>,
+    },
+
+    /// Pick the next free block(s) of a given size in a supernet
+    Allocate {
+        /// Parent block (e.g., 10.0.0.0/16)
+        supernet: String,
+        /// Prefix length of the blocks to allocate
+        #[arg(short = 'p', long)]
+        prefix: u8,
+        /// Number of blocks to allocate
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: u64,
+        /// Subnets already in use; repeat or comma-separate (e.g., 10.0.0.0/24,10.0.1.0/24)
+        #[arg(long, value_delimiter = ',')]
+        used: Vec<String>,
+    },
+
+    /// Expand an IPv6 address to eight zero-padded groups
+    Expand {
+        /// IPv6 address (e.g., 2001:db8::1)
         address: String,
     },
 
+    /// Compress an IPv6 address to its RFC 5952 canonical form
+    Compress {
+        /// IPv6 address (e.g., 2001:0db8:0000:0000:0000:0000:0000:0001)
+        address: String,
+    },
+
+    /// Derive the SLAAC (modified EUI-64) address of a MAC address in an IPv6 prefix
+    Eui64 {
+        /// IPv6 prefix of /64 or shorter (e.g., 2001:db8::/64)
+        prefix: String,
+        /// MAC address with colon or dash separators (e.g., 00:1a:2b:3c:4d:5e)
+        mac: String,
+    },
+
+    /// Split an IPv6 address into its network and interface identifier at the prefix boundary
+    Ifid {
+        /// IPv6 address with prefix length (e.g., 2001:db8::1234:5678:9abc:def0/64)
+        cidr: String,
+    },
+
     /// Convert an IP range (start–end) into minimal CIDR blocks
     FromRange {
         /// Start IP address (e.g., 192.168.1.10 or 2001:db8::1)
         start: String,
         /// End IP address (e.g., 192.168.1.20 or 2001:db8::ff)
         end: String,
+        /// Report how many CIDRs would be generated and the approximate memory, without generating
+        #[arg(long)]
+        estimate: bool,
+        /// Check that the CIDRs tile the range exactly (exit code 70 if not)
+        #[arg(long, conflicts_with = "estimate")]
+        verify: bool,
+    },
+
+    /// List every same-size IPv4 subnet from one CIDR to another, inclusive
+    Walk {
+        /// First subnet (e.g., 10.0.0.0/24)
+        start: String,
+        /// Last subnet, with the same prefix length (e.g., 10.0.5.0/24)
+        end: String,
+    },
+
+    /// Add an integer offset to an IP address (negative to go back)
+    Add {
+        /// IPv4 or IPv6 address (e.g., 10.0.0.0 or 2001:db8::)
+        address: String,
+        /// Number of addresses to move by (e.g., 256 or -1)
+        #[arg(allow_negative_numbers = true)]
+        offset: i128,
+    },
+
+    /// List the usable host addresses of a subnet, one per line in text output
+    Hosts {
+        /// CIDR notation (e.g., 192.168.1.0/29 or 2001:db8::/120)
+        cidr: String,
+        /// Stop after this many hosts (capped at the subnet generation limit)
+        #[arg(long)]
+        max: Option<usize>,
+    },
+
+    /// Print the nth usable host of a subnet, counting from 0
+    NthHost {
+        /// CIDR notation (e.g., 10.0.0.0/24 or 2001:db8::/64)
+        cidr: String,
+        /// 0-based index of the host, where 0 is the first usable host
+        /// (e.g., 5 picks 10.0.0.6 in 10.0.0.0/24)
+        n: u128,
     },
 
     /// Summarize/aggregate CIDRs into the minimal covering set
     Summarize {
         /// CIDR ranges to summarize
+        #[arg(required_unless_present_any = ["file", "base"], num_args = 1..)]
+        cidrs: Vec<String>,
+        /// Read CIDRs from a file (one per line, blank lines and # comments skipped)
+        #[arg(long, conflicts_with = "cidrs")]
+        file: Option<String>,
+        /// Scan each line of --file for the first CIDR, ignoring other columns
+        /// (e.g. `show ip route` or BGP table dumps)
+        #[arg(long, requires = "file")]
+        extract: bool,
+        /// Custom extraction pattern; the first capture group (or whole match) is used.
+        /// Implies --extract
+        #[arg(long, requires = "file")]
+        extract_regex: Option<String>,
+        /// Include a trace of the summarization steps (normalized input,
+        /// contained entries removed, sibling merges)
+        #[arg(long)]
+        explain: bool,
+        /// Update a previous `summarize --format json` result instead of
+        /// summarizing from scratch
+        #[arg(long, conflicts_with_all = ["cidrs", "file", "explain"])]
+        base: Option<String>,
+        /// File of CIDRs to merge into the --base summary
+        #[arg(long, requires = "base")]
+        add: Option<String>,
+        /// File of CIDRs to cut out of the --base summary (may split blocks)
+        #[arg(long, requires = "base")]
+        remove: Option<String>,
+        /// Fail unless every summarized block is in this address space,
+        /// instead of warning about mixed private and public space
+        #[arg(long)]
+        expect: Option<AddressSpaceArg>,
+    },
+
+    /// Count distinct addresses covered by a list of CIDRs (overlaps counted once)
+    Addresses {
+        /// CIDR ranges to count (IPv4 and/or IPv6)
+        #[arg(required = true, num_args = 1..)]
+        cidrs: Vec<String>,
+    },
+
+    /// List every pair of CIDRs that overlap, and how they relate
+    #[command(visible_alias = "overlaps")]
+    Overlap {
+        /// CIDR ranges to check (IPv4 and/or IPv6)
+        #[arg(required = true, num_args = 1..)]
+        cidrs: Vec<String>,
+    },
+
+    /// Find the smallest single CIDR containing every input (may over-cover)
+    Supernet {
+        /// CIDR ranges to cover (all IPv4 or all IPv6)
         #[arg(required = true, num_args = 1..)]
         cidrs: Vec<String>,
     },
 
+    /// Show the tightest CIDR covering two blocks, as a full subnet calculation
+    #[command(name = "common-supernet")]
+    CommonSupernet {
+        /// First CIDR (e.g., 192.168.1.0/24)
+        a: String,
+        /// Second CIDR of the same IP version (e.g., 192.168.3.0/24)
+        b: String,
+    },
+
+    /// Carve an IPv4 supernet into variable-length subnets (VLSM), largest first
+    Vlsm {
+        /// IPv4 supernet in CIDR notation (e.g., 10.0.0.0/24)
+        supernet: String,
+        /// Required host count of each subnet (e.g., 100 50 20 10)
+        #[arg(
+            num_args = 1..,
+            value_delimiter = ',',
+            required_unless_present = "host_list"
+        )]
+        hosts: Vec<u64>,
+        /// Required host counts as one comma-separated list (e.g., 100,50,20,10)
+        #[arg(long = "hosts", value_delimiter = ',', conflicts_with = "hosts")]
+        host_list: Vec<u64>,
+    },
+
+    /// Check that a CIDR list covers a target exactly (exit code 1 on gaps or excess)
+    Covers {
+        /// Target block, e.g. the aggregate being replaced (10.0.0.0/16)
+        target: String,
+        /// File of covering CIDRs (one per line, blank lines and # comments skipped)
+        #[arg(long)]
+        by: String,
+    },
+
+    /// Show the CIDRs added and removed between two lists (--format json-patch for RFC 6902)
+    Diff {
+        /// File with the old CIDR list (one per line, blank lines and # comments skipped)
+        old: String,
+        /// File with the new CIDR list
+        new: String,
+    },
+
+    /// Merge JSON batch result files into one, optionally removing duplicate CIDRs
+    MergeResults {
+        /// Batch result files written with --format json
+        #[arg(required = true, num_args = 1..)]
+        files: Vec<String>,
+        /// Keep one entry per CIDR, preferring the first successful result
+        #[arg(long)]
+        dedupe: bool,
+    },
+
+    /// Rewrite a CIDR list in canonical network/prefix form, keeping comments
+    Normalize {
+        /// File with one CIDR per line (reads standard input if omitted)
+        file: Option<String>,
+        /// Sort CIDRs (IPv4 before IPv6, then by network and prefix length)
+        #[arg(long)]
+        sort: bool,
+        /// Remove duplicate CIDRs, keeping the first occurrence
+        #[arg(long)]
+        dedupe: bool,
+        /// Rewrite FILE with the normalized list
+        #[arg(long, requires = "file")]
+        in_place: bool,
+    },
+
+    /// Generate reverse DNS (in-addr.arpa) zone data for an IPv4 CIDR
+    Zonefile {
+        /// Network in CIDR notation (e.g., 10.1.0.0/16)
+        cidr: String,
+        /// Emit one $ORIGIN zone per /24 instead of a single zone
+        #[arg(long = "split-24")]
+        split_24: bool,
+        /// Domain used for the generated PTR targets
+        #[arg(long, default_value = crate::zonefile::DEFAULT_ZONE_DOMAIN)]
+        domain: String,
+    },
+
+    /// Show the reverse DNS zone names of a CIDR and, for IPv4, a PTR stub per host
+    #[command(name = "reverse-dns")]
+    ReverseDns {
+        /// Network in CIDR notation (e.g., 192.0.2.0/24 or 2001:db8::/48)
+        cidr: String,
+        /// Only show the zone name, without per-host records
+        #[arg(long)]
+        zone_only: bool,
+    },
+
     /// IP Address Management — track allocations, supernets, and free space
     Ipam {
         /// Path to SQLite database (overrides IPCALC_DB env and config file)
@@ -95,6 +595,22 @@ pub enum Commands {
         ipam_db: Option<String>,
     },
 
+    /// Show the request limits a server would enforce (defaults plus config-file overrides)
+    Limits {
+        /// Path to config file (TOML)
+        #[arg(long)]
+        config: Option<String>,
+    },
+
+    /// List every compiled-in default limit with the module that enforces it
+    Defaults,
+
+    /// Inspect server configuration files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
     /// Start the HTTP API server
     Serve {
         /// Address to bind to
@@ -125,6 +641,22 @@ pub enum Commands {
         #[arg(long)]
         enable_swagger: bool,
 
+        /// Serve the embedded mini web UI at /ui (requires the webui feature)
+        #[arg(long)]
+        enable_ui: bool,
+
+        /// Serve an HTML endpoint listing at / instead of the dashboard
+        #[arg(long)]
+        enable_landing_page: bool,
+
+        /// Take the client IP from X-Forwarded-For when the peer is a trusted proxy
+        #[arg(long)]
+        trust_forwarded_for: bool,
+
+        /// Trusted proxy address or CIDR (repeatable; overrides config file)
+        #[arg(long = "trusted-proxy", value_name = "CIDR")]
+        trusted_proxies: Vec<String>,
+
         /// Maximum CIDRs in a batch request (overrides config file)
         #[arg(long)]
         max_batch_size: Option<usize>,
@@ -168,6 +700,44 @@ pub enum Commands {
         /// IPAM PostgreSQL connection URL (overrides IPCALC_IPAM_DB_URL env and config file)
         #[arg(long)]
         ipam_db_url: Option<String>,
+
+        /// Run every startup step, print what would be served, and exit without serving
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+impl Commands {
+    /// Whether the command's result is a CIDR list that `--since` can diff.
+    pub fn lists_cidrs(&self) -> bool {
+        matches!(
+            self,
+            Commands::Split {
+                count_only: false,
+                estimate: false,
+                index: None,
+                ..
+            } | Commands::FromRange {
+                estimate: false,
+                ..
+            } | Commands::Complement { .. }
+                | Commands::Exclude { .. }
+                | Commands::Gaps { .. }
+                | Commands::FreeSpace { .. }
+                | Commands::Walk { .. }
+                | Commands::Summarize { .. }
+        )
+    }
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Show the effective configuration after includes and ${VAR} expansion,
+    /// and which file set each value
+    Show {
+        /// Path to config file (TOML); defaults only when omitted
+        #[arg(long)]
+        config: Option<String>,
     },
 }
 
@@ -263,6 +833,14 @@ pub enum IpamCommands {
     Utilization {
         /// Supernet ID
         supernet_id: String,
+
+        /// Add an ASCII bar of allocated vs free space to text output
+        #[arg(long)]
+        bar: bool,
+
+        /// Width of the usage bar in characters
+        #[arg(long, default_value_t = 40)]
+        bar_width: usize,
     },
 
     /// List free blocks in a supernet
@@ -416,6 +994,14 @@ pub enum OutputFormatArg {
     Text,
     Csv,
     Yaml,
+    /// Shell variable assignments (IPCALC_<FIELD>=value), for `eval`
+    Env,
+    /// RFC 6902 JSON Patch; only supported by `diff`
+    JsonPatch,
+    /// Layout of the classic ipcalc(1) tool, or sipcalc for IPv6
+    Classic,
+    /// Newline-delimited JSON; splits write one subnet per line as they are generated
+    Ndjson,
 }
 
 impl From<OutputFormatArg> for crate::output::OutputFormat {
@@ -425,6 +1011,100 @@ impl From<OutputFormatArg> for crate::output::OutputFormat {
             OutputFormatArg::Text => crate::output::OutputFormat::Text,
             OutputFormatArg::Csv => crate::output::OutputFormat::Csv,
             OutputFormatArg::Yaml => crate::output::OutputFormat::Yaml,
+            OutputFormatArg::Env => crate::output::OutputFormat::Env,
+            OutputFormatArg::Classic => crate::output::OutputFormat::Classic,
+            OutputFormatArg::Ndjson => crate::output::OutputFormat::Ndjson,
+            // The patch itself is serialized as JSON
+            OutputFormatArg::JsonPatch => crate::output::OutputFormat::Json,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum AddressSpaceArg {
+    /// RFC 1918, or IPv6 unique local
+    Private,
+    /// Public IPv4, or IPv6 global unicast
+    Public,
+}
+
+impl From<AddressSpaceArg> for crate::summarize::AddressSpace {
+    fn from(arg: AddressSpaceArg) -> Self {
+        match arg {
+            AddressSpaceArg::Private => crate::summarize::AddressSpace::Private,
+            AddressSpaceArg::Public => crate::summarize::AddressSpace::Public,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum, Default)]
+pub enum Ipv6StyleArg {
+    #[default]
+    Compressed,
+    Expanded,
+    Full,
+    Nibble,
+}
+
+impl From<Ipv6StyleArg> for crate::ipv6::Ipv6Style {
+    fn from(arg: Ipv6StyleArg) -> Self {
+        match arg {
+            Ipv6StyleArg::Compressed => crate::ipv6::Ipv6Style::Compressed,
+            Ipv6StyleArg::Expanded => crate::ipv6::Ipv6Style::Expanded,
+            Ipv6StyleArg::Full => crate::ipv6::Ipv6Style::Full,
+            Ipv6StyleArg::Nibble => crate::ipv6::Ipv6Style::Nibble,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum, Default)]
+pub enum Ipv6CaseArg {
+    #[default]
+    Lower,
+    Upper,
+}
+
+impl From<Ipv6CaseArg> for crate::ipv6::Ipv6Case {
+    fn from(arg: Ipv6CaseArg) -> Self {
+        match arg {
+            Ipv6CaseArg::Lower => crate::ipv6::Ipv6Case::Lower,
+            Ipv6CaseArg::Upper => crate::ipv6::Ipv6Case::Upper,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum, Default)]
+pub enum JsonCaseArg {
+    #[default]
+    Snake,
+    Camel,
+}
+
+impl From<JsonCaseArg> for crate::output::JsonCase {
+    fn from(arg: JsonCaseArg) -> Self {
+        match arg {
+            JsonCaseArg::Snake => crate::output::JsonCase::Snake,
+            JsonCaseArg::Camel => crate::output::JsonCase::Camel,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum, Default)]
+pub enum P2pUsableArg {
+    /// Count neither address of a /31 as usable
+    #[value(name = "0")]
+    Zero,
+    /// Count both addresses of a /31 as usable (RFC 3021)
+    #[default]
+    #[value(name = "2")]
+    Two,
+}
+
+impl From<P2pUsableArg> for u64 {
+    fn from(arg: P2pUsableArg) -> Self {
+        match arg {
+            P2pUsableArg::Zero => 0,
+            P2pUsableArg::Two => 2,
         }
     }
 }
@@ -0,0 +1,271 @@
+use crate::config::ServerConfig;
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::ipv4_mask;
+use crate::ipv6::ipv6_mask;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// The client address a request is attributed to, inserted as a request
+/// extension by [`client_ip_middleware`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Decides which address a request came from: the socket peer, or an
+/// `X-Forwarded-For` entry when the peer is a trusted proxy.
+#[derive(Debug, Clone, Default)]
+pub struct ClientIpResolver {
+    trust_forwarded_for: bool,
+    /// (network, prefix length); never empty while `trust_forwarded_for` is set
+    trusted_proxies: Vec<(IpAddr, u8)>,
+}
+
+impl ClientIpResolver {
+    /// Build a resolver from `trust_forwarded_for` and `trusted_proxies`.
+    ///
+    /// Trusting the header without naming any proxy is rejected, since every
+    /// direct client could then spoof its address.
+    pub fn from_config(config: &ServerConfig) -> Result<Self> {
+        if config.trust_forwarded_for && config.trusted_proxies.is_empty() {
+            return Err(IpCalcError::ConfigParse(
+                "trust_forwarded_for requires at least one trusted proxy".to_string(),
+            ));
+        }
+        let trusted_proxies = config
+            .trusted_proxies
+            .iter()
+            .map(|cidr| parse_proxy_cidr(cidr))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            trust_forwarded_for: config.trust_forwarded_for,
+            trusted_proxies,
+        })
+    }
+
+    /// Whether forwarding headers from `ip` are believed.
+    fn trusts(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies
+            .iter()
+            .any(|&(network, prefix)| in_network(ip, network, prefix))
+    }
+
+    /// The client address for a request from `peer` carrying `headers`.
+    ///
+    /// `X-Forwarded-For` is read right to left: each trusted hop vouches for
+    /// the entry before it, and the first address not vouched for further is
+    /// the client. Unparseable entries stop the walk.
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let peer = peer.to_canonical();
+        if !self.trust_forwarded_for {
+            return peer;
+        }
+
+        let entries: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .collect();
+
+        let mut client = peer;
+        for entry in entries.iter().rev() {
+            if !self.trusts(client) {
+                break;
+            }
+            let Some(ip) = parse_forwarded_addr(entry) else {
+                break;
+            };
+            client = ip;
+        }
+        client
+    }
+}
+
+/// Parse one `X-Forwarded-For` entry, which may carry a port.
+fn parse_forwarded_addr(entry: &str) -> Option<IpAddr> {
+    entry
+        .parse::<IpAddr>()
+        .or_else(|_| entry.parse::<SocketAddr>().map(|s| s.ip()))
+        .ok()
+        .map(|ip| ip.to_canonical())
+}
+
+fn parse_proxy_cidr(cidr: &str) -> Result<(IpAddr, u8)> {
+    let invalid = || IpCalcError::ConfigParse(format!("invalid trusted proxy '{}'", cidr));
+    let (addr, prefix) = match cidr.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (cidr, None),
+    };
+    let addr: IpAddr = addr.trim().parse().map_err(|_| invalid())?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(p) => p.trim().parse::<u8>().map_err(|_| invalid())?,
+        None => max,
+    };
+    if prefix > max {
+        return Err(invalid());
+    }
+    Ok((addr, prefix))
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(net)) => {
+            let mask = ipv4_mask(prefix);
+            u32::from(ip) & mask == u32::from(net) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(net)) => {
+            let mask = ipv6_mask(prefix);
+            u128::from(ip) & mask == u128::from(net) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Axum middleware that records the [`ClientIp`] of each request.
+///
+/// Requests without a socket address (e.g. in-process tests) get none.
+pub async fn client_ip_middleware(
+    State(resolver): State<Arc<ClientIpResolver>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(ConnectInfo(peer)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        let client = resolver.resolve(peer.ip(), request.headers());
+        request.extensions_mut().insert(ClientIp(client));
+    }
+    next.run(request).await
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::HeaderValue;
+    use axum::{Extension, Router, middleware, routing::get};
+    use tower::ServiceExt;
+
+    fn resolver(trust: bool, proxies: &[&str]) -> ClientIpResolver {
+        ClientIpResolver::from_config(&ServerConfig {
+            trust_forwarded_for: trust,
+            trusted_proxies: proxies.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn xff(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_defaults_to_socket_peer() {
+        let r = resolver(false, &["10.0.0.0/8"]);
+        assert_eq!(
+            r.resolve(ip("10.0.0.1"), &xff("203.0.113.9")),
+            ip("10.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_trusted_proxy_header_used() {
+        let r = resolver(true, &["10.0.0.0/8"]);
+        assert_eq!(
+            r.resolve(ip("10.0.0.1"), &xff("203.0.113.9")),
+            ip("203.0.113.9")
+        );
+        // A chain of trusted proxies is skipped; the spoofed leftmost entry is not
+        assert_eq!(
+            r.resolve(ip("10.0.0.1"), &xff("1.1.1.1, 203.0.113.9, 10.0.0.2")),
+            ip("203.0.113.9")
+        );
+    }
+
+    #[test]
+    fn test_untrusted_peer_header_ignored() {
+        let r = resolver(true, &["10.0.0.0/8"]);
+        assert_eq!(
+            r.resolve(ip("198.51.100.7"), &xff("203.0.113.9")),
+            ip("198.51.100.7")
+        );
+    }
+
+    #[test]
+    fn test_empty_proxy_list_rejected() {
+        // Trusting any peer would let a direct client spoof its address
+        assert!(
+            ClientIpResolver::from_config(&ServerConfig {
+                trust_forwarded_for: true,
+                ..Default::default()
+            })
+            .is_err()
+        );
+        let r = ClientIpResolver::default();
+        assert_eq!(
+            r.resolve(ip("192.0.2.1"), &xff("203.0.113.9")),
+            ip("192.0.2.1")
+        );
+    }
+
+    #[test]
+    fn test_malformed_entries() {
+        let r = resolver(true, &["10.0.0.0/8"]);
+        assert_eq!(r.resolve(ip("10.0.0.1"), &xff("garbage")), ip("10.0.0.1"));
+        assert_eq!(
+            r.resolve(ip("10.0.0.1"), &xff("203.0.113.9:4711")),
+            ip("203.0.113.9")
+        );
+        assert_eq!(
+            r.resolve(ip("::ffff:10.0.0.1"), &xff("[2001:db8::1]:443")),
+            ip("2001:db8::1")
+        );
+        assert!(
+            ClientIpResolver::from_config(&ServerConfig {
+                trusted_proxies: vec!["10.0.0.0/33".to_string()],
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_middleware_inserts_client_ip() {
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Extension(ClientIp(ip)): Extension<ClientIp>| async move { ip.to_string() }),
+            )
+            .layer(middleware::from_fn_with_state(
+                Arc::new(resolver(true, &["127.0.0.1"])),
+                client_ip_middleware,
+            ));
+
+        let mut request = Request::builder()
+            .uri("/")
+            .header("x-forwarded-for", "203.0.113.9")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 5000))));
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"203.0.113.9");
+    }
+}
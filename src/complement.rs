@@ -0,0 +1,250 @@
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::{Ipv4Subnet, ipv4_mask};
+use crate::ipv6::{Ipv6Format, Ipv6Subnet, ipv6_mask};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// The space left in `within` once `cidr` is removed.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv4ComplementResult {
+    pub cidr: String,
+    pub within: String,
+    pub cidr_count: usize,
+    pub cidrs: Vec<Ipv4Subnet>,
+}
+
+/// The space left in `within` once `cidr` is removed.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv6ComplementResult {
+    pub cidr: String,
+    pub within: String,
+    pub cidr_count: usize,
+    pub cidrs: Vec<Ipv6Subnet>,
+}
+
+impl Ipv6ComplementResult {
+    /// Report every `total_addresses` as an exact decimal.
+    pub fn apply_decimal_totals(&mut self) {
+        for subnet in &mut self.cidrs {
+            subnet.apply_decimal_total();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Core algorithm
+// ---------------------------------------------------------------------------
+
+/// Subtract the block `(network, prefix)` from its ancestor of length `parent`.
+///
+/// Walking up from `prefix` to `parent + 1`, the sibling at each level is the
+/// block that differs only in that level's bit; together those siblings tile
+/// the ancestor minus the original block. Returned in ascending order.
+fn subtract_from_parent(network: u128, prefix: u8, parent: u8, bits: u8) -> Vec<(u128, u8)> {
+    let mut siblings: Vec<(u128, u8)> = (parent + 1..=prefix)
+        .map(|level| {
+            let flip = 1u128 << (bits - level);
+            let mask = if bits == 32 {
+                ipv4_mask(level) as u128
+            } else {
+                ipv6_mask(level)
+            };
+            ((network ^ flip) & mask, level)
+        })
+        .collect();
+    siblings.sort();
+    siblings
+}
+
+fn parent_prefix(prefix: u8, within_prefix: Option<u8>, cidr: &str, within: &str) -> Result<u8> {
+    match within_prefix {
+        Some(p) if p > prefix => Err(IpCalcError::InvalidInput(format!(
+            "{} is smaller than {} and cannot contain it",
+            within, cidr
+        ))),
+        Some(p) => Ok(p),
+        None if prefix == 0 => Err(IpCalcError::InvalidInput(format!(
+            "{} is the whole address space and has no parent block",
+            cidr
+        ))),
+        None => Ok(prefix - 1),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Complement of an IPv4 CIDR within `within`, or within its one-bit-shorter
+/// parent when `within` is `None`.
+pub fn complement_ipv4(cidr: &str, within: Option<&str>) -> Result<Ipv4ComplementResult> {
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    let outer = within.map(Ipv4Subnet::from_cidr).transpose()?;
+
+    let parent = parent_prefix(
+        subnet.prefix_length,
+        outer.as_ref().map(|o| o.prefix_length),
+        &subnet.input,
+        within.unwrap_or_default(),
+    )?;
+    let network = u32::from(subnet.network);
+    if let Some(outer) = &outer
+        && network & ipv4_mask(parent) != u32::from(outer.network)
+    {
+        return Err(IpCalcError::InvalidInput(format!(
+            "{}/{} is not inside {}/{}",
+            subnet.network, subnet.prefix_length, outer.network, outer.prefix_length
+        )));
+    }
+
+    let cidrs = subtract_from_parent(network as u128, subnet.prefix_length, parent, 32)
+        .into_iter()
+        .map(|(net, prefix)| Ipv4Subnet::new(Ipv4Addr::from(net as u32), prefix))
+        .collect::<Result<Vec<_>>>()?;
+
+    let parent_network = Ipv4Addr::from(network & ipv4_mask(parent));
+    Ok(Ipv4ComplementResult {
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        within: format!("{}/{}", parent_network, parent),
+        cidr_count: cidrs.len(),
+        cidrs,
+    })
+}
+
+/// Complement of an IPv6 CIDR within `within`, or within its one-bit-shorter
+/// parent when `within` is `None`, with every block rendered in `format`.
+pub fn complement_ipv6(
+    cidr: &str,
+    within: Option<&str>,
+    format: Ipv6Format,
+) -> Result<Ipv6ComplementResult> {
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+    let outer = within.map(Ipv6Subnet::from_cidr).transpose()?;
+
+    let parent = parent_prefix(
+        subnet.prefix_length,
+        outer.as_ref().map(|o| o.prefix_length),
+        &subnet.input,
+        within.unwrap_or_default(),
+    )?;
+    let network = u128::from(subnet.network);
+    if let Some(outer) = &outer
+        && network & ipv6_mask(parent) != u128::from(outer.network)
+    {
+        return Err(IpCalcError::InvalidInput(format!(
+            "{}/{} is not inside {}/{}",
+            subnet.network, subnet.prefix_length, outer.network, outer.prefix_length
+        )));
+    }
+
+    let cidrs = subtract_from_parent(network, subnet.prefix_length, parent, 128)
+        .into_iter()
+        .map(|(net, prefix)| Ipv6Subnet::with_format(Ipv6Addr::from(net), prefix, format))
+        .collect::<Result<Vec<_>>>()?;
+
+    let parent_network = Ipv6Addr::from(network & ipv6_mask(parent));
+    Ok(Ipv6ComplementResult {
+        cidr: format!(
+            "{}/{}",
+            format.compressed(&subnet.network),
+            subnet.prefix_length
+        ),
+        within: format!("{}/{}", format.compressed(&parent_network), parent),
+        cidr_count: cidrs.len(),
+        cidrs,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v4_cidrs(result: &Ipv4ComplementResult) -> Vec<String> {
+        result
+            .cidrs
+            .iter()
+            .map(|s| format!("{}/{}", s.network, s.prefix_length))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_parent() {
+        let result = complement_ipv4("10.0.1.0/24", None).unwrap();
+        assert_eq!(result.within, "10.0.0.0/23");
+        assert_eq!(v4_cidrs(&result), ["10.0.0.0/24"]);
+    }
+
+    #[test]
+    fn test_explicit_within() {
+        let result = complement_ipv4("10.0.1.0/24", Some("10.0.0.0/22")).unwrap();
+        assert_eq!(result.within, "10.0.0.0/22");
+        assert_eq!(v4_cidrs(&result), ["10.0.0.0/24", "10.0.2.0/23"]);
+
+        let result = complement_ipv4("192.168.0.64/26", Some("192.168.0.0/24")).unwrap();
+        assert_eq!(v4_cidrs(&result), ["192.168.0.0/26", "192.168.0.128/25"]);
+    }
+
+    #[test]
+    fn test_within_equal_is_empty() {
+        let result = complement_ipv4("10.0.0.0/24", Some("10.0.0.0/24")).unwrap();
+        assert_eq!(result.cidr_count, 0);
+    }
+
+    #[test]
+    fn test_within_errors() {
+        let err = complement_ipv4("10.0.1.0/24", Some("10.0.2.0/23")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: 10.0.1.0/24 is not inside 10.0.2.0/23"
+        );
+        let err = complement_ipv4("10.0.0.0/16", Some("10.0.0.0/24")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: 10.0.0.0/24 is smaller than 10.0.0.0/16 and cannot contain it"
+        );
+        let err = complement_ipv4("0.0.0.0/0", None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: 0.0.0.0/0 is the whole address space and has no parent block"
+        );
+        let err = complement_ipv6("::/0", None, Ipv6Format::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: ::/0 is the whole address space and has no parent block"
+        );
+    }
+
+    #[test]
+    fn test_ipv6_complement() {
+        let result = complement_ipv6(
+            "2001:db8:1::/48",
+            Some("2001:db8::/46"),
+            Ipv6Format::default(),
+        )
+        .unwrap();
+        let cidrs: Vec<String> = result.cidrs.iter().map(Ipv6Subnet::cidr).collect();
+        assert_eq!(cidrs, ["2001:db8::/48", "2001:db8:2::/47"]);
+
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let result = complement_ipv6("2001:db8:1::/48", Some("2001:db8::/46"), upper).unwrap();
+        assert_eq!(result.cidr, "2001:DB8:1::/48");
+        assert_eq!(result.within, "2001:DB8::/46");
+        assert_eq!(result.cidrs[1].cidr(), "2001:DB8:2::/47");
+
+        let result = complement_ipv6("::1/128", None, Ipv6Format::default()).unwrap();
+        assert_eq!(result.within, "::/127");
+        assert_eq!(result.cidrs[0].network, "::".parse::<Ipv6Addr>().unwrap());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::covers::{Block, contains, format_block, overlaps};
 use crate::error::{IpCalcError, Result};
 use crate::ipv4::{Ipv4Subnet, ipv4_mask};
 use crate::ipv6::{Ipv6Subnet, ipv6_mask};
@@ -7,19 +8,280 @@ use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ContainsResult {
     pub cidr: String,
     pub address: String,
     pub contained: bool,
+    /// True when `address` is a range only some of whose addresses are in the CIDR
+    pub partial: bool,
+    /// Share of the range's addresses inside the CIDR, when it is partially contained
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contained_fraction: Option<f64>,
     pub network_address: String,
     pub broadcast_address: String,
+    /// Mapping used to bring the address into the CIDR's family, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translation: Option<AddressTranslation>,
+    /// The address after translation, in the CIDR's family
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translated_address: Option<String>,
+    /// Reverse DNS name of the address, when a PTR lookup was requested and succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ptr_name: Option<String>,
+}
+
+/// Whether one CIDR lies inside another. A block contains itself, and
+/// `overlaps` is true whenever the two share any address.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SubnetContainsResult {
+    pub outer: String,
+    pub inner: String,
+    pub fully_contained: bool,
+    pub overlaps: bool,
+    pub relationship: ContainmentRelationship,
+    pub outer_prefix: u8,
+    pub inner_prefix: u8,
+}
+
+/// How the outer block of a subnet containment check relates to the inner one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ContainmentRelationship {
+    /// The two blocks are the same
+    Identical,
+    /// The outer block contains the inner one
+    Contains,
+    /// The outer block lies inside the inner one
+    ContainedBy,
+    /// The blocks share some addresses but neither contains the other.
+    /// Aligned CIDR blocks always nest or are disjoint, so CIDR inputs never
+    /// produce this.
+    Overlapping,
+    /// The blocks share no addresses
+    Disjoint,
+}
+
+impl ContainmentRelationship {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContainmentRelationship::Identical => "identical",
+            ContainmentRelationship::Contains => "contains",
+            ContainmentRelationship::ContainedBy => "contained_by",
+            ContainmentRelationship::Overlapping => "overlapping",
+            ContainmentRelationship::Disjoint => "disjoint",
+        }
+    }
+}
+
+/// Standard IPv4-in-IPv6 mappings tried by translated containment checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AddressTranslation {
+    /// `::ffff:0:0/96` (RFC 4291)
+    #[serde(rename = "ipv4-mapped")]
+    Ipv4Mapped,
+    /// `::/96`, deprecated by RFC 4291 but still seen in old configs; `::` and
+    /// `::1` are the unspecified and loopback addresses, not IPv4
+    #[serde(rename = "ipv4-compatible")]
+    Ipv4Compatible,
+    /// `2002::/16` (RFC 3056)
+    #[serde(rename = "6to4")]
+    SixToFour,
+    /// `64:ff9b::/96` well-known prefix (RFC 6052)
+    #[serde(rename = "nat64")]
+    Nat64,
+}
+
+impl AddressTranslation {
+    /// Mappings in the order they are attempted.
+    pub const ALL: [AddressTranslation; 4] = [
+        Self::Ipv4Mapped,
+        Self::Ipv4Compatible,
+        Self::SixToFour,
+        Self::Nat64,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ipv4Mapped => "ipv4-mapped",
+            Self::Ipv4Compatible => "ipv4-compatible",
+            Self::SixToFour => "6to4",
+            Self::Nat64 => "nat64",
+        }
+    }
+
+    /// The IPv6 prefix this mapping embeds IPv4 addresses in.
+    fn prefix(&self) -> (u128, u8) {
+        match self {
+            Self::Ipv4Mapped => (0xffff_u128 << 32, 96),
+            Self::Ipv4Compatible => (0, 96),
+            Self::SixToFour => (0x2002_u128 << 112, 16),
+            Self::Nat64 => (0x0064_ff9b_u128 << 96, 96),
+        }
+    }
+
+    /// Embed an IPv4 address into IPv6 using this mapping.
+    pub fn to_ipv6(&self, addr: Ipv4Addr) -> Ipv6Addr {
+        let v4 = u32::from(addr) as u128;
+        let v6 = match self {
+            Self::SixToFour => self.prefix().0 | v4 << 80,
+            _ => self.prefix().0 | v4,
+        };
+        Ipv6Addr::from(v6)
+    }
+
+    /// Extract the embedded IPv4 address if `addr` falls inside this mapping's prefix.
+    pub fn to_ipv4(&self, addr: Ipv6Addr) -> Option<Ipv4Addr> {
+        let v6 = u128::from(addr);
+        let embedded = match self {
+            Self::Ipv4Mapped if v6 >> 32 == 0xffff => v6 as u32,
+            Self::Ipv4Compatible if v6 >> 32 == 0 && v6 > 1 => v6 as u32,
+            Self::SixToFour if v6 >> 112 == 0x2002 => (v6 >> 80) as u32,
+            Self::Nat64 if v6 >> 32 == 0x0064_ff9b_u128 << 64 => v6 as u32,
+            _ => return None,
+        };
+        Some(Ipv4Addr::from(embedded))
+    }
+}
+
+fn family_mismatch(cidr: &str, address: &str) -> IpCalcError {
+    IpCalcError::AddressFamilyMismatch {
+        cidr: cidr.to_string(),
+        address: address.to_string(),
+    }
+}
+
+fn no_mapping(cidr: &str, address: &str) -> IpCalcError {
+    IpCalcError::NoAddressMapping {
+        cidr: cidr.to_string(),
+        address: address.to_string(),
+    }
+}
+
+/// Split an address range `start-end` into its two ends. The end may give
+/// only the trailing octets of an IPv4 address, taking the rest from the
+/// start: `192.168.1.10-20` ends at 192.168.1.20.
+fn range_ends(range: &str) -> Option<(String, String)> {
+    let (start, end) = range.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+    if start.contains(':') || end.contains(':') {
+        return Some((start.to_string(), end.to_string()));
+    }
+    let start_octets: Vec<&str> = start.split('.').collect();
+    let end_octets: Vec<&str> = end.split('.').collect();
+    let end = if start_octets.len() == 4 && end_octets.len() < 4 {
+        let mut octets = start_octets[..4 - end_octets.len()].to_vec();
+        octets.extend(end_octets);
+        octets.join(".")
+    } else {
+        end.to_string()
+    };
+    Some((start.to_string(), end))
+}
+
+/// Fill in the containment of the inclusive range `start..=end` within
+/// `network..=last`: contained when the whole range is inside, partial when
+/// only some of it is.
+fn apply_range_overlap(
+    result: &mut ContainsResult,
+    start: u128,
+    end: u128,
+    network: u128,
+    last: u128,
+) {
+    let (lo, hi) = (start.max(network), end.min(last));
+    if lo > hi {
+        result.contained = false;
+        return;
+    }
+    result.contained = (lo, hi) == (start, end);
+    result.partial = !result.contained;
+    if result.partial {
+        // Sizes less one, so the whole IPv6 space does not overflow
+        result.contained_fraction = Some(((hi - lo) as f64 + 1.0) / ((end - start) as f64 + 1.0));
+    }
+}
+
+/// Check whether an IPv4 address range (`start-end`) lies within a CIDR.
+fn check_ipv4_range_contains(cidr: &str, range: &str) -> Result<ContainsResult> {
+    let (start, end) = range_ends(range).expect("range contains '-'");
+    let parse = |addr: &str| {
+        Ipv4Addr::from_str(addr).map_err(|_| {
+            if Ipv6Addr::from_str(addr).is_ok() {
+                family_mismatch(cidr, range)
+            } else {
+                IpCalcError::InvalidIpv4Address(range.to_string())
+            }
+        })
+    };
+    let (start_u32, end_u32) = (u32::from(parse(&start)?), u32::from(parse(&end)?));
+    if start_u32 > end_u32 {
+        return Err(IpCalcError::InvalidRange(start, end));
+    }
+    let mut result = check_ipv4_contains(cidr, &Ipv4Addr::from(start_u32).to_string())?;
+    result.address = range.to_string();
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    apply_range_overlap(
+        &mut result,
+        start_u32 as u128,
+        end_u32 as u128,
+        u32::from(subnet.network) as u128,
+        u32::from(subnet.broadcast) as u128,
+    );
+    Ok(result)
+}
+
+/// Check whether an IPv6 address range (`start-end`) lies within a CIDR.
+fn check_ipv6_range_contains(cidr: &str, range: &str) -> Result<ContainsResult> {
+    let (start, end) = range_ends(range).expect("range contains '-'");
+    let parse = |addr: &str| {
+        Ipv6Addr::from_str(addr).map_err(|_| {
+            if Ipv4Addr::from_str(addr).is_ok() {
+                family_mismatch(cidr, range)
+            } else {
+                IpCalcError::InvalidIpv6Address(range.to_string())
+            }
+        })
+    };
+    let (start_u128, end_u128) = (u128::from(parse(&start)?), u128::from(parse(&end)?));
+    if start_u128 > end_u128 {
+        return Err(IpCalcError::InvalidRange(start, end));
+    }
+    let mut result = check_ipv6_contains(cidr, &Ipv6Addr::from(start_u128).to_string())?;
+    result.address = range.to_string();
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+    apply_range_overlap(
+        &mut result,
+        start_u128,
+        end_u128,
+        u128::from(subnet.network),
+        u128::from(subnet.last),
+    );
+    Ok(result)
 }
 
 /// Check if an IPv4 address is contained within a CIDR range.
+///
+/// `address` may also be a range, `192.168.1.10-192.168.1.20` or
+/// `192.168.1.10-20`, which is contained only if every address in it is.
 pub fn check_ipv4_contains(cidr: &str, address: &str) -> Result<ContainsResult> {
+    if address.contains('-') {
+        return check_ipv4_range_contains(cidr, address);
+    }
     let subnet = Ipv4Subnet::from_cidr(cidr)?;
-    let addr = Ipv4Addr::from_str(address)
-        .map_err(|_| IpCalcError::InvalidIpv4Address(address.to_string()))?;
+    let addr = Ipv4Addr::from_str(address).map_err(|_| {
+        if Ipv6Addr::from_str(address).is_ok() {
+            family_mismatch(cidr, address)
+        } else {
+            IpCalcError::InvalidIpv4Address(address.to_string())
+        }
+    })?;
 
     let addr_u32 = u32::from(addr);
     let network_u32 = u32::from(subnet.network);
@@ -27,20 +289,39 @@ pub fn check_ipv4_contains(cidr: &str, address: &str) -> Result<ContainsResult>
 
     let contained = (addr_u32 & mask) == (network_u32 & mask);
 
-    Ok(ContainsResult {
+    Ok(ipv4_contains_result(&subnet, address, contained))
+}
+
+fn ipv4_contains_result(subnet: &Ipv4Subnet, address: &str, contained: bool) -> ContainsResult {
+    ContainsResult {
         cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
         address: address.to_string(),
         contained,
+        partial: false,
+        contained_fraction: None,
         network_address: subnet.network.to_string(),
         broadcast_address: subnet.broadcast.to_string(),
-    })
+        translation: None,
+        translated_address: None,
+        ptr_name: None,
+    }
 }
 
 /// Check if an IPv6 address is contained within a CIDR range.
+///
+/// `address` may also be a range `start-end`; see [`check_ipv4_contains`].
 pub fn check_ipv6_contains(cidr: &str, address: &str) -> Result<ContainsResult> {
+    if address.contains('-') {
+        return check_ipv6_range_contains(cidr, address);
+    }
     let subnet = Ipv6Subnet::from_cidr(cidr)?;
-    let addr = Ipv6Addr::from_str(address)
-        .map_err(|_| IpCalcError::InvalidIpv6Address(address.to_string()))?;
+    let addr = Ipv6Addr::from_str(address).map_err(|_| {
+        if Ipv4Addr::from_str(address).is_ok() {
+            family_mismatch(cidr, address)
+        } else {
+            IpCalcError::InvalidIpv6Address(address.to_string())
+        }
+    })?;
 
     let addr_u128 = u128::from(addr);
     let network_u128 = u128::from(subnet.network);
@@ -48,13 +329,132 @@ pub fn check_ipv6_contains(cidr: &str, address: &str) -> Result<ContainsResult>
 
     let contained = (addr_u128 & mask) == (network_u128 & mask);
 
-    Ok(ContainsResult {
+    Ok(ipv6_contains_result(&subnet, address, contained))
+}
+
+fn ipv6_contains_result(subnet: &Ipv6Subnet, address: &str, contained: bool) -> ContainsResult {
+    ContainsResult {
         cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
         address: address.to_string(),
         contained,
+        partial: false,
+        contained_fraction: None,
         network_address: subnet.network.to_string(),
         broadcast_address: subnet.last.to_string(),
-    })
+        translation: None,
+        translated_address: None,
+        ptr_name: None,
+    }
+}
+
+fn subnet_contains_result(outer: Block, inner: Block, bits: u8) -> SubnetContainsResult {
+    let fully_contained = contains(outer, inner, bits);
+    let overlaps = overlaps(outer, inner, bits);
+    let relationship = if outer == inner {
+        ContainmentRelationship::Identical
+    } else if fully_contained {
+        ContainmentRelationship::Contains
+    } else if contains(inner, outer, bits) {
+        ContainmentRelationship::ContainedBy
+    } else if overlaps {
+        ContainmentRelationship::Overlapping
+    } else {
+        ContainmentRelationship::Disjoint
+    };
+    SubnetContainsResult {
+        outer: format_block(outer, bits),
+        inner: format_block(inner, bits),
+        fully_contained,
+        overlaps,
+        relationship,
+        outer_prefix: outer.1,
+        inner_prefix: inner.1,
+    }
+}
+
+/// Check if an IPv4 CIDR lies entirely within another.
+pub fn check_ipv4_subnet_contains(outer: &str, inner: &str) -> Result<SubnetContainsResult> {
+    if inner.contains(':') {
+        return Err(family_mismatch(outer, inner));
+    }
+    let o = Ipv4Subnet::from_cidr(outer)?;
+    let i = Ipv4Subnet::from_cidr(inner)?;
+    Ok(subnet_contains_result(
+        (u32::from(o.network) as u128, o.prefix_length),
+        (u32::from(i.network) as u128, i.prefix_length),
+        32,
+    ))
+}
+
+/// Check if an IPv6 CIDR lies entirely within another.
+pub fn check_ipv6_subnet_contains(outer: &str, inner: &str) -> Result<SubnetContainsResult> {
+    if !inner.contains(':') {
+        return Err(family_mismatch(outer, inner));
+    }
+    let o = Ipv6Subnet::from_cidr(outer)?;
+    let i = Ipv6Subnet::from_cidr(inner)?;
+    Ok(subnet_contains_result(
+        (u128::from(o.network), o.prefix_length),
+        (u128::from(i.network), i.prefix_length),
+        128,
+    ))
+}
+
+/// Check containment, translating the address between families when they differ.
+///
+/// When the CIDR and address are the same family this is a plain containment
+/// check. Otherwise the IPv4-mapped, IPv4-compatible, 6to4, and NAT64
+/// mappings whose prefix overlaps the CIDR are tried in order; the first one
+/// that places the address inside the CIDR is reported, or else the first one
+/// that applies, with the address not contained. An IPv6 address is
+/// translated through the mapping it was embedded with. When no mapping
+/// applies, a `NoAddressMapping` error is returned.
+pub fn check_contains_translated(cidr: &str, address: &str) -> Result<ContainsResult> {
+    let cidr_is_v6 = cidr.contains(':');
+    let addr_is_v6 = address.contains(':');
+
+    if cidr_is_v6 == addr_is_v6 {
+        return if cidr_is_v6 {
+            check_ipv6_contains(cidr, address)
+        } else {
+            check_ipv4_contains(cidr, address)
+        };
+    }
+
+    if cidr_is_v6 {
+        let subnet = Ipv6Subnet::from_cidr(cidr)?;
+        let v4 = Ipv4Addr::from_str(address)
+            .map_err(|_| IpCalcError::InvalidIpv4Address(address.to_string()))?;
+        let block = (u128::from(subnet.network), subnet.prefix_length);
+        let mut applied = None;
+        for mapping in AddressTranslation::ALL {
+            if !overlaps(block, mapping.prefix(), 128) {
+                continue;
+            }
+            let mapped = mapping.to_ipv6(v4).to_string();
+            let mut result = check_ipv6_contains(cidr, &mapped)?;
+            result.address = address.to_string();
+            result.translation = Some(mapping);
+            result.translated_address = Some(mapped);
+            if result.contained {
+                return Ok(result);
+            }
+            applied.get_or_insert(result);
+        }
+        applied.ok_or_else(|| no_mapping(cidr, address))
+    } else {
+        let v6 = Ipv6Addr::from_str(address)
+            .map_err(|_| IpCalcError::InvalidIpv6Address(address.to_string()))?;
+        let (mapping, v4) = AddressTranslation::ALL
+            .iter()
+            .find_map(|m| m.to_ipv4(v6).map(|v4| (*m, v4)))
+            .ok_or_else(|| no_mapping(cidr, address))?;
+        let mut result = check_ipv4_contains(cidr, &v4.to_string())?;
+        result.address = address.to_string();
+        result.translation = Some(mapping);
+        result.translated_address = Some(v4.to_string());
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
@@ -90,6 +490,79 @@ mod tests {
         assert!(result.contained);
     }
 
+    #[test]
+    fn test_ipv4_range_contained() {
+        let result = check_ipv4_contains("192.168.1.0/24", "192.168.1.10-192.168.1.20").unwrap();
+        assert!(result.contained);
+        assert!(!result.partial);
+        assert_eq!(result.contained_fraction, None);
+        assert_eq!(result.address, "192.168.1.10-192.168.1.20");
+
+        // Shorthand end, and a range spanning the whole subnet
+        assert!(
+            check_ipv4_contains("192.168.1.0/24", "192.168.1.10-20")
+                .unwrap()
+                .contained
+        );
+        assert!(
+            check_ipv4_contains("192.168.0.0/16", "192.168.1.0-2.255")
+                .unwrap()
+                .contained
+        );
+        assert!(
+            check_ipv4_contains("10.0.0.0/24", "10.0.0.0-10.0.0.255")
+                .unwrap()
+                .contained
+        );
+    }
+
+    #[test]
+    fn test_ipv4_range_partially_contained() {
+        // 192.168.1.250-192.168.2.9: 6 of 16 addresses are in the /24
+        let result = check_ipv4_contains("192.168.1.0/24", "192.168.1.250-192.168.2.9").unwrap();
+        assert!(!result.contained);
+        assert!(result.partial);
+        assert_eq!(result.contained_fraction, Some(6.0 / 16.0));
+
+        // The range encloses the whole subnet
+        let result = check_ipv4_contains("10.0.0.0/30", "10.0.0.0-10.0.0.7").unwrap();
+        assert!(result.partial);
+        assert_eq!(result.contained_fraction, Some(0.5));
+    }
+
+    #[test]
+    fn test_ipv4_range_outside() {
+        let result = check_ipv4_contains("192.168.1.0/24", "10.0.0.1-10.0.0.9").unwrap();
+        assert!(!result.contained);
+        assert!(!result.partial);
+        assert_eq!(result.contained_fraction, None);
+
+        assert!(matches!(
+            check_ipv4_contains("192.168.1.0/24", "192.168.1.20-10"),
+            Err(IpCalcError::InvalidRange(..))
+        ));
+        assert!(matches!(
+            check_ipv4_contains("192.168.1.0/24", "192.168.1.1-2001:db8::1"),
+            Err(IpCalcError::AddressFamilyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ipv6_range_contains() {
+        let result = check_ipv6_contains("2001:db8::/64", "2001:db8::1-2001:db8::ff").unwrap();
+        assert!(result.contained);
+
+        let result = check_ipv6_contains("2001:db8::/127", "2001:db8::-2001:db8::3").unwrap();
+        assert!(result.partial);
+        assert_eq!(result.contained_fraction, Some(0.5));
+
+        // The whole address space does not overflow the range size
+        let result =
+            check_ipv6_contains("::/1", "::-ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff").unwrap();
+        assert!(result.partial);
+        assert_eq!(result.contained_fraction, Some(0.5));
+    }
+
     #[test]
     fn test_ipv6_contained() {
         let result = check_ipv6_contains("2001:db8::/32", "2001:db8::1").unwrap();
@@ -102,6 +575,197 @@ mod tests {
         assert!(!result.contained);
     }
 
+    #[test]
+    fn test_subnet_contains() {
+        // Proper containment
+        let result = check_ipv4_subnet_contains("192.168.0.0/22", "192.168.1.0/26").unwrap();
+        assert!(result.fully_contained);
+        assert!(result.overlaps);
+        assert_eq!(result.relationship, ContainmentRelationship::Contains);
+        assert_eq!((result.outer_prefix, result.inner_prefix), (22, 26));
+
+        // Exact equality, spelled with host bits
+        let result = check_ipv4_subnet_contains("10.0.0.0/24", "10.0.0.77/24").unwrap();
+        assert!(result.fully_contained);
+        assert_eq!(result.inner, "10.0.0.0/24");
+        assert_eq!(result.relationship, ContainmentRelationship::Identical);
+
+        // Partial overlap: the inner block is larger
+        let result = check_ipv4_subnet_contains("192.168.1.0/26", "192.168.0.0/22").unwrap();
+        assert!(!result.fully_contained);
+        assert!(result.overlaps);
+        assert_eq!(result.relationship, ContainmentRelationship::ContainedBy);
+
+        // Disjoint
+        let result = check_ipv4_subnet_contains("192.168.0.0/22", "192.168.4.0/24").unwrap();
+        assert!(!result.fully_contained);
+        assert!(!result.overlaps);
+        assert_eq!(result.relationship, ContainmentRelationship::Disjoint);
+    }
+
+    #[test]
+    fn test_subnet_contains_ipv6() {
+        let result = check_ipv6_subnet_contains("2001:db8::/32", "2001:db8:1::/48").unwrap();
+        assert!(result.fully_contained);
+        let result = check_ipv6_subnet_contains("::/0", "::/0").unwrap();
+        assert!(result.fully_contained);
+        assert_eq!(result.relationship, ContainmentRelationship::Identical);
+        let result = check_ipv6_subnet_contains("2001:db8::/48", "2001:db8::/32").unwrap();
+        assert!(!result.fully_contained && result.overlaps);
+        let result = check_ipv6_subnet_contains("2001:db8::/32", "2001:db9::/48").unwrap();
+        assert!(!result.overlaps);
+
+        assert!(matches!(
+            check_ipv6_subnet_contains("2001:db8::/32", "10.0.0.0/8"),
+            Err(IpCalcError::AddressFamilyMismatch { .. })
+        ));
+        assert!(check_ipv4_subnet_contains("10.0.0.0/8", "10.0.0.0/33").is_err());
+    }
+
+    #[test]
+    fn test_family_mismatch_without_translate() {
+        let result = check_ipv4_contains("10.0.0.0/8", "::ffff:10.0.0.5");
+        assert!(
+            matches!(result, Err(IpCalcError::AddressFamilyMismatch { .. })),
+            "expected AddressFamilyMismatch, got {:?}",
+            result
+        );
+
+        let result = check_ipv6_contains("2002::/16", "192.0.2.1");
+        assert!(
+            matches!(result, Err(IpCalcError::AddressFamilyMismatch { .. })),
+            "expected AddressFamilyMismatch, got {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_translate_same_family_is_plain_check() {
+        let result = check_contains_translated("192.168.1.0/24", "192.168.1.7").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, None);
+        assert_eq!(result.translated_address, None);
+    }
+
+    #[test]
+    fn test_translate_ipv4_mapped_into_ipv4_cidr() {
+        let result = check_contains_translated("10.0.0.0/8", "::ffff:10.0.0.5").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.address, "::ffff:10.0.0.5");
+        assert_eq!(result.translation, Some(AddressTranslation::Ipv4Mapped));
+        assert_eq!(result.translated_address.as_deref(), Some("10.0.0.5"));
+    }
+
+    #[test]
+    fn test_translate_ipv4_into_ipv4_mapped_cidr() {
+        let result = check_contains_translated("::ffff:0:0/96", "192.0.2.1").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::Ipv4Mapped));
+        assert_eq!(
+            result.translated_address.as_deref(),
+            Some("::ffff:192.0.2.1")
+        );
+    }
+
+    #[test]
+    fn test_translate_6to4() {
+        let result = check_contains_translated("2002::/16", "192.0.2.1").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::SixToFour));
+        assert_eq!(
+            result.translated_address.as_deref(),
+            Some("2002:c000:201::")
+        );
+
+        let result = check_contains_translated("192.0.2.0/24", "2002:c000:201::1").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::SixToFour));
+        assert_eq!(result.translated_address.as_deref(), Some("192.0.2.1"));
+    }
+
+    #[test]
+    fn test_translate_nat64() {
+        let result = check_contains_translated("64:ff9b::/96", "198.51.100.7").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::Nat64));
+        assert_eq!(
+            result.translated_address.as_deref(),
+            Some("64:ff9b::c633:6407")
+        );
+
+        let result = check_contains_translated("198.51.100.0/24", "64:ff9b::198.51.100.7").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::Nat64));
+    }
+
+    #[test]
+    fn test_translate_ipv4_compatible() {
+        let result = check_contains_translated("10.0.0.0/8", "::10.0.0.5").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::Ipv4Compatible));
+        assert_eq!(result.translated_address.as_deref(), Some("10.0.0.5"));
+
+        // Told apart from the IPv4-mapped form of the same address
+        let result = check_contains_translated("10.0.0.0/8", "::ffff:10.0.0.5").unwrap();
+        assert_eq!(result.translation, Some(AddressTranslation::Ipv4Mapped));
+
+        let result = check_contains_translated("::/96", "192.0.2.1").unwrap();
+        assert!(result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::Ipv4Compatible));
+        assert_eq!(result.translated_address.as_deref(), Some("::c000:201"));
+
+        // The loopback address is not an IPv4-compatible address
+        assert!(matches!(
+            check_contains_translated("0.0.0.0/0", "::1"),
+            Err(IpCalcError::NoAddressMapping { .. })
+        ));
+    }
+
+    #[test]
+    fn test_translate_applied_mapping_not_contained() {
+        let result = check_contains_translated("::ffff:192.0.2.0/120", "10.0.0.1").unwrap();
+        assert!(!result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::Ipv4Mapped));
+        assert_eq!(
+            result.translated_address.as_deref(),
+            Some("::ffff:10.0.0.1")
+        );
+
+        let result = check_contains_translated("192.0.2.0/24", "::ffff:10.0.0.1").unwrap();
+        assert!(!result.contained);
+        assert_eq!(result.translation, Some(AddressTranslation::Ipv4Mapped));
+    }
+
+    #[test]
+    fn test_translate_no_mapping() {
+        // No mapping prefix overlaps the CIDR
+        let err = check_contains_translated("2001:db8::/32", "192.0.2.1").unwrap_err();
+        assert!(
+            matches!(err, IpCalcError::NoAddressMapping { .. }),
+            "expected NoAddressMapping, got {:?}",
+            err
+        );
+        assert_eq!(err.to_string(), "No IPv4/IPv6 mapping applies to 192.0.2.1");
+
+        // No IPv4 address is embedded in the IPv6 address
+        let err = check_contains_translated("10.0.0.0/8", "2001:db8::1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No IPv4/IPv6 mapping applies to 2001:db8::1"
+        );
+        assert!(!err.to_string().contains("--translate"));
+
+        // Bad input is still rejected as such in both directions
+        assert!(matches!(
+            check_contains_translated("10.0.0.0/8", "2001:db8::zz"),
+            Err(IpCalcError::InvalidIpv6Address(_))
+        ));
+        assert!(matches!(
+            check_contains_translated("2001:db8::/32", "192.0.2.999"),
+            Err(IpCalcError::InvalidIpv4Address(_))
+        ));
+    }
+
     #[test]
     fn test_invalid_ipv4_address() {
         let result = check_ipv4_contains("192.168.1.0/24", "not-an-ip");
@@ -0,0 +1,238 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use crate::summarize::{prefix_mask, summarize_ipv4_with_limit, summarize_ipv6_with_limit};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// Whether a CIDR list covers a target block exactly.
+///
+/// `gaps` are the parts of the target no input covers; `excess` are the parts
+/// of the inputs outside the target. Coverage is exact when both are empty.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CoverageResult {
+    pub target: String,
+    pub input_count: usize,
+    pub exact: bool,
+    pub gaps: Vec<String>,
+    pub excess: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Block arithmetic over (network, prefix) pairs
+// ---------------------------------------------------------------------------
+
+pub(crate) type Block = (u128, u8);
+
+/// True if `outer` contains `inner`.
+pub(crate) fn contains(outer: Block, inner: Block, bits: u8) -> bool {
+    outer.1 <= inner.1 && inner.0 & prefix_mask(outer.1, bits) == outer.0
+}
+
+pub(crate) fn overlaps(a: Block, b: Block, bits: u8) -> bool {
+    contains(a, b, bits) || contains(b, a, bits)
+}
+
+/// Append the parts of `block` not covered by `holes` to `out`, as the
+/// fewest aligned CIDRs, in ascending order.
+///
+/// Splits `block` in half until each half is either untouched by the holes
+/// or entirely inside one of them.
+pub(crate) fn exclude(block: Block, holes: &[Block], bits: u8, out: &mut Vec<Block>) {
+    let relevant: Vec<Block> = holes
+        .iter()
+        .copied()
+        .filter(|&hole| overlaps(block, hole, bits))
+        .collect();
+    if relevant.is_empty() {
+        out.push(block);
+        return;
+    }
+    if relevant.iter().any(|&hole| contains(hole, block, bits)) {
+        return;
+    }
+    // Some hole lies strictly inside `block`, so it can be split further
+    let child = block.1 + 1;
+    let half = 1u128 << (bits - child);
+    exclude((block.0, child), &relevant, bits, out);
+    exclude((block.0 | half, child), &relevant, bits, out);
+}
+
+/// Gaps and excess of the disjoint `blocks` against `target`.
+fn coverage(target: Block, blocks: &[Block], bits: u8) -> (Vec<Block>, Vec<Block>) {
+    let mut gaps = Vec::new();
+    exclude(target, blocks, bits, &mut gaps);
+
+    let mut excess = Vec::new();
+    for &block in blocks {
+        exclude(block, &[target], bits, &mut excess);
+    }
+    (gaps, excess)
+}
+
+pub(crate) fn format_block(block: Block, bits: u8) -> String {
+    format_block_as(block, bits, Ipv6Format::default())
+}
+
+/// [`format_block`] with an IPv6 network rendered compressed in `format`'s
+/// case.
+pub(crate) fn format_block_as((network, prefix): Block, bits: u8, format: Ipv6Format) -> String {
+    if bits == 32 {
+        format!("{}/{}", Ipv4Addr::from(network as u32), prefix)
+    } else {
+        format!("{}/{}", format.compressed(&Ipv6Addr::from(network)), prefix)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+pub fn check_coverage(target: &str, cidrs: &[String]) -> Result<CoverageResult> {
+    check_coverage_with_limit(target, cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+/// Check that `cidrs` cover `target` exactly: no gaps, no spillover.
+///
+/// The inputs are summarized first, so overlaps and duplicates do not count
+/// as excess. Inputs of the other address family are reported as excess.
+pub fn check_coverage_with_limit(
+    target: &str,
+    cidrs: &[String],
+    max_inputs: usize,
+) -> Result<CoverageResult> {
+    if cidrs.is_empty() {
+        return Err(IpCalcError::EmptyCidrList);
+    }
+    if cidrs.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: cidrs.len(),
+            limit: max_inputs,
+        });
+    }
+
+    let (v6, v4): (Vec<String>, Vec<String>) = cidrs
+        .iter()
+        .map(|c| c.trim().to_string())
+        .partition(|c| c.contains(':'));
+    let v4_blocks: Vec<Block> = if v4.is_empty() {
+        Vec::new()
+    } else {
+        summarize_ipv4_with_limit(&v4, max_inputs)?
+            .cidrs
+            .iter()
+            .map(|s| (u32::from(s.network) as u128, s.prefix_length))
+            .collect()
+    };
+    let v6_blocks: Vec<Block> = if v6.is_empty() {
+        Vec::new()
+    } else {
+        summarize_ipv6_with_limit(&v6, max_inputs)?
+            .cidrs
+            .iter()
+            .map(|s| (u128::from(s.network), s.prefix_length))
+            .collect()
+    };
+
+    let (target_block, bits, same, other, other_bits) = if target.contains(':') {
+        let t = Ipv6Subnet::from_cidr(target)?;
+        let block = (u128::from(t.network), t.prefix_length);
+        (block, 128, v6_blocks, v4_blocks, 32)
+    } else {
+        let t = Ipv4Subnet::from_cidr(target)?;
+        let block = (u32::from(t.network) as u128, t.prefix_length);
+        (block, 32, v4_blocks, v6_blocks, 128)
+    };
+
+    let (gaps, excess) = coverage(target_block, &same, bits);
+    let gaps: Vec<String> = gaps.into_iter().map(|b| format_block(b, bits)).collect();
+    let excess: Vec<String> = excess
+        .into_iter()
+        .map(|b| format_block(b, bits))
+        .chain(other.into_iter().map(|b| format_block(b, other_bits)))
+        .collect();
+
+    Ok(CoverageResult {
+        target: format_block(target_block, bits),
+        input_count: cidrs.len(),
+        exact: gaps.is_empty() && excess.is_empty(),
+        gaps,
+        excess,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(target: &str, cidrs: &[&str]) -> CoverageResult {
+        let cidrs: Vec<String> = cidrs.iter().map(|s| s.to_string()).collect();
+        check_coverage(target, &cidrs).unwrap()
+    }
+
+    #[test]
+    fn test_exact_coverage() {
+        let result = check(
+            "10.0.0.0/16",
+            &["10.0.0.0/17", "10.0.128.0/18", "10.0.192.0/18"],
+        );
+        assert!(result.exact);
+        assert!(result.gaps.is_empty() && result.excess.is_empty());
+
+        // Overlapping inputs are not excess
+        let result = check("10.0.0.0/16", &["10.0.0.0/16", "10.0.1.0/24"]);
+        assert!(result.exact);
+
+        let result = check("2001:db8::/32", &["2001:db8::/33", "2001:db8:8000::/33"]);
+        assert!(result.exact);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let result = check("10.0.0.0/16", &["10.0.0.0/17", "10.0.192.0/18"]);
+        assert!(!result.exact);
+        assert_eq!(result.gaps, ["10.0.128.0/18"]);
+        assert!(result.excess.is_empty());
+
+        let result = check("10.0.0.0/22", &["10.0.1.0/24"]);
+        assert_eq!(result.gaps, ["10.0.0.0/24", "10.0.2.0/23"]);
+
+        let result = check("2001:db8::/32", &["2001:db8::/33"]);
+        assert_eq!(result.gaps, ["2001:db8:8000::/33"]);
+    }
+
+    #[test]
+    fn test_excess() {
+        // A larger input spills over on the sibling side
+        let result = check("10.0.0.0/16", &["10.0.0.0/15"]);
+        assert!(result.gaps.is_empty());
+        assert_eq!(result.excess, ["10.1.0.0/16"]);
+
+        let result = check(
+            "10.0.0.0/16",
+            &["10.0.0.0/16", "192.168.0.0/24", "2001:db8::/48"],
+        );
+        assert_eq!(result.excess, ["192.168.0.0/24", "2001:db8::/48"]);
+
+        let result = check("2001:db8::/32", &["2001:db8::/31"]);
+        assert_eq!(result.excess, ["2001:db9::/32"]);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(check_coverage("10.0.0.0/16", &[]).is_err());
+        assert!(check_coverage("bogus", &["10.0.0.0/16".to_string()]).is_err());
+        assert!(check_coverage("10.0.0.0/16", &["bogus".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::normalize::{canonical_cidr, sort_key};
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// The CIDRs added and removed between two lists.
+///
+/// Both lists are compared as canonical subnet sets: host bits cleared,
+/// duplicates dropped, sorted IPv4 before IPv6.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DiffResult {
+    pub old_count: usize,
+    pub new_count: usize,
+    pub unchanged: usize,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// One RFC 6902 JSON Patch operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PatchOperation {
+    pub op: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// A diff as RFC 6902 JSON Patch operations over the canonical subnet-set
+/// document `{"cidrs": [...]}`, serialized as a bare array.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct JsonPatch(pub Vec<PatchOperation>);
+
+// ---------------------------------------------------------------------------
+// Diffing
+// ---------------------------------------------------------------------------
+
+/// Canonicalize, dedupe, and sort a CIDR list.
+pub fn canonical_set(cidrs: &[String]) -> Result<Vec<String>> {
+    let mut set = cidrs
+        .iter()
+        .map(|c| canonical_cidr(c))
+        .collect::<Result<Vec<_>>>()?;
+    set.sort_by_cached_key(|c| sort_key(c));
+    set.dedup();
+    Ok(set)
+}
+
+fn check_limit(old: &[String], new: &[String]) -> Result<()> {
+    for list in [old, new] {
+        if list.len() > DEFAULTS.max_summarize_inputs {
+            return Err(IpCalcError::SummarizeInputLimitExceeded {
+                count: list.len(),
+                limit: DEFAULTS.max_summarize_inputs,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Which side(s) of a merge walk over two sorted sets an entry came from.
+enum Side<'a> {
+    Old(usize, &'a str),
+    New(usize, &'a str),
+    Both,
+}
+
+/// Walk two sorted canonical sets in step, yielding each entry once.
+fn walk<'a>(old: &'a [String], new: &'a [String]) -> Vec<Side<'a>> {
+    let (mut i, mut j) = (0, 0);
+    let mut sides = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() || j < new.len() {
+        let order = match (old.get(i), new.get(j)) {
+            (Some(a), Some(b)) => sort_key(a).cmp(&sort_key(b)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            _ => std::cmp::Ordering::Greater,
+        };
+        match order {
+            std::cmp::Ordering::Less => {
+                sides.push(Side::Old(i, &old[i]));
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                sides.push(Side::New(j, &new[j]));
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                sides.push(Side::Both);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    sides
+}
+
+/// Compare two CIDR lists as subnet sets.
+pub fn diff_cidr_sets(old: &[String], new: &[String]) -> Result<DiffResult> {
+    check_limit(old, new)?;
+    Ok(diff_canonical_sets(
+        &canonical_set(old)?,
+        &canonical_set(new)?,
+    ))
+}
+
+/// [`diff_cidr_sets`] for sets already in [`canonical_set`] form, without
+/// the input limit.
+pub(crate) fn diff_canonical_sets(old: &[String], new: &[String]) -> DiffResult {
+    let mut result = DiffResult {
+        old_count: old.len(),
+        new_count: new.len(),
+        unchanged: 0,
+        added: Vec::new(),
+        removed: Vec::new(),
+    };
+    for side in walk(old, new) {
+        match side {
+            Side::Old(_, cidr) => result.removed.push(cidr.to_string()),
+            Side::New(_, cidr) => result.added.push(cidr.to_string()),
+            Side::Both => result.unchanged += 1,
+        }
+    }
+    result
+}
+
+/// Express the diff between two CIDR lists as a JSON Patch that turns the
+/// canonical document of `old` into that of `new`.
+///
+/// Removals come first, highest index first, so earlier indices stay valid;
+/// additions follow in ascending order of their final index.
+pub fn diff_json_patch(old: &[String], new: &[String]) -> Result<JsonPatch> {
+    check_limit(old, new)?;
+    let old = canonical_set(old)?;
+    let new = canonical_set(new)?;
+
+    let mut removes = Vec::new();
+    let mut adds = Vec::new();
+    for side in walk(&old, &new) {
+        match side {
+            Side::Old(index, _) => removes.push(PatchOperation {
+                op: "remove".to_string(),
+                path: format!("/cidrs/{}", index),
+                value: None,
+            }),
+            Side::New(index, cidr) => adds.push(PatchOperation {
+                op: "add".to_string(),
+                path: format!("/cidrs/{}", index),
+                value: Some(cidr.to_string()),
+            }),
+            Side::Both => {}
+        }
+    }
+    removes.reverse();
+    removes.extend(adds);
+    Ok(JsonPatch(removes))
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Apply a patch to a canonical document, as a JSON Patch consumer would.
+    fn apply(doc: &[String], patch: &JsonPatch) -> Vec<String> {
+        let mut doc = doc.to_vec();
+        for op in &patch.0 {
+            let index: usize = op.path.trim_start_matches("/cidrs/").parse().unwrap();
+            match op.op.as_str() {
+                "remove" => {
+                    doc.remove(index);
+                }
+                "add" => doc.insert(index, op.value.clone().unwrap()),
+                other => panic!("unexpected op {}", other),
+            }
+        }
+        doc
+    }
+
+    #[test]
+    fn test_diff_sets() {
+        let old = strings(&["10.0.0.0/24", "10.0.1.0/24", "2001:db8::/48"]);
+        let new = strings(&["10.0.1.5/24", "10.0.2.0/24", "2001:db8::/48"]);
+        let result = diff_cidr_sets(&old, &new).unwrap();
+        assert_eq!(result.added, ["10.0.2.0/24"]);
+        assert_eq!(result.removed, ["10.0.0.0/24"]);
+        assert_eq!(result.unchanged, 2);
+    }
+
+    #[test]
+    fn test_json_patch_add_and_remove() {
+        let old = strings(&["10.0.0.0/24", "10.0.1.0/24"]);
+        let new = strings(&["10.0.1.0/24", "10.0.2.0/24"]);
+        let patch = diff_json_patch(&old, &new).unwrap();
+        assert_eq!(
+            patch.0,
+            [
+                PatchOperation {
+                    op: "remove".to_string(),
+                    path: "/cidrs/0".to_string(),
+                    value: None,
+                },
+                PatchOperation {
+                    op: "add".to_string(),
+                    path: "/cidrs/1".to_string(),
+                    value: Some("10.0.2.0/24".to_string()),
+                },
+            ]
+        );
+        let json = serde_json::to_value(&patch).unwrap();
+        assert_eq!(
+            json[0],
+            serde_json::json!({"op": "remove", "path": "/cidrs/0"})
+        );
+    }
+
+    #[test]
+    fn test_json_patch_applies_cleanly() {
+        let old = strings(&["10.0.0.0/24", "10.0.3.0/24", "10.0.5.0/24", "2001:db8::/32"]);
+        let new = strings(&["10.0.1.0/24", "10.0.3.0/24", "10.0.4.0/24", "10.0.9.0/24"]);
+        let patch = diff_json_patch(&old, &new).unwrap();
+        let old_doc = canonical_set(&old).unwrap();
+        assert_eq!(apply(&old_doc, &patch), canonical_set(&new).unwrap());
+
+        // Identical sets give an empty patch
+        assert!(diff_json_patch(&old, &old).unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(diff_cidr_sets(&strings(&["bogus"]), &[]).is_err());
+    }
+}
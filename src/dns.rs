@@ -0,0 +1,97 @@
+use crate::contains::ContainsResult;
+use std::future::Future;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// Upper bound on a single PTR lookup, including retries.
+pub const PTR_LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Look up the PTR name for `addr` using the system resolver configuration.
+///
+/// Returns `None` when there is no record, the lookup fails, or it does not
+/// finish within [`PTR_LOOKUP_TIMEOUT`]. The trailing root dot is removed.
+#[cfg(feature = "dns")]
+pub async fn lookup_ptr(addr: IpAddr) -> Option<String> {
+    use hickory_resolver::TokioAsyncResolver;
+    use hickory_resolver::config::{ResolverConfig, ResolverOpts};
+    use tracing::debug;
+
+    let (config, mut opts) = hickory_resolver::system_conf::read_system_conf()
+        .unwrap_or_else(|_| (ResolverConfig::default(), ResolverOpts::default()));
+    opts.timeout = PTR_LOOKUP_TIMEOUT;
+    opts.attempts = 1;
+    let resolver = TokioAsyncResolver::tokio(config, opts);
+
+    match tokio::time::timeout(PTR_LOOKUP_TIMEOUT, resolver.reverse_lookup(addr)).await {
+        Ok(Ok(lookup)) => lookup
+            .iter()
+            .next()
+            .map(|name| name.to_utf8().trim_end_matches('.').to_string()),
+        Ok(Err(e)) => {
+            debug!(address = %addr, error = %e, "PTR lookup returned no record");
+            None
+        }
+        Err(_) => {
+            debug!(address = %addr, "PTR lookup timed out");
+            None
+        }
+    }
+}
+
+/// Without the `dns` feature no lookups are made and `None` is returned.
+#[cfg(not(feature = "dns"))]
+pub async fn lookup_ptr(addr: IpAddr) -> Option<String> {
+    tracing::warn!(address = %addr, "PTR lookup skipped: built without the `dns` feature");
+    None
+}
+
+/// Fill in `ptr_name` for the queried address using [`lookup_ptr`].
+pub async fn resolve_contains(result: &mut ContainsResult) {
+    resolve_contains_with(result, lookup_ptr).await;
+}
+
+/// Fill in `ptr_name` for the queried address using `lookup`.
+///
+/// Never fails: an unparseable address or a failed lookup leaves `ptr_name`
+/// unset.
+pub async fn resolve_contains_with<F, Fut>(result: &mut ContainsResult, lookup: F)
+where
+    F: FnOnce(IpAddr) -> Fut,
+    Fut: Future<Output = Option<String>>,
+{
+    if let Ok(addr) = result.address.parse::<IpAddr>() {
+        result.ptr_name = lookup(addr).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contains::check_ipv4_contains;
+
+    #[tokio::test]
+    async fn test_resolve_with_mock_lookup() {
+        let mut result = check_ipv4_contains("192.168.1.0/24", "192.168.1.10").unwrap();
+        assert!(result.ptr_name.is_none());
+
+        resolve_contains_with(&mut result, |addr| async move {
+            assert_eq!(addr, "192.168.1.10".parse::<IpAddr>().unwrap());
+            Some("host10.example.net".to_string())
+        })
+        .await;
+        assert_eq!(result.ptr_name.as_deref(), Some("host10.example.net"));
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["ptr_name"], "host10.example.net");
+    }
+
+    #[tokio::test]
+    async fn test_no_record_leaves_field_absent() {
+        let mut result = check_ipv4_contains("192.168.1.0/24", "192.168.1.10").unwrap();
+        resolve_contains_with(&mut result, |_| async { None }).await;
+        assert!(result.ptr_name.is_none());
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json.get("ptr_name").is_none());
+    }
+}
@@ -8,11 +8,26 @@ pub enum IpCalcError {
     #[error("Invalid IPv6 address: {0}")]
     InvalidIpv6Address(String),
 
+    #[error("IPv6 address {input} is not in canonical form; use {canonical}")]
+    NonCanonicalIpv6 { input: String, canonical: String },
+
+    #[error("Invalid MAC address: {0}")]
+    InvalidMacAddress(String),
+
+    #[error("EUI-64 addresses need a prefix of /64 or shorter, got /{0}")]
+    Eui64PrefixTooLong(u8),
+
     #[error("Invalid CIDR notation: {0}")]
     InvalidCidr(String),
 
+    #[error("Invalid netmask: {0} (mask bits must be contiguous)")]
+    InvalidNetmask(String),
+
+    #[error("Expected only {expected} address space, found: {blocks}")]
+    UnexpectedAddressSpace { expected: String, blocks: String },
+
     #[error("Invalid prefix length: {0} (must be 0-32 for IPv4, 0-128 for IPv6)")]
-    InvalidPrefixLength(u8),
+    InvalidPrefixLength(u32),
 
     #[error(
         "Cannot generate {requested} /{new_prefix} subnets from /{original_prefix} (only {available} available)"
@@ -24,11 +39,53 @@ pub enum IpCalcError {
         original_prefix: u8,
     },
 
+    #[error(
+        "Subnet index {index} is out of range: a /{original_prefix} has only {available} /{new_prefix} subnets (indexed from 0)"
+    )]
+    SubnetIndexOutOfRange {
+        index: u64,
+        available: String,
+        new_prefix: u8,
+        original_prefix: u8,
+    },
+
+    #[error(
+        "{family} supernet ran out: cannot generate {requested} /{new_prefix} subnets from /{original_prefix} (only {available} available)"
+    )]
+    DualStackExhausted {
+        family: String,
+        requested: u64,
+        available: u64,
+        new_prefix: u8,
+        original_prefix: u8,
+    },
+
+    #[error(
+        "{supernet} cannot fit the requested subnets: they need {required} addresses but it has {available}"
+    )]
+    VlsmExhausted {
+        supernet: String,
+        required: u64,
+        available: u64,
+    },
+
     #[error(
         "New prefix length {new_prefix} must be greater than original prefix {original_prefix}"
     )]
     InvalidSubnetSplit { new_prefix: u8, original_prefix: u8 },
 
+    #[error(
+        "Tree level {level} prefix /{prefix} must be longer than the level above it (/{parent_prefix})"
+    )]
+    InvalidTreeLevel {
+        level: usize,
+        prefix: u8,
+        parent_prefix: u8,
+    },
+
+    #[error("A split tree of {count} blocks exceeds the limit of {limit}")]
+    TreeNodeLimitExceeded { count: String, limit: u64 },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -46,6 +103,21 @@ pub enum IpCalcError {
     #[error("YAML serialization error: {0}")]
     Yaml(String),
 
+    #[error("Output format '{0}' is not available in this build")]
+    UnsupportedFormat(String),
+
+    #[error(
+        "Output failed schema validation at {schema_path} (value at '{instance_path}'): {message}"
+    )]
+    OutputValidation {
+        schema_path: String,
+        instance_path: String,
+        message: String,
+    },
+
+    #[error("Output validation is not available in this build")]
+    OutputValidationUnavailable,
+
     #[error("No CIDRs provided for summarization")]
     EmptyCidrList,
 
@@ -58,6 +130,9 @@ pub enum IpCalcError {
     #[error("Generated CIDR count {count} exceeds maximum of {limit}")]
     FromRangeLimitExceeded { count: usize, limit: usize },
 
+    #[error("Generating {count} records exceeds the limit of {limit}")]
+    RecordLimitExceeded { count: u64, limit: u64 },
+
     #[error("Summarize input count {count} exceeds maximum of {limit}")]
     SummarizeInputLimitExceeded { count: usize, limit: usize },
 
@@ -85,8 +160,248 @@ pub enum IpCalcError {
     #[error("No free space in {supernet} for a /{prefix} allocation")]
     NoFreeSpace { supernet: String, prefix: u8 },
 
+    #[error(
+        "{supernet} has room for only {available} free /{prefix} blocks, {requested} requested"
+    )]
+    InsufficientFreeSpace {
+        supernet: String,
+        prefix: u8,
+        requested: u64,
+        available: u64,
+    },
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+
+    #[error("Internal verification failed: {0}")]
+    VerificationFailed(String),
+
+    #[error("Address family mismatch: {address} and {cidr} are different IP versions")]
+    AddressFamilyMismatch { cidr: String, address: String },
+
+    #[error("No IPv4/IPv6 mapping applies to {address}")]
+    NoAddressMapping { cidr: String, address: String },
+
+    #[error("{cidr} has no {direction} subnet of the same size in the address space")]
+    NoAdjacentSubnet { cidr: String, direction: String },
+
+    #[error("{address} plus {offset} is outside the address space")]
+    AddressOutOfRange { address: String, offset: String },
+
+    #[error("Server startup failed at {step}: {message}")]
+    StartupFailed { step: String, message: String },
 }
 
 pub type Result<T> = std::result::Result<T, IpCalcError>;
+
+impl IpCalcError {
+    /// Process exit code for this error: 70 (`EX_SOFTWARE`) for a failed
+    /// self-check, which indicates a bug, and 1 for everything else.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::VerificationFailed(_) | Self::OutputValidation { .. } => 70,
+            _ => 1,
+        }
+    }
+
+    /// Multi-line guidance describing why an input was rejected and what is expected.
+    ///
+    /// Returns `None` for errors that are not caused by malformed user input
+    /// (I/O, serialization, database failures).
+    pub fn explanation(&self) -> Option<String> {
+        let text = match self {
+            Self::InvalidIpv4Address(addr) => format!(
+                "'{}' is not a valid IPv4 address.\n\
+                 Expected four decimal octets (0-255) separated by dots.\n\
+                 Example: 192.168.1.10",
+                addr
+            ),
+            Self::InvalidIpv6Address(addr) => format!(
+                "'{}' is not a valid IPv6 address.\n\
+                 Expected up to eight groups of hex digits separated by colons;\n\
+                 a single '::' may replace one run of zero groups.\n\
+                 Example: 2001:db8::1",
+                addr
+            ),
+            Self::NonCanonicalIpv6 { canonical, .. } => format!(
+                "Strict mode requires RFC 5952 canonical IPv6 addresses:\n\
+                 lowercase hex, no leading zeros, and '::' replacing the longest\n\
+                 run of two or more zero groups (the first, on a tie).\n\
+                 Canonical form of this input: {}",
+                canonical
+            ),
+            Self::InvalidMacAddress(mac) => format!(
+                "'{}' is not a valid MAC address.\n\
+                 Expected six two-digit hex groups separated by colons or dashes.\n\
+                 Examples: 00:1a:2b:3c:4d:5e, 00-1A-2B-3C-4D-5E",
+                mac
+            ),
+            Self::Eui64PrefixTooLong(prefix) => format!(
+                "The EUI-64 interface identifier fills the low 64 bits of the address,\n\
+                 so the prefix can be at most /64; /{} leaves too few host bits.\n\
+                 Example: ipcalc eui64 2001:db8::/64 00:1a:2b:3c:4d:5e",
+                prefix
+            ),
+            Self::InvalidCidr(cidr) => format!(
+                "'{}' is not valid CIDR notation.\n\
+                 Expected an address followed by '/' and a decimal prefix length.\n\
+                 Examples: 192.168.1.0/24, 2001:db8::/48",
+                cidr
+            ),
+            Self::InvalidNetmask(mask) => format!(
+                "'{}' is not a contiguous IPv4 mask.\n\
+                 A netmask is a run of one bits followed by zero bits (255.255.255.192);\n\
+                 a wildcard mask is the inverse (0.0.0.63).\n\
+                 Examples: 192.168.1.0/255.255.255.0, 192.168.1.0 0.0.0.255",
+                mask
+            ),
+            Self::UnexpectedAddressSpace { expected, .. } => format!(
+                "--expect {} requires every summarized block to be {} space.\n\
+                 Check the listed blocks for typos (e.g. 100.x for 10.x), or drop\n\
+                 --expect to get a warning instead of an error.",
+                expected, expected
+            ),
+            Self::InvalidPrefixLength(prefix) => format!(
+                "Prefix length /{} is out of range.\n\
+                 Valid range: 0-32 for IPv4, 0-128 for IPv6.\n\
+                 Examples: 10.0.0.0/8, 2001:db8::/32",
+                prefix
+            ),
+            Self::InsufficientSubnets {
+                new_prefix,
+                original_prefix,
+                ..
+            } => format!(
+                "A /{} contains 2^({} - {}) subnets of size /{}.\n\
+                 Request fewer subnets with -n, use --max, or pick a shorter prefix.",
+                original_prefix, new_prefix, original_prefix, new_prefix
+            ),
+            Self::SubnetIndexOutOfRange {
+                new_prefix,
+                original_prefix,
+                ..
+            } => format!(
+                "A /{} contains 2^({} - {}) subnets of size /{}, numbered from 0.\n\
+                 Pick a smaller --index or a longer prefix.",
+                original_prefix, new_prefix, original_prefix, new_prefix
+            ),
+            Self::DualStackExhausted {
+                family,
+                new_prefix,
+                original_prefix,
+                ..
+            } => format!(
+                "Dual-stack plans pair the subnets of both families row by row,\n\
+                 so each supernet must supply every requested row.\n\
+                 A /{} holds 2^({} - {}) {} subnets of size /{}; request fewer rows\n\
+                 or use a longer {} split prefix.",
+                original_prefix, new_prefix, original_prefix, family, new_prefix, family
+            ),
+            Self::VlsmExhausted { .. } => {
+                "Each requirement gets the smallest power-of-two block whose usable hosts\n\
+                 cover it (a /25 for 100 hosts), so plans can need more than the raw sum.\n\
+                 Use a larger supernet or fewer or smaller requirements."
+                    .to_string()
+            }
+            Self::InvalidSubnetSplit {
+                new_prefix,
+                original_prefix,
+            } => format!(
+                "Splitting produces smaller subnets, so the new prefix must be longer.\n\
+                 /{} is not longer than /{}.\n\
+                 Example: ipcalc split 10.0.0.0/8 -p 16 -n 4",
+                new_prefix, original_prefix
+            ),
+            Self::InvalidTreeLevel { .. } => {
+                "Each level splits the blocks of the level above it, so every -p must be\n\
+                 longer than the one before it (and than the supernet's prefix).\n\
+                 Example: ipcalc tree 10.0.0.0/16 -p 20 -p 24"
+                    .to_string()
+            }
+            Self::TreeNodeLimitExceeded { limit, .. } => format!(
+                "At most {} blocks are built in one tree to bound memory use.\n\
+                 Use shorter level prefixes or a smaller supernet.",
+                limit
+            ),
+            Self::RecordLimitExceeded { limit, .. } => format!(
+                "At most {} records are generated in one run to bound memory use.\n\
+                 Use a longer prefix to cover fewer addresses.",
+                limit
+            ),
+            Self::SubnetLimitExceeded { limit, .. } => format!(
+                "At most {} subnets are generated in one run to bound memory use.\n\
+                 Use --count-only to see the total, or -n to generate fewer.",
+                limit
+            ),
+            Self::EmptyCidrList => "At least one CIDR is required.\n\
+                 Example: ipcalc summarize 10.0.0.0/24 10.0.1.0/24"
+                .to_string(),
+            Self::InvalidRange(start, end) => format!(
+                "The start address must not be greater than the end address.\n\
+                 Got start {} and end {}; try swapping them.",
+                start, end
+            ),
+            Self::InputTooLong { limit, .. } => format!(
+                "Inputs are limited to {} bytes.\n\
+                 Check for stray characters or concatenated values.",
+                limit
+            ),
+            Self::BatchSizeExceeded { limit, .. }
+            | Self::FromRangeLimitExceeded { limit, .. }
+            | Self::SummarizeInputLimitExceeded { limit, .. } => format!(
+                "The configured limit for this operation is {}.\n\
+                 Split the input into smaller runs.",
+                limit
+            ),
+            Self::AddressFamilyMismatch { .. } => {
+                "IPv4 and IPv6 inputs cannot be combined in one calculation.\n\
+                 For contains, use --translate to apply IPv4/IPv6 mappings: ::ffff:0:0/96, ::/96,\n\
+                 2002::/16, and 64:ff9b::/96.\n\
+                 Example: ipcalc contains 10.0.0.0/8 ::ffff:10.0.0.5 --translate"
+                    .to_string()
+            }
+            Self::NoAddressMapping { cidr, .. } => format!(
+                "--translate only applies the IPv4-mapped (::ffff:0:0/96), IPv4-compatible\n\
+                 (::/96), 6to4 (2002::/16), and NAT64 (64:ff9b::/96) mappings. An IPv6\n\
+                 address must embed an IPv4 address through one of them, and an IPv6\n\
+                 CIDR must overlap one of their prefixes; {} was checked against each.",
+                cidr
+            ),
+            Self::VerificationFailed(_) => {
+                "A --verify self-check found the result inconsistent with its input.\n\
+                 This is a bug in ipcalc, not a problem with the input; please report it\n\
+                 with the full command line."
+                    .to_string()
+            }
+            Self::OutputValidation { .. } => {
+                "--validate-output found the serialized result does not match its own\n\
+                 JSON Schema, so nothing was written. This is a bug in ipcalc, not a\n\
+                 problem with the input; please report it with the full command line."
+                    .to_string()
+            }
+            Self::OutputValidationUnavailable => {
+                "This ipcalc was built without the `schema` cargo feature.\n\
+                 Drop --validate-output, or rebuild with --features schema."
+                    .to_string()
+            }
+            Self::UnsupportedFormat(format) => format!(
+                "This ipcalc was built without the `{}` cargo feature.\n\
+                 Use --format json or --format text, or rebuild with --features {}.",
+                format, format
+            ),
+            Self::NoFreeSpace { .. } | Self::InsufficientFreeSpace { .. } => {
+                "Blocks start on a multiple of their size, so free space split into\n\
+                 smaller fragments cannot hold a larger block.\n\
+                 Example: ipcalc gaps 10.0.0.0/16 --used 10.0.0.0/24 shows what is free"
+                    .to_string()
+            }
+            Self::InvalidInput(_) => {
+                "The input contains characters or values that are not allowed.\n\
+                 Remove control characters and check the value against the command's help."
+                    .to_string()
+            }
+            _ => return None,
+        };
+        Some(text)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::from_range::count_range_cidrs;
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::Ipv6Subnet;
+use crate::subnet_generator::count_subnets;
+use crate::validation::parse_cidr_parts;
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// Dry-run estimate of a split or from-range generation.
+///
+/// `count` and `estimated_bytes` are strings because a split can exceed u64
+/// (reported as `2^N`).
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GenerationEstimate {
+    pub operation: String,
+    pub input: String,
+    pub count: String,
+    pub limit: u64,
+    pub within_limit: bool,
+    pub estimated_bytes: String,
+    pub estimated_memory: String,
+}
+
+// ---------------------------------------------------------------------------
+// Estimation
+// ---------------------------------------------------------------------------
+
+/// Approximate heap bytes per generated IPv4 result: the struct plus its
+/// `input`, `network_class`, and `address_type` strings.
+const IPV4_HEAP_BYTES: u128 = 64;
+
+/// Approximate heap bytes per generated IPv6 result: the struct plus its
+/// `input`, full-address, and `address_type` strings and the eight hextets.
+const IPV6_HEAP_BYTES: u128 = 384;
+
+fn bytes_per_item(is_v6: bool) -> u128 {
+    if is_v6 {
+        std::mem::size_of::<Ipv6Subnet>() as u128 + IPV6_HEAP_BYTES
+    } else {
+        std::mem::size_of::<Ipv4Subnet>() as u128 + IPV4_HEAP_BYTES
+    }
+}
+
+/// Render a byte count with a binary unit suffix (e.g. `1.5 MiB`).
+fn human_bytes(bytes: u128) -> String {
+    const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn build_estimate(
+    operation: &str,
+    input: String,
+    count: Option<u128>,
+    count_display: String,
+    limit: u64,
+    is_v6: bool,
+) -> GenerationEstimate {
+    let (within_limit, estimated_bytes, estimated_memory) = match count {
+        Some(n) => {
+            let bytes = n.saturating_mul(bytes_per_item(is_v6));
+            (n <= limit as u128, bytes.to_string(), human_bytes(bytes))
+        }
+        // Too large to represent: certainly over any limit
+        None => (false, "unbounded".to_string(), "unbounded".to_string()),
+    };
+    GenerationEstimate {
+        operation: operation.to_string(),
+        input,
+        count: count_display,
+        limit,
+        within_limit,
+        estimated_bytes,
+        estimated_memory,
+    }
+}
+
+/// Estimate a split without generating subnets.
+///
+/// With `count`, the estimate is for that many subnets (validated against what
+/// is available); otherwise it is for the maximum.
+pub fn estimate_split(
+    cidr: &str,
+    new_prefix: u8,
+    count: Option<u64>,
+) -> Result<GenerationEstimate> {
+    let summary = count_subnets(cidr, new_prefix)?;
+    let available: Option<u128> = summary.available_subnets.parse().ok();
+    let n = match (count, available) {
+        (Some(c), Some(a)) if (c as u128) > a => {
+            let (_, original_prefix) = parse_cidr_parts(cidr.trim())?;
+            return Err(IpCalcError::InsufficientSubnets {
+                requested: c,
+                available: a as u64,
+                new_prefix,
+                original_prefix,
+            });
+        }
+        (Some(c), _) => Some(c as u128),
+        (None, a) => a,
+    };
+    let display = match n {
+        Some(v) => v.to_string(),
+        None => summary.available_subnets.clone(),
+    };
+    Ok(build_estimate(
+        "split",
+        format!("{} -> /{}", summary.supernet, new_prefix),
+        n,
+        display,
+        DEFAULTS.max_generated_subnets,
+        cidr.contains(':'),
+    ))
+}
+
+/// Estimate a from-range conversion by counting blocks without building results.
+pub fn estimate_from_range(start: &str, end: &str) -> Result<GenerationEstimate> {
+    let n = count_range_cidrs(start, end)?;
+    Ok(build_estimate(
+        "from-range",
+        format!("{} - {}", start, end),
+        Some(n as u128),
+        n.to_string(),
+        DEFAULTS.max_generated_cidrs as u64,
+        start.contains(':'),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::from_range::{from_range_ipv4, from_range_ipv6};
+    use crate::ipv6::Ipv6Format;
+    use crate::subnet_generator::generate_ipv4_subnets;
+
+    #[test]
+    fn test_split_estimate_matches_generated() {
+        let estimate = estimate_split("192.168.0.0/22", 27, None).unwrap();
+        let generated = generate_ipv4_subnets("192.168.0.0/22", 27, None).unwrap();
+        assert_eq!(estimate.count, generated.subnets.len().to_string());
+        assert!(estimate.within_limit);
+
+        let estimate = estimate_split("192.168.0.0/22", 27, Some(10)).unwrap();
+        assert_eq!(estimate.count, "10");
+    }
+
+    #[test]
+    fn test_from_range_estimate_matches_generated() {
+        let estimate = estimate_from_range("192.168.1.10", "192.168.1.20").unwrap();
+        let generated = from_range_ipv4("192.168.1.10", "192.168.1.20").unwrap();
+        assert_eq!(estimate.count, generated.cidr_count.to_string());
+
+        let estimate = estimate_from_range("2001:db8::1", "2001:db8::ff").unwrap();
+        let generated =
+            from_range_ipv6("2001:db8::1", "2001:db8::ff", Ipv6Format::default()).unwrap();
+        assert_eq!(estimate.count, generated.cidr_count.to_string());
+    }
+
+    #[test]
+    fn test_split_estimate_over_limit() {
+        let estimate = estimate_split("10.0.0.0/8", 32, None).unwrap();
+        assert_eq!(estimate.count, "16777216");
+        assert!(!estimate.within_limit);
+
+        let estimate = estimate_split("::/0", 128, None).unwrap();
+        assert_eq!(estimate.count, "2^128");
+        assert!(!estimate.within_limit);
+        assert_eq!(estimate.estimated_bytes, "unbounded");
+    }
+
+    #[test]
+    fn test_split_estimate_insufficient() {
+        assert!(estimate_split("192.168.0.0/22", 27, Some(33)).is_err());
+    }
+
+    #[test]
+    fn test_human_bytes() {
+        assert_eq!(human_bytes(512), "512 B");
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+    }
+}
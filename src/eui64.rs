@@ -0,0 +1,160 @@
+use crate::error::{IpCalcError, Result};
+use crate::ipv6::{Ipv6Style, Ipv6Subnet, format_ipv6};
+use serde::Serialize;
+use std::net::Ipv6Addr;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// The SLAAC address a host with `mac` derives in `prefix` (RFC 4291
+/// appendix A): `fffe` is inserted in the middle of the MAC and the
+/// universal/local bit is flipped.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Eui64Result {
+    pub prefix: String,
+    /// The MAC address, lowercase with colon separators
+    pub mac: String,
+    pub derived_address: String,
+    /// `derived_address` with all eight groups zero-padded
+    pub derived_address_full: String,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Parse a 48-bit MAC written as six two-digit hex groups separated by
+/// colons or dashes (not a mix of both).
+fn parse_mac(mac: &str) -> Result<[u8; 6]> {
+    let invalid = || IpCalcError::InvalidMacAddress(mac.to_string());
+    let separator = if mac.contains(':') { ':' } else { '-' };
+    let groups: Vec<&str> = mac.split(separator).collect();
+    if groups.len() != 6 {
+        return Err(invalid());
+    }
+
+    let mut octets = [0u8; 6];
+    for (octet, group) in octets.iter_mut().zip(groups) {
+        if group.len() != 2 || !group.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        *octet = u8::from_str_radix(group, 16).map_err(|_| invalid())?;
+    }
+    Ok(octets)
+}
+
+/// The modified EUI-64 interface identifier of a MAC address.
+fn interface_id(mac: [u8; 6]) -> u64 {
+    let eui = [
+        mac[0] ^ 0x02,
+        mac[1],
+        mac[2],
+        0xff,
+        0xfe,
+        mac[3],
+        mac[4],
+        mac[5],
+    ];
+    u64::from_be_bytes(eui)
+}
+
+// ---------------------------------------------------------------------------
+// Public entry point
+// ---------------------------------------------------------------------------
+
+/// Derive the EUI-64 address of `mac` in an IPv6 prefix of /64 or shorter.
+///
+/// Bits of a shorter prefix's network up to /64 are kept as given, so
+/// `2001:db8:0:5::/48` derives into `2001:db8:0:5::/64`.
+pub fn derive_eui64(prefix: &str, mac: &str) -> Result<Eui64Result> {
+    let octets = parse_mac(mac.trim())?;
+    let subnet = Ipv6Subnet::from_cidr(prefix)?;
+    if subnet.prefix_length > 64 {
+        return Err(IpCalcError::Eui64PrefixTooLong(subnet.prefix_length));
+    }
+
+    // from_cidr clears host bits, so read the upper 64 bits from the input
+    let given: Ipv6Addr = subnet
+        .input
+        .split_once('/')
+        .and_then(|(addr, _)| addr.parse().ok())
+        .unwrap_or(subnet.network);
+    let upper = u128::from(given) & !u128::from(u64::MAX);
+    let address = Ipv6Addr::from(upper | u128::from(interface_id(octets)));
+
+    Ok(Eui64Result {
+        prefix: format!("{}/{}", subnet.network, subnet.prefix_length),
+        mac: octets
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+        derived_address: address.to_string(),
+        derived_address_full: format_ipv6(&address, Ipv6Style::Full),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive() {
+        let result = derive_eui64("2001:db8::/64", "00:1a:2b:3c:4d:5e").unwrap();
+        assert_eq!(result.derived_address, "2001:db8::21a:2bff:fe3c:4d5e");
+        assert_eq!(
+            result.derived_address_full,
+            "2001:0db8:0000:0000:021a:2bff:fe3c:4d5e"
+        );
+        assert_eq!(result.mac, "00:1a:2b:3c:4d:5e");
+        assert_eq!(result.prefix, "2001:db8::/64");
+    }
+
+    #[test]
+    fn test_separators_and_ul_bit() {
+        // A locally administered MAC has the bit set, and flipping clears it
+        let result = derive_eui64("fe80::/64", "02-00-5E-10-00-01").unwrap();
+        assert_eq!(result.derived_address, "fe80::5eff:fe10:1");
+        assert_eq!(result.mac, "02:00:5e:10:00:01");
+    }
+
+    #[test]
+    fn test_shorter_prefix_keeps_subnet_bits() {
+        let result = derive_eui64("2001:db8:0:5::/48", "00:1a:2b:3c:4d:5e").unwrap();
+        assert_eq!(result.prefix, "2001:db8::/48");
+        assert_eq!(result.derived_address, "2001:db8:0:5:21a:2bff:fe3c:4d5e");
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        for mac in [
+            "00:1a:2b:3c:4d",
+            "00:1a:2b:3c:4d:5e:6f",
+            "00:1a-2b:3c:4d:5e",
+            "0:1a:2b:3c:4d:5e",
+            "00:1a:2b:3c:4d:zz",
+            "001a.2b3c.4d5e",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    derive_eui64("2001:db8::/64", mac),
+                    Err(IpCalcError::InvalidMacAddress(_))
+                ),
+                "{mac}"
+            );
+        }
+        assert!(matches!(
+            derive_eui64("2001:db8::/80", "00:1a:2b:3c:4d:5e"),
+            Err(IpCalcError::Eui64PrefixTooLong(80))
+        ));
+        assert!(derive_eui64("10.0.0.0/8", "00:1a:2b:3c:4d:5e").is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use crate::config::DEFAULTS;
+use crate::covers::{Block, contains, exclude, format_block_as, overlaps};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// What is left of `supernet` once the `excluded` blocks are removed.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv4ExcludeResult {
+    pub supernet: String,
+    pub excluded: Vec<String>,
+    /// Excluded blocks outside the supernet, which leave it unchanged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored: Vec<String>,
+    pub cidr_count: usize,
+    pub cidrs: Vec<Ipv4Subnet>,
+}
+
+/// What is left of `supernet` once the `excluded` blocks are removed.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv6ExcludeResult {
+    pub supernet: String,
+    pub excluded: Vec<String>,
+    /// Excluded blocks outside the supernet, which leave it unchanged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored: Vec<String>,
+    pub cidr_count: usize,
+    pub cidrs: Vec<Ipv6Subnet>,
+}
+
+impl Ipv6ExcludeResult {
+    /// Report every `total_addresses` as an exact decimal.
+    pub fn apply_decimal_totals(&mut self) {
+        for subnet in &mut self.cidrs {
+            subnet.apply_decimal_total();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Subtraction over (network, prefix) pairs
+// ---------------------------------------------------------------------------
+
+/// The remaining blocks of `supernet`, plus the excluded and ignored holes
+/// as CIDR strings (IPv6 ones in `format`'s case). A hole outside the
+/// supernet is ignored; one that overlaps it without being contained in it
+/// is an error.
+fn subtract(
+    supernet: Block,
+    holes: &[Block],
+    bits: u8,
+    format: Ipv6Format,
+) -> Result<(Vec<Block>, Vec<String>, Vec<String>)> {
+    let (inside, outside): (Vec<Block>, Vec<Block>) = holes
+        .iter()
+        .partition(|&&hole| overlaps(supernet, hole, bits));
+    if let Some(&hole) = inside.iter().find(|&&hole| !contains(supernet, hole, bits)) {
+        return Err(IpCalcError::InvalidInput(format!(
+            "{} is not contained in {}",
+            format_block_as(hole, bits, format),
+            format_block_as(supernet, bits, format)
+        )));
+    }
+
+    let mut remaining = Vec::new();
+    exclude(supernet, &inside, bits, &mut remaining);
+    let names = |blocks: Vec<Block>| {
+        blocks
+            .into_iter()
+            .map(|b| format_block_as(b, bits, format))
+            .collect()
+    };
+    Ok((remaining, names(inside), names(outside)))
+}
+
+fn check_limit(cidrs: &[String], max_inputs: usize) -> Result<()> {
+    if cidrs.is_empty() {
+        return Err(IpCalcError::EmptyCidrList);
+    }
+    if cidrs.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: cidrs.len(),
+            limit: max_inputs,
+        });
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+pub fn exclude_ipv4(supernet: &str, excluded: &[String]) -> Result<Ipv4ExcludeResult> {
+    exclude_ipv4_with_limit(supernet, excluded, DEFAULTS.max_summarize_inputs)
+}
+
+/// The fewest CIDRs covering `supernet` minus every `excluded` block, in
+/// ascending order. Excluded blocks may overlap each other; one equal to the
+/// supernet leaves nothing, and one disjoint from it leaves it unchanged.
+pub fn exclude_ipv4_with_limit(
+    supernet: &str,
+    excluded: &[String],
+    max_inputs: usize,
+) -> Result<Ipv4ExcludeResult> {
+    check_limit(excluded, max_inputs)?;
+    let s = Ipv4Subnet::from_cidr(supernet)?;
+    let holes = excluded
+        .iter()
+        .map(|c| {
+            let h = Ipv4Subnet::from_cidr(c.trim())?;
+            Ok((u32::from(h.network) as u128, h.prefix_length))
+        })
+        .collect::<Result<Vec<Block>>>()?;
+
+    let block = (u32::from(s.network) as u128, s.prefix_length);
+    let (remaining, excluded, ignored) = subtract(block, &holes, 32, Ipv6Format::default())?;
+    let cidrs = remaining
+        .into_iter()
+        .map(|(net, prefix)| Ipv4Subnet::new(Ipv4Addr::from(net as u32), prefix))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Ipv4ExcludeResult {
+        supernet: format_block_as(block, 32, Ipv6Format::default()),
+        excluded,
+        ignored,
+        cidr_count: cidrs.len(),
+        cidrs,
+    })
+}
+
+pub fn exclude_ipv6(
+    supernet: &str,
+    excluded: &[String],
+    format: Ipv6Format,
+) -> Result<Ipv6ExcludeResult> {
+    exclude_ipv6_with_limit(supernet, excluded, DEFAULTS.max_summarize_inputs, format)
+}
+
+/// IPv6 counterpart of [`exclude_ipv4_with_limit`], with every block
+/// rendered in `format`.
+pub fn exclude_ipv6_with_limit(
+    supernet: &str,
+    excluded: &[String],
+    max_inputs: usize,
+    format: Ipv6Format,
+) -> Result<Ipv6ExcludeResult> {
+    check_limit(excluded, max_inputs)?;
+    let s = Ipv6Subnet::from_cidr(supernet)?;
+    let holes = excluded
+        .iter()
+        .map(|c| {
+            let h = Ipv6Subnet::from_cidr(c.trim())?;
+            Ok((u128::from(h.network), h.prefix_length))
+        })
+        .collect::<Result<Vec<Block>>>()?;
+
+    let block = (u128::from(s.network), s.prefix_length);
+    let (remaining, excluded, ignored) = subtract(block, &holes, 128, format)?;
+    let cidrs = remaining
+        .into_iter()
+        .map(|(net, prefix)| Ipv6Subnet::with_format(Ipv6Addr::from(net), prefix, format))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Ipv6ExcludeResult {
+        supernet: format_block_as(block, 128, format),
+        excluded,
+        ignored,
+        cidr_count: cidrs.len(),
+        cidrs,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn v4_cidrs(result: &Ipv4ExcludeResult) -> Vec<String> {
+        result
+            .cidrs
+            .iter()
+            .map(|s| format!("{}/{}", s.network, s.prefix_length))
+            .collect()
+    }
+
+    #[test]
+    fn test_exclude_ipv4() {
+        let result = exclude_ipv4("10.0.0.0/8", &strings(&["10.1.0.0/16", "10.2.0.0/16"])).unwrap();
+        assert_eq!(
+            v4_cidrs(&result),
+            [
+                "10.0.0.0/16",
+                "10.3.0.0/16",
+                "10.4.0.0/14",
+                "10.8.0.0/13",
+                "10.16.0.0/12",
+                "10.32.0.0/11",
+                "10.64.0.0/10",
+                "10.128.0.0/9"
+            ]
+        );
+        assert_eq!(result.excluded, ["10.1.0.0/16", "10.2.0.0/16"]);
+        assert!(result.ignored.is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_holes() {
+        let result =
+            exclude_ipv4("10.0.0.0/24", &strings(&["10.0.0.0/25", "10.0.0.0/26"])).unwrap();
+        assert_eq!(v4_cidrs(&result), ["10.0.0.128/25"]);
+    }
+
+    #[test]
+    fn test_equal_hole_leaves_nothing() {
+        let result = exclude_ipv4("10.0.0.0/24", &strings(&["10.0.0.0/24"])).unwrap();
+        assert_eq!(result.cidr_count, 0);
+        assert_eq!(result.excluded, ["10.0.0.0/24"]);
+    }
+
+    #[test]
+    fn test_disjoint_hole_leaves_supernet_unchanged() {
+        let result = exclude_ipv4("10.0.0.0/8", &strings(&["11.0.0.0/16"])).unwrap();
+        assert_eq!(v4_cidrs(&result), ["10.0.0.0/8"]);
+        assert!(result.excluded.is_empty());
+        assert_eq!(result.ignored, ["11.0.0.0/16"]);
+
+        let holes = strings(&["10.0.0.0/25", "192.168.0.0/16"]);
+        let result = exclude_ipv4("10.0.0.0/24", &holes).unwrap();
+        assert_eq!(v4_cidrs(&result), ["10.0.0.128/25"]);
+        assert_eq!(result.ignored, ["192.168.0.0/16"]);
+    }
+
+    #[test]
+    fn test_partially_overlapping_hole_rejected() {
+        // 10.0.0.0/8 overlaps the supernet but is not contained in it
+        let result = exclude_ipv4("10.0.0.0/24", &strings(&["10.0.0.0/8"]));
+        assert!(
+            matches!(result, Err(IpCalcError::InvalidInput(ref msg)) if msg.contains("not contained")),
+            "expected InvalidInput, got {:?}",
+            result
+        );
+        assert!(
+            exclude_ipv6(
+                "2001:db8::/48",
+                &strings(&["2001:db8::/32"]),
+                Ipv6Format::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_exclude_ipv6() {
+        let holes = strings(&["2001:db8:1::/48"]);
+        let result = exclude_ipv6("2001:db8::/46", &holes, Ipv6Format::default()).unwrap();
+        let cidrs: Vec<String> = result.cidrs.iter().map(Ipv6Subnet::cidr).collect();
+        assert_eq!(cidrs, ["2001:db8::/48", "2001:db8:2::/47"]);
+
+        let result = exclude_ipv6("::/0", &strings(&["::/1"]), Ipv6Format::default()).unwrap();
+        assert_eq!(result.cidrs[0].prefix_length, 1);
+    }
+
+    #[test]
+    fn test_exclude_ipv6_upper_case() {
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let holes = strings(&["2001:db8:1::/48"]);
+        let result = exclude_ipv6("2001:db8::/46", &holes, upper).unwrap();
+        assert_eq!(result.supernet, "2001:DB8::/46");
+        assert_eq!(result.excluded, ["2001:DB8:1::/48"]);
+        let cidrs: Vec<String> = result.cidrs.iter().map(Ipv6Subnet::cidr).collect();
+        assert_eq!(cidrs, ["2001:DB8::/48", "2001:DB8:2::/47"]);
+    }
+
+    #[test]
+    fn test_summarize_round_trip() {
+        use crate::summarize::{summarize_ipv4, summarize_ipv6};
+
+        for (supernet, holes) in [
+            ("10.0.0.0/22", &["10.0.1.0/24"][..]),
+            (
+                "10.0.0.0/8",
+                &["10.1.0.0/16", "10.2.0.0/16", "10.200.3.4/32"][..],
+            ),
+            ("0.0.0.0/0", &["192.168.0.0/16", "10.0.0.0/8"][..]),
+        ] {
+            let result = exclude_ipv4(supernet, &strings(holes)).unwrap();
+            // Already minimal: summarizing the remainder leaves it unchanged
+            let remaining = v4_cidrs(&result);
+            let summary = summarize_ipv4(&remaining).unwrap();
+            assert_eq!(summary.output_count, remaining.len(), "{supernet}");
+            // Adding the holes back gives the supernet
+            let mut all = remaining;
+            all.extend(strings(holes));
+            let summary = summarize_ipv4(&all).unwrap();
+            assert_eq!(summary.output_count, 1);
+            assert_eq!(
+                format!(
+                    "{}/{}",
+                    summary.cidrs[0].network, summary.cidrs[0].prefix_length
+                ),
+                supernet
+            );
+        }
+
+        let holes = strings(&["2001:db8:1::/48", "2001:db8:3:4::/64"]);
+        let result = exclude_ipv6("2001:db8::/46", &holes, Ipv6Format::default()).unwrap();
+        let mut all: Vec<String> = result
+            .cidrs
+            .iter()
+            .map(|s| format!("{}/{}", s.network, s.prefix_length))
+            .collect();
+        all.extend(holes);
+        let summary = summarize_ipv6(&all).unwrap();
+        assert_eq!(summary.output_count, 1);
+        assert_eq!(summary.cidrs[0].prefix_length, 46);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(matches!(
+            exclude_ipv4("10.0.0.0/8", &[]),
+            Err(IpCalcError::EmptyCidrList)
+        ));
+        assert!(exclude_ipv4("10.0.0.0/8", &strings(&["2001:db8::/32"])).is_err());
+        assert!(matches!(
+            exclude_ipv4_with_limit("10.0.0.0/8", &strings(&["10.0.0.0/9", "10.128.0.0/9"]), 1),
+            Err(IpCalcError::SummarizeInputLimitExceeded { .. })
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::{IpCalcError, Result};
+use crate::validation::validate_cidr;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Line counts from extracting CIDRs out of free-form text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ExtractionStats {
+    pub extracted_lines: usize,
+    pub skipped_lines: usize,
+}
+
+/// CIDRs pulled out of free-form text, one per matching line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extraction {
+    pub cidrs: Vec<String>,
+    pub stats: ExtractionStats,
+}
+
+/// Characters stripped from either end of a token before it is tested as a CIDR.
+const TOKEN_PUNCTUATION: &[char] = &[',', ';', '(', ')', '[', ']', '{', '}', '<', '>', '"', '\''];
+
+/// Return the first whitespace-separated token on `line` that is a valid CIDR.
+fn first_cidr_token(line: &str) -> Option<&str> {
+    line.split_whitespace()
+        .map(|token| token.trim_matches(TOKEN_PUNCTUATION))
+        .find(|token| token.contains('/') && validate_cidr(token).is_ok())
+}
+
+/// Return the first match of `re` on `line` that is a valid CIDR.
+///
+/// Uses the first capture group when the pattern has one, otherwise the
+/// whole match.
+fn first_cidr_match<'a>(re: &Regex, line: &'a str) -> Option<&'a str> {
+    re.captures_iter(line)
+        .filter_map(|caps| caps.get(1).or_else(|| caps.get(0)))
+        .map(|m| m.as_str().trim())
+        .find(|candidate| validate_cidr(candidate).is_ok())
+}
+
+/// Extract CIDRs from routing-table style text (e.g. `show ip route`, BGP
+/// table dumps).
+///
+/// Each line contributes at most one CIDR: the first token that parses as an
+/// IPv4 or IPv6 CIDR, or the first valid match of `pattern` when given. Lines
+/// with no match (headers, blank lines, next-hop continuation lines) are
+/// counted as skipped.
+pub fn extract_cidrs(text: &str, pattern: Option<&str>) -> Result<Extraction> {
+    let re = pattern
+        .map(|p| {
+            Regex::new(p)
+                .map_err(|e| IpCalcError::InvalidInput(format!("invalid extract regex: {}", e)))
+        })
+        .transpose()?;
+
+    let mut extraction = Extraction::default();
+    for line in text.lines() {
+        let found = match &re {
+            Some(re) => first_cidr_match(re, line),
+            None => first_cidr_token(line),
+        };
+        match found {
+            Some(cidr) => {
+                extraction.cidrs.push(cidr.to_string());
+                extraction.stats.extracted_lines += 1;
+            }
+            None => extraction.stats.skipped_lines += 1,
+        }
+    }
+
+    Ok(extraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IOS: &str = include_str!("../tests/fixtures/routes/ios.txt");
+    const JUNOS: &str = include_str!("../tests/fixtures/routes/junos.txt");
+    const BIRD: &str = include_str!("../tests/fixtures/routes/bird.txt");
+
+    #[test]
+    fn test_extract_ios() {
+        let result = extract_cidrs(IOS, None).unwrap();
+        assert_eq!(
+            result.cidrs,
+            [
+                "0.0.0.0/0",
+                "10.1.0.0/24",
+                "10.1.1.0/24",
+                "192.0.2.0/29",
+                "192.0.2.2/32"
+            ]
+        );
+        assert_eq!(result.stats.extracted_lines, 5);
+        assert_eq!(result.stats.skipped_lines, 6);
+    }
+
+    #[test]
+    fn test_extract_junos_skips_next_hop_lines() {
+        let result = extract_cidrs(JUNOS, None).unwrap();
+        assert_eq!(result.cidrs, ["10.2.0.0/24", "10.2.1.0/24"]);
+        assert_eq!(result.stats.extracted_lines, 2);
+        assert_eq!(result.stats.skipped_lines, 7);
+    }
+
+    #[test]
+    fn test_extract_bird_ipv6() {
+        let result = extract_cidrs(BIRD, None).unwrap();
+        assert_eq!(result.cidrs, ["2001:db8:10::/48", "2001:db8:11::/48"]);
+        assert_eq!(result.stats.skipped_lines, 4);
+    }
+
+    #[test]
+    fn test_extract_custom_regex_capture_group() {
+        // Only take BGP routes from the IOS table
+        let result = extract_cidrs(IOS, Some(r"^B\s+(\S+)")).unwrap();
+        assert_eq!(result.cidrs, ["10.1.0.0/24", "10.1.1.0/24"]);
+        assert_eq!(result.stats.extracted_lines, 2);
+    }
+
+    #[test]
+    fn test_extract_invalid_regex() {
+        let err = extract_cidrs(IOS, Some("(unclosed")).unwrap_err();
+        assert!(matches!(err, IpCalcError::InvalidInput(_)));
+    }
+}
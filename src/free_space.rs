@@ -0,0 +1,211 @@
+use crate::config::DEFAULTS;
+use crate::covers::{Block, contains, exclude, format_block_as};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// The CIDRs of a parent block left once its allocated children are removed.
+///
+/// Children that are not wholly inside the parent are clamped to it and
+/// listed in `clamped`: one covering the parent claims all of it, and one
+/// outside it claims nothing.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FreeSpaceResult {
+    pub parent: String,
+    pub allocated_count: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clamped: Vec<String>,
+    pub free_count: usize,
+    pub free_cidrs: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Build the result, with IPv6 blocks rendered in `format`'s case.
+fn free_space_result(
+    parent: Block,
+    children: &[Block],
+    bits: u8,
+    format: Ipv6Format,
+) -> FreeSpaceResult {
+    let mut clamped = Vec::new();
+    let mut holes = Vec::new();
+    for &child in children {
+        if contains(parent, child, bits) {
+            holes.push(child);
+        } else {
+            clamped.push(format_block_as(child, bits, format));
+            if contains(child, parent, bits) {
+                holes.push(parent);
+            }
+        }
+    }
+
+    // exclude copes with overlapping and duplicate holes
+    let mut free = Vec::new();
+    exclude(parent, &holes, bits, &mut free);
+
+    FreeSpaceResult {
+        parent: format_block_as(parent, bits, format),
+        allocated_count: children.len(),
+        clamped,
+        free_count: free.len(),
+        free_cidrs: free
+            .into_iter()
+            .map(|b| format_block_as(b, bits, format))
+            .collect(),
+    }
+}
+
+fn check_limit(allocated: &[String]) -> Result<()> {
+    if allocated.len() > DEFAULTS.max_summarize_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: allocated.len(),
+            limit: DEFAULTS.max_summarize_inputs,
+        });
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// The free space of `parent` once every `allocated` block is removed, as the
+/// fewest CIDRs in ascending order. Allocations may overlap each other, and
+/// any not wholly inside the parent are clamped to it rather than rejected.
+pub fn free_space_v4(parent: &str, allocated: &[String]) -> Result<FreeSpaceResult> {
+    check_limit(allocated)?;
+    let p = Ipv4Subnet::from_cidr(parent)?;
+    let children = allocated
+        .iter()
+        .map(|c| {
+            let s = Ipv4Subnet::from_cidr(c.trim())?;
+            Ok((u32::from(s.network) as u128, s.prefix_length))
+        })
+        .collect::<Result<Vec<Block>>>()?;
+    Ok(free_space_result(
+        (u32::from(p.network) as u128, p.prefix_length),
+        &children,
+        32,
+        Ipv6Format::default(),
+    ))
+}
+
+/// IPv6 counterpart of [`free_space_v4`], with every block rendered in
+/// `format`'s case.
+pub fn free_space_v6(
+    parent: &str,
+    allocated: &[String],
+    format: Ipv6Format,
+) -> Result<FreeSpaceResult> {
+    check_limit(allocated)?;
+    let p = Ipv6Subnet::from_cidr(parent)?;
+    let children = allocated
+        .iter()
+        .map(|c| {
+            let s = Ipv6Subnet::from_cidr(c.trim())?;
+            Ok((u128::from(s.network), s.prefix_length))
+        })
+        .collect::<Result<Vec<Block>>>()?;
+    Ok(free_space_result(
+        (u128::from(p.network), p.prefix_length),
+        &children,
+        128,
+        format,
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_free_space_ipv4() {
+        let result =
+            free_space_v4("10.0.0.0/16", &strings(&["10.0.1.0/24", "10.0.5.0/24"])).unwrap();
+        assert_eq!(
+            result.free_cidrs,
+            [
+                "10.0.0.0/24",
+                "10.0.2.0/23",
+                "10.0.4.0/24",
+                "10.0.6.0/23",
+                "10.0.8.0/21",
+                "10.0.16.0/20",
+                "10.0.32.0/19",
+                "10.0.64.0/18",
+                "10.0.128.0/17",
+            ]
+        );
+        assert_eq!(result.allocated_count, 2);
+        assert_eq!(result.free_count, 9);
+        assert!(result.clamped.is_empty());
+    }
+
+    #[test]
+    fn test_fully_allocated_and_overlapping() {
+        let result =
+            free_space_v4("10.0.0.0/24", &strings(&["10.0.0.0/25", "10.0.0.128/25"])).unwrap();
+        assert!(result.free_cidrs.is_empty());
+        assert_eq!(result.free_count, 0);
+
+        // Overlapping and duplicate children claim their union once
+        let result = free_space_v4(
+            "10.0.0.0/24",
+            &strings(&["10.0.0.0/25", "10.0.0.64/26", "10.0.0.0/25"]),
+        )
+        .unwrap();
+        assert_eq!(result.free_cidrs, ["10.0.0.128/25"]);
+        assert_eq!(result.allocated_count, 3);
+    }
+
+    #[test]
+    fn test_children_outside_parent_are_clamped() {
+        // A child covering the parent claims all of it
+        let result = free_space_v4("10.0.0.0/24", &strings(&["10.0.0.0/16"])).unwrap();
+        assert!(result.free_cidrs.is_empty());
+        assert_eq!(result.clamped, ["10.0.0.0/16"]);
+
+        // A child outside the parent claims nothing
+        let result =
+            free_space_v4("10.0.0.0/24", &strings(&["10.0.1.0/24", "10.0.0.0/25"])).unwrap();
+        assert_eq!(result.free_cidrs, ["10.0.0.128/25"]);
+        assert_eq!(result.clamped, ["10.0.1.0/24"]);
+    }
+
+    #[test]
+    fn test_free_space_ipv6_and_errors() {
+        let result = free_space_v6(
+            "2001:db8::/46",
+            &strings(&["2001:db8:1::/48"]),
+            Ipv6Format::default(),
+        )
+        .unwrap();
+        assert_eq!(result.free_cidrs, ["2001:db8::/48", "2001:db8:2::/47"]);
+
+        assert!(free_space_v4("10.0.0.0/16", &strings(&["2001:db8::/32"])).is_err());
+        assert!(free_space_v4("10.0.0.0/33", &[]).is_err());
+        assert_eq!(
+            free_space_v4("10.0.0.0/16", &[]).unwrap().free_cidrs,
+            ["10.0.0.0/16"]
+        );
+    }
+}
@@ -1,6 +1,7 @@
+use crate::config::DEFAULTS;
 use crate::error::{IpCalcError, Result};
 use crate::ipv4::Ipv4Subnet;
-use crate::ipv6::Ipv6Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
 use serde::Serialize;
 use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
@@ -11,6 +12,7 @@ use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv4FromRangeResult {
     pub start_address: String,
     pub end_address: String,
@@ -20,6 +22,7 @@ pub struct Ipv4FromRangeResult {
 
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv6FromRangeResult {
     pub start_address: String,
     pub end_address: String,
@@ -27,8 +30,6 @@ pub struct Ipv6FromRangeResult {
     pub cidrs: Vec<Ipv6Subnet>,
 }
 
-pub const DEFAULT_MAX_GENERATED_CIDRS: usize = 1_000_000;
-
 // ---------------------------------------------------------------------------
 // Core algorithms
 // ---------------------------------------------------------------------------
@@ -73,7 +74,8 @@ fn range_to_cidrs_v6(start: u128, end: u128, limit: usize) -> Vec<(u128, u8)> {
         } else {
             current.trailing_zeros()
         };
-        let range_size = end - current + 1;
+        // Wraps to 0 for the whole address space
+        let range_size = (end - current).wrapping_add(1);
         let range_bits = if range_size == 0 {
             128
         } else {
@@ -82,8 +84,9 @@ fn range_to_cidrs_v6(start: u128, end: u128, limit: usize) -> Vec<(u128, u8)> {
         let bits = max_bits.min(range_bits);
         let prefix = 128 - bits as u8;
         result.push((current, prefix));
-        let block_size: u128 = 1u128 << bits;
-        let next = current.checked_add(block_size);
+        let next = 1u128
+            .checked_shl(bits)
+            .and_then(|block_size| current.checked_add(block_size));
         match next {
             Some(n) => current = n,
             None => break,
@@ -97,7 +100,7 @@ fn range_to_cidrs_v6(start: u128, end: u128, limit: usize) -> Vec<(u128, u8)> {
 // ---------------------------------------------------------------------------
 
 pub fn from_range_ipv4(start: &str, end: &str) -> Result<Ipv4FromRangeResult> {
-    from_range_ipv4_with_limit(start, end, DEFAULT_MAX_GENERATED_CIDRS)
+    from_range_ipv4_with_limit(start, end, DEFAULTS.max_generated_cidrs)
 }
 
 pub fn from_range_ipv4_with_limit(
@@ -142,14 +145,17 @@ pub fn from_range_ipv4_with_limit(
     })
 }
 
-pub fn from_range_ipv6(start: &str, end: &str) -> Result<Ipv6FromRangeResult> {
-    from_range_ipv6_with_limit(start, end, DEFAULT_MAX_GENERATED_CIDRS)
+pub fn from_range_ipv6(start: &str, end: &str, format: Ipv6Format) -> Result<Ipv6FromRangeResult> {
+    from_range_ipv6_with_limit(start, end, DEFAULTS.max_generated_cidrs, format)
 }
 
+/// The fewest CIDRs covering `start..=end`, with the range bounds and every
+/// block rendered in `format`.
 pub fn from_range_ipv6_with_limit(
     start: &str,
     end: &str,
     max_cidrs: usize,
+    format: Ipv6Format,
 ) -> Result<Ipv6FromRangeResult> {
     let start_addr = Ipv6Addr::from_str(start)
         .map_err(|_| IpCalcError::InvalidIpv6Address(start.to_string()))?;
@@ -177,17 +183,50 @@ pub fn from_range_ipv6_with_limit(
     let mut cidrs = Vec::with_capacity(pairs.len());
     for (network, prefix) in &pairs {
         let addr = Ipv6Addr::from(*network);
-        cidrs.push(Ipv6Subnet::new(addr, *prefix)?);
+        cidrs.push(Ipv6Subnet::with_format(addr, *prefix, format)?);
     }
 
     Ok(Ipv6FromRangeResult {
-        start_address: start_addr.to_string(),
-        end_address: end_addr.to_string(),
+        start_address: format.compressed(&start_addr),
+        end_address: format.compressed(&end_addr),
         cidr_count: cidrs.len(),
         cidrs,
     })
 }
 
+/// Count the CIDR blocks covering `start..=end` without building subnet results.
+/// Auto-detects IPv4 vs IPv6 from the start address.
+pub fn count_range_cidrs(start: &str, end: &str) -> Result<usize> {
+    let (start_u128, end_u128, is_v6) = if start.contains(':') {
+        let s = Ipv6Addr::from_str(start)
+            .map_err(|_| IpCalcError::InvalidIpv6Address(start.to_string()))?;
+        let e = Ipv6Addr::from_str(end)
+            .map_err(|_| IpCalcError::InvalidIpv6Address(end.to_string()))?;
+        (u128::from(s), u128::from(e), true)
+    } else {
+        let s = Ipv4Addr::from_str(start)
+            .map_err(|_| IpCalcError::InvalidIpv4Address(start.to_string()))?;
+        let e = Ipv4Addr::from_str(end)
+            .map_err(|_| IpCalcError::InvalidIpv4Address(end.to_string()))?;
+        (u32::from(s) as u128, u32::from(e) as u128, false)
+    };
+
+    if start_u128 > end_u128 {
+        return Err(IpCalcError::InvalidRange(
+            start.to_string(),
+            end.to_string(),
+        ));
+    }
+
+    // A range decomposes into at most ~2 blocks per address bit, so the
+    // block lists here stay tiny regardless of the range size.
+    Ok(if is_v6 {
+        range_to_cidrs_v6(start_u128, end_u128, usize::MAX).len()
+    } else {
+        range_to_cidrs_v4(start_u128 as u32, end_u128 as u32, usize::MAX).len()
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Unit tests
 // ---------------------------------------------------------------------------
@@ -254,29 +293,36 @@ mod tests {
 
     #[test]
     fn test_single_address_v6() {
-        let result = from_range_ipv6("2001:db8::1", "2001:db8::1").unwrap();
+        let result = from_range_ipv6("2001:db8::1", "2001:db8::1", Ipv6Format::default()).unwrap();
         assert_eq!(result.cidr_count, 1);
         assert_eq!(result.cidrs[0].prefix_length, 128);
     }
 
     #[test]
     fn test_range_v6() {
-        let result = from_range_ipv6("2001:db8::1", "2001:db8::ff").unwrap();
+        let result = from_range_ipv6("2001:db8::1", "2001:db8::ff", Ipv6Format::default()).unwrap();
         assert!(result.cidr_count > 1);
         assert_eq!(result.start_address, "2001:db8::1");
         assert_eq!(result.end_address, "2001:db8::ff");
+
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let result = from_range_ipv6("2001:db8::1", "2001:db8::ff", upper).unwrap();
+        assert_eq!(result.start_address, "2001:DB8::1");
+        assert_eq!(result.end_address, "2001:DB8::FF");
+        assert_eq!(result.cidrs[0].cidr(), "2001:DB8::1/128");
     }
 
     #[test]
     fn test_aligned_v6() {
-        let result = from_range_ipv6("2001:db8::", "2001:db8::ffff").unwrap();
+        let result =
+            from_range_ipv6("2001:db8::", "2001:db8::ffff", Ipv6Format::default()).unwrap();
         assert_eq!(result.cidr_count, 1);
         assert_eq!(result.cidrs[0].prefix_length, 112);
     }
 
     #[test]
     fn test_start_greater_than_end_v6() {
-        let result = from_range_ipv6("2001:db8::ff", "2001:db8::1");
+        let result = from_range_ipv6("2001:db8::ff", "2001:db8::1", Ipv6Format::default());
         assert!(
             matches!(result, Err(IpCalcError::InvalidRange(_, _))),
             "expected InvalidRange, got {:?}",
@@ -0,0 +1,308 @@
+use crate::addresses::format_full_ipv6_plus;
+use crate::config::DEFAULTS;
+use crate::covers::{Block, contains, exclude, format_block_as};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// A run of consecutive free addresses, possibly spanning several CIDRs.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FreeRange {
+    pub first: String,
+    pub last: String,
+    pub address_count: String,
+}
+
+/// The unallocated space of a supernet given its used child subnets.
+///
+/// Address counts are decimal strings since an IPv6 total can exceed u64.
+/// `largest_free_block` is the longest run of adjacent free CIDRs (the lowest
+/// one on a tie) and is `None` when the supernet is fully used.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GapsResult {
+    pub supernet: String,
+    pub used: Vec<String>,
+    pub free_addresses: String,
+    pub largest_free_block: Option<FreeRange>,
+    pub free_cidr_count: usize,
+    pub free_cidrs: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Gap analysis over (network, prefix) pairs
+// ---------------------------------------------------------------------------
+
+/// Addresses in a block of `prefix`, or `None` for the whole IPv6 space.
+fn block_size(prefix: u8, bits: u8) -> Option<u128> {
+    1u128.checked_shl(u32::from(bits - prefix))
+}
+
+fn format_count(count: Option<u128>) -> String {
+    count.map_or_else(|| format_full_ipv6_plus(0), |n| n.to_string())
+}
+
+fn format_addr(addr: u128, bits: u8, format: Ipv6Format) -> String {
+    if bits == 32 {
+        Ipv4Addr::from(addr as u32).to_string()
+    } else {
+        format.compressed(&Ipv6Addr::from(addr))
+    }
+}
+
+/// Merge ascending, disjoint blocks into inclusive `(first, last)` ranges.
+fn free_ranges(free: &[Block], bits: u8) -> Vec<(u128, u128)> {
+    let mut ranges: Vec<(u128, u128)> = Vec::new();
+    for &(network, prefix) in free {
+        let last = network + block_size(prefix, bits).map_or(u128::MAX, |n| n - 1);
+        match ranges.last_mut() {
+            Some((_, end)) if end.checked_add(1) == Some(network) => *end = last,
+            _ => ranges.push((network, last)),
+        }
+    }
+    ranges
+}
+
+/// Build the result, with IPv6 addresses rendered in `format`'s case.
+fn gaps_result(
+    supernet: Block,
+    used: &[Block],
+    bits: u8,
+    format: Ipv6Format,
+) -> Result<GapsResult> {
+    if let Some(&block) = used.iter().find(|&&b| !contains(supernet, b, bits)) {
+        return Err(IpCalcError::InvalidInput(format!(
+            "allocation {} is not inside {}",
+            format_block_as(block, bits, format),
+            format_block_as(supernet, bits, format)
+        )));
+    }
+
+    let mut free = Vec::new();
+    exclude(supernet, used, bits, &mut free);
+
+    // The free blocks are disjoint, so only a free ::/0 reaches 2^128
+    let free_addresses = free
+        .iter()
+        .map(|&(_, prefix)| block_size(prefix, bits))
+        .try_fold(0u128, |sum, size| sum.checked_add(size?));
+    let largest_free_block = free_ranges(&free, bits)
+        .into_iter()
+        .rev()
+        .max_by_key(|&(first, last)| last - first)
+        .map(|(first, last)| FreeRange {
+            first: format_addr(first, bits, format),
+            last: format_addr(last, bits, format),
+            address_count: format_count((last - first).checked_add(1)),
+        });
+
+    Ok(GapsResult {
+        supernet: format_block_as(supernet, bits, format),
+        used: used
+            .iter()
+            .map(|&b| format_block_as(b, bits, format))
+            .collect(),
+        free_addresses: format_count(free_addresses),
+        largest_free_block,
+        free_cidr_count: free.len(),
+        free_cidrs: free
+            .into_iter()
+            .map(|b| format_block_as(b, bits, format))
+            .collect(),
+    })
+}
+
+fn check_limit(used: &[String], max_inputs: usize) -> Result<()> {
+    if used.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: used.len(),
+            limit: max_inputs,
+        });
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+pub fn find_gaps_v4(supernet: &str, used: &[String]) -> Result<GapsResult> {
+    find_gaps_v4_with_limit(supernet, used, DEFAULTS.max_summarize_inputs)
+}
+
+/// The free space of `supernet` once every `used` block is allocated, as the
+/// fewest CIDRs in ascending order. Used blocks may overlap each other but
+/// must each lie inside the supernet.
+pub fn find_gaps_v4_with_limit(
+    supernet: &str,
+    used: &[String],
+    max_inputs: usize,
+) -> Result<GapsResult> {
+    check_limit(used, max_inputs)?;
+    let s = Ipv4Subnet::from_cidr(supernet)?;
+    let holes = used
+        .iter()
+        .map(|c| {
+            let h = Ipv4Subnet::from_cidr(c.trim())?;
+            Ok((u32::from(h.network) as u128, h.prefix_length))
+        })
+        .collect::<Result<Vec<Block>>>()?;
+    gaps_result(
+        (u32::from(s.network) as u128, s.prefix_length),
+        &holes,
+        32,
+        Ipv6Format::default(),
+    )
+}
+
+pub fn find_gaps_v6(supernet: &str, used: &[String], format: Ipv6Format) -> Result<GapsResult> {
+    find_gaps_v6_with_limit(supernet, used, DEFAULTS.max_summarize_inputs, format)
+}
+
+/// IPv6 counterpart of [`find_gaps_v4_with_limit`], with every address
+/// rendered in `format`'s case.
+pub fn find_gaps_v6_with_limit(
+    supernet: &str,
+    used: &[String],
+    max_inputs: usize,
+    format: Ipv6Format,
+) -> Result<GapsResult> {
+    check_limit(used, max_inputs)?;
+    let s = Ipv6Subnet::from_cidr(supernet)?;
+    let holes = used
+        .iter()
+        .map(|c| {
+            let h = Ipv6Subnet::from_cidr(c.trim())?;
+            Ok((u128::from(h.network), h.prefix_length))
+        })
+        .collect::<Result<Vec<Block>>>()?;
+    gaps_result(
+        (u128::from(s.network), s.prefix_length),
+        &holes,
+        128,
+        format,
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_gaps_ipv4() {
+        let result =
+            find_gaps_v4("10.0.0.0/16", &strings(&["10.0.0.0/24", "10.0.4.0/22"])).unwrap();
+        assert_eq!(
+            result.free_cidrs,
+            [
+                "10.0.1.0/24",
+                "10.0.2.0/23",
+                "10.0.8.0/21",
+                "10.0.16.0/20",
+                "10.0.32.0/19",
+                "10.0.64.0/18",
+                "10.0.128.0/17",
+            ]
+        );
+        assert_eq!(result.free_cidr_count, 7);
+        assert_eq!(result.free_addresses, (65536 - 256 - 1024).to_string());
+
+        // 10.0.8.0 onwards is one run across five CIDRs
+        let largest = result.largest_free_block.unwrap();
+        assert_eq!(largest.first, "10.0.8.0");
+        assert_eq!(largest.last, "10.0.255.255");
+        assert_eq!(largest.address_count, "63488");
+    }
+
+    #[test]
+    fn test_fully_used_and_empty() {
+        let result =
+            find_gaps_v4("10.0.0.0/24", &strings(&["10.0.0.0/25", "10.0.0.128/25"])).unwrap();
+        assert!(result.free_cidrs.is_empty());
+        assert_eq!(result.free_addresses, "0");
+        assert!(result.largest_free_block.is_none());
+
+        let result = find_gaps_v4("10.0.0.0/24", &[]).unwrap();
+        assert_eq!(result.free_cidrs, ["10.0.0.0/24"]);
+        assert_eq!(result.free_addresses, "256");
+    }
+
+    #[test]
+    fn test_largest_tie_keeps_lowest() {
+        let result =
+            find_gaps_v4("10.0.0.0/24", &strings(&["10.0.0.64/26", "10.0.0.192/26"])).unwrap();
+        let largest = result.largest_free_block.unwrap();
+        assert_eq!(largest.first, "10.0.0.0");
+        assert_eq!(largest.address_count, "64");
+    }
+
+    #[test]
+    fn test_gaps_ipv6() {
+        let result = find_gaps_v6(
+            "2001:db8::/46",
+            &strings(&["2001:db8:1::/48"]),
+            Ipv6Format::default(),
+        )
+        .unwrap();
+        assert_eq!(result.free_cidrs, ["2001:db8::/48", "2001:db8:2::/47"]);
+        assert_eq!(result.free_addresses, (3u128 << 80).to_string());
+
+        let result = find_gaps_v6("::/0", &[], Ipv6Format::default()).unwrap();
+        assert_eq!(
+            result.free_addresses,
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(
+            result.largest_free_block.unwrap().address_count,
+            result.free_addresses
+        );
+    }
+
+    #[test]
+    fn test_gaps_ipv6_upper_case() {
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let result = find_gaps_v6("2001:db8::/46", &strings(&["2001:db8:1::/48"]), upper).unwrap();
+        assert_eq!(result.supernet, "2001:DB8::/46");
+        assert_eq!(result.used, ["2001:DB8:1::/48"]);
+        assert_eq!(result.free_cidrs, ["2001:DB8::/48", "2001:DB8:2::/47"]);
+        let largest = result.largest_free_block.unwrap();
+        assert_eq!(largest.first, "2001:DB8:2::");
+        assert_eq!(largest.last, "2001:DB8:3:FFFF:FFFF:FFFF:FFFF:FFFF");
+
+        let err = find_gaps_v6("2001:db8::/46", &strings(&["2001:db9::/48"]), upper).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: allocation 2001:DB9::/48 is not inside 2001:DB8::/46"
+        );
+    }
+
+    #[test]
+    fn test_allocation_outside_rejected() {
+        let err = find_gaps_v4("10.0.0.0/16", &strings(&["10.1.0.0/24"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid input: allocation 10.1.0.0/24 is not inside 10.0.0.0/16"
+        );
+        // A block covering the supernet is not inside it either
+        assert!(find_gaps_v4("10.0.0.0/16", &strings(&["10.0.0.0/8"])).is_err());
+        assert!(find_gaps_v4("10.0.0.0/16", &strings(&["2001:db8::/32"])).is_err());
+        assert!(find_gaps_v4_with_limit("10.0.0.0/16", &strings(&["10.0.0.0/24"; 3]), 2).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::batch::{AggregateSummary, parse_batch_line};
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::ipv6::Ipv6Format;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// The summarized CIDRs that share one value of the grouping tag.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TagGroup {
+    pub value: String,
+    pub input_count: usize,
+    pub summary: AggregateSummary,
+}
+
+/// Tagged CIDRs summarized separately for each value of one tag key.
+///
+/// Groups are ordered by tag value. CIDRs without the tag are listed in
+/// `untagged` as given, unsummarized.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GroupedSummaryResult {
+    pub tag: String,
+    pub input_count: usize,
+    pub groups: Vec<TagGroup>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub untagged: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Group batch-style lines (`10.0.0.0/24 # role=web`) by the value of `tag`
+/// and summarize each group into its minimal covering set per family.
+pub fn summarize_grouped(lines: &[String], tag: &str) -> Result<GroupedSummaryResult> {
+    summarize_grouped_with_limit(lines, tag, DEFAULTS.max_summarize_inputs)
+}
+
+pub fn summarize_grouped_with_limit(
+    lines: &[String],
+    tag: &str,
+    max_inputs: usize,
+) -> Result<GroupedSummaryResult> {
+    if tag.trim().is_empty() {
+        return Err(IpCalcError::InvalidInput(
+            "a tag key to group by is required".to_string(),
+        ));
+    }
+    if lines.is_empty() {
+        return Err(IpCalcError::EmptyCidrList);
+    }
+    if lines.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: lines.len(),
+            limit: max_inputs,
+        });
+    }
+
+    let tag = tag.trim();
+    // Tag value -> (IPv4, IPv6) CIDRs
+    let mut grouped: BTreeMap<String, (Vec<String>, Vec<String>)> = BTreeMap::new();
+    let mut untagged = Vec::new();
+    for line in lines.iter().map(|l| parse_batch_line(l)) {
+        let Some(value) = line.tags.get(tag) else {
+            untagged.push(line.cidr);
+            continue;
+        };
+        let (v4, v6) = grouped.entry(value.clone()).or_default();
+        if line.cidr.contains(':') {
+            v6.push(line.cidr);
+        } else {
+            v4.push(line.cidr);
+        }
+    }
+
+    let groups = grouped
+        .into_iter()
+        .map(|(value, (v4, v6))| {
+            Ok(TagGroup {
+                value,
+                input_count: v4.len() + v6.len(),
+                summary: AggregateSummary::summarize(&v4, &v6, Ipv6Format::default())?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(GroupedSummaryResult {
+        tag: tag.to_string(),
+        input_count: lines.len(),
+        groups,
+        untagged,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_two_groups_summarized_independently() {
+        let result = summarize_grouped(
+            &strings(&[
+                "10.0.0.0/24 # role=web",
+                "10.0.2.0/24 # role=db env=prod",
+                "10.0.1.0/24 # role=web",
+                "10.0.3.0/24 # role=db",
+                "2001:db8::/48 # role=web",
+            ]),
+            "role",
+        )
+        .unwrap();
+        assert_eq!(result.input_count, 5);
+        assert_eq!(result.groups.len(), 2);
+
+        // 10.0.2.0/24 and 10.0.3.0/24 merge only within the db group
+        let db = &result.groups[0];
+        assert_eq!(db.value, "db");
+        assert_eq!(db.input_count, 2);
+        assert_eq!(db.summary.ipv4, ["10.0.2.0/23"]);
+        assert!(db.summary.ipv6.is_empty());
+
+        let web = &result.groups[1];
+        assert_eq!(web.value, "web");
+        assert_eq!(web.summary.ipv4, ["10.0.0.0/23"]);
+        assert_eq!(web.summary.ipv6, ["2001:db8::/48"]);
+        assert!(result.untagged.is_empty());
+    }
+
+    #[test]
+    fn test_untagged_entries_are_listed() {
+        let result = summarize_grouped(
+            &strings(&[
+                "10.0.0.0/24 # role=web",
+                "10.0.1.0/24 # env=prod",
+                "10.0.2.0/24",
+            ]),
+            "role",
+        )
+        .unwrap();
+        assert_eq!(result.groups.len(), 1);
+        assert_eq!(result.untagged, ["10.0.1.0/24", "10.0.2.0/24"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(summarize_grouped(&[], "role").is_err());
+        assert!(summarize_grouped(&strings(&["10.0.0.0/24 # role=web"]), " ").is_err());
+        assert!(summarize_grouped(&strings(&["bogus # role=web"]), "role").is_err());
+        assert!(matches!(
+            summarize_grouped_with_limit(&strings(&["10.0.0.0/24", "10.0.1.0/24"]), "role", 1),
+            Err(IpCalcError::SummarizeInputLimitExceeded { .. })
+        ));
+    }
+}
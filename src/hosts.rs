@@ -0,0 +1,141 @@
+use crate::config::DEFAULTS;
+use crate::error::Result;
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::Ipv6Subnet;
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// The usable host addresses of a subnet, in order, up to a limit.
+///
+/// `truncated` is true when the subnet has more hosts than were listed.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HostsResult {
+    pub cidr: String,
+    pub count: usize,
+    pub truncated: bool,
+    pub hosts: Vec<String>,
+}
+
+/// `limit`, capped at `DEFAULTS.max_generated_subnets` so a large prefix is
+/// never enumerated by accident.
+fn capped(limit: usize) -> usize {
+    limit.min(DEFAULTS.max_generated_subnets as usize)
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// The usable hosts of an IPv4 subnet, yielded one at a time and stopping
+/// after `limit` (itself capped at `DEFAULTS.max_generated_subnets`).
+///
+/// Network and broadcast addresses are skipped, except in a /31 or /32
+/// where every address is a host.
+pub fn host_iter_v4(cidr: &str, limit: usize) -> Result<impl Iterator<Item = Ipv4Addr>> {
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    let (first, last) = (u32::from(subnet.first_host), u32::from(subnet.last_host));
+    Ok((first..=last).take(capped(limit)).map(Ipv4Addr::from))
+}
+
+/// IPv6 counterpart of [`host_iter_v4`]. The Subnet-Router anycast address
+/// is skipped outside a /127 or /128.
+pub fn host_iter_v6(cidr: &str, limit: usize) -> Result<impl Iterator<Item = Ipv6Addr>> {
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+    let first = u128::from(subnet.network) + u128::from(subnet.prefix_length < 127);
+    let last = u128::from(subnet.last);
+    Ok((first..=last).take(capped(limit)).map(Ipv6Addr::from))
+}
+
+/// The hosts of [`host_iter_v4`], flagging whether any were left out.
+pub fn list_hosts_v4(cidr: &str, limit: usize) -> Result<HostsResult> {
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    let hosts: Vec<String> = host_iter_v4(cidr, limit)?.map(|a| a.to_string()).collect();
+    Ok(HostsResult {
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        count: hosts.len(),
+        truncated: subnet.nth_host(hosts.len() as u64).is_some(),
+        hosts,
+    })
+}
+
+/// The hosts of [`host_iter_v6`], flagging whether any were left out.
+pub fn list_hosts_v6(cidr: &str, limit: usize) -> Result<HostsResult> {
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+    let hosts: Vec<String> = host_iter_v6(cidr, limit)?.map(|a| a.to_string()).collect();
+    Ok(HostsResult {
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        count: hosts.len(),
+        truncated: subnet.nth_host(hosts.len() as u128).is_some(),
+        hosts,
+    })
+}
+
+/// List the hosts of an IPv4 or IPv6 CIDR, picking the family from it.
+pub fn list_hosts(cidr: &str, limit: usize) -> Result<HostsResult> {
+    if cidr.contains(':') {
+        list_hosts_v6(cidr, limit)
+    } else {
+        list_hosts_v4(cidr, limit)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_iter_v4() {
+        let hosts: Vec<Ipv4Addr> = host_iter_v4("192.168.1.0/29", 100).unwrap().collect();
+        assert_eq!(hosts.len(), 6);
+        assert_eq!(hosts[0], Ipv4Addr::new(192, 168, 1, 1));
+        assert_eq!(hosts[5], Ipv4Addr::new(192, 168, 1, 6));
+
+        // Every address of a /31 or /32 is a host
+        assert_eq!(host_iter_v4("10.0.0.0/31", 10).unwrap().count(), 2);
+        assert_eq!(host_iter_v4("255.255.255.255/32", 10).unwrap().count(), 1);
+        assert!(host_iter_v4("10.0.0.0/33", 10).is_err());
+    }
+
+    #[test]
+    fn test_host_iter_v6() {
+        let hosts: Vec<Ipv6Addr> = host_iter_v6("2001:db8::/126", 10).unwrap().collect();
+        assert_eq!(hosts.len(), 3);
+        assert_eq!(hosts[0], "2001:db8::1".parse::<Ipv6Addr>().unwrap());
+        assert_eq!(host_iter_v6("2001:db8::/127", 10).unwrap().count(), 2);
+        // The last address of the space does not overflow
+        let last = host_iter_v6("ffff:ffff:ffff:ffff:ffff:ffff:ffff:fff0/124", 100)
+            .unwrap()
+            .last();
+        assert_eq!(last, Some(Ipv6Addr::from(u128::MAX)));
+    }
+
+    #[test]
+    fn test_truncation() {
+        let result = list_hosts("192.168.1.0/29", 6).unwrap();
+        assert_eq!(result.count, 6);
+        assert!(!result.truncated);
+
+        let result = list_hosts("192.168.1.0/29", 4).unwrap();
+        assert_eq!(
+            result.hosts,
+            ["192.168.1.1", "192.168.1.2", "192.168.1.3", "192.168.1.4"]
+        );
+        assert!(result.truncated);
+
+        // A huge IPv6 prefix stops at the generation cap
+        let result = list_hosts("2001:db8::/64", usize::MAX).unwrap();
+        assert_eq!(result.count as u64, DEFAULTS.max_generated_subnets);
+        assert!(result.truncated);
+        assert_eq!(result.cidr, "2001:db8::/64");
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::Result;
+use crate::ipv6::{Ipv6Style, Ipv6Subnet, format_ipv6};
+use serde::Serialize;
+use std::net::Ipv6Addr;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// An IPv6 address split at its prefix boundary into the network it belongs
+/// to and the host bits below it. For a /64 the host bits are the interface
+/// identifier.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct InterfaceIdResult {
+    pub input: String,
+    /// The address with its host bits intact, compressed
+    pub address: String,
+    pub network: String,
+    pub prefix_length: u8,
+    /// Number of bits below the prefix
+    pub host_bits: u8,
+    /// The host bits as colon-separated hextets, one per 16 bits or part
+    /// thereof (e.g., `1234:5678:9abc:def0` for a /64)
+    pub interface_id: String,
+    /// The host bits alone as an address (e.g., `::1234:5678:9abc:def0`)
+    pub interface_id_address: String,
+    /// `interface_id_address` with all eight groups zero-padded
+    pub interface_id_full: String,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Format the trailing hextets covering `host_bits` bits of `host`. A /128
+/// has no host bits and formats as `0`.
+fn host_hextets(host: u128, host_bits: u8) -> String {
+    let groups = u32::from(host_bits).div_ceil(16).max(1);
+    (0..groups)
+        .rev()
+        .map(|i| format!("{:x}", (host >> (i * 16)) as u16))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+// ---------------------------------------------------------------------------
+// Public entry point
+// ---------------------------------------------------------------------------
+
+/// Split an IPv6 address in CIDR notation into its network and host
+/// portions at the given prefix length.
+pub fn split_interface_id(cidr: &str) -> Result<InterfaceIdResult> {
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+
+    // from_cidr clears host bits, so read the address from the input
+    let given: Ipv6Addr = subnet
+        .input
+        .split_once('/')
+        .and_then(|(addr, _)| addr.parse().ok())
+        .unwrap_or(subnet.network);
+    let host_bits = 128 - subnet.prefix_length;
+    // The network is the address with its host bits cleared
+    let host = u128::from(given) ^ u128::from(subnet.network);
+    let host_addr = Ipv6Addr::from(host);
+
+    Ok(InterfaceIdResult {
+        input: cidr.trim().to_string(),
+        address: given.to_string(),
+        network: format!("{}/{}", subnet.network, subnet.prefix_length),
+        prefix_length: subnet.prefix_length,
+        host_bits,
+        interface_id: host_hextets(host, host_bits),
+        interface_id_address: host_addr.to_string(),
+        interface_id_full: format_ipv6(&host_addr, Ipv6Style::Full),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_64() {
+        let result = split_interface_id("2001:db8::1234:5678:9abc:def0/64").unwrap();
+        assert_eq!(result.network, "2001:db8::/64");
+        assert_eq!(result.address, "2001:db8::1234:5678:9abc:def0");
+        assert_eq!(result.host_bits, 64);
+        assert_eq!(result.interface_id, "1234:5678:9abc:def0");
+        assert_eq!(result.interface_id_address, "::1234:5678:9abc:def0");
+        assert_eq!(
+            result.interface_id_full,
+            "0000:0000:0000:0000:1234:5678:9abc:def0"
+        );
+
+        // Leading zero groups of the identifier are kept
+        let result = split_interface_id("fe80::1/64").unwrap();
+        assert_eq!(result.network, "fe80::/64");
+        assert_eq!(result.interface_id, "0:0:0:1");
+    }
+
+    #[test]
+    fn test_split_other_prefixes() {
+        // 72 host bits: the top group holds only the low byte of hextet 4
+        let result = split_interface_id("2001:db8:0:12ab:1:2:3:4/56").unwrap();
+        assert_eq!(result.network, "2001:db8:0:1200::/56");
+        assert_eq!(result.host_bits, 72);
+        assert_eq!(result.interface_id, "ab:1:2:3:4");
+        assert_eq!(result.interface_id_address, "::ab:1:2:3:4");
+
+        let result = split_interface_id("2001:db8::abcd/120").unwrap();
+        assert_eq!(result.network, "2001:db8::ab00/120");
+        assert_eq!(result.interface_id, "cd");
+
+        let result = split_interface_id("2001:db8::1/128").unwrap();
+        assert_eq!(result.host_bits, 0);
+        assert_eq!(result.interface_id, "0");
+        assert_eq!(result.interface_id_address, "::");
+
+        let result = split_interface_id("2001:db8::1/0").unwrap();
+        assert_eq!(result.network, "::/0");
+        assert_eq!(result.interface_id, "2001:db8:0:0:0:0:0:1");
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(split_interface_id("10.0.0.1/24").is_err());
+        assert!(split_interface_id("2001:db8::1").is_err());
+        assert!(split_interface_id("2001:db8::1/129").is_err());
+    }
+}
@@ -8,6 +8,7 @@ pub mod sqlite;
 pub mod store;
 
 use crate::error::{IpCalcError, Result};
+use crate::validation::{Family, validate_prefix};
 use config::IpamConfig;
 use std::sync::Arc;
 use store::IpamStore;
@@ -67,9 +68,7 @@ pub(crate) fn parse_cidr_metadata(cidr: &str) -> Result<(String, String, u8, u12
         .map_err(|_| IpCalcError::InvalidCidr(cidr.to_string()))?;
 
     if let Ok(addr) = addr_str.parse::<std::net::Ipv4Addr>() {
-        if prefix > 32 {
-            return Err(IpCalcError::InvalidPrefixLength(prefix));
-        }
+        validate_prefix(prefix, Family::V4)?;
         let addr_u32 = u32::from(addr);
         let mask = if prefix == 0 {
             0u32
@@ -87,9 +86,7 @@ pub(crate) fn parse_cidr_metadata(cidr: &str) -> Result<(String, String, u8, u12
             4,
         ))
     } else if let Ok(addr) = addr_str.parse::<std::net::Ipv6Addr>() {
-        if prefix > 128 {
-            return Err(IpCalcError::InvalidPrefixLength(prefix));
-        }
+        validate_prefix(prefix, Family::V6)?;
         let addr_u128 = u128::from(addr);
         let mask = if prefix == 0 {
             0u128
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Supernet {
     pub id: String,
     pub cidr: String,
@@ -33,6 +34,7 @@ pub struct CreateSupernet {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SupernetList {
     pub supernets: Vec<Supernet>,
     pub count: usize,
@@ -44,6 +46,7 @@ pub struct SupernetList {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum AllocationStatus {
     Active,
@@ -75,6 +78,7 @@ impl std::str::FromStr for AllocationStatus {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Allocation {
     pub id: String,
     pub supernet_id: String,
@@ -159,6 +163,7 @@ pub struct AllocationFilter {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AllocationList {
     pub allocations: Vec<Allocation>,
     pub count: usize,
@@ -170,6 +175,7 @@ pub struct AllocationList {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Tag {
     pub key: String,
     pub value: String,
@@ -181,6 +187,7 @@ pub struct Tag {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AuditEntry {
     pub id: String,
     pub entity_type: String,
@@ -200,6 +207,7 @@ pub struct AuditFilter {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AuditList {
     pub entries: Vec<AuditEntry>,
     pub count: usize,
@@ -211,6 +219,7 @@ pub struct AuditList {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct UtilizationReport {
     pub supernet_id: String,
     pub supernet_cidr: String,
@@ -219,10 +228,14 @@ pub struct UtilizationReport {
     pub free_addresses: u128,
     pub utilization_percent: f64,
     pub allocation_count: usize,
+    /// Width of the ASCII usage bar in text output; `None` omits the bar
+    #[serde(skip)]
+    pub bar_width: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FreeBlock {
     pub cidr: String,
     pub size: u128,
@@ -230,6 +243,7 @@ pub struct FreeBlock {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FreeBlocksReport {
     pub supernet_id: String,
     pub supernet_cidr: String,
@@ -6,7 +6,7 @@ use chrono::Utc;
 use crate::error::{IpCalcError, Result};
 use crate::ipam::models::*;
 use crate::ipam::store::IpamStore;
-use crate::validation;
+use crate::validation::{self, Family};
 
 /// High-level IPAM operations that sit above the store trait.
 /// All conflict detection and free-space logic lives here, keeping
@@ -257,6 +257,7 @@ impl IpamOps {
             free_addresses: free,
             utilization_percent: pct,
             allocation_count: active.len(),
+            bar_width: None,
         })
     }
 
@@ -568,10 +569,13 @@ fn find_free_blocks(
     prefix: u8,
     count: u32,
 ) -> Result<Vec<String>> {
-    let bits = if supernet.is_v4 { 32 } else { 128 };
-    if prefix > bits {
-        return Err(IpCalcError::InvalidPrefixLength(prefix));
-    }
+    let family = if supernet.is_v4 {
+        Family::V4
+    } else {
+        Family::V6
+    };
+    validation::validate_prefix(prefix, family)?;
+    let bits = family.bits();
     let block_size: u128 = 1u128 << (bits - prefix);
 
     let gaps = find_gaps(supernet, allocated);
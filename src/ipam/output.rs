@@ -1,8 +1,29 @@
-use crate::error::{IpCalcError, Result};
 use crate::ipam::models::*;
-use crate::output::{CsvOutput, TextOutput};
+use crate::output::{EnvOutput, TextOutput, impl_result_kind};
 use std::fmt::Write;
 
+// ---------------------------------------------------------------------------
+// ResultKind and EnvOutput implementations
+// ---------------------------------------------------------------------------
+
+impl_result_kind! {
+    Supernet => Some("ipam_supernet"),
+    SupernetList => Some("ipam_supernet_list"),
+    Allocation => Some("ipam_allocation"),
+    AllocationList => Some("ipam_allocation_list"),
+    UtilizationReport => Some("ipam_utilization"),
+    FreeBlocksReport => Some("ipam_free_blocks"),
+    AuditList => Some("ipam_audit"),
+}
+
+impl EnvOutput for Supernet {}
+impl EnvOutput for SupernetList {}
+impl EnvOutput for Allocation {}
+impl EnvOutput for AllocationList {}
+impl EnvOutput for UtilizationReport {}
+impl EnvOutput for FreeBlocksReport {}
+impl EnvOutput for AuditList {}
+
 // ---------------------------------------------------------------------------
 // TextOutput implementations
 // ---------------------------------------------------------------------------
@@ -130,11 +151,32 @@ impl TextOutput for UtilizationReport {
         writeln!(out, "Allocated:         {}", self.allocated_addresses).unwrap();
         writeln!(out, "Free:              {}", self.free_addresses).unwrap();
         writeln!(out, "Utilization:       {:.2}%", self.utilization_percent).unwrap();
+        if let Some(width) = self.bar_width {
+            writeln!(
+                out,
+                "Usage:             {}",
+                utilization_bar(self.utilization_percent, width)
+            )
+            .unwrap();
+        }
         writeln!(out, "Allocation Count:  {}", self.allocation_count).unwrap();
         out
     }
 }
 
+/// Render `percent` as an ASCII bar `width` cells wide, e.g. `[####------] 40%`.
+pub fn utilization_bar(percent: f64, width: usize) -> String {
+    let percent = percent.clamp(0.0, 100.0);
+    let filled = ((percent / 100.0) * width as f64).round() as usize;
+    let filled = filled.min(width);
+    format!(
+        "[{}{}] {:.0}%",
+        "#".repeat(filled),
+        "-".repeat(width - filled),
+        percent
+    )
+}
+
 impl TextOutput for FreeBlocksReport {
     fn to_text(&self) -> String {
         let mut out = String::new();
@@ -178,214 +220,23 @@ impl TextOutput for AuditList {
     }
 }
 
+#[cfg(feature = "csv")]
+mod csv_output;
+
 // ---------------------------------------------------------------------------
-// CsvOutput implementations
+// Unit tests
 // ---------------------------------------------------------------------------
 
-fn csv_err(e: impl std::fmt::Display) -> IpCalcError {
-    IpCalcError::Csv(e.to_string())
-}
-
-fn finish_csv(wtr: csv::Writer<Vec<u8>>) -> Result<String> {
-    let bytes = wtr.into_inner().map_err(csv_err)?;
-    String::from_utf8(bytes).map_err(csv_err)
-}
-
-impl CsvOutput for Supernet {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record([
-            "id",
-            "cidr",
-            "network_address",
-            "broadcast_address",
-            "prefix_length",
-            "total_hosts",
-            "name",
-            "description",
-            "ip_version",
-            "created_at",
-        ])
-        .map_err(csv_err)?;
-        wtr.write_record([
-            &self.id,
-            &self.cidr,
-            &self.network_address,
-            &self.broadcast_address,
-            &self.prefix_length.to_string(),
-            &self.total_hosts.to_string(),
-            self.name.as_deref().unwrap_or(""),
-            self.description.as_deref().unwrap_or(""),
-            &self.ip_version.to_string(),
-            &self.created_at,
-        ])
-        .map_err(csv_err)?;
-        finish_csv(wtr)
-    }
-}
-
-impl CsvOutput for SupernetList {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record([
-            "id",
-            "cidr",
-            "network_address",
-            "broadcast_address",
-            "prefix_length",
-            "total_hosts",
-            "name",
-            "description",
-            "ip_version",
-            "created_at",
-        ])
-        .map_err(csv_err)?;
-        for sn in &self.supernets {
-            wtr.write_record([
-                &sn.id,
-                &sn.cidr,
-                &sn.network_address,
-                &sn.broadcast_address,
-                &sn.prefix_length.to_string(),
-                &sn.total_hosts.to_string(),
-                sn.name.as_deref().unwrap_or(""),
-                sn.description.as_deref().unwrap_or(""),
-                &sn.ip_version.to_string(),
-                &sn.created_at,
-            ])
-            .map_err(csv_err)?;
-        }
-        finish_csv(wtr)
-    }
-}
-
-impl CsvOutput for Allocation {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(allocation_csv_header()).map_err(csv_err)?;
-        write_allocation_csv_row(&mut wtr, self)?;
-        finish_csv(wtr)
-    }
-}
-
-impl CsvOutput for AllocationList {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(allocation_csv_header()).map_err(csv_err)?;
-        for a in &self.allocations {
-            write_allocation_csv_row(&mut wtr, a)?;
-        }
-        finish_csv(wtr)
-    }
-}
-
-impl CsvOutput for UtilizationReport {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record([
-            "supernet_id",
-            "supernet_cidr",
-            "total_addresses",
-            "allocated_addresses",
-            "free_addresses",
-            "utilization_percent",
-            "allocation_count",
-        ])
-        .map_err(csv_err)?;
-        wtr.write_record([
-            &self.supernet_id,
-            &self.supernet_cidr,
-            &self.total_addresses.to_string(),
-            &self.allocated_addresses.to_string(),
-            &self.free_addresses.to_string(),
-            &format!("{:.2}", self.utilization_percent),
-            &self.allocation_count.to_string(),
-        ])
-        .map_err(csv_err)?;
-        finish_csv(wtr)
-    }
-}
-
-impl CsvOutput for FreeBlocksReport {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(["cidr", "size"]).map_err(csv_err)?;
-        for block in &self.blocks {
-            wtr.write_record([&block.cidr, &block.size.to_string()])
-                .map_err(csv_err)?;
-        }
-        finish_csv(wtr)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utilization_bar() {
+        assert_eq!(utilization_bar(40.0, 10), "[####------] 40%");
+        assert_eq!(utilization_bar(0.0, 4), "[----] 0%");
+        assert_eq!(utilization_bar(100.0, 4), "[####] 100%");
+        // Out-of-range percentages are clamped
+        assert_eq!(utilization_bar(150.0, 4), "[####] 100%");
     }
 }
-
-impl CsvOutput for AuditList {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record([
-            "id",
-            "timestamp",
-            "action",
-            "entity_type",
-            "entity_id",
-            "details",
-        ])
-        .map_err(csv_err)?;
-        for e in &self.entries {
-            wtr.write_record([
-                &e.id,
-                &e.timestamp,
-                &e.action,
-                &e.entity_type,
-                &e.entity_id,
-                e.details.as_deref().unwrap_or(""),
-            ])
-            .map_err(csv_err)?;
-        }
-        finish_csv(wtr)
-    }
-}
-
-fn allocation_csv_header() -> &'static [&'static str] {
-    &[
-        "id",
-        "supernet_id",
-        "cidr",
-        "network_address",
-        "broadcast_address",
-        "prefix_length",
-        "total_hosts",
-        "status",
-        "resource_id",
-        "resource_type",
-        "name",
-        "description",
-        "environment",
-        "owner",
-        "created_at",
-        "updated_at",
-        "released_at",
-    ]
-}
-
-fn write_allocation_csv_row(wtr: &mut csv::Writer<Vec<u8>>, a: &Allocation) -> Result<()> {
-    wtr.write_record([
-        &a.id,
-        &a.supernet_id,
-        &a.cidr,
-        &a.network_address,
-        &a.broadcast_address,
-        &a.prefix_length.to_string(),
-        &a.total_hosts.to_string(),
-        &a.status.to_string(),
-        a.resource_id.as_deref().unwrap_or(""),
-        a.resource_type.as_deref().unwrap_or(""),
-        a.name.as_deref().unwrap_or(""),
-        a.description.as_deref().unwrap_or(""),
-        a.environment.as_deref().unwrap_or(""),
-        a.owner.as_deref().unwrap_or(""),
-        &a.created_at,
-        &a.updated_at,
-        a.released_at.as_deref().unwrap_or(""),
-    ])
-    .map_err(csv_err)
-}
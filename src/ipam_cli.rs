@@ -3,13 +3,13 @@ use ipcalc::error::Result;
 use ipcalc::ipam::config::IpamConfig;
 use ipcalc::ipam::models::*;
 use ipcalc::ipam::operations::IpamOps;
-use ipcalc::output::{CsvOutput, OutputWriter, TextOutput};
+use ipcalc::output::{CsvOutput, EnvOutput, OutputWriter, ResultKind, TextOutput};
 use ipcalc::validation;
 use serde::Serialize;
 
 use crate::print_stdout;
 
-fn output_result<T: Serialize + TextOutput + CsvOutput>(
+fn output_result<T: Serialize + TextOutput + CsvOutput + EnvOutput + ResultKind>(
     writer: &OutputWriter,
     output_file: &Option<String>,
     data: &T,
@@ -224,8 +224,15 @@ pub async fn handle_ipam_command(
             output_result(writer, output_file, &alloc);
         }
 
-        IpamCommands::Utilization { supernet_id } => {
-            let report = ops.utilization(&supernet_id).await?;
+        IpamCommands::Utilization {
+            supernet_id,
+            bar,
+            bar_width,
+        } => {
+            let mut report = ops.utilization(&supernet_id).await?;
+            if bar {
+                report.bar_width = Some(bar_width);
+            }
             output_result(writer, output_file, &report);
         }
 
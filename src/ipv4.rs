@@ -1,11 +1,13 @@
 use crate::error::{IpCalcError, Result};
-use crate::validation;
-use serde::Serialize;
+use crate::special_purpose::{self, SpecialPurposeEntry};
+use crate::validation::{self, Family};
+use serde::{Deserialize, Serialize};
 use std::net::Ipv4Addr;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv4Subnet {
     pub input: String,
     #[serde(rename = "network_address")]
@@ -20,6 +22,12 @@ pub struct Ipv4Subnet {
     #[serde(rename = "wildcard_mask")]
     #[cfg_attr(feature = "swagger", schema(value_type = String))]
     pub wildcard: Ipv4Addr,
+    /// Network address as four dot-separated 8-bit groups
+    #[serde(default)]
+    pub binary_address: String,
+    /// Subnet mask as four dot-separated 8-bit groups
+    #[serde(default)]
+    pub binary_mask: String,
     pub prefix_length: u8,
     #[cfg_attr(feature = "swagger", schema(value_type = String))]
     pub first_host: Ipv4Addr,
@@ -30,6 +38,25 @@ pub struct Ipv4Subnet {
     pub network_class: String,
     pub is_private: bool,
     pub address_type: String,
+    /// /31 point-to-point link (RFC 3021): both addresses are usable hosts.
+    #[serde(default)]
+    pub rfc3021: bool,
+    /// /32 host route: the single address is network, broadcast, and host.
+    #[serde(default)]
+    pub host_route: bool,
+    /// IANA special-purpose registry entry containing the subnet (RFC 6890),
+    /// set by [`Ipv4Subnet::apply_rfc6890`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub special_purpose: Option<SpecialPurposeEntry>,
+}
+
+/// `value` as four dot-separated 8-bit groups, e.g.
+/// `11000000.10101000.00000001.00000000` for 192.168.1.0.
+pub fn dotted_binary(value: u32) -> String {
+    value
+        .to_be_bytes()
+        .map(|octet| format!("{:08b}", octet))
+        .join(".")
 }
 
 /// Compute the IPv4 subnet mask for a given prefix length.
@@ -43,27 +70,29 @@ pub fn ipv4_mask(prefix: u8) -> u32 {
 }
 
 impl Ipv4Subnet {
+    /// Parse `addr/prefix`, `addr/netmask`, or `addr wildcard` notation.
     pub fn from_cidr(cidr: &str) -> Result<Self> {
+        let cidr = cidr.trim();
+        if let Some(converted) = validation::mask_notation_to_cidr(cidr)? {
+            return Self::from_cidr(&converted);
+        }
         validation::validate_cidr(cidr)?;
 
-        let (addr_str, prefix_str) = cidr
-            .split_once('/')
-            .ok_or_else(|| IpCalcError::InvalidCidr(cidr.to_string()))?;
+        let (addr_str, prefix) = validation::parse_cidr_parts(cidr)?;
 
         let addr = Ipv4Addr::from_str(addr_str)
             .map_err(|_| IpCalcError::InvalidIpv4Address(addr_str.to_string()))?;
 
-        let prefix: u8 = prefix_str
-            .parse()
-            .map_err(|_| IpCalcError::InvalidCidr(cidr.to_string()))?;
-
         Self::new(addr, prefix)
     }
 
+    /// Like [`Ipv4Subnet::from_cidr`], but a bare address is taken as a /32.
+    pub fn from_addr_or_cidr(input: &str) -> Result<Self> {
+        Self::from_cidr(&validation::with_host_prefix(input))
+    }
+
     pub fn new(addr: Ipv4Addr, prefix: u8) -> Result<Self> {
-        if prefix > 32 {
-            return Err(IpCalcError::InvalidPrefixLength(prefix));
-        }
+        validation::validate_prefix(prefix, Family::V4)?;
 
         let addr_u32 = u32::from(addr);
         let mask = ipv4_mask(prefix);
@@ -117,6 +146,8 @@ impl Ipv4Subnet {
             broadcast: broadcast_addr,
             mask: subnet_mask,
             wildcard: wildcard_mask,
+            binary_address: dotted_binary(network),
+            binary_mask: dotted_binary(mask),
             prefix_length: prefix,
             first_host,
             last_host,
@@ -125,9 +156,59 @@ impl Ipv4Subnet {
             network_class,
             is_private,
             address_type,
+            rfc3021: prefix == 31,
+            host_route: prefix == 32,
+            special_purpose: None,
         })
     }
 
+    /// The block of the same prefix length right after this one, or `None`
+    /// past the end of the address space.
+    pub fn next_subnet(&self) -> Option<Ipv4Subnet> {
+        let size = 1u64 << (32 - self.prefix_length);
+        let next = u32::try_from(u32::from(self.network) as u64 + size).ok()?;
+        Self::new(Ipv4Addr::from(next), self.prefix_length).ok()
+    }
+
+    /// The block of the same prefix length right before this one, or `None`
+    /// before the start of the address space.
+    pub fn prev_subnet(&self) -> Option<Ipv4Subnet> {
+        let size = 1u64 << (32 - self.prefix_length);
+        let prev = (u32::from(self.network) as u64).checked_sub(size)?;
+        Self::new(Ipv4Addr::from(prev as u32), self.prefix_length).ok()
+    }
+
+    /// The `n`th usable host, counting from 0 at `first_host`, or `None` past
+    /// the last one. Every address of a /31 or /32 counts, whatever
+    /// `usable_hosts` reports.
+    pub fn nth_host(&self, n: u64) -> Option<Ipv4Addr> {
+        let hosts = if self.prefix_length >= 31 {
+            self.total_hosts
+        } else {
+            self.usable_hosts
+        };
+        (n < hosts).then(|| Ipv4Addr::from(u32::from(self.first_host) + n as u32))
+    }
+
+    /// `network_address/prefix_length`.
+    pub fn cidr(&self) -> String {
+        format!("{}/{}", self.network, self.prefix_length)
+    }
+
+    /// Override the usable host count reported for a /31 (normally 2, per
+    /// RFC 3021). Has no effect on other prefix lengths.
+    pub fn apply_p2p_usable(&mut self, usable: u64) {
+        if self.rfc3021 {
+            self.usable_hosts = usable;
+        }
+    }
+
+    /// Attach the most specific special-purpose registry entry containing
+    /// this subnet, if any.
+    pub fn apply_rfc6890(&mut self) {
+        self.special_purpose = special_purpose::lookup_ipv4(self.network, self.prefix_length);
+    }
+
     fn determine_address_type(network: u32) -> String {
         // Check more-specific ranges before less-specific ones
         let label = if network & 0xff00_0000 == 0x0000_0000 {
@@ -186,6 +267,56 @@ impl Ipv4Subnet {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_next_and_prev_subnet() {
+        let subnet = Ipv4Subnet::from_cidr("192.168.1.0/24").unwrap();
+        assert_eq!(
+            subnet.next_subnet().unwrap().network,
+            Ipv4Addr::new(192, 168, 2, 0)
+        );
+        assert_eq!(
+            subnet.prev_subnet().unwrap().network,
+            Ipv4Addr::new(192, 168, 0, 0)
+        );
+        // Host bits in the input do not shift the neighbours
+        let subnet = Ipv4Subnet::from_cidr("10.0.0.77/30").unwrap();
+        assert_eq!(
+            subnet.next_subnet().unwrap().network,
+            Ipv4Addr::new(10, 0, 0, 80)
+        );
+        assert_eq!(subnet.next_subnet().unwrap().prefix_length, 30);
+
+        // No wrap-around at either end of the address space
+        assert!(
+            Ipv4Subnet::from_cidr("0.0.0.0/8")
+                .unwrap()
+                .prev_subnet()
+                .is_none()
+        );
+        assert!(
+            Ipv4Subnet::from_cidr("255.0.0.0/8")
+                .unwrap()
+                .next_subnet()
+                .is_none()
+        );
+        assert!(
+            Ipv4Subnet::from_cidr("255.255.255.255/32")
+                .unwrap()
+                .next_subnet()
+                .is_none()
+        );
+        let all = Ipv4Subnet::from_cidr("0.0.0.0/0").unwrap();
+        assert!(all.next_subnet().is_none() && all.prev_subnet().is_none());
+        assert_eq!(
+            Ipv4Subnet::from_cidr("254.0.0.0/8")
+                .unwrap()
+                .next_subnet()
+                .unwrap()
+                .network,
+            Ipv4Addr::new(255, 0, 0, 0)
+        );
+    }
+
     #[test]
     fn test_ipv4_subnet_24() {
         let subnet = Ipv4Subnet::from_cidr("192.168.1.100/24").unwrap();
@@ -304,8 +435,76 @@ mod tests {
         assert_eq!(json["broadcast_address"], "192.168.1.255");
         assert_eq!(json["subnet_mask"], "255.255.255.0");
         assert_eq!(json["wildcard_mask"], "0.0.0.255");
+        assert_eq!(
+            json["binary_address"],
+            "11000000.10101000.00000001.00000000"
+        );
+        assert_eq!(json["binary_mask"], "11111111.11111111.11111111.00000000");
         assert_eq!(json["first_host"], "192.168.1.1");
         assert_eq!(json["last_host"], "192.168.1.254");
         assert_eq!(json["prefix_length"], 24);
     }
+
+    #[test]
+    fn test_small_subnet_flags() {
+        let s30 = Ipv4Subnet::from_cidr("10.0.0.0/30").unwrap();
+        assert!(!s30.rfc3021);
+        assert!(!s30.host_route);
+        assert_eq!(s30.usable_hosts, 2);
+
+        let s31 = Ipv4Subnet::from_cidr("10.0.0.0/31").unwrap();
+        assert!(s31.rfc3021);
+        assert!(!s31.host_route);
+        assert_eq!(s31.first_host, s31.network);
+        assert_eq!(s31.last_host, s31.broadcast);
+
+        let s32 = Ipv4Subnet::from_cidr("10.0.0.1/32").unwrap();
+        assert!(!s32.rfc3021);
+        assert!(s32.host_route);
+        assert_eq!(s32.usable_hosts, 1);
+    }
+
+    #[test]
+    fn test_apply_p2p_usable() {
+        let mut s31 = Ipv4Subnet::from_cidr("10.0.0.0/31").unwrap();
+        assert_eq!(s31.usable_hosts, 2);
+        s31.apply_p2p_usable(0);
+        assert_eq!(s31.usable_hosts, 0);
+
+        let mut s30 = Ipv4Subnet::from_cidr("10.0.0.0/30").unwrap();
+        s30.apply_p2p_usable(0);
+        assert_eq!(s30.usable_hosts, 2);
+
+        let mut s32 = Ipv4Subnet::from_cidr("10.0.0.1/32").unwrap();
+        s32.apply_p2p_usable(0);
+        assert_eq!(s32.usable_hosts, 1);
+    }
+
+    #[test]
+    fn test_bare_address() {
+        let subnet = Ipv4Subnet::from_addr_or_cidr("8.8.8.8").unwrap();
+        assert_eq!(subnet.prefix_length, 32);
+        assert_eq!(subnet.network.to_string(), "8.8.8.8");
+        assert_eq!(subnet.address_type, "Public");
+
+        let subnet = Ipv4Subnet::from_addr_or_cidr("10.1.2.3/8").unwrap();
+        assert_eq!(subnet.prefix_length, 8);
+        assert!(Ipv4Subnet::from_addr_or_cidr("10.1.2.3/").is_err());
+    }
+
+    #[test]
+    fn test_mask_notation() {
+        let subnet = Ipv4Subnet::from_cidr("192.168.1.0/255.255.255.192").unwrap();
+        assert_eq!(subnet.prefix_length, 26);
+        assert_eq!(subnet.broadcast.to_string(), "192.168.1.63");
+
+        let subnet = Ipv4Subnet::from_cidr("192.168.1.0 0.0.0.255").unwrap();
+        assert_eq!(subnet.prefix_length, 24);
+        assert_eq!(subnet.mask.to_string(), "255.255.255.0");
+
+        assert!(matches!(
+            Ipv4Subnet::from_cidr("192.168.1.0/255.0.255.0"),
+            Err(IpCalcError::InvalidNetmask(_))
+        ));
+    }
 }
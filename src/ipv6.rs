@@ -1,11 +1,17 @@
 use crate::error::{IpCalcError, Result};
-use crate::validation;
-use serde::Serialize;
-use std::net::Ipv6Addr;
+use crate::validation::{self, Family};
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize)]
+/// An IPv6 block and its derived fields.
+///
+/// Every address string, including `network_address` and `last_address`, is
+/// rendered in the hex digit case of the subnet's [`Ipv6Format`]; `input`
+/// echoes the parsed input compressed, in that same case.
+#[derive(Debug, Clone, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv6Subnet {
     pub input: String,
     #[serde(rename = "network_address")]
@@ -20,6 +26,237 @@ pub struct Ipv6Subnet {
     pub total_addresses: String,
     pub hextets: Vec<String>,
     pub address_type: String,
+    /// The embedded IPv4 address, when the block lies within the
+    /// IPv4-mapped range `::ffff:0:0/96`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv4_mapped: Option<String>,
+    /// The IPv4 address embedded in bits 16-47, when the block lies within
+    /// the 6to4 range `2002::/16` (RFC 3056)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sixto4_ipv4: Option<String>,
+    /// Whether the address was supplied in RFC 5952 canonical form
+    #[serde(default = "default_canonical_input")]
+    pub canonical_input: bool,
+    /// Style requested via [`Ipv6Subnet::apply_style`], when not compressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Ipv6Style>,
+    /// Hex digit case requested via [`Ipv6Subnet::apply_style`], when upper.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub case: Option<Ipv6Case>,
+    /// The network address in the requested style, when not compressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_address_styled: Option<String>,
+    /// The last address in the requested style, when not compressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_address_styled: Option<String>,
+}
+
+/// The serialized form of [`Ipv6Subnet`], with the network and last address
+/// rendered in the subnet's case rather than by `Ipv6Addr`'s lowercase
+/// `Display`. Field order and attributes mirror the struct, whose derived
+/// schema describes this output.
+#[derive(Serialize)]
+struct Ipv6SubnetView<'a> {
+    input: &'a str,
+    network_address: String,
+    network_address_full: &'a str,
+    last_address: String,
+    last_address_full: &'a str,
+    prefix_length: u8,
+    total_addresses: &'a str,
+    hextets: &'a [String],
+    address_type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ipv4_mapped: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sixto4_ipv4: Option<&'a str>,
+    canonical_input: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    style: Option<Ipv6Style>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    case: Option<Ipv6Case>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    network_address_styled: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_address_styled: Option<&'a str>,
+}
+
+impl Serialize for Ipv6Subnet {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        Ipv6SubnetView {
+            input: &self.input,
+            network_address: self.network_address(),
+            network_address_full: &self.network_address_full,
+            last_address: self.last_address(),
+            last_address_full: &self.last_address_full,
+            prefix_length: self.prefix_length,
+            total_addresses: &self.total_addresses,
+            hextets: &self.hextets,
+            address_type: &self.address_type,
+            ipv4_mapped: self.ipv4_mapped.as_deref(),
+            sixto4_ipv4: self.sixto4_ipv4.as_deref(),
+            canonical_input: self.canonical_input,
+            style: self.style,
+            case: self.case,
+            network_address_styled: self.network_address_styled.as_deref(),
+            last_address_styled: self.last_address_styled.as_deref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+fn default_canonical_input() -> bool {
+    true
+}
+
+/// Rendering style for IPv6 addresses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Ipv6Style {
+    /// RFC 5952 form with `::` compression (`2001:db8::1`)
+    #[default]
+    Compressed,
+    /// All eight groups, leading zeros dropped (`2001:db8:0:0:0:0:0:1`)
+    Expanded,
+    /// All eight groups zero-padded to four digits (`2001:0db8:0000:...:0001`)
+    Full,
+    /// Zero-padded groups with dot-separated nibbles (`2.0.0.1:0.d.b.8:...`)
+    Nibble,
+}
+
+/// Case of the hex digits in rendered IPv6 addresses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Ipv6Case {
+    /// Lowercase, as RFC 5952 recommends (`2001:db8::`)
+    #[default]
+    Lower,
+    /// Uppercase, for systems that expect it (`2001:DB8::`)
+    Upper,
+}
+
+/// How to render IPv6 addresses: a style and a hex digit case.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ipv6Format {
+    pub style: Ipv6Style,
+    pub case: Ipv6Case,
+}
+
+impl From<Ipv6Style> for Ipv6Format {
+    fn from(style: Ipv6Style) -> Self {
+        Self {
+            style,
+            case: Ipv6Case::Lower,
+        }
+    }
+}
+
+impl From<Ipv6Case> for Ipv6Format {
+    fn from(case: Ipv6Case) -> Self {
+        Self {
+            style: Ipv6Style::Compressed,
+            case,
+        }
+    }
+}
+
+impl Ipv6Format {
+    /// One group as four zero-padded hex digits.
+    pub fn hextet(&self, segment: u16) -> String {
+        match self.case {
+            Ipv6Case::Lower => format!("{:04x}", segment),
+            Ipv6Case::Upper => format!("{:04X}", segment),
+        }
+    }
+
+    /// All eight groups, zero-padded and colon-separated.
+    pub fn full(&self, addr: &Ipv6Addr) -> String {
+        addr.segments()
+            .iter()
+            .map(|&s| self.hextet(s))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+
+    /// One group with leading zeros dropped.
+    fn group(&self, segment: u16) -> String {
+        match self.case {
+            Ipv6Case::Lower => format!("{:x}", segment),
+            Ipv6Case::Upper => format!("{:X}", segment),
+        }
+    }
+
+    /// RFC 5952 compressed form in this case: leading zeros dropped and the
+    /// longest run of two or more zero groups (the leftmost on a tie)
+    /// replaced with `::`. IPv4-mapped addresses keep their dotted quad, as
+    /// `Ipv6Addr`'s `Display` does.
+    pub fn compressed(&self, addr: &Ipv6Addr) -> String {
+        if let Some(v4) = addr.to_ipv4_mapped() {
+            return format!("::{}:{}", self.group(0xffff), v4);
+        }
+        let segments = addr.segments();
+        let (mut best, mut run) = ((0, 0), (0, 0));
+        for (i, &s) in segments.iter().enumerate() {
+            if s != 0 {
+                continue;
+            }
+            run = if run.1 > 0 && run.0 + run.1 == i {
+                (run.0, run.1 + 1)
+            } else {
+                (i, 1)
+            };
+            if run.1 > best.1 {
+                best = run;
+            }
+        }
+        let join = |groups: &[u16]| {
+            groups
+                .iter()
+                .map(|&s| self.group(s))
+                .collect::<Vec<_>>()
+                .join(":")
+        };
+        if best.1 < 2 {
+            return join(&segments);
+        }
+        let (start, end) = (best.0, best.0 + best.1);
+        format!("{}::{}", join(&segments[..start]), join(&segments[end..]))
+    }
+
+    /// Render `addr` in this style and case.
+    pub fn format(&self, addr: &Ipv6Addr) -> String {
+        match self.style {
+            Ipv6Style::Compressed => self.compressed(addr),
+            Ipv6Style::Expanded => addr
+                .segments()
+                .iter()
+                .map(|&s| self.group(s))
+                .collect::<Vec<_>>()
+                .join(":"),
+            Ipv6Style::Full => self.full(addr),
+            Ipv6Style::Nibble => addr
+                .segments()
+                .iter()
+                .map(|&s| {
+                    let hex = self.hextet(s);
+                    hex.chars().map(String::from).collect::<Vec<_>>().join(".")
+                })
+                .collect::<Vec<_>>()
+                .join(":"),
+        }
+    }
+}
+
+/// Render `addr` in the given style.
+pub fn format_ipv6(addr: &Ipv6Addr, style: Ipv6Style) -> String {
+    Ipv6Format::from(style).format(addr)
 }
 
 /// Compute the IPv6 subnet mask for a given prefix length.
@@ -32,28 +269,103 @@ pub fn ipv6_mask(prefix: u8) -> u128 {
     }
 }
 
+/// `2^bits` as an exact decimal string, for `bits` in 0..=128.
+///
+/// 2^128 does not fit in a u128, so it is derived from `u128::MAX` (2^128 - 1)
+/// by adding one to its decimal digits.
+pub fn pow2_decimal(bits: u8) -> String {
+    if bits < 128 {
+        return (1u128 << bits).to_string();
+    }
+    let mut digits = u128::MAX.to_string().into_bytes();
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return String::from_utf8(digits).expect("ASCII digits");
+        }
+    }
+    // Every digit carried (not reachable for 2^128 - 1, which ends in 5)
+    format!("1{}", String::from_utf8(digits).expect("ASCII digits"))
+}
+
+/// One IPv6 address in RFC 5952 compressed form and fully expanded form.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv6FormatResult {
+    pub input: String,
+    pub compressed: String,
+    pub expanded: String,
+}
+
+fn ipv6_format_result(address: &str) -> Result<Ipv6FormatResult> {
+    let input = address.trim();
+    let addr = Ipv6Addr::from_str(input)
+        .map_err(|_| IpCalcError::InvalidIpv6Address(input.to_string()))?;
+    Ok(Ipv6FormatResult {
+        input: input.to_string(),
+        compressed: format_ipv6(&addr, Ipv6Style::Compressed),
+        expanded: format_ipv6(&addr, Ipv6Style::Full),
+    })
+}
+
+/// Expand an IPv6 address to eight zero-padded groups
+/// (`2001:db8::1` → `2001:0db8:0000:0000:0000:0000:0000:0001`).
+pub fn expand_ipv6(address: &str) -> Result<Ipv6FormatResult> {
+    ipv6_format_result(address)
+}
+
+/// Compress an IPv6 address per RFC 5952: lowercase, leading zeros dropped,
+/// and the longest run of two or more zero groups (the leftmost on a tie)
+/// replaced with `::`.
+pub fn compress_ipv6(address: &str) -> Result<Ipv6FormatResult> {
+    ipv6_format_result(address)
+}
+
 impl Ipv6Subnet {
     pub fn from_cidr(cidr: &str) -> Result<Self> {
+        let cidr = cidr.trim();
         validation::validate_cidr(cidr)?;
 
-        let (addr_str, prefix_str) = cidr
-            .split_once('/')
-            .ok_or_else(|| IpCalcError::InvalidCidr(cidr.to_string()))?;
+        let (addr_str, prefix) = validation::parse_cidr_parts(cidr)?;
 
         let addr = Ipv6Addr::from_str(addr_str)
             .map_err(|_| IpCalcError::InvalidIpv6Address(addr_str.to_string()))?;
 
-        let prefix: u8 = prefix_str
-            .parse()
-            .map_err(|_| IpCalcError::InvalidCidr(cidr.to_string()))?;
+        let mut subnet = Self::new(addr, prefix)?;
+        subnet.canonical_input = addr_str == format_ipv6(&addr, Ipv6Style::Compressed);
+        Ok(subnet)
+    }
 
-        Self::new(addr, prefix)
+    /// Like [`Ipv6Subnet::from_cidr`], but a bare address is taken as a /128.
+    pub fn from_addr_or_cidr(input: &str) -> Result<Self> {
+        Self::from_cidr(&validation::with_host_prefix(input))
     }
 
-    pub fn new(addr: Ipv6Addr, prefix: u8) -> Result<Self> {
-        if prefix > 128 {
-            return Err(IpCalcError::InvalidPrefixLength(prefix));
+    /// Like [`Ipv6Subnet::from_cidr`], but rejects addresses that are not in
+    /// RFC 5952 canonical form (lowercase, no leading zeros, longest zero run
+    /// compressed), reporting the canonical spelling.
+    pub fn from_cidr_canonical(cidr: &str) -> Result<Self> {
+        let subnet = Self::from_cidr(cidr)?;
+        if !subnet.canonical_input {
+            return Err(IpCalcError::NonCanonicalIpv6 {
+                input: cidr.trim().to_string(),
+                canonical: subnet.input,
+            });
         }
+        Ok(subnet)
+    }
+
+    pub fn new(addr: Ipv6Addr, prefix: u8) -> Result<Self> {
+        Self::with_format(addr, prefix, Ipv6Format::default())
+    }
+
+    /// Like [`Ipv6Subnet::new`], with every address string rendered in
+    /// `format`.
+    pub fn with_format(addr: Ipv6Addr, prefix: u8, format: impl Into<Ipv6Format>) -> Result<Self> {
+        validation::validate_prefix(prefix, Family::V6)?;
 
         let addr_u128 = u128::from(addr);
         let mask = ipv6_mask(prefix);
@@ -64,9 +376,6 @@ impl Ipv6Subnet {
         let network_addr = Ipv6Addr::from(network);
         let last_addr = Ipv6Addr::from(last);
 
-        let segments = network_addr.segments();
-        let hextets: Vec<String> = segments.iter().map(|s| format!("{:04x}", s)).collect();
-
         let total_addresses = if prefix == 128 {
             "1".to_string()
         } else {
@@ -79,26 +388,118 @@ impl Ipv6Subnet {
         };
 
         let address_type = Self::determine_address_type(&network_addr);
+        let ipv4_mapped = network_addr
+            .to_ipv4_mapped()
+            .filter(|_| prefix >= 96)
+            .map(|v4| v4.to_string());
+        let sixto4_ipv4 = Self::sixto4_ipv4(&network_addr).map(|v4| v4.to_string());
 
-        Ok(Self {
+        let mut subnet = Self {
             input: format!("{}/{}", addr, prefix),
             network: network_addr,
-            network_address_full: hextets.join(":"),
+            network_address_full: String::new(),
             last: last_addr,
-            last_address_full: Self::format_full(&last_addr),
+            last_address_full: String::new(),
             prefix_length: prefix,
             total_addresses,
-            hextets,
+            hextets: Vec::new(),
             address_type,
-        })
+            ipv4_mapped,
+            sixto4_ipv4,
+            canonical_input: true,
+            style: None,
+            case: None,
+            network_address_styled: None,
+            last_address_styled: None,
+        };
+        subnet.apply_style(format);
+        Ok(subnet)
+    }
+
+    /// The block of the same prefix length right after this one, or `None`
+    /// past the end of the address space.
+    pub fn next_subnet(&self) -> Option<Ipv6Subnet> {
+        let size = 1u128.checked_shl(128 - self.prefix_length as u32)?;
+        let next = u128::from(self.network).checked_add(size)?;
+        Self::new(Ipv6Addr::from(next), self.prefix_length).ok()
+    }
+
+    /// The block of the same prefix length right before this one, or `None`
+    /// before the start of the address space.
+    pub fn prev_subnet(&self) -> Option<Ipv6Subnet> {
+        let size = 1u128.checked_shl(128 - self.prefix_length as u32)?;
+        let prev = u128::from(self.network).checked_sub(size)?;
+        Self::new(Ipv6Addr::from(prev), self.prefix_length).ok()
+    }
+
+    /// The `n`th host, counting from 0, or `None` past the last address. The
+    /// Subnet-Router anycast address (the network address) is skipped except
+    /// in a /127 or /128.
+    pub fn nth_host(&self, n: u128) -> Option<Ipv6Addr> {
+        let offset = n.checked_add(u128::from(self.prefix_length < 127))?;
+        let span = u128::from(self.last) - u128::from(self.network);
+        (offset <= span).then(|| Ipv6Addr::from(u128::from(self.network) + offset))
+    }
+
+    /// Re-render every address string from the typed addresses in
+    /// `format`: the compressed network and last address and the full forms
+    /// and hextets in its case, plus the styled fields for a style other
+    /// than compressed. The default (compressed, lowercase) clears any
+    /// previously applied format.
+    pub fn apply_style(&mut self, format: impl Into<Ipv6Format>) {
+        let format = format.into();
+        if let Some((addr, prefix)) = self.input.split_once('/')
+            && let Ok(addr) = addr.parse::<Ipv6Addr>()
+        {
+            self.input = format!("{}/{}", format.compressed(&addr), prefix);
+        }
+        self.hextets = self.network.segments().map(|s| format.hextet(s)).to_vec();
+        self.network_address_full = format.full(&self.network);
+        self.last_address_full = format.full(&self.last);
+        self.case = (format.case == Ipv6Case::Upper).then_some(Ipv6Case::Upper);
+        if format.style == Ipv6Style::Compressed {
+            self.style = None;
+            self.network_address_styled = None;
+            self.last_address_styled = None;
+        } else {
+            self.style = Some(format.style);
+            self.network_address_styled = Some(format.format(&self.network));
+            self.last_address_styled = Some(format.format(&self.last));
+        }
+    }
+
+    /// The format last applied with [`Ipv6Subnet::apply_style`].
+    pub fn format(&self) -> Ipv6Format {
+        Ipv6Format {
+            style: self.style.unwrap_or_default(),
+            case: self.case.unwrap_or_default(),
+        }
+    }
+
+    /// Render `addr` compressed, in this subnet's case.
+    pub fn render(&self, addr: &Ipv6Addr) -> String {
+        Ipv6Format::from(self.case.unwrap_or_default()).compressed(addr)
+    }
+
+    /// The network address, compressed, in this subnet's case.
+    pub fn network_address(&self) -> String {
+        self.render(&self.network)
     }
 
-    fn format_full(addr: &Ipv6Addr) -> String {
-        let s = addr.segments();
-        format!(
-            "{:04x}:{:04x}:{:04x}:{:04x}:{:04x}:{:04x}:{:04x}:{:04x}",
-            s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]
-        )
+    /// The last address, compressed, in this subnet's case.
+    pub fn last_address(&self) -> String {
+        self.render(&self.last)
+    }
+
+    /// `network_address/prefix_length`, in this subnet's case.
+    pub fn cidr(&self) -> String {
+        format!("{}/{}", self.network_address(), self.prefix_length)
+    }
+
+    /// Report `total_addresses` as an exact decimal even above 2^64, where it
+    /// is normally abbreviated to `2^N`.
+    pub fn apply_decimal_total(&mut self) {
+        self.total_addresses = pow2_decimal(128 - self.prefix_length);
     }
 
     fn determine_address_type(addr: &Ipv6Addr) -> String {
@@ -108,6 +509,10 @@ impl Ipv6Subnet {
             "Unspecified (RFC 4291)".to_string()
         } else if addr.is_multicast() {
             "Multicast (RFC 4291)".to_string()
+        } else if addr.to_ipv4_mapped().is_some() {
+            "IPv4-Mapped (RFC 4291)".to_string()
+        } else if Self::sixto4_ipv4(addr).is_some() {
+            "6to4 (RFC 3056)".to_string()
         } else if Self::is_link_local(addr) {
             "Link-Local Unicast (RFC 4291)".to_string()
         } else if Self::is_unique_local(addr) {
@@ -121,6 +526,13 @@ impl Ipv6Subnet {
         }
     }
 
+    /// The IPv4 address a 6to4 (`2002::/16`) address carries after its
+    /// first hextet.
+    fn sixto4_ipv4(addr: &Ipv6Addr) -> Option<Ipv4Addr> {
+        let bits = u128::from(*addr);
+        (bits >> 112 == 0x2002).then(|| Ipv4Addr::from((bits >> 80) as u32))
+    }
+
     fn is_documentation(addr: &Ipv6Addr) -> bool {
         let segments = addr.segments();
         segments[0] == 0x2001 && segments[1] == 0x0db8
@@ -161,6 +573,128 @@ mod tests {
         assert_eq!(subnet.address_type, "Documentation (RFC 3849)");
     }
 
+    #[test]
+    fn test_ipv4_mapped() {
+        let subnet = Ipv6Subnet::from_cidr("::ffff:192.168.1.1/128").unwrap();
+        assert_eq!(subnet.network.to_string(), "::ffff:192.168.1.1");
+        assert_eq!(subnet.address_type, "IPv4-Mapped (RFC 4291)");
+        assert_eq!(subnet.ipv4_mapped.as_deref(), Some("192.168.1.1"));
+        assert!(subnet.canonical_input);
+
+        // The whole range, and networks inside it
+        let subnet = Ipv6Subnet::from_cidr("::ffff:0:0/96").unwrap();
+        assert_eq!(subnet.address_type, "IPv4-Mapped (RFC 4291)");
+        assert_eq!(subnet.ipv4_mapped.as_deref(), Some("0.0.0.0"));
+        let subnet = Ipv6Subnet::from_cidr("::ffff:10.1.2.3/120").unwrap();
+        assert_eq!(subnet.ipv4_mapped.as_deref(), Some("10.1.2.0"));
+
+        // Wider than the range, or outside it
+        let subnet = Ipv6Subnet::from_cidr("::ffff:0:0/95").unwrap();
+        assert_eq!(subnet.ipv4_mapped, None);
+        let subnet = Ipv6Subnet::from_cidr("2001:db8::/64").unwrap();
+        assert_eq!(subnet.ipv4_mapped, None);
+        let subnet = Ipv6Subnet::from_cidr("::1/128").unwrap();
+        assert_eq!(subnet.address_type, "Loopback (RFC 4291)");
+        assert_eq!(subnet.ipv4_mapped, None);
+    }
+
+    #[test]
+    fn test_sixto4() {
+        let subnet = Ipv6Subnet::from_cidr("2002:c000:0200::/48").unwrap();
+        assert_eq!(subnet.address_type, "6to4 (RFC 3056)");
+        assert_eq!(subnet.sixto4_ipv4.as_deref(), Some("192.0.2.0"));
+        assert_eq!(subnet.ipv4_mapped, None);
+
+        // The whole range carries the zero address
+        let subnet = Ipv6Subnet::from_cidr("2002::/16").unwrap();
+        assert_eq!(subnet.address_type, "6to4 (RFC 3056)");
+        assert_eq!(subnet.sixto4_ipv4.as_deref(), Some("0.0.0.0"));
+
+        // Site subnets keep the embedded address
+        let subnet = Ipv6Subnet::from_cidr("2002:c000:0201:1::/64").unwrap();
+        assert_eq!(subnet.sixto4_ipv4.as_deref(), Some("192.0.2.1"));
+
+        // Wider than the range, or outside it
+        let subnet = Ipv6Subnet::from_cidr("2000::/3").unwrap();
+        assert_eq!(subnet.sixto4_ipv4, None);
+        let subnet = Ipv6Subnet::from_cidr("2001:db8::/32").unwrap();
+        assert_eq!(subnet.sixto4_ipv4, None);
+    }
+
+    #[test]
+    fn test_next_and_prev_subnet() {
+        let subnet = Ipv6Subnet::from_cidr("2001:db8:1::/48").unwrap();
+        assert_eq!(subnet.next_subnet().unwrap().input, "2001:db8:2::/48");
+        assert_eq!(subnet.prev_subnet().unwrap().input, "2001:db8::/48");
+
+        assert!(
+            Ipv6Subnet::from_cidr("::/8")
+                .unwrap()
+                .prev_subnet()
+                .is_none()
+        );
+        assert!(
+            Ipv6Subnet::from_cidr("ff00::/8")
+                .unwrap()
+                .next_subnet()
+                .is_none()
+        );
+        let last = Ipv6Subnet::from_cidr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128").unwrap();
+        assert!(last.next_subnet().is_none());
+        let all = Ipv6Subnet::from_cidr("::/0").unwrap();
+        assert!(all.next_subnet().is_none() && all.prev_subnet().is_none());
+    }
+
+    #[test]
+    fn test_expand_compress_round_trip() {
+        let expanded = expand_ipv6("2001:db8::1").unwrap();
+        assert_eq!(expanded.expanded, "2001:0db8:0000:0000:0000:0000:0000:0001");
+        let compressed = compress_ipv6(&expanded.expanded).unwrap();
+        assert_eq!(compressed.compressed, "2001:db8::1");
+        assert_eq!(compressed.input, expanded.expanded);
+
+        assert_eq!(
+            expand_ipv6("::").unwrap().expanded,
+            "0000:0000:0000:0000:0000:0000:0000:0000"
+        );
+        assert_eq!(
+            compress_ipv6("2001:DB8:0:0:0:0:0:0").unwrap().compressed,
+            "2001:db8::"
+        );
+        assert!(expand_ipv6("2001:db8::g").is_err());
+        assert!(compress_ipv6("10.0.0.1").is_err());
+    }
+
+    #[test]
+    fn test_compress_zero_run_placement() {
+        // Longest run wins, wherever it is
+        assert_eq!(
+            compress_ipv6("2001:0:0:1:0:0:0:1").unwrap().compressed,
+            "2001:0:0:1::1"
+        );
+        // Leftmost run wins a tie
+        assert_eq!(
+            compress_ipv6("2001:db8:0:0:1:0:0:1").unwrap().compressed,
+            "2001:db8::1:0:0:1"
+        );
+        // A single zero group is never compressed
+        assert_eq!(
+            compress_ipv6("2001:db8:0:1:1:1:1:1").unwrap().compressed,
+            "2001:db8:0:1:1:1:1:1"
+        );
+    }
+
+    #[test]
+    fn test_bare_address() {
+        let subnet = Ipv6Subnet::from_addr_or_cidr("2001:db8::1").unwrap();
+        assert_eq!(subnet.prefix_length, 128);
+        assert!(subnet.canonical_input);
+
+        let subnet = Ipv6Subnet::from_addr_or_cidr("2001:DB8::1").unwrap();
+        assert!(!subnet.canonical_input);
+        assert!(Ipv6Subnet::from_addr_or_cidr("2001:db8::1/").is_err());
+    }
+
     #[test]
     fn test_ipv6_subnet_128() {
         let subnet = Ipv6Subnet::from_cidr("::1/128").unwrap();
@@ -241,4 +775,186 @@ mod tests {
         assert!(json["last_address_full"].is_string());
         assert_eq!(json["prefix_length"], 32);
     }
+
+    #[test]
+    fn test_format_ipv6_styles() {
+        let addr: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        assert_eq!(format_ipv6(&addr, Ipv6Style::Compressed), "2001:db8::1");
+        assert_eq!(
+            format_ipv6(&addr, Ipv6Style::Expanded),
+            "2001:db8:0:0:0:0:0:1"
+        );
+        assert_eq!(
+            format_ipv6(&addr, Ipv6Style::Full),
+            "2001:0db8:0000:0000:0000:0000:0000:0001"
+        );
+        assert_eq!(
+            format_ipv6(&addr, Ipv6Style::Nibble),
+            "2.0.0.1:0.d.b.8:0.0.0.0:0.0.0.0:0.0.0.0:0.0.0.0:0.0.0.0:0.0.0.1"
+        );
+    }
+
+    #[test]
+    fn test_apply_style() {
+        let mut subnet = Ipv6Subnet::from_cidr("2001:db8::/32").unwrap();
+        subnet.apply_style(Ipv6Style::Expanded);
+        let json = serde_json::to_value(&subnet).unwrap();
+        assert_eq!(json["style"], "expanded");
+        assert_eq!(json["network_address_styled"], "2001:db8:0:0:0:0:0:0");
+        assert_eq!(json["network_address"], "2001:db8::");
+
+        subnet.apply_style(Ipv6Style::Compressed);
+        let json = serde_json::to_value(&subnet).unwrap();
+        assert!(json.get("style").is_none());
+        assert!(json.get("network_address_styled").is_none());
+    }
+
+    #[test]
+    fn test_apply_upper_case() {
+        let mut subnet = Ipv6Subnet::from_cidr("2001:db8:abcd::/48").unwrap();
+        subnet.apply_style(Ipv6Format {
+            style: Ipv6Style::Compressed,
+            case: Ipv6Case::Upper,
+        });
+        let json = serde_json::to_value(&subnet).unwrap();
+        assert_eq!(json["case"], "upper");
+        assert_eq!(json["network_address"], "2001:DB8:ABCD::");
+        assert_eq!(
+            json["last_address"],
+            "2001:DB8:ABCD:FFFF:FFFF:FFFF:FFFF:FFFF"
+        );
+        assert!(json.get("network_address_styled").is_none());
+        assert_eq!(subnet.cidr(), "2001:DB8:ABCD::/48");
+        assert_eq!(
+            json["last_address_full"],
+            "2001:0DB8:ABCD:FFFF:FFFF:FFFF:FFFF:FFFF"
+        );
+        assert_eq!(json["hextets"][2], "ABCD");
+
+        subnet.apply_style(Ipv6Format {
+            style: Ipv6Style::Nibble,
+            case: Ipv6Case::Upper,
+        });
+        assert!(
+            subnet
+                .network_address_styled
+                .as_deref()
+                .unwrap()
+                .starts_with("2.0.0.1:0.D.B.8")
+        );
+
+        // Back to the default restores lowercase everywhere
+        subnet.apply_style(Ipv6Style::Compressed);
+        assert_eq!(subnet.hextets[2], "abcd");
+        assert!(subnet.case.is_none());
+        assert!(subnet.network_address_styled.is_none());
+        assert_eq!(subnet.network_address(), "2001:db8:abcd::");
+    }
+
+    #[test]
+    fn test_with_format_matches_apply_style() {
+        let upper = Ipv6Format::from(Ipv6Case::Upper);
+        let built = Ipv6Subnet::with_format("2001:db8:abcd::".parse().unwrap(), 48, upper).unwrap();
+        let mut applied = Ipv6Subnet::from_cidr("2001:db8:abcd::/48").unwrap();
+        applied.apply_style(upper);
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&applied).unwrap()
+        );
+        assert_eq!(built.format(), upper);
+    }
+
+    #[test]
+    fn test_compressed_matches_display() {
+        let lower = Ipv6Format::default();
+        let upper = Ipv6Format::from(Ipv6Case::Upper);
+        for addr in [
+            "::",
+            "::1",
+            "1::",
+            "2001:db8::1",
+            "2001:db8:0:1:0:0:0:1",
+            "2001:0:0:1:0:0:0:1",
+            "2001:db8:0:0:1:0:0:1",
+            "2001:db8:1:1:1:1:1:1",
+            "1:0:1:0:1:0:1:0",
+            "fe80::abcd:0:0:1",
+            "::ffff:192.0.2.1",
+            "::192.0.2.1",
+        ] {
+            let addr: Ipv6Addr = addr.parse().unwrap();
+            assert_eq!(lower.compressed(&addr), addr.to_string());
+            assert_eq!(
+                upper.compressed(&addr),
+                addr.to_string().to_ascii_uppercase()
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonical_input_detection() {
+        for cidr in [
+            "2001:db8::1/64",
+            "::/0",
+            "::1/128",
+            "fe80::/10",
+            "2001:db8:0:1::/64",
+            "::ffff:192.0.2.1/128",
+        ] {
+            let subnet = Ipv6Subnet::from_cidr(cidr).unwrap();
+            assert!(subnet.canonical_input, "{} should be canonical", cidr);
+            assert!(Ipv6Subnet::from_cidr_canonical(cidr).is_ok());
+        }
+
+        for (cidr, canonical) in [
+            // uppercase
+            ("2001:DB8::1/64", "2001:db8::1/64"),
+            // not compressed
+            ("2001:db8:0:0:0:0:0:1/64", "2001:db8::1/64"),
+            // leading zeros
+            ("2001:0db8::0001/64", "2001:db8::1/64"),
+            // a single zero group must not be compressed
+            ("2001:db8::1:1:1:1:1/64", "2001:db8:0:1:1:1:1:1/64"),
+            // the longest zero run must be the one compressed
+            ("2001::1:0:0:0:1/64", "2001:0:0:1::1/64"),
+            // on a tie, the first run is compressed
+            ("2001:db8:0:0:1::1/64", "2001:db8::1:0:0:1/64"),
+        ] {
+            let subnet = Ipv6Subnet::from_cidr(cidr).unwrap();
+            assert!(!subnet.canonical_input, "{} should not be canonical", cidr);
+            match Ipv6Subnet::from_cidr_canonical(cidr) {
+                Err(IpCalcError::NonCanonicalIpv6 { canonical: c, .. }) => {
+                    assert_eq!(c, canonical)
+                }
+                other => panic!("expected NonCanonicalIpv6 for {}, got {:?}", cidr, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_decimal_totals() {
+        assert_eq!(pow2_decimal(0), "1");
+        assert_eq!(pow2_decimal(64), "18446744073709551616");
+        assert_eq!(pow2_decimal(127), "170141183460469231731687303715884105728");
+        assert_eq!(pow2_decimal(128), "340282366920938463463374607431768211456");
+
+        let mut subnet = Ipv6Subnet::from_cidr("::/0").unwrap();
+        assert_eq!(subnet.total_addresses, "2^128");
+        subnet.apply_decimal_total();
+        assert_eq!(
+            subnet.total_addresses,
+            "340282366920938463463374607431768211456"
+        );
+
+        let mut subnet = Ipv6Subnet::from_cidr("8000::/1").unwrap();
+        subnet.apply_decimal_total();
+        assert_eq!(
+            subnet.total_addresses,
+            "170141183460469231731687303715884105728"
+        );
+
+        let mut subnet = Ipv6Subnet::from_cidr("2001:db8::1/128").unwrap();
+        subnet.apply_decimal_total();
+        assert_eq!(subnet.total_addresses, "1");
+    }
 }
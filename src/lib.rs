@@ -5,24 +5,58 @@
 //! range-to-CIDR conversion, route summarization, and IPAM.
 
 // Core calculation modules
+pub mod acl;
+pub mod addresses;
+pub mod arithmetic;
+pub mod available;
 pub mod batch;
+pub mod complement;
 pub mod contains;
+pub mod covers;
+pub mod diff;
+pub mod dns;
+pub mod estimate;
+pub mod eui64;
+pub mod exclude;
+pub mod extract;
+pub mod free_space;
 pub mod from_range;
+pub mod gaps;
+pub mod grouped_summary;
+pub mod hosts;
+pub mod ifid;
 pub mod ipv4;
 pub mod ipv6;
+pub mod lookup;
+pub mod lpm;
+pub mod merge;
+pub mod normalize;
+pub mod overlap;
+pub mod reverse_dns;
+pub mod snapshot;
+pub mod special_purpose;
 pub mod subnet_generator;
 pub mod summarize;
+pub mod supernet;
+pub mod tree;
+pub mod usable;
+pub mod verify;
+pub mod vlsm;
+pub mod walk;
+pub mod zonefile;
 
 // I/O and interface modules
 pub mod api;
 pub mod cli;
 pub mod ipam_api;
 pub mod output;
+pub mod server;
 
 // IPAM persistence layer
 pub mod ipam;
 
 // Infrastructure
+pub mod client_ip;
 pub mod config;
 pub mod error;
 pub mod logging;
@@ -35,11 +69,30 @@ pub mod tui;
 pub mod mcp;
 
 // Public API re-exports
+pub use acl::AclWildcardResult;
+pub use addresses::AddressCountResult;
+pub use available::{AllocateResult, AvailableResult};
 pub use batch::{BatchResult, process_batch, process_batch_with_limit};
-pub use contains::ContainsResult;
+pub use complement::{Ipv4ComplementResult, Ipv6ComplementResult};
+pub use contains::{ContainsResult, SubnetContainsResult};
+pub use covers::CoverageResult;
+pub use eui64::Eui64Result;
+pub use exclude::{Ipv4ExcludeResult, Ipv6ExcludeResult};
 pub use from_range::{Ipv4FromRangeResult, Ipv6FromRangeResult};
+pub use gaps::GapsResult;
+pub use ifid::InterfaceIdResult;
 pub use ipv4::Ipv4Subnet;
-pub use ipv6::Ipv6Subnet;
+pub use ipv6::{Ipv6FormatResult, Ipv6Subnet};
 pub use logging::{LogConfig, init_logging};
+pub use lookup::LookupResult;
+pub use lpm::LpmResult;
+pub use normalize::NormalizeResult;
 pub use output::{OutputFormat, OutputWriter};
+pub use overlap::OverlapResult;
+pub use reverse_dns::ReverseDnsResult;
 pub use summarize::{Ipv4SummaryResult, Ipv6SummaryResult};
+pub use supernet::SupernetResult;
+pub use tree::SplitTree;
+pub use usable::UsabilityResult;
+pub use vlsm::VlsmResult;
+pub use zonefile::ZoneFileResult;
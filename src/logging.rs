@@ -13,6 +13,8 @@ pub struct LogConfig {
     pub level: Level,
     pub file_path: Option<String>,
     pub json_format: bool,
+    /// Write to stderr instead of stdout when there is no log file
+    pub stderr: bool,
 }
 
 impl Default for LogConfig {
@@ -21,6 +23,7 @@ impl Default for LogConfig {
             level: Level::INFO,
             file_path: None,
             json_format: false,
+            stderr: false,
         }
     }
 }
@@ -42,6 +45,11 @@ impl LogConfig {
         self.json_format = json;
         self
     }
+
+    pub fn with_stderr(mut self, stderr: bool) -> Self {
+        self.stderr = stderr;
+        self
+    }
 }
 
 /// Initialize logging and return a guard that must be held for the lifetime of the program.
@@ -62,6 +70,7 @@ pub fn init_logging(config: &LogConfig) -> WorkerGuard {
             let file_appender = tracing_appender::rolling::never(parent, filename);
             tracing_appender::non_blocking(file_appender)
         }
+        None if config.stderr => tracing_appender::non_blocking(std::io::stderr()),
         None => tracing_appender::non_blocking(std::io::stdout()),
     };
 
@@ -89,6 +98,18 @@ pub fn init_logging(config: &LogConfig) -> WorkerGuard {
     guard
 }
 
+/// Check that the log file can be opened for appending, creating it if needed.
+///
+/// [`init_logging`] panics on a file it cannot open; call this first to
+/// report the problem as an error instead.
+pub fn check_log_file(path: &str) -> std::io::Result<()> {
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map(drop)
+}
+
 pub fn parse_log_level(s: &str) -> Result<Level, String> {
     match s.to_lowercase().as_str() {
         "trace" => Ok(Level::TRACE),
@@ -0,0 +1,299 @@
+use crate::config::DEFAULTS;
+use crate::covers::{Block, format_block};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::Ipv6Subnet;
+use crate::summarize::prefix_mask;
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// Order in which matching CIDRs are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum LookupOrder {
+    /// Most specific (longest prefix) first, as longest-prefix match picks
+    LongestFirst,
+    /// Least specific (shortest prefix) first
+    ShortestFirst,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LookupMatch {
+    pub cidr: String,
+    pub prefix_length: u8,
+}
+
+/// Every CIDR in a list that overlaps a query address or block: those that
+/// contain it and, for a block query, those nested inside it.
+///
+/// `total_matches` counts every overlapping CIDR, even when `matches` was cut
+/// to the requested maximum.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LookupResult {
+    pub query: String,
+    pub order: LookupOrder,
+    pub total_matches: usize,
+    pub truncated: bool,
+    pub matches: Vec<LookupMatch>,
+}
+
+// ---------------------------------------------------------------------------
+// Prefix table
+// ---------------------------------------------------------------------------
+
+/// CIDRs bucketed by prefix length, each bucket sorted by network.
+///
+/// A lookup masks the query once per shorter prefix length and binary
+/// searches that bucket, and takes the sorted run inside the query from each
+/// longer one. Walking the buckets forwards or backwards yields the matches
+/// in either order with ties already broken by ascending network.
+#[derive(Debug, Clone)]
+pub struct PrefixTable {
+    v4: Vec<Vec<u128>>,
+    v6: Vec<Vec<u128>>,
+}
+
+impl PrefixTable {
+    /// Build a table from IPv4 and/or IPv6 CIDRs. Bare addresses are host
+    /// routes, host bits are cleared and duplicates collapse to one entry.
+    pub fn new(cidrs: &[String]) -> Result<Self> {
+        let mut v4 = vec![Vec::new(); 33];
+        let mut v6 = vec![Vec::new(); 129];
+        for cidr in cidrs {
+            if cidr.contains(':') {
+                let subnet = Ipv6Subnet::from_addr_or_cidr(cidr)?;
+                v6[subnet.prefix_length as usize].push(u128::from(subnet.network));
+            } else {
+                let subnet = Ipv4Subnet::from_addr_or_cidr(cidr)?;
+                v4[subnet.prefix_length as usize].push(u32::from(subnet.network) as u128);
+            }
+        }
+        for bucket in v4.iter_mut().chain(v6.iter_mut()) {
+            bucket.sort_unstable();
+            bucket.dedup();
+        }
+        Ok(Self { v4, v6 })
+    }
+
+    /// Networks overlapping the `(network, prefix)` query block, as blocks
+    /// in the given order. `bits` is 32 to search the IPv4 entries and 128
+    /// for IPv6.
+    pub fn matches(
+        &self,
+        (query, query_prefix): Block,
+        bits: u8,
+        order: LookupOrder,
+    ) -> Vec<Block> {
+        let buckets = if bits == 32 { &self.v4 } else { &self.v6 };
+        let last = query | !prefix_mask(query_prefix, bits) & prefix_mask(bits, bits);
+        let bucket_hits = |prefix: u8| -> Vec<Block> {
+            let bucket = &buckets[prefix as usize];
+            if prefix <= query_prefix {
+                let network = query & prefix_mask(prefix, bits);
+                match bucket.binary_search(&network) {
+                    Ok(_) => vec![(network, prefix)],
+                    Err(_) => Vec::new(),
+                }
+            } else {
+                let start = bucket.partition_point(|&n| n < query);
+                let end = bucket.partition_point(|&n| n <= last);
+                bucket[start..end].iter().map(|&n| (n, prefix)).collect()
+            }
+        };
+        match order {
+            LookupOrder::LongestFirst => (0..=bits).rev().flat_map(bucket_hits).collect(),
+            LookupOrder::ShortestFirst => (0..=bits).flat_map(bucket_hits).collect(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+pub fn lookup(
+    query: &str,
+    cidrs: &[String],
+    order: LookupOrder,
+    max_results: Option<usize>,
+) -> Result<LookupResult> {
+    lookup_with_limit(
+        query,
+        cidrs,
+        order,
+        max_results,
+        DEFAULTS.max_summarize_inputs,
+    )
+}
+
+/// List the CIDRs in `cidrs` that overlap `query` (an address or CIDR), most
+/// specific first unless `order` says otherwise, keeping at most
+/// `max_results` of them. Only CIDRs of the query's family can match.
+pub fn lookup_with_limit(
+    query: &str,
+    cidrs: &[String],
+    order: LookupOrder,
+    max_results: Option<usize>,
+    max_inputs: usize,
+) -> Result<LookupResult> {
+    if cidrs.is_empty() {
+        return Err(IpCalcError::EmptyCidrList);
+    }
+    if cidrs.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: cidrs.len(),
+            limit: max_inputs,
+        });
+    }
+    let (block, bits) = if query.contains(':') {
+        let subnet = Ipv6Subnet::from_addr_or_cidr(query)?;
+        ((u128::from(subnet.network), subnet.prefix_length), 128)
+    } else {
+        let subnet = Ipv4Subnet::from_addr_or_cidr(query)?;
+        (
+            (u32::from(subnet.network) as u128, subnet.prefix_length),
+            32,
+        )
+    };
+
+    let table = PrefixTable::new(cidrs)?;
+    let all = table.matches(block, bits, order);
+    let total_matches = all.len();
+    let keep = max_results.unwrap_or(total_matches).min(total_matches);
+    let matches = all[..keep]
+        .iter()
+        .map(|&(network, prefix)| LookupMatch {
+            cidr: format_block((network, prefix), bits),
+            prefix_length: prefix,
+        })
+        .collect();
+
+    Ok(LookupResult {
+        query: format_block(block, bits),
+        order,
+        total_matches,
+        truncated: keep < total_matches,
+        matches,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidrs(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn matched(result: &LookupResult) -> Vec<&str> {
+        result.matches.iter().map(|m| m.cidr.as_str()).collect()
+    }
+
+    #[test]
+    fn test_nested_order_both_ways() {
+        let list = cidrs(&["10.0.0.0/8", "10.1.2.0/24", "10.1.0.0/16", "10.2.0.0/16"]);
+        let longest = lookup("10.1.2.3", &list, LookupOrder::LongestFirst, None).unwrap();
+        assert_eq!(
+            matched(&longest),
+            ["10.1.2.0/24", "10.1.0.0/16", "10.0.0.0/8"]
+        );
+        assert_eq!(longest.total_matches, 3);
+        assert!(!longest.truncated);
+
+        let shortest = lookup("10.1.2.3", &list, LookupOrder::ShortestFirst, None).unwrap();
+        assert_eq!(
+            matched(&shortest),
+            ["10.0.0.0/8", "10.1.0.0/16", "10.1.2.0/24"]
+        );
+    }
+
+    #[test]
+    fn test_truncation_keeps_total() {
+        let list = cidrs(&["0.0.0.0/0", "10.0.0.0/8", "10.1.0.0/16", "10.1.2.3"]);
+        let result = lookup("10.1.2.3", &list, LookupOrder::LongestFirst, Some(2)).unwrap();
+        assert_eq!(matched(&result), ["10.1.2.3/32", "10.1.0.0/16"]);
+        assert_eq!(result.total_matches, 4);
+        assert!(result.truncated);
+
+        let result = lookup("10.1.2.3", &list, LookupOrder::ShortestFirst, Some(1)).unwrap();
+        assert_eq!(matched(&result), ["0.0.0.0/0"]);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn test_duplicates_and_siblings() {
+        // Spellings of the same network collapse; siblings never both match
+        let list = cidrs(&["10.1.2.77/24", "10.1.2.0/24", "10.1.3.0/24", "10.1.2.0/23"]);
+        let result = lookup("10.1.2.9", &list, LookupOrder::LongestFirst, None).unwrap();
+        assert_eq!(matched(&result), ["10.1.2.0/24", "10.1.2.0/23"]);
+        let result = lookup("10.1.3.9", &list, LookupOrder::LongestFirst, None).unwrap();
+        assert_eq!(matched(&result), ["10.1.3.0/24", "10.1.2.0/23"]);
+    }
+
+    #[test]
+    fn test_block_query_ties_by_network() {
+        // Siblings inside the query share a prefix length and sort by network
+        let list = cidrs(&[
+            "10.1.3.0/24",
+            "10.0.0.0/8",
+            "10.1.1.0/24",
+            "10.1.0.0/16",
+            "10.1.2.0/25",
+            "10.1.2.128/25",
+            "10.2.0.0/24",
+        ]);
+        let longest = lookup("10.1.0.0/16", &list, LookupOrder::LongestFirst, None).unwrap();
+        assert_eq!(
+            matched(&longest),
+            [
+                "10.1.2.0/25",
+                "10.1.2.128/25",
+                "10.1.1.0/24",
+                "10.1.3.0/24",
+                "10.1.0.0/16",
+                "10.0.0.0/8",
+            ]
+        );
+        let shortest = lookup("10.1.0.0/16", &list, LookupOrder::ShortestFirst, Some(4)).unwrap();
+        assert_eq!(
+            matched(&shortest),
+            ["10.0.0.0/8", "10.1.0.0/16", "10.1.1.0/24", "10.1.3.0/24"]
+        );
+        assert_eq!(shortest.total_matches, 6);
+        assert!(shortest.truncated);
+    }
+
+    #[test]
+    fn test_families_and_errors() {
+        let list = cidrs(&["2001:db8::/32", "2001:db8:1::/48", "10.0.0.0/8"]);
+        let result = lookup("2001:db8:1::5", &list, LookupOrder::LongestFirst, None).unwrap();
+        assert_eq!(matched(&result), ["2001:db8:1::/48", "2001:db8::/32"]);
+        let result = lookup("192.0.2.1", &list, LookupOrder::LongestFirst, None).unwrap();
+        assert_eq!(result.total_matches, 0);
+
+        assert!(lookup("nope", &list, LookupOrder::LongestFirst, None).is_err());
+        assert!(
+            lookup(
+                "10.0.0.1",
+                &cidrs(&["10.0.0.0/33"]),
+                LookupOrder::LongestFirst,
+                None
+            )
+            .is_err()
+        );
+        assert!(lookup("10.0.0.1", &[], LookupOrder::LongestFirst, None).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::Ipv6Subnet;
+use crate::lookup::{LookupOrder, PrefixTable};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// The most specific CIDR in a list that contains an address, as a router's
+/// longest-prefix-match lookup would pick it. `matched` is `None` when no
+/// CIDR contains the address.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LpmResult {
+    pub address: String,
+    pub candidate_count: usize,
+    pub matched: Option<String>,
+    pub prefix_length: Option<u8>,
+}
+
+// ---------------------------------------------------------------------------
+// Matching
+// ---------------------------------------------------------------------------
+
+/// The CIDR in `cidrs` with the longest prefix containing `address`, or
+/// `None` if none contains it. Equal prefixes keep the first one listed.
+///
+/// The match is the first [`PrefixTable`] lookup result, most specific first,
+/// so `lpm` and `lookup` always agree.
+pub fn longest_prefix_match_v4(address: &str, cidrs: &[String]) -> Result<Option<Ipv4Subnet>> {
+    let trimmed = address.trim();
+    let addr = Ipv4Addr::from_str(trimmed)
+        .map_err(|_| IpCalcError::InvalidIpv4Address(trimmed.to_string()))?;
+    let subnets = cidrs
+        .iter()
+        .map(|c| Ipv4Subnet::from_addr_or_cidr(c))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(&(network, prefix)) = PrefixTable::new(cidrs)?
+        .matches((u32::from(addr) as u128, 32), 32, LookupOrder::LongestFirst)
+        .first()
+    else {
+        return Ok(None);
+    };
+    // Spellings of the same block share one table entry; keep the first listed
+    Ok(subnets
+        .into_iter()
+        .find(|s| u32::from(s.network) as u128 == network && s.prefix_length == prefix))
+}
+
+/// IPv6 counterpart of [`longest_prefix_match_v4`].
+pub fn longest_prefix_match_v6(address: &str, cidrs: &[String]) -> Result<Option<Ipv6Subnet>> {
+    let trimmed = address.trim();
+    let addr = Ipv6Addr::from_str(trimmed)
+        .map_err(|_| IpCalcError::InvalidIpv6Address(trimmed.to_string()))?;
+    let subnets = cidrs
+        .iter()
+        .map(|c| Ipv6Subnet::from_addr_or_cidr(c))
+        .collect::<Result<Vec<_>>>()?;
+    let Some(&(network, prefix)) = PrefixTable::new(cidrs)?
+        .matches((u128::from(addr), 128), 128, LookupOrder::LongestFirst)
+        .first()
+    else {
+        return Ok(None);
+    };
+    Ok(subnets
+        .into_iter()
+        .find(|s| u128::from(s.network) == network && s.prefix_length == prefix))
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+fn check_inputs(cidrs: &[String], max_inputs: usize) -> Result<()> {
+    if cidrs.is_empty() {
+        return Err(IpCalcError::EmptyCidrList);
+    }
+    if cidrs.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: cidrs.len(),
+            limit: max_inputs,
+        });
+    }
+    Ok(())
+}
+
+pub fn lpm_ipv4(address: &str, cidrs: &[String]) -> Result<LpmResult> {
+    lpm_ipv4_with_limit(address, cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+/// Longest prefix match for an IPv4 address, at most `max_inputs` CIDRs.
+pub fn lpm_ipv4_with_limit(
+    address: &str,
+    cidrs: &[String],
+    max_inputs: usize,
+) -> Result<LpmResult> {
+    check_inputs(cidrs, max_inputs)?;
+    let best = longest_prefix_match_v4(address, cidrs)?;
+    Ok(LpmResult {
+        address: address.trim().to_string(),
+        candidate_count: cidrs.len(),
+        matched: best
+            .as_ref()
+            .map(|s| format!("{}/{}", s.network, s.prefix_length)),
+        prefix_length: best.map(|s| s.prefix_length),
+    })
+}
+
+pub fn lpm_ipv6(address: &str, cidrs: &[String]) -> Result<LpmResult> {
+    lpm_ipv6_with_limit(address, cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+/// Longest prefix match for an IPv6 address, at most `max_inputs` CIDRs.
+pub fn lpm_ipv6_with_limit(
+    address: &str,
+    cidrs: &[String],
+    max_inputs: usize,
+) -> Result<LpmResult> {
+    check_inputs(cidrs, max_inputs)?;
+    let best = longest_prefix_match_v6(address, cidrs)?;
+    Ok(LpmResult {
+        address: address.trim().to_string(),
+        candidate_count: cidrs.len(),
+        matched: best
+            .as_ref()
+            .map(|s| format!("{}/{}", s.network, s.prefix_length)),
+        prefix_length: best.map(|s| s.prefix_length),
+    })
+}
+
+pub fn lpm(address: &str, cidrs: &[String]) -> Result<LpmResult> {
+    lpm_with_limit(address, cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+/// Longest prefix match for an address of either family, as used by `lpm`
+/// (alias `match`) and `POST /match`.
+///
+/// Every CIDR must share the address's family, so mixing IPv4 and IPv6 is
+/// rejected up front rather than failing to parse one of them.
+pub fn lpm_with_limit(address: &str, cidrs: &[String], max_inputs: usize) -> Result<LpmResult> {
+    let is_v6 = address.contains(':');
+    if let Some(cidr) = cidrs.iter().find(|c| c.contains(':') != is_v6) {
+        return Err(IpCalcError::AddressFamilyMismatch {
+            cidr: cidr.trim().to_string(),
+            address: address.trim().to_string(),
+        });
+    }
+    if is_v6 {
+        lpm_ipv6_with_limit(address, cidrs, max_inputs)
+    } else {
+        lpm_ipv4_with_limit(address, cidrs, max_inputs)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidrs(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_most_specific_wins() {
+        let list = cidrs(&["10.0.0.0/8", "10.1.2.0/24", "10.1.0.0/16"]);
+        let best = longest_prefix_match_v4("10.1.2.3", &list).unwrap().unwrap();
+        assert_eq!(best.network, Ipv4Addr::new(10, 1, 2, 0));
+        assert_eq!(best.prefix_length, 24);
+
+        let best = longest_prefix_match_v4("10.9.9.9", &list).unwrap().unwrap();
+        assert_eq!(best.prefix_length, 8);
+    }
+
+    #[test]
+    fn test_no_match_is_none() {
+        let list = cidrs(&["10.0.0.0/8", "172.16.0.0/12"]);
+        assert!(
+            longest_prefix_match_v4("192.0.2.1", &list)
+                .unwrap()
+                .is_none()
+        );
+        let result = lpm_ipv4("192.0.2.1", &list).unwrap();
+        assert_eq!(result.matched, None);
+        assert_eq!(result.candidate_count, 2);
+    }
+
+    #[test]
+    fn test_tie_keeps_first_listed() {
+        // Two spellings of the same /24: the first one listed is returned
+        let list = cidrs(&["10.1.2.99/24", "10.1.2.0/24", "10.0.0.0/8"]);
+        let best = longest_prefix_match_v4("10.1.2.3", &list).unwrap().unwrap();
+        assert_eq!(best.input, "10.1.2.99/24");
+    }
+
+    #[test]
+    fn test_agrees_with_lookup() {
+        use crate::lookup::lookup;
+        let list = cidrs(&["10.1.2.0/24", "0.0.0.0/0", "10.1.2.0/25", "10.1.0.0/16"]);
+        for address in ["10.1.2.3", "10.1.2.200", "10.1.9.9", "192.0.2.1"] {
+            let best = lpm_ipv4(address, &list).unwrap();
+            let all = lookup(address, &list, LookupOrder::LongestFirst, None).unwrap();
+            assert_eq!(
+                best.matched.as_deref(),
+                all.matches.first().map(|m| m.cidr.as_str()),
+                "{address}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ipv6_and_errors() {
+        let list = cidrs(&["2001:db8::/32", "2001:db8:1::/48", "::/0"]);
+        let result = lpm_ipv6("2001:db8:1::5", &list).unwrap();
+        assert_eq!(result.matched.as_deref(), Some("2001:db8:1::/48"));
+        assert_eq!(result.prefix_length, Some(48));
+        let result = lpm_ipv6("fe80::1", &list).unwrap();
+        assert_eq!(result.matched.as_deref(), Some("::/0"));
+
+        assert!(lpm_ipv4("10.0.0.1", &[]).is_err());
+        assert!(lpm_ipv4("not-an-ip", &cidrs(&["10.0.0.0/8"])).is_err());
+        assert!(lpm_ipv4("10.0.0.1", &cidrs(&["2001:db8::/32"])).is_err());
+    }
+
+    #[test]
+    fn test_lpm_either_family() {
+        let list = cidrs(&["10.0.0.0/8", "10.1.0.0/16", "10.1.2.0/24"]);
+        let result = lpm("10.1.2.3", &list).unwrap();
+        assert_eq!(result.matched.as_deref(), Some("10.1.2.0/24"));
+        assert_eq!(result.prefix_length, Some(24));
+        let result = lpm("192.0.2.1", &list).unwrap();
+        assert_eq!(result.matched, None);
+        let result = lpm("2001:db8::1", &cidrs(&["2001:db8::/32"])).unwrap();
+        assert_eq!(result.prefix_length, Some(32));
+
+        let mixed = cidrs(&["10.0.0.0/8", "2001:db8::/32"]);
+        let result = lpm("10.1.2.3", &mixed);
+        assert!(
+            matches!(result, Err(IpCalcError::AddressFamilyMismatch { .. })),
+            "expected AddressFamilyMismatch, got {:?}",
+            result
+        );
+        let result = lpm("2001:db8::1", &mixed);
+        assert!(matches!(
+            result,
+            Err(IpCalcError::AddressFamilyMismatch { ref cidr, .. }) if cidr == "10.0.0.0/8"
+        ));
+    }
+}
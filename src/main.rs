@@ -1,20 +1,77 @@
 use clap::{CommandFactory, Parser};
-use ipcalc::api::{RouterConfig, create_router};
-use ipcalc::batch::process_batch;
-use ipcalc::cli::{Cli, Commands};
-use ipcalc::config::{CliOverrides, ServerConfig};
-use ipcalc::contains::{check_ipv4_contains, check_ipv6_contains};
-use ipcalc::from_range::{from_range_ipv4, from_range_ipv6};
+use ipcalc::acl::acl_wildcard;
+use ipcalc::addresses::count_addresses;
+use ipcalc::api::ENDPOINTS;
+use ipcalc::arithmetic::add_ip;
+use ipcalc::available::{allocate_v4, allocate_v6};
+use ipcalc::batch::{BatchFilter, BatchResult, parse_batch_line, process_batch_with_options};
+use ipcalc::cli::{Cli, Commands, ConfigCommands, OutputFormatArg};
+use ipcalc::complement::{
+    Ipv4ComplementResult, Ipv6ComplementResult, complement_ipv4, complement_ipv6,
+};
+use ipcalc::config::DEFAULTS;
+use ipcalc::config::{CliOverrides, LoadedConfig, ServerConfig};
+use ipcalc::contains::{
+    check_contains_translated, check_ipv4_contains, check_ipv4_subnet_contains,
+    check_ipv6_contains, check_ipv6_subnet_contains,
+};
+use ipcalc::covers::check_coverage;
+use ipcalc::diff::{DiffResult, diff_cidr_sets, diff_json_patch};
+use ipcalc::dns::resolve_contains;
+use ipcalc::error::IpCalcError;
+use ipcalc::estimate::{estimate_from_range, estimate_split};
+use ipcalc::eui64::derive_eui64;
+use ipcalc::exclude::{Ipv4ExcludeResult, Ipv6ExcludeResult, exclude_ipv4, exclude_ipv6};
+use ipcalc::extract::{ExtractionStats, extract_cidrs};
+use ipcalc::free_space::{free_space_v4, free_space_v6};
+use ipcalc::from_range::{
+    Ipv4FromRangeResult, Ipv6FromRangeResult, from_range_ipv4, from_range_ipv6,
+};
+use ipcalc::gaps::{find_gaps_v4, find_gaps_v6};
+use ipcalc::hosts::{HostsResult, list_hosts};
+use ipcalc::ifid::split_interface_id;
 use ipcalc::ipv4::Ipv4Subnet;
-use ipcalc::ipv6::Ipv6Subnet;
-use ipcalc::logging::{LogConfig, init_logging, parse_log_level};
-use ipcalc::output::{CsvOutput, OutputFormat, OutputWriter, TextOutput};
-use ipcalc::subnet_generator::{count_subnets, generate_ipv4_subnets, generate_ipv6_subnets};
-use ipcalc::summarize::{summarize_ipv4, summarize_ipv6};
+use ipcalc::ipv6::{Ipv6Format, Ipv6Subnet, compress_ipv6, expand_ipv6};
+use ipcalc::logging::{LogConfig, check_log_file, init_logging, parse_log_level};
+use ipcalc::lookup::{LookupOrder, lookup};
+use ipcalc::lpm::lpm;
+use ipcalc::merge::{MergeResult, load_batch_result, merge_batch_results};
+use ipcalc::normalize::{NormalizeOptions, NormalizeResult, normalize_cidr_list};
+use ipcalc::output::{CsvOutput, EnvOutput, OutputFormat, OutputWriter, ResultKind, TextOutput};
+use ipcalc::overlap::find_overlaps;
+use ipcalc::reverse_dns::reverse_dns;
+use ipcalc::server::{PreparedServer, ServeOptions, prepare_server, startup_error};
+use ipcalc::snapshot::{CidrList, changes_since, save_snapshot};
+use ipcalc::special_purpose::special_purpose_blocks;
+use ipcalc::subnet_generator::{
+    DualStackPlan, Ipv4SubnetList, Ipv6SubnetList, count_subnets, generate_dualstack_subnets,
+    generate_ipv4_subnets_from, generate_ipv6_subnets_from, iter_ipv4_subnets_from,
+    iter_ipv6_subnets_from, nth_ipv4_subnet, nth_ipv6_subnet, page_count, plan_split, random_seed,
+    split_prefix_for_hosts,
+};
+use ipcalc::summarize::{
+    AddressSpace, Ipv4SummaryResult, Ipv6SummaryResult, SummaryBase, load_summary_base,
+    summarize_ipv4_with_options, summarize_ipv6_with_options, update_ipv4_summary,
+    update_ipv6_summary,
+};
+use ipcalc::supernet::{
+    find_common_supernet_v4, find_common_supernet_v6, find_supernet_v4, find_supernet_v6,
+};
+use ipcalc::tree::build_split_tree;
+use ipcalc::usable::{
+    UsabilityResult, check_ipv4_host, check_ipv4_usable, check_ipv6_host, nth_host_v4, nth_host_v6,
+};
+use ipcalc::validation::{mask_notation_to_cidr, with_host_prefix};
+use ipcalc::verify::{
+    verify_from_range_ipv4, verify_from_range_ipv6, verify_split_ipv4, verify_split_ipv6,
+};
+use ipcalc::vlsm::allocate_vlsm;
+use ipcalc::walk::{Ipv4WalkResult, walk_ipv4};
+use ipcalc::zonefile::generate_zonefile;
 use serde::Serialize;
 use std::io::{self, BufRead, Write};
 use std::net::SocketAddr;
-use tracing::{info, warn};
+use tracing::info;
 
 mod ipam_cli;
 
@@ -30,22 +87,282 @@ fn print_stdout(s: &str) {
     }
 }
 
+/// Print summary warnings to stderr; they are also part of the result.
+fn warn_summary(warnings: &[String]) {
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
+/// Collect summarize inputs from positional CIDRs or `--file`, optionally
+/// extracting them from routing-table style lines.
+fn load_summarize_inputs(
+    cidrs: Vec<String>,
+    file: Option<&str>,
+    extract: bool,
+    extract_regex: Option<String>,
+) -> Result<(Vec<String>, Option<ExtractionStats>), IpCalcError> {
+    let Some(path) = file else {
+        return Ok((cidrs, None));
+    };
+    let text = std::fs::read_to_string(path)?;
+
+    if extract || extract_regex.is_some() {
+        let extraction = extract_cidrs(&text, extract_regex.as_deref())?;
+        return Ok((extraction.cidrs, Some(extraction.stats)));
+    }
+
+    let cidrs = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    Ok((cidrs, None))
+}
+
+/// The IPv4 block next to `cidr` (after it when `next`, else before it).
+fn adjacent_ipv4(cidr: &str, next: bool) -> Result<Ipv4Subnet, IpCalcError> {
+    let subnet = Ipv4Subnet::from_addr_or_cidr(cidr)?;
+    let adjacent = if next {
+        subnet.next_subnet()
+    } else {
+        subnet.prev_subnet()
+    };
+    adjacent.ok_or_else(|| no_adjacent_subnet(&subnet.input, next))
+}
+
+/// The IPv6 block next to `cidr` (after it when `next`, else before it).
+fn adjacent_ipv6(cidr: &str, next: bool) -> Result<Ipv6Subnet, IpCalcError> {
+    let subnet = Ipv6Subnet::from_addr_or_cidr(cidr)?;
+    let adjacent = if next {
+        subnet.next_subnet()
+    } else {
+        subnet.prev_subnet()
+    };
+    adjacent.ok_or_else(|| no_adjacent_subnet(&subnet.input, next))
+}
+
+fn no_adjacent_subnet(cidr: &str, next: bool) -> IpCalcError {
+    IpCalcError::NoAdjacentSubnet {
+        cidr: cidr.to_string(),
+        direction: if next { "next" } else { "previous" }.to_string(),
+    }
+}
+
+/// Normalize a CIDR list read from `file` (or stdin), rewriting the file when
+/// `in_place` is set. The file is only touched once the whole list is valid.
+fn run_normalize(
+    file: Option<&str>,
+    options: NormalizeOptions,
+    in_place: bool,
+) -> Result<NormalizeResult, IpCalcError> {
+    let text = match file {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => io::read_to_string(io::stdin())?,
+    };
+    let result = normalize_cidr_list(&text, options)?;
+    if in_place && let Some(path) = file {
+        std::fs::write(path, result.to_text())?;
+    }
+    Ok(result)
+}
+
+/// List a subnet's hosts, warning on stderr when the list stops early.
+fn run_hosts(cidr: &str, max: Option<usize>) -> Result<HostsResult, IpCalcError> {
+    let result = list_hosts(cidr, max.unwrap_or(usize::MAX))?;
+    if result.truncated {
+        eprintln!(
+            "Warning: listed the first {} hosts of {}; it has more",
+            result.count, result.cidr
+        );
+    }
+    Ok(result)
+}
+
+/// Load and merge batch result files, warning on stderr about any conflicts.
+fn run_merge(files: &[String], dedupe: bool) -> Result<MergeResult, IpCalcError> {
+    let inputs = files
+        .iter()
+        .map(|path| load_batch_result(path).map(|batch| (path.clone(), batch)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let result = merge_batch_results(inputs, dedupe);
+    for conflict in &result.conflicts {
+        eprintln!(
+            "Warning: conflicting results for {} in {} and {} (kept the first)",
+            conflict.cidr, conflict.kept_from, conflict.conflicting_file
+        );
+    }
+    Ok(result)
+}
+
+/// Print an error to stderr, followed by guidance for the variant when `explain` is set.
+fn report_error(e: &IpCalcError, explain: bool) {
+    eprintln!("Error: {}", e);
+    if explain && let Some(text) = e.explanation() {
+        eprintln!();
+        for line in text.lines() {
+            eprintln!("  {}", line);
+        }
+    }
+}
+
 /// Handle a Result from a calculation: write output on success, print error and exit on failure.
-fn handle_result<T: Serialize + TextOutput + CsvOutput>(
+fn handle_result<T: Serialize + TextOutput + CsvOutput + EnvOutput + ResultKind>(
     writer: &OutputWriter,
     result: ipcalc::error::Result<T>,
     output_file: &Option<String>,
+    explain: bool,
 ) {
-    match result {
-        Ok(val) => {
-            let output = writer.write(&val).expect("Failed to write output");
+    match result.and_then(|val| writer.write(&val)) {
+        Ok(output) => {
             if output_file.is_none() {
                 print_stdout(&output);
             }
         }
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            report_error(&e, explain);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
+
+/// [`handle_result`] for a command that lists CIDRs. With `--since`, only the
+/// changes from the saved snapshot are written, and the snapshot is then
+/// replaced with the current list.
+fn handle_list_result<T: Serialize + TextOutput + CsvOutput + EnvOutput + ResultKind + CidrList>(
+    writer: &OutputWriter,
+    result: ipcalc::error::Result<T>,
+    since: Option<&str>,
+    output_file: &Option<String>,
+    explain: bool,
+) {
+    let Some(path) = since else {
+        return handle_result(writer, result, output_file, explain);
+    };
+    match result.and_then(|val| changes_since(path, &val.cidr_list())) {
+        Ok((changes, current)) => {
+            handle_result(writer, Ok(changes), output_file, explain);
+            if let Err(e) = save_snapshot(path, &current) {
+                report_error(&e, explain);
+                std::process::exit(e.exit_code());
+            }
+        }
+        Err(e) => handle_result::<DiffResult>(writer, Err(e), output_file, explain),
+    }
+}
+
+/// Global flags that change how computed subnets are reported: `--ipv6-style`
+/// and `--v6-case`, `--decimal-totals`, `--p2p-usable`, and `--rfc6890`.
+#[derive(Debug, Clone, Copy)]
+struct DisplayOptions {
+    ipv6_format: Ipv6Format,
+    decimal_totals: bool,
+    p2p_usable: u64,
+    rfc6890: bool,
+}
+
+impl DisplayOptions {
+    fn from_cli(cli: &Cli) -> Self {
+        Self {
+            ipv6_format: Ipv6Format {
+                style: cli.ipv6_style.into(),
+                case: cli.v6_case.into(),
+            },
+            decimal_totals: cli.decimal_totals,
+            p2p_usable: cli.p2p_usable.into(),
+            rfc6890: cli.rfc6890,
+        }
+    }
+
+    /// Apply every flag that concerns the subnets in `result`.
+    fn finish<T: Finish>(&self, mut result: T) -> T {
+        result.finish(self);
+        result
+    }
+}
+
+/// A result holding subnets whose reporting depends on [`DisplayOptions`].
+/// Every command arm passes such a result through [`DisplayOptions::finish`].
+trait Finish {
+    fn finish(&mut self, options: &DisplayOptions);
+}
+
+impl Finish for Ipv4Subnet {
+    fn finish(&mut self, options: &DisplayOptions) {
+        self.apply_p2p_usable(options.p2p_usable);
+        if options.rfc6890 {
+            self.apply_rfc6890();
+        }
+    }
+}
+
+impl Finish for Ipv6Subnet {
+    fn finish(&mut self, options: &DisplayOptions) {
+        self.apply_style(options.ipv6_format);
+        if options.decimal_totals {
+            self.apply_decimal_total();
+        }
+    }
+}
+
+impl<T: Finish> Finish for Vec<T> {
+    fn finish(&mut self, options: &DisplayOptions) {
+        for item in self {
+            item.finish(options);
+        }
+    }
+}
+
+/// [`Finish`] for results whose subnets are all in the named fields.
+macro_rules! impl_finish {
+    ($($ty:ty => [$($field:ident),+]),* $(,)?) => {
+        $(impl Finish for $ty {
+            fn finish(&mut self, options: &DisplayOptions) {
+                $(self.$field.finish(options);)+
+            }
+        })*
+    };
+}
+
+impl_finish! {
+    Ipv4SubnetList => [supernet, subnets],
+    Ipv6SubnetList => [supernet, subnets],
+    Ipv4ComplementResult => [cidrs],
+    Ipv6ComplementResult => [cidrs],
+    Ipv4ExcludeResult => [cidrs],
+    Ipv6ExcludeResult => [cidrs],
+    Ipv4SummaryResult => [cidrs],
+    Ipv6SummaryResult => [cidrs],
+    Ipv4FromRangeResult => [cidrs],
+    Ipv6FromRangeResult => [cidrs],
+    Ipv4WalkResult => [subnets],
+}
+
+impl Finish for DualStackPlan {
+    fn finish(&mut self, options: &DisplayOptions) {
+        for pair in &mut self.pairs {
+            pair.v4.finish(options);
+            pair.v6.finish(options);
+        }
+    }
+}
+
+impl Finish for UsabilityResult {
+    fn finish(&mut self, options: &DisplayOptions) {
+        self.apply_p2p_usable(options.p2p_usable);
+    }
+}
+
+impl Finish for BatchResult {
+    fn finish(&mut self, options: &DisplayOptions) {
+        self.apply_ipv6_style(options.ipv6_format);
+        if options.decimal_totals {
+            self.apply_decimal_totals();
+        }
+        self.apply_p2p_usable(options.p2p_usable);
+        if options.rfc6890 {
+            self.apply_rfc6890();
         }
     }
 }
@@ -89,11 +406,45 @@ async fn main() {
         return;
     }
 
+    let explain = cli.explain_errors;
+    let display = DisplayOptions::from_cli(&cli);
+    let p2p_usable = display.p2p_usable;
+    let json_patch = matches!(cli.format, OutputFormatArg::JsonPatch);
+    if json_patch && !matches!(cli.command, Some(Commands::Diff { .. })) {
+        report_error(
+            &IpCalcError::InvalidInput(
+                "--format json-patch is only supported by the diff command".to_string(),
+            ),
+            explain,
+        );
+        std::process::exit(1);
+    }
+    if cli.since.is_some() && !cli.command.as_ref().is_some_and(Commands::lists_cidrs) {
+        report_error(
+            &IpCalcError::InvalidInput(
+                "--since is only supported by commands that list CIDRs (split, from-range, \
+                 complement, exclude, gaps, free-space, walk, summarize)"
+                    .to_string(),
+            ),
+            explain,
+        );
+        std::process::exit(1);
+    }
     let format: OutputFormat = cli.format.into();
-    let writer = OutputWriter::new(format, cli.output.clone());
+    let writer = OutputWriter::new(format, cli.output.clone())
+        .with_kind(!cli.no_kind)
+        .with_json_case(cli.json_case.into())
+        .with_validate_output(cli.validate_output);
 
     // Collect CIDRs from positional args and/or stdin
     let mut cidrs = cli.cidr;
+    // An unquoted `192.168.1.0 255.255.255.0` arrives as two arguments
+    if cidrs.len() == 2 && !cidrs[0].contains('/') {
+        let joined = cidrs.join(" ");
+        if matches!(mask_notation_to_cidr(&joined), Ok(Some(_))) {
+            cidrs = vec![joined];
+        }
+    }
     if cli.stdin {
         let stdin = io::stdin();
         for line in stdin.lock().lines() {
@@ -108,17 +459,61 @@ async fn main() {
 
     // Handle direct CIDR input (auto-detect)
     if !cidrs.is_empty() {
-        if cidrs.len() == 1 {
+        let filter = if cli.only_errors {
+            Some(BatchFilter::Errors)
+        } else if cli.only_ok {
+            Some(BatchFilter::Ok)
+        } else {
+            None
+        };
+        let ignoring = !cli.ignore.is_empty() || cli.ignore_file.is_some() || cli.ignore_special;
+        // Only batch entries have somewhere to report comment tags
+        let line = parse_batch_line(&cidrs[0]);
+        let tagged = !line.tags.is_empty() || !line.warnings.is_empty();
+        if cidrs.len() == 1 && !cli.with_summary && filter.is_none() && !ignoring && !tagged {
             // Single CIDR — preserve flat output for backward compatibility
-            let cidr = &cidrs[0];
+            let cidr = &line.cidr;
             if cidr.contains(':') {
-                handle_result(&writer, Ipv6Subnet::from_cidr(cidr), &cli.output);
+                let parsed = if cli.require_canonical {
+                    Ipv6Subnet::from_cidr_canonical(&with_host_prefix(cidr))
+                } else {
+                    Ipv6Subnet::from_addr_or_cidr(cidr)
+                };
+                let result = parsed.map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
             } else {
-                handle_result(&writer, Ipv4Subnet::from_cidr(cidr), &cli.output);
+                let result = Ipv4Subnet::from_addr_or_cidr(cidr).map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
             }
         } else {
-            // Multiple CIDRs — batch mode
-            handle_result(&writer, process_batch(&cidrs), &cli.output);
+            // Multiple CIDRs (or tags, --with-summary, --only-*, --ignore*) — batch mode
+            let result = process_batch_with_options(
+                &cidrs,
+                DEFAULTS.max_batch_size,
+                cli.require_canonical,
+                !cli.no_batch_cache,
+            )
+            .and_then(|mut r| {
+                if ignoring {
+                    let mut ignore = cli.ignore;
+                    if let Some(path) = cli.ignore_file.as_deref() {
+                        ignore
+                            .extend(load_summarize_inputs(Vec::new(), Some(path), false, None)?.0);
+                    }
+                    if cli.ignore_special {
+                        ignore.extend(special_purpose_blocks());
+                    }
+                    r.apply_ignore(&ignore)?;
+                }
+                if cli.with_summary {
+                    r.add_aggregate_summary(display.ipv6_format)?;
+                }
+                if let Some(filter) = filter {
+                    r.apply_filter(filter);
+                }
+                Ok(display.finish(r))
+            });
+            handle_result(&writer, result, &cli.output, explain);
         }
         return;
     }
@@ -128,69 +523,610 @@ async fn main() {
         Some(Commands::Split {
             cidr,
             prefix,
+            hosts,
             count,
             max,
             count_only,
+            estimate,
+            shuffle,
+            seed,
+            offset,
+            limit,
+            index,
+            verify,
         }) => {
+            let prefix = match (prefix, hosts) {
+                (Some(prefix), _) => prefix,
+                (None, Some(hosts)) => match split_prefix_for_hosts(&cidr, hosts, p2p_usable) {
+                    Ok(prefix) => prefix,
+                    Err(e) => {
+                        report_error(&e, explain);
+                        std::process::exit(1);
+                    }
+                },
+                (None, None) => unreachable!("clap requires --prefix or --hosts"),
+            };
             if count_only {
-                handle_result(&writer, count_subnets(&cidr, prefix), &cli.output);
+                handle_result(&writer, count_subnets(&cidr, prefix), &cli.output, explain);
+                return;
+            }
+            if estimate {
+                let count = if max { None } else { count };
+                handle_result(
+                    &writer,
+                    estimate_split(&cidr, prefix, count),
+                    &cli.output,
+                    explain,
+                );
+                return;
+            }
+            if let Some(index) = index {
+                if cidr.contains(':') {
+                    let result = nth_ipv6_subnet(&cidr, prefix, index).map(|s| display.finish(s));
+                    handle_result(&writer, result, &cli.output, explain);
+                } else {
+                    let result = nth_ipv4_subnet(&cidr, prefix, index).map(|s| display.finish(s));
+                    handle_result(&writer, result, &cli.output, explain);
+                }
                 return;
             }
 
             // Determine the actual count to use
             let actual_count = if max {
                 None // Signal to generate maximum
+            } else if let Some(limit) = limit {
+                match page_count(&cidr, prefix, offset, limit) {
+                    Ok(count) => Some(count),
+                    Err(e) => {
+                        report_error(&e, explain);
+                        std::process::exit(1);
+                    }
+                }
             } else {
                 match count {
                     Some(c) => Some(c),
                     None => {
-                        eprintln!("Error: Either --count or --max must be specified");
+                        eprintln!("Error: Either --count, --limit, or --max must be specified");
                         std::process::exit(1);
                     }
                 }
             };
 
+            let seed = shuffle.then(|| seed.unwrap_or_else(random_seed));
+            // Text and CSV print each subnet as it is generated; shuffling and
+            // the JSON/YAML documents need the whole list. Nothing is buffered
+            // on stdout, so the generation limit only guards output files and
+            // a reader such as `head` can stop a split of any size.
+            let limit_stream = cli.output.is_some();
+            if seed.is_none() && !verify && cli.since.is_none() && writer.can_stream() {
+                let streamed = if cidr.contains(':') {
+                    iter_ipv6_subnets_from(&cidr, prefix, offset, actual_count).and_then(|iter| {
+                        let (supernet, count) = (iter.supernet().clone(), iter.requested_count());
+                        if limit_stream {
+                            iter.check_generation_limit()?;
+                        }
+                        writer.stream_ipv6_split(
+                            &supernet,
+                            prefix,
+                            count,
+                            offset,
+                            iter.map(|subnet| subnet.map(|s| display.finish(s))),
+                        )
+                    })
+                } else {
+                    iter_ipv4_subnets_from(&cidr, prefix, offset, actual_count).and_then(|iter| {
+                        let (supernet, count) = (iter.supernet().clone(), iter.requested_count());
+                        if limit_stream {
+                            iter.check_generation_limit()?;
+                        }
+                        writer.stream_ipv4_split(
+                            &supernet,
+                            prefix,
+                            count,
+                            offset,
+                            iter.map(|subnet| subnet.map(|s| display.finish(s))),
+                        )
+                    })
+                };
+                match streamed {
+                    Err(IpCalcError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+                    Err(e) => {
+                        report_error(&e, explain);
+                        std::process::exit(1);
+                    }
+                    Ok(()) => {}
+                }
+                return;
+            }
             if cidr.contains(':') {
-                handle_result(
-                    &writer,
-                    generate_ipv6_subnets(&cidr, prefix, actual_count),
-                    &cli.output,
-                );
+                let result = generate_ipv6_subnets_from(&cidr, prefix, offset, actual_count)
+                    .and_then(|list| {
+                        if verify {
+                            verify_split_ipv6(&list)?;
+                        }
+                        Ok(list)
+                    })
+                    .map(|mut list| {
+                        if let Some(seed) = seed {
+                            list.shuffle(seed);
+                        }
+                        list.hosts_required = hosts;
+                        display.finish(list)
+                    });
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
             } else {
+                let result = generate_ipv4_subnets_from(&cidr, prefix, offset, actual_count)
+                    .and_then(|list| {
+                        if verify {
+                            verify_split_ipv4(&list)?;
+                        }
+                        Ok(list)
+                    })
+                    .map(|mut list| {
+                        if let Some(seed) = seed {
+                            list.shuffle(seed);
+                        }
+                        list.hosts_required = hosts;
+                        display.finish(list)
+                    });
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            }
+        }
+        Some(Commands::Dualstack {
+            v4,
+            v6,
+            split_prefix_v4,
+            split_prefix_v6,
+            count,
+        }) => {
+            let result = generate_dualstack_subnets(
+                &v4,
+                &v6,
+                split_prefix_v4,
+                split_prefix_v6,
+                count,
+                display.ipv6_format,
+            )
+            .map(|plan| display.finish(plan));
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::PlanSplit {
+            cidr,
+            subnets,
+            hosts,
+        }) => {
+            handle_result(
+                &writer,
+                plan_split(&cidr, subnets, hosts, p2p_usable),
+                &cli.output,
+                explain,
+            );
+        }
+        Some(Commands::Tree { cidr, prefixes }) => {
+            handle_result(
+                &writer,
+                build_split_tree(&cidr, &prefixes, display.ipv6_format),
+                &cli.output,
+                explain,
+            );
+        }
+        Some(Commands::Contains {
+            cidr,
+            address,
+            translate,
+            resolve,
+        }) if address.contains('/') => {
+            let result = if translate || resolve {
+                Err(IpCalcError::InvalidInput(
+                    "--translate and --resolve apply to a single address, not a CIDR".to_string(),
+                ))
+            } else if cidr.contains(':') {
+                check_ipv6_subnet_contains(&cidr, &address)
+            } else {
+                check_ipv4_subnet_contains(&cidr, &address)
+            };
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::Contains {
+            cidr,
+            address,
+            translate,
+            resolve,
+        }) => {
+            let mut result = if (translate || resolve) && address.contains('-') {
+                Err(IpCalcError::InvalidInput(
+                    "--translate and --resolve apply to a single address, not a range".to_string(),
+                ))
+            } else if translate {
+                check_contains_translated(&cidr, &address)
+            } else if cidr.contains(':') {
+                check_ipv6_contains(&cidr, &address)
+            } else {
+                check_ipv4_contains(&cidr, &address)
+            };
+            if resolve && let Ok(r) = &mut result {
+                resolve_contains(r).await;
+            }
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::SubnetContains { outer, inner }) => {
+            let result = if outer.contains(':') {
+                check_ipv6_subnet_contains(&outer, &inner)
+            } else {
+                check_ipv4_subnet_contains(&outer, &inner)
+            };
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::Lookup {
+            query,
+            cidrs,
+            file,
+            max_results,
+            shortest_first,
+        }) => {
+            let order = if shortest_first {
+                LookupOrder::ShortestFirst
+            } else {
+                LookupOrder::LongestFirst
+            };
+            let result = load_summarize_inputs(cidrs, file.as_deref(), false, None)
+                .and_then(|(cidrs, _)| lookup(&query, &cidrs, order, max_results));
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::Lpm { address, cidrs }) => {
+            let result = lpm(&address, &cidrs);
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::IsUsable { cidr }) => {
+            let result = check_ipv4_usable(&cidr).map(|r| display.finish(r));
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::HostCheck { address, cidr }) => {
+            let result = if cidr.contains(':') {
+                check_ipv6_host(&address, &cidr)
+            } else {
+                check_ipv4_host(&address, &cidr).map(|r| display.finish(r))
+            };
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::AclWildcard { cidr }) => {
+            handle_result(&writer, acl_wildcard(&cidr), &cli.output, explain);
+        }
+        Some(Commands::NextSubnet { cidr }) => {
+            if cidr.contains(':') {
+                let result = adjacent_ipv6(&cidr, true).map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
+            } else {
+                let result = adjacent_ipv4(&cidr, true).map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
+            }
+        }
+        Some(Commands::PrevSubnet { cidr }) => {
+            if cidr.contains(':') {
+                let result = adjacent_ipv6(&cidr, false).map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
+            } else {
+                let result = adjacent_ipv4(&cidr, false).map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
+            }
+        }
+        Some(Commands::Complement { cidr, within }) => {
+            if cidr.contains(':') {
+                let result = complement_ipv6(&cidr, within.as_deref(), display.ipv6_format)
+                    .map(|r| display.finish(r));
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            } else {
+                let result = complement_ipv4(&cidr, within.as_deref()).map(|r| display.finish(r));
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            }
+        }
+        Some(Commands::Exclude { supernet, cidrs }) => {
+            if supernet.contains(':') {
+                let result =
+                    exclude_ipv6(&supernet, &cidrs, display.ipv6_format).map(|r| display.finish(r));
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            } else {
+                let result = exclude_ipv4(&supernet, &cidrs).map(|r| display.finish(r));
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            }
+        }
+        Some(Commands::Gaps { supernet, used }) => {
+            let result = if supernet.contains(':') {
+                find_gaps_v6(&supernet, &used, display.ipv6_format)
+            } else {
+                find_gaps_v4(&supernet, &used)
+            };
+            handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+        }
+        Some(Commands::FreeSpace { parent, allocated }) => {
+            let result = if parent.contains(':') {
+                free_space_v6(&parent, &allocated, display.ipv6_format)
+            } else {
+                free_space_v4(&parent, &allocated)
+            };
+            handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+        }
+        Some(Commands::Allocate {
+            supernet,
+            prefix,
+            count,
+            used,
+        }) => {
+            let result = if supernet.contains(':') {
+                allocate_v6(&supernet, prefix, count, &used, display.ipv6_format)
+            } else {
+                allocate_v4(&supernet, prefix, count, &used)
+            };
+            handle_result(&writer, result, &cli.output, explain);
+        }
+        Some(Commands::Expand { address }) => {
+            handle_result(&writer, expand_ipv6(&address), &cli.output, explain);
+        }
+        Some(Commands::Compress { address }) => {
+            handle_result(&writer, compress_ipv6(&address), &cli.output, explain);
+        }
+        Some(Commands::Eui64 { prefix, mac }) => {
+            handle_result(&writer, derive_eui64(&prefix, &mac), &cli.output, explain);
+        }
+        Some(Commands::Ifid { cidr }) => {
+            handle_result(&writer, split_interface_id(&cidr), &cli.output, explain);
+        }
+        Some(Commands::FromRange {
+            start,
+            end,
+            estimate,
+            verify,
+        }) => {
+            if estimate {
                 handle_result(
                     &writer,
-                    generate_ipv4_subnets(&cidr, prefix, actual_count),
+                    estimate_from_range(&start, &end),
                     &cli.output,
+                    explain,
                 );
+            } else if start.contains(':') {
+                let result = from_range_ipv6(&start, &end, display.ipv6_format).and_then(|r| {
+                    if verify {
+                        verify_from_range_ipv6(&r)?;
+                    }
+                    Ok(display.finish(r))
+                });
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            } else {
+                let result = from_range_ipv4(&start, &end).and_then(|r| {
+                    if verify {
+                        verify_from_range_ipv4(&r)?;
+                    }
+                    Ok(display.finish(r))
+                });
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
             }
         }
-        Some(Commands::Contains { cidr, address }) => {
-            let result = if cidr.contains(':') {
-                check_ipv6_contains(&cidr, &address)
+        Some(Commands::Walk { start, end }) => {
+            handle_list_result(
+                &writer,
+                walk_ipv4(&start, &end).map(|r| display.finish(r)),
+                cli.since.as_deref(),
+                &cli.output,
+                explain,
+            );
+        }
+        Some(Commands::Add { address, offset }) => {
+            handle_result(&writer, add_ip(&address, offset), &cli.output, explain);
+        }
+        Some(Commands::Hosts { cidr, max }) => {
+            handle_result(&writer, run_hosts(&cidr, max), &cli.output, explain);
+        }
+        Some(Commands::NthHost { cidr, n }) => {
+            if cidr.contains(':') {
+                handle_result(&writer, nth_host_v6(&cidr, n), &cli.output, explain);
             } else {
-                check_ipv4_contains(&cidr, &address)
+                handle_result(&writer, nth_host_v4(&cidr, n), &cli.output, explain);
+            }
+        }
+        Some(Commands::Summarize {
+            base: Some(base),
+            add,
+            remove,
+            expect,
+            ..
+        }) => {
+            let expect: Option<AddressSpace> = expect.map(Into::into);
+            let load = |path: Option<String>| match path {
+                Some(path) => load_summarize_inputs(Vec::new(), Some(&path), false, None)
+                    .map(|(cidrs, _)| cidrs),
+                None => Ok(Vec::new()),
+            };
+            let inputs =
+                load_summary_base(&base).and_then(|base| Ok((base, load(add)?, load(remove)?)));
+            match inputs {
+                Ok((SummaryBase::V4(base), add, remove)) => {
+                    let result =
+                        update_ipv4_summary(&base, &add, &remove, DEFAULTS.max_summarize_inputs)
+                            .and_then(|r| {
+                                if let Some(expect) = expect {
+                                    r.check_expected_space(expect)?;
+                                }
+                                warn_summary(&r.warnings);
+                                Ok(display.finish(r))
+                            });
+                    handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+                }
+                Ok((SummaryBase::V6(base), add, remove)) => {
+                    let result = update_ipv6_summary(
+                        &base,
+                        &add,
+                        &remove,
+                        DEFAULTS.max_summarize_inputs,
+                        display.ipv6_format,
+                    )
+                    .and_then(|r| {
+                        if let Some(expect) = expect {
+                            r.check_expected_space(expect)?;
+                        }
+                        warn_summary(&r.warnings);
+                        Ok(display.finish(r))
+                    });
+                    handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+                }
+                Err(e) => {
+                    report_error(&e, explain);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Summarize {
+            cidrs,
+            file,
+            extract,
+            extract_regex,
+            explain: explain_steps,
+            expect,
+            ..
+        }) => {
+            let expect: Option<AddressSpace> = expect.map(Into::into);
+            let (cidrs, extraction) =
+                match load_summarize_inputs(cidrs, file.as_deref(), extract, extract_regex) {
+                    Ok(inputs) => inputs,
+                    Err(e) => {
+                        report_error(&e, explain);
+                        std::process::exit(1);
+                    }
+                };
+            if cidrs.iter().any(|c| c.contains(':')) {
+                let result = summarize_ipv6_with_options(
+                    &cidrs,
+                    DEFAULTS.max_summarize_inputs,
+                    explain_steps,
+                    display.ipv6_format,
+                )
+                .and_then(|mut r| {
+                    if let Some(expect) = expect {
+                        r.check_expected_space(expect)?;
+                    }
+                    warn_summary(&r.warnings);
+                    r.extraction = extraction;
+                    Ok(display.finish(r))
+                });
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            } else {
+                let result = summarize_ipv4_with_options(
+                    &cidrs,
+                    DEFAULTS.max_summarize_inputs,
+                    explain_steps,
+                )
+                .and_then(|mut r| {
+                    if let Some(expect) = expect {
+                        r.check_expected_space(expect)?;
+                    }
+                    warn_summary(&r.warnings);
+                    r.extraction = extraction;
+                    Ok(display.finish(r))
+                });
+                handle_list_result(&writer, result, cli.since.as_deref(), &cli.output, explain);
+            }
+        }
+        Some(Commands::Addresses { cidrs }) => {
+            handle_result(&writer, count_addresses(&cidrs), &cli.output, explain);
+        }
+        Some(Commands::Overlap { cidrs }) => {
+            handle_result(&writer, find_overlaps(&cidrs), &cli.output, explain);
+        }
+        Some(Commands::Supernet { cidrs }) => {
+            let result = if cidrs.iter().any(|c| c.contains(':')) {
+                find_supernet_v6(&cidrs, display.ipv6_format)
+            } else {
+                find_supernet_v4(&cidrs)
             };
-            handle_result(&writer, result, &cli.output);
+            handle_result(&writer, result, &cli.output, explain);
         }
-        Some(Commands::FromRange { start, end }) => {
-            if start.contains(':') {
-                handle_result(&writer, from_range_ipv6(&start, &end), &cli.output);
+        Some(Commands::CommonSupernet { a, b }) => {
+            if a.contains(':') {
+                let result = find_common_supernet_v6(&a, &b).map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
             } else {
-                handle_result(&writer, from_range_ipv4(&start, &end), &cli.output);
+                let result = find_common_supernet_v4(&a, &b).map(|s| display.finish(s));
+                handle_result(&writer, result, &cli.output, explain);
             }
         }
-        Some(Commands::Summarize { cidrs }) => {
-            if cidrs.iter().any(|c| c.contains(':')) {
-                handle_result(&writer, summarize_ipv6(&cidrs), &cli.output);
+        Some(Commands::Vlsm {
+            supernet,
+            hosts,
+            host_list,
+        }) => {
+            let hosts = if host_list.is_empty() {
+                hosts
+            } else {
+                host_list
+            };
+            handle_result(
+                &writer,
+                allocate_vlsm(&supernet, &hosts, p2p_usable),
+                &cli.output,
+                explain,
+            );
+        }
+        Some(Commands::Covers { target, by }) => {
+            let result = load_summarize_inputs(Vec::new(), Some(&by), false, None)
+                .and_then(|(cidrs, _)| check_coverage(&target, &cidrs));
+            // Exit 0 only for exact coverage so the check can gate automation
+            let exact = matches!(&result, Ok(r) if r.exact);
+            handle_result(&writer, result, &cli.output, explain);
+            if !exact {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Diff { old, new }) => {
+            let lists =
+                load_summarize_inputs(Vec::new(), Some(&old), false, None).and_then(|(old, _)| {
+                    load_summarize_inputs(Vec::new(), Some(&new), false, None)
+                        .map(|(new, _)| (old, new))
+                });
+            if json_patch {
+                let result = lists.and_then(|(old, new)| diff_json_patch(&old, &new));
+                handle_result(&writer, result, &cli.output, explain);
             } else {
-                handle_result(&writer, summarize_ipv4(&cidrs), &cli.output);
+                let result = lists.and_then(|(old, new)| diff_cidr_sets(&old, &new));
+                handle_result(&writer, result, &cli.output, explain);
             }
         }
+        Some(Commands::MergeResults { files, dedupe }) => {
+            handle_result(&writer, run_merge(&files, dedupe), &cli.output, explain);
+        }
+        Some(Commands::Normalize {
+            file,
+            sort,
+            dedupe,
+            in_place,
+        }) => {
+            let options = NormalizeOptions { sort, dedupe };
+            handle_result(
+                &writer,
+                run_normalize(file.as_deref(), options, in_place),
+                &cli.output,
+                explain,
+            );
+        }
+        Some(Commands::Zonefile {
+            cidr,
+            split_24,
+            domain,
+        }) => {
+            handle_result(
+                &writer,
+                generate_zonefile(&cidr, split_24, &domain),
+                &cli.output,
+                explain,
+            );
+        }
+        Some(Commands::ReverseDns { cidr, zone_only }) => {
+            handle_result(&writer, reverse_dns(&cidr, zone_only), &cli.output, explain);
+        }
         Some(Commands::Ipam { db, command }) => {
             if let Err(e) =
                 ipam_cli::handle_ipam_command(&writer, &cli.output, db.as_deref(), command).await
             {
-                eprintln!("Error: {}", e);
+                report_error(&e, explain);
                 std::process::exit(1);
             }
         }
@@ -201,6 +1137,25 @@ async fn main() {
                 std::process::exit(1);
             }
         }
+        Some(Commands::Limits { config }) => {
+            let limits = match config {
+                Some(path) => ServerConfig::load(&path).map(|c| c.limits()),
+                None => Ok(ServerConfig::default().limits()),
+            };
+            handle_result(&writer, limits, &cli.output, explain);
+        }
+        Some(Commands::Defaults) => {
+            handle_result(&writer, Ok(DEFAULTS.report()), &cli.output, explain);
+        }
+        Some(Commands::Config {
+            command: ConfigCommands::Show { config },
+        }) => {
+            let report = match config {
+                Some(path) => ServerConfig::load_with_sources(&path).map(|l| l.report()),
+                None => Ok(LoadedConfig::default().report()),
+            };
+            handle_result(&writer, report, &cli.output, explain);
+        }
         Some(Commands::Serve {
             address,
             port,
@@ -209,6 +1164,10 @@ async fn main() {
             log_json,
             config,
             enable_swagger,
+            enable_ui,
+            enable_landing_page,
+            trust_forwarded_for,
+            trusted_proxies,
             max_batch_size,
             max_range_cidrs,
             max_summarize_inputs,
@@ -220,130 +1179,114 @@ async fn main() {
             ipam_backend,
             ipam_db,
             ipam_db_url,
+            dry_run,
         }) => {
-            // Parse and validate log level
             let level = match parse_log_level(&log_level) {
                 Ok(l) => l,
                 Err(e) => {
-                    eprintln!("Error: {}", e);
+                    report_error(&startup_error("log_level", e), explain);
                     std::process::exit(1);
                 }
             };
 
-            // Initialize logging
-            let log_config = LogConfig::new(level).with_json(log_json);
+            // A dry run's summary owns stdout
+            let log_config = LogConfig::new(level)
+                .with_json(log_json)
+                .with_stderr(dry_run);
             let log_config = match log_file {
-                Some(path) => log_config.with_file(path),
+                Some(path) => {
+                    if let Err(e) = check_log_file(&path) {
+                        report_error(
+                            &startup_error("log_file", format!("{}: {}", path, e)),
+                            explain,
+                        );
+                        std::process::exit(1);
+                    }
+                    log_config.with_file(path)
+                }
                 None => log_config,
             };
 
             // Keep the guard alive for the lifetime of the program
             let _guard = init_logging(&log_config);
 
-            // Load server config
-            let mut server_config = if let Some(ref path) = config {
-                match ServerConfig::load(path) {
-                    Ok(c) => {
-                        info!("Loaded config from {}", path);
-                        c
-                    }
-                    Err(e) => {
-                        eprintln!("Error loading config: {}", e);
-                        std::process::exit(1);
-                    }
+            let prepared = prepare_server(ServeOptions {
+                address,
+                port,
+                config,
+                overrides: CliOverrides {
+                    enable_swagger,
+                    enable_ui,
+                    enable_landing_page,
+                    trust_forwarded_for,
+                    trusted_proxies,
+                    max_batch_size,
+                    max_range_cidrs,
+                    max_summarize_inputs,
+                    max_body_size,
+                    rate_limit_per_second,
+                    rate_limit_burst,
+                    timeout,
+                    ipam_enabled,
+                    ipam_backend,
+                    ipam_db,
+                    ipam_db_url,
+                },
+            })
+            .await;
+            let PreparedServer {
+                listener,
+                router,
+                summary,
+            } = match prepared {
+                Ok(p) => p,
+                Err(e) => {
+                    report_error(&e, explain);
+                    std::process::exit(e.exit_code());
                 }
-            } else {
-                ServerConfig::default()
             };
 
-            // Apply CLI overrides
-            server_config.merge_cli_overrides(&CliOverrides {
-                enable_swagger,
-                max_batch_size,
-                max_range_cidrs,
-                max_summarize_inputs,
-                max_body_size,
-                rate_limit_per_second,
-                rate_limit_burst,
-                timeout,
-                ipam_enabled,
-                ipam_backend,
-                ipam_db,
-                ipam_db_url,
-            });
-
-            // Bind-address warning
-            if address != "127.0.0.1" && address != "::1" {
-                warn!(
-                    "Binding to non-loopback address '{}'. Use 127.0.0.1 for local-only access.",
-                    address
-                );
+            if dry_run {
+                info!("Dry run complete; not serving");
+                drop(listener);
+                handle_result(&writer, Ok(summary), &cli.output, explain);
+                return;
             }
 
-            let addr: SocketAddr = format!("{}:{}", address, port)
-                .parse()
-                .expect("Invalid address");
-
-            info!("Starting ipcalc API server on http://{}", addr);
+            info!("Starting ipcalc API server on http://{}", summary.address);
             info!("Log level: {}", log_level);
 
             // Print to stdout as well for visibility
-            println!("Starting ipcalc API server on http://{}", addr);
+            println!("Starting ipcalc API server on http://{}", summary.address);
             println!("Endpoints:");
-            println!("  GET /health              - Health check");
-            println!("  GET /version             - Version information");
-            println!("  GET /v4?cidr=<cidr>      - Calculate IPv4 subnet");
-            println!("  GET /v6?cidr=<cidr>      - Calculate IPv6 subnet");
-            println!("  GET /v4/split?cidr=<cidr>&prefix=<n>&count=<n> - Split IPv4 supernet");
-            println!("  GET /v6/split?cidr=<cidr>&prefix=<n>&count=<n> - Split IPv6 supernet");
-            println!("  GET /v4/contains?cidr=<cidr>&address=<ip>     - Check IPv4 containment");
-            println!("  GET /v6/contains?cidr=<cidr>&address=<ip>     - Check IPv6 containment");
-            println!("  GET /v4/summarize?cidrs=<cidr,cidr,...>       - Summarize IPv4 CIDRs");
-            println!("  GET /v6/summarize?cidrs=<cidr,cidr,...>       - Summarize IPv6 CIDRs");
-            println!("  GET /v4/from-range?start=<ip>&end=<ip>       - IPv4 range to CIDRs");
-            println!("  GET /v6/from-range?start=<ip>&end=<ip>       - IPv6 range to CIDRs");
-            println!("  POST /batch                                  - Batch CIDR processing");
-            if server_config.enable_swagger {
-                #[cfg(feature = "swagger")]
-                {
-                    println!("  GET /swagger-ui          - Interactive API documentation");
-                    println!("  GET /api-docs/openapi.json - OpenAPI specification");
-                }
+            for ep in ENDPOINTS {
+                println!("  {:<4} {:<45} - {}", ep.method, ep.usage, ep.description);
             }
-
-            // Initialize IPAM if enabled
-            let ipam_ops = if server_config.ipam_enabled {
-                use ipcalc::ipam;
-                let mut ipam_config = ipam::config::IpamConfig::default();
-                if let Ok(backend) = server_config.ipam_backend.parse::<ipam::config::Backend>() {
-                    ipam_config.backend = backend;
-                }
-                let store = ipam::create_store(
-                    &ipam_config,
-                    server_config.ipam_db.as_deref(),
-                    server_config.ipam_db_url.as_deref(),
-                )
-                .await
-                .expect("Failed to initialize IPAM store");
-                info!("IPAM enabled, backend: {}", server_config.ipam_backend);
+            if summary.ui {
+                println!("  GET  {:<45} - Mini web UI", "/ui");
+            }
+            if summary.swagger {
+                println!(
+                    "  GET  {:<45} - Interactive API documentation",
+                    "/swagger-ui"
+                );
+                println!(
+                    "  GET  {:<45} - OpenAPI specification",
+                    "/api-docs/openapi.json"
+                );
+            }
+            if summary.ipam_backend.is_some() {
                 println!("IPAM endpoints enabled at /ipam/");
                 println!("IPAM dashboard at /dashboard");
-                Some(std::sync::Arc::new(ipam::operations::IpamOps::new(store)))
-            } else {
-                None
-            };
-
-            let router_config = RouterConfig {
-                server: server_config,
-                ipam_ops,
-            };
-            let router = create_router(router_config);
+            }
 
-            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-            axum::serve(listener, router)
-                .with_graceful_shutdown(shutdown_signal())
-                .await
-                .unwrap();
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .unwrap();
 
             info!("Server shut down gracefully");
         }
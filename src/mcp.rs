@@ -7,11 +7,11 @@ use rmcp::{ServerHandler, ServiceExt, tool, tool_handler, tool_router};
 use schemars::JsonSchema;
 use serde::Deserialize;
 
-use crate::contains::{check_ipv4_contains, check_ipv6_contains};
+use crate::contains::{check_contains_translated, check_ipv4_contains, check_ipv6_contains};
 use crate::from_range::{from_range_ipv4, from_range_ipv6};
 use crate::ipam::operations::IpamOps;
 use crate::ipv4::Ipv4Subnet;
-use crate::ipv6::Ipv6Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
 use crate::subnet_generator::{count_subnets, generate_ipv4_subnets, generate_ipv6_subnets};
 use crate::summarize::{summarize_ipv4, summarize_ipv6};
 
@@ -43,6 +43,8 @@ struct ContainsCheckParams {
     cidr: String,
     /// IP address to check, e.g. 192.168.1.100
     address: String,
+    /// Translate between IPv4 and IPv6 (IPv4-mapped, 6to4, NAT64) when families differ
+    translate: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -245,10 +247,12 @@ impl IpCalcMcp {
 
     #[tool(
         name = "contains_check",
-        description = "Check if an IP address is contained within a CIDR range. Auto-detects IPv4 vs IPv6."
+        description = "Check if an IP address is contained within a CIDR range. Auto-detects IPv4 vs IPv6. Set translate=true to check an address of the other family via IPv4-mapped, IPv4-compatible, 6to4, or NAT64 mappings."
     )]
     async fn contains_check(&self, Parameters(params): Parameters<ContainsCheckParams>) -> String {
-        if is_ipv6(&params.cidr) {
+        if params.translate.unwrap_or(false) {
+            result_to_string(check_contains_translated(&params.cidr, &params.address))
+        } else if is_ipv6(&params.cidr) {
             result_to_string(check_ipv6_contains(&params.cidr, &params.address))
         } else {
             result_to_string(check_ipv4_contains(&params.cidr, &params.address))
@@ -261,7 +265,11 @@ impl IpCalcMcp {
     )]
     async fn from_range(&self, Parameters(params): Parameters<FromRangeParams>) -> String {
         if is_ipv6(&params.start) {
-            result_to_string(from_range_ipv6(&params.start, &params.end))
+            result_to_string(from_range_ipv6(
+                &params.start,
+                &params.end,
+                Ipv6Format::default(),
+            ))
         } else {
             result_to_string(from_range_ipv4(&params.start, &params.end))
         }
@@ -609,6 +617,7 @@ mod tests {
             .contains_check(Parameters(ContainsCheckParams {
                 cidr: "192.168.1.0/24".into(),
                 address: "192.168.1.100".into(),
+                translate: None,
             }))
             .await;
         assert!(result.contains("true"));
@@ -622,6 +631,7 @@ mod tests {
             .contains_check(Parameters(ContainsCheckParams {
                 cidr: "192.168.1.0/24".into(),
                 address: "10.0.0.1".into(),
+                translate: None,
             }))
             .await;
         assert!(result.contains("false"));
@@ -634,8 +644,23 @@ mod tests {
             .contains_check(Parameters(ContainsCheckParams {
                 cidr: "2001:db8::/32".into(),
                 address: "2001:db8::1".into(),
+                translate: None,
+            }))
+            .await;
+        assert!(result.contains("true"));
+    }
+
+    #[tokio::test]
+    async fn test_contains_check_translate() {
+        let server = calc_server();
+        let result = server
+            .contains_check(Parameters(ContainsCheckParams {
+                cidr: "64:ff9b::/96".into(),
+                address: "198.51.100.7".into(),
+                translate: Some(true),
             }))
             .await;
+        assert!(result.contains("\"nat64\""));
         assert!(result.contains("true"));
     }
 
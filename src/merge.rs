@@ -0,0 +1,236 @@
+use crate::batch::{BatchEntry, BatchEntryResult, BatchResult, SubnetResult, count_non_canonical};
+use crate::error::{IpCalcError, Result};
+use crate::normalize::canonical_cidr;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// Two files disagree about the subnet computed for the same CIDR.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MergeConflict {
+    pub cidr: String,
+    pub kept_from: String,
+    pub conflicting_file: String,
+}
+
+/// Several batch result files combined into one.
+///
+/// The batch fields are flattened into the top level, so the merged output is
+/// itself a valid batch result file and can be merged again.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MergeResult {
+    pub files: usize,
+    pub input_entries: usize,
+    pub duplicates_removed: usize,
+    pub conflicts: Vec<MergeConflict>,
+    #[serde(flatten)]
+    pub batch: BatchResult,
+}
+
+// ---------------------------------------------------------------------------
+// Loading and merging
+// ---------------------------------------------------------------------------
+
+/// Read a JSON batch result file (as written by `ipcalc --format json`).
+pub fn load_batch_result(path: &str) -> Result<BatchResult> {
+    // nosemgrep: rust.actix.path-traversal.tainted-path.tainted-path — CLI-only input file, not reachable from HTTP input
+    let contents = std::fs::read_to_string(path)?;
+    serde_json::from_str(&contents)
+        .map_err(|e| IpCalcError::InvalidInput(format!("{}: not a batch result: {}", path, e)))
+}
+
+/// Key used to recognise the same CIDR across files: the canonical
+/// `network/prefix` form, or the trimmed input when it does not parse.
+fn entry_key(entry: &BatchEntry) -> String {
+    canonical_cidr(&entry.cidr).unwrap_or_else(|_| entry.cidr.trim().to_string())
+}
+
+/// Compare two subnet results, ignoring the echoed `input` string (which may
+/// legitimately differ, e.g. `10.0.1.5/24` vs `10.0.1.0/24`).
+fn same_subnet(a: &SubnetResult, b: &SubnetResult) -> bool {
+    let strip = |s: &SubnetResult| {
+        let mut value = serde_json::to_value(s).unwrap_or_default();
+        if let Some(obj) = value.as_object_mut() {
+            obj.remove("input");
+        }
+        value
+    };
+    strip(a) == strip(b)
+}
+
+/// Concatenate batch results from `(file name, result)` pairs, in order.
+///
+/// Entries for the same CIDR are compared: when two successful results
+/// differ, a [`MergeConflict`] is recorded and the first is kept. With
+/// `dedupe`, only one entry per CIDR is kept, at the position of its first
+/// occurrence, preferring the first success over any error.
+pub fn merge_batch_results(inputs: Vec<(String, BatchResult)>, dedupe: bool) -> MergeResult {
+    let files = inputs.len();
+    let mut input_entries = 0;
+    let mut duplicates_removed = 0;
+    let mut conflicts = Vec::new();
+    let mut results: Vec<BatchEntry> = Vec::new();
+    // key -> (index of the kept entry in `results`, file it came from)
+    let mut seen: HashMap<String, (usize, String)> = HashMap::new();
+
+    for (file, batch) in inputs {
+        for entry in batch.results {
+            input_entries += 1;
+            let key = entry_key(&entry);
+
+            let Some((idx, kept_from)) = seen.get(&key).cloned() else {
+                seen.insert(key, (results.len(), file.clone()));
+                results.push(entry);
+                continue;
+            };
+
+            match (&results[idx].result, &entry.result) {
+                (BatchEntryResult::Ok { subnet: kept }, BatchEntryResult::Ok { subnet: new })
+                    if !same_subnet(kept, new) =>
+                {
+                    conflicts.push(MergeConflict {
+                        cidr: key.clone(),
+                        kept_from,
+                        conflicting_file: file.clone(),
+                    });
+                }
+                (BatchEntryResult::Err { .. }, BatchEntryResult::Ok { .. }) if dedupe => {
+                    seen.insert(key, (idx, file.clone()));
+                    results[idx] = entry;
+                    duplicates_removed += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            if dedupe {
+                duplicates_removed += 1;
+            } else {
+                results.push(entry);
+            }
+        }
+    }
+
+    MergeResult {
+        files,
+        input_entries,
+        duplicates_removed,
+        conflicts,
+        batch: BatchResult {
+            count: results.len(),
+            non_canonical: count_non_canonical(&results),
+            cache_hits: 0,
+            filter: None,
+            shown_count: None,
+            ignored_count: None,
+            ignored: Vec::new(),
+            results,
+            aggregate_summary: None,
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> Vec<(String, BatchResult)> {
+        ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                let path = format!("tests/fixtures/merge/{}.json", name);
+                let batch = load_batch_result(&path).unwrap();
+                (path, batch)
+            })
+            .collect()
+    }
+
+    fn cidrs(result: &MergeResult) -> Vec<&str> {
+        result
+            .batch
+            .results
+            .iter()
+            .map(|e| e.cidr.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_without_dedupe_concatenates() {
+        let result = merge_batch_results(fixtures(), false);
+        assert_eq!(result.files, 3);
+        assert_eq!(result.input_entries, 11);
+        assert_eq!(result.batch.count, 11);
+        assert_eq!(result.duplicates_removed, 0);
+        assert_eq!(result.conflicts.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_dedupe_prefers_success() {
+        let result = merge_batch_results(fixtures(), true);
+        assert_eq!(result.input_entries, 11);
+        assert_eq!(result.batch.count, 6);
+        assert_eq!(result.duplicates_removed, 5);
+        assert_eq!(
+            cidrs(&result),
+            [
+                "10.0.0.0/24",
+                "10.0.1.5/24",
+                "not-a-cidr",
+                "2001:db8::/32",
+                "172.16.0.0/12",
+                "192.168.0.0/16",
+            ]
+        );
+        // a.json recorded an error for 172.16.0.0/12; b.json's success wins
+        assert!(matches!(
+            result.batch.results[4].result,
+            BatchEntryResult::Ok { .. }
+        ));
+    }
+
+    #[test]
+    fn test_merge_reports_conflict() {
+        let result = merge_batch_results(fixtures(), true);
+        assert_eq!(
+            result.conflicts,
+            [MergeConflict {
+                cidr: "10.0.0.0/24".to_string(),
+                kept_from: "tests/fixtures/merge/a.json".to_string(),
+                conflicting_file: "tests/fixtures/merge/c.json".to_string(),
+            }]
+        );
+        // The first result is kept
+        let BatchEntryResult::Ok { subnet } = &result.batch.results[0].result else {
+            panic!("expected a subnet");
+        };
+        let SubnetResult::V4(s) = subnet.as_ref() else {
+            panic!("expected IPv4");
+        };
+        assert_eq!(s.broadcast.to_string(), "10.0.0.255");
+    }
+
+    #[test]
+    fn test_merged_output_round_trips() {
+        let result = merge_batch_results(fixtures(), true);
+        let json = serde_json::to_string(&result).unwrap();
+        let reparsed: BatchResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(reparsed.count, 6);
+    }
+
+    #[test]
+    fn test_load_rejects_non_batch_file() {
+        let err = load_batch_result("tests/fixtures/routes/ios.txt").unwrap_err();
+        assert!(err.to_string().contains("not a batch result"), "{}", err);
+    }
+}
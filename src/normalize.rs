@@ -0,0 +1,243 @@
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::Ipv6Subnet;
+use serde::Serialize;
+use std::collections::HashSet;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// A CIDR list rewritten in canonical `network/prefix` form.
+///
+/// `lines` is the cleaned file content; the text output format prints it
+/// verbatim so it can be redirected back over the original.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct NormalizeResult {
+    pub input_lines: usize,
+    pub cidr_count: usize,
+    /// CIDR lines whose text changed when canonicalized
+    pub rewritten: usize,
+    pub duplicates_removed: usize,
+    pub lines: Vec<String>,
+}
+
+/// Options for [`normalize_cidr_list`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeOptions {
+    /// Sort CIDRs (IPv4 before IPv6, then by network and prefix length)
+    pub sort: bool,
+    /// Drop repeated CIDRs, keeping the first occurrence and its comment
+    pub dedupe: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Normalization
+// ---------------------------------------------------------------------------
+
+/// Canonicalize a single CIDR: host bits cleared, IPv6 compressed and lowercase.
+pub fn canonical_cidr(cidr: &str) -> Result<String> {
+    let cidr = cidr.trim();
+    if cidr.contains(':') {
+        let subnet = Ipv6Subnet::from_cidr(cidr)?;
+        Ok(format!("{}/{}", subnet.network, subnet.prefix_length))
+    } else {
+        let subnet = Ipv4Subnet::from_cidr(cidr)?;
+        Ok(format!("{}/{}", subnet.network, subnet.prefix_length))
+    }
+}
+
+/// Sort key for a canonical CIDR: (is IPv6, network, prefix length).
+pub(crate) fn sort_key(canonical: &str) -> (bool, u128, u8) {
+    if canonical.contains(':') {
+        let subnet = Ipv6Subnet::from_cidr(canonical).expect("canonical CIDR");
+        (true, u128::from(subnet.network), subnet.prefix_length)
+    } else {
+        let subnet = Ipv4Subnet::from_cidr(canonical).expect("canonical CIDR");
+        (
+            false,
+            u32::from(subnet.network) as u128,
+            subnet.prefix_length,
+        )
+    }
+}
+
+struct CidrLine {
+    canonical: String,
+    comment: Option<String>,
+}
+
+impl CidrLine {
+    fn render(&self) -> String {
+        match &self.comment {
+            Some(comment) => format!("{} {}", self.canonical, comment),
+            None => self.canonical.clone(),
+        }
+    }
+}
+
+/// Rewrite a CIDR list file in canonical form.
+///
+/// Each non-blank, non-comment line holds one CIDR, optionally followed by a
+/// `#` comment (which may carry batch-style `key=value` labels). Comments are
+/// kept verbatim. Blank lines and comment-only lines stay in place unless
+/// `sort` is set, in which case comment-only lines move to the top (in their
+/// original order) and blank lines are dropped.
+///
+/// Any line that is not a valid CIDR fails the whole rewrite, naming the
+/// 1-based line number, so a bad file is never partially rewritten.
+pub fn normalize_cidr_list(text: &str, options: NormalizeOptions) -> Result<NormalizeResult> {
+    enum Line {
+        Verbatim(String),
+        Cidr(CidrLine),
+    }
+
+    let mut parsed = Vec::new();
+    let mut seen = HashSet::new();
+    let mut input_lines = 0;
+    let mut rewritten = 0;
+    let mut duplicates_removed = 0;
+
+    for (idx, raw) in text.lines().enumerate() {
+        input_lines += 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            parsed.push(Line::Verbatim(trimmed.to_string()));
+            continue;
+        }
+
+        let (cidr, comment) = match trimmed.split_once('#') {
+            Some((cidr, rest)) => (cidr.trim(), Some(format!("#{}", rest))),
+            None => (trimmed, None),
+        };
+        let canonical = canonical_cidr(cidr)
+            .map_err(|e| IpCalcError::InvalidInput(format!("line {}: {}", idx + 1, e)))?;
+
+        if options.dedupe && !seen.insert(canonical.clone()) {
+            duplicates_removed += 1;
+            continue;
+        }
+        if canonical != cidr {
+            rewritten += 1;
+        }
+        parsed.push(Line::Cidr(CidrLine { canonical, comment }));
+    }
+
+    let lines: Vec<String> = if options.sort {
+        let mut comments = Vec::new();
+        let mut cidrs = Vec::new();
+        for line in parsed {
+            match line {
+                Line::Verbatim(text) if text.is_empty() => {}
+                Line::Verbatim(text) => comments.push(text),
+                Line::Cidr(cidr) => cidrs.push(cidr),
+            }
+        }
+        // Stable sort keeps duplicate CIDRs in input order
+        cidrs.sort_by_cached_key(|c| sort_key(&c.canonical));
+        comments
+            .into_iter()
+            .chain(cidrs.iter().map(CidrLine::render))
+            .collect()
+    } else {
+        parsed
+            .into_iter()
+            .map(|line| match line {
+                Line::Verbatim(text) => text,
+                Line::Cidr(cidr) => cidr.render(),
+            })
+            .collect()
+    };
+
+    let cidr_count = lines
+        .iter()
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .count();
+
+    Ok(NormalizeResult {
+        input_lines,
+        cidr_count,
+        rewritten,
+        duplicates_removed,
+        lines,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = "\
+# core prefixes
+10.0.0.5/8   # site=hq
+2001:DB8:0:0::1/32
+192.168.1.77/24
+
+10.0.0.0/8 # duplicate of the first
+2001:db8::/32
+";
+
+    #[test]
+    fn test_canonical_cidr() {
+        assert_eq!(canonical_cidr("10.1.2.3/16").unwrap(), "10.1.0.0/16");
+        assert_eq!(
+            canonical_cidr(" 2001:DB8:0:0:0:0:0:1/64 ").unwrap(),
+            "2001:db8::/64"
+        );
+        assert!(canonical_cidr("10.0.0.0").is_err());
+    }
+
+    #[test]
+    fn test_normalize_preserves_layout() {
+        let result = normalize_cidr_list(MESSY, NormalizeOptions::default()).unwrap();
+        assert_eq!(
+            result.lines,
+            [
+                "# core prefixes",
+                "10.0.0.0/8 # site=hq",
+                "2001:db8::/32",
+                "192.168.1.0/24",
+                "",
+                "10.0.0.0/8 # duplicate of the first",
+                "2001:db8::/32",
+            ]
+        );
+        assert_eq!(result.input_lines, 7);
+        assert_eq!(result.cidr_count, 5);
+        assert_eq!(result.rewritten, 3);
+        assert_eq!(result.duplicates_removed, 0);
+    }
+
+    #[test]
+    fn test_normalize_sort_and_dedupe() {
+        let options = NormalizeOptions {
+            sort: true,
+            dedupe: true,
+        };
+        let result = normalize_cidr_list(MESSY, options).unwrap();
+        assert_eq!(
+            result.lines,
+            [
+                "# core prefixes",
+                "10.0.0.0/8 # site=hq",
+                "192.168.1.0/24",
+                "2001:db8::/32",
+            ]
+        );
+        assert_eq!(result.cidr_count, 3);
+        assert_eq!(result.duplicates_removed, 2);
+    }
+
+    #[test]
+    fn test_normalize_reports_bad_line() {
+        let err = normalize_cidr_list("10.0.0.0/8\nnot-a-cidr\n", NormalizeOptions::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+    }
+}
@@ -1,16 +1,49 @@
-use crate::batch::{BatchEntryResult, BatchResult, SubnetResult};
-use crate::contains::ContainsResult;
+use crate::acl::AclWildcardResult;
+use crate::addresses::AddressCountResult;
+use crate::arithmetic::IpAddResult;
+use crate::available::{AllocateResult, AvailableResult};
+use crate::batch::{BatchEntryResult, BatchFilter, BatchResult, SubnetResult};
+use crate::complement::{Ipv4ComplementResult, Ipv6ComplementResult};
+use crate::config::{ConfigReport, DefaultsReport, Limits};
+use crate::contains::{ContainsResult, SubnetContainsResult};
+use crate::covers::CoverageResult;
+use crate::diff::{DiffResult, JsonPatch};
 use crate::error::{IpCalcError, Result};
+use crate::estimate::GenerationEstimate;
+use crate::eui64::Eui64Result;
+use crate::exclude::{Ipv4ExcludeResult, Ipv6ExcludeResult};
+use crate::free_space::FreeSpaceResult;
 use crate::from_range::{Ipv4FromRangeResult, Ipv6FromRangeResult};
+use crate::gaps::GapsResult;
+use crate::grouped_summary::GroupedSummaryResult;
+use crate::hosts::HostsResult;
+use crate::ifid::InterfaceIdResult;
 use crate::ipv4::Ipv4Subnet;
-use crate::ipv6::Ipv6Subnet;
-use crate::subnet_generator::{Ipv4SubnetList, Ipv6SubnetList, SplitSummary};
-use crate::summarize::{Ipv4SummaryResult, Ipv6SummaryResult};
-use serde::Serialize;
+use crate::ipv6::{Ipv6Format, Ipv6FormatResult, Ipv6Style, Ipv6Subnet, ipv6_mask};
+use crate::lookup::LookupResult;
+use crate::lpm::LpmResult;
+use crate::merge::MergeResult;
+use crate::normalize::NormalizeResult;
+use crate::overlap::{OverlapKind, OverlapResult};
+use crate::reverse_dns::ReverseDnsResult;
+use crate::server::ServeSummary;
+use crate::subnet_generator::{
+    DualStackPlan, Ipv4SubnetList, Ipv6SubnetList, SplitPlan, SplitSummary,
+};
+use crate::summarize::{Ipv4SummaryResult, Ipv6SummaryResult, SummaryExplanation};
+use crate::supernet::SupernetResult;
+use crate::tree::SplitTree;
+use crate::usable::{Ipv4NthHostResult, Ipv6NthHostResult, UsabilityResult};
+use crate::vlsm::VlsmResult;
+use crate::walk::Ipv4WalkResult;
+use crate::zonefile::ZoneFileResult;
+use serde::{Serialize, Serializer};
 use std::fmt::Write as FmtWrite;
 use std::fs::File;
 use std::io::Write;
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::path::Path;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub enum OutputFormat {
@@ -19,6 +52,12 @@ pub enum OutputFormat {
     Text,
     Csv,
     Yaml,
+    Env,
+    /// The layout of the classic ipcalc(1) tool (IPv4) and sipcalc (IPv6)
+    Classic,
+    /// Newline-delimited JSON: one compact record per line, and one line per
+    /// subnet for a split
+    Ndjson,
 }
 
 impl std::str::FromStr for OutputFormat {
@@ -30,33 +69,122 @@ impl std::str::FromStr for OutputFormat {
             "text" | "plain" | "txt" => Ok(Self::Text),
             "csv" => Ok(Self::Csv),
             "yaml" | "yml" => Ok(Self::Yaml),
+            "env" => Ok(Self::Env),
+            "classic" => Ok(Self::Classic),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
             _ => Err(format!("Unknown output format: {}", s)),
         }
     }
 }
 
-fn csv_err(e: impl std::fmt::Display) -> IpCalcError {
-    IpCalcError::Csv(e.to_string())
+impl OutputFormat {
+    /// Fail with [`IpCalcError::UnsupportedFormat`] if this format was
+    /// compiled out (the `csv` and `yaml` cargo features).
+    pub fn ensure_available(self) -> Result<()> {
+        match self {
+            Self::Csv if !cfg!(feature = "csv") => {
+                Err(IpCalcError::UnsupportedFormat("csv".to_string()))
+            }
+            Self::Yaml if !cfg!(feature = "yaml") => {
+                Err(IpCalcError::UnsupportedFormat("yaml".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Serialize `value` as YAML (requires the `yaml` feature).
+pub(crate) fn to_yaml<T: Serialize>(value: &T) -> Result<String> {
+    #[cfg(feature = "yaml")]
+    {
+        serde_saphyr::to_string(value).map_err(|e| IpCalcError::Yaml(e.to_string()))
+    }
+    #[cfg(not(feature = "yaml"))]
+    {
+        let _ = value;
+        Err(IpCalcError::UnsupportedFormat("yaml".to_string()))
+    }
 }
 
 pub struct OutputWriter {
     format: OutputFormat,
     file_path: Option<String>,
+    include_kind: bool,
+    json_case: JsonCase,
+    validate_output: bool,
 }
 
 impl OutputWriter {
     pub fn new(format: OutputFormat, file_path: Option<String>) -> Self {
-        Self { format, file_path }
+        Self {
+            format,
+            file_path,
+            include_kind: true,
+            json_case: JsonCase::Snake,
+            validate_output: false,
+        }
+    }
+
+    /// Whether JSON and YAML output starts with the result's `kind` (on by
+    /// default; `--no-kind` turns it off).
+    pub fn with_kind(mut self, include_kind: bool) -> Self {
+        self.include_kind = include_kind;
+        self
+    }
+
+    /// Key naming of JSON output (snake_case by default; `--json-case camel`
+    /// switches it).
+    pub fn with_json_case(mut self, json_case: JsonCase) -> Self {
+        self.json_case = json_case;
+        self
+    }
+
+    /// Check JSON, YAML, and NDJSON output against the result's JSON Schema
+    /// before anything is written (`--validate-output`; needs the `schema`
+    /// feature).
+    pub fn with_validate_output(mut self, validate_output: bool) -> Self {
+        self.validate_output = validate_output;
+        self
     }
 
-    pub fn write<T: Serialize + TextOutput + CsvOutput>(&self, data: &T) -> Result<String> {
+    pub fn write<T: Serialize + TextOutput + CsvOutput + EnvOutput + ResultKind>(
+        &self,
+        data: &T,
+    ) -> Result<String> {
+        self.format.ensure_available()?;
+        let tagged = Kinded::new(data, self.include_kind);
+        if self.validate_output {
+            #[cfg(feature = "schema")]
+            if matches!(
+                self.format,
+                OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Ndjson
+            ) {
+                validate::validate_document::<T>(&serde_json::to_string(&tagged)?)?;
+            }
+            #[cfg(not(feature = "schema"))]
+            return Err(IpCalcError::OutputValidationUnavailable);
+        }
         let output = match self.format {
-            OutputFormat::Json => serde_json::to_string_pretty(data)?,
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&tagged)?;
+                match self.json_case {
+                    JsonCase::Snake => json,
+                    JsonCase::Camel => camel_case_json(&json, true)?,
+                }
+            }
             OutputFormat::Text => data.to_text(),
             OutputFormat::Csv => data.to_csv()?,
-            OutputFormat::Yaml => {
-                serde_saphyr::to_string(data).map_err(|e| IpCalcError::Yaml(e.to_string()))?
-            }
+            OutputFormat::Yaml => to_yaml(&tagged)?,
+            OutputFormat::Env => data.to_env()?,
+            OutputFormat::Classic => data.to_classic()?,
+            OutputFormat::Ndjson => match data.ndjson_records(self.include_kind) {
+                Some(records) => records?
+                    .into_iter()
+                    .map(|record| self.cased_line(record))
+                    .collect::<Result<Vec<_>>>()?
+                    .join("\n"),
+                None => self.json_line(&tagged)?,
+            },
         };
 
         if let Some(ref path) = self.file_path {
@@ -66,6 +194,279 @@ impl OutputWriter {
 
         Ok(output)
     }
+
+    /// `value` as compact single-line JSON in the configured key naming.
+    fn json_line<T: Serialize>(&self, value: &T) -> Result<String> {
+        self.cased_line(serde_json::to_string(value)?)
+    }
+
+    /// A compact JSON line in the configured key naming.
+    fn cased_line(&self, json: String) -> Result<String> {
+        match self.json_case {
+            JsonCase::Snake => Ok(json),
+            JsonCase::Camel => camel_case_json(&json, false),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Result kind discriminator
+// ---------------------------------------------------------------------------
+
+/// The record type of a top-level result, written as a leading `"kind"` field
+/// in JSON and YAML so output from different commands can share one log
+/// stream.
+pub trait ResultKind {
+    /// `None` for results whose JSON shape is fixed elsewhere, such as an
+    /// RFC 6902 patch array.
+    const KIND: Option<&'static str>;
+
+    /// JSON Schema of the result as serialized, without the leading `kind`.
+    #[cfg(feature = "schema")]
+    fn json_schema() -> schemars::Schema;
+
+    /// The compact JSON records NDJSON output writes one per line, in place
+    /// of the whole result on a single line. Splits give one record per
+    /// subnet, the same as a streamed split.
+    fn ndjson_records(&self, _include_kind: bool) -> Option<Result<Vec<String>>> {
+        None
+    }
+}
+
+macro_rules! impl_result_kind {
+    ($($ty:ty => $kind:expr),* $(,)?) => {
+        $(impl $crate::output::ResultKind for $ty {
+            const KIND: Option<&'static str> = $kind;
+
+            #[cfg(feature = "schema")]
+            fn json_schema() -> schemars::Schema {
+                schemars::schema_for!($ty)
+            }
+        })*
+    };
+}
+pub(crate) use impl_result_kind;
+
+/// [`ResultKind`] for a split list, whose NDJSON records are its subnets.
+macro_rules! impl_split_result_kind {
+    ($($ty:ty),* $(,)?) => {
+        $(impl ResultKind for $ty {
+            const KIND: Option<&'static str> = Some("split");
+
+            #[cfg(feature = "schema")]
+            fn json_schema() -> schemars::Schema {
+                schemars::schema_for!($ty)
+            }
+
+            fn ndjson_records(&self, include_kind: bool) -> Option<Result<Vec<String>>> {
+                Some(
+                    self.subnets
+                        .iter()
+                        .map(|subnet| Ok(serde_json::to_string(&Kinded::new(subnet, include_kind))?))
+                        .collect(),
+                )
+            }
+        })*
+    };
+}
+
+impl_split_result_kind!(Ipv4SubnetList, Ipv6SubnetList);
+
+impl_result_kind! {
+    Ipv4Subnet => Some("ipv4_subnet"),
+    Ipv6Subnet => Some("ipv6_subnet"),
+    SplitSummary => Some("split_summary"),
+    SplitPlan => Some("split_plan"),
+    DualStackPlan => Some("dualstack_split"),
+    SplitTree => Some("split_tree"),
+    ContainsResult => Some("contains"),
+    SubnetContainsResult => Some("subnet_contains"),
+    UsabilityResult => Some("is_usable"),
+    Ipv4SummaryResult => Some("summary"),
+    Ipv6SummaryResult => Some("summary"),
+    Ipv4FromRangeResult => Some("from_range"),
+    Ipv6FromRangeResult => Some("from_range"),
+    Ipv4WalkResult => Some("walk"),
+    IpAddResult => Some("add"),
+    HostsResult => Some("hosts"),
+    Ipv4NthHostResult => Some("nth_host"),
+    Ipv6NthHostResult => Some("nth_host"),
+    Ipv4ComplementResult => Some("complement"),
+    Ipv6ComplementResult => Some("complement"),
+    Eui64Result => Some("eui64"),
+    InterfaceIdResult => Some("ifid"),
+    AclWildcardResult => Some("acl_wildcard"),
+    LookupResult => Some("lookup"),
+    LpmResult => Some("lpm"),
+    Ipv6FormatResult => Some("ipv6_format"),
+    Ipv4ExcludeResult => Some("exclude"),
+    Ipv6ExcludeResult => Some("exclude"),
+    GapsResult => Some("gaps"),
+    FreeSpaceResult => Some("free_space"),
+    BatchResult => Some("batch"),
+    CoverageResult => Some("covers"),
+    DiffResult => Some("diff"),
+    JsonPatch => None,
+    AddressCountResult => Some("addresses"),
+    AvailableResult => Some("available"),
+    AllocateResult => Some("allocate"),
+    VlsmResult => Some("vlsm"),
+    OverlapResult => Some("overlap"),
+    GroupedSummaryResult => Some("summarize_grouped"),
+    SupernetResult => Some("supernet"),
+    ReverseDnsResult => Some("reverse_dns"),
+    Limits => Some("limits"),
+    ConfigReport => Some("config"),
+    DefaultsReport => Some("defaults"),
+    ServeSummary => Some("serve"),
+    GenerationEstimate => Some("estimate"),
+    ZoneFileResult => Some("zonefile"),
+    MergeResult => Some("merge"),
+    NormalizeResult => Some("normalize"),
+}
+
+/// A result that serializes with its `kind` as the first field, or exactly as
+/// the result itself when the kind is left out.
+pub struct Kinded<'a, T> {
+    kind: Option<&'static str>,
+    result: &'a T,
+}
+
+impl<'a, T: ResultKind> Kinded<'a, T> {
+    pub fn new(result: &'a T, include_kind: bool) -> Self {
+        Self {
+            kind: T::KIND.filter(|_| include_kind),
+            result,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Kinded<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Tagged<'a, T> {
+            kind: &'static str,
+            #[serde(flatten)]
+            result: &'a T,
+        }
+
+        match self.kind {
+            Some(kind) => Tagged {
+                kind,
+                result: self.result,
+            }
+            .serialize(serializer),
+            None => self.result.serialize(serializer),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Streamed split output
+// ---------------------------------------------------------------------------
+
+impl OutputWriter {
+    /// Whether this format can be written one subnet at a time. JSON and YAML
+    /// documents are built whole, as is NDJSON when it is validated.
+    pub fn can_stream(&self) -> bool {
+        match self.format {
+            OutputFormat::Text | OutputFormat::Csv => true,
+            // Validated output is checked as one document
+            OutputFormat::Ndjson => !self.validate_output,
+            _ => false,
+        }
+    }
+
+    fn stream_target(&self) -> Result<Box<dyn Write>> {
+        Ok(match &self.file_path {
+            Some(path) => Box::new(std::io::BufWriter::new(File::create(Path::new(path))?)),
+            None => Box::new(std::io::BufWriter::new(std::io::stdout().lock())),
+        })
+    }
+
+    /// Finish a stream, matching the trailing blank line [`OutputWriter::write`]
+    /// output gets on stdout. NDJSON gets none, as a blank line is not a record.
+    fn finish_stream(&self, mut out: Box<dyn Write>) -> Result<()> {
+        if self.file_path.is_none() && !matches!(self.format, OutputFormat::Ndjson) {
+            writeln!(out)?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Write an IPv4 split in text, CSV, or NDJSON as `subnets` yields each subnet.
+    pub fn stream_ipv4_split(
+        &self,
+        supernet: &Ipv4Subnet,
+        new_prefix: u8,
+        count: u64,
+        offset: u64,
+        subnets: impl Iterator<Item = Result<Ipv4Subnet>>,
+    ) -> Result<()> {
+        self.format.ensure_available()?;
+        let mut out = self.stream_target()?;
+        if matches!(self.format, OutputFormat::Csv) {
+            #[cfg(feature = "csv")]
+            csv_output::stream_ipv4_split(
+                &mut out,
+                &supernet.input,
+                new_prefix,
+                count,
+                offset,
+                subnets,
+            )?;
+        } else if matches!(self.format, OutputFormat::Ndjson) {
+            for subnet in subnets {
+                let line = self.json_line(&Kinded::new(&subnet?, self.include_kind))?;
+                writeln!(out, "{}", line)?;
+            }
+        } else {
+            let header =
+                split_text_header("IPv4", &supernet.input, new_prefix, None, count, offset);
+            out.write_all(header.as_bytes())?;
+            for (i, subnet) in (offset + 1..).zip(subnets) {
+                writeln!(out, "{}", ipv4_split_text_line(i, &subnet?))?;
+            }
+        }
+        self.finish_stream(out)
+    }
+
+    /// Write an IPv6 split in text, CSV, or NDJSON as `subnets` yields each subnet.
+    pub fn stream_ipv6_split(
+        &self,
+        supernet: &Ipv6Subnet,
+        new_prefix: u8,
+        count: u64,
+        offset: u64,
+        subnets: impl Iterator<Item = Result<Ipv6Subnet>>,
+    ) -> Result<()> {
+        self.format.ensure_available()?;
+        let mut out = self.stream_target()?;
+        if matches!(self.format, OutputFormat::Csv) {
+            #[cfg(feature = "csv")]
+            csv_output::stream_ipv6_split(
+                &mut out,
+                &supernet.input,
+                new_prefix,
+                count,
+                offset,
+                subnets,
+            )?;
+        } else if matches!(self.format, OutputFormat::Ndjson) {
+            for subnet in subnets {
+                let line = self.json_line(&Kinded::new(&subnet?, self.include_kind))?;
+                writeln!(out, "{}", line)?;
+            }
+        } else {
+            let header =
+                split_text_header("IPv6", &supernet.input, new_prefix, None, count, offset);
+            out.write_all(header.as_bytes())?;
+            for (i, subnet) in (offset as u128 + 1..).zip(subnets) {
+                writeln!(out, "{}", ipv6_split_text_line(i, &subnet?))?;
+            }
+        }
+        self.finish_stream(out)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -74,9 +475,170 @@ impl OutputWriter {
 
 pub trait TextOutput {
     fn to_text(&self) -> String;
+
+    /// The classic ipcalc(1)/sipcalc layout, for scripts written against
+    /// those tools. Only subnet calculations have one.
+    fn to_classic(&self) -> Result<String> {
+        Err(IpCalcError::InvalidInput(
+            "--format classic is only supported for subnet calculations".to_string(),
+        ))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Classic ipcalc(1) / sipcalc layout
+// ---------------------------------------------------------------------------
+
+/// Dotted binary with a space where the network bits end, as ipcalc(1)
+/// prints it (`11000000.10101000.00000000. 00000001` for a /24).
+fn classic_binary(value: u32, prefix: u8) -> String {
+    let mut out = String::with_capacity(36);
+    for bit in 0..32u8 {
+        if bit > 0 && bit % 8 == 0 {
+            out.push('.');
+        }
+        if bit == prefix {
+            out.push(' ');
+        }
+        out.push(if (value >> (31 - bit)) & 1 == 1 {
+            '1'
+        } else {
+            '0'
+        });
+    }
+    out
+}
+
+/// The address as given, before host bits were cleared.
+fn classic_input_addr<T: FromStr>(input: &str) -> Option<T> {
+    input
+        .split_once('/')
+        .and_then(|(addr, _)| addr.parse().ok())
+}
+
+/// One `Label:    value    binary` row of the ipcalc(1) layout.
+fn classic_row(out: &mut String, label: &str, value: String, addr: Ipv4Addr, prefix: u8) {
+    let bits = classic_binary(u32::from(addr), prefix);
+    writeln!(out, "{:<11}{:<21}{}", label, value, bits).unwrap();
+}
+
+fn classic_ipv4(s: &Ipv4Subnet) -> String {
+    let mut out = String::new();
+    let address: Ipv4Addr = classic_input_addr(&s.input).unwrap_or(s.network);
+    let prefix = s.prefix_length;
+    let netmask = format!("{} = {}", s.mask, prefix);
+    classic_row(&mut out, "Address:", address.to_string(), address, prefix);
+    classic_row(&mut out, "Netmask:", netmask, s.mask, prefix);
+    classic_row(
+        &mut out,
+        "Wildcard:",
+        s.wildcard.to_string(),
+        s.wildcard,
+        prefix,
+    );
+    writeln!(out, "=>").unwrap();
+
+    if prefix == 32 {
+        classic_row(
+            &mut out,
+            "Hostroute:",
+            s.network.to_string(),
+            s.network,
+            prefix,
+        );
+    } else {
+        let network = format!("{}/{}", s.network, prefix);
+        classic_row(&mut out, "Network:", network, s.network, prefix);
+        classic_row(
+            &mut out,
+            "HostMin:",
+            s.first_host.to_string(),
+            s.first_host,
+            prefix,
+        );
+        classic_row(
+            &mut out,
+            "HostMax:",
+            s.last_host.to_string(),
+            s.last_host,
+            prefix,
+        );
+        if prefix < 31 {
+            classic_row(
+                &mut out,
+                "Broadcast:",
+                s.broadcast.to_string(),
+                s.broadcast,
+                prefix,
+            );
+        }
+    }
+
+    let mut class = format!("Class {}", s.network_class);
+    if s.is_private {
+        class.push_str(", Private Internet");
+    }
+    writeln!(out, "{:<11}{:<22}{}", "Hosts/Net:", s.usable_hosts, class).unwrap();
+    out
+}
+
+fn classic_ipv6(s: &Ipv6Subnet) -> String {
+    let mut out = String::new();
+    let address: Ipv6Addr = classic_input_addr(&s.input).unwrap_or(s.network);
+    let case = s.case.unwrap_or_default();
+    let render = |addr: Ipv6Addr, style: Ipv6Style| Ipv6Format { style, case }.format(&addr);
+    let mask = ipv6_mask(s.prefix_length);
+    let host_id = Ipv6Addr::from(u128::from(address) & !mask);
+
+    writeln!(out, "-[ipv6 : {}] - 0", s.input).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "[IPV6 INFO]").unwrap();
+    writeln!(
+        out,
+        "Expanded Address\t- {}",
+        render(address, Ipv6Style::Full)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "Compressed address\t- {}",
+        render(address, Ipv6Style::Compressed)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "Subnet prefix (masked)\t- {}/{}",
+        render(s.network, Ipv6Style::Expanded),
+        s.prefix_length
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "Address ID (masked)\t- {}/{}",
+        render(host_id, Ipv6Style::Expanded),
+        s.prefix_length
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "Prefix address\t\t- {}",
+        render(Ipv6Addr::from(mask), Ipv6Style::Expanded)
+    )
+    .unwrap();
+    writeln!(out, "Prefix length\t\t- {}", s.prefix_length).unwrap();
+    writeln!(out, "Address type\t\t- {}", s.address_type).unwrap();
+    writeln!(out, "Network range\t\t- {} -", s.network_address_full).unwrap();
+    writeln!(out, "\t\t\t  {}", s.last_address_full).unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "-").unwrap();
+    out
 }
 
 impl TextOutput for Ipv4Subnet {
+    fn to_classic(&self) -> Result<String> {
+        Ok(classic_ipv4(self))
+    }
+
     fn to_text(&self) -> String {
         let mut out = String::new();
         writeln!(out, "IPv4 Subnet Calculator").unwrap();
@@ -86,9 +648,18 @@ impl TextOutput for Ipv4Subnet {
         writeln!(out, "Broadcast Address: {}", self.broadcast).unwrap();
         writeln!(out, "Subnet Mask:       {}", self.mask).unwrap();
         writeln!(out, "Wildcard Mask:     {}", self.wildcard).unwrap();
+        writeln!(out, "Binary Address:    {}", self.binary_address).unwrap();
+        writeln!(out, "Binary Mask:       {}", self.binary_mask).unwrap();
         writeln!(out, "Prefix Length:     /{}", self.prefix_length).unwrap();
-        writeln!(out, "First Host:        {}", self.first_host).unwrap();
-        writeln!(out, "Last Host:         {}", self.last_host).unwrap();
+        let note = if self.rfc3021 {
+            " (point-to-point, RFC 3021)"
+        } else if self.host_route {
+            " (host route)"
+        } else {
+            ""
+        };
+        writeln!(out, "First Host:        {}{}", self.first_host, note).unwrap();
+        writeln!(out, "Last Host:         {}{}", self.last_host, note).unwrap();
         writeln!(out, "Total Hosts:       {}", self.total_hosts).unwrap();
         writeln!(out, "Usable Hosts:      {}", self.usable_hosts).unwrap();
         writeln!(out, "Network Class:     {}", self.network_class).unwrap();
@@ -99,24 +670,72 @@ impl TextOutput for Ipv4Subnet {
         )
         .unwrap();
         writeln!(out, "Address Type:      {}", self.address_type).unwrap();
+        if let Some(entry) = &self.special_purpose {
+            let flag = |f: Option<bool>| match f {
+                Some(true) => "yes",
+                Some(false) => "no",
+                None => "n/a",
+            };
+            let a = &entry.allocation_attributes;
+            writeln!(
+                out,
+                "Registry Entry:    {} ({}, {})",
+                entry.name, entry.block, entry.rfc
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "Attributes:        source={} destination={} forwardable={} globally-reachable={} reserved-by-protocol={}",
+                flag(a.source),
+                flag(a.destination),
+                flag(a.forwardable),
+                flag(a.globally_reachable),
+                flag(Some(a.reserved_by_protocol))
+            )
+            .unwrap();
+        }
         out
     }
 }
 
 impl TextOutput for Ipv6Subnet {
+    fn to_classic(&self) -> Result<String> {
+        Ok(classic_ipv6(self))
+    }
+
     fn to_text(&self) -> String {
         let mut out = String::new();
         writeln!(out, "IPv6 Subnet Calculator").unwrap();
         writeln!(out, "======================").unwrap();
         writeln!(out, "Input:               {}", self.input).unwrap();
-        writeln!(out, "Network Address:     {}", self.network).unwrap();
+        if !self.canonical_input {
+            writeln!(
+                out,
+                "Warning:             input is not in RFC 5952 canonical form (canonical: {})",
+                self.input
+            )
+            .unwrap();
+        }
+        writeln!(out, "Network Address:     {}", self.network_address()).unwrap();
         writeln!(out, "Network (Full):      {}", self.network_address_full).unwrap();
-        writeln!(out, "Last Address:        {}", self.last).unwrap();
+        if let Some(styled) = &self.network_address_styled {
+            writeln!(out, "Network (Styled):    {}", styled).unwrap();
+        }
+        writeln!(out, "Last Address:        {}", self.last_address()).unwrap();
         writeln!(out, "Last Address (Full): {}", self.last_address_full).unwrap();
+        if let Some(styled) = &self.last_address_styled {
+            writeln!(out, "Last (Styled):       {}", styled).unwrap();
+        }
         writeln!(out, "Prefix Length:       /{}", self.prefix_length).unwrap();
         writeln!(out, "Total Addresses:     {}", self.total_addresses).unwrap();
         writeln!(out, "Hextets:             {}", self.hextets.join(":")).unwrap();
         writeln!(out, "Address Type:        {}", self.address_type).unwrap();
+        if let Some(v4) = &self.ipv4_mapped {
+            writeln!(out, "IPv4-Mapped:         {}", v4).unwrap();
+        }
+        if let Some(v4) = &self.sixto4_ipv4 {
+            writeln!(out, "6to4 IPv4:           {}", v4).unwrap();
+        }
         out
     }
 }
@@ -134,55 +753,133 @@ impl TextOutput for ContainsResult {
             if self.contained { "Yes" } else { "No" }
         )
         .unwrap();
+        if let Some(fraction) = self.contained_fraction {
+            writeln!(
+                out,
+                "Partial:           Yes ({:.2}% of the range)",
+                fraction * 100.0
+            )
+            .unwrap();
+        }
         writeln!(out, "Network Address:   {}", self.network_address).unwrap();
         writeln!(out, "Broadcast Address: {}", self.broadcast_address).unwrap();
+        if let (Some(translation), Some(translated)) = (&self.translation, &self.translated_address)
+        {
+            writeln!(out, "Translation:       {}", translation.as_str()).unwrap();
+            writeln!(out, "Translated:        {}", translated).unwrap();
+        }
+        if let Some(ptr_name) = &self.ptr_name {
+            writeln!(out, "PTR Name:          {}", ptr_name).unwrap();
+        }
         out
     }
 }
 
-impl TextOutput for Ipv4SubnetList {
+impl TextOutput for SubnetContainsResult {
     fn to_text(&self) -> String {
+        let yes_no = |b: bool| if b { "Yes" } else { "No" };
         let mut out = String::new();
-        writeln!(out, "IPv4 Subnet Generator").unwrap();
-        writeln!(out, "=====================").unwrap();
-        writeln!(out, "Supernet: {}", self.supernet.input).unwrap();
-        writeln!(out, "New Prefix: /{}", self.new_prefix).unwrap();
-        writeln!(out, "Generated {} subnets:\n", self.requested_count).unwrap();
+        writeln!(out, "Subnet Containment Check").unwrap();
+        writeln!(out, "========================").unwrap();
+        writeln!(out, "Outer:             {}", self.outer).unwrap();
+        writeln!(out, "Inner:             {}", self.inner).unwrap();
+        writeln!(out, "Fully Contained:   {}", yes_no(self.fully_contained)).unwrap();
+        writeln!(out, "Overlaps:          {}", yes_no(self.overlaps)).unwrap();
+        writeln!(out, "Relationship:      {}", self.relationship.as_str()).unwrap();
+        out
+    }
+}
 
-        for (i, subnet) in self.subnets.iter().enumerate() {
-            writeln!(
-                out,
-                "  {}. {}/{} (Hosts: {}-{})",
-                i + 1,
-                subnet.network,
-                subnet.prefix_length,
-                subnet.first_host,
-                subnet.last_host
-            )
-            .unwrap();
+impl TextOutput for UsabilityResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Address Usability Check").unwrap();
+        writeln!(out, "=======================").unwrap();
+        writeln!(out, "Subnet:            {}", self.cidr).unwrap();
+        writeln!(out, "Address:           {}", self.address).unwrap();
+        writeln!(
+            out,
+            "Role:              {} ({})",
+            self.role.as_str(),
+            if self.usable { "usable" } else { "not usable" }
+        )
+        .unwrap();
+        writeln!(out, "Network Address:   {}", self.network_address).unwrap();
+        if let Some(broadcast) = &self.broadcast_address {
+            writeln!(out, "Broadcast Address: {}", broadcast).unwrap();
         }
         out
     }
 }
 
-impl TextOutput for Ipv6SubnetList {
+/// Header shared by the buffered and streamed text forms of a split.
+fn split_text_header(
+    family: &str,
+    supernet: &str,
+    new_prefix: u8,
+    seed: Option<u64>,
+    count: u64,
+    offset: u64,
+) -> String {
+    let mut out = String::new();
+    writeln!(out, "{} Subnet Generator", family).unwrap();
+    writeln!(out, "=====================").unwrap();
+    writeln!(out, "Supernet: {}", supernet).unwrap();
+    writeln!(out, "New Prefix: /{}", new_prefix).unwrap();
+    if offset > 0 {
+        writeln!(out, "Offset: {}", offset).unwrap();
+    }
+    if let Some(seed) = seed {
+        writeln!(out, "Shuffled: yes (seed: {})", seed).unwrap();
+    }
+    writeln!(out, "Generated {} subnets:\n", count).unwrap();
+    out
+}
+
+fn ipv4_split_text_line(index: u64, subnet: &Ipv4Subnet) -> String {
+    format!(
+        "  {}. {}/{} (Hosts: {}-{})",
+        index, subnet.network, subnet.prefix_length, subnet.first_host, subnet.last_host
+    )
+}
+
+fn ipv6_split_text_line(index: u128, subnet: &Ipv6Subnet) -> String {
+    let network = match &subnet.network_address_styled {
+        Some(styled) => styled.clone(),
+        None => subnet.network_address(),
+    };
+    format!("  {}. {}/{}", index, network, subnet.prefix_length)
+}
+
+impl TextOutput for Ipv4SubnetList {
     fn to_text(&self) -> String {
-        let mut out = String::new();
-        writeln!(out, "IPv6 Subnet Generator").unwrap();
-        writeln!(out, "=====================").unwrap();
-        writeln!(out, "Supernet: {}", self.supernet.input).unwrap();
-        writeln!(out, "New Prefix: /{}", self.new_prefix).unwrap();
-        writeln!(out, "Generated {} subnets:\n", self.requested_count).unwrap();
+        let mut out = split_text_header(
+            "IPv4",
+            &self.supernet.input,
+            self.new_prefix,
+            self.seed,
+            self.requested_count,
+            self.offset,
+        );
+        for (i, subnet) in (self.offset + 1..).zip(&self.subnets) {
+            writeln!(out, "{}", ipv4_split_text_line(i, subnet)).unwrap();
+        }
+        out
+    }
+}
 
-        for (i, subnet) in self.subnets.iter().enumerate() {
-            writeln!(
-                out,
-                "  {}. {}/{}",
-                i + 1,
-                subnet.network,
-                subnet.prefix_length
-            )
-            .unwrap();
+impl TextOutput for Ipv6SubnetList {
+    fn to_text(&self) -> String {
+        let mut out = split_text_header(
+            "IPv6",
+            &self.supernet.input,
+            self.new_prefix,
+            self.seed,
+            self.requested_count,
+            self.offset,
+        );
+        for (i, subnet) in (self.offset as u128 + 1..).zip(&self.subnets) {
+            writeln!(out, "{}", ipv6_split_text_line(i, subnet)).unwrap();
         }
         out
     }
@@ -200,6 +897,76 @@ impl TextOutput for SplitSummary {
     }
 }
 
+impl TextOutput for SplitPlan {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Subnet Split Plan").unwrap();
+        writeln!(out, "=================").unwrap();
+        writeln!(out, "Supernet:           {}", self.supernet).unwrap();
+        writeln!(
+            out,
+            "Required:           {} subnets x {} hosts",
+            self.subnets_required, self.hosts_required
+        )
+        .unwrap();
+        writeln!(out, "New Prefix:         /{}", self.new_prefix).unwrap();
+        writeln!(out, "Hosts per Subnet:   {}", self.hosts_per_subnet).unwrap();
+        writeln!(out, "Available Subnets:  {}", self.available_subnets).unwrap();
+        out
+    }
+}
+
+impl TextOutput for SplitTree {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Subnet Split Tree").unwrap();
+        writeln!(out, "=================").unwrap();
+        writeln!(out, "Supernet: {}", self.supernet).unwrap();
+        let levels: Vec<String> = self.levels.iter().map(|p| format!("/{}", p)).collect();
+        writeln!(out, "Levels:   {}", levels.join(" -> ")).unwrap();
+        writeln!(out, "Blocks:   {}", self.node_count).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "{}", self.supernet).unwrap();
+        for (level, cidr, _) in self.walk() {
+            writeln!(out, "{:indent$}{}", "", cidr, indent = level * 2).unwrap();
+        }
+        out
+    }
+}
+
+impl TextOutput for DualStackPlan {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Dual-Stack Plan").unwrap();
+        writeln!(out, "===============").unwrap();
+        writeln!(
+            out,
+            "IPv4 Supernet:  {} -> /{}",
+            self.v4_supernet, self.v4_prefix
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "IPv6 Supernet:  {} -> /{}",
+            self.v6_supernet, self.v6_prefix
+        )
+        .unwrap();
+        writeln!(out, "Pairs:          {}", self.pairs.len()).unwrap();
+        writeln!(out).unwrap();
+        for pair in &self.pairs {
+            writeln!(
+                out,
+                "  {}. {:<18} {}",
+                pair.index,
+                pair.v4.cidr(),
+                pair.v6.cidr()
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
 macro_rules! impl_summary_text_output {
     ($ty:ty) => {
         impl TextOutput for $ty {
@@ -209,9 +976,33 @@ macro_rules! impl_summary_text_output {
                 writeln!(out, "==================").unwrap();
                 writeln!(out, "Input CIDRs:   {}", self.input_count).unwrap();
                 writeln!(out, "Output CIDRs:  {}", self.output_count).unwrap();
+                if let Some(stats) = &self.extraction {
+                    writeln!(out, "Extracted:     {} line(s)", stats.extracted_lines).unwrap();
+                    writeln!(out, "Skipped:       {} line(s)", stats.skipped_lines).unwrap();
+                }
                 writeln!(out).unwrap();
                 for (i, cidr) in self.cidrs.iter().enumerate() {
-                    writeln!(out, "  {}. {}/{}", i + 1, cidr.network, cidr.prefix_length).unwrap();
+                    writeln!(out, "  {}. {}", i + 1, cidr.cidr()).unwrap();
+                }
+                if let Some(explanation) = &self.explanation {
+                    write_summary_explanation(&mut out, explanation);
+                }
+                if let Some(changes) = &self.changes {
+                    writeln!(out).unwrap();
+                    writeln!(out, "Changes:").unwrap();
+                    for cidr in &changes.removed {
+                        writeln!(out, "  - {}", cidr).unwrap();
+                    }
+                    for cidr in &changes.added {
+                        writeln!(out, "  + {}", cidr).unwrap();
+                    }
+                }
+                if !self.warnings.is_empty() {
+                    writeln!(out).unwrap();
+                    writeln!(out, "Warnings:").unwrap();
+                    for warning in &self.warnings {
+                        writeln!(out, "  {}", warning).unwrap();
+                    }
                 }
                 out
             }
@@ -219,6 +1010,35 @@ macro_rules! impl_summary_text_output {
     };
 }
 
+fn write_summary_explanation(out: &mut String, explanation: &SummaryExplanation) {
+    writeln!(out).unwrap();
+    writeln!(out, "Explanation:").unwrap();
+    writeln!(out, "  Normalized input:").unwrap();
+    for cidr in &explanation.normalized {
+        writeln!(out, "    {}", cidr).unwrap();
+    }
+    writeln!(out, "  Removed as contained:").unwrap();
+    if explanation.removed.is_empty() {
+        writeln!(out, "    (none)").unwrap();
+    }
+    for removal in &explanation.removed {
+        writeln!(out, "    {} (inside {})", removal.cidr, removal.parent).unwrap();
+    }
+    writeln!(out, "  Sibling merges:").unwrap();
+    if explanation.merges.is_empty() {
+        writeln!(out, "    (none)").unwrap();
+    }
+    for merge in &explanation.merges {
+        writeln!(
+            out,
+            "    {} -> {}",
+            merge.children.join(" + "),
+            merge.parent
+        )
+        .unwrap();
+    }
+}
+
 impl_summary_text_output!(Ipv4SummaryResult);
 impl_summary_text_output!(Ipv6SummaryResult);
 
@@ -234,7 +1054,7 @@ macro_rules! impl_from_range_text_output {
                 writeln!(out, "CIDR Count:    {}", self.cidr_count).unwrap();
                 writeln!(out).unwrap();
                 for (i, cidr) in self.cidrs.iter().enumerate() {
-                    writeln!(out, "  {}. {}/{}", i + 1, cidr.network, cidr.prefix_length).unwrap();
+                    writeln!(out, "  {}. {}", i + 1, cidr.cidr()).unwrap();
                 }
                 out
             }
@@ -245,377 +1065,1021 @@ macro_rules! impl_from_range_text_output {
 impl_from_range_text_output!(Ipv4FromRangeResult);
 impl_from_range_text_output!(Ipv6FromRangeResult);
 
-impl TextOutput for BatchResult {
+impl TextOutput for Ipv4WalkResult {
     fn to_text(&self) -> String {
         let mut out = String::new();
-        writeln!(out, "Batch CIDR Processing").unwrap();
-        writeln!(out, "=====================").unwrap();
-        writeln!(out, "Total CIDRs: {}", self.count).unwrap();
+        writeln!(out, "Subnet Walk").unwrap();
+        writeln!(out, "===========").unwrap();
+        writeln!(out, "Start: {}", self.start).unwrap();
+        writeln!(out, "End:   {}", self.end).unwrap();
+        writeln!(out, "Count: {}", self.count).unwrap();
         writeln!(out).unwrap();
+        for subnet in &self.subnets {
+            writeln!(out, "  {}/{}", subnet.network, subnet.prefix_length).unwrap();
+        }
+        out
+    }
+}
 
-        let total = self.count;
-        for (i, entry) in self.results.iter().enumerate() {
-            writeln!(out, "--- [{}/{}] {} ---", i + 1, total, entry.cidr).unwrap();
-            match &entry.result {
-                BatchEntryResult::Ok { subnet } => match subnet.as_ref() {
-                    SubnetResult::V4(s) => out.push_str(&s.to_text()),
-                    SubnetResult::V6(s) => out.push_str(&s.to_text()),
-                },
+impl TextOutput for IpAddResult {
+    fn to_text(&self) -> String {
+        format!("{}\n", self.result)
+    }
+}
+
+impl TextOutput for HostsResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        for host in &self.hosts {
+            writeln!(out, "{}", host).unwrap();
+        }
+        out
+    }
+}
+
+macro_rules! impl_nth_host_text_output {
+    ($($ty:ty),*) => {
+        $(impl TextOutput for $ty {
+            fn to_text(&self) -> String {
+                format!("{}\n", self.address)
+            }
+        })*
+    };
+}
+
+impl_nth_host_text_output!(Ipv4NthHostResult, Ipv6NthHostResult);
+
+macro_rules! impl_complement_text_output {
+    ($ty:ty) => {
+        impl TextOutput for $ty {
+            fn to_text(&self) -> String {
+                let mut out = String::new();
+                writeln!(out, "CIDR Complement").unwrap();
+                writeln!(out, "===============").unwrap();
+                writeln!(out, "CIDR:          {}", self.cidr).unwrap();
+                writeln!(out, "Within:        {}", self.within).unwrap();
+                writeln!(out, "CIDR Count:    {}", self.cidr_count).unwrap();
+                writeln!(out).unwrap();
+                for (i, cidr) in self.cidrs.iter().enumerate() {
+                    writeln!(out, "  {}. {}", i + 1, cidr.cidr()).unwrap();
+                }
+                out
+            }
+        }
+    };
+}
+
+impl_complement_text_output!(Ipv4ComplementResult);
+impl_complement_text_output!(Ipv6ComplementResult);
+
+impl TextOutput for AclWildcardResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "ACL Wildcard").unwrap();
+        writeln!(out, "============").unwrap();
+        writeln!(out, "CIDR:     {}", self.cidr).unwrap();
+        writeln!(out, "Base:     {}", self.base).unwrap();
+        writeln!(out, "Wildcard: {}", self.wildcard).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "{}", self.snippet).unwrap();
+        out
+    }
+}
+
+impl TextOutput for LookupResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "CIDR Lookup").unwrap();
+        writeln!(out, "===========").unwrap();
+        writeln!(out, "Query:   {}", self.query).unwrap();
+        if self.truncated {
+            writeln!(
+                out,
+                "Matches: {} (showing {})",
+                self.total_matches,
+                self.matches.len()
+            )
+            .unwrap();
+        } else {
+            writeln!(out, "Matches: {}", self.total_matches).unwrap();
+        }
+        if !self.matches.is_empty() {
+            writeln!(out).unwrap();
+        }
+        for (i, m) in self.matches.iter().enumerate() {
+            writeln!(out, "  {}. {}", i + 1, m.cidr).unwrap();
+        }
+        out
+    }
+}
+
+impl TextOutput for LpmResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Longest Prefix Match").unwrap();
+        writeln!(out, "====================").unwrap();
+        writeln!(out, "Address:    {}", self.address).unwrap();
+        writeln!(out, "Candidates: {}", self.candidate_count).unwrap();
+        writeln!(
+            out,
+            "Match:      {}",
+            self.matched.as_deref().unwrap_or("no match")
+        )
+        .unwrap();
+        out
+    }
+}
+
+impl TextOutput for Ipv6FormatResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "IPv6 Address").unwrap();
+        writeln!(out, "============").unwrap();
+        writeln!(out, "Input:      {}", self.input).unwrap();
+        writeln!(out, "Compressed: {}", self.compressed).unwrap();
+        writeln!(out, "Expanded:   {}", self.expanded).unwrap();
+        out
+    }
+}
+
+impl TextOutput for Eui64Result {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "EUI-64 Address").unwrap();
+        writeln!(out, "==============").unwrap();
+        writeln!(out, "Prefix:       {}", self.prefix).unwrap();
+        writeln!(out, "MAC:          {}", self.mac).unwrap();
+        writeln!(out, "Address:      {}", self.derived_address).unwrap();
+        writeln!(out, "Full Address: {}", self.derived_address_full).unwrap();
+        out
+    }
+}
+
+impl TextOutput for InterfaceIdResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Interface Identifier").unwrap();
+        writeln!(out, "====================").unwrap();
+        writeln!(out, "Address:      {}", self.address).unwrap();
+        writeln!(out, "Network:      {}", self.network).unwrap();
+        writeln!(out, "Host Bits:    {}", self.host_bits).unwrap();
+        writeln!(out, "Interface ID: {}", self.interface_id).unwrap();
+        writeln!(out, "As Address:   {}", self.interface_id_address).unwrap();
+        writeln!(out, "Full:         {}", self.interface_id_full).unwrap();
+        out
+    }
+}
+
+macro_rules! impl_exclude_text_output {
+    ($ty:ty) => {
+        impl TextOutput for $ty {
+            fn to_text(&self) -> String {
+                let mut out = String::new();
+                writeln!(out, "CIDR Exclusion").unwrap();
+                writeln!(out, "==============").unwrap();
+                writeln!(out, "Supernet:      {}", self.supernet).unwrap();
+                writeln!(out, "Excluded:      {}", self.excluded.join(", ")).unwrap();
+                if !self.ignored.is_empty() {
+                    writeln!(out, "Ignored:       {}", self.ignored.join(", ")).unwrap();
+                }
+                writeln!(out, "CIDR Count:    {}", self.cidr_count).unwrap();
+                writeln!(out).unwrap();
+                for (i, cidr) in self.cidrs.iter().enumerate() {
+                    writeln!(out, "  {}. {}", i + 1, cidr.cidr()).unwrap();
+                }
+                out
+            }
+        }
+    };
+}
+
+impl_exclude_text_output!(Ipv4ExcludeResult);
+impl_exclude_text_output!(Ipv6ExcludeResult);
+
+impl TextOutput for GapsResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Free Space").unwrap();
+        writeln!(out, "==========").unwrap();
+        writeln!(out, "Supernet:       {}", self.supernet).unwrap();
+        if !self.used.is_empty() {
+            writeln!(out, "Used:           {}", self.used.join(", ")).unwrap();
+        }
+        writeln!(out, "Free Addresses: {}", self.free_addresses).unwrap();
+        if let Some(range) = &self.largest_free_block {
+            writeln!(
+                out,
+                "Largest Free:   {} - {} ({} addresses)",
+                range.first, range.last, range.address_count
+            )
+            .unwrap();
+        }
+        writeln!(out, "Free CIDRs:     {}", self.free_cidr_count).unwrap();
+        if !self.free_cidrs.is_empty() {
+            writeln!(out).unwrap();
+        }
+        for (i, cidr) in self.free_cidrs.iter().enumerate() {
+            writeln!(out, "  {}. {}", i + 1, cidr).unwrap();
+        }
+        out
+    }
+}
+
+impl TextOutput for FreeSpaceResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Free Space").unwrap();
+        writeln!(out, "==========").unwrap();
+        writeln!(out, "Parent:     {}", self.parent).unwrap();
+        writeln!(out, "Allocated:  {}", self.allocated_count).unwrap();
+        if !self.clamped.is_empty() {
+            writeln!(
+                out,
+                "Clamped:    {} (not inside the parent)",
+                self.clamped.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(out, "Free CIDRs: {}", self.free_count).unwrap();
+        if !self.free_cidrs.is_empty() {
+            writeln!(out).unwrap();
+        }
+        for (i, cidr) in self.free_cidrs.iter().enumerate() {
+            writeln!(out, "  {}. {}", i + 1, cidr).unwrap();
+        }
+        out
+    }
+}
+
+impl TextOutput for BatchResult {
+    /// Each entry in its classic layout, blank-line separated; invalid
+    /// entries print as ipcalc(1) reports them.
+    fn to_classic(&self) -> Result<String> {
+        let blocks: Vec<String> = self
+            .results
+            .iter()
+            .map(|entry| match &entry.result {
+                BatchEntryResult::Ok { subnet } => match subnet.as_ref() {
+                    SubnetResult::V4(s) => classic_ipv4(s),
+                    SubnetResult::V6(s) => classic_ipv6(s),
+                },
+                BatchEntryResult::Err { .. } => format!("INVALID ADDRESS: {}\n", entry.cidr),
+            })
+            .collect();
+        Ok(blocks.join("\n"))
+    }
+
+    /// Filtered to errors, just one `CIDR: error` line per failed entry.
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        if self.filter == Some(BatchFilter::Errors) {
+            for entry in &self.results {
+                if let BatchEntryResult::Err { error } = &entry.result {
+                    writeln!(out, "{}: {}", entry.cidr, error).unwrap();
+                }
+            }
+            return out;
+        }
+
+        writeln!(out, "Batch CIDR Processing").unwrap();
+        writeln!(out, "=====================").unwrap();
+        writeln!(out, "Total CIDRs: {}", self.count).unwrap();
+        if let Some(shown) = self.shown_count {
+            writeln!(out, "Shown: {}", shown).unwrap();
+        }
+        match self.ignored_count {
+            Some(n) if n > self.ignored.len() => {
+                writeln!(out, "Ignored: {} ({}, ...)", n, self.ignored.join(", ")).unwrap()
+            }
+            Some(n) if n > 0 => {
+                writeln!(out, "Ignored: {} ({})", n, self.ignored.join(", ")).unwrap()
+            }
+            Some(_) => writeln!(out, "Ignored: 0").unwrap(),
+            None => {}
+        }
+        if self.non_canonical > 0 {
+            writeln!(out, "Non-canonical IPv6: {}", self.non_canonical).unwrap();
+        }
+        if self.cache_hits > 0 {
+            writeln!(out, "Cache Hits: {}", self.cache_hits).unwrap();
+        }
+        writeln!(out).unwrap();
+
+        let total = self.results.len();
+        for (i, entry) in self.results.iter().enumerate() {
+            writeln!(out, "--- [{}/{}] {} ---", i + 1, total, entry.cidr).unwrap();
+            if !entry.tags.is_empty() {
+                let tags: Vec<String> = entry
+                    .tags
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect();
+                writeln!(out, "Tags: {}", tags.join(" ")).unwrap();
+            }
+            for warning in &entry.warnings {
+                writeln!(out, "Warning: {}", warning).unwrap();
+            }
+            match &entry.result {
+                BatchEntryResult::Ok { subnet } => match subnet.as_ref() {
+                    SubnetResult::V4(s) => out.push_str(&s.to_text()),
+                    SubnetResult::V6(s) => out.push_str(&s.to_text()),
+                },
                 BatchEntryResult::Err { error } => {
                     writeln!(out, "Error: {}", error).unwrap();
                     writeln!(out).unwrap();
                 }
             }
         }
+        if let Some(summary) = &self.aggregate_summary {
+            writeln!(out, "Aggregate Summary:").unwrap();
+            for (i, cidr) in summary.ipv4.iter().chain(&summary.ipv6).enumerate() {
+                writeln!(out, "  {}. {}", i + 1, cidr).unwrap();
+            }
+        }
         out
     }
 }
 
-// ---------------------------------------------------------------------------
-// CsvOutput trait + implementations
-// ---------------------------------------------------------------------------
+impl TextOutput for CoverageResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Coverage Check").unwrap();
+        writeln!(out, "==============").unwrap();
+        writeln!(out, "Target:   {}", self.target).unwrap();
+        writeln!(out, "Inputs:   {}", self.input_count).unwrap();
+        writeln!(out, "Exact:    {}", if self.exact { "Yes" } else { "No" }).unwrap();
+        for (label, cidrs) in [("Gaps", &self.gaps), ("Excess", &self.excess)] {
+            writeln!(out).unwrap();
+            writeln!(out, "{} ({}):", label, cidrs.len()).unwrap();
+            for cidr in cidrs {
+                writeln!(out, "  {}", cidr).unwrap();
+            }
+        }
+        out
+    }
+}
 
-pub trait CsvOutput {
-    fn to_csv(&self) -> Result<String>;
+impl TextOutput for DiffResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "CIDR Diff").unwrap();
+        writeln!(out, "=========").unwrap();
+        writeln!(out, "Old:       {}", self.old_count).unwrap();
+        writeln!(out, "New:       {}", self.new_count).unwrap();
+        writeln!(out, "Unchanged: {}", self.unchanged).unwrap();
+        writeln!(out).unwrap();
+        for cidr in &self.removed {
+            writeln!(out, "- {}", cidr).unwrap();
+        }
+        for cidr in &self.added {
+            writeln!(out, "+ {}", cidr).unwrap();
+        }
+        out
+    }
 }
 
-fn ipv4_csv_header() -> &'static [&'static str] {
-    &[
-        "input",
-        "network_address",
-        "broadcast_address",
-        "subnet_mask",
-        "wildcard_mask",
-        "prefix_length",
-        "first_host",
-        "last_host",
-        "total_hosts",
-        "usable_hosts",
-        "network_class",
-        "is_private",
-        "address_type",
-    ]
-}
-
-fn write_ipv4_csv_record(wtr: &mut csv::Writer<Vec<u8>>, s: &Ipv4Subnet) -> Result<()> {
-    wtr.write_record([
-        &s.input,
-        &s.network.to_string(),
-        &s.broadcast.to_string(),
-        &s.mask.to_string(),
-        &s.wildcard.to_string(),
-        &s.prefix_length.to_string(),
-        &s.first_host.to_string(),
-        &s.last_host.to_string(),
-        &s.total_hosts.to_string(),
-        &s.usable_hosts.to_string(),
-        &s.network_class,
-        &s.is_private.to_string(),
-        &s.address_type,
-    ])
-    .map_err(csv_err)
-}
-
-fn ipv6_csv_header() -> &'static [&'static str] {
-    &[
-        "input",
-        "network_address",
-        "network_address_full",
-        "last_address",
-        "last_address_full",
-        "prefix_length",
-        "total_addresses",
-        "hextets",
-        "address_type",
-    ]
-}
-
-fn write_ipv6_csv_record(wtr: &mut csv::Writer<Vec<u8>>, s: &Ipv6Subnet) -> Result<()> {
-    wtr.write_record([
-        &s.input,
-        &s.network.to_string(),
-        &s.network_address_full,
-        &s.last.to_string(),
-        &s.last_address_full,
-        &s.prefix_length.to_string(),
-        &s.total_addresses,
-        &s.hextets.join(":"),
-        &s.address_type,
-    ])
-    .map_err(csv_err)
-}
-
-fn finish_csv(wtr: csv::Writer<Vec<u8>>) -> Result<String> {
-    let bytes = wtr.into_inner().map_err(csv_err)?;
-    String::from_utf8(bytes).map_err(csv_err)
-}
-
-impl CsvOutput for Ipv4Subnet {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
-        write_ipv4_csv_record(&mut wtr, self)?;
-        finish_csv(wtr)
+impl TextOutput for JsonPatch {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        for op in &self.0 {
+            match &op.value {
+                Some(value) => writeln!(out, "{} {} {}", op.op, op.path, value).unwrap(),
+                None => writeln!(out, "{} {}", op.op, op.path).unwrap(),
+            }
+        }
+        out
     }
 }
 
-impl CsvOutput for Ipv6Subnet {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
-        write_ipv6_csv_record(&mut wtr, self)?;
-        finish_csv(wtr)
+impl TextOutput for AddressCountResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Distinct Address Count").unwrap();
+        writeln!(out, "======================").unwrap();
+        writeln!(out, "Input CIDRs:   {}", self.input_count).unwrap();
+        writeln!(out, "Merged Blocks: {}", self.block_count).unwrap();
+        writeln!(out, "Addresses:     {}", self.address_count).unwrap();
+        writeln!(out).unwrap();
+        for (i, block) in self.blocks.iter().enumerate() {
+            writeln!(out, "  {}. {}", i + 1, block).unwrap();
+        }
+        out
     }
 }
 
-impl CsvOutput for ContainsResult {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record([
-            "cidr",
-            "address",
-            "contained",
-            "network_address",
-            "broadcast_address",
-        ])
-        .map_err(csv_err)?;
-        wtr.write_record([
-            &self.cidr,
-            &self.address,
-            &self.contained.to_string(),
-            &self.network_address,
-            &self.broadcast_address,
-        ])
-        .map_err(csv_err)?;
-        finish_csv(wtr)
-    }
-}
-
-impl CsvOutput for SplitSummary {
-    fn to_csv(&self) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(["supernet", "new_prefix", "available_subnets"])
-            .map_err(csv_err)?;
-        wtr.write_record([
-            &self.supernet,
-            &self.new_prefix.to_string(),
-            &self.available_subnets,
-        ])
-        .map_err(csv_err)?;
-        finish_csv(wtr)
+impl TextOutput for OverlapResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "CIDR Overlap Check").unwrap();
+        writeln!(out, "==================").unwrap();
+        writeln!(out, "Input CIDRs:   {}", self.input_count).unwrap();
+        writeln!(out, "Overlaps:      {}", self.overlap_count).unwrap();
+        writeln!(out).unwrap();
+        for pair in &self.pairs {
+            let relation = match pair.relation {
+                OverlapKind::Equal => "equals",
+                OverlapKind::Contains => "contains",
+                OverlapKind::ContainedBy => "is contained by",
+                OverlapKind::Partial => "partially overlaps",
+            };
+            writeln!(out, "  {} {} {}", pair.first, relation, pair.second).unwrap();
+        }
+        out
     }
 }
 
-impl CsvOutput for Ipv4SubnetList {
-    fn to_csv(&self) -> Result<String> {
+impl TextOutput for GroupedSummaryResult {
+    fn to_text(&self) -> String {
         let mut out = String::new();
-        writeln!(out, "# supernet: {}", self.supernet.input).unwrap();
-        writeln!(out, "# new_prefix: {}", self.new_prefix).unwrap();
-        writeln!(out, "# count: {}", self.requested_count).unwrap();
+        writeln!(out, "Grouped Summary").unwrap();
+        writeln!(out, "===============").unwrap();
+        writeln!(out, "Tag:         {}", self.tag).unwrap();
+        writeln!(out, "Input CIDRs: {}", self.input_count).unwrap();
+        for group in &self.groups {
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "{}={} ({} CIDRs):",
+                self.tag, group.value, group.input_count
+            )
+            .unwrap();
+            for cidr in group.summary.ipv4.iter().chain(&group.summary.ipv6) {
+                writeln!(out, "  {}", cidr).unwrap();
+            }
+        }
+        if !self.untagged.is_empty() {
+            writeln!(out).unwrap();
+            writeln!(out, "Untagged: {}", self.untagged.join(", ")).unwrap();
+        }
+        out
+    }
+}
 
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
-        for subnet in &self.subnets {
-            write_ipv4_csv_record(&mut wtr, subnet)?;
+impl TextOutput for AvailableResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Available Subnets").unwrap();
+        writeln!(out, "=================").unwrap();
+        writeln!(out, "Supernet:      {}", self.supernet).unwrap();
+        writeln!(out, "Prefix:        /{}", self.prefix).unwrap();
+        writeln!(out, "Used CIDRs:    {}", self.used_count).unwrap();
+        writeln!(out, "Available:     {}", self.available_count).unwrap();
+        writeln!(out).unwrap();
+        for (i, cidr) in self.available.iter().enumerate() {
+            writeln!(out, "  {}. {}", i + 1, cidr).unwrap();
         }
-        out.push_str(&finish_csv(wtr)?);
-        Ok(out)
+        out
     }
 }
 
-impl CsvOutput for Ipv6SubnetList {
-    fn to_csv(&self) -> Result<String> {
+impl TextOutput for AllocateResult {
+    fn to_text(&self) -> String {
         let mut out = String::new();
-        writeln!(out, "# supernet: {}", self.supernet.input).unwrap();
-        writeln!(out, "# new_prefix: {}", self.new_prefix).unwrap();
-        writeln!(out, "# count: {}", self.requested_count).unwrap();
+        writeln!(out, "Allocated Subnets").unwrap();
+        writeln!(out, "=================").unwrap();
+        writeln!(out, "Supernet:      {}", self.supernet).unwrap();
+        writeln!(out, "Prefix:        /{}", self.prefix).unwrap();
+        writeln!(out, "Used CIDRs:    {}", self.used_count).unwrap();
+        writeln!(out, "Remaining:     {}", self.remaining_count).unwrap();
+        writeln!(out).unwrap();
+        for (i, cidr) in self.allocated.iter().enumerate() {
+            writeln!(out, "  {}. {}", i + 1, cidr).unwrap();
+        }
+        out
+    }
+}
 
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
-        for subnet in &self.subnets {
-            write_ipv6_csv_record(&mut wtr, subnet)?;
+impl TextOutput for VlsmResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "VLSM Plan").unwrap();
+        writeln!(out, "=========").unwrap();
+        writeln!(out, "Supernet:   {}", self.supernet).unwrap();
+        writeln!(
+            out,
+            "Allocated:  {} of {} addresses",
+            self.allocated_addresses, self.total_addresses
+        )
+        .unwrap();
+        writeln!(out, "Remaining:  {}", self.remaining_addresses).unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "Hosts      Subnet               First Host         Last Host          Usable   Waste    Remaining"
+        )
+        .unwrap();
+        for a in &self.allocations {
+            writeln!(
+                out,
+                "{:<10} {:<20} {:<18} {:<18} {:<8} {:<8} {}",
+                a.hosts,
+                format!("{}/{}", a.subnet.network, a.prefix),
+                a.subnet.first_host,
+                a.subnet.last_host,
+                a.subnet.usable_hosts,
+                a.waste,
+                a.remaining_addresses
+            )
+            .unwrap();
         }
-        out.push_str(&finish_csv(wtr)?);
-        Ok(out)
+        if !self.free.is_empty() {
+            writeln!(out).unwrap();
+            writeln!(out, "Free: {}", self.free.join(", ")).unwrap();
+        }
+        out
     }
 }
 
-impl CsvOutput for Ipv4SummaryResult {
-    fn to_csv(&self) -> Result<String> {
+impl TextOutput for SupernetResult {
+    fn to_text(&self) -> String {
         let mut out = String::new();
-        writeln!(out, "# input_count: {}", self.input_count).unwrap();
-        writeln!(out, "# output_count: {}", self.output_count).unwrap();
+        writeln!(out, "Covering Supernet").unwrap();
+        writeln!(out, "=================").unwrap();
+        writeln!(out, "Input CIDRs:     {}", self.input_count).unwrap();
+        writeln!(out, "Supernet:        {}", self.supernet).unwrap();
+        writeln!(out, "Total Addresses: {}", self.total_addresses).unwrap();
+        writeln!(out, "Extra Addresses: {}", self.extra_addresses).unwrap();
+        out
+    }
+}
 
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
-        for subnet in &self.cidrs {
-            write_ipv4_csv_record(&mut wtr, subnet)?;
+impl TextOutput for Limits {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Server Limits").unwrap();
+        writeln!(out, "=============").unwrap();
+        writeln!(out, "Max Batch Size:         {}", self.max_batch_size).unwrap();
+        writeln!(out, "Max Body Size:          {} bytes", self.max_body_size).unwrap();
+        writeln!(out, "Max Summarize Inputs:   {}", self.max_summarize_inputs).unwrap();
+        writeln!(out, "Max Generated CIDRs:    {}", self.max_generated_cidrs).unwrap();
+        writeln!(
+            out,
+            "Max Generated Subnets:  {}",
+            self.max_generated_subnets
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "Max Input Length:       {} bytes",
+            self.max_input_length
+        )
+        .unwrap();
+        writeln!(out, "Timeout:                {}s", self.timeout_seconds).unwrap();
+        if let Some(rate) = self.rate_limit_per_second {
+            writeln!(out, "Rate Limit:             {}/s", rate).unwrap();
         }
-        out.push_str(&finish_csv(wtr)?);
-        Ok(out)
+        if let Some(burst) = self.rate_limit_burst {
+            writeln!(out, "Rate Limit Burst:       {}", burst).unwrap();
+        }
+        out
     }
 }
 
-impl CsvOutput for Ipv6SummaryResult {
-    fn to_csv(&self) -> Result<String> {
+/// Render a config value the way it would be written in TOML.
+fn config_value_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "(unset)".to_string(),
+        serde_json::Value::String(s) => format!("\"{}\"", s),
+        other => other.to_string(),
+    }
+}
+
+impl TextOutput for ServeSummary {
+    fn to_text(&self) -> String {
+        let on_off = |b: bool| if b { "on" } else { "off" };
         let mut out = String::new();
-        writeln!(out, "# input_count: {}", self.input_count).unwrap();
-        writeln!(out, "# output_count: {}", self.output_count).unwrap();
+        writeln!(out, "Serve Dry Run").unwrap();
+        writeln!(out, "=============").unwrap();
+        writeln!(out, "Address:                http://{}", self.address).unwrap();
+        writeln!(
+            out,
+            "Config File:            {}",
+            self.config_file
+                .as_deref()
+                .unwrap_or("(none, defaults only)")
+        )
+        .unwrap();
+        writeln!(out, "Swagger UI:             {}", on_off(self.swagger)).unwrap();
+        writeln!(out, "Web UI:                 {}", on_off(self.ui)).unwrap();
+        writeln!(out, "Landing Page:           {}", on_off(self.landing_page)).unwrap();
+        writeln!(
+            out,
+            "IPAM:                   {}",
+            self.ipam_backend.as_deref().unwrap_or("off")
+        )
+        .unwrap();
+        writeln!(out, "Endpoints:              {}", self.endpoint_count).unwrap();
+        writeln!(out).unwrap();
+        out.push_str(&self.limits.to_text());
+        out
+    }
+}
 
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
-        for subnet in &self.cidrs {
-            write_ipv6_csv_record(&mut wtr, subnet)?;
+impl TextOutput for DefaultsReport {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Compiled-in Defaults").unwrap();
+        writeln!(out, "====================").unwrap();
+        writeln!(out, "Version: {}", self.version).unwrap();
+        writeln!(out).unwrap();
+        let width = self
+            .defaults
+            .iter()
+            .map(|d| d.name.len())
+            .max()
+            .unwrap_or(0);
+        let value_width = self
+            .defaults
+            .iter()
+            .map(|d| d.value.to_string().len())
+            .max()
+            .unwrap_or(0);
+        for entry in &self.defaults {
+            writeln!(
+                out,
+                "{:<width$} = {:<value_width$}  # {}",
+                entry.name, entry.value, entry.module
+            )
+            .unwrap();
         }
-        out.push_str(&finish_csv(wtr)?);
-        Ok(out)
+        out
     }
 }
 
-impl CsvOutput for Ipv4FromRangeResult {
-    fn to_csv(&self) -> Result<String> {
+impl TextOutput for ConfigReport {
+    fn to_text(&self) -> String {
         let mut out = String::new();
-        writeln!(out, "# start_address: {}", self.start_address).unwrap();
-        writeln!(out, "# end_address: {}", self.end_address).unwrap();
-        writeln!(out, "# cidr_count: {}", self.cidr_count).unwrap();
+        writeln!(out, "Effective Configuration").unwrap();
+        writeln!(out, "=======================").unwrap();
+        if self.files.is_empty() {
+            writeln!(out, "Files: (none, defaults only)").unwrap();
+        } else {
+            writeln!(out, "Files: {}", self.files.join(" -> ")).unwrap();
+        }
+        writeln!(out).unwrap();
+        let width = self.entries.iter().map(|e| e.key.len()).max().unwrap_or(0);
+        let value_width = self
+            .entries
+            .iter()
+            .map(|e| config_value_text(&e.value).len())
+            .max()
+            .unwrap_or(0);
+        for entry in &self.entries {
+            writeln!(
+                out,
+                "{:<width$} = {:<value_width$}  # {}",
+                entry.key,
+                config_value_text(&entry.value),
+                entry.source
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+impl TextOutput for GenerationEstimate {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Generation Estimate").unwrap();
+        writeln!(out, "===================").unwrap();
+        writeln!(out, "Operation:         {}", self.operation).unwrap();
+        writeln!(out, "Input:             {}", self.input).unwrap();
+        writeln!(out, "Would Generate:    {}", self.count).unwrap();
+        writeln!(out, "Limit:             {}", self.limit).unwrap();
+        writeln!(
+            out,
+            "Within Limit:      {}",
+            if self.within_limit { "Yes" } else { "No" }
+        )
+        .unwrap();
+        writeln!(out, "Estimated Memory:  {}", self.estimated_memory).unwrap();
+        out
+    }
+}
 
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
-        for subnet in &self.cidrs {
-            write_ipv4_csv_record(&mut wtr, subnet)?;
+impl TextOutput for ZoneFileResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "; Reverse zone data for {}", self.cidr).unwrap();
+        writeln!(
+            out,
+            "; {} zone(s), {} PTR record(s)",
+            self.zone_count, self.record_count
+        )
+        .unwrap();
+        for zone in &self.zones {
+            writeln!(out).unwrap();
+            writeln!(out, "$ORIGIN {}", zone.origin).unwrap();
+            for record in &zone.records {
+                writeln!(out, "{}\tIN\tPTR\t{}", record.name, record.ptr).unwrap();
+            }
         }
-        out.push_str(&finish_csv(wtr)?);
-        Ok(out)
+        out
     }
 }
 
-impl CsvOutput for Ipv6FromRangeResult {
-    fn to_csv(&self) -> Result<String> {
+impl TextOutput for ReverseDnsResult {
+    fn to_text(&self) -> String {
         let mut out = String::new();
-        writeln!(out, "# start_address: {}", self.start_address).unwrap();
-        writeln!(out, "# end_address: {}", self.end_address).unwrap();
-        writeln!(out, "# cidr_count: {}", self.cidr_count).unwrap();
+        writeln!(out, "Reverse DNS").unwrap();
+        writeln!(out, "===========").unwrap();
+        writeln!(out, "CIDR:    {}", self.cidr).unwrap();
+        for zone in &self.zones {
+            writeln!(out, "Zone:    {}", zone).unwrap();
+        }
+        if let Some(records) = &self.records {
+            writeln!(out, "Records: {}", records.len()).unwrap();
+            for record in records {
+                writeln!(out, "  {}\tIN\tPTR\t; {}", record.name, record.address).unwrap();
+            }
+        }
+        out
+    }
+}
 
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
-        for subnet in &self.cidrs {
-            write_ipv6_csv_record(&mut wtr, subnet)?;
+impl TextOutput for MergeResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Merged Batch Results").unwrap();
+        writeln!(out, "====================").unwrap();
+        writeln!(out, "Files:              {}", self.files).unwrap();
+        writeln!(out, "Input Entries:      {}", self.input_entries).unwrap();
+        writeln!(out, "Duplicates Removed: {}", self.duplicates_removed).unwrap();
+        writeln!(out, "Conflicts:          {}", self.conflicts.len()).unwrap();
+        for conflict in &self.conflicts {
+            writeln!(
+                out,
+                "  {} ({} vs {})",
+                conflict.cidr, conflict.kept_from, conflict.conflicting_file
+            )
+            .unwrap();
         }
-        out.push_str(&finish_csv(wtr)?);
+        writeln!(out).unwrap();
+        out.push_str(&self.batch.to_text());
+        out
+    }
+}
+
+impl TextOutput for NormalizeResult {
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+        for line in &self.lines {
+            writeln!(out, "{}", line).unwrap();
+        }
+        out
+    }
+}
+
+// ---------------------------------------------------------------------------
+// EnvOutput trait + implementations
+// ---------------------------------------------------------------------------
+
+/// Shell variable assignments (`IPCALC_NETWORK_ADDRESS=10.0.0.0`), one per
+/// line, for `eval "$(ipcalc ... --format env)"`.
+///
+/// By default every serialized field becomes `IPCALC_<FIELD>`, with nested
+/// fields joined by `_` and list items numbered (`IPCALC_CIDRS_0_...`).
+pub trait EnvOutput: Serialize {
+    fn to_env(&self) -> Result<String> {
+        let mut out = String::new();
+        write_env(&mut out, "IPCALC", &serde_json::to_value(self)?);
         Ok(out)
     }
 }
 
-impl CsvOutput for BatchResult {
-    fn to_csv(&self) -> Result<String> {
+/// Append `value` as assignments under the variable-name prefix `name`.
+/// Nulls are left out.
+fn write_env(out: &mut String, name: &str, value: &serde_json::Value) {
+    use serde_json::Value;
+    match value {
+        Value::Null => {}
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                write_env(out, &format!("{}_{}", name, env_name(key)), field);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                write_env(out, &format!("{}_{}", name, i), item);
+            }
+        }
+        Value::String(s) => writeln!(out, "{}={}", name, shell_quote(s)).unwrap(),
+        other => writeln!(out, "{}={}", name, other).unwrap(),
+    }
+}
+
+/// Uppercase a field name, mapping anything that cannot appear in a shell
+/// variable name to `_`.
+fn env_name(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Single-quote `s` unless it only holds characters the shell takes literally.
+fn shell_quote(s: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "._:/@%+-,".contains(c);
+    if !s.is_empty() && s.chars().all(plain) {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+macro_rules! impl_env_output {
+    ($($ty:ty),* $(,)?) => {
+        $(impl EnvOutput for $ty {})*
+    };
+}
+
+impl_env_output! {
+    Ipv4Subnet,
+    Ipv6Subnet,
+    Ipv4SubnetList,
+    Ipv6SubnetList,
+    SplitSummary,
+    SplitPlan,
+    DualStackPlan,
+    SplitTree,
+    ContainsResult,
+    SubnetContainsResult,
+    UsabilityResult,
+    Ipv4SummaryResult,
+    Ipv6SummaryResult,
+    Ipv4FromRangeResult,
+    Ipv6FromRangeResult,
+    Ipv4WalkResult,
+    IpAddResult,
+    HostsResult,
+    Ipv4NthHostResult,
+    Ipv6NthHostResult,
+    Ipv4ComplementResult,
+    Ipv6ComplementResult,
+    Eui64Result,
+    InterfaceIdResult,
+    AclWildcardResult,
+    LookupResult,
+    LpmResult,
+    Ipv6FormatResult,
+    Ipv4ExcludeResult,
+    Ipv6ExcludeResult,
+    GapsResult,
+    FreeSpaceResult,
+    CoverageResult,
+    DiffResult,
+    JsonPatch,
+    AddressCountResult,
+    AvailableResult,
+    AllocateResult,
+    VlsmResult,
+    OverlapResult,
+    GroupedSummaryResult,
+    SupernetResult,
+    ReverseDnsResult,
+    Limits,
+    ConfigReport,
+    ServeSummary,
+    GenerationEstimate,
+    ZoneFileResult,
+    MergeResult,
+    NormalizeResult,
+}
+
+/// Batch entries are numbered, with each subnet's fields directly under the
+/// entry's index (`IPCALC_0_NETWORK_ADDRESS`, `IPCALC_1_ERROR`).
+impl EnvOutput for DefaultsReport {
+    fn to_env(&self) -> Result<String> {
         let mut out = String::new();
-        writeln!(out, "# count: {}", self.count).unwrap();
-
-        let mut wtr = csv::Writer::from_writer(Vec::new());
-        // Unified header covering both IPv4/IPv6 fields + error column
-        wtr.write_record([
-            "cidr",
-            "network_address",
-            "broadcast_address",
-            "subnet_mask",
-            "wildcard_mask",
-            "prefix_length",
-            "first_host",
-            "last_host",
-            "total_hosts",
-            "usable_hosts",
-            "network_class",
-            "is_private",
-            "network_address_full",
-            "last_address",
-            "last_address_full",
-            "total_addresses",
-            "hextets",
-            "address_type",
-            "error",
-        ])
-        .map_err(csv_err)?;
-
-        for entry in &self.results {
-            match &entry.result {
+        writeln!(out, "IPCALC_VERSION={}", shell_quote(&self.version)).unwrap();
+        for entry in &self.defaults {
+            writeln!(out, "IPCALC_{}={}", env_name(&entry.name), entry.value).unwrap();
+        }
+        Ok(out)
+    }
+}
+
+impl EnvOutput for BatchResult {
+    fn to_env(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "IPCALC_COUNT={}", self.count).unwrap();
+        for (i, entry) in self.results.iter().enumerate() {
+            let name = format!("IPCALC_{}", i);
+            writeln!(out, "{}_CIDR={}", name, shell_quote(&entry.cidr)).unwrap();
+            let value = match &entry.result {
                 BatchEntryResult::Ok { subnet } => match subnet.as_ref() {
-                    SubnetResult::V4(s) => {
-                        wtr.write_record([
-                            &entry.cidr,
-                            &s.network.to_string(),
-                            &s.broadcast.to_string(),
-                            &s.mask.to_string(),
-                            &s.wildcard.to_string(),
-                            &s.prefix_length.to_string(),
-                            &s.first_host.to_string(),
-                            &s.last_host.to_string(),
-                            &s.total_hosts.to_string(),
-                            &s.usable_hosts.to_string(),
-                            &s.network_class,
-                            &s.is_private.to_string(),
-                            "",
-                            "",
-                            "",
-                            "",
-                            "",
-                            &s.address_type,
-                            "",
-                        ])
-                        .map_err(csv_err)?;
-                    }
-                    SubnetResult::V6(s) => {
-                        wtr.write_record([
-                            &entry.cidr,
-                            &s.network.to_string(),
-                            "",
-                            "",
-                            "",
-                            &s.prefix_length.to_string(),
-                            "",
-                            "",
-                            "",
-                            "",
-                            "",
-                            "",
-                            &s.network_address_full,
-                            &s.last.to_string(),
-                            &s.last_address_full,
-                            &s.total_addresses,
-                            &s.hextets.join(":"),
-                            &s.address_type,
-                            "",
-                        ])
-                        .map_err(csv_err)?;
-                    }
+                    SubnetResult::V4(s) => serde_json::to_value(s)?,
+                    SubnetResult::V6(s) => serde_json::to_value(s)?,
                 },
-                BatchEntryResult::Err { error } => {
-                    wtr.write_record([
-                        &entry.cidr,
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        "",
-                        error.as_str(),
-                    ])
-                    .map_err(csv_err)?;
-                }
+                BatchEntryResult::Err { error } => serde_json::json!({ "error": error }),
+            };
+            write_env(&mut out, &name, &value);
+        }
+        Ok(out)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CsvOutput trait + implementations
+// ---------------------------------------------------------------------------
+
+pub trait CsvOutput {
+    fn to_csv(&self) -> Result<String>;
+}
+
+#[cfg(feature = "csv")]
+mod csv_output;
+#[cfg(feature = "csv")]
+pub use csv_output::{CsvRecord, CsvSession, ipv4_split_csv_chunks, ipv6_split_csv_chunks};
+
+mod json_case;
+pub use json_case::{JsonCase, camel_case_json};
+
+#[cfg(feature = "schema")]
+mod validate;
+
+/// Without the `csv` feature every result refuses CSV output.
+#[cfg(not(feature = "csv"))]
+impl<T: ?Sized> CsvOutput for T {
+    fn to_csv(&self) -> Result<String> {
+        Err(IpCalcError::UnsupportedFormat("csv".to_string()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `cargo test --no-default-features --lib output::` covers the minimal
+    // build: it still compiles, and asking for CSV or YAML is a clear error
+    // rather than a missing match arm.
+    #[test]
+    fn test_compiled_out_formats_rejected() {
+        let subnet = Ipv4Subnet::from_cidr("10.0.0.0/24").unwrap();
+        for (format, enabled) in [
+            (OutputFormat::Csv, cfg!(feature = "csv")),
+            (OutputFormat::Yaml, cfg!(feature = "yaml")),
+        ] {
+            let result = OutputWriter::new(format, None).write(&subnet);
+            if enabled {
+                assert!(result.is_ok());
+            } else {
+                assert!(matches!(result, Err(IpCalcError::UnsupportedFormat(_))));
             }
         }
+        assert!(
+            OutputWriter::new(OutputFormat::Json, None)
+                .write(&subnet)
+                .is_ok()
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv_session_writes_header_once() {
+        let mut session = CsvSession::new(Vec::new());
+        for i in 0..1000u32 {
+            let cidr = format!("{}/32", std::net::Ipv4Addr::from(0x0a00_0000 + i));
+            session
+                .append(&Ipv4Subnet::from_cidr(&cidr).unwrap())
+                .unwrap();
+        }
+        assert_eq!(session.rows(), 1000);
+        let csv = session.finish().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 1001);
+        assert!(lines[0].starts_with("input,network_address,"));
+        assert_eq!(csv.matches("input,").count(), 1);
+        assert!(lines[1000].starts_with("10.0.3.231/32,"));
 
-        out.push_str(&finish_csv(wtr)?);
-        Ok(out)
+        // An empty session is just the header
+        let empty = CsvSession::<Ipv6Subnet>::new(Vec::new()).finish().unwrap();
+        assert_eq!(empty.lines().count(), 1);
+    }
+
+    #[test]
+    fn test_env_names_and_quoting() {
+        assert_eq!(env_name("network_address"), "NETWORK_ADDRESS");
+        assert_eq!(env_name("ipv4-mapped"), "IPV4_MAPPED");
+        assert_eq!(shell_quote("10.0.0.0/24"), "10.0.0.0/24");
+        assert_eq!(shell_quote("Private (RFC 1918)"), "'Private (RFC 1918)'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+
+        let subnet = Ipv4Subnet::from_cidr("192.168.1.0/24").unwrap();
+        let env = subnet.to_env().unwrap();
+        assert!(env.contains("IPCALC_NETWORK_ADDRESS=192.168.1.0\n"));
+        assert!(env.contains("IPCALC_PREFIX_LENGTH=24\n"));
+        assert!(env.contains("IPCALC_IS_PRIVATE=true\n"));
     }
 }
@@ -0,0 +1,1451 @@
+//! CSV renderings of the result types, compiled with the `csv` feature.
+
+use super::*;
+use std::marker::PhantomData;
+
+fn csv_err(e: impl std::fmt::Display) -> IpCalcError {
+    IpCalcError::Csv(e.to_string())
+}
+
+fn ipv4_csv_header() -> &'static [&'static str] {
+    &[
+        "input",
+        "network_address",
+        "broadcast_address",
+        "subnet_mask",
+        "wildcard_mask",
+        "binary_address",
+        "binary_mask",
+        "prefix_length",
+        "first_host",
+        "last_host",
+        "total_hosts",
+        "usable_hosts",
+        "network_class",
+        "is_private",
+        "address_type",
+        "rfc3021",
+        "host_route",
+    ]
+}
+
+fn write_ipv4_csv_record<W: Write>(wtr: &mut csv::Writer<W>, s: &Ipv4Subnet) -> Result<()> {
+    wtr.write_record([
+        &s.input,
+        &s.network.to_string(),
+        &s.broadcast.to_string(),
+        &s.mask.to_string(),
+        &s.wildcard.to_string(),
+        &s.binary_address,
+        &s.binary_mask,
+        &s.prefix_length.to_string(),
+        &s.first_host.to_string(),
+        &s.last_host.to_string(),
+        &s.total_hosts.to_string(),
+        &s.usable_hosts.to_string(),
+        &s.network_class,
+        &s.is_private.to_string(),
+        &s.address_type,
+        &s.rfc3021.to_string(),
+        &s.host_route.to_string(),
+    ])
+    .map_err(csv_err)
+}
+
+fn ipv6_csv_header() -> &'static [&'static str] {
+    &[
+        "input",
+        "network_address",
+        "network_address_full",
+        "last_address",
+        "last_address_full",
+        "prefix_length",
+        "total_addresses",
+        "hextets",
+        "address_type",
+        "sixto4_ipv4",
+        "canonical_input",
+    ]
+}
+
+fn write_ipv6_csv_record<W: Write>(wtr: &mut csv::Writer<W>, s: &Ipv6Subnet) -> Result<()> {
+    let sixto4 = s.sixto4_ipv4.clone().unwrap_or_default();
+    wtr.write_record([
+        &s.input,
+        &s.network_address(),
+        &s.network_address_full,
+        &s.last_address(),
+        &s.last_address_full,
+        &s.prefix_length.to_string(),
+        &s.total_addresses,
+        &s.hextets.join(":"),
+        &s.address_type,
+        &sixto4,
+        &s.canonical_input.to_string(),
+    ])
+    .map_err(csv_err)
+}
+
+fn finish_csv(wtr: csv::Writer<Vec<u8>>) -> Result<String> {
+    let bytes = wtr.into_inner().map_err(csv_err)?;
+    String::from_utf8(bytes).map_err(csv_err)
+}
+
+// ---------------------------------------------------------------------------
+// Reusable CSV session
+// ---------------------------------------------------------------------------
+
+/// A result written as one CSV row under a fixed header, so many of them can
+/// share a [`CsvSession`].
+pub trait CsvRecord {
+    fn csv_header() -> &'static [&'static str];
+    fn write_csv_record<W: Write>(&self, wtr: &mut csv::Writer<W>) -> Result<()>;
+}
+
+impl CsvRecord for Ipv4Subnet {
+    fn csv_header() -> &'static [&'static str] {
+        ipv4_csv_header()
+    }
+
+    fn write_csv_record<W: Write>(&self, wtr: &mut csv::Writer<W>) -> Result<()> {
+        write_ipv4_csv_record(wtr, self)
+    }
+}
+
+impl CsvRecord for Ipv6Subnet {
+    fn csv_header() -> &'static [&'static str] {
+        ipv6_csv_header()
+    }
+
+    fn write_csv_record<W: Write>(&self, wtr: &mut csv::Writer<W>) -> Result<()> {
+        write_ipv6_csv_record(wtr, self)
+    }
+}
+
+/// One CSV writer kept across many single-subnet results, for callers that
+/// calculate subnets one at a time and want a single table. The header is
+/// written once, ahead of the first row, rather than once per result as
+/// concatenating [`CsvOutput::to_csv`] output would.
+pub struct CsvSession<T, W: Write = Vec<u8>> {
+    wtr: csv::Writer<W>,
+    rows: usize,
+    header_written: bool,
+    record: PhantomData<fn(&T)>,
+}
+
+impl<T: CsvRecord, W: Write> CsvSession<T, W> {
+    pub fn new(out: W) -> Self {
+        CsvSession {
+            wtr: csv::Writer::from_writer(out),
+            rows: 0,
+            header_written: false,
+            record: PhantomData,
+        }
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        if !self.header_written {
+            self.wtr.write_record(T::csv_header()).map_err(csv_err)?;
+            self.header_written = true;
+        }
+        Ok(())
+    }
+
+    /// Add `record` as the next row.
+    pub fn append(&mut self, record: &T) -> Result<()> {
+        self.write_header()?;
+        record.write_csv_record(&mut self.wtr)?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Rows appended so far, not counting the header.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Flush and return the underlying writer. A session with no rows still
+    /// gets its header.
+    pub fn into_inner(mut self) -> Result<W> {
+        self.write_header()?;
+        self.wtr.into_inner().map_err(csv_err)
+    }
+}
+
+impl<T: CsvRecord> CsvSession<T> {
+    /// The table written so far, as a string.
+    pub fn finish(self) -> Result<String> {
+        String::from_utf8(self.into_inner()?).map_err(csv_err)
+    }
+}
+
+impl CsvOutput for Ipv4Subnet {
+    fn to_csv(&self) -> Result<String> {
+        let mut session = CsvSession::new(Vec::new());
+        session.append(self)?;
+        session.finish()
+    }
+}
+
+impl CsvOutput for Ipv6Subnet {
+    fn to_csv(&self) -> Result<String> {
+        let mut session = CsvSession::new(Vec::new());
+        session.append(self)?;
+        session.finish()
+    }
+}
+
+impl CsvOutput for ContainsResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "cidr",
+            "address",
+            "contained",
+            "partial",
+            "contained_fraction",
+            "network_address",
+            "broadcast_address",
+            "translation",
+            "translated_address",
+            "ptr_name",
+        ])
+        .map_err(csv_err)?;
+        wtr.write_record([
+            &self.cidr,
+            &self.address,
+            &self.contained.to_string(),
+            &self.partial.to_string(),
+            &self
+                .contained_fraction
+                .map(|f| f.to_string())
+                .unwrap_or_default(),
+            &self.network_address,
+            &self.broadcast_address,
+            self.translation.map(|t| t.as_str()).unwrap_or(""),
+            self.translated_address.as_deref().unwrap_or(""),
+            self.ptr_name.as_deref().unwrap_or(""),
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for SubnetContainsResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "outer",
+            "inner",
+            "fully_contained",
+            "overlaps",
+            "relationship",
+            "outer_prefix",
+            "inner_prefix",
+        ])
+        .map_err(csv_err)?;
+        wtr.write_record([
+            &self.outer,
+            &self.inner,
+            &self.fully_contained.to_string(),
+            &self.overlaps.to_string(),
+            self.relationship.as_str(),
+            &self.outer_prefix.to_string(),
+            &self.inner_prefix.to_string(),
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for UsabilityResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "cidr",
+            "address",
+            "role",
+            "usable",
+            "network_address",
+            "broadcast_address",
+        ])
+        .map_err(csv_err)?;
+        wtr.write_record([
+            &self.cidr,
+            &self.address,
+            self.role.as_str(),
+            &self.usable.to_string(),
+            &self.network_address,
+            self.broadcast_address.as_deref().unwrap_or(""),
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for SplitSummary {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["supernet", "new_prefix", "available_subnets"])
+            .map_err(csv_err)?;
+        wtr.write_record([
+            &self.supernet,
+            &self.new_prefix.to_string(),
+            &self.available_subnets,
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for CoverageResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# target: {}", self.target).unwrap();
+        writeln!(out, "# exact: {}", self.exact).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["kind", "cidr"]).map_err(csv_err)?;
+        for cidr in &self.gaps {
+            wtr.write_record(["gap", cidr]).map_err(csv_err)?;
+        }
+        for cidr in &self.excess {
+            wtr.write_record(["excess", cidr]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for DiffResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# old_count: {}", self.old_count).unwrap();
+        writeln!(out, "# new_count: {}", self.new_count).unwrap();
+        writeln!(out, "# unchanged: {}", self.unchanged).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["change", "cidr"]).map_err(csv_err)?;
+        for cidr in &self.removed {
+            wtr.write_record(["removed", cidr]).map_err(csv_err)?;
+        }
+        for cidr in &self.added {
+            wtr.write_record(["added", cidr]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for JsonPatch {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["op", "path", "value"]).map_err(csv_err)?;
+        for op in &self.0 {
+            wtr.write_record([&op.op, &op.path, op.value.as_deref().unwrap_or("")])
+                .map_err(csv_err)?;
+        }
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for DualStackPlan {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# v4_supernet: {}", self.v4_supernet).unwrap();
+        writeln!(out, "# v6_supernet: {}", self.v6_supernet).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "index",
+            "v4_network",
+            "v4_prefix_length",
+            "v4_first_host",
+            "v4_last_host",
+            "v4_usable_hosts",
+            "v6_network",
+            "v6_prefix_length",
+            "v6_last_address",
+        ])
+        .map_err(csv_err)?;
+        for pair in &self.pairs {
+            wtr.write_record([
+                &pair.index.to_string(),
+                &pair.v4.network.to_string(),
+                &pair.v4.prefix_length.to_string(),
+                &pair.v4.first_host.to_string(),
+                &pair.v4.last_host.to_string(),
+                &pair.v4.usable_hosts.to_string(),
+                &pair.v6.network_address(),
+                &pair.v6.prefix_length.to_string(),
+                &pair.v6.last_address(),
+            ])
+            .map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for SplitPlan {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "supernet",
+            "subnets_required",
+            "hosts_required",
+            "new_prefix",
+            "hosts_per_subnet",
+            "available_subnets",
+        ])
+        .map_err(csv_err)?;
+        wtr.write_record([
+            &self.supernet,
+            &self.subnets_required.to_string(),
+            &self.hosts_required.to_string(),
+            &self.new_prefix.to_string(),
+            &self.hosts_per_subnet,
+            &self.available_subnets,
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for SplitTree {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["level", "cidr", "parent"])
+            .map_err(csv_err)?;
+        for (level, cidr, parent) in self.walk() {
+            wtr.write_record([level.to_string().as_str(), cidr, parent])
+                .map_err(csv_err)?;
+        }
+        finish_csv(wtr)
+    }
+}
+
+/// `# key: value` preamble shared by the buffered and streamed CSV forms of
+/// a split.
+fn split_csv_preamble(
+    supernet: &str,
+    new_prefix: u8,
+    count: u64,
+    offset: u64,
+    seed: Option<u64>,
+) -> String {
+    let mut out = String::new();
+    writeln!(out, "# supernet: {}", supernet).unwrap();
+    writeln!(out, "# new_prefix: {}", new_prefix).unwrap();
+    writeln!(out, "# count: {}", count).unwrap();
+    if offset > 0 {
+        writeln!(out, "# offset: {}", offset).unwrap();
+    }
+    if let Some(seed) = seed {
+        writeln!(out, "# shuffle_seed: {}", seed).unwrap();
+    }
+    out
+}
+
+impl CsvOutput for Ipv4SubnetList {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = split_csv_preamble(
+            &self.supernet.input,
+            self.new_prefix,
+            self.requested_count,
+            self.offset,
+            self.seed,
+        );
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
+        for subnet in &self.subnets {
+            write_ipv4_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv6SubnetList {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = split_csv_preamble(
+            &self.supernet.input,
+            self.new_prefix,
+            self.requested_count,
+            self.offset,
+            self.seed,
+        );
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
+        for subnet in &self.subnets {
+            write_ipv6_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv4SummaryResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# input_count: {}", self.input_count).unwrap();
+        writeln!(out, "# output_count: {}", self.output_count).unwrap();
+        if let Some(stats) = &self.extraction {
+            writeln!(out, "# extracted_lines: {}", stats.extracted_lines).unwrap();
+            writeln!(out, "# skipped_lines: {}", stats.skipped_lines).unwrap();
+        }
+        if let Some(changes) = &self.changes {
+            writeln!(out, "# blocks_added: {}", changes.added.join(" ")).unwrap();
+            writeln!(out, "# blocks_removed: {}", changes.removed.join(" ")).unwrap();
+        }
+        for warning in &self.warnings {
+            writeln!(out, "# warning: {}", warning).unwrap();
+        }
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv4_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv6SummaryResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# input_count: {}", self.input_count).unwrap();
+        writeln!(out, "# output_count: {}", self.output_count).unwrap();
+        if let Some(stats) = &self.extraction {
+            writeln!(out, "# extracted_lines: {}", stats.extracted_lines).unwrap();
+            writeln!(out, "# skipped_lines: {}", stats.skipped_lines).unwrap();
+        }
+        if let Some(changes) = &self.changes {
+            writeln!(out, "# blocks_added: {}", changes.added.join(" ")).unwrap();
+            writeln!(out, "# blocks_removed: {}", changes.removed.join(" ")).unwrap();
+        }
+        for warning in &self.warnings {
+            writeln!(out, "# warning: {}", warning).unwrap();
+        }
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv6_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv4FromRangeResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# start_address: {}", self.start_address).unwrap();
+        writeln!(out, "# end_address: {}", self.end_address).unwrap();
+        writeln!(out, "# cidr_count: {}", self.cidr_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv4_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv4WalkResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# start: {}", self.start).unwrap();
+        writeln!(out, "# end: {}", self.end).unwrap();
+        writeln!(out, "# count: {}", self.count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
+        for subnet in &self.subnets {
+            write_ipv4_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for IpAddResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["address", "offset", "result"])
+            .map_err(csv_err)?;
+        wtr.write_record([&self.address, &self.offset, &self.result])
+            .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for HostsResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# cidr: {}", self.cidr).unwrap();
+        writeln!(out, "# count: {}", self.count).unwrap();
+        writeln!(out, "# truncated: {}", self.truncated).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["address"]).map_err(csv_err)?;
+        for host in &self.hosts {
+            wtr.write_record([host]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+macro_rules! impl_nth_host_csv_output {
+    ($($ty:ty),*) => {
+        $(impl CsvOutput for $ty {
+            fn to_csv(&self) -> Result<String> {
+                let mut wtr = csv::Writer::from_writer(Vec::new());
+                wtr.write_record(["cidr", "n", "address"])
+                    .map_err(csv_err)?;
+                wtr.write_record([&self.cidr, &self.n.to_string(), &self.address])
+                    .map_err(csv_err)?;
+                finish_csv(wtr)
+            }
+        })*
+    };
+}
+
+impl_nth_host_csv_output!(Ipv4NthHostResult, Ipv6NthHostResult);
+
+impl CsvOutput for Ipv6FromRangeResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# start_address: {}", self.start_address).unwrap();
+        writeln!(out, "# end_address: {}", self.end_address).unwrap();
+        writeln!(out, "# cidr_count: {}", self.cidr_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv6_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv4ComplementResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# cidr: {}", self.cidr).unwrap();
+        writeln!(out, "# within: {}", self.within).unwrap();
+        writeln!(out, "# cidr_count: {}", self.cidr_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv4_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv6ComplementResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# cidr: {}", self.cidr).unwrap();
+        writeln!(out, "# within: {}", self.within).unwrap();
+        writeln!(out, "# cidr_count: {}", self.cidr_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv6_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for AclWildcardResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["cidr", "base", "wildcard", "snippet"])
+            .map_err(csv_err)?;
+        wtr.write_record([&self.cidr, &self.base, &self.wildcard, &self.snippet])
+            .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for LookupResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# query: {}", self.query).unwrap();
+        writeln!(out, "# total_matches: {}", self.total_matches).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["cidr", "prefix_length"])
+            .map_err(csv_err)?;
+        for m in &self.matches {
+            wtr.write_record([m.cidr.as_str(), &m.prefix_length.to_string()])
+                .map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for LpmResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["address", "candidate_count", "matched", "prefix_length"])
+            .map_err(csv_err)?;
+        wtr.write_record([
+            self.address.clone(),
+            self.candidate_count.to_string(),
+            self.matched.clone().unwrap_or_default(),
+            self.prefix_length
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for Ipv6FormatResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["input", "compressed", "expanded"])
+            .map_err(csv_err)?;
+        wtr.write_record([&self.input, &self.compressed, &self.expanded])
+            .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for Eui64Result {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["prefix", "mac", "derived_address", "derived_address_full"])
+            .map_err(csv_err)?;
+        wtr.write_record([
+            &self.prefix,
+            &self.mac,
+            &self.derived_address,
+            &self.derived_address_full,
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for InterfaceIdResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "input",
+            "address",
+            "network",
+            "prefix_length",
+            "host_bits",
+            "interface_id",
+            "interface_id_address",
+            "interface_id_full",
+        ])
+        .map_err(csv_err)?;
+        wtr.write_record([
+            &self.input,
+            &self.address,
+            &self.network,
+            &self.prefix_length.to_string(),
+            &self.host_bits.to_string(),
+            &self.interface_id,
+            &self.interface_id_address,
+            &self.interface_id_full,
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for Ipv4ExcludeResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = exclude_csv_preamble(&self.supernet, &self.excluded, &self.ignored);
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv4_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Ipv6ExcludeResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = exclude_csv_preamble(&self.supernet, &self.excluded, &self.ignored);
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
+        for subnet in &self.cidrs {
+            write_ipv6_csv_record(&mut wtr, subnet)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for GapsResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# supernet: {}", self.supernet).unwrap();
+        writeln!(out, "# used: {}", self.used.join(" ")).unwrap();
+        writeln!(out, "# free_addresses: {}", self.free_addresses).unwrap();
+        if let Some(range) = &self.largest_free_block {
+            writeln!(out, "# largest_free_block: {}-{}", range.first, range.last).unwrap();
+        }
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["cidr"]).map_err(csv_err)?;
+        for cidr in &self.free_cidrs {
+            wtr.write_record([cidr]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for FreeSpaceResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# parent: {}", self.parent).unwrap();
+        writeln!(out, "# allocated_count: {}", self.allocated_count).unwrap();
+        if !self.clamped.is_empty() {
+            writeln!(out, "# clamped: {}", self.clamped.join(" ")).unwrap();
+        }
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["cidr"]).map_err(csv_err)?;
+        for cidr in &self.free_cidrs {
+            wtr.write_record([cidr]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+fn exclude_csv_preamble(supernet: &str, excluded: &[String], ignored: &[String]) -> String {
+    let mut out = String::new();
+    writeln!(out, "# supernet: {}", supernet).unwrap();
+    writeln!(out, "# excluded: {}", excluded.join(" ")).unwrap();
+    if !ignored.is_empty() {
+        writeln!(out, "# ignored: {}", ignored.join(" ")).unwrap();
+    }
+    out
+}
+
+impl CsvOutput for BatchResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# count: {}", self.count).unwrap();
+        if let Some(shown) = self.shown_count {
+            writeln!(out, "# shown_count: {}", shown).unwrap();
+        }
+        if let Some(ignored) = self.ignored_count {
+            writeln!(out, "# ignored_count: {}", ignored).unwrap();
+            writeln!(out, "# ignored: {}", self.ignored.join(" ")).unwrap();
+        }
+        writeln!(out, "# non_canonical: {}", self.non_canonical).unwrap();
+        writeln!(out, "# cache_hits: {}", self.cache_hits).unwrap();
+        if let Some(summary) = &self.aggregate_summary {
+            writeln!(out, "# aggregate_summary_v4: {}", summary.ipv4.join(" ")).unwrap();
+            writeln!(out, "# aggregate_summary_v6: {}", summary.ipv6.join(" ")).unwrap();
+        }
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        // Unified header covering both IPv4/IPv6 fields + error column
+        wtr.write_record([
+            "cidr",
+            "network_address",
+            "broadcast_address",
+            "subnet_mask",
+            "wildcard_mask",
+            "prefix_length",
+            "first_host",
+            "last_host",
+            "total_hosts",
+            "usable_hosts",
+            "network_class",
+            "is_private",
+            "network_address_full",
+            "last_address",
+            "last_address_full",
+            "total_addresses",
+            "hextets",
+            "address_type",
+            "error",
+            "tags",
+        ])
+        .map_err(csv_err)?;
+
+        for entry in &self.results {
+            let tags = entry
+                .tags
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(";");
+            match &entry.result {
+                BatchEntryResult::Ok { subnet } => match subnet.as_ref() {
+                    SubnetResult::V4(s) => {
+                        wtr.write_record([
+                            &entry.cidr,
+                            &s.network.to_string(),
+                            &s.broadcast.to_string(),
+                            &s.mask.to_string(),
+                            &s.wildcard.to_string(),
+                            &s.prefix_length.to_string(),
+                            &s.first_host.to_string(),
+                            &s.last_host.to_string(),
+                            &s.total_hosts.to_string(),
+                            &s.usable_hosts.to_string(),
+                            &s.network_class,
+                            &s.is_private.to_string(),
+                            "",
+                            "",
+                            "",
+                            "",
+                            "",
+                            &s.address_type,
+                            "",
+                            &tags,
+                        ])
+                        .map_err(csv_err)?;
+                    }
+                    SubnetResult::V6(s) => {
+                        wtr.write_record([
+                            &entry.cidr,
+                            &s.network_address(),
+                            "",
+                            "",
+                            "",
+                            &s.prefix_length.to_string(),
+                            "",
+                            "",
+                            "",
+                            "",
+                            "",
+                            "",
+                            &s.network_address_full,
+                            &s.last_address(),
+                            &s.last_address_full,
+                            &s.total_addresses,
+                            &s.hextets.join(":"),
+                            &s.address_type,
+                            "",
+                            &tags,
+                        ])
+                        .map_err(csv_err)?;
+                    }
+                },
+                BatchEntryResult::Err { error } => {
+                    wtr.write_record([
+                        &entry.cidr,
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        "",
+                        error.as_str(),
+                        &tags,
+                    ])
+                    .map_err(csv_err)?;
+                }
+            }
+        }
+
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for AddressCountResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# input_count: {}", self.input_count).unwrap();
+        writeln!(out, "# block_count: {}", self.block_count).unwrap();
+        writeln!(out, "# address_count: {}", self.address_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["block"]).map_err(csv_err)?;
+        for block in &self.blocks {
+            wtr.write_record([block]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for OverlapResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# input_count: {}", self.input_count).unwrap();
+        writeln!(out, "# overlap_count: {}", self.overlap_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["first", "second", "relation"])
+            .map_err(csv_err)?;
+        for pair in &self.pairs {
+            wtr.write_record([&pair.first, &pair.second, pair.relation.as_str()])
+                .map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for GroupedSummaryResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# tag: {}", self.tag).unwrap();
+        writeln!(out, "# input_count: {}", self.input_count).unwrap();
+        if !self.untagged.is_empty() {
+            writeln!(out, "# untagged: {}", self.untagged.join(",")).unwrap();
+        }
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["value", "cidr"]).map_err(csv_err)?;
+        for group in &self.groups {
+            for cidr in group.summary.ipv4.iter().chain(&group.summary.ipv6) {
+                wtr.write_record([&group.value, cidr]).map_err(csv_err)?;
+            }
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for AvailableResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# supernet: {}", self.supernet).unwrap();
+        writeln!(out, "# prefix: {}", self.prefix).unwrap();
+        writeln!(out, "# used_count: {}", self.used_count).unwrap();
+        writeln!(out, "# available_count: {}", self.available_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["cidr"]).map_err(csv_err)?;
+        for cidr in &self.available {
+            wtr.write_record([cidr]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for AllocateResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# supernet: {}", self.supernet).unwrap();
+        writeln!(out, "# prefix: {}", self.prefix).unwrap();
+        writeln!(out, "# used_count: {}", self.used_count).unwrap();
+        writeln!(out, "# requested: {}", self.requested).unwrap();
+        writeln!(out, "# remaining_count: {}", self.remaining_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["cidr"]).map_err(csv_err)?;
+        for cidr in &self.allocated {
+            wtr.write_record([cidr]).map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for VlsmResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# supernet: {}", self.supernet).unwrap();
+        writeln!(out, "# allocated_addresses: {}", self.allocated_addresses).unwrap();
+        writeln!(out, "# remaining_addresses: {}", self.remaining_addresses).unwrap();
+        writeln!(out, "# free: {}", self.free.join(" ")).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "hosts",
+            "cidr",
+            "prefix",
+            "first_host",
+            "last_host",
+            "usable_hosts",
+            "remaining_addresses",
+            "waste",
+        ])
+        .map_err(csv_err)?;
+        for a in &self.allocations {
+            wtr.write_record([
+                a.hosts.to_string(),
+                format!("{}/{}", a.subnet.network, a.prefix),
+                a.prefix.to_string(),
+                a.subnet.first_host.to_string(),
+                a.subnet.last_host.to_string(),
+                a.subnet.usable_hosts.to_string(),
+                a.remaining_addresses.to_string(),
+                a.waste.to_string(),
+            ])
+            .map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for SupernetResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "input_count",
+            "supernet",
+            "prefix_length",
+            "total_addresses",
+            "extra_addresses",
+        ])
+        .map_err(csv_err)?;
+        wtr.write_record([
+            &self.input_count.to_string(),
+            &self.supernet,
+            &self.prefix_length.to_string(),
+            &self.total_addresses,
+            &self.extra_addresses,
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for ServeSummary {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# address: {}", self.address).unwrap();
+        if let Some(path) = &self.config_file {
+            writeln!(out, "# config_file: {}", path).unwrap();
+        }
+        writeln!(out, "# swagger: {}", self.swagger).unwrap();
+        writeln!(out, "# ui: {}", self.ui).unwrap();
+        writeln!(out, "# landing_page: {}", self.landing_page).unwrap();
+        if let Some(backend) = &self.ipam_backend {
+            writeln!(out, "# ipam_backend: {}", backend).unwrap();
+        }
+        writeln!(out, "# endpoint_count: {}", self.endpoint_count).unwrap();
+        out.push_str(&self.limits.to_csv()?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for DefaultsReport {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# version: {}", self.version).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["name", "value", "module"])
+            .map_err(csv_err)?;
+        for entry in &self.defaults {
+            wtr.write_record([
+                entry.name.as_str(),
+                &entry.value.to_string(),
+                entry.module.as_str(),
+            ])
+            .map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for Limits {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["limit", "value"]).map_err(csv_err)?;
+        for (name, value) in [
+            ("max_batch_size", self.max_batch_size.to_string()),
+            ("max_body_size", self.max_body_size.to_string()),
+            (
+                "max_summarize_inputs",
+                self.max_summarize_inputs.to_string(),
+            ),
+            ("max_generated_cidrs", self.max_generated_cidrs.to_string()),
+            (
+                "max_generated_subnets",
+                self.max_generated_subnets.to_string(),
+            ),
+            ("max_input_length", self.max_input_length.to_string()),
+            ("timeout_seconds", self.timeout_seconds.to_string()),
+        ] {
+            wtr.write_record([name, value.as_str()]).map_err(csv_err)?;
+        }
+        for (name, value) in [
+            (
+                "rate_limit_per_second",
+                self.rate_limit_per_second.map(|v| v.to_string()),
+            ),
+            (
+                "rate_limit_burst",
+                self.rate_limit_burst.map(|v| v.to_string()),
+            ),
+        ] {
+            if let Some(value) = value {
+                wtr.write_record([name, value.as_str()]).map_err(csv_err)?;
+            }
+        }
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for ConfigReport {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["key", "value", "source"])
+            .map_err(csv_err)?;
+        for entry in &self.entries {
+            let value = match &entry.value {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            wtr.write_record([entry.key.as_str(), value.as_str(), entry.source.as_str()])
+                .map_err(csv_err)?;
+        }
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for GenerationEstimate {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record([
+            "operation",
+            "input",
+            "count",
+            "limit",
+            "within_limit",
+            "estimated_bytes",
+            "estimated_memory",
+        ])
+        .map_err(csv_err)?;
+        wtr.write_record([
+            &self.operation,
+            &self.input,
+            &self.count,
+            &self.limit.to_string(),
+            &self.within_limit.to_string(),
+            &self.estimated_bytes,
+            &self.estimated_memory,
+        ])
+        .map_err(csv_err)?;
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for MergeResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# files: {}", self.files).unwrap();
+        writeln!(out, "# input_entries: {}", self.input_entries).unwrap();
+        writeln!(out, "# duplicates_removed: {}", self.duplicates_removed).unwrap();
+        for conflict in &self.conflicts {
+            writeln!(
+                out,
+                "# conflict: {} ({} vs {})",
+                conflict.cidr, conflict.kept_from, conflict.conflicting_file
+            )
+            .unwrap();
+        }
+        out.push_str(&self.batch.to_csv()?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for NormalizeResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["line"]).map_err(csv_err)?;
+        for line in &self.lines {
+            wtr.write_record([line]).map_err(csv_err)?;
+        }
+        finish_csv(wtr)
+    }
+}
+
+impl CsvOutput for ReverseDnsResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# cidr: {}", self.cidr).unwrap();
+        for zone in &self.zones {
+            writeln!(out, "# zone: {}", zone).unwrap();
+        }
+        writeln!(out, "# prefix: {}", self.prefix).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["address", "name"]).map_err(csv_err)?;
+        for record in self.records.iter().flatten() {
+            wtr.write_record([&record.address, &record.name])
+                .map_err(csv_err)?;
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+impl CsvOutput for ZoneFileResult {
+    fn to_csv(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "# cidr: {}", self.cidr).unwrap();
+        writeln!(out, "# zone_count: {}", self.zone_count).unwrap();
+        writeln!(out, "# record_count: {}", self.record_count).unwrap();
+
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        wtr.write_record(["origin", "name", "address", "ptr"])
+            .map_err(csv_err)?;
+        for zone in &self.zones {
+            for record in &zone.records {
+                wtr.write_record([&zone.origin, &record.name, &record.address, &record.ptr])
+                    .map_err(csv_err)?;
+            }
+        }
+        out.push_str(&finish_csv(wtr)?);
+        Ok(out)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Streamed split output
+// ---------------------------------------------------------------------------
+
+/// CSV body of [`OutputWriter::stream_ipv4_split`].
+pub(super) fn stream_ipv4_split<W: Write>(
+    out: &mut W,
+    supernet: &str,
+    new_prefix: u8,
+    count: u64,
+    offset: u64,
+    subnets: impl Iterator<Item = Result<Ipv4Subnet>>,
+) -> Result<()> {
+    out.write_all(split_csv_preamble(supernet, new_prefix, count, offset, None).as_bytes())?;
+    let mut wtr = csv::Writer::from_writer(out);
+    wtr.write_record(ipv4_csv_header()).map_err(csv_err)?;
+    for subnet in subnets {
+        write_ipv4_csv_record(&mut wtr, &subnet?)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// CSV body of [`OutputWriter::stream_ipv6_split`].
+pub(super) fn stream_ipv6_split<W: Write>(
+    out: &mut W,
+    supernet: &str,
+    new_prefix: u8,
+    count: u64,
+    offset: u64,
+    subnets: impl Iterator<Item = Result<Ipv6Subnet>>,
+) -> Result<()> {
+    out.write_all(split_csv_preamble(supernet, new_prefix, count, offset, None).as_bytes())?;
+    let mut wtr = csv::Writer::from_writer(out);
+    wtr.write_record(ipv6_csv_header()).map_err(csv_err)?;
+    for subnet in subnets {
+        write_ipv6_csv_record(&mut wtr, &subnet?)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Subnet rows per chunk of [`ipv4_split_csv_chunks`] and
+/// [`ipv6_split_csv_chunks`].
+const SPLIT_CSV_CHUNK_ROWS: usize = 1024;
+
+/// The preamble and header row as one chunk, then the rows of `subnets` in
+/// chunks of [`SPLIT_CSV_CHUNK_ROWS`], encoded only as each is requested.
+fn split_csv_chunks<S, I>(
+    preamble: String,
+    header: &'static [&'static str],
+    mut subnets: I,
+    write_record: fn(&mut csv::Writer<Vec<u8>>, &S) -> Result<()>,
+) -> impl Iterator<Item = Result<Vec<u8>>> + use<S, I>
+where
+    I: Iterator<Item = Result<S>>,
+{
+    let mut wtr = csv::Writer::from_writer(preamble.into_bytes());
+    let head = wtr
+        .write_record(header)
+        .map_err(csv_err)
+        .and_then(|()| wtr.into_inner().map_err(csv_err));
+
+    std::iter::once(head).chain(std::iter::from_fn(move || {
+        let mut wtr = csv::Writer::from_writer(Vec::new());
+        let mut rows = 0;
+        for subnet in subnets.by_ref().take(SPLIT_CSV_CHUNK_ROWS) {
+            if let Err(e) = subnet.and_then(|s| write_record(&mut wtr, &s)) {
+                return Some(Err(e));
+            }
+            rows += 1;
+        }
+        (rows > 0).then(|| wtr.into_inner().map_err(csv_err))
+    }))
+}
+
+/// An IPv4 split as CSV chunks for a streamed response body: the same bytes
+/// as [`CsvOutput::to_csv`] on the list, without holding every row at once.
+pub fn ipv4_split_csv_chunks<I>(
+    supernet: &str,
+    new_prefix: u8,
+    count: u64,
+    offset: u64,
+    subnets: I,
+) -> impl Iterator<Item = Result<Vec<u8>>> + use<I>
+where
+    I: Iterator<Item = Result<Ipv4Subnet>>,
+{
+    split_csv_chunks(
+        split_csv_preamble(supernet, new_prefix, count, offset, None),
+        ipv4_csv_header(),
+        subnets,
+        write_ipv4_csv_record,
+    )
+}
+
+/// IPv6 counterpart of [`ipv4_split_csv_chunks`].
+pub fn ipv6_split_csv_chunks<I>(
+    supernet: &str,
+    new_prefix: u8,
+    count: u64,
+    offset: u64,
+    subnets: I,
+) -> impl Iterator<Item = Result<Vec<u8>>> + use<I>
+where
+    I: Iterator<Item = Result<Ipv6Subnet>>,
+{
+    split_csv_chunks(
+        split_csv_preamble(supernet, new_prefix, count, offset, None),
+        ipv6_csv_header(),
+        subnets,
+        write_ipv6_csv_record,
+    )
+}
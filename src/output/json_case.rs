@@ -0,0 +1,171 @@
+//! Key naming of JSON output: the snake_case field names as serialized, or
+//! the same document with its keys rewritten to camelCase.
+
+use crate::error::Result;
+use serde::de::{Deserialize, Deserializer, Error as _, MapAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde_json::value::RawValue;
+use std::fmt;
+
+/// How JSON object keys are named.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum JsonCase {
+    /// Field names as declared (`network_address`)
+    #[default]
+    Snake,
+    /// JavaScript-style names (`networkAddress`)
+    Camel,
+}
+
+/// `network_address` as `networkAddress`. Keys without underscores are kept.
+fn to_camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !out.is_empty() {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// A JSON document with object keys in their original order, which
+/// `serde_json::Value` does not keep without the `preserve_order` feature.
+/// Scalars stay raw so integers beyond u64 come back out exactly.
+enum Node {
+    Raw(Box<RawValue>),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+impl Node {
+    /// Rewrite object keys to camelCase. The keys of a `tags` object are user
+    /// data and stay as given.
+    fn camel_case_keys(&mut self) {
+        match self {
+            Node::Raw(_) => {}
+            Node::Array(items) => items.iter_mut().for_each(Node::camel_case_keys),
+            Node::Object(entries) => {
+                for (key, value) in entries.iter_mut() {
+                    if key != "tags" {
+                        value.camel_case_keys();
+                    }
+                    *key = to_camel_case(key);
+                }
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        let node = match raw.get().trim_start().as_bytes().first() {
+            Some(b'{') => Node::Object(
+                serde_json::from_str::<Entries>(raw.get())
+                    .map_err(D::Error::custom)?
+                    .0,
+            ),
+            Some(b'[') => Node::Array(serde_json::from_str(raw.get()).map_err(D::Error::custom)?),
+            _ => Node::Raw(raw),
+        };
+        Ok(node)
+    }
+}
+
+/// The entries of a JSON object, in document order.
+struct Entries(Vec<(String, Node)>);
+
+impl<'de> Deserialize<'de> for Entries {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = Entries;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> std::result::Result<Entries, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Entries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Node::Raw(raw) => raw.serialize(serializer),
+            Node::Array(items) => serializer.collect_seq(items),
+            Node::Object(entries) => {
+                serializer.collect_map(entries.iter().map(|(key, value)| (key, value)))
+            }
+        }
+    }
+}
+
+/// Rewrite the object keys of a JSON document to camelCase, keeping their
+/// order, and print it compact or pretty.
+pub fn camel_case_json(json: &str, pretty: bool) -> Result<String> {
+    let mut node: Node = serde_json::from_str(json)?;
+    node.camel_case_keys();
+    let out = if pretty {
+        serde_json::to_string_pretty(&node)?
+    } else {
+        serde_json::to_string(&node)?
+    };
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("network_address"), "networkAddress");
+        assert_eq!(to_camel_case("ipv6"), "ipv6");
+        assert_eq!(to_camel_case("aggregate_summary_v4"), "aggregateSummaryV4");
+        assert_eq!(to_camel_case("_private"), "_private");
+    }
+
+    #[test]
+    fn test_keys_renamed_in_order() {
+        let json = r#"{"kind":"x","network_address":"10.0.0.0","prefix_length":24,"results":[{"first_host":null,"tags":{"env_name":"prod"}}]}"#;
+        assert_eq!(
+            camel_case_json(json, false).unwrap(),
+            r#"{"kind":"x","networkAddress":"10.0.0.0","prefixLength":24,"results":[{"firstHost":null,"tags":{"env_name":"prod"}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_large_integers_and_pretty_input_survive() {
+        let json = "{\n  \"total_addresses\": 340282366920938463463374607431768211455,\n  \"ratio\": 0.5\n}";
+        assert_eq!(
+            camel_case_json(json, false).unwrap(),
+            r#"{"totalAddresses":340282366920938463463374607431768211455,"ratio":0.5}"#
+        );
+        assert_eq!(
+            camel_case_json(json, true).unwrap(),
+            json.replace("total_addresses", "totalAddresses")
+        );
+    }
+}
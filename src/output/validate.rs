@@ -0,0 +1,232 @@
+//! Self-validation of serialized results against their JSON Schemas,
+//! compiled with the `schema` feature.
+
+use super::*;
+use serde_json::Value;
+
+/// Check a result's serialized JSON document, with or without its leading
+/// `kind`, against the JSON Schema of `T`.
+pub(super) fn validate_document<T: ResultKind>(json: &str) -> Result<()> {
+    let mut document: Value = serde_json::from_str(json)?;
+    if let (Some(kind), Value::Object(fields)) = (T::KIND, &mut document)
+        && let Some(found) = fields.remove("kind")
+        && found != kind
+    {
+        return Err(IpCalcError::OutputValidation {
+            schema_path: "/properties/kind".to_string(),
+            instance_path: "/kind".to_string(),
+            message: format!("expected kind \"{}\", found {}", kind, found),
+        });
+    }
+    validate_value(&T::json_schema(), &document)
+}
+
+fn validate_value(schema: &schemars::Schema, document: &Value) -> Result<()> {
+    let validator = jsonschema::validator_for(schema.as_value()).map_err(|e| {
+        IpCalcError::OutputValidation {
+            schema_path: e.schema_path().to_string(),
+            instance_path: String::new(),
+            message: format!("schema does not compile: {}", e),
+        }
+    })?;
+    match validator.iter_errors(document).next() {
+        Some(e) => Err(IpCalcError::OutputValidation {
+            schema_path: e.schema_path().to_string(),
+            instance_path: e.instance_path().to_string(),
+            message: e.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup::LookupOrder;
+    use crate::normalize::NormalizeOptions;
+    use crate::*;
+    use serde::Serializer;
+
+    fn cidrs(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn check<T: Serialize + TextOutput + CsvOutput + EnvOutput + ResultKind>(result: &T) {
+        for format in [OutputFormat::Json, OutputFormat::Ndjson] {
+            for include_kind in [true, false] {
+                OutputWriter::new(format, None)
+                    .with_kind(include_kind)
+                    .with_validate_output(true)
+                    .write(result)
+                    .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_subnet_results_match_their_schemas() {
+        check(&Ipv4Subnet::from_cidr("192.168.1.0/24").unwrap());
+        check(&Ipv4Subnet::from_cidr("10.0.0.1/31").unwrap());
+        check(&Ipv6Subnet::from_cidr("2002:c000:0200::/48").unwrap());
+        check(&Ipv6Subnet::from_cidr("::ffff:10.0.0.1/128").unwrap());
+        let expanded_upper = ipv6::Ipv6Format {
+            style: ipv6::Ipv6Style::Expanded,
+            case: ipv6::Ipv6Case::Upper,
+        };
+        check(&Ipv6Subnet::with_format("2001:db8::".parse().unwrap(), 48, expanded_upper).unwrap());
+        check(&subnet_generator::generate_ipv4_subnets("10.0.0.0/24", 26, None).unwrap());
+        check(&subnet_generator::generate_ipv6_subnets("2001:db8::/48", 50, None).unwrap());
+        check(&subnet_generator::count_subnets("10.0.0.0/8", 24).unwrap());
+        check(&subnet_generator::plan_split("10.0.0.0/16", 4, 100, 2).unwrap());
+        check(&tree::build_split_tree("10.0.0.0/16", &[20, 24], Ipv6Format::default()).unwrap());
+        check(
+            &subnet_generator::generate_dualstack_subnets(
+                "10.0.0.0/16",
+                "2001:db8::/48",
+                24,
+                64,
+                2,
+                Ipv6Format::default(),
+            )
+            .unwrap(),
+        );
+        check(&estimate::estimate_split("10.0.0.0/8", 24, None).unwrap());
+        check(&ipv6::expand_ipv6("2001:db8::1").unwrap());
+        check(&ifid::split_interface_id("2001:db8::1/64").unwrap());
+        check(&eui64::derive_eui64("2001:db8::/64", "00:1a:2b:3c:4d:5e").unwrap());
+        check(&acl::acl_wildcard("10.0.0.0/24").unwrap());
+        check(&reverse_dns::reverse_dns("10.0.0.0/24", false).unwrap());
+        check(&reverse_dns::reverse_dns("2001:db8::/48", false).unwrap());
+        check(&zonefile::generate_zonefile("10.0.0.0/24", false, "example.com").unwrap());
+        check(&arithmetic::add_ip("10.0.0.1", 5).unwrap());
+        check(&hosts::list_hosts_v4("10.0.0.0/29", 10).unwrap());
+        check(&usable::check_ipv4_usable("10.0.0.5/24").unwrap());
+        check(&usable::nth_host_v4("10.0.0.0/24", 3).unwrap());
+        check(&usable::nth_host_v6("2001:db8::/64", 3).unwrap());
+        check(&walk::walk_ipv4("10.0.0.0/24", "10.0.3.0/24").unwrap());
+    }
+
+    #[test]
+    fn test_set_results_match_their_schemas() {
+        let set = cidrs(&["10.0.0.0/24", "10.0.1.0/24", "10.0.0.128/25"]);
+        let v6 = cidrs(&["2001:db8::/48", "2001:db8:1::/48"]);
+        check(&contains::check_ipv4_contains("10.0.0.0/8", "10.1.2.3").unwrap());
+        check(&contains::check_ipv4_contains("10.0.0.0/24", "10.0.0.200-10.0.1.10").unwrap());
+        check(&summarize::summarize_ipv4(&set).unwrap());
+        check(&summarize::summarize_ipv6(&v6).unwrap());
+        check(&supernet::find_supernet_v4(&set).unwrap());
+        check(&overlap::find_overlaps(&set).unwrap());
+        check(&addresses::count_addresses(&set).unwrap());
+        check(&covers::check_coverage("10.0.0.0/23", &set).unwrap());
+        check(&lpm::lpm_ipv4("10.0.0.200", &set).unwrap());
+        check(&lookup::lookup("10.0.0.200", &set, LookupOrder::LongestFirst, None).unwrap());
+        check(&gaps::find_gaps_v4("10.0.0.0/22", &set).unwrap());
+        check(&free_space::free_space_v4("10.0.0.0/22", &set).unwrap());
+        check(&available::find_available_v4("10.0.0.0/22", 24, &set).unwrap());
+        check(&available::allocate_v4("10.0.0.0/22", 24, 1, &set).unwrap());
+        check(&exclude::exclude_ipv4("10.0.0.0/22", &set).unwrap());
+        check(&complement::complement_ipv4("10.0.0.0/24", Some("10.0.0.0/22")).unwrap());
+        check(
+            &complement::complement_ipv6(
+                "2001:db8::/48",
+                Some("2001:db8::/46"),
+                Ipv6Format::default(),
+            )
+            .unwrap(),
+        );
+        check(&from_range::from_range_ipv4("10.0.0.1", "10.0.0.9").unwrap());
+        check(
+            &from_range::from_range_ipv6("2001:db8::1", "2001:db8::9", Ipv6Format::default())
+                .unwrap(),
+        );
+        check(&vlsm::allocate_vlsm("10.0.0.0/24", &[50, 20], 2).unwrap());
+        check(&batch::process_batch(&cidrs(&["10.0.0.0/24", "2001:db8::/48", "bogus"])).unwrap());
+        check(
+            &grouped_summary::summarize_grouped(&cidrs(&["10.0.0.0/24 a", "10.0.1.0/24 a"]), "a")
+                .unwrap(),
+        );
+        check(
+            &normalize::normalize_cidr_list(
+                "10.0.1.0/24\n# comment\n10.0.0.0/24\n",
+                NormalizeOptions::default(),
+            )
+            .unwrap(),
+        );
+        let new = cidrs(&["10.0.2.0/24"]);
+        check(&diff::diff_cidr_sets(&set, &new).unwrap());
+        check(&diff::diff_json_patch(&set, &new).unwrap());
+        check(&config::ServerConfig::default().limits());
+    }
+
+    /// Claims a numeric count in its schema but serializes a string, as a
+    /// hand-written serializer that drifted from the struct would.
+    #[derive(schemars::JsonSchema)]
+    struct Drifted {
+        #[allow(dead_code)]
+        count: u64,
+    }
+
+    impl Serialize for Drifted {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            use serde::ser::SerializeStruct;
+            let mut s = serializer.serialize_struct("Drifted", 1)?;
+            s.serialize_field("count", &self.count.to_string())?;
+            s.end()
+        }
+    }
+
+    impl_result_kind! { Drifted => Some("drifted") }
+
+    impl TextOutput for Drifted {
+        fn to_text(&self) -> String {
+            self.count.to_string()
+        }
+    }
+
+    impl EnvOutput for Drifted {}
+
+    #[cfg(feature = "csv")]
+    impl CsvOutput for Drifted {
+        fn to_csv(&self) -> Result<String> {
+            Ok(self.count.to_string())
+        }
+    }
+
+    #[test]
+    fn test_drifted_serializer_is_caught() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.json");
+        let formats = [OutputFormat::Json, OutputFormat::Yaml, OutputFormat::Ndjson];
+        for format in formats.into_iter().filter(|f| f.ensure_available().is_ok()) {
+            let writer = OutputWriter::new(format, Some(path.to_string_lossy().into_owned()))
+                .with_validate_output(true);
+            match writer.write(&Drifted { count: 3 }) {
+                Err(IpCalcError::OutputValidation {
+                    schema_path,
+                    instance_path,
+                    ..
+                }) => {
+                    assert_eq!(schema_path, "/properties/count/type");
+                    assert_eq!(instance_path, "/count");
+                }
+                other => panic!("{format:?}: expected a validation error, got {other:?}"),
+            }
+            // Nothing is written when validation fails
+            assert!(!path.exists());
+        }
+
+        // Formats without a schema are not checked
+        let text = OutputWriter::new(OutputFormat::Text, None)
+            .with_validate_output(true)
+            .write(&Drifted { count: 3 })
+            .unwrap();
+        assert_eq!(text, "3");
+    }
+
+    #[test]
+    fn test_wrong_kind_is_caught() {
+        let json = r#"{"kind":"ipv6_subnet","network_address":"10.0.0.0"}"#;
+        let err = validate_document::<Ipv4Subnet>(json).unwrap_err();
+        assert!(err.to_string().contains("/properties/kind"), "{err}");
+    }
+}
@@ -0,0 +1,379 @@
+use crate::config::DEFAULTS;
+use crate::covers::{Block, format_block};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::Ipv6Subnet;
+use crate::summarize::prefix_mask;
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// How the first CIDR of an overlapping pair relates to the second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapKind {
+    /// The two blocks are the same
+    Equal,
+    /// The first block contains the second
+    Contains,
+    /// The first block lies inside the second
+    ContainedBy,
+    /// The blocks share some addresses but neither contains the other.
+    /// Aligned CIDR blocks always nest or are disjoint, so CIDR inputs never
+    /// produce this.
+    Partial,
+}
+
+impl OverlapKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equal => "equal",
+            Self::Contains => "contains",
+            Self::ContainedBy => "contained_by",
+            Self::Partial => "partial",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OverlapPair {
+    pub first: String,
+    pub second: String,
+    pub relation: OverlapKind,
+}
+
+/// Every overlapping pair in a CIDR list, in input order.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OverlapResult {
+    pub input_count: usize,
+    pub overlap_count: usize,
+    pub pairs: Vec<OverlapPair>,
+}
+
+impl OverlapResult {
+    pub fn new(input_count: usize, pairs: Vec<(String, String, OverlapKind)>) -> Self {
+        let pairs: Vec<OverlapPair> = pairs
+            .into_iter()
+            .map(|(first, second, relation)| OverlapPair {
+                first,
+                second,
+                relation,
+            })
+            .collect();
+        Self {
+            input_count,
+            overlap_count: pairs.len(),
+            pairs,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Pairwise comparison over (network, prefix) pairs
+// ---------------------------------------------------------------------------
+
+fn last_address((network, prefix): Block, bits: u8) -> u128 {
+    let width = if bits == 32 {
+        u32::MAX as u128
+    } else {
+        u128::MAX
+    };
+    network | (!prefix_mask(prefix, bits) & width)
+}
+
+fn relation(a: Block, b: Block, bits: u8) -> Option<OverlapKind> {
+    let (a_last, b_last) = (last_address(a, bits), last_address(b, bits));
+    if a.0 > b_last || b.0 > a_last {
+        return None;
+    }
+    Some(if a.0 == b.0 && a_last == b_last {
+        OverlapKind::Equal
+    } else if a.0 <= b.0 && a_last >= b_last {
+        OverlapKind::Contains
+    } else if b.0 <= a.0 && b_last >= a_last {
+        OverlapKind::ContainedBy
+    } else {
+        OverlapKind::Partial
+    })
+}
+
+/// Every overlapping pair of blocks, ordered by input position.
+///
+/// CIDR blocks either nest or are disjoint, so in (network, prefix) order the
+/// blocks containing the current one are exactly those still open on a stack.
+/// That makes this O(n log n + k log k) for k pairs instead of comparing
+/// every pair.
+fn overlapping_pairs(blocks: &[Block], bits: u8) -> Vec<(String, String, OverlapKind)> {
+    let mut order: Vec<usize> = (0..blocks.len()).collect();
+    order.sort_by_key(|&i| blocks[i]);
+
+    let mut open: Vec<usize> = Vec::new();
+    let mut found: Vec<(usize, usize)> = Vec::new();
+    for i in order {
+        let network = blocks[i].0;
+        while open
+            .last()
+            .is_some_and(|&j| last_address(blocks[j], bits) < network)
+        {
+            open.pop();
+        }
+        found.extend(open.iter().map(|&j| (j.min(i), j.max(i))));
+        open.push(i);
+    }
+    found.sort_unstable();
+
+    found
+        .into_iter()
+        .filter_map(|(i, j)| {
+            let (a, b) = (blocks[i], blocks[j]);
+            relation(a, b, bits).map(|kind| (format_block(a, bits), format_block(b, bits), kind))
+        })
+        .collect()
+}
+
+fn check_limit(cidrs: &[String], max_inputs: usize) -> Result<()> {
+    if cidrs.len() > max_inputs {
+        return Err(IpCalcError::SummarizeInputLimitExceeded {
+            count: cidrs.len(),
+            limit: max_inputs,
+        });
+    }
+    Ok(())
+}
+
+fn ipv4_blocks(cidrs: &[String]) -> Result<Vec<Block>> {
+    cidrs
+        .iter()
+        .map(|c| {
+            let s = Ipv4Subnet::from_cidr(c)?;
+            Ok((u32::from(s.network) as u128, s.prefix_length))
+        })
+        .collect()
+}
+
+fn ipv6_blocks(cidrs: &[String]) -> Result<Vec<Block>> {
+    cidrs
+        .iter()
+        .map(|c| {
+            let s = Ipv6Subnet::from_cidr(c)?;
+            Ok((u128::from(s.network), s.prefix_length))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Every overlapping pair of IPv4 CIDRs, as `(first, second, relation)` in
+/// input order. Fewer than two inputs give an empty list.
+pub fn find_overlapping_pairs_v4(cidrs: &[String]) -> Result<Vec<(String, String, OverlapKind)>> {
+    find_overlapping_pairs_v4_with_limit(cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+pub fn find_overlapping_pairs_v4_with_limit(
+    cidrs: &[String],
+    max_inputs: usize,
+) -> Result<Vec<(String, String, OverlapKind)>> {
+    check_limit(cidrs, max_inputs)?;
+    Ok(overlapping_pairs(&ipv4_blocks(cidrs)?, 32))
+}
+
+/// Every overlapping pair of IPv6 CIDRs; see [`find_overlapping_pairs_v4`].
+pub fn find_overlapping_pairs_v6(cidrs: &[String]) -> Result<Vec<(String, String, OverlapKind)>> {
+    find_overlapping_pairs_v6_with_limit(cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+pub fn find_overlapping_pairs_v6_with_limit(
+    cidrs: &[String],
+    max_inputs: usize,
+) -> Result<Vec<(String, String, OverlapKind)>> {
+    check_limit(cidrs, max_inputs)?;
+    Ok(overlapping_pairs(&ipv6_blocks(cidrs)?, 128))
+}
+
+/// Overlapping pairs in a list of either or both families. IPv4 and IPv6
+/// blocks never overlap each other, so each family is checked on its own.
+pub fn find_overlaps(cidrs: &[String]) -> Result<OverlapResult> {
+    find_overlaps_with_limit(cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+pub fn find_overlaps_with_limit(cidrs: &[String], max_inputs: usize) -> Result<OverlapResult> {
+    check_limit(cidrs, max_inputs)?;
+    let (v6, v4): (Vec<String>, Vec<String>) = cidrs
+        .iter()
+        .map(|c| c.trim().to_string())
+        .partition(|c| c.contains(':'));
+    let mut pairs = overlapping_pairs(&ipv4_blocks(&v4)?, 32);
+    pairs.extend(overlapping_pairs(&ipv6_blocks(&v6)?, 128));
+    Ok(OverlapResult::new(cidrs.len(), pairs))
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ipv4_overlaps() {
+        let pairs =
+            find_overlapping_pairs_v4(&strings(&["10.0.0.0/8", "10.1.0.0/16", "192.168.0.0/24"]))
+                .unwrap();
+        assert_eq!(
+            pairs,
+            [(
+                "10.0.0.0/8".to_string(),
+                "10.1.0.0/16".to_string(),
+                OverlapKind::Contains
+            )]
+        );
+
+        let pairs =
+            find_overlapping_pairs_v4(&strings(&["10.1.2.0/24", "10.1.0.0/16", "10.1.2.3/24"]))
+                .unwrap();
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[0].2, OverlapKind::ContainedBy);
+        // Host bits are cleared, so the first and last entries are equal
+        assert_eq!(pairs[1].0, pairs[1].1);
+        assert_eq!(pairs[1].2, OverlapKind::Equal);
+        assert_eq!(pairs[2].2, OverlapKind::Contains);
+    }
+
+    #[test]
+    fn test_ipv6_overlaps() {
+        let pairs =
+            find_overlapping_pairs_v6(&strings(&["2001:db8:1::/48", "2001:db8::/32", "fd00::/8"]))
+                .unwrap();
+        assert_eq!(
+            pairs,
+            [(
+                "2001:db8:1::/48".to_string(),
+                "2001:db8::/32".to_string(),
+                OverlapKind::ContainedBy
+            )]
+        );
+    }
+
+    #[test]
+    fn test_partial_relation() {
+        // Not reachable from aligned CIDRs, but the comparison handles it:
+        // 0-127 against an unaligned 64-255
+        assert_eq!(relation((0, 25), (64, 24), 32), Some(OverlapKind::Partial));
+        assert_eq!(relation((0, 25), (128, 25), 32), None);
+        assert_eq!(relation((0, 0), (0, 0), 128), Some(OverlapKind::Equal));
+    }
+
+    #[test]
+    fn test_duplicates_and_nesting() {
+        let pairs =
+            find_overlapping_pairs_v4(&strings(&["10.0.0.0/24", "10.0.0.0/24", "10.0.0.0/24"]))
+                .unwrap();
+        assert_eq!(pairs.len(), 3);
+        assert!(pairs.iter().all(|p| p.2 == OverlapKind::Equal));
+
+        // A chain of nested blocks overlaps pairwise, reported in input order
+        let pairs = find_overlapping_pairs_v4(&strings(&[
+            "10.1.2.128/25",
+            "10.0.0.0/8",
+            "10.1.2.0/24",
+            "10.1.0.0/16",
+        ]))
+        .unwrap();
+        let relations: Vec<(&str, &str, OverlapKind)> = pairs
+            .iter()
+            .map(|(a, b, k)| (a.as_str(), b.as_str(), *k))
+            .collect();
+        assert_eq!(
+            relations,
+            [
+                ("10.1.2.128/25", "10.0.0.0/8", OverlapKind::ContainedBy),
+                ("10.1.2.128/25", "10.1.2.0/24", OverlapKind::ContainedBy),
+                ("10.1.2.128/25", "10.1.0.0/16", OverlapKind::ContainedBy),
+                ("10.0.0.0/8", "10.1.2.0/24", OverlapKind::Contains),
+                ("10.0.0.0/8", "10.1.0.0/16", OverlapKind::Contains),
+                ("10.1.2.0/24", "10.1.0.0/16", OverlapKind::ContainedBy),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disjoint_set_is_empty() {
+        let pairs = find_overlapping_pairs_v4(&strings(&[
+            "10.0.1.0/24",
+            "10.0.0.0/24",
+            "10.0.2.0/23",
+            "192.168.0.0/16",
+            "0.0.0.0/32",
+            "255.255.255.255/32",
+        ]))
+        .unwrap();
+        assert!(pairs.is_empty());
+    }
+
+    #[test]
+    fn test_matches_pairwise_comparison() {
+        // Pseudo-random blocks clustered so many of them nest
+        let mut seed = 0x2545_f491_u32;
+        let blocks: Vec<Block> = (0..300)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                let prefix = 20 + (seed % 13) as u8;
+                let network = u128::from(seed & 0x0000_ffff) & prefix_mask(prefix, 32);
+                (network, prefix)
+            })
+            .collect();
+        let mut expected = Vec::new();
+        for (i, &a) in blocks.iter().enumerate() {
+            for &b in &blocks[i + 1..] {
+                if let Some(kind) = relation(a, b, 32) {
+                    expected.push((format_block(a, 32), format_block(b, 32), kind));
+                }
+            }
+        }
+        assert!(!expected.is_empty());
+        assert_eq!(overlapping_pairs(&blocks, 32), expected);
+    }
+
+    #[test]
+    fn test_short_lists_are_empty() {
+        assert!(find_overlapping_pairs_v4(&[]).unwrap().is_empty());
+        assert!(
+            find_overlapping_pairs_v6(&strings(&["2001:db8::/32"]))
+                .unwrap()
+                .is_empty()
+        );
+        let result = find_overlaps(&[]).unwrap();
+        assert_eq!(result.overlap_count, 0);
+    }
+
+    #[test]
+    fn test_mixed_families_and_invalid_input() {
+        let result = find_overlaps(&strings(&[
+            "10.0.0.0/8",
+            "2001:db8::/32",
+            "10.9.0.0/16",
+            "2001:db8::/48",
+        ]))
+        .unwrap();
+        assert_eq!(result.input_count, 4);
+        assert_eq!(result.overlap_count, 2);
+        assert!(find_overlapping_pairs_v4(&strings(&["10.0.0.0/8", "bogus"])).is_err());
+    }
+}
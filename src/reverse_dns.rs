@@ -0,0 +1,262 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::Ipv6Subnet;
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// The reverse name of one host, ready to be given a PTR target.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PtrStub {
+    pub address: String,
+    pub name: String,
+}
+
+/// The reverse DNS zones for a block and, for IPv4, a PTR stub per usable host.
+///
+/// Prefixes longer than /24 get an RFC 2317 classless zone such as
+/// `0/25.2.0.192.in-addr.arpa`. Other prefixes that do not end on an octet
+/// (IPv4) or nibble (IPv6) boundary get one zone per aligned sub-block, so a
+/// /15 lists two /16 zones rather than the enclosing /8. IPv6 blocks never
+/// list records, and `zone_only` leaves them out for IPv4.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReverseDnsResult {
+    pub cidr: String,
+    pub zones: Vec<String>,
+    pub prefix: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub records: Option<Vec<PtrStub>>,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// `labels` reversed and joined under `suffix`.
+fn reverse_name<T: ToString>(labels: &[T], suffix: &str) -> String {
+    labels
+        .iter()
+        .rev()
+        .map(ToString::to_string)
+        .chain(std::iter::once(suffix.to_string()))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Network addresses of the `2^(aligned - prefix)` blocks of length
+/// `aligned` that make up `network/prefix` in a `bits`-wide family.
+fn aligned_blocks(network: u128, prefix: u8, aligned: u8, bits: u8) -> Vec<u128> {
+    let step = 1u128.checked_shl(u32::from(bits - aligned)).unwrap_or(0);
+    (0..1u128 << (aligned - prefix))
+        .map(|i| network + i * step)
+        .collect()
+}
+
+fn ipv4_zones(network: Ipv4Addr, prefix: u8) -> Vec<String> {
+    let octets = network.octets();
+    if prefix > 24 && prefix < 32 {
+        let parent = reverse_name(&octets[..3], "in-addr.arpa");
+        return vec![format!("{}/{}.{}", octets[3], prefix, parent)];
+    }
+    let aligned = prefix.div_ceil(8) * 8;
+    aligned_blocks(u128::from(u32::from(network)), prefix, aligned, 32)
+        .into_iter()
+        .map(|block| {
+            let octets = Ipv4Addr::from(block as u32).octets();
+            reverse_name(&octets[..aligned as usize / 8], "in-addr.arpa")
+        })
+        .collect()
+}
+
+fn ipv6_zones(network: Ipv6Addr, prefix: u8) -> Vec<String> {
+    let aligned = prefix.div_ceil(4) * 4;
+    aligned_blocks(u128::from(network), prefix, aligned, 128)
+        .into_iter()
+        .map(|block| {
+            let nibbles = ipv6_nibbles(Ipv6Addr::from(block));
+            reverse_name(&nibbles[..aligned as usize / 4], "ip6.arpa")
+        })
+        .collect()
+}
+
+fn ipv6_nibbles(addr: Ipv6Addr) -> Vec<char> {
+    addr.octets()
+        .iter()
+        .flat_map(|b| [b >> 4, b & 0xf])
+        .map(|n| char::from_digit(u32::from(n), 16).unwrap_or('0'))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Reverse zone for an IPv4 or IPv6 CIDR; see [`ReverseDnsResult`].
+pub fn reverse_dns(cidr: &str, zone_only: bool) -> Result<ReverseDnsResult> {
+    if cidr.contains(':') {
+        reverse_dns_ipv6(cidr)
+    } else {
+        reverse_dns_ipv4(cidr, zone_only)
+    }
+}
+
+/// IPv4 reverse zone plus one PTR stub per usable host, capped at
+/// `DEFAULTS.max_generated_subnets` records unless `zone_only` is set.
+pub fn reverse_dns_ipv4(cidr: &str, zone_only: bool) -> Result<ReverseDnsResult> {
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    let zones = ipv4_zones(subnet.network, subnet.prefix_length);
+
+    let records = if zone_only {
+        None
+    } else {
+        if subnet.usable_hosts > DEFAULTS.max_generated_subnets {
+            return Err(IpCalcError::RecordLimitExceeded {
+                count: subnet.usable_hosts,
+                limit: DEFAULTS.max_generated_subnets,
+            });
+        }
+        let classless = subnet.prefix_length > 24 && subnet.prefix_length < 32;
+        let hosts = u32::from(subnet.first_host)..=u32::from(subnet.last_host);
+        Some(
+            hosts
+                .map(Ipv4Addr::from)
+                .map(|addr| PtrStub {
+                    address: addr.to_string(),
+                    // Inside a classless zone only the last octet is new
+                    name: if classless {
+                        format!("{}.{}", addr.octets()[3], zones[0])
+                    } else {
+                        reverse_name(&addr.octets(), "in-addr.arpa")
+                    },
+                })
+                .collect(),
+        )
+    };
+
+    Ok(ReverseDnsResult {
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        zones,
+        prefix: subnet.prefix_length,
+        records,
+    })
+}
+
+/// The nibble-reversed `ip6.arpa` zones for an IPv6 prefix.
+pub fn reverse_dns_ipv6(cidr: &str) -> Result<ReverseDnsResult> {
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+    Ok(ReverseDnsResult {
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        zones: ipv6_zones(subnet.network, subnet.prefix_length),
+        prefix: subnet.prefix_length,
+        records: None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_24() {
+        let result = reverse_dns("192.0.2.0/24", false).unwrap();
+        assert_eq!(result.zones, ["2.0.192.in-addr.arpa"]);
+        assert_eq!(result.prefix, 24);
+        let records = result.records.unwrap();
+        assert_eq!(records.len(), 254);
+        assert_eq!(records[0].address, "192.0.2.1");
+        assert_eq!(records[0].name, "1.2.0.192.in-addr.arpa");
+    }
+
+    #[test]
+    fn test_ipv4_25_classless() {
+        let result = reverse_dns("192.0.2.128/25", false).unwrap();
+        assert_eq!(result.zones, ["128/25.2.0.192.in-addr.arpa"]);
+        let records = result.records.unwrap();
+        assert_eq!(records.len(), 126);
+        assert_eq!(records[0].name, "129.128/25.2.0.192.in-addr.arpa");
+    }
+
+    #[test]
+    fn test_ipv4_zone_boundaries() {
+        assert_eq!(
+            reverse_dns("10.1.4.0/22", true).unwrap().zones,
+            [
+                "4.1.10.in-addr.arpa",
+                "5.1.10.in-addr.arpa",
+                "6.1.10.in-addr.arpa",
+                "7.1.10.in-addr.arpa",
+            ]
+        );
+        assert_eq!(
+            reverse_dns("0.0.0.0/0", true).unwrap().zones,
+            ["in-addr.arpa"]
+        );
+        assert_eq!(reverse_dns("0.0.0.0/1", true).unwrap().zones.len(), 128);
+        let result = reverse_dns("192.0.2.7/32", false).unwrap();
+        assert_eq!(result.zones, ["7.2.0.192.in-addr.arpa"]);
+        assert_eq!(result.records.unwrap()[0].name, "7.2.0.192.in-addr.arpa");
+    }
+
+    #[test]
+    fn test_ipv6_48() {
+        let result = reverse_dns("2001:db8:abcd::/48", false).unwrap();
+        assert_eq!(result.zones, ["d.c.b.a.8.b.d.0.1.0.0.2.ip6.arpa"]);
+        assert!(result.records.is_none());
+
+        // A non-nibble prefix gets one zone per nibble-aligned sub-block
+        let result = reverse_dns("2001:db8::/30", false).unwrap();
+        assert_eq!(
+            result.zones,
+            [
+                "8.b.d.0.1.0.0.2.ip6.arpa",
+                "9.b.d.0.1.0.0.2.ip6.arpa",
+                "a.b.d.0.1.0.0.2.ip6.arpa",
+                "b.b.d.0.1.0.0.2.ip6.arpa",
+            ]
+        );
+        assert_eq!(reverse_dns("::/0", false).unwrap().zones, ["ip6.arpa"]);
+    }
+
+    #[test]
+    fn test_ipv4_15_lists_16_zones() {
+        let result = reverse_dns("10.0.0.0/15", true).unwrap();
+        assert_eq!(result.prefix, 15);
+        assert_eq!(result.zones, ["0.10.in-addr.arpa", "1.10.in-addr.arpa"]);
+    }
+
+    #[test]
+    fn test_ipv6_47_lists_48_zones() {
+        let result = reverse_dns("2001:db8::/47", false).unwrap();
+        assert_eq!(result.prefix, 47);
+        assert_eq!(
+            result.zones,
+            [
+                "0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa",
+                "1.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_limit() {
+        assert!(matches!(
+            reverse_dns("10.0.0.0/8", false),
+            Err(IpCalcError::RecordLimitExceeded { .. })
+        ));
+        let result = reverse_dns("10.0.0.0/8", true).unwrap();
+        assert_eq!(result.zones, ["10.in-addr.arpa"]);
+        assert!(result.records.is_none());
+    }
+}
@@ -0,0 +1,214 @@
+//! Startup for `ipcalc serve`, kept apart from serving so `--dry-run` can run
+//! every step and stop before accepting connections.
+
+use crate::api::{ENDPOINTS, RouterConfig, create_router};
+use crate::client_ip::ClientIpResolver;
+use crate::config::{CliOverrides, Limits, ServerConfig};
+use crate::error::{IpCalcError, Result};
+use crate::ipam;
+use axum::Router;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+/// What `ipcalc serve` was asked to start.
+#[derive(Debug, Default)]
+pub struct ServeOptions {
+    pub address: String,
+    pub port: u16,
+    /// Path to a TOML config file
+    pub config: Option<String>,
+    pub overrides: CliOverrides,
+}
+
+/// The server as it would run, reported by `ipcalc serve --dry-run`.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ServeSummary {
+    /// Address the listener is bound to (the actual port when 0 was asked for)
+    pub address: String,
+    pub config_file: Option<String>,
+    pub swagger: bool,
+    pub ui: bool,
+    pub landing_page: bool,
+    /// IPAM storage backend, when IPAM routes are enabled
+    pub ipam_backend: Option<String>,
+    pub endpoint_count: usize,
+    pub limits: Limits,
+}
+
+/// A bound listener and the router to serve on it.
+pub struct PreparedServer {
+    pub listener: TcpListener,
+    pub router: Router,
+    pub summary: ServeSummary,
+}
+
+/// Wrap a startup failure with the step it happened in.
+pub fn startup_error(step: &str, message: impl ToString) -> IpCalcError {
+    IpCalcError::StartupFailed {
+        step: step.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Run every startup step short of serving: load and merge the config,
+/// check the trusted proxies, open the IPAM store, build the router, and bind
+/// the listener. A failure names the step it happened in.
+pub async fn prepare_server(options: ServeOptions) -> Result<PreparedServer> {
+    let mut server_config = match options.config {
+        Some(ref path) => {
+            let c = ServerConfig::load(path).map_err(|e| startup_error("config", e))?;
+            info!("Loaded config from {}", path);
+            c
+        }
+        None => ServerConfig::default(),
+    };
+    server_config.merge_cli_overrides(&options.overrides);
+
+    ClientIpResolver::from_config(&server_config)
+        .map_err(|e| startup_error("trusted_proxies", e))?;
+    if server_config.trust_forwarded_for {
+        info!(
+            "Trusting X-Forwarded-For from {}",
+            server_config.trusted_proxies.join(", ")
+        );
+    }
+
+    if options.address != "127.0.0.1" && options.address != "::1" {
+        warn!(
+            "Binding to non-loopback address '{}'. Use 127.0.0.1 for local-only access.",
+            options.address
+        );
+    }
+    let addr: SocketAddr = format!("{}:{}", options.address, options.port)
+        .parse()
+        .or_else(|_| format!("[{}]:{}", options.address, options.port).parse())
+        .map_err(|_| startup_error("address", format!("invalid address '{}'", options.address)))?;
+
+    let ipam_ops = if server_config.ipam_enabled {
+        let mut ipam_config = ipam::config::IpamConfig::default();
+        if let Ok(backend) = server_config.ipam_backend.parse::<ipam::config::Backend>() {
+            ipam_config.backend = backend;
+        }
+        let store = ipam::create_store(
+            &ipam_config,
+            server_config.ipam_db.as_deref(),
+            server_config.ipam_db_url.as_deref(),
+        )
+        .await
+        .map_err(|e| startup_error("ipam", e))?;
+        info!("IPAM enabled, backend: {}", server_config.ipam_backend);
+        Some(Arc::new(ipam::operations::IpamOps::new(store)))
+    } else {
+        None
+    };
+
+    if server_config.enable_ui && !cfg!(feature = "webui") {
+        warn!("enable_ui is set but this build lacks the webui feature; /ui is not served");
+    }
+
+    let limits = server_config.limits();
+    let mut summary = ServeSummary {
+        address: addr.to_string(),
+        config_file: options.config,
+        swagger: server_config.enable_swagger && cfg!(feature = "swagger"),
+        ui: server_config.enable_ui && cfg!(feature = "webui"),
+        landing_page: server_config.enable_landing_page,
+        ipam_backend: ipam_ops
+            .as_ref()
+            .map(|_| server_config.ipam_backend.clone()),
+        endpoint_count: ENDPOINTS.len(),
+        limits,
+    };
+    let router = create_router(RouterConfig {
+        server: server_config,
+        ipam_ops,
+    });
+
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| startup_error("bind", format!("{}: {}", addr, e)))?;
+    if let Ok(local) = listener.local_addr() {
+        summary.address = local.to_string();
+    }
+
+    Ok(PreparedServer {
+        listener,
+        router,
+        summary,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(port: u16) -> ServeOptions {
+        ServeOptions {
+            address: "127.0.0.1".to_string(),
+            port,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prepare_binds_and_summarizes() {
+        let prepared = prepare_server(options(0)).await.unwrap();
+        let bound = prepared.listener.local_addr().unwrap();
+        assert_ne!(bound.port(), 0);
+        assert_eq!(prepared.summary.address, bound.to_string());
+        assert_eq!(prepared.summary.ipam_backend, None);
+        assert_eq!(prepared.summary.endpoint_count, ENDPOINTS.len());
+    }
+
+    #[tokio::test]
+    async fn test_prepare_names_failing_step() {
+        let held = prepare_server(options(0)).await.unwrap();
+        let port = held.listener.local_addr().unwrap().port();
+        let err = prepare_server(options(port)).await.err().unwrap();
+        assert!(
+            matches!(err, IpCalcError::StartupFailed { ref step, .. } if step == "bind"),
+            "expected a bind failure, got {:?}",
+            err
+        );
+
+        let err = prepare_server(ServeOptions {
+            config: Some("/nonexistent/ipcalc.toml".to_string()),
+            ..options(0)
+        })
+        .await
+        .err()
+        .unwrap();
+        assert!(matches!(err, IpCalcError::StartupFailed { ref step, .. } if step == "config"));
+
+        let err = prepare_server(ServeOptions {
+            address: "not-an-address".to_string(),
+            ..options(0)
+        })
+        .await
+        .err()
+        .unwrap();
+        assert!(matches!(err, IpCalcError::StartupFailed { ref step, .. } if step == "address"));
+
+        let err = prepare_server(ServeOptions {
+            overrides: CliOverrides {
+                trust_forwarded_for: true,
+                ..Default::default()
+            },
+            ..options(0)
+        })
+        .await
+        .err()
+        .unwrap();
+        assert!(
+            matches!(err, IpCalcError::StartupFailed { ref step, .. } if step == "trusted_proxies")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Snapshots of the CIDRs a command listed, so a later run can report only
+//! what changed since.
+
+use crate::complement::{Ipv4ComplementResult, Ipv6ComplementResult};
+use crate::diff::{DiffResult, canonical_set, diff_canonical_sets};
+use crate::error::{IpCalcError, Result};
+use crate::exclude::{Ipv4ExcludeResult, Ipv6ExcludeResult};
+use crate::free_space::FreeSpaceResult;
+use crate::from_range::{Ipv4FromRangeResult, Ipv6FromRangeResult};
+use crate::gaps::GapsResult;
+use crate::subnet_generator::{Ipv4SubnetList, Ipv6SubnetList};
+use crate::summarize::{Ipv4SummaryResult, Ipv6SummaryResult};
+use crate::walk::Ipv4WalkResult;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+
+// ---------------------------------------------------------------------------
+// Snapshot file
+// ---------------------------------------------------------------------------
+
+/// A saved CIDR list: the canonical subnet-set document `{"cidrs": [...]}`
+/// that `diff --format json-patch` operates on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub cidrs: Vec<String>,
+}
+
+/// Read the snapshot at `path`. A missing file is an empty snapshot, so the
+/// first run reports every CIDR as added.
+pub fn load_snapshot(path: &str) -> Result<Vec<String>> {
+    let contents = match std::fs::read_to_string(path) {
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        contents => contents?,
+    };
+    let snapshot: Snapshot = serde_json::from_str(&contents)
+        .map_err(|e| IpCalcError::InvalidInput(format!("{}: not a snapshot: {}", path, e)))?;
+    Ok(snapshot.cidrs)
+}
+
+/// Write `cidrs` to `path` as a snapshot, replacing any previous one.
+pub fn save_snapshot(path: &str, cidrs: &[String]) -> Result<()> {
+    let snapshot = Snapshot {
+        cidrs: cidrs.to_vec(),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&snapshot)? + "\n")?;
+    Ok(())
+}
+
+/// Compare `current` against the snapshot at `path`.
+///
+/// Returns the changes along with `current` in canonical form, which the
+/// caller saves once the changes have been reported so a failed write does
+/// not lose them.
+pub fn changes_since(path: &str, current: &[String]) -> Result<(DiffResult, Vec<String>)> {
+    let old = canonical_set(&load_snapshot(path)?)?;
+    let current = canonical_set(current)?;
+    Ok((diff_canonical_sets(&old, &current), current))
+}
+
+// ---------------------------------------------------------------------------
+// Listed CIDRs
+// ---------------------------------------------------------------------------
+
+/// A result whose substance is a list of CIDRs.
+pub trait CidrList {
+    fn cidr_list(&self) -> Vec<String>;
+}
+
+macro_rules! impl_cidr_list {
+    ($($ty:ty => $field:ident),* $(,)?) => {
+        $(impl CidrList for $ty {
+            fn cidr_list(&self) -> Vec<String> {
+                self.$field
+                    .iter()
+                    .map(|s| format!("{}/{}", s.network, s.prefix_length))
+                    .collect()
+            }
+        })*
+    };
+}
+
+impl_cidr_list! {
+    Ipv4SubnetList => subnets,
+    Ipv6SubnetList => subnets,
+    Ipv4SummaryResult => cidrs,
+    Ipv6SummaryResult => cidrs,
+    Ipv4FromRangeResult => cidrs,
+    Ipv6FromRangeResult => cidrs,
+    Ipv4ExcludeResult => cidrs,
+    Ipv6ExcludeResult => cidrs,
+    Ipv4ComplementResult => cidrs,
+    Ipv6ComplementResult => cidrs,
+    Ipv4WalkResult => subnets,
+}
+
+impl CidrList for GapsResult {
+    fn cidr_list(&self) -> Vec<String> {
+        self.free_cidrs.clone()
+    }
+}
+
+impl CidrList for FreeSpaceResult {
+    fn cidr_list(&self) -> Vec<String> {
+        self.free_cidrs.clone()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_missing_snapshot_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("none.json");
+        assert!(load_snapshot(path.to_str().unwrap()).unwrap().is_empty());
+
+        let (diff, current) = changes_since(
+            path.to_str().unwrap(),
+            &strings(&["10.0.1.0/24", "10.0.0.9/24"]),
+        )
+        .unwrap();
+        assert_eq!(diff.added, ["10.0.0.0/24", "10.0.1.0/24"]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(current, ["10.0.0.0/24", "10.0.1.0/24"]);
+    }
+
+    #[test]
+    fn test_changes_since_saved_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snap.json");
+        let path = path.to_str().unwrap();
+        save_snapshot(path, &strings(&["10.0.0.0/24", "10.0.1.0/24"])).unwrap();
+
+        let (diff, _) = changes_since(path, &strings(&["10.0.1.0/24", "10.0.2.0/24"])).unwrap();
+        assert_eq!(diff.added, ["10.0.2.0/24"]);
+        assert_eq!(diff.removed, ["10.0.0.0/24"]);
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn test_invalid_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.json");
+        std::fs::write(&path, "[\"10.0.0.0/24\"]").unwrap();
+        let err = load_snapshot(path.to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("not a snapshot"));
+    }
+}
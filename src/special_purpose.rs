@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// The allocation attributes the IANA special-purpose registry records for a
+/// block (RFC 6890 section 2.2.2). `None` is the registry's "N/A".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AllocationAttributes {
+    /// Valid as a source address
+    pub source: Option<bool>,
+    /// Valid as a destination address
+    pub destination: Option<bool>,
+    /// Routers may forward packets with this address
+    pub forwardable: Option<bool>,
+    /// Reachable beyond a limited domain ("Global" in RFC 6890)
+    pub globally_reachable: Option<bool>,
+    /// Special handling is required by a protocol specification
+    pub reserved_by_protocol: bool,
+}
+
+/// An IPv4 Special-Purpose Address Registry entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SpecialPurposeEntry {
+    pub block: String,
+    pub name: String,
+    pub rfc: String,
+    pub allocation_attributes: AllocationAttributes,
+}
+
+// ---------------------------------------------------------------------------
+// Registry table
+// ---------------------------------------------------------------------------
+
+struct Row {
+    network: [u8; 4],
+    prefix: u8,
+    name: &'static str,
+    rfc: &'static str,
+    /// Source, destination, forwardable, globally reachable
+    flags: [Option<bool>; 4],
+    reserved_by_protocol: bool,
+}
+
+const T: Option<bool> = Some(true);
+const F: Option<bool> = Some(false);
+const NA: Option<bool> = None;
+
+const fn row(
+    network: [u8; 4],
+    prefix: u8,
+    name: &'static str,
+    rfc: &'static str,
+    flags: [Option<bool>; 4],
+    reserved_by_protocol: bool,
+) -> Row {
+    Row {
+        network,
+        prefix,
+        name,
+        rfc,
+        flags,
+        reserved_by_protocol,
+    }
+}
+
+/// The IANA IPv4 Special-Purpose Address Registry, nested entries included.
+/// Loopback's attributes are false outside the host, as the registry notes.
+#[rustfmt::skip]
+const IPV4_REGISTRY: &[Row] = &[
+    row([0, 0, 0, 0], 8, "\"This network\"", "RFC 791", [T, F, F, F], true),
+    row([0, 0, 0, 0], 32, "\"This host on this network\"", "RFC 1122", [T, F, F, F], true),
+    row([10, 0, 0, 0], 8, "Private-Use", "RFC 1918", [T, T, T, F], false),
+    row([100, 64, 0, 0], 10, "Shared Address Space", "RFC 6598", [T, T, T, F], false),
+    row([127, 0, 0, 0], 8, "Loopback", "RFC 1122", [F, F, F, F], true),
+    row([169, 254, 0, 0], 16, "Link Local", "RFC 3927", [T, T, F, F], true),
+    row([172, 16, 0, 0], 12, "Private-Use", "RFC 1918", [T, T, T, F], false),
+    row([192, 0, 0, 0], 24, "IETF Protocol Assignments", "RFC 6890", [NA, NA, NA, NA], false),
+    row([192, 0, 0, 0], 29, "IPv4 Service Continuity Prefix", "RFC 7335", [T, T, T, F], false),
+    row([192, 0, 0, 8], 32, "IPv4 dummy address", "RFC 7600", [T, F, F, F], false),
+    row([192, 0, 0, 9], 32, "Port Control Protocol Anycast", "RFC 7723", [T, T, T, T], false),
+    row([192, 0, 0, 10], 32, "Traversal Using Relays around NAT Anycast", "RFC 8155", [T, T, T, T], false),
+    row([192, 0, 0, 170], 32, "NAT64/DNS64 Discovery", "RFC 8880", [F, T, F, F], true),
+    row([192, 0, 0, 171], 32, "NAT64/DNS64 Discovery", "RFC 8880", [F, T, F, F], true),
+    row([192, 0, 2, 0], 24, "Documentation (TEST-NET-1)", "RFC 5737", [F, F, F, F], false),
+    row([192, 31, 196, 0], 24, "AS112-v4", "RFC 7535", [T, T, T, T], false),
+    row([192, 52, 193, 0], 24, "AMT", "RFC 7450", [T, T, T, T], false),
+    row([192, 88, 99, 0], 24, "Deprecated (6to4 Relay Anycast)", "RFC 7526", [NA, NA, NA, NA], false),
+    row([192, 168, 0, 0], 16, "Private-Use", "RFC 1918", [T, T, T, F], false),
+    row([192, 175, 48, 0], 24, "Direct Delegation AS112 Service", "RFC 7534", [T, T, T, T], false),
+    row([198, 18, 0, 0], 15, "Benchmarking", "RFC 2544", [T, T, T, F], false),
+    row([198, 51, 100, 0], 24, "Documentation (TEST-NET-2)", "RFC 5737", [F, F, F, F], false),
+    row([203, 0, 113, 0], 24, "Documentation (TEST-NET-3)", "RFC 5737", [F, F, F, F], false),
+    row([240, 0, 0, 0], 4, "Reserved", "RFC 1112", [F, F, F, F], true),
+    row([255, 255, 255, 255], 32, "Limited Broadcast", "RFC 8190", [F, T, F, F], true),
+];
+
+/// The top-level blocks of the IANA IPv6 Special-Purpose Address Registry.
+/// Entries nested inside 2001::/23 are covered by it.
+const IPV6_SPECIAL_BLOCKS: &[&str] = &[
+    "::/128",
+    "::1/128",
+    "::ffff:0:0/96",
+    "64:ff9b::/96",
+    "64:ff9b:1::/48",
+    "100::/64",
+    "2001::/23",
+    "2001:db8::/32",
+    "2002::/16",
+    "2620:4f:8000::/48",
+    "3fff::/20",
+    "5f00::/16",
+    "fc00::/7",
+    "fe80::/10",
+];
+
+/// Every block of the IPv4 registry and the top-level IPv6 special-purpose
+/// blocks, as CIDRs.
+pub fn special_purpose_blocks() -> Vec<String> {
+    IPV4_REGISTRY
+        .iter()
+        .map(|r| format!("{}/{}", Ipv4Addr::from(r.network), r.prefix))
+        .chain(IPV6_SPECIAL_BLOCKS.iter().map(|b| b.to_string()))
+        .collect()
+}
+
+/// The most specific registry entry containing all of `network`/`prefix`, or
+/// `None` when the block is not special-purpose or spans more than one entry.
+pub fn lookup_ipv4(network: Ipv4Addr, prefix: u8) -> Option<SpecialPurposeEntry> {
+    let network = u32::from(network);
+    IPV4_REGISTRY
+        .iter()
+        .filter(|r| {
+            let block = u32::from_be_bytes(r.network);
+            let mask = u32::MAX.checked_shl(32 - u32::from(r.prefix)).unwrap_or(0);
+            r.prefix <= prefix && network & mask == block
+        })
+        .max_by_key(|r| r.prefix)
+        .map(|r| {
+            let [source, destination, forwardable, globally_reachable] = r.flags;
+            SpecialPurposeEntry {
+                block: format!("{}/{}", Ipv4Addr::from(r.network), r.prefix),
+                name: r.name.to_string(),
+                rfc: r.rfc.to_string(),
+                allocation_attributes: AllocationAttributes {
+                    source,
+                    destination,
+                    forwardable,
+                    globally_reachable,
+                    reserved_by_protocol: r.reserved_by_protocol,
+                },
+            }
+        })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(cidr: &str) -> Option<SpecialPurposeEntry> {
+        let (addr, prefix) = cidr.split_once('/').unwrap();
+        lookup_ipv4(addr.parse().unwrap(), prefix.parse().unwrap())
+    }
+
+    fn flags(entry: &SpecialPurposeEntry) -> [Option<bool>; 4] {
+        let a = &entry.allocation_attributes;
+        [a.source, a.destination, a.forwardable, a.globally_reachable]
+    }
+
+    #[test]
+    fn test_private_and_shared_space() {
+        let entry = lookup("10.1.0.0/16").unwrap();
+        assert_eq!(entry.block, "10.0.0.0/8");
+        assert_eq!(entry.name, "Private-Use");
+        assert_eq!(flags(&entry), [T, T, T, F]);
+        assert!(!entry.allocation_attributes.reserved_by_protocol);
+
+        let entry = lookup("100.64.0.0/10").unwrap();
+        assert_eq!(entry.rfc, "RFC 6598");
+    }
+
+    #[test]
+    fn test_most_specific_entry_wins() {
+        // 192.0.0.9 is globally reachable even though its /24 is N/A
+        let entry = lookup("192.0.0.9/32").unwrap();
+        assert_eq!(entry.name, "Port Control Protocol Anycast");
+        assert_eq!(flags(&entry), [T, T, T, T]);
+
+        let entry = lookup("192.0.0.128/25").unwrap();
+        assert_eq!(entry.block, "192.0.0.0/24");
+        assert_eq!(flags(&entry), [NA, NA, NA, NA]);
+
+        let entry = lookup("0.0.0.0/32").unwrap();
+        assert_eq!(entry.name, "\"This host on this network\"");
+        let entry = lookup("0.1.2.0/24").unwrap();
+        assert_eq!(entry.name, "\"This network\"");
+    }
+
+    #[test]
+    fn test_protocol_reserved_blocks() {
+        let entry = lookup("127.0.0.1/32").unwrap();
+        assert_eq!(flags(&entry), [F, F, F, F]);
+        assert!(entry.allocation_attributes.reserved_by_protocol);
+
+        let entry = lookup("255.255.255.255/32").unwrap();
+        assert_eq!(entry.name, "Limited Broadcast");
+        assert_eq!(flags(&entry), [F, T, F, F]);
+        // The broadcast address also lies in 240.0.0.0/4; the /32 is more specific
+        assert_eq!(lookup("255.255.255.0/24").unwrap().name, "Reserved");
+    }
+
+    #[test]
+    fn test_special_purpose_blocks() {
+        let blocks = special_purpose_blocks();
+        assert_eq!(
+            blocks.len(),
+            IPV4_REGISTRY.len() + IPV6_SPECIAL_BLOCKS.len()
+        );
+        assert!(blocks.iter().any(|b| b == "169.254.0.0/16"));
+        assert!(blocks.iter().any(|b| b == "fe80::/10"));
+    }
+
+    #[test]
+    fn test_unlisted_or_spanning_blocks() {
+        assert_eq!(lookup("8.8.8.0/24"), None);
+        // Larger than any entry it touches
+        assert_eq!(lookup("10.0.0.0/7"), None);
+        assert_eq!(lookup("0.0.0.0/0"), None);
+    }
+}
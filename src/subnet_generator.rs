@@ -1,47 +1,209 @@
+use crate::config::DEFAULTS;
 use crate::error::{IpCalcError, Result};
 use crate::ipv4::Ipv4Subnet;
-use crate::ipv6::Ipv6Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use crate::validation::{Family, validate_prefix};
 use serde::Serialize;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
-/// Maximum number of subnets that can be generated in a single request.
-pub const MAX_GENERATED_SUBNETS: u64 = 1_000_000;
-
 #[derive(Debug, Serialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SplitSummary {
     pub supernet: String,
     pub new_prefix: u8,
     pub available_subnets: String,
 }
 
+/// The child prefix chosen by [`plan_split`] for a subnets-and-hosts request.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SplitPlan {
+    pub supernet: String,
+    pub subnets_required: u64,
+    pub hosts_required: u64,
+    pub new_prefix: u8,
+    /// Usable hosts in each subnet of `new_prefix`
+    pub hosts_per_subnet: String,
+    pub available_subnets: String,
+}
+
 #[derive(Debug, Serialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv4SubnetList {
     pub supernet: Ipv4Subnet,
     pub new_prefix: u8,
+    /// The minimum usable hosts `new_prefix` was derived from, when the split
+    /// was requested by host count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts_required: Option<u64>,
     pub requested_count: u64,
+    /// Index of the first generated subnet among all of the split's subnets
+    pub offset: u64,
+    /// Subnets of `new_prefix` in the supernet, as a decimal or `2^N`
+    pub total_available: String,
+    /// Number of subnets in `subnets`
+    pub returned: u64,
     pub subnets: Vec<Ipv4Subnet>,
+    /// True when `subnets` has been permuted by [`Ipv4SubnetList::shuffle`].
+    pub shuffled: bool,
+    /// Seed used for the shuffle, so the order can be reproduced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl Ipv4SubnetList {
+    /// Override the usable host count reported for /31 subnets (see
+    /// [`Ipv4Subnet::apply_p2p_usable`]).
+    pub fn apply_p2p_usable(&mut self, usable: u64) {
+        self.supernet.apply_p2p_usable(usable);
+        for subnet in &mut self.subnets {
+            subnet.apply_p2p_usable(usable);
+        }
+    }
+
+    /// Permute the generated subnets in a reproducible order derived from `seed`.
+    pub fn shuffle(&mut self, seed: u64) {
+        shuffle_with_seed(&mut self.subnets, seed);
+        self.shuffled = true;
+        self.seed = Some(seed);
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv6SubnetList {
     pub supernet: Ipv6Subnet,
     pub new_prefix: u8,
+    /// The minimum usable hosts `new_prefix` was derived from, when the split
+    /// was requested by host count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts_required: Option<u64>,
     pub requested_count: u64,
+    /// Index of the first generated subnet among all of the split's subnets
+    pub offset: u64,
+    /// Subnets of `new_prefix` in the supernet, as a decimal or `2^N`
+    pub total_available: String,
+    /// Number of subnets in `subnets`
+    pub returned: u64,
     pub subnets: Vec<Ipv6Subnet>,
+    /// True when `subnets` has been permuted by [`Ipv6SubnetList::shuffle`].
+    pub shuffled: bool,
+    /// Seed used for the shuffle, so the order can be reproduced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl Ipv6SubnetList {
+    /// Render every subnet (and the supernet) in `style`.
+    pub fn apply_style(&mut self, style: impl Into<Ipv6Format>) {
+        let style = style.into();
+        self.supernet.apply_style(style);
+        for subnet in &mut self.subnets {
+            subnet.apply_style(style);
+        }
+    }
+
+    /// Report every `total_addresses` as an exact decimal.
+    pub fn apply_decimal_totals(&mut self) {
+        self.supernet.apply_decimal_total();
+        for subnet in &mut self.subnets {
+            subnet.apply_decimal_total();
+        }
+    }
+
+    /// Permute the generated subnets in a reproducible order derived from `seed`.
+    pub fn shuffle(&mut self, seed: u64) {
+        shuffle_with_seed(&mut self.subnets, seed);
+        self.shuffled = true;
+        self.seed = Some(seed);
+    }
+}
+
+/// Row `index` (1-based) of a dual-stack plan: the IPv4 and IPv6 subnets at
+/// the same position in their supernets.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DualStackPair {
+    pub index: u64,
+    pub v4: Ipv4Subnet,
+    pub v6: Ipv6Subnet,
+}
+
+/// Parallel IPv4 and IPv6 splits paired index by index.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DualStackPlan {
+    pub v4_supernet: String,
+    pub v6_supernet: String,
+    pub v4_prefix: u8,
+    pub v6_prefix: u8,
+    pub requested_count: u64,
+    pub pairs: Vec<DualStackPair>,
+}
+
+impl DualStackPlan {
+    /// Override the usable host count reported for /31 subnets.
+    pub fn apply_p2p_usable(&mut self, usable: u64) {
+        for pair in &mut self.pairs {
+            pair.v4.apply_p2p_usable(usable);
+        }
+    }
+
+    /// Report every IPv6 `total_addresses` as an exact decimal.
+    pub fn apply_decimal_totals(&mut self) {
+        for pair in &mut self.pairs {
+            pair.v6.apply_decimal_total();
+        }
+    }
+}
+
+/// SplitMix64: a small, well-distributed PRNG whose output depends only on the
+/// seed, so shuffles are stable across platforms and releases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Fisher-Yates shuffle driven by a seeded [`SplitMix64`].
+fn shuffle_with_seed<T>(items: &mut [T], seed: u64) {
+    let mut rng = SplitMix64(seed);
+    for i in (1..items.len()).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Pick a random shuffle seed.
+///
+/// Kept within 32 bits so it survives JSON consumers that parse numbers as doubles.
+pub fn random_seed() -> u64 {
+    use std::hash::BuildHasher;
+    std::collections::hash_map::RandomState::new().hash_one(std::time::SystemTime::now())
+        & 0xFFFF_FFFF
 }
 
 /// Count available subnets without generating them.
 /// Auto-detects IPv4 vs IPv6 based on the CIDR notation.
 pub fn count_subnets(cidr: &str, new_prefix: u8) -> Result<SplitSummary> {
-    let (input, original_prefix, max_bits) = if cidr.contains(':') {
+    let (input, original_prefix, family) = if cidr.contains(':') {
         let s = Ipv6Subnet::from_cidr(cidr)?;
-        (s.input, s.prefix_length, 128u8)
+        (s.input, s.prefix_length, Family::V6)
     } else {
         let s = Ipv4Subnet::from_cidr(cidr)?;
-        (s.input, s.prefix_length, 32u8)
+        (s.input, s.prefix_length, Family::V4)
     };
 
     if new_prefix <= original_prefix {
@@ -50,31 +212,381 @@ pub fn count_subnets(cidr: &str, new_prefix: u8) -> Result<SplitSummary> {
             original_prefix,
         });
     }
-    if new_prefix > max_bits {
-        return Err(IpCalcError::InvalidPrefixLength(new_prefix));
-    }
+    validate_prefix(new_prefix, family)?;
+
+    Ok(SplitSummary {
+        supernet: input,
+        new_prefix,
+        available_subnets: available_subnets(new_prefix - original_prefix),
+    })
+}
 
-    let bits_diff = new_prefix - original_prefix;
-    let available = if bits_diff > 63 {
+/// The number of subnets a split `bits_diff` bits deeper yields, written out
+/// up to 2^63 and as `2^N` beyond.
+fn available_subnets(bits_diff: u8) -> String {
+    if bits_diff > 63 {
         format!("2^{}", bits_diff)
     } else {
         2u64.pow(bits_diff as u32).to_string()
+    }
+}
+
+/// Usable hosts in an IPv4 subnet of length `prefix`, given the usable count
+/// reported for a /31 point-to-point link.
+fn ipv4_usable_hosts(prefix: u8, p2p_usable: u64) -> u64 {
+    match prefix {
+        32 => 1,
+        31 => p2p_usable,
+        _ => (1u64 << (32 - prefix)) - 2,
+    }
+}
+
+/// The longest prefix whose subnets hold at least `hosts` usable hosts.
+///
+/// IPv4 excludes the network and broadcast addresses (a /31 counts as
+/// `p2p_usable`, a /32 as one host); IPv6 counts every address.
+pub fn prefix_for_hosts(hosts: u64, ipv6: bool, p2p_usable: u64) -> Result<u8> {
+    if hosts == 0 {
+        return Err(IpCalcError::InvalidInput(
+            "host count must be at least 1".to_string(),
+        ));
+    }
+    if ipv6 {
+        // 2^(128 - p) >= hosts
+        let bits = 64 - (hosts - 1).leading_zeros() as u8;
+        return Ok(128 - bits);
+    }
+    (0..=32u8)
+        .rev()
+        .find(|&p| ipv4_usable_hosts(p, p2p_usable) >= hosts)
+        .ok_or_else(|| IpCalcError::InvalidInput(format!("no IPv4 subnet holds {} hosts", hosts)))
+}
+
+/// The split prefix for subnets of `cidr` holding at least `hosts` usable
+/// hosts each (see [`prefix_for_hosts`]). It must be longer than the
+/// supernet's own prefix, or there is nothing to split.
+pub fn split_prefix_for_hosts(cidr: &str, hosts: u64, p2p_usable: u64) -> Result<u8> {
+    let ipv6 = cidr.contains(':');
+    let (input, original_prefix) = if ipv6 {
+        let s = Ipv6Subnet::from_cidr(cidr)?;
+        (s.input, s.prefix_length)
+    } else {
+        let s = Ipv4Subnet::from_cidr(cidr)?;
+        (s.input, s.prefix_length)
     };
+    let new_prefix = prefix_for_hosts(hosts, ipv6, p2p_usable)?;
+    if new_prefix <= original_prefix {
+        return Err(IpCalcError::InvalidInput(format!(
+            "{} hosts need a /{}, which does not split {}",
+            hosts, new_prefix, input
+        )));
+    }
+    Ok(new_prefix)
+}
 
-    Ok(SplitSummary {
+/// Pick the child prefix of `cidr` that holds `hosts` hosts per subnet and
+/// check that the supernet yields at least `subnets` of them.
+pub fn plan_split(cidr: &str, subnets: u64, hosts: u64, p2p_usable: u64) -> Result<SplitPlan> {
+    if subnets == 0 {
+        return Err(IpCalcError::InvalidInput(
+            "subnet count must be at least 1".to_string(),
+        ));
+    }
+    let ipv6 = cidr.contains(':');
+    let (input, original_prefix) = if ipv6 {
+        let s = Ipv6Subnet::from_cidr(cidr)?;
+        (s.input, s.prefix_length)
+    } else {
+        let s = Ipv4Subnet::from_cidr(cidr)?;
+        (s.input, s.prefix_length)
+    };
+
+    let new_prefix = prefix_for_hosts(hosts, ipv6, p2p_usable)?;
+    if new_prefix < original_prefix {
+        return Err(IpCalcError::InvalidInput(format!(
+            "{} is too small for even one subnet of {} hosts (needs a /{} or larger)",
+            input, hosts, new_prefix
+        )));
+    }
+
+    // A whole-supernet "split" is a single subnet, which count_subnets rejects
+    let available_subnets = if new_prefix == original_prefix {
+        "1".to_string()
+    } else {
+        count_subnets(cidr, new_prefix)?.available_subnets
+    };
+    let bits_diff = (new_prefix - original_prefix) as u32;
+    if bits_diff < 64 && subnets > 1u64 << bits_diff {
+        return Err(IpCalcError::InsufficientSubnets {
+            requested: subnets,
+            available: 1u64 << bits_diff,
+            new_prefix,
+            original_prefix,
+        });
+    }
+
+    let hosts_per_subnet = if ipv6 {
+        let bits = 128 - new_prefix;
+        if bits < 64 {
+            (1u64 << bits).to_string()
+        } else {
+            format!("2^{}", bits)
+        }
+    } else {
+        ipv4_usable_hosts(new_prefix, p2p_usable).to_string()
+    };
+
+    Ok(SplitPlan {
         supernet: input,
+        subnets_required: subnets,
+        hosts_required: hosts,
         new_prefix,
-        available_subnets: available,
+        hosts_per_subnet,
+        available_subnets,
     })
 }
 
-/// Generate IPv4 subnets from a supernet.
-/// If count is None, generates the maximum number of subnets possible.
-pub fn generate_ipv4_subnets(
+// ---------------------------------------------------------------------------
+// Lazy subnet iterators
+// ---------------------------------------------------------------------------
+
+/// Yields the subnets of a split one at a time, in address order, holding
+/// only the supernet and a cursor.
+#[derive(Debug, Clone)]
+pub struct Ipv4SubnetIter {
+    supernet: Ipv4Subnet,
+    new_prefix: u8,
+    offset: u64,
+    next: u64,
+    end: u64,
+}
+
+impl Ipv4SubnetIter {
+    pub fn supernet(&self) -> &Ipv4Subnet {
+        &self.supernet
+    }
+
+    pub fn new_prefix(&self) -> u8 {
+        self.new_prefix
+    }
+
+    /// Total number of subnets the iterator yields from the start.
+    pub fn requested_count(&self) -> u64 {
+        self.end - self.offset
+    }
+
+    /// Reject an iterator yielding more than `DEFAULTS.max_generated_subnets`.
+    pub fn check_generation_limit(&self) -> Result<()> {
+        check_generation_limit(self.requested_count())
+    }
+
+    /// Index of the first subnet yielded among all of the split's subnets.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Subnets of the new prefix in the whole supernet.
+    pub fn total_available(&self) -> String {
+        available_subnets(self.new_prefix - self.supernet.prefix_length)
+    }
+}
+
+impl Iterator for Ipv4SubnetIter {
+    type Item = Result<Ipv4Subnet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let offset = self.next << (32 - self.new_prefix);
+        self.next += 1;
+        let network = u32::from(self.supernet.network) as u64 + offset;
+        Some(Ipv4Subnet::new(
+            Ipv4Addr::from(network as u32),
+            self.new_prefix,
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.end - self.next).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
+/// Yields the subnets of an IPv6 split one at a time; see [`Ipv4SubnetIter`].
+///
+/// The cursor is a u128, as a split 64 or more bits deep can run past
+/// `u64::MAX` subnets.
+#[derive(Debug, Clone)]
+pub struct Ipv6SubnetIter {
+    supernet: Ipv6Subnet,
+    new_prefix: u8,
+    offset: u64,
+    next: u128,
+    end: u128,
+}
+
+impl Ipv6SubnetIter {
+    pub fn supernet(&self) -> &Ipv6Subnet {
+        &self.supernet
+    }
+
+    pub fn new_prefix(&self) -> u8 {
+        self.new_prefix
+    }
+
+    /// Total number of subnets the iterator yields from the start, capped at
+    /// `u64::MAX`.
+    pub fn requested_count(&self) -> u64 {
+        u64::try_from(self.end - self.offset as u128).unwrap_or(u64::MAX)
+    }
+
+    /// Reject an iterator yielding more than `DEFAULTS.max_generated_subnets`,
+    /// reporting the true count however deep the split.
+    pub fn check_generation_limit(&self) -> Result<()> {
+        let count = self.end - self.offset as u128;
+        if count <= DEFAULTS.max_generated_subnets as u128 {
+            return Ok(());
+        }
+        let bits_diff = self.new_prefix - self.supernet.prefix_length;
+        let count = if self.offset == 0 && split_size(bits_diff).is_none_or(|n| n == self.end) {
+            available_subnets(bits_diff)
+        } else {
+            count.to_string()
+        };
+        Err(IpCalcError::SubnetLimitExceeded {
+            count,
+            limit: DEFAULTS.max_generated_subnets,
+        })
+    }
+
+    /// Index of the first subnet yielded among all of the split's subnets.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Subnets of the new prefix in the whole supernet.
+    pub fn total_available(&self) -> String {
+        available_subnets(self.new_prefix - self.supernet.prefix_length)
+    }
+}
+
+impl Iterator for Ipv6SubnetIter {
+    type Item = Result<Ipv6Subnet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let offset = self.next << (128 - self.new_prefix);
+        self.next += 1;
+        let network = u128::from(self.supernet.network) + offset;
+        Some(Ipv6Subnet::new(Ipv6Addr::from(network), self.new_prefix))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.end - self.next).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
+/// `2^bits_diff`, the number of subnets in a split, or `None` for the 2^128
+/// of a /0 split to /128.
+fn split_size(bits_diff: u8) -> Option<u128> {
+    1u128.checked_shl(bits_diff as u32)
+}
+
+/// Resolve the index one past the last subnet to yield, starting at
+/// `offset`, rejecting an offset or count the `2^bits_diff` subnets of the
+/// supernet cannot supply. Without a count, every subnet from `offset` on is
+/// yielded, short of the last one of a /0 split to /128.
+fn split_end(
+    offset: u64,
+    count: Option<u64>,
+    bits_diff: u8,
+    new_prefix: u8,
+    original_prefix: u8,
+) -> Result<u128> {
+    // An offset plus a count stays below 2^65, so capping the subnet count
+    // there keeps every comparison exact without overflowing u128 at /0 -> /128
+    let available = 1u128 << bits_diff.min(65);
+    let offset_wide = offset as u128;
+    if offset_wide >= available {
+        return Err(IpCalcError::InvalidInput(format!(
+            "offset {} is past the last of the {} /{} subnets of a /{}",
+            offset,
+            available_subnets(new_prefix - original_prefix),
+            new_prefix,
+            original_prefix
+        )));
+    }
+    let remaining = available - offset_wide;
+    match count {
+        // Below the u64 count, so the true remainder fits the error
+        Some(c) if c as u128 > remaining => Err(IpCalcError::InsufficientSubnets {
+            requested: c,
+            available: remaining as u64,
+            new_prefix,
+            original_prefix,
+        }),
+        Some(c) => Ok(offset_wide + c as u128),
+        None => Ok(split_size(bits_diff).unwrap_or(u128::MAX)),
+    }
+}
+
+/// The number of subnets in a page of at most `limit` starting at subnet
+/// `offset`: `limit`, or fewer when the page reaches the end of the split.
+///
+/// Pass the result as the count to the `*_from` generators, which reject an
+/// offset past the last subnet.
+pub fn page_count(cidr: &str, new_prefix: u8, offset: u64, limit: u64) -> Result<u64> {
+    if offset.checked_add(limit).is_none() {
+        return Err(IpCalcError::InvalidInput(format!(
+            "offset {} plus limit {} is past the largest supported index",
+            offset, limit
+        )));
+    }
+    let original_prefix = if cidr.contains(':') {
+        Ipv6Subnet::from_cidr(cidr)?.prefix_length
+    } else {
+        Ipv4Subnet::from_cidr(cidr)?.prefix_length
+    };
+    // An invalid new prefix is left for the generator to report
+    let bits_diff = new_prefix.saturating_sub(original_prefix);
+    let remaining = (1u128 << bits_diff.min(65)).saturating_sub(offset as u128);
+    Ok((limit as u128).min(remaining) as u64)
+}
+
+/// Reject a split larger than `DEFAULTS.max_generated_subnets`.
+pub fn check_generation_limit(count: u64) -> Result<()> {
+    if count > DEFAULTS.max_generated_subnets {
+        return Err(IpCalcError::SubnetLimitExceeded {
+            count: count.to_string(),
+            limit: DEFAULTS.max_generated_subnets,
+        });
+    }
+    Ok(())
+}
+
+/// Lazily split an IPv4 supernet. If count is None, yields every subnet.
+///
+/// All validation happens here, before the first subnet is produced. The
+/// `DEFAULTS.max_generated_subnets` cap is left to the caller, via
+/// [`check_generation_limit`].
+pub fn iter_ipv4_subnets(cidr: &str, new_prefix: u8, count: Option<u64>) -> Result<Ipv4SubnetIter> {
+    iter_ipv4_subnets_from(cidr, new_prefix, 0, count)
+}
+
+/// [`iter_ipv4_subnets`] starting at subnet `offset` (0-based), computed
+/// directly rather than by skipping the subnets before it. If count is None,
+/// yields every subnet from there on. An offset past the last subnet is an
+/// error.
+pub fn iter_ipv4_subnets_from(
     cidr: &str,
     new_prefix: u8,
+    offset: u64,
     count: Option<u64>,
-) -> Result<Ipv4SubnetList> {
+) -> Result<Ipv4SubnetIter> {
     let supernet = Ipv4Subnet::from_cidr(cidr)?;
 
     if new_prefix <= supernet.prefix_length {
@@ -84,63 +596,83 @@ pub fn generate_ipv4_subnets(
         });
     }
 
-    if new_prefix > 32 {
-        return Err(IpCalcError::InvalidPrefixLength(new_prefix));
-    }
+    validate_prefix(new_prefix, Family::V4)?;
 
     let bits_diff = new_prefix - supernet.prefix_length;
-    let available: u64 = 2u64.pow(bits_diff as u32);
-
-    // Use provided count or maximum available
-    let actual_count = match count {
-        Some(c) => {
-            if c > available {
-                return Err(IpCalcError::InsufficientSubnets {
-                    requested: c,
-                    available,
-                    new_prefix,
-                    original_prefix: supernet.prefix_length,
-                });
-            }
-            c
-        }
-        None => available,
-    };
+    // At most 2^32 for IPv4
+    let end = split_end(offset, count, bits_diff, new_prefix, supernet.prefix_length)? as u64;
 
-    if actual_count > MAX_GENERATED_SUBNETS {
-        return Err(IpCalcError::SubnetLimitExceeded {
-            count: actual_count.to_string(),
-            limit: MAX_GENERATED_SUBNETS,
+    Ok(Ipv4SubnetIter {
+        supernet,
+        new_prefix,
+        offset,
+        next: offset,
+        end,
+    })
+}
+
+/// Lazily split an IPv6 supernet. If count is None, yields every subnet.
+pub fn iter_ipv6_subnets(cidr: &str, new_prefix: u8, count: Option<u64>) -> Result<Ipv6SubnetIter> {
+    iter_ipv6_subnets_from(cidr, new_prefix, 0, count)
+}
+
+/// [`iter_ipv6_subnets`] starting at subnet `offset` (0-based); see
+/// [`iter_ipv4_subnets_from`].
+pub fn iter_ipv6_subnets_from(
+    cidr: &str,
+    new_prefix: u8,
+    offset: u64,
+    count: Option<u64>,
+) -> Result<Ipv6SubnetIter> {
+    let supernet = Ipv6Subnet::from_cidr(cidr)?;
+
+    if new_prefix <= supernet.prefix_length {
+        return Err(IpCalcError::InvalidSubnetSplit {
+            new_prefix,
+            original_prefix: supernet.prefix_length,
         });
     }
 
-    let network_u32 = u32::from(supernet.network);
-    let subnet_size = 2u32.pow((32 - new_prefix) as u32);
+    validate_prefix(new_prefix, Family::V6)?;
 
-    let subnets: Result<Vec<Ipv4Subnet>> = (0..actual_count)
-        .map(|i| {
-            let subnet_network = network_u32 + (i as u32 * subnet_size);
-            let addr = Ipv4Addr::from(subnet_network);
-            Ipv4Subnet::new(addr, new_prefix)
-        })
-        .collect();
+    let bits_diff = new_prefix - supernet.prefix_length;
+    let end = split_end(offset, count, bits_diff, new_prefix, supernet.prefix_length)?;
 
-    Ok(Ipv4SubnetList {
+    Ok(Ipv6SubnetIter {
         supernet,
         new_prefix,
-        requested_count: actual_count,
-        subnets: subnets?,
+        offset,
+        next: offset as u128,
+        end,
     })
 }
 
-/// Generate IPv6 subnets from a supernet.
-/// If count is None, generates the maximum number of subnets possible.
-pub fn generate_ipv6_subnets(
-    cidr: &str,
+// ---------------------------------------------------------------------------
+// Single subnet lookup
+// ---------------------------------------------------------------------------
+
+/// Reject an index past the last of the `2^bits_diff` subnets of a split.
+fn check_subnet_index(
+    index: u64,
+    bits_diff: u8,
     new_prefix: u8,
-    count: Option<u64>,
-) -> Result<Ipv6SubnetList> {
-    let supernet = Ipv6Subnet::from_cidr(cidr)?;
+    original_prefix: u8,
+) -> Result<()> {
+    if bits_diff < 64 && index >= 1u64 << bits_diff {
+        return Err(IpCalcError::SubnetIndexOutOfRange {
+            index,
+            available: available_subnets(bits_diff),
+            new_prefix,
+            original_prefix,
+        });
+    }
+    Ok(())
+}
+
+/// The `/new_prefix` subnet at 0-based `index` within an IPv4 supernet,
+/// computed directly rather than by generating the subnets before it.
+pub fn nth_ipv4_subnet(cidr: &str, new_prefix: u8, index: u64) -> Result<Ipv4Subnet> {
+    let supernet = Ipv4Subnet::from_cidr(cidr)?;
 
     if new_prefix <= supernet.prefix_length {
         return Err(IpCalcError::InvalidSubnetSplit {
@@ -149,62 +681,168 @@ pub fn generate_ipv6_subnets(
         });
     }
 
-    if new_prefix > 128 {
-        return Err(IpCalcError::InvalidPrefixLength(new_prefix));
-    }
+    validate_prefix(new_prefix, Family::V4)?;
 
     let bits_diff = new_prefix - supernet.prefix_length;
+    check_subnet_index(index, bits_diff, new_prefix, supernet.prefix_length)?;
+    let network = u32::from(supernet.network) as u64 + (index << (32 - new_prefix));
+    Ipv4Subnet::new(Ipv4Addr::from(network as u32), new_prefix)
+}
 
-    // For IPv6, we need to handle larger ranges
-    let available: u64 = if bits_diff >= 64 {
-        u64::MAX
-    } else {
-        2u64.pow(bits_diff as u32)
-    };
-
-    // Use provided count or maximum available
-    let actual_count = match count {
-        Some(c) => {
-            if c > available {
-                return Err(IpCalcError::InsufficientSubnets {
-                    requested: c,
-                    available,
-                    new_prefix,
-                    original_prefix: supernet.prefix_length,
-                });
-            }
-            c
-        }
-        None => available,
-    };
+/// The `/new_prefix` subnet at 0-based `index` within an IPv6 supernet; see
+/// [`nth_ipv4_subnet`].
+pub fn nth_ipv6_subnet(cidr: &str, new_prefix: u8, index: u64) -> Result<Ipv6Subnet> {
+    let supernet = Ipv6Subnet::from_cidr(cidr)?;
 
-    if actual_count > MAX_GENERATED_SUBNETS {
-        return Err(IpCalcError::SubnetLimitExceeded {
-            count: actual_count.to_string(),
-            limit: MAX_GENERATED_SUBNETS,
+    if new_prefix <= supernet.prefix_length {
+        return Err(IpCalcError::InvalidSubnetSplit {
+            new_prefix,
+            original_prefix: supernet.prefix_length,
         });
     }
 
-    let network_u128 = u128::from(supernet.network);
-    let subnet_size: u128 = if new_prefix == 128 {
-        1
-    } else {
-        1u128 << (128 - new_prefix)
-    };
+    validate_prefix(new_prefix, Family::V6)?;
 
-    let subnets: Result<Vec<Ipv6Subnet>> = (0..actual_count)
-        .map(|i| {
-            let subnet_network = network_u128 + (i as u128 * subnet_size);
-            let addr = Ipv6Addr::from(subnet_network);
-            Ipv6Subnet::new(addr, new_prefix)
-        })
-        .collect();
+    let bits_diff = new_prefix - supernet.prefix_length;
+    check_subnet_index(index, bits_diff, new_prefix, supernet.prefix_length)?;
+    let network = u128::from(supernet.network) + ((index as u128) << (128 - new_prefix));
+    Ipv6Subnet::new(Ipv6Addr::from(network), new_prefix)
+}
+
+/// Generate IPv4 subnets from a supernet.
+/// If count is None, generates the maximum number of subnets possible.
+pub fn generate_ipv4_subnets(
+    cidr: &str,
+    new_prefix: u8,
+    count: Option<u64>,
+) -> Result<Ipv4SubnetList> {
+    generate_ipv4_subnets_from(cidr, new_prefix, 0, count)
+}
 
+/// [`generate_ipv4_subnets`] starting at subnet `offset`, for paging
+/// through a large split. If count is None, generates every subnet from
+/// `offset` on.
+pub fn generate_ipv4_subnets_from(
+    cidr: &str,
+    new_prefix: u8,
+    offset: u64,
+    count: Option<u64>,
+) -> Result<Ipv4SubnetList> {
+    let iter = iter_ipv4_subnets_from(cidr, new_prefix, offset, count)?;
+    iter.check_generation_limit()?;
+
+    let supernet = iter.supernet().clone();
+    let requested_count = iter.requested_count();
+    let total_available = iter.total_available();
+    let subnets = iter.collect::<Result<Vec<_>>>()?;
+    Ok(Ipv4SubnetList {
+        supernet,
+        new_prefix,
+        hosts_required: None,
+        requested_count,
+        offset,
+        total_available,
+        returned: subnets.len() as u64,
+        subnets,
+        shuffled: false,
+        seed: None,
+    })
+}
+
+/// Generate IPv6 subnets from a supernet.
+/// If count is None, generates the maximum number of subnets possible.
+pub fn generate_ipv6_subnets(
+    cidr: &str,
+    new_prefix: u8,
+    count: Option<u64>,
+) -> Result<Ipv6SubnetList> {
+    generate_ipv6_subnets_from(cidr, new_prefix, 0, count)
+}
+
+/// [`generate_ipv6_subnets`] starting at subnet `offset`, for paging
+/// through a large split. If count is None, generates every subnet from
+/// `offset` on.
+pub fn generate_ipv6_subnets_from(
+    cidr: &str,
+    new_prefix: u8,
+    offset: u64,
+    count: Option<u64>,
+) -> Result<Ipv6SubnetList> {
+    let iter = iter_ipv6_subnets_from(cidr, new_prefix, offset, count)?;
+    iter.check_generation_limit()?;
+
+    let supernet = iter.supernet().clone();
+    let requested_count = iter.requested_count();
+    let total_available = iter.total_available();
+    let subnets = iter.collect::<Result<Vec<_>>>()?;
     Ok(Ipv6SubnetList {
         supernet,
         new_prefix,
-        requested_count: actual_count,
-        subnets: subnets?,
+        hosts_required: None,
+        requested_count,
+        offset,
+        total_available,
+        returned: subnets.len() as u64,
+        subnets,
+        shuffled: false,
+        seed: None,
+    })
+}
+
+/// Name the family that ran out when one side of a dual-stack plan cannot
+/// supply the requested count.
+fn family_exhausted(family: &str, e: IpCalcError) -> IpCalcError {
+    match e {
+        IpCalcError::InsufficientSubnets {
+            requested,
+            available,
+            new_prefix,
+            original_prefix,
+        } => IpCalcError::DualStackExhausted {
+            family: family.to_string(),
+            requested,
+            available,
+            new_prefix,
+            original_prefix,
+        },
+        other => other,
+    }
+}
+
+/// Generate the first `count` subnets of an IPv4 and an IPv6 supernet and
+/// pair them by position, so row N gives both networks for site or VLAN N.
+///
+/// The generation limit applies to each family separately. IPv6 blocks are
+/// rendered in `format`.
+pub fn generate_dualstack_subnets(
+    v4_cidr: &str,
+    v6_cidr: &str,
+    v4_prefix: u8,
+    v6_prefix: u8,
+    count: u64,
+    format: Ipv6Format,
+) -> Result<DualStackPlan> {
+    let v4 = generate_ipv4_subnets(v4_cidr, v4_prefix, Some(count))
+        .map_err(|e| family_exhausted("IPv4", e))?;
+    let mut v6 = generate_ipv6_subnets(v6_cidr, v6_prefix, Some(count))
+        .map_err(|e| family_exhausted("IPv6", e))?;
+    v6.apply_style(format);
+
+    let pairs = v4
+        .subnets
+        .into_iter()
+        .zip(v6.subnets)
+        .zip(1..)
+        .map(|((v4, v6), index)| DualStackPair { index, v4, v6 })
+        .collect();
+
+    Ok(DualStackPlan {
+        v4_supernet: format!("{}/{}", v4.supernet.network, v4.supernet.prefix_length),
+        v6_supernet: v6.supernet.cidr(),
+        v4_prefix,
+        v6_prefix,
+        requested_count: count,
+        pairs,
     })
 }
 
@@ -270,6 +908,210 @@ mod tests {
         assert_eq!(result.requested_count, 256);
     }
 
+    #[test]
+    fn test_generate_ipv6_subnets_deep_split() {
+        // /16 to /96 is 80 bits, far more subnets than a u64 count
+        let result = generate_ipv6_subnets("2001::/16", 96, Some(3)).unwrap();
+        assert_eq!(result.total_available, "2^80");
+        assert_eq!(result.requested_count, 3);
+        assert_eq!(result.subnets[2].network.to_string(), "2001::2:0:0");
+
+        // A page may run past index u64::MAX instead of overflowing
+        let page = generate_ipv6_subnets_from("2001::/16", 96, u64::MAX, Some(2)).unwrap();
+        assert_eq!(page.subnets.len(), 2);
+        assert_eq!(page.subnets[1].network.to_string(), "2001:1::");
+
+        // A 64-bit split has exactly 2^64 subnets, the last at u64::MAX
+        let last = generate_ipv6_subnets_from("2001:db8::/32", 96, u64::MAX, Some(1)).unwrap();
+        assert_eq!(
+            last.subnets[0].network.to_string(),
+            "2001:db8:ffff:ffff:ffff:ffff::"
+        );
+        assert!(matches!(
+            generate_ipv6_subnets_from("2001:db8::/32", 96, u64::MAX, Some(2)),
+            Err(IpCalcError::InsufficientSubnets {
+                requested: 2,
+                available: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_generate_ipv6_subnets_generation_limit() {
+        let limit = DEFAULTS.max_generated_subnets;
+        let result = generate_ipv6_subnets("2001::/16", 96, Some(limit + 1));
+        assert!(
+            matches!(result, Err(IpCalcError::SubnetLimitExceeded { ref count, .. }) if *count == (limit + 1).to_string()),
+            "expected SubnetLimitExceeded, got {:?}",
+            result
+        );
+        assert!(iter_ipv6_subnets("2001::/16", 96, Some(limit + 1)).is_ok());
+    }
+
+    #[test]
+    fn test_ipv6_generation_limit_reports_deep_split_count() {
+        let count_of =
+            |cidr, prefix, offset| match generate_ipv6_subnets_from(cidr, prefix, offset, None) {
+                Err(IpCalcError::SubnetLimitExceeded { count, .. }) => count,
+                other => panic!("expected SubnetLimitExceeded, got {:?}", other),
+            };
+        assert_eq!(count_of("2001::/16", 96, 0), "2^80");
+        assert_eq!(count_of("::/0", 64, 0), "2^64");
+        assert_eq!(count_of("::/0", 128, 0), "2^128");
+        // From an offset, the remainder is written out
+        assert_eq!(count_of("::/0", 64, 1), (u64::MAX as u128).to_string());
+        assert_eq!(
+            count_of("2001::/16", 96, 5),
+            ((1u128 << 80) - 5).to_string()
+        );
+    }
+
+    #[test]
+    fn test_iter_ipv4_subnets_lazy() {
+        let mut iter = iter_ipv4_subnets("10.0.0.0/16", 28, None).unwrap();
+        assert_eq!(iter.requested_count(), 4096);
+        assert_eq!(iter.size_hint(), (4096, Some(4096)));
+        let first = iter.next().unwrap().unwrap();
+        assert_eq!(first.network.to_string(), "10.0.0.0");
+        let last = iter.last().unwrap().unwrap();
+        assert_eq!(last.network.to_string(), "10.0.255.240");
+
+        // Matches the buffered generator
+        let generated = generate_ipv4_subnets("192.168.0.0/22", 27, Some(7)).unwrap();
+        let iterated: Vec<_> = iter_ipv4_subnets("192.168.0.0/22", 27, Some(7))
+            .unwrap()
+            .map(|s| s.unwrap().network)
+            .collect();
+        let buffered: Vec<_> = generated.subnets.iter().map(|s| s.network).collect();
+        assert_eq!(iterated, buffered);
+    }
+
+    #[test]
+    fn test_iter_ipv6_subnets_lazy() {
+        // 2^64 subnets, none of which are produced up front
+        let mut iter = iter_ipv6_subnets("2001:db8::/32", 96, None).unwrap();
+        assert_eq!(iter.requested_count(), u64::MAX);
+        assert_eq!(
+            iter.nth(1).unwrap().unwrap().network.to_string(),
+            "2001:db8::1:0:0"
+        );
+    }
+
+    #[test]
+    fn test_iter_subnets_edge_counts() {
+        assert_eq!(
+            iter_ipv4_subnets("10.0.0.0/24", 26, Some(0))
+                .unwrap()
+                .count(),
+            0
+        );
+        assert_eq!(
+            iter_ipv6_subnets("2001:db8::/48", 64, Some(0))
+                .unwrap()
+                .count(),
+            0
+        );
+
+        // Too many is an error before iteration starts
+        assert!(matches!(
+            iter_ipv4_subnets("10.0.0.0/24", 26, Some(5)),
+            Err(IpCalcError::InsufficientSubnets { available: 4, .. })
+        ));
+        assert!(matches!(
+            iter_ipv6_subnets("2001:db8::/48", 50, Some(5)),
+            Err(IpCalcError::InsufficientSubnets { available: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_split_from_offset() {
+        let page = generate_ipv4_subnets_from("10.0.0.0/8", 24, 5000, Some(100)).unwrap();
+        assert_eq!(page.offset, 5000);
+        assert_eq!(page.total_available, "65536");
+        assert_eq!(page.subnets.len(), 100);
+        // 5000 = 19 * 256 + 136
+        assert_eq!(page.subnets[0].network.to_string(), "10.19.136.0");
+        assert_eq!(page.subnets[99].network.to_string(), "10.19.235.0");
+
+        // Without a count the page runs to the end of the supernet
+        let rest = generate_ipv4_subnets_from("192.168.0.0/22", 27, 30, None).unwrap();
+        assert_eq!(rest.subnets.len(), 2);
+        assert_eq!(rest.subnets[1].network.to_string(), "192.168.3.224");
+
+        let page = generate_ipv6_subnets_from("2001:db8::/32", 96, 1 << 40, Some(1)).unwrap();
+        assert_eq!(page.total_available, "2^64");
+        assert_eq!(page.subnets[0].network.to_string(), "2001:db8:0:100::");
+    }
+
+    #[test]
+    fn test_page_count() {
+        // 32 /27s in a /22: the last page is short
+        assert_eq!(page_count("192.168.0.0/22", 27, 0, 5).unwrap(), 5);
+        assert_eq!(page_count("192.168.0.0/22", 27, 30, 5).unwrap(), 2);
+        assert_eq!(page_count("2001:db8::/32", 96, u64::MAX - 1, 1).unwrap(), 1);
+
+        let page = generate_ipv4_subnets_from("192.168.0.0/22", 27, 30, Some(2)).unwrap();
+        assert_eq!(page.returned, 2);
+        assert_eq!(page.subnets[1].network.to_string(), "192.168.3.224");
+
+        let err = page_count("2001:db8::/32", 96, u64::MAX, 1).unwrap_err();
+        assert!(err.to_string().contains("largest supported index"));
+    }
+
+    #[test]
+    fn test_split_offset_out_of_range() {
+        let err = iter_ipv4_subnets_from("192.168.0.0/22", 27, 32, None).unwrap_err();
+        assert!(err.to_string().contains("offset 32 is past the last"));
+        assert!(matches!(
+            iter_ipv4_subnets_from("192.168.0.0/22", 27, 30, Some(3)),
+            Err(IpCalcError::InsufficientSubnets { available: 2, .. })
+        ));
+        assert!(iter_ipv6_subnets_from("2001:db8::/48", 50, 4, Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_nth_subnet() {
+        // Eight /27s per /24, so subnet 1000 starts 10.0.125.0/24
+        let subnet = nth_ipv4_subnet("10.0.0.0/16", 27, 1000).unwrap();
+        assert_eq!(subnet.network.to_string(), "10.0.125.0");
+        assert_eq!(subnet.prefix_length, 27);
+        let last = nth_ipv4_subnet("10.0.0.0/16", 27, 2047).unwrap();
+        assert_eq!(last.network.to_string(), "10.0.255.224");
+        assert_eq!(
+            nth_ipv4_subnet("0.0.0.0/0", 32, u32::MAX as u64)
+                .unwrap()
+                .network
+                .to_string(),
+            "255.255.255.255"
+        );
+
+        let subnet = nth_ipv6_subnet("2001:db8::/32", 96, 1 << 40).unwrap();
+        assert_eq!(subnet.network.to_string(), "2001:db8:0:100::");
+        let subnet = nth_ipv6_subnet("::/0", 128, u64::MAX).unwrap();
+        assert_eq!(subnet.network.to_string(), "::ffff:ffff:ffff:ffff");
+    }
+
+    #[test]
+    fn test_nth_subnet_out_of_range() {
+        let err = nth_ipv4_subnet("10.0.0.0/16", 27, 2048).unwrap_err();
+        assert!(matches!(
+            err,
+            IpCalcError::SubnetIndexOutOfRange {
+                index: 2048,
+                new_prefix: 27,
+                original_prefix: 16,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("only 2048 /27 subnets"));
+        assert!(nth_ipv6_subnet("2001:db8::/48", 50, 4).is_err());
+        assert!(matches!(
+            nth_ipv4_subnet("10.0.0.0/16", 16, 0),
+            Err(IpCalcError::InvalidSubnetSplit { .. })
+        ));
+    }
+
     #[test]
     fn test_invalid_new_prefix_smaller() {
         let result = generate_ipv4_subnets("192.168.0.0/24", 22, Some(1));
@@ -285,4 +1127,171 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_shuffle_same_seed_same_order() {
+        let mut a = generate_ipv4_subnets("10.0.0.0/16", 24, None).unwrap();
+        let mut b = generate_ipv4_subnets("10.0.0.0/16", 24, None).unwrap();
+        a.shuffle(42);
+        b.shuffle(42);
+        let order_a: Vec<_> = a.subnets.iter().map(|s| s.network).collect();
+        let order_b: Vec<_> = b.subnets.iter().map(|s| s.network).collect();
+        assert_eq!(order_a, order_b);
+        assert!(a.shuffled);
+        assert_eq!(a.seed, Some(42));
+        // Still a permutation of the original set
+        let mut sorted = order_a.clone();
+        sorted.sort();
+        let original: Vec<_> = generate_ipv4_subnets("10.0.0.0/16", 24, None)
+            .unwrap()
+            .subnets
+            .iter()
+            .map(|s| s.network)
+            .collect();
+        assert_eq!(sorted, original);
+        assert_ne!(order_a, original);
+    }
+
+    #[test]
+    fn test_shuffle_different_seeds_differ() {
+        let mut a = generate_ipv6_subnets("2001:db8::/48", 56, None).unwrap();
+        let mut b = generate_ipv6_subnets("2001:db8::/48", 56, None).unwrap();
+        a.shuffle(1);
+        b.shuffle(2);
+        let order_a: Vec<_> = a.subnets.iter().map(|s| s.network).collect();
+        let order_b: Vec<_> = b.subnets.iter().map(|s| s.network).collect();
+        assert_ne!(order_a, order_b);
+    }
+
+    #[test]
+    fn test_random_seed_fits_32_bits() {
+        assert!(random_seed() <= u64::from(u32::MAX));
+    }
+
+    #[test]
+    fn test_prefix_for_hosts() {
+        assert_eq!(prefix_for_hosts(200, false, 2).unwrap(), 24);
+        assert_eq!(prefix_for_hosts(254, false, 2).unwrap(), 24);
+        assert_eq!(prefix_for_hosts(255, false, 2).unwrap(), 23);
+        assert_eq!(prefix_for_hosts(1, false, 2).unwrap(), 32);
+        assert_eq!(prefix_for_hosts(2, false, 2).unwrap(), 31);
+        assert_eq!(prefix_for_hosts(2, false, 0).unwrap(), 30);
+        assert_eq!(prefix_for_hosts(256, true, 2).unwrap(), 120);
+        assert_eq!(prefix_for_hosts(257, true, 2).unwrap(), 119);
+        assert_eq!(prefix_for_hosts(1, true, 2).unwrap(), 128);
+        assert!(prefix_for_hosts(0, false, 2).is_err());
+        assert!(prefix_for_hosts(u64::MAX, false, 2).is_err());
+    }
+
+    #[test]
+    fn test_split_prefix_for_hosts() {
+        // A /25 holds 126 usable hosts, so 127 and 128 need a /24
+        assert_eq!(split_prefix_for_hosts("10.0.0.0/22", 100, 2).unwrap(), 25);
+        assert_eq!(split_prefix_for_hosts("10.0.0.0/22", 126, 2).unwrap(), 25);
+        assert_eq!(split_prefix_for_hosts("10.0.0.0/22", 127, 2).unwrap(), 24);
+        assert_eq!(split_prefix_for_hosts("10.0.0.0/22", 128, 2).unwrap(), 24);
+        // IPv6 counts every address: 128 fit a /121 exactly
+        assert_eq!(
+            split_prefix_for_hosts("2001:db8::/64", 128, 2).unwrap(),
+            121
+        );
+        assert_eq!(
+            split_prefix_for_hosts("2001:db8::/64", 129, 2).unwrap(),
+            120
+        );
+
+        // The derived prefix must be longer than the supernet's
+        let err = split_prefix_for_hosts("10.0.0.0/24", 200, 2).unwrap_err();
+        assert!(err.to_string().contains("does not split"));
+        assert!(split_prefix_for_hosts("10.0.0.0/24", 300, 2).is_err());
+        assert!(split_prefix_for_hosts("10.0.0.0/24", 0, 2).is_err());
+    }
+
+    #[test]
+    fn test_plan_split_satisfiable() {
+        let plan = plan_split("10.0.0.0/8", 10, 200, 2).unwrap();
+        assert_eq!(plan.new_prefix, 24);
+        assert_eq!(plan.hosts_per_subnet, "254");
+        assert_eq!(plan.available_subnets, "65536");
+
+        let plan = plan_split("10.0.0.0/24", 1, 254, 2).unwrap();
+        assert_eq!(plan.new_prefix, 24);
+        assert_eq!(plan.available_subnets, "1");
+
+        let plan = plan_split("2001:db8::/32", 1000, 1 << 20, 2).unwrap();
+        assert_eq!(plan.new_prefix, 108);
+        assert_eq!(plan.available_subnets, "2^76");
+    }
+
+    #[test]
+    fn test_plan_split_unsatisfiable() {
+        // 16 /24s in a /20
+        assert!(matches!(
+            plan_split("10.0.0.0/20", 17, 200, 2),
+            Err(IpCalcError::InsufficientSubnets {
+                requested: 17,
+                available: 16,
+                ..
+            })
+        ));
+        // Even one subnet of 300 hosts does not fit in a /24
+        assert!(plan_split("10.0.0.0/24", 1, 300, 2).is_err());
+        assert!(plan_split("10.0.0.0/8", 0, 200, 2).is_err());
+    }
+
+    #[test]
+    fn test_dualstack_pairs_by_index() {
+        let plan = generate_dualstack_subnets(
+            "10.20.0.0/16",
+            "2001:db8:20::/48",
+            24,
+            64,
+            3,
+            Ipv6Format::default(),
+        )
+        .unwrap();
+        assert_eq!(plan.pairs.len(), 3);
+        let last = &plan.pairs[2];
+        assert_eq!(last.index, 3);
+        assert_eq!(last.v4.network, Ipv4Addr::new(10, 20, 2, 0));
+        assert_eq!(
+            last.v6.network,
+            "2001:db8:20:2::".parse::<Ipv6Addr>().unwrap()
+        );
+
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let plan = generate_dualstack_subnets("10.0.0.0/16", "2001:db8:ab::/48", 24, 64, 2, upper)
+            .unwrap();
+        assert_eq!(plan.v6_supernet, "2001:DB8:AB::/48");
+        assert_eq!(plan.pairs[1].v6.cidr(), "2001:DB8:AB:1::/64");
+        assert_eq!(plan.v4_supernet, "10.0.0.0/16");
+    }
+
+    #[test]
+    fn test_dualstack_names_exhausted_family() {
+        // A /22 holds only four /24s
+        let err = generate_dualstack_subnets(
+            "10.0.0.0/22",
+            "2001:db8::/48",
+            24,
+            64,
+            5,
+            Ipv6Format::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            IpCalcError::DualStackExhausted { family, available: 4, .. } if family == "IPv4"
+        ));
+        let err = generate_dualstack_subnets(
+            "10.0.0.0/16",
+            "2001:db8::/62",
+            24,
+            64,
+            5,
+            Ipv6Format::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("IPv6 supernet ran out"));
+    }
 }
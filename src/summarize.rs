@@ -1,27 +1,196 @@
+use crate::config::DEFAULTS;
+use crate::covers::{exclude, format_block_as};
 use crate::error::{IpCalcError, Result};
+use crate::extract::ExtractionStats;
 use crate::ipv4::Ipv4Subnet;
-use crate::ipv6::Ipv6Subnet;
-use serde::Serialize;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use serde::{Deserialize, Serialize};
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 // ---------------------------------------------------------------------------
 // Result structs
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv4SummaryResult {
     pub input_count: usize,
     pub output_count: usize,
     pub cidrs: Vec<Ipv4Subnet>,
+    /// Present when the inputs were extracted from a routing-table style file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<ExtractionStats>,
+    /// Pipeline trace, present when requested with `--explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<SummaryExplanation>,
+    /// Blocks that entered or left the summary, present for `--base` updates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<SummaryChanges>,
+    /// Suspicious mixes of address space in the output, such as private
+    /// blocks alongside public ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Ipv6SummaryResult {
     pub input_count: usize,
     pub output_count: usize,
     pub cidrs: Vec<Ipv6Subnet>,
+    /// Present when the inputs were extracted from a routing-table style file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<ExtractionStats>,
+    /// Pipeline trace, present when requested with `--explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<SummaryExplanation>,
+    /// Blocks that entered or left the summary, present for `--base` updates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<SummaryChanges>,
+    /// Suspicious mixes of address space in the output, such as private
+    /// blocks alongside public ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// An input dropped because another entry already covers it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ContainedRemoval {
+    pub cidr: String,
+    pub parent: String,
+}
+
+/// Two sibling blocks combined into their parent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SiblingMerge {
+    pub children: Vec<String>,
+    pub parent: String,
+}
+
+/// The blocks an incremental update added to and dropped from a summary.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SummaryChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// The summarization pipeline, stage by stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SummaryExplanation {
+    /// Inputs with host bits cleared, sorted and deduplicated
+    pub normalized: Vec<String>,
+    /// Entries removed as contained, in the order they were found
+    pub removed: Vec<ContainedRemoval>,
+    /// Sibling merges, in the order they were performed
+    pub merges: Vec<SiblingMerge>,
+}
+
+impl Ipv4SummaryResult {
+    /// Fail unless every output block lies in `expected` space.
+    pub fn check_expected_space(&self, expected: AddressSpace) -> Result<()> {
+        check_expected_space(
+            self.cidrs.iter().map(|s| {
+                let cidr = format!("{}/{}", s.network, s.prefix_length);
+                (cidr, s.address_type.as_str())
+            }),
+            expected,
+        )
+    }
+}
+
+impl Ipv6SummaryResult {
+    /// Fail unless every output block lies in `expected` space.
+    pub fn check_expected_space(&self, expected: AddressSpace) -> Result<()> {
+        check_expected_space(
+            self.cidrs.iter().map(|s| {
+                let cidr = format!("{}/{}", s.network, s.prefix_length);
+                (cidr, s.address_type.as_str())
+            }),
+            expected,
+        )
+    }
+
+    /// Report every `total_addresses` as an exact decimal.
+    pub fn apply_decimal_totals(&mut self) {
+        for subnet in &mut self.cidrs {
+            subnet.apply_decimal_total();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Address space checks
+// ---------------------------------------------------------------------------
+
+/// Broad class of address space, for spotting summaries that mix private
+/// networks with public ones (e.g. a `10.x` typed as `100.x`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressSpace {
+    /// RFC 1918, or IPv6 unique local (RFC 4193)
+    Private,
+    /// Public IPv4, or IPv6 global unicast
+    Public,
+    /// Every other special-purpose range: CGNAT, loopback, documentation, ...
+    Special,
+}
+
+impl AddressSpace {
+    /// Classify an `address_type` label of [`Ipv4Subnet`] or [`Ipv6Subnet`].
+    pub fn from_address_type(label: &str) -> Self {
+        match label {
+            "Private (RFC 1918)" | "Unique Local Address (RFC 4193)" => Self::Private,
+            "Public" | "Global Unicast (RFC 4291)" => Self::Public,
+            _ => Self::Special,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Public => "public",
+            Self::Special => "special-purpose",
+        }
+    }
+}
+
+/// One warning per kind of space found alongside private space.
+fn address_space_warnings<'a>(labels: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let spaces: Vec<AddressSpace> = labels.map(AddressSpace::from_address_type).collect();
+    if !spaces.contains(&AddressSpace::Private) {
+        return Vec::new();
+    }
+    [AddressSpace::Public, AddressSpace::Special]
+        .into_iter()
+        .filter(|space| spaces.contains(space))
+        .map(|space| format!("output contains both private and {} space", space.as_str()))
+        .collect()
+}
+
+fn check_expected_space<'a>(
+    blocks: impl Iterator<Item = (String, &'a str)>,
+    expected: AddressSpace,
+) -> Result<()> {
+    let unexpected: Vec<String> = blocks
+        .filter(|(_, label)| AddressSpace::from_address_type(label) != expected)
+        .map(|(cidr, label)| format!("{} ({})", cidr, label))
+        .collect();
+    if unexpected.is_empty() {
+        return Ok(());
+    }
+    Err(IpCalcError::UnexpectedAddressSpace {
+        expected: expected.as_str().to_string(),
+        blocks: unexpected.join(", "),
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -30,7 +199,7 @@ pub struct Ipv6SummaryResult {
 
 /// Compute a u128 mask for a given prefix, accounting for address family bit width.
 /// For IPv4 (bits=32), computes via u32 then extends; for IPv6 (bits=128), computes directly.
-fn prefix_mask(prefix: u8, bits: u8) -> u128 {
+pub(crate) fn prefix_mask(prefix: u8, bits: u8) -> u128 {
     if prefix == 0 {
         0u128
     } else if bits == 32 {
@@ -40,7 +209,41 @@ fn prefix_mask(prefix: u8, bits: u8) -> u128 {
     }
 }
 
-fn normalize_and_sort(entries: &mut Vec<(u128, u8)>, bits: u8) {
+/// Records pipeline events for [`SummaryExplanation`]. Stages take an
+/// `Option<&mut SummaryTrace>` so the untraced path does no extra work.
+struct SummaryTrace {
+    bits: u8,
+    ipv6_format: Ipv6Format,
+    explanation: SummaryExplanation,
+}
+
+impl SummaryTrace {
+    fn format(&self, block: (u128, u8)) -> String {
+        format_block_as(block, self.bits, self.ipv6_format)
+    }
+
+    fn normalized(&mut self, entries: &[(u128, u8)]) {
+        self.explanation.normalized = entries.iter().map(|&e| self.format(e)).collect();
+    }
+
+    fn removed(&mut self, entry: (u128, u8), parent: (u128, u8)) {
+        let removal = ContainedRemoval {
+            cidr: self.format(entry),
+            parent: self.format(parent),
+        };
+        self.explanation.removed.push(removal);
+    }
+
+    fn merged(&mut self, a: (u128, u8), b: (u128, u8), parent: (u128, u8)) {
+        let merge = SiblingMerge {
+            children: vec![self.format(a), self.format(b)],
+            parent: self.format(parent),
+        };
+        self.explanation.merges.push(merge);
+    }
+}
+
+fn normalize_and_sort(entries: &mut Vec<(u128, u8)>, bits: u8, trace: Option<&mut SummaryTrace>) {
     // Normalize: zero host bits
     for entry in entries.iter_mut() {
         entry.0 &= prefix_mask(entry.1, bits);
@@ -51,9 +254,13 @@ fn normalize_and_sort(entries: &mut Vec<(u128, u8)>, bits: u8) {
 
     // Dedup exact duplicates
     entries.dedup();
+
+    if let Some(trace) = trace {
+        trace.normalized(entries);
+    }
 }
 
-fn remove_contained(entries: &mut Vec<(u128, u8)>, bits: u8) {
+fn remove_contained(entries: &mut Vec<(u128, u8)>, bits: u8, mut trace: Option<&mut SummaryTrace>) {
     if entries.is_empty() {
         return;
     }
@@ -68,6 +275,9 @@ fn remove_contained(entries: &mut Vec<(u128, u8)>, bits: u8) {
 
         if entry.1 >= last.1 && (entry.0 & mask) == last.0 {
             // entry is contained in last, skip
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.removed(entry, *last);
+            }
             continue;
         }
         kept.push(entry);
@@ -76,7 +286,7 @@ fn remove_contained(entries: &mut Vec<(u128, u8)>, bits: u8) {
     *entries = kept;
 }
 
-fn merge_siblings(entries: &mut Vec<(u128, u8)>, bits: u8) {
+fn merge_siblings(entries: &mut Vec<(u128, u8)>, bits: u8, mut trace: Option<&mut SummaryTrace>) {
     loop {
         let mut merged = false;
         let mut result: Vec<(u128, u8)> = Vec::with_capacity(entries.len());
@@ -96,7 +306,11 @@ fn merge_siblings(entries: &mut Vec<(u128, u8)>, bits: u8) {
 
                     if parent_a == parent_b {
                         // Merge into parent
-                        result.push((net_a & prefix_mask(parent_prefix, bits), parent_prefix));
+                        let parent = (net_a & prefix_mask(parent_prefix, bits), parent_prefix);
+                        if let Some(trace) = trace.as_deref_mut() {
+                            trace.merged(entries[i], entries[i + 1], parent);
+                        }
+                        result.push(parent);
                         merged = true;
                         i += 2;
                         continue;
@@ -116,32 +330,41 @@ fn merge_siblings(entries: &mut Vec<(u128, u8)>, bits: u8) {
         // After merging, we may have new containment or new siblings, so re-sort and re-clean
         entries.sort();
         entries.dedup();
-        remove_contained(entries, bits);
+        remove_contained(entries, bits, trace.as_deref_mut());
     }
 }
 
-fn summarize_entries(entries: &mut Vec<(u128, u8)>, bits: u8) {
+fn summarize_entries(
+    entries: &mut Vec<(u128, u8)>,
+    bits: u8,
+    mut trace: Option<&mut SummaryTrace>,
+) {
     if entries.is_empty() {
         return;
     }
-    normalize_and_sort(entries, bits);
-    remove_contained(entries, bits);
-    merge_siblings(entries, bits);
+    normalize_and_sort(entries, bits, trace.as_deref_mut());
+    remove_contained(entries, bits, trace.as_deref_mut());
+    merge_siblings(entries, bits, trace);
 }
 
-pub const DEFAULT_MAX_SUMMARIZE_INPUTS: usize = 10_000;
-
 // ---------------------------------------------------------------------------
 // Public entry points
 // ---------------------------------------------------------------------------
 
-/// Validate inputs and run the summarization algorithm, returning raw (network, prefix) pairs.
+/// Input count, summarized (network, prefix) pairs, and the optional trace.
+type Summarized = (usize, Vec<(u128, u8)>, Option<SummaryExplanation>);
+
+/// Validate inputs and run the summarization algorithm, returning raw
+/// (network, prefix) pairs and, when `explain` is set, the pipeline trace
+/// with IPv6 blocks rendered in `ipv6_format`'s case.
 fn validate_and_summarize(
     cidrs: &[String],
     max_inputs: usize,
     bits: u8,
+    explain: bool,
+    ipv6_format: Ipv6Format,
     parse: impl Fn(&str) -> Result<(u128, u8)>,
-) -> Result<(usize, Vec<(u128, u8)>)> {
+) -> Result<Summarized> {
     if cidrs.is_empty() {
         return Err(IpCalcError::EmptyCidrList);
     }
@@ -158,19 +381,41 @@ fn validate_and_summarize(
         entries.push(parse(cidr)?);
     }
 
-    summarize_entries(&mut entries, bits);
-    Ok((input_count, entries))
+    let mut trace = explain.then(|| SummaryTrace {
+        bits,
+        ipv6_format,
+        explanation: SummaryExplanation::default(),
+    });
+    summarize_entries(&mut entries, bits, trace.as_mut());
+    Ok((input_count, entries, trace.map(|t| t.explanation)))
 }
 
 pub fn summarize_ipv4(cidrs: &[String]) -> Result<Ipv4SummaryResult> {
-    summarize_ipv4_with_limit(cidrs, DEFAULT_MAX_SUMMARIZE_INPUTS)
+    summarize_ipv4_with_limit(cidrs, DEFAULTS.max_summarize_inputs)
 }
 
 pub fn summarize_ipv4_with_limit(cidrs: &[String], max_inputs: usize) -> Result<Ipv4SummaryResult> {
-    let (input_count, entries) = validate_and_summarize(cidrs, max_inputs, 32, |cidr| {
-        let subnet = Ipv4Subnet::from_cidr(cidr)?;
-        Ok((u32::from(subnet.network) as u128, subnet.prefix_length))
-    })?;
+    summarize_ipv4_with_options(cidrs, max_inputs, false)
+}
+
+/// Summarize with a size limit, optionally recording the pipeline trace in
+/// `explanation`.
+pub fn summarize_ipv4_with_options(
+    cidrs: &[String],
+    max_inputs: usize,
+    explain: bool,
+) -> Result<Ipv4SummaryResult> {
+    let (input_count, entries, explanation) = validate_and_summarize(
+        cidrs,
+        max_inputs,
+        32,
+        explain,
+        Ipv6Format::default(),
+        |cidr| {
+            let subnet = Ipv4Subnet::from_cidr(cidr)?;
+            Ok((u32::from(subnet.network) as u128, subnet.prefix_length))
+        },
+    )?;
 
     let mut result_cidrs = Vec::with_capacity(entries.len());
     for (network, prefix) in &entries {
@@ -181,30 +426,303 @@ pub fn summarize_ipv4_with_limit(cidrs: &[String], max_inputs: usize) -> Result<
     Ok(Ipv4SummaryResult {
         input_count,
         output_count: result_cidrs.len(),
+        warnings: address_space_warnings(result_cidrs.iter().map(|s| s.address_type.as_str())),
         cidrs: result_cidrs,
+        extraction: None,
+        explanation,
+        changes: None,
     })
 }
 
 pub fn summarize_ipv6(cidrs: &[String]) -> Result<Ipv6SummaryResult> {
-    summarize_ipv6_with_limit(cidrs, DEFAULT_MAX_SUMMARIZE_INPUTS)
+    summarize_ipv6_with_limit(cidrs, DEFAULTS.max_summarize_inputs)
 }
 
 pub fn summarize_ipv6_with_limit(cidrs: &[String], max_inputs: usize) -> Result<Ipv6SummaryResult> {
-    let (input_count, entries) = validate_and_summarize(cidrs, max_inputs, 128, |cidr| {
+    summarize_ipv6_with_options(cidrs, max_inputs, false, Ipv6Format::default())
+}
+
+/// Summarize with a size limit, optionally recording the pipeline trace in
+/// `explanation`, with every block rendered in `format`.
+pub fn summarize_ipv6_with_options(
+    cidrs: &[String],
+    max_inputs: usize,
+    explain: bool,
+    format: Ipv6Format,
+) -> Result<Ipv6SummaryResult> {
+    let (input_count, entries, explanation) =
+        validate_and_summarize(cidrs, max_inputs, 128, explain, format, |cidr| {
+            let subnet = Ipv6Subnet::from_cidr(cidr)?;
+            Ok((u128::from(subnet.network), subnet.prefix_length))
+        })?;
+
+    let mut result_cidrs = Vec::with_capacity(entries.len());
+    for (network, prefix) in &entries {
+        let addr = Ipv6Addr::from(*network);
+        result_cidrs.push(Ipv6Subnet::with_format(addr, *prefix, format)?);
+    }
+
+    Ok(Ipv6SummaryResult {
+        input_count,
+        output_count: result_cidrs.len(),
+        warnings: address_space_warnings(result_cidrs.iter().map(|s| s.address_type.as_str())),
+        cidrs: result_cidrs,
+        extraction: None,
+        explanation,
+        changes: None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Incremental updates
+// ---------------------------------------------------------------------------
+
+/// A summary result of either family, as read back by [`load_summary_base`].
+#[derive(Debug, Clone)]
+pub enum SummaryBase {
+    V4(Ipv4SummaryResult),
+    V6(Ipv6SummaryResult),
+}
+
+/// Read a JSON summary result file (as written by `ipcalc summarize --format json`).
+///
+/// The family is taken from the first block; a summary with no blocks is
+/// read as IPv4.
+pub fn load_summary_base(path: &str) -> Result<SummaryBase> {
+    // nosemgrep: rust.actix.path-traversal.tainted-path.tainted-path — CLI-only input file, not reachable from HTTP input
+    let contents = std::fs::read_to_string(path)?;
+    let invalid = |e: serde_json::Error| {
+        IpCalcError::InvalidInput(format!("{}: not a summary result: {}", path, e))
+    };
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(invalid)?;
+    let is_ipv6 = value["cidrs"][0]["network_address"]
+        .as_str()
+        .is_some_and(|addr| addr.contains(':'));
+    if is_ipv6 {
+        serde_json::from_value(value)
+            .map(SummaryBase::V6)
+            .map_err(invalid)
+    } else {
+        serde_json::from_value(value)
+            .map(SummaryBase::V4)
+            .map_err(invalid)
+    }
+}
+
+/// Merge the sorted `additions` into the sorted `entries` in a single pass.
+fn merge_sorted(entries: &[(u128, u8)], additions: &[(u128, u8)]) -> Vec<(u128, u8)> {
+    let mut merged = Vec::with_capacity(entries.len() + additions.len());
+    let (mut i, mut j) = (0, 0);
+    while i < entries.len() && j < additions.len() {
+        if additions[j] < entries[i] {
+            merged.push(additions[j]);
+            j += 1;
+        } else {
+            merged.push(entries[i]);
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&entries[i..]);
+    merged.extend_from_slice(&additions[j..]);
+    merged
+}
+
+/// Last address of a block.
+pub(crate) fn block_end((network, prefix): (u128, u8), bits: u8) -> u128 {
+    let family_max = if bits == 32 {
+        u32::MAX as u128
+    } else {
+        u128::MAX
+    };
+    network | (!prefix_mask(prefix, bits) & family_max)
+}
+
+/// Cut `holes` (summarized, so sorted and disjoint) out of the summarized
+/// `entries`. Each entry only looks at the run of holes overlapping it.
+fn exclude_holes(entries: &[(u128, u8)], holes: &[(u128, u8)], bits: u8) -> Vec<(u128, u8)> {
+    let mut out = Vec::with_capacity(entries.len());
+    for &entry in entries {
+        let end = block_end(entry, bits);
+        let first = holes.partition_point(|&hole| block_end(hole, bits) < entry.0);
+        let last = first + holes[first..].partition_point(|&hole| hole.0 <= end);
+        exclude(entry, &holes[first..last], bits, &mut out);
+    }
+    out
+}
+
+/// Blocks only in `after` and blocks only in `before`; both inputs sorted.
+type BlockChanges = (Vec<(u128, u8)>, Vec<(u128, u8)>);
+
+fn changed_blocks(before: &[(u128, u8)], after: &[(u128, u8)]) -> BlockChanges {
+    let (mut added, mut removed) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        match (before.get(i), after.get(j)) {
+            (Some(a), Some(b)) if a == b => {
+                i += 1;
+                j += 1;
+            }
+            (Some(a), Some(b)) if a < b => {
+                removed.push(*a);
+                i += 1;
+            }
+            (Some(a), None) => {
+                removed.push(*a);
+                i += 1;
+            }
+            (_, Some(b)) => {
+                added.push(*b);
+                j += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+    (added, removed)
+}
+
+/// Apply `add` and `remove` to an already summarized entry list.
+///
+/// Additions are merged into the sorted base without a full re-sort, then
+/// removals are cut out, splitting blocks where needed. An address in both
+/// lists ends up removed. IPv6 changes are rendered in `ipv6_format`'s case.
+fn update_entries(
+    base: Vec<(u128, u8)>,
+    add: &[String],
+    remove: &[String],
+    max_inputs: usize,
+    bits: u8,
+    ipv6_format: Ipv6Format,
+    parse: impl Fn(&str) -> Result<(u128, u8)>,
+) -> Result<(Vec<(u128, u8)>, SummaryChanges)> {
+    for list in [add, remove] {
+        if list.len() > max_inputs {
+            return Err(IpCalcError::SummarizeInputLimitExceeded {
+                count: list.len(),
+                limit: max_inputs,
+            });
+        }
+    }
+    let mut additions = add.iter().map(|c| parse(c)).collect::<Result<Vec<_>>>()?;
+    let mut holes = remove
+        .iter()
+        .map(|c| parse(c))
+        .collect::<Result<Vec<_>>>()?;
+
+    // A hand-edited base may not be in summary order
+    let mut before = base;
+    if !before.is_sorted() {
+        normalize_and_sort(&mut before, bits, None);
+    }
+
+    normalize_and_sort(&mut additions, bits, None);
+    let mut entries = merge_sorted(&before, &additions);
+    remove_contained(&mut entries, bits, None);
+    merge_siblings(&mut entries, bits, None);
+
+    if !holes.is_empty() {
+        summarize_entries(&mut holes, bits, None);
+        // Fragments of a summarized block never merge with a neighbour, so
+        // the result is still fully summarized
+        entries = exclude_holes(&entries, &holes, bits);
+    }
+
+    let (added, removed) = changed_blocks(&before, &entries);
+    let changes = SummaryChanges {
+        added: added
+            .into_iter()
+            .map(|b| format_block_as(b, bits, ipv6_format))
+            .collect(),
+        removed: removed
+            .into_iter()
+            .map(|b| format_block_as(b, bits, ipv6_format))
+            .collect(),
+    };
+    Ok((entries, changes))
+}
+
+/// Update a previous IPv4 summary with added and removed CIDRs.
+///
+/// The result equals summarizing the base's address space plus `add` minus
+/// `remove` from scratch; `changes` lists the blocks that differ from the
+/// base. `input_count` grows by the number of added CIDRs.
+pub fn update_ipv4_summary(
+    base: &Ipv4SummaryResult,
+    add: &[String],
+    remove: &[String],
+    max_inputs: usize,
+) -> Result<Ipv4SummaryResult> {
+    let entries = base
+        .cidrs
+        .iter()
+        .map(|s| (u32::from(s.network) as u128, s.prefix_length))
+        .collect();
+    let parse = |cidr: &str| {
+        let subnet = Ipv4Subnet::from_cidr(cidr)?;
+        Ok((u32::from(subnet.network) as u128, subnet.prefix_length))
+    };
+    let (entries, changes) = update_entries(
+        entries,
+        add,
+        remove,
+        max_inputs,
+        32,
+        Ipv6Format::default(),
+        parse,
+    )?;
+
+    let mut result_cidrs = Vec::with_capacity(entries.len());
+    for (network, prefix) in &entries {
+        let addr = Ipv4Addr::from(*network as u32);
+        result_cidrs.push(Ipv4Subnet::new(addr, *prefix)?);
+    }
+
+    Ok(Ipv4SummaryResult {
+        input_count: base.input_count + add.len(),
+        output_count: result_cidrs.len(),
+        warnings: address_space_warnings(result_cidrs.iter().map(|s| s.address_type.as_str())),
+        cidrs: result_cidrs,
+        extraction: None,
+        explanation: None,
+        changes: Some(changes),
+    })
+}
+
+/// Update a previous IPv6 summary with added and removed CIDRs, with every
+/// block rendered in `format`.
+///
+/// See [`update_ipv4_summary`].
+pub fn update_ipv6_summary(
+    base: &Ipv6SummaryResult,
+    add: &[String],
+    remove: &[String],
+    max_inputs: usize,
+    format: Ipv6Format,
+) -> Result<Ipv6SummaryResult> {
+    let entries = base
+        .cidrs
+        .iter()
+        .map(|s| (u128::from(s.network), s.prefix_length))
+        .collect();
+    let parse = |cidr: &str| {
         let subnet = Ipv6Subnet::from_cidr(cidr)?;
         Ok((u128::from(subnet.network), subnet.prefix_length))
-    })?;
+    };
+    let (entries, changes) = update_entries(entries, add, remove, max_inputs, 128, format, parse)?;
 
     let mut result_cidrs = Vec::with_capacity(entries.len());
     for (network, prefix) in &entries {
         let addr = Ipv6Addr::from(*network);
-        result_cidrs.push(Ipv6Subnet::new(addr, *prefix)?);
+        result_cidrs.push(Ipv6Subnet::with_format(addr, *prefix, format)?);
     }
 
     Ok(Ipv6SummaryResult {
-        input_count,
+        input_count: base.input_count + add.len(),
         output_count: result_cidrs.len(),
+        warnings: address_space_warnings(result_cidrs.iter().map(|s| s.address_type.as_str())),
         cidrs: result_cidrs,
+        extraction: None,
+        explanation: None,
+        changes: Some(changes),
     })
 }
 
@@ -333,4 +851,238 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_explain_cascade_merge_chain() {
+        let cidrs: Vec<String> = ["10.0.3.0/24", "10.0.1.0/24", "10.0.2.0/24", "10.0.0.5/24"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result =
+            summarize_ipv4_with_options(&cidrs, DEFAULTS.max_summarize_inputs, true).unwrap();
+        let explanation = result.explanation.unwrap();
+        assert_eq!(
+            explanation.normalized,
+            ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/24", "10.0.3.0/24"]
+        );
+        assert!(explanation.removed.is_empty());
+        let merge = |a: &str, b: &str, parent: &str| SiblingMerge {
+            children: vec![a.to_string(), b.to_string()],
+            parent: parent.to_string(),
+        };
+        assert_eq!(
+            explanation.merges,
+            [
+                merge("10.0.0.0/24", "10.0.1.0/24", "10.0.0.0/23"),
+                merge("10.0.2.0/24", "10.0.3.0/24", "10.0.2.0/23"),
+                merge("10.0.0.0/23", "10.0.2.0/23", "10.0.0.0/22"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain_records_removals() {
+        let cidrs = vec!["10.0.0.0/8".to_string(), "10.1.0.0/16".to_string()];
+        let result =
+            summarize_ipv4_with_options(&cidrs, DEFAULTS.max_summarize_inputs, true).unwrap();
+        assert_eq!(
+            result.explanation.unwrap().removed,
+            [ContainedRemoval {
+                cidr: "10.1.0.0/16".to_string(),
+                parent: "10.0.0.0/8".to_string(),
+            }]
+        );
+
+        // Not requested: no trace
+        assert!(summarize_ipv4(&cidrs).unwrap().explanation.is_none());
+    }
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_update_merges_and_splits() {
+        let base = summarize_ipv4(&strings(&["10.0.0.0/24", "10.0.2.0/24"])).unwrap();
+        let result = update_ipv4_summary(
+            &base,
+            &strings(&["10.0.1.0/24", "10.0.3.0/24"]),
+            &strings(&["10.0.2.128/25"]),
+            DEFAULTS.max_summarize_inputs,
+        )
+        .unwrap();
+        let cidrs: Vec<String> = result
+            .cidrs
+            .iter()
+            .map(|s| format!("{}/{}", s.network, s.prefix_length))
+            .collect();
+        assert_eq!(cidrs, ["10.0.0.0/23", "10.0.2.0/25", "10.0.3.0/24"]);
+        assert_eq!(result.input_count, 4);
+        assert_eq!(
+            result.changes.unwrap(),
+            SummaryChanges {
+                added: strings(&["10.0.0.0/23", "10.0.2.0/25", "10.0.3.0/24"]),
+                removed: strings(&["10.0.0.0/24", "10.0.2.0/24"]),
+            }
+        );
+    }
+
+    /// Differential check: incremental updates must match a from-scratch
+    /// summary of the same address set, enumerated address by address.
+    #[test]
+    fn test_update_matches_full_summarize() {
+        // Deterministic pseudo-random blocks inside 10.0.0.0/24
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move |bound: u32| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            ((state >> 33) as u32) % bound
+        };
+        let mut random_blocks = |count: u32| -> Vec<(u32, u8)> {
+            (0..next(count) + 1)
+                .map(|_| {
+                    let prefix = 24 + next(9) as u8;
+                    let size = 1u32 << (32 - prefix);
+                    (next(256) / size * size, prefix)
+                })
+                .collect()
+        };
+
+        for _ in 0..300 {
+            let base_blocks = random_blocks(8);
+            let add_blocks = random_blocks(4);
+            let remove_blocks = random_blocks(4);
+            let render = |blocks: &[(u32, u8)]| -> Vec<String> {
+                blocks
+                    .iter()
+                    .map(|&(offset, prefix)| {
+                        format!("{}/{}", Ipv4Addr::from(0x0a00_0000 + offset), prefix)
+                    })
+                    .collect()
+            };
+
+            let mut present = [false; 256];
+            for (blocks, value) in [
+                (&base_blocks, true),
+                (&add_blocks, true),
+                (&remove_blocks, false),
+            ] {
+                for &(offset, prefix) in blocks {
+                    let size = 1u32 << (32 - prefix);
+                    for addr in offset..offset + size {
+                        present[addr as usize] = value;
+                    }
+                }
+            }
+            let addresses: Vec<String> = (0..256u32)
+                .filter(|&a| present[a as usize])
+                .map(|a| format!("{}/32", Ipv4Addr::from(0x0a00_0000 + a)))
+                .collect();
+            let expected: Vec<(Ipv4Addr, u8)> = if addresses.is_empty() {
+                Vec::new()
+            } else {
+                summarize_ipv4(&addresses)
+                    .unwrap()
+                    .cidrs
+                    .iter()
+                    .map(|s| (s.network, s.prefix_length))
+                    .collect()
+            };
+
+            let base = summarize_ipv4(&render(&base_blocks)).unwrap();
+            let updated = update_ipv4_summary(
+                &base,
+                &render(&add_blocks),
+                &render(&remove_blocks),
+                DEFAULTS.max_summarize_inputs,
+            )
+            .unwrap();
+            let actual: Vec<(Ipv4Addr, u8)> = updated
+                .cidrs
+                .iter()
+                .map(|s| (s.network, s.prefix_length))
+                .collect();
+            assert_eq!(
+                actual,
+                expected,
+                "base {:?} add {:?} remove {:?}",
+                render(&base_blocks),
+                render(&add_blocks),
+                render(&remove_blocks)
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_ipv6_round_trip() {
+        // An uppercase base reads back and updates in the same case
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let base =
+            summarize_ipv6_with_options(&strings(&["2001:db8::/48"]), 10, false, upper).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("base.json");
+        std::fs::write(&path, serde_json::to_string(&base).unwrap()).unwrap();
+
+        let SummaryBase::V6(loaded) = load_summary_base(path.to_str().unwrap()).unwrap() else {
+            panic!("expected an IPv6 base");
+        };
+        let result = update_ipv6_summary(
+            &loaded,
+            &strings(&["2001:db8:1::/48"]),
+            &strings(&["2001:db8::/49"]),
+            DEFAULTS.max_summarize_inputs,
+            upper,
+        )
+        .unwrap();
+        let cidrs: Vec<String> = result.cidrs.iter().map(Ipv6Subnet::cidr).collect();
+        assert_eq!(cidrs, ["2001:DB8:0:8000::/49", "2001:DB8:1::/48"]);
+        assert_eq!(result.changes.unwrap().removed, ["2001:DB8::/48"]);
+    }
+
+    #[test]
+    fn test_mixed_space_warnings() {
+        // 100.x typed for 10.x puts public space in a private summary
+        let result = summarize_ipv4(&strings(&["10.1.0.0/16", "100.1.0.0/16"])).unwrap();
+        assert_eq!(
+            result.warnings,
+            ["output contains both private and public space"]
+        );
+
+        let result = summarize_ipv4(&strings(&["192.168.0.0/24", "100.64.0.0/24"])).unwrap();
+        assert_eq!(
+            result.warnings,
+            ["output contains both private and special-purpose space"]
+        );
+
+        let result = summarize_ipv6(&strings(&["fd00::/8", "2001:4860::/32"])).unwrap();
+        assert_eq!(result.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_private_only_is_clean() {
+        let result =
+            summarize_ipv4(&strings(&["10.0.0.0/24", "10.0.1.0/24", "192.168.5.0/24"])).unwrap();
+        assert!(result.warnings.is_empty());
+        assert!(result.check_expected_space(AddressSpace::Private).is_ok());
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json.get("warnings").is_none());
+
+        // Public-only output has nothing to warn about either
+        let result = summarize_ipv4(&strings(&["8.8.8.0/24", "1.1.1.0/24"])).unwrap();
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_expected_space_enforced() {
+        let result = summarize_ipv4(&strings(&["10.1.0.0/16", "100.1.0.0/16"])).unwrap();
+        let err = result
+            .check_expected_space(AddressSpace::Private)
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            IpCalcError::UnexpectedAddressSpace { blocks, .. } if blocks == "100.1.0.0/16 (Public)"
+        ));
+        assert!(result.check_expected_space(AddressSpace::Public).is_err());
+    }
 }
@@ -0,0 +1,333 @@
+use crate::addresses::format_full_ipv6_plus;
+use crate::config::DEFAULTS;
+use crate::covers::{Block, format_block_as};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use crate::summarize::{
+    block_end, prefix_mask, summarize_ipv4_with_limit, summarize_ipv6_with_limit,
+};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// The smallest single CIDR that contains every input.
+///
+/// Unlike a summary, the supernet may take in addresses no input covers;
+/// `extra_addresses` counts them. Counts are decimal strings so IPv6
+/// magnitudes are not truncated.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SupernetResult {
+    pub input_count: usize,
+    pub supernet: String,
+    pub prefix_length: u8,
+    /// Addresses in the supernet
+    pub total_addresses: String,
+    /// Addresses in the supernet that no input covers
+    pub extra_addresses: String,
+}
+
+// ---------------------------------------------------------------------------
+// Covering block over (network, prefix) pairs
+// ---------------------------------------------------------------------------
+
+/// The smallest aligned block spanning the lowest and highest address of the
+/// summarized, non-empty `blocks`. Inputs sharing no leading bits give /0.
+fn covering_block(blocks: &[Block], bits: u8) -> Block {
+    let first = blocks
+        .iter()
+        .map(|&(network, _)| network)
+        .min()
+        .unwrap_or(0);
+    let last = blocks
+        .iter()
+        .map(|&block| block_end(block, bits))
+        .max()
+        .unwrap_or(0);
+    // Values are right-aligned in a u128, so IPv4 has 96 extra leading zeros
+    let common = ((first ^ last).leading_zeros() as u8 - (128 - bits)).min(bits);
+    (first & prefix_mask(common, bits), common)
+}
+
+/// Addresses in disjoint blocks, none of them ::/0.
+fn address_count(blocks: &[Block], bits: u8) -> u128 {
+    blocks
+        .iter()
+        .map(|&(_, prefix)| 1u128 << (bits - prefix))
+        .sum()
+}
+
+/// Build the result, with an IPv6 supernet rendered in `format`'s case.
+fn supernet_result(
+    input_count: usize,
+    blocks: &[Block],
+    bits: u8,
+    format: Ipv6Format,
+) -> SupernetResult {
+    let supernet = covering_block(blocks, bits);
+    let (total, extra) = if bits == 128 && supernet.1 == 0 {
+        // ::/0 holds 2^128 addresses, one more than a u128 can count. A
+        // summary that contains ::/0 is just ::/0; any other summary has
+        // fewer than 2^128 addresses.
+        let extra = if blocks == [supernet] {
+            0
+        } else {
+            u128::MAX - address_count(blocks, bits) + 1
+        };
+        (format_full_ipv6_plus(0), extra.to_string())
+    } else {
+        let total = 1u128 << (bits - supernet.1);
+        let extra = total - address_count(blocks, bits);
+        (total.to_string(), extra.to_string())
+    };
+
+    SupernetResult {
+        input_count,
+        supernet: format_block_as(supernet, bits, format),
+        prefix_length: supernet.1,
+        total_addresses: total,
+        extra_addresses: extra,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+pub fn find_supernet_v4(cidrs: &[String]) -> Result<SupernetResult> {
+    find_supernet_v4_with_limit(cidrs, DEFAULTS.max_summarize_inputs)
+}
+
+/// The smallest IPv4 CIDR containing every input. Inputs are summarized
+/// first, so overlaps are counted once in `extra_addresses`. A list mixing
+/// IPv4 and IPv6 is rejected before anything is parsed.
+pub fn find_supernet_v4_with_limit(cidrs: &[String], max_inputs: usize) -> Result<SupernetResult> {
+    check_list_family(cidrs)?;
+    let blocks: Vec<Block> = summarize_ipv4_with_limit(cidrs, max_inputs)?
+        .cidrs
+        .iter()
+        .map(|s| (u32::from(s.network) as u128, s.prefix_length))
+        .collect();
+    Ok(supernet_result(
+        cidrs.len(),
+        &blocks,
+        32,
+        Ipv6Format::default(),
+    ))
+}
+
+pub fn find_supernet_v6(cidrs: &[String], format: Ipv6Format) -> Result<SupernetResult> {
+    find_supernet_v6_with_limit(cidrs, DEFAULTS.max_summarize_inputs, format)
+}
+
+/// The smallest IPv6 CIDR containing every input, rendered in `format`'s
+/// case; see [`find_supernet_v4_with_limit`].
+pub fn find_supernet_v6_with_limit(
+    cidrs: &[String],
+    max_inputs: usize,
+    format: Ipv6Format,
+) -> Result<SupernetResult> {
+    check_list_family(cidrs)?;
+    let blocks: Vec<Block> = summarize_ipv6_with_limit(cidrs, max_inputs)?
+        .cidrs
+        .iter()
+        .map(|s| (u128::from(s.network), s.prefix_length))
+        .collect();
+    Ok(supernet_result(cidrs.len(), &blocks, 128, format))
+}
+
+fn check_same_family(a: &str, b: &str) -> Result<()> {
+    if a.contains(':') != b.contains(':') {
+        return Err(IpCalcError::AddressFamilyMismatch {
+            cidr: a.to_string(),
+            address: b.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Reject a list holding both IPv4 and IPv6 entries, naming the first entry
+/// and the first one of the other family.
+fn check_list_family(cidrs: &[String]) -> Result<()> {
+    let Some(first) = cidrs.first() else {
+        return Ok(());
+    };
+    match cidrs
+        .iter()
+        .find(|c| c.contains(':') != first.contains(':'))
+    {
+        Some(other) => check_same_family(first.trim(), other.trim()),
+        None => Ok(()),
+    }
+}
+
+/// The tightest IPv4 CIDR covering both `a` and `b`: the longest prefix
+/// their addresses share, never longer than either input's. Identical
+/// inputs give the input itself.
+pub fn find_common_supernet_v4(a: &str, b: &str) -> Result<Ipv4Subnet> {
+    check_same_family(a, b)?;
+    let (a, b) = (Ipv4Subnet::from_cidr(a)?, Ipv4Subnet::from_cidr(b)?);
+    let (network, prefix) = covering_block(
+        &[
+            (u32::from(a.network) as u128, a.prefix_length),
+            (u32::from(b.network) as u128, b.prefix_length),
+        ],
+        32,
+    );
+    Ipv4Subnet::new(Ipv4Addr::from(network as u32), prefix)
+}
+
+/// IPv6 counterpart of [`find_common_supernet_v4`].
+pub fn find_common_supernet_v6(a: &str, b: &str) -> Result<Ipv6Subnet> {
+    check_same_family(a, b)?;
+    let (a, b) = (Ipv6Subnet::from_cidr(a)?, Ipv6Subnet::from_cidr(b)?);
+    let (network, prefix) = covering_block(
+        &[
+            (u128::from(a.network), a.prefix_length),
+            (u128::from(b.network), b.prefix_length),
+        ],
+        128,
+    );
+    Ipv6Subnet::new(Ipv6Addr::from(network), prefix)
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(cidrs: &[&str]) -> Vec<String> {
+        cidrs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ipv4_supernet() {
+        let result = find_supernet_v4(&strings(&["10.1.4.0/24", "10.1.7.0/24"])).unwrap();
+        assert_eq!(result.supernet, "10.1.4.0/22");
+        assert_eq!(result.prefix_length, 22);
+        assert_eq!(result.total_addresses, "1024");
+        assert_eq!(result.extra_addresses, "512");
+
+        // Overlapping inputs are counted once
+        let result = find_supernet_v4(&strings(&["10.0.0.0/16", "10.0.1.0/24"])).unwrap();
+        assert_eq!(result.supernet, "10.0.0.0/16");
+        assert_eq!(result.extra_addresses, "0");
+    }
+
+    #[test]
+    fn test_single_input_returns_itself() {
+        let result = find_supernet_v4(&strings(&["192.168.1.77/24"])).unwrap();
+        assert_eq!(result.supernet, "192.168.1.0/24");
+        assert_eq!(result.extra_addresses, "0");
+
+        let result =
+            find_supernet_v6(&strings(&["2001:db8::1/128"]), Ipv6Format::default()).unwrap();
+        assert_eq!(result.supernet, "2001:db8::1/128");
+        assert_eq!(result.total_addresses, "1");
+
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let result = find_supernet_v6(&strings(&["2001:db8:a::/48", "2001:db8:b::/48"]), upper);
+        assert_eq!(result.unwrap().supernet, "2001:DB8:A::/47");
+    }
+
+    #[test]
+    fn test_root_supernet() {
+        let result = find_supernet_v4(&strings(&["10.0.0.0/8", "192.168.0.0/16"])).unwrap();
+        assert_eq!(result.supernet, "0.0.0.0/0");
+        assert_eq!(result.total_addresses, "4294967296");
+        assert_eq!(
+            result.extra_addresses,
+            (4294967296u64 - 16777216 - 65536).to_string()
+        );
+
+        let result = find_supernet_v6(
+            &strings(&["2001:db8::/32", "fd00::/8"]),
+            Ipv6Format::default(),
+        )
+        .unwrap();
+        assert_eq!(result.supernet, "::/0");
+        assert_eq!(
+            result.total_addresses,
+            "340282366920938463463374607431768211456"
+        );
+        let extra = (u128::MAX - (1u128 << 96) - (1u128 << 120) + 1).to_string();
+        assert_eq!(result.extra_addresses, extra);
+
+        let result =
+            find_supernet_v6(&strings(&["::/0", "2001:db8::/32"]), Ipv6Format::default()).unwrap();
+        assert_eq!(result.supernet, "::/0");
+        assert_eq!(result.extra_addresses, "0");
+    }
+
+    #[test]
+    fn test_common_supernet() {
+        let s = find_common_supernet_v4("192.168.1.0/24", "192.168.3.0/24").unwrap();
+        assert_eq!(
+            (s.network, s.prefix_length),
+            (Ipv4Addr::new(192, 168, 0, 0), 22)
+        );
+
+        // Identical inputs give the input; a nested pair gives the outer block
+        let s = find_common_supernet_v4("10.1.2.0/24", "10.1.2.0/24").unwrap();
+        assert_eq!(s.prefix_length, 24);
+        let s = find_common_supernet_v4("10.0.0.0/8", "10.200.0.0/16").unwrap();
+        assert_eq!(
+            (s.network, s.prefix_length),
+            (Ipv4Addr::new(10, 0, 0, 0), 8)
+        );
+
+        // /0 with anything is /0
+        let s = find_common_supernet_v4("0.0.0.0/0", "192.0.2.0/24").unwrap();
+        assert_eq!(s.prefix_length, 0);
+        let s = find_common_supernet_v6("2001:db8::/32", "::/0").unwrap();
+        assert_eq!(s.prefix_length, 0);
+
+        let s = find_common_supernet_v6("2001:db8:1::/48", "2001:db8:2::/48").unwrap();
+        assert_eq!(s.network.to_string(), "2001:db8::");
+        assert_eq!(s.prefix_length, 46);
+    }
+
+    #[test]
+    fn test_supernet_mixed_families() {
+        let mixed = strings(&["10.0.0.0/8", "2001:db8::/32"]);
+        for result in [
+            find_supernet_v4(&mixed),
+            find_supernet_v6(&mixed, Ipv6Format::default()),
+        ] {
+            let err = result.unwrap_err();
+            assert!(
+                matches!(err, IpCalcError::AddressFamilyMismatch { ref address, ref cidr }
+                    if address == "2001:db8::/32" && cidr == "10.0.0.0/8"),
+                "expected AddressFamilyMismatch, got {:?}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_common_supernet_mixed_families() {
+        let err = find_common_supernet_v4("10.0.0.0/8", "2001:db8::/32").unwrap_err();
+        assert!(matches!(err, IpCalcError::AddressFamilyMismatch { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Address family mismatch: 2001:db8::/32 and 10.0.0.0/8 are different IP versions"
+        );
+        assert!(find_common_supernet_v6("2001:db8::/32", "10.0.0.0/8").is_err());
+        assert!(find_common_supernet_v4("10.0.0.0/33", "10.0.0.0/8").is_err());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(find_supernet_v4(&[]).is_err());
+        assert!(find_supernet_v4(&strings(&["10.0.0.0/8", "2001:db8::/32"])).is_err());
+        assert!(find_supernet_v6(&strings(&["bogus"]), Ipv6Format::default()).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Format, Ipv6Subnet};
+use crate::validation::{self, Family};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Most split levels a tree may have below its supernet.
+pub const MAX_TREE_LEVELS: usize = 8;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// One block of a split tree, with the blocks it splits into at the next
+/// level (none at the last level).
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SplitTreeNode {
+    pub cidr: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SplitTreeNode>,
+}
+
+/// A supernet split in layers: into blocks of the first level's prefix, each
+/// of those into blocks of the second level's prefix, and so on.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SplitTree {
+    pub supernet: String,
+    /// Prefix length of each level, outermost first
+    pub levels: Vec<u8>,
+    /// Blocks in the tree, not counting the supernet
+    pub node_count: u64,
+    pub children: Vec<SplitTreeNode>,
+}
+
+impl SplitTree {
+    /// Every block depth-first, with its level (1 for the supernet's
+    /// children) and parent CIDR.
+    pub fn walk(&self) -> Vec<(usize, &str, &str)> {
+        fn visit<'a>(
+            nodes: &'a [SplitTreeNode],
+            level: usize,
+            parent: &'a str,
+            out: &mut Vec<(usize, &'a str, &'a str)>,
+        ) {
+            for node in nodes {
+                out.push((level, &node.cidr, parent));
+                visit(&node.children, level + 1, &node.cidr, out);
+            }
+        }
+        let mut out = Vec::new();
+        visit(&self.children, 1, &self.supernet, &mut out);
+        out
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Check that `levels` lengthen the prefix at every step, and return the
+/// number of blocks they produce below a /`base` supernet.
+fn check_levels(base: u8, levels: &[u8], family: Family) -> Result<u64> {
+    if levels.is_empty() {
+        return Err(IpCalcError::InvalidInput(
+            "a split tree needs at least one level prefix".to_string(),
+        ));
+    }
+    if levels.len() > MAX_TREE_LEVELS {
+        return Err(IpCalcError::InvalidInput(format!(
+            "a split tree has at most {} levels, got {}",
+            MAX_TREE_LEVELS,
+            levels.len()
+        )));
+    }
+
+    let mut parent_prefix = base;
+    let mut count: u128 = 0;
+    for (i, &prefix) in levels.iter().enumerate() {
+        validation::validate_prefix(prefix, family)?;
+        if prefix <= parent_prefix {
+            return Err(IpCalcError::InvalidTreeLevel {
+                level: i + 1,
+                prefix,
+                parent_prefix,
+            });
+        }
+        // Blocks at this level, out of the whole supernet
+        count = count.saturating_add(1u128 << (prefix - base).min(127));
+        parent_prefix = prefix;
+    }
+
+    let limit = DEFAULTS.max_tree_nodes;
+    if count > u128::from(limit) {
+        let count = match deepest_level_bits(base, levels) {
+            Some(bits) => format!("more than 2^{}", bits),
+            None => count.to_string(),
+        };
+        return Err(IpCalcError::TreeNodeLimitExceeded { count, limit });
+    }
+    Ok(count as u64)
+}
+
+/// For trees too large to count exactly, the exponent of the deepest level's
+/// block count.
+fn deepest_level_bits(base: u8, levels: &[u8]) -> Option<u8> {
+    let deepest = levels.last()? - base;
+    (deepest >= 64).then_some(deepest)
+}
+
+/// Split the /`prefix` block at `network` by the first of `levels`, then each
+/// resulting block by the rest. Addresses are `width`-bit integers.
+fn build_nodes(
+    network: u128,
+    prefix: u8,
+    levels: &[u8],
+    width: u8,
+    format: &dyn Fn(u128, u8) -> String,
+) -> Vec<SplitTreeNode> {
+    let Some((&next, rest)) = levels.split_first() else {
+        return Vec::new();
+    };
+    let step = 1u128 << (width - next);
+    (0..1u128 << (next - prefix))
+        .map(|i| {
+            let child = network + i * step;
+            SplitTreeNode {
+                cidr: format(child, next),
+                children: build_nodes(child, next, rest, width, format),
+            }
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Public entry point
+// ---------------------------------------------------------------------------
+
+/// Split `cidr` at each prefix in `levels` in turn, keeping every block's
+/// children under it. IPv4 and IPv6 supernets are both accepted.
+///
+/// Each level must be longer than the one above it, and the whole tree may
+/// hold at most `DEFAULTS.max_tree_nodes` blocks. IPv6 blocks are rendered in
+/// `ipv6_format`'s case.
+pub fn build_split_tree(cidr: &str, levels: &[u8], ipv6_format: Ipv6Format) -> Result<SplitTree> {
+    let (supernet, network, base, width, family) = if cidr.contains(':') {
+        let subnet = Ipv6Subnet::from_cidr(cidr)?;
+        let network = ipv6_format.compressed(&subnet.network);
+        let supernet = format!("{}/{}", network, subnet.prefix_length);
+        (
+            supernet,
+            u128::from(subnet.network),
+            subnet.prefix_length,
+            128,
+            Family::V6,
+        )
+    } else {
+        let subnet = Ipv4Subnet::from_cidr(cidr)?;
+        let supernet = format!("{}/{}", subnet.network, subnet.prefix_length);
+        (
+            supernet,
+            u128::from(u32::from(subnet.network)),
+            subnet.prefix_length,
+            32,
+            Family::V4,
+        )
+    };
+    let node_count = check_levels(base, levels, family)?;
+
+    let format: &dyn Fn(u128, u8) -> String = match family {
+        Family::V4 => &|addr, prefix| format!("{}/{}", Ipv4Addr::from(addr as u32), prefix),
+        Family::V6 => &|addr, prefix| {
+            let network = ipv6_format.compressed(&Ipv6Addr::from(addr));
+            format!("{}/{}", network, prefix)
+        },
+    };
+    Ok(SplitTree {
+        supernet,
+        levels: levels.to_vec(),
+        node_count,
+        children: build_nodes(network, base, levels, width, format),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_levels() {
+        let tree = build_split_tree("10.0.0.0/16", &[20, 24], Ipv6Format::default()).unwrap();
+        assert_eq!(tree.supernet, "10.0.0.0/16");
+        assert_eq!(tree.levels, vec![20, 24]);
+        assert_eq!(tree.node_count, 16 + 256);
+        assert_eq!(tree.children.len(), 16);
+        assert_eq!(tree.children[0].cidr, "10.0.0.0/20");
+        assert_eq!(tree.children[1].cidr, "10.0.16.0/20");
+        assert_eq!(tree.children[15].cidr, "10.0.240.0/20");
+
+        let second = &tree.children[1].children;
+        assert_eq!(second.len(), 16);
+        assert_eq!(second[0].cidr, "10.0.16.0/24");
+        assert_eq!(second[15].cidr, "10.0.31.0/24");
+        assert!(second[0].children.is_empty());
+        assert_eq!(tree.walk().len() as u64, tree.node_count);
+    }
+
+    #[test]
+    fn test_four_levels_and_ipv6() {
+        let tree =
+            build_split_tree("10.0.0.0/8", &[10, 12, 14, 16], Ipv6Format::default()).unwrap();
+        assert_eq!(tree.node_count, 4 + 16 + 64 + 256);
+        let deepest = &tree.children[3].children[3].children[3].children[3];
+        assert_eq!(deepest.cidr, "10.255.0.0/16");
+
+        let tree = build_split_tree("2001:db8::/32", &[34, 36], Ipv6Format::default()).unwrap();
+        assert_eq!(tree.children[1].cidr, "2001:db8:4000::/34");
+        assert_eq!(tree.children[1].children[2].cidr, "2001:db8:6000::/36");
+
+        let upper = Ipv6Format::from(crate::ipv6::Ipv6Case::Upper);
+        let tree = build_split_tree("2001:db8:ab::/48", &[49], upper).unwrap();
+        assert_eq!(tree.supernet, "2001:DB8:AB::/48");
+        assert_eq!(tree.children[1].cidr, "2001:DB8:AB:8000::/49");
+
+        // Host bits of the supernet are cleared
+        let tree = build_split_tree("10.0.0.7/30", &[32], Ipv6Format::default()).unwrap();
+        assert_eq!(tree.supernet, "10.0.0.4/30");
+        assert_eq!(tree.children[3].cidr, "10.0.0.7/32");
+    }
+
+    #[test]
+    fn test_levels_must_lengthen() {
+        assert!(matches!(
+            build_split_tree("10.0.0.0/16", &[24, 20], Ipv6Format::default()),
+            Err(IpCalcError::InvalidTreeLevel {
+                level: 2,
+                prefix: 20,
+                parent_prefix: 24,
+            })
+        ));
+        assert!(matches!(
+            build_split_tree("10.0.0.0/16", &[16], Ipv6Format::default()),
+            Err(IpCalcError::InvalidTreeLevel { level: 1, .. })
+        ));
+        assert!(matches!(
+            build_split_tree("10.0.0.0/16", &[20, 20], Ipv6Format::default()),
+            Err(IpCalcError::InvalidTreeLevel { level: 2, .. })
+        ));
+        assert!(matches!(
+            build_split_tree("10.0.0.0/16", &[33], Ipv6Format::default()),
+            Err(IpCalcError::InvalidPrefixLength(33))
+        ));
+        assert!(build_split_tree("10.0.0.0/16", &[], Ipv6Format::default()).is_err());
+        assert!(
+            build_split_tree(
+                "10.0.0.0/8",
+                &[9, 10, 11, 12, 13, 14, 15, 16, 17],
+                Ipv6Format::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_node_limit() {
+        let limit = DEFAULTS.max_tree_nodes;
+        match build_split_tree("10.0.0.0/8", &[16, 32], Ipv6Format::default()) {
+            Err(IpCalcError::TreeNodeLimitExceeded { count, limit: l }) => {
+                assert_eq!(count, (256u64 + (1 << 24)).to_string());
+                assert_eq!(l, limit);
+            }
+            other => panic!("expected the node limit, got {other:?}"),
+        }
+        match build_split_tree("2001:db8::/32", &[48, 128], Ipv6Format::default()) {
+            Err(IpCalcError::TreeNodeLimitExceeded { count, .. }) => {
+                assert_eq!(count, "more than 2^96");
+            }
+            other => panic!("expected the node limit, got {other:?}"),
+        }
+    }
+}
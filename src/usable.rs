@@ -0,0 +1,353 @@
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::ipv6::{Ipv6Subnet, ipv6_mask};
+use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// Where an address sits within its subnet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum AddressRole {
+    Network,
+    Broadcast,
+    Host,
+    /// Not inside the subnet at all
+    Outside,
+}
+
+impl AddressRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Broadcast => "broadcast",
+            Self::Host => "host",
+            Self::Outside => "outside",
+        }
+    }
+}
+
+/// Whether an address can be assigned to a host in its subnet.
+///
+/// Every address of a /31 (RFC 3021) or /32 is usable; in larger subnets the
+/// network and broadcast addresses are not. IPv6 has no broadcast address,
+/// but its network address is the Subnet-Router anycast address (RFC 4291)
+/// except in a /127 (RFC 6164) or /128.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct UsabilityResult {
+    pub address: String,
+    /// The subnet the address belongs to
+    pub cidr: String,
+    pub role: AddressRole,
+    pub usable: bool,
+    pub network_address: String,
+    /// `None` for IPv6
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast_address: Option<String>,
+}
+
+impl UsabilityResult {
+    /// With a /31 usable count of 0, treat the two /31 addresses as network
+    /// and broadcast, as in a pre-RFC 3021 network.
+    pub fn apply_p2p_usable(&mut self, usable: u64) {
+        if usable == 0 && self.role == AddressRole::Host && self.cidr.ends_with("/31") {
+            self.role = if self.address == self.network_address {
+                AddressRole::Network
+            } else {
+                AddressRole::Broadcast
+            };
+            self.usable = false;
+        }
+    }
+}
+
+/// The IPv4 host at a 0-based index within a subnet, where 0 is the first
+/// usable host.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv4NthHostResult {
+    pub cidr: String,
+    pub n: u64,
+    pub address: String,
+}
+
+/// The IPv6 host at a 0-based index within a subnet.
+///
+/// `n` is a decimal string since an IPv6 index can exceed u64.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv6NthHostResult {
+    pub cidr: String,
+    pub n: String,
+    pub address: String,
+}
+
+// ---------------------------------------------------------------------------
+// Classification
+// ---------------------------------------------------------------------------
+
+fn classify_ipv4(address: Ipv4Addr, subnet: &Ipv4Subnet) -> UsabilityResult {
+    let role = if u32::from(address) & u32::from(subnet.mask) != u32::from(subnet.network) {
+        AddressRole::Outside
+    } else if subnet.prefix_length >= 31 {
+        AddressRole::Host
+    } else if address == subnet.network {
+        AddressRole::Network
+    } else if address == subnet.broadcast {
+        AddressRole::Broadcast
+    } else {
+        AddressRole::Host
+    };
+
+    UsabilityResult {
+        address: address.to_string(),
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        role,
+        usable: role == AddressRole::Host,
+        network_address: subnet.network.to_string(),
+        broadcast_address: Some(subnet.broadcast.to_string()),
+    }
+}
+
+fn classify_ipv6(address: Ipv6Addr, subnet: &Ipv6Subnet) -> UsabilityResult {
+    let mask = ipv6_mask(subnet.prefix_length);
+    let role = if u128::from(address) & mask != u128::from(subnet.network) {
+        AddressRole::Outside
+    } else if subnet.prefix_length < 127 && address == subnet.network {
+        AddressRole::Network
+    } else {
+        AddressRole::Host
+    };
+
+    UsabilityResult {
+        address: address.to_string(),
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        role,
+        usable: role == AddressRole::Host,
+        network_address: subnet.network.to_string(),
+        broadcast_address: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Classify the address of an `addr/prefix` (or netmask/wildcard) input as
+/// the network address, the broadcast address, or a usable host.
+pub fn check_ipv4_usable(input: &str) -> Result<UsabilityResult> {
+    let subnet = Ipv4Subnet::from_cidr(input)?;
+    // `input` keeps the address as given, before host bits are cleared
+    let address: Ipv4Addr = subnet
+        .input
+        .split_once('/')
+        .and_then(|(addr, _)| addr.parse().ok())
+        .unwrap_or(subnet.network);
+    Ok(classify_ipv4(address, &subnet))
+}
+
+/// Classify `address` within `cidr`: a usable host, the network or broadcast
+/// address, or outside the subnet.
+pub fn check_ipv4_host(address: &str, cidr: &str) -> Result<UsabilityResult> {
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    let trimmed = address.trim();
+    let addr = Ipv4Addr::from_str(trimmed)
+        .map_err(|_| IpCalcError::InvalidIpv4Address(trimmed.to_string()))?;
+    Ok(classify_ipv4(addr, &subnet))
+}
+
+/// IPv6 counterpart of [`check_ipv4_host`], with no broadcast address.
+pub fn check_ipv6_host(address: &str, cidr: &str) -> Result<UsabilityResult> {
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+    let trimmed = address.trim();
+    let addr = Ipv6Addr::from_str(trimmed)
+        .map_err(|_| IpCalcError::InvalidIpv6Address(trimmed.to_string()))?;
+    Ok(classify_ipv6(addr, &subnet))
+}
+
+/// The `n`th usable host of `cidr`, counting from 0 at the first host. An
+/// index that does not fit in u64 is past every IPv4 subnet.
+pub fn nth_host_v4(cidr: &str, n: u128) -> Result<Ipv4NthHostResult> {
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    let cidr = format!("{}/{}", subnet.network, subnet.prefix_length);
+    let found = u64::try_from(n)
+        .ok()
+        .and_then(|i| subnet.nth_host(i).map(|address| (i, address)));
+    let (n, address) = found.ok_or_else(|| {
+        let hosts = if subnet.prefix_length >= 31 {
+            subnet.total_hosts
+        } else {
+            subnet.usable_hosts
+        };
+        IpCalcError::InvalidInput(format!(
+            "host index {n} is out of range for {cidr} ({hosts} usable hosts)"
+        ))
+    })?;
+    Ok(Ipv4NthHostResult {
+        cidr,
+        n,
+        address: address.to_string(),
+    })
+}
+
+/// IPv6 counterpart of [`nth_host_v4`]. The Subnet-Router anycast address is
+/// not a host, so index 0 is the address after it outside a /127 or /128.
+pub fn nth_host_v6(cidr: &str, n: u128) -> Result<Ipv6NthHostResult> {
+    let subnet = Ipv6Subnet::from_cidr(cidr)?;
+    let cidr = format!("{}/{}", subnet.network, subnet.prefix_length);
+    let address = subnet.nth_host(n).ok_or_else(|| {
+        let span = u128::from(subnet.last) - u128::from(subnet.network);
+        let hosts = span - u128::from(subnet.prefix_length < 127) + 1;
+        IpCalcError::InvalidInput(format!(
+            "host index {n} is out of range for {cidr} ({hosts} usable hosts)"
+        ))
+    })?;
+    Ok(Ipv6NthHostResult {
+        cidr,
+        n: n.to_string(),
+        address: address.to_string(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_address() {
+        let result = check_ipv4_usable("192.168.1.0/24").unwrap();
+        assert_eq!(result.role, AddressRole::Network);
+        assert!(!result.usable);
+        assert_eq!(result.cidr, "192.168.1.0/24");
+    }
+
+    #[test]
+    fn test_broadcast_address() {
+        let result = check_ipv4_usable("192.168.1.255/24").unwrap();
+        assert_eq!(result.role, AddressRole::Broadcast);
+        assert!(!result.usable);
+        assert_eq!(result.address, "192.168.1.255");
+    }
+
+    #[test]
+    fn test_usable_host() {
+        let result = check_ipv4_usable("192.168.1.77/24").unwrap();
+        assert_eq!(result.role, AddressRole::Host);
+        assert!(result.usable);
+
+        let result = check_ipv4_usable("10.0.0.5/32").unwrap();
+        assert!(result.usable);
+    }
+
+    #[test]
+    fn test_point_to_point() {
+        for input in ["10.0.0.0/31", "10.0.0.1/31"] {
+            let result = check_ipv4_usable(input).unwrap();
+            assert_eq!(result.role, AddressRole::Host);
+            assert!(result.usable);
+        }
+
+        let mut result = check_ipv4_usable("10.0.0.1/31").unwrap();
+        result.apply_p2p_usable(0);
+        assert_eq!(result.role, AddressRole::Broadcast);
+        assert!(!result.usable);
+    }
+
+    #[test]
+    fn test_host_check_ipv4() {
+        let result = check_ipv4_host("192.168.1.0", "192.168.1.0/24").unwrap();
+        assert_eq!(result.role, AddressRole::Network);
+        let result = check_ipv4_host("192.168.1.254", "192.168.1.0/24").unwrap();
+        assert!(result.usable);
+
+        let mut result = check_ipv4_host("192.168.2.1", "192.168.1.0/24").unwrap();
+        assert_eq!(result.role, AddressRole::Outside);
+        assert!(!result.usable);
+        // An outside address of a /31 stays outside
+        result.apply_p2p_usable(0);
+        assert_eq!(result.role, AddressRole::Outside);
+
+        assert!(check_ipv4_host("10.0.0.1", "10.0.0.1/32").unwrap().usable);
+        assert!(check_ipv4_host("2001:db8::1", "10.0.0.0/24").is_err());
+    }
+
+    #[test]
+    fn test_host_check_ipv6() {
+        let result = check_ipv6_host("2001:db8::", "2001:db8::/64").unwrap();
+        assert_eq!(result.role, AddressRole::Network);
+        assert_eq!(result.broadcast_address, None);
+
+        let result = check_ipv6_host("2001:db8::ffff:ffff:ffff:ffff", "2001:db8::/64").unwrap();
+        assert_eq!(result.role, AddressRole::Host);
+
+        let result = check_ipv6_host("2001:db8::", "2001:db8::/127").unwrap();
+        assert!(result.usable);
+        let result = check_ipv6_host("2001:db9::1", "2001:db8::/32").unwrap();
+        assert_eq!(result.role, AddressRole::Outside);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(check_ipv4_usable("192.168.1.0").is_err());
+        assert!(check_ipv4_usable("2001:db8::/64").is_err());
+    }
+
+    #[test]
+    fn test_nth_host_ipv4() {
+        assert_eq!(nth_host_v4("10.0.0.0/24", 0).unwrap().address, "10.0.0.1");
+        assert_eq!(nth_host_v4("10.0.0.0/24", 5).unwrap().address, "10.0.0.6");
+        assert_eq!(
+            nth_host_v4("10.0.0.0/24", 253).unwrap().address,
+            "10.0.0.254"
+        );
+        let err = nth_host_v4("10.0.0.0/24", 254).unwrap_err();
+        assert!(err.to_string().contains("254 usable hosts"));
+
+        // Every address of a /31 or /32 is a host
+        assert_eq!(nth_host_v4("10.0.0.0/31", 1).unwrap().address, "10.0.0.1");
+        assert!(nth_host_v4("10.0.0.0/31", 2).is_err());
+        assert_eq!(nth_host_v4("10.0.0.7/32", 0).unwrap().address, "10.0.0.7");
+        assert!(nth_host_v4("10.0.0.7/32", 1).is_err());
+
+        let result = nth_host_v4("10.0.0.77/24", 5).unwrap();
+        assert_eq!(result.cidr, "10.0.0.0/24");
+        assert_eq!(result.n, 5);
+        assert!(nth_host_v4("10.0.0.0/8", 1 << 64).is_err());
+    }
+
+    #[test]
+    fn test_nth_host_ipv6() {
+        // The Subnet-Router anycast address is skipped
+        assert_eq!(
+            nth_host_v6("2001:db8::/64", 0).unwrap().address,
+            "2001:db8::1"
+        );
+        assert_eq!(
+            nth_host_v6("2001:db8::/120", 254).unwrap().address,
+            "2001:db8::ff"
+        );
+        assert!(nth_host_v6("2001:db8::/120", 255).is_err());
+        assert_eq!(
+            nth_host_v6("2001:db8::/127", 0).unwrap().address,
+            "2001:db8::"
+        );
+        let result = nth_host_v6("::/0", u128::MAX - 1).unwrap();
+        assert_eq!(result.address, "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff");
+        assert_eq!(result.n, (u128::MAX - 1).to_string());
+        assert!(nth_host_v6("::/0", u128::MAX).is_err());
+    }
+}
@@ -1,11 +1,10 @@
+use std::borrow::Cow;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
+use crate::config::DEFAULTS;
 use crate::error::{IpCalcError, Result};
 use crate::ipam::models::AllocationStatus;
 
-/// Maximum length for CIDR and IP address input strings.
-pub const MAX_INPUT_LENGTH: usize = 256;
-
 /// Maximum length for freeform text fields (name, description, owner, etc.).
 const MAX_TEXT_FIELD_LENGTH: usize = 1024;
 
@@ -23,12 +22,108 @@ fn has_path_traversal(s: &str) -> bool {
     s.contains("..") || s.contains('\0')
 }
 
+/// Split a trimmed CIDR string into its address part and prefix length.
+///
+/// Rejects internal whitespace and anything other than plain ASCII digits in
+/// the prefix (so `+8` and `-8` fail), and distinguishes a missing prefix from
+/// a non-numeric or out-of-range one in the error message.
+pub fn parse_cidr_parts(s: &str) -> Result<(&str, u8)> {
+    if s.chars().any(char::is_whitespace) {
+        return Err(IpCalcError::InvalidCidr(format!(
+            "{} (contains whitespace)",
+            s
+        )));
+    }
+
+    let (addr_str, prefix_str) = match s.split_once('/') {
+        Some((addr, prefix)) if !prefix.is_empty() => (addr, prefix),
+        _ => {
+            return Err(IpCalcError::InvalidCidr(format!(
+                "{} (missing prefix length)",
+                s
+            )));
+        }
+    };
+
+    if !prefix_str.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(IpCalcError::InvalidCidr(format!(
+            "{} (non-numeric prefix length)",
+            s
+        )));
+    }
+
+    // Too long for a u8 is out of range for both families; report it the
+    // same way as /33 or /129 (saturating digits too long for a u32)
+    let prefix: u8 = prefix_str
+        .parse()
+        .map_err(|_| IpCalcError::InvalidPrefixLength(prefix_str.parse().unwrap_or(u32::MAX)))?;
+
+    Ok((addr_str, prefix))
+}
+
+/// Prefix length of a contiguous netmask (leading ones, then zeros).
+fn netmask_prefix(mask: u32) -> Option<u8> {
+    (mask.leading_ones() + mask.trailing_zeros() == 32).then_some(mask.leading_ones() as u8)
+}
+
+/// Rewrite IPv4 netmask (`192.168.1.0/255.255.255.0`) or Cisco-style
+/// wildcard (`192.168.1.0 0.0.0.255`) notation as `addr/prefix`.
+///
+/// Returns `Ok(None)` for input in neither form, leaving it to the normal CIDR
+/// parser.
+pub fn mask_notation_to_cidr(s: &str) -> Result<Option<String>> {
+    if s.len() > DEFAULTS.max_input_length {
+        return Ok(None);
+    }
+    let (addr, mask, wildcard) = match s.split_once('/') {
+        Some((addr, mask)) => (addr, mask, false),
+        None => match s.split_once(' ') {
+            Some((addr, mask)) => (addr, mask.trim(), true),
+            None => return Ok(None),
+        },
+    };
+    if !mask.contains('.') {
+        return Ok(None);
+    }
+    let Ok(addr) = addr.parse::<Ipv4Addr>() else {
+        return Ok(None);
+    };
+    let invalid = || IpCalcError::InvalidNetmask(mask.to_string());
+    let mask = u32::from(mask.parse::<Ipv4Addr>().map_err(|_| invalid())?);
+
+    // After a space, a mask with its top bit set is a netmask, as in
+    // `ip address 192.168.1.1 255.255.255.0`; otherwise it is a wildcard.
+    let prefix = if wildcard && mask & 0x8000_0000 == 0 {
+        netmask_prefix(!mask)
+    } else {
+        netmask_prefix(mask)
+    };
+    let prefix = prefix.ok_or_else(invalid)?;
+    Ok(Some(format!("{}/{}", addr, prefix)))
+}
+
+/// Append the host prefix (`/32` or `/128`) to a bare IP address, so a single
+/// host can be given where a CIDR is expected. Any other input is returned
+/// unchanged for the CIDR parser to judge.
+pub fn with_host_prefix(s: &str) -> Cow<'_, str> {
+    let trimmed = s.trim();
+    if trimmed.parse::<Ipv4Addr>().is_ok() {
+        Cow::Owned(format!("{}/32", trimmed))
+    } else if trimmed.parse::<Ipv6Addr>().is_ok() {
+        Cow::Owned(format!("{}/128", trimmed))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
 /// Validate a CIDR string: length, no control chars, valid format (addr/prefix).
+///
+/// Surrounding whitespace is ignored; whitespace inside the CIDR is rejected.
 pub fn validate_cidr(s: &str) -> Result<()> {
-    if s.len() > MAX_INPUT_LENGTH {
+    if s.len() > DEFAULTS.max_input_length {
         return Err(IpCalcError::InputTooLong {
             length: s.len(),
-            limit: MAX_INPUT_LENGTH,
+            limit: DEFAULTS.max_input_length,
         });
     }
 
@@ -38,13 +133,8 @@ pub fn validate_cidr(s: &str) -> Result<()> {
         ));
     }
 
-    let (addr_str, prefix_str) = s
-        .split_once('/')
-        .ok_or_else(|| IpCalcError::InvalidCidr(s.to_string()))?;
-
-    let _prefix: u8 = prefix_str
-        .parse()
-        .map_err(|_| IpCalcError::InvalidCidr(s.to_string()))?;
+    let s = s.trim();
+    let (addr_str, prefix) = parse_cidr_parts(s)?;
 
     // Must parse as either IPv4 or IPv6
     let is_v4 = addr_str.parse::<Ipv4Addr>().is_ok();
@@ -55,21 +145,17 @@ pub fn validate_cidr(s: &str) -> Result<()> {
     }
 
     // Validate prefix range
-    if is_v4 {
-        validate_prefix_length(_prefix, 4)?;
-    } else {
-        validate_prefix_length(_prefix, 6)?;
-    }
+    validate_prefix(prefix, if is_v4 { Family::V4 } else { Family::V6 })?;
 
     Ok(())
 }
 
 /// Validate an IP address string: length, no control chars, parseable.
 pub fn validate_ip_address(s: &str) -> Result<()> {
-    if s.len() > MAX_INPUT_LENGTH {
+    if s.len() > DEFAULTS.max_input_length {
         return Err(IpCalcError::InputTooLong {
             length: s.len(),
-            limit: MAX_INPUT_LENGTH,
+            limit: DEFAULTS.max_input_length,
         });
     }
 
@@ -92,11 +178,30 @@ pub fn validate_ip_address(s: &str) -> Result<()> {
     Ok(())
 }
 
-/// Validate prefix length for the given IP version (4 or 6).
-pub fn validate_prefix_length(prefix: u8, ip_version: u8) -> Result<()> {
-    let max = if ip_version == 4 { 32 } else { 128 };
-    if prefix > max {
-        return Err(IpCalcError::InvalidPrefixLength(prefix));
+/// IP address family, for checks whose bounds differ between IPv4 and IPv6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    V4,
+    V6,
+}
+
+impl Family {
+    /// Address width in bits, which is also the longest valid prefix.
+    pub const fn bits(self) -> u8 {
+        match self {
+            Self::V4 => 32,
+            Self::V6 => 128,
+        }
+    }
+}
+
+/// Reject a prefix longer than the family's address width.
+///
+/// Every prefix bounds check goes through here so that all entry points
+/// fail the same way, with [`IpCalcError::InvalidPrefixLength`].
+pub fn validate_prefix(prefix: u8, family: Family) -> Result<()> {
+    if prefix > family.bits() {
+        return Err(IpCalcError::InvalidPrefixLength(u32::from(prefix)));
     }
     Ok(())
 }
@@ -188,6 +293,80 @@ pub fn validate_optional_identifier(field: &Option<String>) -> Result<()> {
 mod tests {
     use super::*;
 
+    // -----------------------------------------------------------------------
+    // mask_notation_to_cidr
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn netmask_notation() {
+        assert_eq!(
+            mask_notation_to_cidr("192.168.1.0/255.255.255.192").unwrap(),
+            Some("192.168.1.0/26".to_string())
+        );
+        assert_eq!(
+            mask_notation_to_cidr("192.168.1.0/255.255.255.0").unwrap(),
+            Some("192.168.1.0/24".to_string())
+        );
+        assert_eq!(
+            mask_notation_to_cidr("10.0.0.0/0.0.0.0").unwrap(),
+            Some("10.0.0.0/0".to_string())
+        );
+        assert_eq!(
+            mask_notation_to_cidr("10.0.0.1/255.255.255.255").unwrap(),
+            Some("10.0.0.1/32".to_string())
+        );
+        // Plain prefix notation is left alone
+        assert_eq!(mask_notation_to_cidr("192.168.1.0/24").unwrap(), None);
+        assert_eq!(mask_notation_to_cidr("2001:db8::/32").unwrap(), None);
+    }
+
+    #[test]
+    fn wildcard_notation() {
+        assert_eq!(
+            mask_notation_to_cidr("192.168.1.0 0.0.0.255").unwrap(),
+            Some("192.168.1.0/24".to_string())
+        );
+        assert_eq!(
+            mask_notation_to_cidr("10.1.2.3 0.0.0.0").unwrap(),
+            Some("10.1.2.3/32".to_string())
+        );
+        // A netmask after the space is accepted too
+        assert_eq!(
+            mask_notation_to_cidr("192.168.1.1 255.255.255.0").unwrap(),
+            Some("192.168.1.1/24".to_string())
+        );
+        assert_eq!(mask_notation_to_cidr("10.0.0.0 /24").unwrap(), None);
+    }
+
+    #[test]
+    fn non_contiguous_mask_rejected() {
+        assert!(matches!(
+            mask_notation_to_cidr("192.168.1.0/255.0.255.0"),
+            Err(IpCalcError::InvalidNetmask(m)) if m == "255.0.255.0"
+        ));
+        assert!(matches!(
+            mask_notation_to_cidr("192.168.1.0 0.255.0.255"),
+            Err(IpCalcError::InvalidNetmask(_))
+        ));
+        assert!(matches!(
+            mask_notation_to_cidr("192.168.1.0/255.255.255.1"),
+            Err(IpCalcError::InvalidNetmask(_))
+        ));
+        assert!(matches!(
+            mask_notation_to_cidr("192.168.1.0/255.255.255.256"),
+            Err(IpCalcError::InvalidNetmask(_))
+        ));
+    }
+
+    #[test]
+    fn bare_address_gets_host_prefix() {
+        assert_eq!(with_host_prefix("8.8.8.8"), "8.8.8.8/32");
+        assert_eq!(with_host_prefix(" 2001:db8::1 "), "2001:db8::1/128");
+        assert_eq!(with_host_prefix("10.0.0.0/8"), "10.0.0.0/8");
+        assert_eq!(with_host_prefix("10.0.0.0/"), "10.0.0.0/");
+        assert_eq!(with_host_prefix("bogus"), "bogus");
+    }
+
     // -----------------------------------------------------------------------
     // validate_cidr
     // -----------------------------------------------------------------------
@@ -250,6 +429,89 @@ mod tests {
         assert!(matches!(err, IpCalcError::InvalidCidr(_)));
     }
 
+    // -----------------------------------------------------------------------
+    // Prefix parsing matrix (shared across families and entry points)
+    // -----------------------------------------------------------------------
+
+    /// (suffix appended to the base address, expected error substring or None for ok)
+    const PREFIX_MATRIX: &[(&str, Option<&str>)] = &[
+        ("/24", None),
+        ("/24\n", None),
+        ("/24 ", None),
+        ("/ 24", Some("contains whitespace")),
+        (" /24", Some("contains whitespace")),
+        ("/2 4", Some("contains whitespace")),
+        ("/+8", Some("non-numeric prefix length")),
+        ("/-8", Some("non-numeric prefix length")),
+        ("/abc", Some("non-numeric prefix length")),
+        ("/", Some("missing prefix length")),
+        ("", Some("missing prefix length")),
+        ("/300", Some("Invalid prefix length: 300")),
+        ("/99999999999", Some("Invalid prefix length: 4294967295")),
+        ("/129", Some("Invalid prefix length: 129")),
+    ];
+
+    fn assert_prefix_case<T>(input: &str, expected: Option<&str>, result: Result<T>) {
+        match (expected, result) {
+            (None, Ok(_)) => {}
+            (None, Err(e)) => panic!("{input:?}: expected ok, got {e}"),
+            (Some(msg), Ok(_)) => panic!("{input:?}: expected error containing {msg:?}"),
+            (Some(msg), Err(e)) => assert!(
+                e.to_string().contains(msg),
+                "{input:?}: expected {msg:?}, got {e}"
+            ),
+        }
+    }
+
+    #[test]
+    fn prefix_matrix_consistent_across_entry_points() {
+        use crate::batch::{BatchEntryResult, process_batch};
+        use crate::ipv4::Ipv4Subnet;
+        use crate::ipv6::Ipv6Subnet;
+
+        for base in ["192.168.1.0", "2001:db8::"] {
+            for (suffix, expected) in PREFIX_MATRIX {
+                let input = format!("{base}{suffix}");
+                assert_prefix_case(&input, *expected, validate_cidr(&input));
+
+                if base.contains(':') {
+                    assert_prefix_case(&input, *expected, Ipv6Subnet::from_cidr(&input));
+                } else {
+                    assert_prefix_case(&input, *expected, Ipv4Subnet::from_cidr(&input));
+                }
+
+                let batch = process_batch(std::slice::from_ref(&input)).unwrap();
+                let batch_result = match &batch.results[0].result {
+                    BatchEntryResult::Ok { .. } => Ok(()),
+                    BatchEntryResult::Err { error } => {
+                        Err(IpCalcError::InvalidInput(error.clone()))
+                    }
+                };
+                // Batch input takes a bare address as a single host
+                let expected = if suffix.is_empty() { None } else { *expected };
+                assert_prefix_case(&input, expected, batch_result);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_cidr_parts_splits_address_and_prefix() {
+        assert_eq!(parse_cidr_parts("10.0.0.0/8").unwrap(), ("10.0.0.0", 8));
+        assert_eq!(parse_cidr_parts("::/0").unwrap(), ("::", 0));
+    }
+
+    #[test]
+    fn parse_cidr_parts_reports_overflowing_prefix_as_out_of_range() {
+        assert!(matches!(
+            parse_cidr_parts("10.0.0.0/300"),
+            Err(IpCalcError::InvalidPrefixLength(300))
+        ));
+        assert!(matches!(
+            validate_cidr("2001:db8::/256"),
+            Err(IpCalcError::InvalidPrefixLength(256))
+        ));
+    }
+
     // -----------------------------------------------------------------------
     // validate_ip_address
     // -----------------------------------------------------------------------
@@ -286,35 +548,77 @@ mod tests {
     }
 
     // -----------------------------------------------------------------------
-    // validate_prefix_length
+    // validate_prefix
     // -----------------------------------------------------------------------
 
     #[test]
     fn prefix_v4_valid_range() {
         for p in 0..=32 {
-            assert!(validate_prefix_length(p, 4).is_ok());
+            assert!(validate_prefix(p, Family::V4).is_ok());
         }
     }
 
     #[test]
     fn prefix_v4_out_of_range() {
-        let err = validate_prefix_length(33, 4).unwrap_err();
+        let err = validate_prefix(33, Family::V4).unwrap_err();
         assert!(matches!(err, IpCalcError::InvalidPrefixLength(33)));
     }
 
     #[test]
     fn prefix_v6_valid_range() {
-        assert!(validate_prefix_length(0, 6).is_ok());
-        assert!(validate_prefix_length(64, 6).is_ok());
-        assert!(validate_prefix_length(128, 6).is_ok());
+        assert!(validate_prefix(0, Family::V6).is_ok());
+        assert!(validate_prefix(64, Family::V6).is_ok());
+        assert!(validate_prefix(128, Family::V6).is_ok());
     }
 
     #[test]
     fn prefix_v6_out_of_range() {
-        let err = validate_prefix_length(129, 6).unwrap_err();
+        let err = validate_prefix(129, Family::V6).unwrap_err();
         assert!(matches!(err, IpCalcError::InvalidPrefixLength(129)));
     }
 
+    #[test]
+    fn prefix_entry_points_agree() {
+        use crate::ipv4::Ipv4Subnet;
+        use crate::ipv6::Ipv6Subnet;
+        use crate::subnet_generator::{
+            count_subnets, generate_ipv4_subnets, generate_ipv6_subnets, iter_ipv4_subnets,
+            iter_ipv6_subnets,
+        };
+
+        let v4: Vec<Result<()>> = vec![
+            validate_cidr("10.0.0.0/33"),
+            Ipv4Subnet::new(Ipv4Addr::new(10, 0, 0, 0), 33).map(drop),
+            Ipv4Subnet::from_cidr("10.0.0.0/33").map(drop),
+            count_subnets("10.0.0.0/24", 33).map(drop),
+            iter_ipv4_subnets("10.0.0.0/24", 33, None).map(drop),
+            generate_ipv4_subnets("10.0.0.0/24", 33, None).map(drop),
+        ];
+        for result in v4 {
+            assert!(
+                matches!(result, Err(IpCalcError::InvalidPrefixLength(33))),
+                "expected InvalidPrefixLength(33), got {:?}",
+                result
+            );
+        }
+
+        let v6: Vec<Result<()>> = vec![
+            validate_cidr("2001:db8::/129"),
+            Ipv6Subnet::new(Ipv6Addr::LOCALHOST, 129).map(drop),
+            Ipv6Subnet::from_cidr("2001:db8::/129").map(drop),
+            count_subnets("2001:db8::/48", 129).map(drop),
+            iter_ipv6_subnets("2001:db8::/48", 129, None).map(drop),
+            generate_ipv6_subnets("2001:db8::/48", 129, None).map(drop),
+        ];
+        for result in v6 {
+            assert!(
+                matches!(result, Err(IpCalcError::InvalidPrefixLength(129))),
+                "expected InvalidPrefixLength(129), got {:?}",
+                result
+            );
+        }
+    }
+
     // -----------------------------------------------------------------------
     // validate_text_field
     // -----------------------------------------------------------------------
@@ -0,0 +1,265 @@
+//! Runtime self-checks for generated CIDR lists.
+//!
+//! These re-derive the invariants the generators promise, so a wrong result
+//! is reported instead of printed. A failure is an
+//! [`IpCalcError::VerificationFailed`], which means a bug rather than bad
+//! input. Fuzz targets can call them on every successful result.
+
+use crate::covers::{Block, format_block};
+use crate::error::{IpCalcError, Result};
+use crate::from_range::{Ipv4FromRangeResult, Ipv6FromRangeResult};
+use crate::subnet_generator::{Ipv4SubnetList, Ipv6SubnetList};
+use crate::summarize::prefix_mask;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// ---------------------------------------------------------------------------
+// Tiling check over (network, prefix) pairs
+// ---------------------------------------------------------------------------
+
+fn failed(message: String) -> IpCalcError {
+    IpCalcError::VerificationFailed(message)
+}
+
+/// Check that `blocks` cover `first..=last` exactly once: every block is
+/// aligned, and in address order each starts right after the previous one.
+fn check_tiling(mut blocks: Vec<Block>, first: u128, last: u128, bits: u8) -> Result<()> {
+    blocks.sort_unstable();
+    let width = if bits == 32 {
+        u32::MAX as u128
+    } else {
+        u128::MAX
+    };
+
+    // `None` once the block ending at the top of the address space is seen
+    let mut next = Some(first);
+    for &block in &blocks {
+        let host_bits = !prefix_mask(block.1, bits) & width;
+        if block.0 & host_bits != 0 {
+            return Err(failed(format!(
+                "{} is not aligned to its prefix",
+                format_block(block, bits)
+            )));
+        }
+        match next {
+            Some(expected) if block.0 > expected => {
+                return Err(failed(format!("gap before {}", format_block(block, bits))));
+            }
+            Some(expected) if block.0 < expected => {
+                return Err(failed(format!(
+                    "{} overlaps the block before it",
+                    format_block(block, bits)
+                )));
+            }
+            None => {
+                return Err(failed(format!(
+                    "{} lies past the end of the range",
+                    format_block(block, bits)
+                )));
+            }
+            Some(_) => {}
+        }
+        let block_last = block.0 | host_bits;
+        if block_last > last {
+            return Err(failed(format!(
+                "{} extends past the end of the range",
+                format_block(block, bits)
+            )));
+        }
+        next = block_last.checked_add(1).filter(|&n| n <= width);
+    }
+
+    match next {
+        Some(n) if n <= last => Err(failed(
+            "the blocks stop short of the end of the range".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_prefixes(blocks: &[Block], new_prefix: u8, bits: u8) -> Result<()> {
+    match blocks.iter().find(|&&(_, p)| p != new_prefix) {
+        Some(&block) => Err(failed(format!(
+            "{} is not a /{}",
+            format_block(block, bits),
+            new_prefix
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn parse_v4(addr: &str) -> Result<u128> {
+    addr.parse::<Ipv4Addr>()
+        .map(|a| u32::from(a) as u128)
+        .map_err(|_| IpCalcError::InvalidIpv4Address(addr.to_string()))
+}
+
+fn parse_v6(addr: &str) -> Result<u128> {
+    addr.parse::<Ipv6Addr>()
+        .map(u128::from)
+        .map_err(|_| IpCalcError::InvalidIpv6Address(addr.to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Check that a full (`--max`) split summarizes back to its supernet: every
+/// subnet has the new prefix, and together they tile the supernet with no
+/// gaps, overlaps, or duplicates. Shuffled lists are fine.
+pub fn verify_split_ipv4(list: &Ipv4SubnetList) -> Result<()> {
+    let blocks: Vec<Block> = list
+        .subnets
+        .iter()
+        .map(|s| (u32::from(s.network) as u128, s.prefix_length))
+        .collect();
+    check_prefixes(&blocks, list.new_prefix, 32)?;
+    let first = u32::from(list.supernet.network) as u128;
+    let last = u32::from(list.supernet.broadcast) as u128;
+    check_tiling(blocks, first, last, 32)
+}
+
+/// IPv6 counterpart of [`verify_split_ipv4`].
+pub fn verify_split_ipv6(list: &Ipv6SubnetList) -> Result<()> {
+    let blocks: Vec<Block> = list
+        .subnets
+        .iter()
+        .map(|s| (u128::from(s.network), s.prefix_length))
+        .collect();
+    check_prefixes(&blocks, list.new_prefix, 128)?;
+    let first = u128::from(list.supernet.network);
+    let last = first | !prefix_mask(list.supernet.prefix_length, 128);
+    check_tiling(blocks, first, last, 128)
+}
+
+/// Check that the CIDRs of a range conversion tile `start..=end` exactly.
+pub fn verify_from_range_ipv4(result: &Ipv4FromRangeResult) -> Result<()> {
+    let blocks: Vec<Block> = result
+        .cidrs
+        .iter()
+        .map(|s| (u32::from(s.network) as u128, s.prefix_length))
+        .collect();
+    let first = parse_v4(&result.start_address)?;
+    let last = parse_v4(&result.end_address)?;
+    check_tiling(blocks, first, last, 32)
+}
+
+/// IPv6 counterpart of [`verify_from_range_ipv4`].
+pub fn verify_from_range_ipv6(result: &Ipv6FromRangeResult) -> Result<()> {
+    let blocks: Vec<Block> = result
+        .cidrs
+        .iter()
+        .map(|s| (u128::from(s.network), s.prefix_length))
+        .collect();
+    let first = parse_v6(&result.start_address)?;
+    let last = parse_v6(&result.end_address)?;
+    check_tiling(blocks, first, last, 128)
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::from_range::{from_range_ipv4, from_range_ipv6};
+    use crate::ipv4::Ipv4Subnet;
+    use crate::ipv6::Ipv6Format;
+    use crate::ipv6::Ipv6Subnet;
+    use crate::subnet_generator::{generate_ipv4_subnets, generate_ipv6_subnets};
+
+    fn assert_fails<T>(result: Result<T>, reason: &str) {
+        match result {
+            Err(IpCalcError::VerificationFailed(message)) => {
+                assert!(message.contains(reason), "{message}")
+            }
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("verification passed; expected '{reason}'"),
+        }
+    }
+
+    #[test]
+    fn test_valid_results_pass() {
+        let mut list = generate_ipv4_subnets("10.0.0.0/24", 26, None).unwrap();
+        verify_split_ipv4(&list).unwrap();
+        list.shuffle(7);
+        verify_split_ipv4(&list).unwrap();
+        verify_split_ipv4(&generate_ipv4_subnets("0.0.0.0/0", 1, None).unwrap()).unwrap();
+        verify_split_ipv6(&generate_ipv6_subnets("2001:db8::/46", 48, None).unwrap()).unwrap();
+        verify_split_ipv6(&generate_ipv6_subnets("::/0", 1, None).unwrap()).unwrap();
+
+        verify_from_range_ipv4(&from_range_ipv4("10.0.0.5", "10.0.0.130").unwrap()).unwrap();
+        verify_from_range_ipv4(&from_range_ipv4("0.0.0.0", "255.255.255.255").unwrap()).unwrap();
+        verify_from_range_ipv6(
+            &from_range_ipv6("::1", "2001:db8::ff", Ipv6Format::default()).unwrap(),
+        )
+        .unwrap();
+        verify_from_range_ipv6(
+            &from_range_ipv6(
+                "::",
+                "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+                Ipv6Format::default(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_split_fails() {
+        let list = || generate_ipv4_subnets("10.0.0.0/24", 26, None).unwrap();
+
+        let mut missing = list();
+        missing.subnets.remove(1);
+        assert_fails(verify_split_ipv4(&missing), "gap before 10.0.0.128/26");
+
+        let mut truncated = list();
+        truncated.subnets.pop();
+        assert_fails(verify_split_ipv4(&truncated), "stop short");
+
+        let mut duplicated = list();
+        duplicated.subnets[1] = duplicated.subnets[0].clone();
+        assert_fails(verify_split_ipv4(&duplicated), "overlaps");
+
+        let mut resized = list();
+        resized.subnets[3] = Ipv4Subnet::from_cidr("10.0.0.192/27").unwrap();
+        assert_fails(verify_split_ipv4(&resized), "is not a /26");
+
+        let mut outside = list();
+        outside.subnets[3] = Ipv4Subnet::from_cidr("10.0.1.0/26").unwrap();
+        assert_fails(verify_split_ipv4(&outside), "gap before 10.0.1.0/26");
+
+        let mut v6 = generate_ipv6_subnets("2001:db8::/46", 48, None).unwrap();
+        v6.subnets[2] = Ipv6Subnet::from_cidr("2001:db8:1::/48").unwrap();
+        assert_fails(verify_split_ipv6(&v6), "overlaps");
+    }
+
+    #[test]
+    fn test_corrupted_range_fails() {
+        let result = from_range_ipv4("10.0.0.5", "10.0.0.130").unwrap();
+
+        let mut short = result.clone();
+        short.end_address = "10.0.0.200".to_string();
+        assert_fails(verify_from_range_ipv4(&short), "stop short");
+
+        let mut long = result.clone();
+        long.end_address = "10.0.0.129".to_string();
+        assert_fails(verify_from_range_ipv4(&long), "extends past the end");
+
+        let mut unaligned = result;
+        unaligned.cidrs[0].network = Ipv4Addr::new(10, 0, 0, 5);
+        unaligned.cidrs[0].prefix_length = 24;
+        assert_fails(verify_from_range_ipv4(&unaligned), "not aligned");
+
+        let mut empty =
+            from_range_ipv6("2001:db8::", "2001:db8::ff", Ipv6Format::default()).unwrap();
+        empty.cidrs.clear();
+        assert_fails(verify_from_range_ipv6(&empty), "stop short");
+    }
+
+    #[test]
+    fn test_verification_exit_code() {
+        assert_eq!(failed(String::new()).exit_code(), 70);
+        assert_eq!(IpCalcError::EmptyCidrList.exit_code(), 1);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::covers::{Block, exclude, format_block};
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::subnet_generator::{check_generation_limit, prefix_for_hosts};
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// One host requirement and the block chosen for it.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VlsmAllocation {
+    /// Host count as requested
+    pub hosts: u64,
+    pub prefix: u8,
+    pub subnet: Ipv4Subnet,
+    /// Usable hosts of the block beyond the requested count
+    pub waste: u64,
+    /// Addresses of the supernet still unallocated after this block
+    pub remaining_addresses: u64,
+}
+
+/// A variable-length subnet plan, largest requirement first.
+///
+/// Blocks are packed from the start of the supernet; `free` is the space
+/// left after the last allocation, as the fewest aligned CIDRs.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VlsmResult {
+    pub supernet: String,
+    pub total_addresses: u64,
+    pub allocated_addresses: u64,
+    pub remaining_addresses: u64,
+    pub allocations: Vec<VlsmAllocation>,
+    pub free: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Public entry point
+// ---------------------------------------------------------------------------
+
+/// Carve `supernet` into one subnet per host requirement.
+///
+/// Requirements are sorted largest-first and each gets the tightest block
+/// that holds it, counting usable hosts as [`prefix_for_hosts`] does: a /31
+/// holds `p2p_usable` hosts, and the network and broadcast addresses of
+/// shorter prefixes are not usable. Waste is reported on the same basis. Packing power-of-two blocks in descending size keeps every
+/// block aligned, so the plan fits exactly when the block sizes sum to no
+/// more than the supernet.
+pub fn allocate_vlsm(supernet: &str, requirements: &[u64], p2p_usable: u64) -> Result<VlsmResult> {
+    if requirements.is_empty() {
+        return Err(IpCalcError::InvalidInput(
+            "at least one host requirement is required".to_string(),
+        ));
+    }
+    if requirements.contains(&0) {
+        return Err(IpCalcError::InvalidInput(
+            "host requirements must be at least 1".to_string(),
+        ));
+    }
+    check_generation_limit(requirements.len() as u64)?;
+
+    let s = Ipv4Subnet::from_cidr(supernet)?;
+    let total = s.total_hosts;
+
+    let mut sorted = requirements.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut planned = Vec::with_capacity(sorted.len());
+    let mut required: u64 = 0;
+    for &hosts in &sorted {
+        // A requirement no IPv4 block holds counts as one past the whole space
+        let (prefix, size) = match prefix_for_hosts(hosts, false, p2p_usable) {
+            Ok(prefix) => (prefix, 1u64 << (32 - prefix)),
+            Err(_) => (0, (1u64 << 32) + 1),
+        };
+        required = required.saturating_add(size);
+        planned.push((hosts, prefix, size));
+    }
+    if required > total {
+        return Err(IpCalcError::VlsmExhausted {
+            supernet: format!("{}/{}", s.network, s.prefix_length),
+            required,
+            available: total,
+        });
+    }
+
+    let start = u32::from(s.network) as u128;
+    let mut offset: u64 = 0;
+    let mut allocations = Vec::with_capacity(planned.len());
+    let mut used: Vec<Block> = Vec::with_capacity(planned.len());
+    for (hosts, prefix, size) in planned {
+        let block = (start + offset as u128, prefix);
+        offset += size;
+        used.push(block);
+        let mut subnet = Ipv4Subnet::from_cidr(&format_block(block, 32))?;
+        subnet.apply_p2p_usable(p2p_usable);
+        allocations.push(VlsmAllocation {
+            hosts,
+            prefix,
+            waste: subnet.usable_hosts.saturating_sub(hosts),
+            subnet,
+            remaining_addresses: total - offset,
+        });
+    }
+
+    let mut free = Vec::new();
+    exclude((start, s.prefix_length), &used, 32, &mut free);
+
+    Ok(VlsmResult {
+        supernet: format!("{}/{}", s.network, s.prefix_length),
+        total_addresses: total,
+        allocated_addresses: offset,
+        remaining_addresses: total - offset,
+        allocations,
+        free: free.into_iter().map(|b| format_block(b, 32)).collect(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidrs(result: &VlsmResult) -> Vec<String> {
+        result
+            .allocations
+            .iter()
+            .map(|a| format!("{}/{}", a.subnet.network, a.prefix))
+            .collect()
+    }
+
+    fn allocate_vlsm_rfc3021(supernet: &str, requirements: &[u64]) -> Result<VlsmResult> {
+        allocate_vlsm(supernet, requirements, 2)
+    }
+
+    #[test]
+    fn test_classic_plan() {
+        let result = allocate_vlsm_rfc3021("10.0.0.0/24", &[100, 50, 20, 10]).unwrap();
+        assert_eq!(
+            cidrs(&result),
+            [
+                "10.0.0.0/25",
+                "10.0.0.128/26",
+                "10.0.0.192/27",
+                "10.0.0.224/28"
+            ]
+        );
+        assert_eq!(result.allocations[0].hosts, 100);
+        assert_eq!(result.allocations[0].waste, 26);
+        assert_eq!(result.allocations[3].waste, 4);
+        assert_eq!(result.allocations[0].remaining_addresses, 128);
+        assert_eq!(result.allocated_addresses, 240);
+        assert_eq!(result.remaining_addresses, 16);
+        assert_eq!(result.free, ["10.0.0.240/28"]);
+    }
+
+    #[test]
+    fn test_unsorted_requirements() {
+        let result = allocate_vlsm_rfc3021("192.168.0.0/23", &[2, 254, 1, 60]).unwrap();
+        assert_eq!(
+            cidrs(&result),
+            [
+                "192.168.0.0/24",
+                "192.168.1.0/26",
+                "192.168.1.64/31",
+                "192.168.1.66/32"
+            ]
+        );
+        assert_eq!(result.allocations[2].hosts, 2);
+    }
+
+    #[test]
+    fn test_exact_fit_and_boundaries() {
+        // 126 usable hosts needs a full /25, and 127 spills into a /24
+        let result = allocate_vlsm_rfc3021("10.0.0.0/24", &[126, 126]).unwrap();
+        assert_eq!(result.remaining_addresses, 0);
+        assert_eq!(result.allocations[1].waste, 0);
+        assert!(result.free.is_empty());
+        let result = allocate_vlsm_rfc3021("10.0.0.0/24", &[127]).unwrap();
+        assert_eq!(result.allocations[0].prefix, 24);
+    }
+
+    #[test]
+    fn test_one_and_two_hosts_follow_p2p_usable() {
+        // RFC 3021: a /31 holds two hosts, a /32 one
+        let result = allocate_vlsm("10.0.0.0/29", &[2, 1], 2).unwrap();
+        assert_eq!(cidrs(&result), ["10.0.0.0/31", "10.0.0.2/32"]);
+        assert_eq!(result.allocations[0].subnet.usable_hosts, 2);
+        assert_eq!(result.allocations[0].waste, 0);
+
+        // Without it a /31 holds none, so two hosts need a /30
+        let result = allocate_vlsm("10.0.0.0/29", &[2, 1], 0).unwrap();
+        assert_eq!(cidrs(&result), ["10.0.0.0/30", "10.0.0.4/32"]);
+        assert_eq!(result.allocations[0].waste, 0);
+        assert_eq!(result.free, ["10.0.0.5/32", "10.0.0.6/31"]);
+
+        let result = allocate_vlsm("10.0.0.0/29", &[1, 1, 1], 0).unwrap();
+        assert!(result.allocations.iter().all(|a| a.prefix == 32));
+
+        // Matches the prefix `split --hosts` picks for the same count
+        for p2p_usable in [0, 2] {
+            for hosts in [1, 2, 3] {
+                let result = allocate_vlsm("10.0.0.0/24", &[hosts], p2p_usable).unwrap();
+                assert_eq!(
+                    result.allocations[0].prefix,
+                    prefix_for_hosts(hosts, false, p2p_usable).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_blocks_aligned_and_disjoint() {
+        let result = allocate_vlsm_rfc3021("10.0.0.0/22", &[10, 500, 50, 200, 3, 100]).unwrap();
+        let hosts: Vec<u64> = result.allocations.iter().map(|a| a.hosts).collect();
+        assert_eq!(hosts, [500, 200, 100, 50, 10, 3]);
+
+        let mut next = u32::from(result.allocations[0].subnet.network);
+        for a in &result.allocations {
+            let network = u32::from(a.subnet.network);
+            let size = 1u32 << (32 - a.prefix);
+            assert_eq!(network % size, 0, "{} is not aligned", network);
+            assert_eq!(network, next, "blocks must be packed without overlap");
+            next = network + size;
+        }
+        assert_eq!(result.free, ["10.0.3.216/29", "10.0.3.224/27"]);
+    }
+
+    #[test]
+    fn test_does_not_fit() {
+        assert!(matches!(
+            allocate_vlsm_rfc3021("10.0.0.0/24", &[127, 100]),
+            Err(IpCalcError::VlsmExhausted {
+                required: 384,
+                available: 256,
+                ..
+            })
+        ));
+        assert!(allocate_vlsm_rfc3021("0.0.0.0/0", &[u64::MAX]).is_err());
+        assert!(allocate_vlsm_rfc3021("10.0.0.0/24", &[]).is_err());
+        assert!(allocate_vlsm_rfc3021("10.0.0.0/24", &[10, 0]).is_err());
+        assert!(allocate_vlsm_rfc3021("2001:db8::/48", &[10]).is_err());
+    }
+}
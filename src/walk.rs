@@ -0,0 +1,129 @@
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::subnet_generator::check_generation_limit;
+use serde::Serialize;
+use std::net::Ipv4Addr;
+
+// ---------------------------------------------------------------------------
+// Result struct
+// ---------------------------------------------------------------------------
+
+/// Every block of one size from `start` to `end`, both included.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Ipv4WalkResult {
+    pub start: String,
+    pub end: String,
+    pub prefix_length: u8,
+    pub count: usize,
+    pub subnets: Vec<Ipv4Subnet>,
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// The same-size subnets from `start` to `end` inclusive, in order.
+///
+/// Both CIDRs must share a prefix length and `start` must not come after
+/// `end`. Host bits are cleared first, and the count is capped by the
+/// subnet generation limit.
+pub fn walk_v4(start: &str, end: &str) -> Result<Vec<Ipv4Subnet>> {
+    let first = Ipv4Subnet::from_cidr(start)?;
+    let last = Ipv4Subnet::from_cidr(end)?;
+    if first.prefix_length != last.prefix_length {
+        return Err(IpCalcError::InvalidInput(format!(
+            "{}/{} and {}/{} have different prefix lengths",
+            first.network, first.prefix_length, last.network, last.prefix_length
+        )));
+    }
+    if first.network > last.network {
+        return Err(IpCalcError::InvalidInput(format!(
+            "{}/{} comes after {}/{}",
+            first.network, first.prefix_length, last.network, last.prefix_length
+        )));
+    }
+
+    let shift = 32 - u32::from(first.prefix_length);
+    let (from, to) = (u32::from(first.network), u32::from(last.network));
+    let count = u64::from(to - from).checked_shr(shift).unwrap_or(0) + 1;
+    check_generation_limit(count)?;
+
+    (0..count)
+        .map(|i| {
+            let network = u64::from(from) + (i << shift);
+            Ipv4Subnet::new(Ipv4Addr::from(network as u32), first.prefix_length)
+        })
+        .collect()
+}
+
+/// [`walk_v4`] with the endpoints and count it walked.
+pub fn walk_ipv4(start: &str, end: &str) -> Result<Ipv4WalkResult> {
+    let subnets = walk_v4(start, end)?;
+    let (first, last) = (&subnets[0], &subnets[subnets.len() - 1]);
+    Ok(Ipv4WalkResult {
+        start: format!("{}/{}", first.network, first.prefix_length),
+        end: format!("{}/{}", last.network, last.prefix_length),
+        prefix_length: first.prefix_length,
+        count: subnets.len(),
+        subnets,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn networks(subnets: &[Ipv4Subnet]) -> Vec<String> {
+        subnets
+            .iter()
+            .map(|s| format!("{}/{}", s.network, s.prefix_length))
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_inclusive() {
+        let subnets = walk_v4("10.0.0.0/24", "10.0.5.0/24").unwrap();
+        assert_eq!(subnets.len(), 6);
+        assert_eq!(networks(&subnets)[0], "10.0.0.0/24");
+        assert_eq!(networks(&subnets)[5], "10.0.5.0/24");
+
+        // Host bits are cleared, and a single block walks to itself
+        let subnets = walk_v4("10.0.0.77/30", "10.0.0.77/30").unwrap();
+        assert_eq!(networks(&subnets), ["10.0.0.76/30"]);
+    }
+
+    #[test]
+    fn test_walk_edges_of_space() {
+        let subnets = walk_v4("255.255.255.252/32", "255.255.255.255/32").unwrap();
+        assert_eq!(subnets.len(), 4);
+        let subnets = walk_v4("0.0.0.0/0", "0.0.0.0/0").unwrap();
+        assert_eq!(subnets.len(), 1);
+    }
+
+    #[test]
+    fn test_walk_errors() {
+        let err = walk_v4("10.0.0.0/24", "10.0.5.0/25").unwrap_err();
+        assert!(err.to_string().contains("different prefix lengths"));
+        let err = walk_v4("10.0.5.0/24", "10.0.0.0/24").unwrap_err();
+        assert!(err.to_string().contains("comes after"));
+        assert!(matches!(
+            walk_v4("0.0.0.0/32", "255.255.255.255/32"),
+            Err(IpCalcError::SubnetLimitExceeded { .. })
+        ));
+        assert!(walk_v4("2001:db8::/48", "2001:db8:1::/48").is_err());
+    }
+
+    #[test]
+    fn test_walk_result() {
+        let result = walk_ipv4("192.168.0.9/29", "192.168.0.40/29").unwrap();
+        assert_eq!(result.start, "192.168.0.8/29");
+        assert_eq!(result.end, "192.168.0.40/29");
+        assert_eq!(result.count, 5);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::config::DEFAULTS;
+use crate::error::{IpCalcError, Result};
+use crate::ipv4::Ipv4Subnet;
+use crate::validation;
+use serde::Serialize;
+use std::net::Ipv4Addr;
+
+// ---------------------------------------------------------------------------
+// Result structs
+// ---------------------------------------------------------------------------
+
+/// A single PTR record, named relative to its zone's `$ORIGIN`.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PtrRecord {
+    pub name: String,
+    pub address: String,
+    pub ptr: String,
+}
+
+/// One `in-addr.arpa` zone and the PTR records it holds.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReverseZone {
+    pub origin: String,
+    pub records: Vec<PtrRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "swagger", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ZoneFileResult {
+    pub cidr: String,
+    pub split_24: bool,
+    pub zone_count: usize,
+    pub record_count: usize,
+    pub zones: Vec<ReverseZone>,
+}
+
+pub const DEFAULT_ZONE_DOMAIN: &str = "example.com";
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Reverse `in-addr.arpa` name for the first `octets` octets of `addr`.
+fn reverse_origin(addr: Ipv4Addr, octets: usize) -> String {
+    let mut labels: Vec<String> = addr.octets()[..octets]
+        .iter()
+        .rev()
+        .map(|o| o.to_string())
+        .collect();
+    labels.push("in-addr.arpa.".to_string());
+    labels.join(".")
+}
+
+/// Record name relative to an origin covering the first `octets` octets.
+fn relative_name(addr: Ipv4Addr, octets: usize) -> String {
+    let labels: Vec<String> = addr.octets()[octets..]
+        .iter()
+        .rev()
+        .map(|o| o.to_string())
+        .collect();
+    labels.join(".")
+}
+
+fn ptr_target(addr: Ipv4Addr, domain: &str) -> String {
+    let o = addr.octets();
+    format!("host-{}-{}-{}-{}.{}.", o[0], o[1], o[2], o[3], domain)
+}
+
+fn build_zone(origin_addr: Ipv4Addr, octets: usize, hosts: &[u32], domain: &str) -> ReverseZone {
+    ReverseZone {
+        origin: reverse_origin(origin_addr, octets),
+        records: hosts
+            .iter()
+            .map(|&h| {
+                let addr = Ipv4Addr::from(h);
+                PtrRecord {
+                    name: relative_name(addr, octets),
+                    address: addr.to_string(),
+                    ptr: ptr_target(addr, domain),
+                }
+            })
+            .collect(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Public entry points
+// ---------------------------------------------------------------------------
+
+/// Generate reverse DNS zone data for the usable hosts of an IPv4 CIDR.
+///
+/// Without `split_24`, a single zone is emitted whose origin is the covering
+/// octet boundary. With `split_24`, one zone is emitted per /24, matching how
+/// `in-addr.arpa` delegation is done in practice.
+pub fn generate_zonefile(cidr: &str, split_24: bool, domain: &str) -> Result<ZoneFileResult> {
+    if cidr.contains(':') {
+        return Err(IpCalcError::InvalidInput(
+            "zonefile generation supports IPv4 only".to_string(),
+        ));
+    }
+    validation::validate_identifier(domain)?;
+
+    let subnet = Ipv4Subnet::from_cidr(cidr)?;
+    if subnet.usable_hosts > DEFAULTS.max_generated_subnets {
+        return Err(IpCalcError::RecordLimitExceeded {
+            count: subnet.usable_hosts,
+            limit: DEFAULTS.max_generated_subnets,
+        });
+    }
+
+    let first = u32::from(subnet.first_host);
+    let last = u32::from(subnet.last_host);
+    let hosts: Vec<u32> = (first..=last).collect();
+
+    let zones = if split_24 {
+        hosts
+            .chunk_by(|a, b| a >> 8 == b >> 8)
+            .map(|chunk| build_zone(Ipv4Addr::from(chunk[0]), 3, chunk, domain))
+            .collect()
+    } else {
+        let octets = (subnet.prefix_length / 8) as usize;
+        vec![build_zone(subnet.network, octets, &hosts, domain)]
+    };
+
+    Ok(ZoneFileResult {
+        cidr: format!("{}/{}", subnet.network, subnet.prefix_length),
+        split_24,
+        zone_count: zones.len(),
+        record_count: hosts.len(),
+        zones,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Unit tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::TextOutput;
+
+    #[test]
+    fn test_single_zone_24() {
+        let result = generate_zonefile("192.0.2.0/24", false, DEFAULT_ZONE_DOMAIN).unwrap();
+        assert_eq!(result.zone_count, 1);
+        assert_eq!(result.record_count, 254);
+        let zone = &result.zones[0];
+        assert_eq!(zone.origin, "2.0.192.in-addr.arpa.");
+        assert_eq!(zone.records[0].name, "1");
+        assert_eq!(zone.records[0].ptr, "host-192-0-2-1.example.com.");
+    }
+
+    #[test]
+    fn test_single_zone_16_uses_two_label_names() {
+        let result = generate_zonefile("10.1.0.0/16", false, DEFAULT_ZONE_DOMAIN).unwrap();
+        assert_eq!(result.zone_count, 1);
+        assert_eq!(result.zones[0].origin, "1.10.in-addr.arpa.");
+        assert_eq!(result.zones[0].records[0].name, "1.0");
+    }
+
+    #[test]
+    fn test_split_24_for_16() {
+        let result = generate_zonefile("10.1.0.0/16", true, DEFAULT_ZONE_DOMAIN).unwrap();
+        assert_eq!(result.zone_count, 256);
+        assert_eq!(result.zones[0].origin, "0.1.10.in-addr.arpa.");
+        assert_eq!(result.zones[255].origin, "255.1.10.in-addr.arpa.");
+        assert_eq!(result.zones[1].records[0].name, "0");
+
+        let text = result.to_text();
+        let origins = text.lines().filter(|l| l.starts_with("$ORIGIN")).count();
+        assert_eq!(origins, 256);
+    }
+
+    #[test]
+    fn test_ipv6_rejected() {
+        let result = generate_zonefile("2001:db8::/48", false, DEFAULT_ZONE_DOMAIN);
+        assert!(
+            matches!(result, Err(IpCalcError::InvalidInput(_))),
+            "expected InvalidInput, got {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_record_limit_exceeded() {
+        let result = generate_zonefile("10.0.0.0/8", true, DEFAULT_ZONE_DOMAIN);
+        assert!(
+            matches!(result, Err(IpCalcError::RecordLimitExceeded { .. })),
+            "expected RecordLimitExceeded, got {:?}",
+            result
+        );
+    }
+}
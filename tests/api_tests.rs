@@ -74,6 +74,50 @@ async fn test_version() {
     assert!(json["version"].is_string());
 }
 
+#[tokio::test]
+async fn test_limits_defaults() {
+    let (status, body) = get("/limits").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["max_batch_size"], 10_000);
+    assert_eq!(json["max_generated_subnets"], 1_000_000);
+}
+
+#[tokio::test]
+async fn test_limits_reflect_router_config() {
+    use ipcalc::config::ServerConfig;
+    let config = RouterConfig {
+        server: ServerConfig {
+            max_batch_size: 25,
+            max_body_size: 4096,
+            max_summarize_inputs: 50,
+            max_generated_cidrs: 75,
+            timeout_seconds: 5,
+            rate_limit_per_second: 3,
+            rate_limit_burst: 6,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let app = create_router(config);
+    let req = Request::builder()
+        .uri("/limits")
+        .body(Body::empty())
+        .unwrap();
+    let resp: Response = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["max_batch_size"], 25);
+    assert_eq!(json["max_body_size"], 4096);
+    assert_eq!(json["max_summarize_inputs"], 50);
+    assert_eq!(json["max_generated_cidrs"], 75);
+    assert_eq!(json["timeout_seconds"], 5);
+    // Configured but not enforced, so not advertised
+    assert!(json.get("rate_limit_per_second").is_none());
+    assert!(json.get("rate_limit_burst").is_none());
+}
+
 // ── IPv4 ────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -84,6 +128,10 @@ async fn test_v4_valid() {
     assert_eq!(json["network_address"], "192.168.1.0");
     assert_eq!(json["broadcast_address"], "192.168.1.255");
     assert_eq!(json["prefix_length"], 24);
+    let binary = json["binary_address"].as_str().unwrap();
+    assert_eq!(binary.len(), 35);
+    assert_eq!(binary, "11000000.10101000.00000001.00000000");
+    assert_eq!(json["binary_mask"], "11111111.11111111.11111111.00000000");
 }
 
 #[tokio::test]
@@ -94,6 +142,46 @@ async fn test_v4_invalid() {
     assert!(json["error"].is_string());
 }
 
+#[tokio::test]
+async fn test_prefix_parsing_matrix() {
+    // (url-encoded suffix, raw suffix, expected error substring or None for ok)
+    let cases: &[(&str, &str, Option<&str>)] = &[
+        ("/24", "/24", None),
+        ("/24%0A", "/24\n", None),
+        ("/%2024", "/ 24", Some("contains whitespace")),
+        ("/%2B8", "/+8", Some("non-numeric prefix length")),
+        ("/-8", "/-8", Some("non-numeric prefix length")),
+        ("/", "/", Some("missing prefix length")),
+        ("/300", "/300", Some("Invalid prefix length: 300")),
+    ];
+
+    for (version, base) in [("v4", "192.168.1.0"), ("v6", "2001:db8::")] {
+        for (encoded, raw, expected) in cases {
+            let (status, body) = get(&format!("/{version}?cidr={base}{encoded}")).await;
+            let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+            match expected {
+                None => assert_eq!(status, 200, "{base}{raw}: {body}"),
+                Some(msg) => {
+                    assert_eq!(status, 400, "{base}{raw}: {body}");
+                    assert!(json["error"].as_str().unwrap().contains(msg), "{body}");
+                }
+            }
+
+            let payload = serde_json::json!({ "cidrs": [format!("{base}{raw}")] });
+            let (status, body) = post_json("/batch", &payload.to_string()).await;
+            assert_eq!(status, 200);
+            let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+            match expected {
+                None => assert!(json["results"][0]["subnet"].is_object(), "{body}"),
+                Some(msg) => assert!(
+                    json["results"][0]["error"].as_str().unwrap().contains(msg),
+                    "{body}"
+                ),
+            }
+        }
+    }
+}
+
 // ── IPv6 ────────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -123,6 +211,75 @@ async fn test_v4_split() {
     assert_eq!(json["subnets"].as_array().unwrap().len(), 5);
 }
 
+#[tokio::test]
+async fn test_split_offset() {
+    let (status, body) = get("/v4/split?cidr=10.0.0.0/8&prefix=24&offset=5000&count=100").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["offset"], 5000);
+    assert_eq!(json["total_available"], "65536");
+    assert_eq!(json["subnets"][0]["network_address"], "10.19.136.0");
+
+    let (status, body) = get("/v6/split?cidr=2001:db8::/48&prefix=64&offset=65535&max=true").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["subnets"].as_array().unwrap().len(), 1);
+    assert_eq!(json["subnets"][0]["network_address"], "2001:db8:0:ffff::");
+
+    let (status, body) = get("/v4/split?cidr=10.0.0.0/24&prefix=26&offset=4&max=true").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("past the last"));
+}
+
+#[tokio::test]
+async fn test_split_limit() {
+    // 32 /27s in a /22: the page from 30 holds only subnets 31 and 32
+    let (status, body) = get("/v4/split?cidr=192.168.0.0/22&prefix=27&offset=30&limit=5").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["total_available"], "32");
+    assert_eq!(json["returned"], 2);
+    let subnets = json["subnets"].as_array().unwrap();
+    assert_eq!(subnets.len(), 2);
+    assert_eq!(subnets[0]["network_address"], "192.168.3.192");
+    assert_eq!(subnets[1]["network_address"], "192.168.3.224");
+
+    let (status, body) = get("/v4/split?cidr=192.168.0.0/22&prefix=27&limit=5").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["returned"], 5);
+
+    let (status, body) = get("/v4/split?cidr=192.168.0.0/22&prefix=27&limit=5&count=5").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("mutually exclusive"));
+
+    let (status, body) = get(&format!(
+        "/v6/split?cidr=2001:db8::/32&prefix=96&offset={}&limit=1",
+        u64::MAX
+    ))
+    .await;
+    assert_eq!(status, 400);
+    assert!(body.contains("largest supported index"));
+}
+
+#[tokio::test]
+async fn test_split_index() {
+    let (status, body) = get("/v4/split?cidr=10.0.0.0/16&prefix=27&index=1000").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network_address"], "10.0.125.0");
+    assert_eq!(json["prefix_length"], 27);
+
+    let (status, body) = get("/v6/split?cidr=2001:db8::/32&prefix=48&index=65535").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network_address"], "2001:db8:ffff::");
+
+    let (status, body) = get("/v4/split?cidr=10.0.0.0/16&prefix=27&index=2048").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("out of range"));
+}
+
 #[tokio::test]
 async fn test_v4_split_max() {
     let (status, body) = get("/v4/split?cidr=192.168.0.0/24&prefix=26&max=true").await;
@@ -158,6 +315,8 @@ async fn test_v4_contains_true() {
     assert_eq!(status, 200);
     let json: serde_json::Value = serde_json::from_str(&body).unwrap();
     assert_eq!(json["contained"], true);
+    assert!(json.get("translation").is_none());
+    assert!(json.get("translated_address").is_none());
 }
 
 #[tokio::test]
@@ -168,6 +327,17 @@ async fn test_v4_contains_false() {
     assert_eq!(json["contained"], false);
 }
 
+#[tokio::test]
+async fn test_v4_contains_range() {
+    let (status, body) =
+        get("/v4/contains?cidr=192.168.1.0/24&address=192.168.1.250-192.168.2.9").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["contained"], false);
+    assert_eq!(json["partial"], true);
+    assert_eq!(json["contained_fraction"], 0.375);
+}
+
 #[tokio::test]
 async fn test_v4_contains_invalid() {
     let (status, body) = get("/v4/contains?cidr=192.168.1.0/24&address=bad").await;
@@ -178,6 +348,27 @@ async fn test_v4_contains_invalid() {
 
 // ── IPv6 Contains ───────────────────────────────────────────────────
 
+#[tokio::test]
+async fn test_v4_contains_family_mismatch() {
+    let (status, body) = get("/v4/contains?cidr=10.0.0.0/8&address=::ffff:10.0.0.5").await;
+    assert_eq!(status, 400);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let error = json["error"].as_str().unwrap();
+    assert!(error.contains("family mismatch"));
+    assert!(!error.contains("--translate"));
+}
+
+#[tokio::test]
+async fn test_v4_contains_translate() {
+    let (status, body) =
+        get("/v4/contains?cidr=10.0.0.0/8&address=::ffff:10.0.0.5&translate=true").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["contained"], true);
+    assert_eq!(json["translation"], "ipv4-mapped");
+    assert_eq!(json["translated_address"], "10.0.0.5");
+}
+
 #[tokio::test]
 async fn test_v6_contains() {
     let (status, body) = get("/v6/contains?cidr=2001:db8::/32&address=2001:db8::1").await;
@@ -186,6 +377,23 @@ async fn test_v6_contains() {
     assert_eq!(json["contained"], true);
 }
 
+#[tokio::test]
+async fn test_subnet_contains() {
+    let (status, body) = get("/v4/subnet-contains?outer=10.0.0.0/8&inner=10.1.0.0/16").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["fully_contained"], true);
+    assert_eq!(json["relationship"], "contains");
+
+    let (status, body) = get("/v6/subnet-contains?outer=2001:db8:1::/48&inner=2001:db8::/32").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["relationship"], "contained_by");
+
+    let (status, _) = get("/v4/subnet-contains?outer=10.0.0.0/8&inner=2001:db8::/32").await;
+    assert_eq!(status, 400);
+}
+
 // ── Pretty Output ───────────────────────────────────────────────────
 
 // ── Split Count Only ────────────────────────────────────────────────
@@ -258,6 +466,99 @@ async fn test_batch_mixed() {
     assert_eq!(json["results"][1]["subnet"]["version"], "v6");
 }
 
+#[tokio::test]
+async fn test_batch_filter() {
+    let cidrs = r#""cidrs":["192.168.1.0/24","bogus","2001:db8::/32"]"#;
+    let (status, body) = post_json("/batch", &format!(r#"{{{cidrs},"filter":"errors"}}"#)).await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 3);
+    assert_eq!(json["shown_count"], 1);
+    assert_eq!(json["results"][0]["cidr"], "bogus");
+
+    let (_, body) = post_json("/batch", &format!(r#"{{{cidrs},"filter":"ok"}}"#)).await;
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["shown_count"], 2);
+
+    let (_, body) = post_json("/batch", &format!("{{{cidrs}}}")).await;
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(json.get("shown_count").is_none());
+    assert_eq!(json["results"].as_array().unwrap().len(), 3);
+
+    let (status, _) = post_json("/batch", &format!(r#"{{{cidrs},"filter":"all"}}"#)).await;
+    assert_ne!(status, 200);
+}
+
+#[tokio::test]
+async fn test_batch_ignore() {
+    let body = r#"{"cidrs":["192.168.1.0/24","10.2.0.0/16","8.8.8.0/24"],"ignore":["10.0.0.0/8"],"ignore_special":true}"#;
+    let (status, body) = post_json("/batch", body).await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 1);
+    assert_eq!(json["ignored_count"], 2);
+    assert_eq!(json["results"][0]["cidr"], "8.8.8.0/24");
+}
+
+#[tokio::test]
+async fn test_json_case_camel() {
+    let (status, body) = get("/v4?cidr=192.168.1.0/24&case=camel").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["networkAddress"], "192.168.1.0");
+    assert_eq!(json["usableHosts"], 254);
+    assert!(json.get("network_address").is_none());
+
+    // Pretty output stays pretty, and tag keys are left alone
+    let (status, body) = post_json(
+        "/batch?case=camel&pretty=true",
+        r#"{"cidrs":["10.0.0.0/24 # env_name=prod"],"pretty":true}"#,
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert!(body.contains('\n'));
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["cacheHits"], 0);
+    assert_eq!(json["results"][0]["tags"]["env_name"], "prod");
+    assert_eq!(json["results"][0]["subnet"]["networkAddress"], "10.0.0.0");
+
+    let (status, _) = get("/v4?cidr=192.168.1.0/24&case=kebab").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_batch_v6_case_upper() {
+    let (status, body) = post_json(
+        "/batch",
+        r#"{"cidrs":["192.168.1.0/24","2001:db8:abcd::/48"],"v6_case":"upper"}"#,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(json["results"][0]["subnet"].get("case").is_none());
+    assert_eq!(json["results"][1]["subnet"]["hextets"][2], "ABCD");
+
+    let (status, body) = post_json(
+        "/batch",
+        r#"{"cidrs":["10.0.0.0/24","2001:db8:a::/48","2001:db8:b::/48"],"v6_case":"upper","with_summary":true}"#,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["aggregate_summary"]["ipv4"][0], "10.0.0.0/24");
+    assert_eq!(json["aggregate_summary"]["ipv6"][0], "2001:DB8:A::/47");
+
+    let (status, body) = get("/v6?cidr=2001:db8:abcd::/48&v6_case=upper").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network_address"], "2001:DB8:ABCD::");
+    assert_eq!(
+        json["last_address"],
+        "2001:DB8:ABCD:FFFF:FFFF:FFFF:FFFF:FFFF"
+    );
+    assert!(json.get("network_address_styled").is_none());
+}
+
 #[tokio::test]
 async fn test_batch_with_invalid() {
     let (status, body) = post_json(
@@ -273,6 +574,27 @@ async fn test_batch_with_invalid() {
     assert!(json["results"][2]["subnet"].is_object());
 }
 
+#[tokio::test]
+async fn test_addresses_overlapping_union() {
+    let (status, body) = post_json(
+        "/addresses",
+        r#"{"cidrs":["10.0.0.0/24","10.0.0.128/25","192.168.0.0/24"]}"#,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["address_count"], "512");
+    assert_eq!(json["blocks"][0], "10.0.0.0/24");
+}
+
+#[tokio::test]
+async fn test_addresses_invalid() {
+    let (status, body) = post_json("/addresses", r#"{"cidrs":["bogus"]}"#).await;
+    assert_eq!(status, 400);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(json["error"].is_string());
+}
+
 #[tokio::test]
 async fn test_batch_empty() {
     let (status, body) = post_json("/batch", r#"{"cidrs":[]}"#).await;
@@ -322,6 +644,44 @@ async fn test_v4_split_csv_format() {
     assert_eq!(data_lines.len(), 5);
 }
 
+#[tokio::test]
+async fn test_split_csv_streams_rows() {
+    let app = create_router(RouterConfig::default());
+    let req = Request::builder()
+        .uri("/v4/split?cidr=10.0.0.0/12&prefix=28&max=true&format=csv")
+        .body(Body::empty())
+        .unwrap();
+    let resp: Response = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "text/csv");
+    assert!(resp.headers().get(header::CONTENT_LENGTH).is_none());
+
+    // Count rows frame by frame; the full body is never held
+    let mut body = resp.into_body();
+    let (mut frames, mut lines, mut comments) = (0, 0, 0);
+    let mut at_line_start = true;
+    while let Some(frame) = body.frame().await {
+        let data = frame.unwrap().into_data().unwrap();
+        frames += 1;
+        for &b in data.iter() {
+            if at_line_start && b == b'#' {
+                comments += 1;
+            }
+            at_line_start = b == b'\n';
+            lines += usize::from(at_line_start);
+        }
+    }
+    assert!(frames > 1);
+    assert_eq!(comments, 3);
+    // comments + header + 65536 subnets
+    assert_eq!(lines - comments - 1, 65536);
+
+    // The generation cap still applies, as a JSON error before any row
+    let (status, body) = get("/v4/split?cidr=10.0.0.0/8&prefix=30&max=true&format=csv").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("\"error\""));
+}
+
 // ── YAML Format ─────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -369,6 +729,34 @@ async fn test_security_headers_present() {
     assert_eq!(headers.get("cache-control").unwrap(), "no-store");
 }
 
+#[tokio::test]
+async fn test_security_headers_on_error_responses() {
+    for uri in ["/no-such-route", "/v4?cidr=invalid"] {
+        let (status, _body, headers) = get_with_headers(uri).await;
+        assert!(status.is_client_error(), "{uri}: {status}");
+        assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+        assert_eq!(headers.get("x-frame-options").unwrap(), "DENY");
+        assert_eq!(headers.get("cache-control").unwrap(), "no-store");
+    }
+}
+
+#[tokio::test]
+async fn test_security_headers_on_cors_preflight() {
+    let app = create_router(RouterConfig::default());
+    let req = Request::builder()
+        .method("OPTIONS")
+        .uri("/v4")
+        .header(header::ORIGIN, "https://example.com")
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .body(Body::empty())
+        .unwrap();
+    let resp: Response = app.oneshot(req).await.unwrap();
+    let headers = resp.headers();
+    assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+    // No origins are allowed by default
+    assert!(headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+}
+
 #[tokio::test]
 async fn test_batch_size_exceeded() {
     use ipcalc::config::ServerConfig;
@@ -404,6 +792,70 @@ async fn test_swagger_disabled_by_default() {
     assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_result_kind_config_switch() {
+    use ipcalc::config::ServerConfig;
+
+    // Off by default, so API responses are unchanged
+    let (status, body) = get("/v4?cidr=192.168.1.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(json.get("kind").is_none());
+
+    let config = || RouterConfig {
+        server: ServerConfig {
+            result_kind: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    for (uri, kind) in [
+        ("/v4?cidr=192.168.1.0/24", "ipv4_subnet"),
+        ("/v6?cidr=2001:db8::/32", "ipv6_subnet"),
+        ("/v4/summarize?cidrs=10.0.0.0/24,10.0.1.0/24", "summary"),
+    ] {
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let resp: Response = create_router(config()).oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), 200);
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(
+            body.starts_with(&format!(r#"{{"kind":"{kind}","#)),
+            "{body}"
+        );
+    }
+}
+
+#[cfg(feature = "swagger")]
+#[tokio::test]
+async fn test_openapi_documents_result_kind() {
+    use ipcalc::config::ServerConfig;
+    let config = RouterConfig {
+        server: ServerConfig {
+            enable_swagger: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let req = Request::builder()
+        .uri("/api-docs/openapi.json")
+        .body(Body::empty())
+        .unwrap();
+    let resp: Response = create_router(config).oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let schemas = &json["components"]["schemas"];
+    assert_eq!(
+        schemas["Ipv4Subnet"]["properties"]["kind"]["enum"],
+        serde_json::json!(["ipv4_subnet"])
+    );
+    assert_eq!(
+        schemas["BatchResult"]["properties"]["kind"]["enum"],
+        serde_json::json!(["batch"])
+    );
+}
+
 #[tokio::test]
 async fn test_input_too_long_rejected() {
     let long_cidr = "a".repeat(300);
@@ -448,3 +900,850 @@ async fn test_body_size_limit() {
     let resp: Response = app.oneshot(req).await.unwrap();
     assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
 }
+
+#[tokio::test]
+async fn test_body_size_limit_json_error() {
+    use ipcalc::config::ServerConfig;
+    let config = || RouterConfig {
+        server: ServerConfig {
+            max_body_size: 64,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let large_body = format!(
+        r#"{{"cidrs":[{}]}}"#,
+        (0..20)
+            .map(|i| format!(r#""10.0.{}.0/24""#, i))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    // Rejected up front from the Content-Length header
+    let req = Request::builder()
+        .method("POST")
+        .uri("/batch")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::CONTENT_LENGTH, large_body.len())
+        .body(Body::from(large_body.clone()))
+        .unwrap();
+    let resp: Response = create_router(config()).oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "application/json");
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["error"].as_str().unwrap().contains("64 bytes"));
+    assert_eq!(json["max_body_size"], 64);
+    assert_eq!(json["content_length"], large_body.len());
+
+    // Rejected while reading a body without Content-Length
+    let req = Request::builder()
+        .method("POST")
+        .uri("/batch")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(large_body))
+        .unwrap();
+    let resp: Response = create_router(config()).oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["max_body_size"], 64);
+    assert!(json["content_length"].is_null());
+}
+
+#[tokio::test]
+async fn test_contains_resolve_disabled_by_default() {
+    let (status, body) = get("/v4/contains?cidr=192.168.1.0/24&address=192.168.1.10").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(json.get("ptr_name").is_none());
+
+    let (status, body) =
+        get("/v4/contains?cidr=192.168.1.0/24&address=192.168.1.10&resolve=true").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(
+        json["error"]
+            .as_str()
+            .unwrap()
+            .contains("allow_dns_lookups")
+    );
+
+    let (status, _) = get("/v6/contains?cidr=2001:db8::/32&address=2001:db8::1&resolve=true").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_web_ui_not_found_when_disabled() {
+    let (status, _) = get("/ui").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[cfg(feature = "webui")]
+#[tokio::test]
+async fn test_web_ui_served_when_enabled() {
+    use ipcalc::config::ServerConfig;
+    let config = RouterConfig {
+        server: ServerConfig {
+            enable_ui: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let app = create_router(config);
+    let req = Request::builder().uri("/ui").body(Body::empty()).unwrap();
+    let resp: Response = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let content_type = resp.headers()[header::CONTENT_TYPE].to_str().unwrap();
+    assert!(content_type.starts_with("text/html"), "{}", content_type);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    // No external assets or absolute URLs: everything is embedded or same-origin
+    assert!(!html.contains("http://") && !html.contains("https://"));
+    assert!(!html.contains("<script src") && !html.contains("<link"));
+    assert_eq!(html.matches("fetch(").count(), 1);
+    assert!(html.contains("fetch(path,"));
+    assert!(html.contains(r#"if (!path.startsWith("/") || path.startsWith("//"))"#));
+}
+
+#[tokio::test]
+async fn test_v4_complement() {
+    let (status, body) = get("/v4/complement?cidr=10.0.1.0/24").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["within"], "10.0.0.0/23");
+    assert_eq!(json["cidr_count"], 1);
+    assert_eq!(json["cidrs"][0]["network_address"], "10.0.0.0");
+
+    let (status, body) = get("/v4/complement?cidr=10.0.1.0/24&within=10.0.0.0/22").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["cidr_count"], 2);
+    assert_eq!(json["cidrs"][1]["network_address"], "10.0.2.0");
+    assert_eq!(json["cidrs"][1]["prefix_length"], 23);
+
+    let (status, _) = get("/v4/complement?cidr=10.0.1.0/24&within=192.168.0.0/16").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_v6_complement() {
+    let (status, body) = get("/v6/complement?cidr=2001:db8:1::/48").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["within"], "2001:db8::/47");
+    assert_eq!(json["cidrs"][0]["network_address"], "2001:db8::");
+}
+
+#[tokio::test]
+async fn test_landing_page_when_enabled() {
+    use ipcalc::config::ServerConfig;
+    let config = RouterConfig {
+        server: ServerConfig {
+            enable_landing_page: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let app = create_router(config);
+    let req = Request::builder().uri("/").body(Body::empty()).unwrap();
+    let resp: Response = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let content_type = resp.headers()[header::CONTENT_TYPE].to_str().unwrap();
+    assert!(content_type.starts_with("text/html"), "{}", content_type);
+
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    let html = String::from_utf8(body.to_vec()).unwrap();
+    assert!(html.contains("<code>/v4?cidr=&lt;cidr&gt;</code>"));
+    assert!(html.contains(r#"<a href="/v4?cidr=192.168.1.0/24">"#));
+    assert!(html.contains("/v4/split?cidr=10.0.0.0/8&amp;prefix=16&amp;count=5"));
+}
+
+#[tokio::test]
+async fn test_landing_page_disabled_by_default() {
+    // Without the landing page, `/` keeps serving the dashboard
+    let (status, body) = get("/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body.contains("ipcalc API"));
+    let (status, dashboard) = get("/dashboard").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, dashboard);
+}
+
+#[tokio::test]
+async fn test_v6_require_canonical() {
+    let (status, body) = get("/v6?cidr=2001:DB8:0:0:0:0:0:1/64").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["canonical_input"], false);
+
+    let (status, body) = get("/v6?cidr=2001:DB8:0:0:0:0:0:1/64&require_canonical=true").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("use 2001:db8::1/64"), "{}", body);
+
+    let (status, _) = get("/v6?cidr=2001:db8::1/64&require_canonical=true").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_batch_require_canonical() {
+    let request = r#"{"cidrs":["2001:DB8::/32","2001:db8::/48"],"require_canonical":true}"#;
+    let (status, body) = post_json("/batch", request).await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["non_canonical"], 1);
+    assert!(json["results"][0]["error"].as_str().is_some());
+    assert!(json["results"][1]["error"].is_null());
+}
+
+#[tokio::test]
+async fn test_batch_with_summary() {
+    let request =
+        r#"{"cidrs":["10.0.0.0/24","10.0.1.0/24","2001:db8::/48","bogus"],"with_summary":true}"#;
+    let (status, body) = post_json("/batch", request).await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["aggregate_summary"]["ipv4"][0], "10.0.0.0/23");
+    assert_eq!(json["aggregate_summary"]["ipv6"][0], "2001:db8::/48");
+}
+
+#[tokio::test]
+async fn test_covers_exact_gaps_and_excess() {
+    let (status, body) = post_json(
+        "/covers",
+        r#"{"target":"10.0.0.0/16","cidrs":["10.0.0.0/17","10.0.128.0/17"]}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["exact"], true);
+
+    let (status, body) = post_json(
+        "/covers",
+        r#"{"target":"2001:db8::/32","cidrs":["2001:db8::/33","2001:db8::/31"]}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["exact"], false);
+    assert_eq!(json["gaps"].as_array().unwrap().len(), 0);
+    assert_eq!(json["excess"][0], "2001:db9::/32");
+
+    let (status, body) = post_json(
+        "/covers",
+        r#"{"target":"2001:db8::/32","cidrs":["2001:db8:8000::/33"]}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["gaps"][0], "2001:db8::/33");
+
+    let (status, _) = post_json("/covers", r#"{"target":"10.0.0.0/16","cidrs":[]}"#).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_dualstack_split() {
+    let (status, body) = get(
+        "/dualstack/split?v4=10.20.0.0/16&v6=2001:db8:20::/48&v4_prefix=24&v6_prefix=64&count=2",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pairs"].as_array().unwrap().len(), 2);
+    assert_eq!(json["pairs"][1]["index"], 2);
+    assert_eq!(json["pairs"][1]["v4"]["network_address"], "10.20.1.0");
+    assert_eq!(json["pairs"][1]["v6"]["network_address"], "2001:db8:20:1::");
+
+    let (status, body) =
+        get("/dualstack/split?v4=10.0.0.0/22&v6=2001:db8::/48&v4_prefix=24&v6_prefix=64&count=5")
+            .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("IPv4 supernet ran out"));
+}
+
+#[tokio::test]
+async fn test_v4_netmask_notation() {
+    let (status, body) = get("/v4?cidr=192.168.1.0/255.255.255.0").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["prefix_length"], 24);
+
+    let (status, _) = get("/v4?cidr=192.168.1.0/255.0.255.0").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_bare_address() {
+    let (status, body) = get("/v4?cidr=8.8.8.8").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["prefix_length"], 32);
+
+    let (status, body) = get("/v6?cidr=2001:db8::1").await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["prefix_length"], 128);
+
+    let (status, body) = post_json("/batch", r#"{"cidrs":["10.0.0.1","10.0.0.0/8"]}"#).await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["results"][0]["subnet"]["prefix_length"], 32);
+}
+
+#[tokio::test]
+async fn test_v4_is_usable() {
+    let (status, body) = get("/v4/is-usable?cidr=192.168.1.255/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["role"], "broadcast");
+    assert_eq!(json["usable"], false);
+
+    let (status, body) = get("/v4/is-usable?cidr=10.0.0.0/31").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["usable"], true);
+
+    let (status, _) = get("/v4/is-usable?cidr=bogus").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_host_check() {
+    let (status, body) = get("/v4/host-check?address=192.168.1.255&cidr=192.168.1.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["role"], "broadcast");
+
+    let (status, body) = get("/v6/host-check?address=2001:db8::&cidr=2001:db8::/64").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["role"], "network");
+    assert_eq!(json["usable"], false);
+
+    let (status, _) = get("/v6/host-check?address=10.0.0.1&cidr=2001:db8::/64").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_v4_overlaps() {
+    let (status, body) = get("/v4/overlaps?cidrs=10.0.0.0/8,10.1.0.0/16,192.168.0.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["overlap_count"], 1);
+    assert_eq!(json["pairs"][0]["second"], "10.1.0.0/16");
+
+    let (status, body) = get("/v4/overlaps?cidrs=10.0.0.0/8").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["pairs"].as_array().unwrap().len(), 0);
+
+    let (status, _) = get("/v4/overlaps?cidrs=10.0.0.0/8,2001:db8::/32").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_summarize_grouped() {
+    let (status, body) = post_json(
+        "/summarize-grouped",
+        r#"{"tag":"role","cidrs":["10.0.0.0/24 # role=web","10.1.0.0/24 # role=db","10.0.1.0/24 # role=web","10.1.1.0/24 # role=db"]}"#,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["groups"][0]["value"], "db");
+    assert_eq!(json["groups"][0]["summary"]["ipv4"][0], "10.1.0.0/23");
+    assert_eq!(json["groups"][1]["value"], "web");
+    assert_eq!(json["groups"][1]["summary"]["ipv4"][0], "10.0.0.0/23");
+    assert_eq!(json["groups"].as_array().unwrap().len(), 2);
+
+    let (status, _) = post_json("/summarize-grouped", r#"{"tag":"role","cidrs":[]}"#).await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_post_overlaps() {
+    let (status, body) = post_json(
+        "/overlaps",
+        r#"{"cidrs":["10.0.0.0/8","2001:db8::/32","10.0.0.0/8","2001:db8:1::/48"]}"#,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["overlap_count"], 2);
+    assert_eq!(json["pairs"][0]["relation"], "equal");
+    assert_eq!(json["pairs"][1]["relation"], "contains");
+
+    let (status, _) = post_json("/overlaps", r#"{"cidrs":["10.0.0.0/8","bogus"]}"#).await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_supernet() {
+    let (status, body) = get("/v4/supernet?cidrs=10.1.4.0/24,10.1.7.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["supernet"], "10.1.4.0/22");
+    assert_eq!(json["total_addresses"], "1024");
+    assert_eq!(json["extra_addresses"], "512");
+
+    let (status, body) = get("/v6/supernet?cidrs=2001:db8::/48,2001:db8:3::/48").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["supernet"], "2001:db8::/46");
+
+    let (status, body) = get("/v4/supernet?cidrs=0.0.0.0/1,128.0.0.0/2").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["supernet"], "0.0.0.0/0");
+
+    let (status, _) = get("/v6/supernet?cidrs=10.0.0.0/8").await;
+    assert_eq!(status, 400);
+
+    for path in ["/v4/supernet", "/v6/supernet"] {
+        let (status, body) = get(&format!("{}?cidrs=10.0.0.0/8,2001:db8::/32", path)).await;
+        assert_eq!(status, 400, "{}", path);
+        assert!(
+            body.contains("Address family mismatch"),
+            "{}: {}",
+            path,
+            body
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_common_supernet() {
+    let (status, body) = get("/v4/common-supernet?a=192.168.1.0/24&b=192.168.3.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network_address"], "192.168.0.0");
+    assert_eq!(json["prefix_length"], 22);
+
+    let (status, body) = get("/v6/common-supernet?a=2001:db8:1::/48&b=2001:db8:2::/48").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["prefix_length"], 46);
+
+    let (status, body) = get("/v4/common-supernet?a=10.0.0.0/8&b=2001:db8::/32").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("Address family mismatch"));
+}
+
+#[tokio::test]
+async fn test_eui64() {
+    let (status, body) = get("/v6/eui64?prefix=2001:db8::/64&mac=00:1a:2b:3c:4d:5e").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["derived_address"], "2001:db8::21a:2bff:fe3c:4d5e");
+    assert_eq!(
+        json["derived_address_full"],
+        "2001:0db8:0000:0000:021a:2bff:fe3c:4d5e"
+    );
+
+    let (status, body) = get("/v6/eui64?prefix=2001:db8::/64&mac=00:1a:2b").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("Invalid MAC address"));
+    let (status, body) = get("/v6/eui64?prefix=2001:db8::/96&mac=00:1a:2b:3c:4d:5e").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("/64 or shorter"));
+}
+
+#[tokio::test]
+async fn test_ifid() {
+    let (status, body) = get("/v6/ifid?cidr=2001:db8::1234:5678:9abc:def0/64").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network"], "2001:db8::/64");
+    assert_eq!(json["interface_id"], "1234:5678:9abc:def0");
+
+    let (status, body) = get("/v6/ifid?cidr=2001:db8::abcd/120").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network"], "2001:db8::ab00/120");
+    assert_eq!(json["host_bits"], 8);
+    assert_eq!(json["interface_id"], "cd");
+
+    let (status, _) = get("/v6/ifid?cidr=10.0.0.1/24").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_walk() {
+    let (status, body) = get("/v4/walk?start=10.0.0.0/24&end=10.0.5.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["count"], 6);
+    assert_eq!(json["subnets"][0]["network_address"], "10.0.0.0");
+
+    let (status, body) = get("/v4/walk?start=10.0.0.0/24&end=10.0.5.0/25").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("different prefix lengths"));
+    let (status, _) = get("/v4/walk?start=10.0.5.0/24&end=10.0.0.0/24").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_add() {
+    let (status, body) = get("/v4/add?address=10.0.0.0&offset=256").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["result"], "10.0.1.0");
+
+    let (status, body) = get("/v4/add?address=10.0.0.0&offset=-1&format=text").await;
+    assert_eq!(status, 200);
+    assert_eq!(body.trim(), "9.255.255.255");
+    let (status, body) = get("/v4/add?address=255.255.255.255&offset=1").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("outside the address space"));
+}
+
+#[tokio::test]
+async fn test_hosts() {
+    let (status, body) = get("/v4/hosts?cidr=192.168.1.0/29&max=6").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["hosts"].as_array().unwrap().len(), 6);
+    assert_eq!(json["hosts"][5], "192.168.1.6");
+    assert_eq!(json["truncated"], false);
+
+    let (status, body) = get("/v6/hosts?cidr=2001:db8::/64&max=2").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["hosts"][0], "2001:db8::1");
+    assert_eq!(json["truncated"], true);
+
+    let (status, _) = get("/v4/hosts?cidr=10.0.0.0/33").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_nth_host() {
+    let (status, body) = get("/v4/nth-host?cidr=10.0.0.0/24&n=5").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["address"], "10.0.0.6");
+    assert_eq!(json["n"], 5);
+
+    let (status, body) = get("/v4/nth-host?cidr=10.0.0.0/31&n=1&format=text").await;
+    assert_eq!(status, 200);
+    assert_eq!(body.trim(), "10.0.0.1");
+    let (status, body) = get("/v4/nth-host?cidr=10.0.0.0/24&n=254").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("out of range"));
+}
+
+#[tokio::test]
+async fn test_exclude() {
+    let (status, body) = get("/v4/exclude?supernet=10.0.0.0/22&cidrs=10.0.1.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["cidr_count"], 2);
+    assert_eq!(json["cidrs"][0]["network_address"], "10.0.0.0");
+    assert_eq!(json["cidrs"][1]["network_address"], "10.0.2.0");
+
+    let (status, body) = get("/v4/exclude?supernet=10.0.0.0/22&exclude=10.0.1.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["excluded"][0], "10.0.1.0/24");
+    assert_eq!(json["cidr_count"], 2);
+
+    let (status, body) = get("/v4/exclude?cidr=10.0.0.0/8&exclude=10.0.0.0/8").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["supernet"], "10.0.0.0/8");
+    assert_eq!(json["cidr_count"], 0);
+
+    let (status, body) = get("/v6/exclude?supernet=2001:db8::/46&cidrs=2001:db8:1::/48").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["cidr_count"], 2);
+
+    let (status, body) = get("/v4/exclude?supernet=10.0.0.0/24&cidrs=10.0.0.0/16").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("not contained"));
+    let (status, body) =
+        get("/v4/exclude?supernet=10.0.0.0/24&cidrs=10.0.0.0/25,192.168.0.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["ignored"][0], "192.168.0.0/24");
+}
+
+#[tokio::test]
+async fn test_next_and_prev_subnet() {
+    let (status, body) = get("/v4/next-subnet?cidr=192.168.1.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network_address"], "192.168.2.0");
+    assert_eq!(json["prefix_length"], 24);
+
+    let (status, body) = get("/v4/prev-subnet?cidr=192.168.1.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["network_address"], "192.168.0.0");
+
+    let (status, body) = get("/v4/next-subnet?cidr=255.0.0.0/8").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("no next subnet"));
+    let (status, _) = get("/v4/prev-subnet?cidr=0.0.0.0/8").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_ipv6_format() {
+    let (status, body) = get("/v6/format?address=2001:0db8:0:0:0:0:0:1").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["compressed"], "2001:db8::1");
+    assert_eq!(json["expanded"], "2001:0db8:0000:0000:0000:0000:0000:0001");
+
+    let (status, _) = get("/v6/format?address=2001:db8::1::2").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_lpm() {
+    let (status, body) =
+        get("/v4/lpm?address=10.1.2.3&cidrs=10.0.0.0/8,10.1.0.0/16,10.1.2.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["matched"], "10.1.2.0/24");
+    assert_eq!(json["candidate_count"], 3);
+
+    let (status, body) = get("/v4/lpm?address=192.0.2.1&cidrs=10.0.0.0/8").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(json["matched"].is_null());
+
+    let (status, body) =
+        get("/v6/lpm?address=2001:db8:1::5&cidrs=2001:db8::/32,2001:db8:1::/48").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["matched"], "2001:db8:1::/48");
+
+    let (status, _) = get("/v4/lpm?address=10.1.2.3&cidrs=2001:db8::/32").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_reverse_dns() {
+    let (status, body) = get("/v4/reverse-dns?cidr=192.0.2.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["zones"], serde_json::json!(["2.0.192.in-addr.arpa"]));
+    assert_eq!(json["records"].as_array().unwrap().len(), 254);
+
+    let (status, body) = get("/v4/reverse-dns?cidr=192.0.2.0/25&zone_only=true").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        json["zones"],
+        serde_json::json!(["0/25.2.0.192.in-addr.arpa"])
+    );
+    assert!(json.get("records").is_none());
+
+    let (status, body) = get("/v6/reverse-dns?cidr=2001:db8:abcd::/48").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        json["zones"],
+        serde_json::json!(["d.c.b.a.8.b.d.0.1.0.0.2.ip6.arpa"])
+    );
+
+    let (status, body) = get("/v4/reverse-dns?cidr=10.0.0.0/15&zone_only=true").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        json["zones"],
+        serde_json::json!(["0.10.in-addr.arpa", "1.10.in-addr.arpa"])
+    );
+
+    let (status, _) = get("/v4/reverse-dns?cidr=2001:db8::/48").await;
+    assert_eq!(status, 400);
+    let (status, _) = get("/v4/reverse-dns?cidr=10.0.0.0/8").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn test_v4_vlsm() {
+    let (status, body) = post_json(
+        "/v4/vlsm",
+        r#"{"supernet":"10.0.0.0/24","requirements":[100,50,20,10]}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let allocations = json["allocations"].as_array().unwrap();
+    assert_eq!(allocations.len(), 4);
+    assert_eq!(allocations[0]["hosts"], 100);
+    assert_eq!(allocations[0]["prefix"], 25);
+    assert_eq!(allocations[3]["subnet"]["network_address"], "10.0.0.224");
+    assert_eq!(json["free"][0], "10.0.0.240/28");
+
+    let (status, body) = post_json(
+        "/v4/vlsm",
+        r#"{"supernet":"10.0.0.0/25","requirements":[100,50]}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("cannot fit"));
+}
+
+#[tokio::test]
+async fn test_v4_vlsm_p2p_usable() {
+    // By default a /31 holds no hosts, so two hosts need a /30
+    let (status, body) = post_json(
+        "/v4/vlsm",
+        r#"{"supernet":"10.0.0.0/29","requirements":[2,1]}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["allocations"][0]["prefix"], 30);
+    assert_eq!(json["allocations"][1]["prefix"], 32);
+
+    let (status, body) = post_json(
+        "/v4/vlsm",
+        r#"{"supernet":"10.0.0.0/29","requirements":[2,1],"p2p_usable":2}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["allocations"][0]["prefix"], 31);
+    assert_eq!(json["allocations"][0]["waste"], 0);
+
+    let (status, body) = post_json(
+        "/v4/vlsm",
+        r#"{"supernet":"10.0.0.0/29","requirements":[2],"p2p_usable":1}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("p2p_usable"));
+}
+
+#[tokio::test]
+async fn test_vlsm_alias() {
+    let (status, body) =
+        post_json("/vlsm", r#"{"cidr":"10.0.0.0/22","hosts":[10,500,50,200]}"#).await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let cidrs: Vec<String> = json["allocations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| {
+            format!(
+                "{}/{}",
+                a["subnet"]["network_address"].as_str().unwrap(),
+                a["prefix"]
+            )
+        })
+        .collect();
+    assert_eq!(
+        cidrs,
+        ["10.0.0.0/23", "10.0.2.0/24", "10.0.3.0/26", "10.0.3.64/28"]
+    );
+    assert_eq!(json["remaining_addresses"], 176);
+    assert_eq!(
+        json["free"],
+        serde_json::json!(["10.0.3.80/28", "10.0.3.96/27", "10.0.3.128/25"])
+    );
+}
+
+#[tokio::test]
+async fn test_v4_available() {
+    let (status, body) = post_json(
+        "/v4/available",
+        r#"{"supernet":"192.168.1.0/24","prefix":26,"used":["192.168.1.0/26","192.168.1.200/29"]}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        json["available"],
+        serde_json::json!(["192.168.1.64/26", "192.168.1.128/26"])
+    );
+    assert_eq!(json["available_count"], 2);
+
+    let (status, body) = post_json(
+        "/v6/available",
+        r#"{"supernet":"2001:db8::/47","prefix":48}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["available_count"], 2);
+
+    // Over the generation limit
+    let (status, body) =
+        post_json("/v4/available", r#"{"supernet":"0.0.0.0/0","prefix":32}"#).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("error"));
+}
+
+#[tokio::test]
+async fn test_gaps() {
+    let (status, body) = get("/v4/gaps?supernet=10.0.0.0/16&used=10.0.0.0/24,10.0.4.0/22").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["free_cidr_count"], 7);
+    assert_eq!(json["free_cidrs"][0], "10.0.1.0/24");
+    assert_eq!(json["largest_free_block"]["address_count"], "63488");
+
+    let (status, body) = get("/v6/gaps?supernet=2001:db8::/46&used=2001:db8:1::/48").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["free_cidrs"][1], "2001:db8:2::/47");
+
+    let (status, body) = get("/v4/gaps?supernet=10.0.0.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["free_addresses"], "256");
+
+    let (status, body) = get("/v4/gaps?supernet=10.0.0.0/24&used=10.0.0.0/16").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("is not inside"));
+}
+
+#[tokio::test]
+async fn test_allocate() {
+    let (status, body) =
+        get("/v4/allocate?supernet=10.0.0.0/16&prefix=24&used=10.0.0.0/24,10.0.1.0/24").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["allocated"][0], "10.0.2.0/24");
+    assert_eq!(json["requested"], 1);
+
+    let (status, body) =
+        get("/v6/allocate?supernet=2001:db8::/48&prefix=64&count=2&used=2001:db8::/64").await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["allocated"][1], "2001:db8:0:2::/64");
+
+    let (status, body) =
+        get("/v4/allocate?supernet=10.0.0.0/24&prefix=25&used=10.0.0.64/26,10.0.0.192/26").await;
+    assert_eq!(status, 400);
+    assert!(body.contains("No free space in 10.0.0.0/24 for a /25"));
+}
+
+#[tokio::test]
+async fn test_match() {
+    let (status, body) = post_json(
+        "/match",
+        r#"{"address":"10.1.2.3","cidrs":["10.0.0.0/8","10.1.0.0/16","10.1.2.0/24"]}"#,
+    )
+    .await;
+    assert_eq!(status, 200);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["matched"], "10.1.2.0/24");
+    assert_eq!(json["prefix_length"], 24);
+
+    let (status, body) = post_json(
+        "/match",
+        r#"{"address":"2001:db8:1::5","cidrs":["2001:db8::/32","10.0.0.0/8"]}"#,
+    )
+    .await;
+    assert_eq!(status, 400);
+    assert!(body.contains("Address family mismatch"));
+}
@@ -47,6 +47,9 @@ fn test_ipv4_json_output() {
     assert_eq!(json["prefix_length"], 24);
     assert_eq!(json["usable_hosts"], 254);
     assert_eq!(json["address_type"], "Private (RFC 1918)");
+    let binary = json["binary_address"].as_str().unwrap();
+    assert_eq!(binary.len(), 35);
+    assert_eq!(binary, "11000000.10101000.00000001.00000000");
 }
 
 #[test]
@@ -56,6 +59,8 @@ fn test_ipv4_text_output() {
     assert!(stdout.contains("IPv4 Subnet Calculator"));
     assert!(stdout.contains("Network Address:   10.0.0.0"));
     assert!(stdout.contains("Broadcast Address: 10.255.255.255"));
+    assert!(stdout.contains("Binary Address:    00001010.00000000.00000000.00000000"));
+    assert!(stdout.contains("Binary Mask:       11111111.00000000.00000000.00000000"));
     assert!(stdout.contains("Address Type:      Private (RFC 1918)"));
 }
 
@@ -115,6 +120,22 @@ fn test_invalid_prefix() {
     assert!(stderr.contains("Error"));
 }
 
+#[test]
+fn test_invalid_prefix_terse_by_default() {
+    let (_, stderr, success) = run_ipcalc(&["192.168.1.0/33"]);
+    assert!(!success);
+    assert!(!stderr.contains("Valid range"));
+}
+
+#[test]
+fn test_invalid_prefix_explain_errors() {
+    let (_, stderr, success) = run_ipcalc(&["192.168.1.0/33", "--explain-errors"]);
+    assert!(!success);
+    assert!(stderr.contains("Error: Invalid prefix length: 33"));
+    assert!(stderr.contains("Valid range: 0-32 for IPv4, 0-128 for IPv6"));
+    assert!(stderr.contains("Examples: 10.0.0.0/8"));
+}
+
 #[test]
 fn test_file_output() {
     let temp_file = "/tmp/ipcalc_test_output.json";
@@ -128,6 +149,85 @@ fn test_file_output() {
     std::fs::remove_file(temp_file).ok();
 }
 
+#[test]
+fn test_normalize_in_place() {
+    let path = std::env::temp_dir().join(format!("ipcalc_normalize_{}.txt", std::process::id()));
+    std::fs::write(
+        &path,
+        "# edge\n10.1.2.3/16 # site=dc1\n2001:DB8::1/32\n10.1.0.0/16\n\n192.168.0.9/24\n",
+    )
+    .unwrap();
+    let file = path.to_str().unwrap();
+
+    let (stdout, _, success) = run_ipcalc(&["normalize", file, "--sort", "--dedupe", "--in-place"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["duplicates_removed"], 1);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "# edge\n10.1.0.0/16 # site=dc1\n192.168.0.0/24\n2001:db8::/32\n"
+    );
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn test_normalize_stdin_text() {
+    let (stdout, _, success) = run_ipcalc_stdin(
+        &["normalize", "--format", "text"],
+        "10.0.0.7/8\nFE80::1/10 # lab\n",
+    );
+    assert!(success);
+    assert_eq!(stdout, "10.0.0.0/8\nfe80::/10 # lab\n\n");
+}
+
+#[test]
+fn test_complement_default_parent_and_within() {
+    let (stdout, _, success) = run_ipcalc(&["complement", "10.0.1.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["within"], "10.0.0.0/23");
+    assert_eq!(json["cidrs"][0]["network_address"], "10.0.0.0");
+    assert_eq!(json["cidrs"][0]["prefix_length"], 24);
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "complement",
+        "10.0.1.0/24",
+        "--within",
+        "10.0.0.0/22",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("1. 10.0.0.0/24"));
+    assert!(stdout.contains("2. 10.0.2.0/23"));
+
+    let (_, stderr, success) =
+        run_ipcalc(&["complement", "10.0.1.0/24", "--within", "10.1.0.0/16"]);
+    assert!(!success);
+    assert!(stderr.contains("is not inside"));
+}
+
+#[test]
+fn test_merge_results_dedupe() {
+    let (stdout, stderr, success) = run_ipcalc(&[
+        "merge-results",
+        "tests/fixtures/merge/a.json",
+        "tests/fixtures/merge/b.json",
+        "tests/fixtures/merge/c.json",
+        "--dedupe",
+    ]);
+    assert!(success);
+    assert!(stderr.contains("conflicting results for 10.0.0.0/24"));
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["count"], 6);
+    assert_eq!(json["input_entries"], 11);
+    assert_eq!(json["duplicates_removed"], 5);
+    assert_eq!(json["conflicts"].as_array().unwrap().len(), 1);
+    assert_eq!(json["results"].as_array().unwrap().len(), 6);
+}
+
 #[test]
 fn test_split_too_many_subnets() {
     // /22 can only fit 32 /27 subnets, requesting 100 should fail
@@ -160,6 +260,172 @@ fn test_split_ipv6_max() {
     assert_eq!(json["subnets"].as_array().unwrap().len(), 16);
 }
 
+#[test]
+fn test_split_ipv6_max_deep_split_limit_error() {
+    // A split 64 or more bits deep reports its true subnet count
+    let (_, stderr, success) = run_ipcalc(&["split", "2001::/16", "-p", "96", "--max"]);
+    assert!(!success);
+    assert!(stderr.contains("2^80"), "{stderr}");
+    assert!(!stderr.contains("18446744073709551615"), "{stderr}");
+
+    let (_, stderr, success) = run_ipcalc(&["split", "::/0", "-p", "64", "--max"]);
+    assert!(!success);
+    assert!(stderr.contains("2^64"), "{stderr}");
+}
+
+#[test]
+fn test_split_streamed_text_and_csv() {
+    // Text and CSV are streamed, and match the buffered layout
+    let (stdout, _, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/16",
+        "-p",
+        "28",
+        "--max",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("Generated 4096 subnets:"));
+    assert!(stdout.contains("  1. 10.0.0.0/28 (Hosts: 10.0.0.1-10.0.0.14)"));
+    assert!(stdout.contains("  4096. 10.0.255.240/28"));
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "split",
+        "2001:db8::/48",
+        "-p",
+        "64",
+        "-n",
+        "3",
+        "--format",
+        "csv",
+    ]);
+    assert!(success);
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines[0], "# supernet: 2001:db8::/48");
+    assert!(lines[3].starts_with("input,network_address"));
+    assert_eq!(lines.len(), 7);
+    assert!(lines[6].starts_with("2001:db8:0:2::/64,"));
+
+    // Errors are still reported before any output
+    let (stdout, stderr, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/24",
+        "-p",
+        "26",
+        "-n",
+        "5",
+        "--format",
+        "text",
+    ]);
+    assert!(!success);
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Error"));
+}
+
+#[test]
+fn test_split_streamed_ndjson() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("split.ndjson");
+    let (stdout, _, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/16",
+        "-p",
+        "28",
+        "--max",
+        "--format",
+        "ndjson",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+    assert!(success);
+    assert!(stdout.is_empty());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 4096);
+    let last: serde_json::Value = serde_json::from_str(lines[4095]).expect("Invalid JSON");
+    assert_eq!(last["kind"], "ipv4_subnet");
+    assert_eq!(last["network_address"], "10.0.255.240");
+
+    // Flags that need the whole list keep the one-subnet-per-line records
+    for extra in [&["--shuffle", "--seed", "7"][..], &["--verify"][..]] {
+        let mut args = vec![
+            "split",
+            "10.0.0.0/24",
+            "-p",
+            "26",
+            "--max",
+            "--format",
+            "ndjson",
+        ];
+        args.extend_from_slice(extra);
+        let (stdout, _, success) = run_ipcalc(&args);
+        assert!(success, "{extra:?}");
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 4, "{extra:?}");
+        for line in lines {
+            let record: serde_json::Value = serde_json::from_str(line).expect("Invalid JSON");
+            assert_eq!(record["kind"], "ipv4_subnet");
+            assert_eq!(record["prefix_length"], 26);
+        }
+    }
+
+    // Other results are a single record on one line
+    let (stdout, _, success) = run_ipcalc(&["10.0.0.0/24", "--format", "ndjson", "--no-kind"]);
+    assert!(success);
+    assert_eq!(stdout.lines().count(), 1);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["broadcast_address"], "10.0.0.255");
+    assert!(json.get("kind").is_none());
+}
+
+#[test]
+fn test_split_streamed_past_generation_limit() {
+    use std::io::{BufRead, BufReader};
+
+    // Streaming to stdout is not capped; the reader stops it early
+    let mut child = Command::new("cargo")
+        .args(["run", "--quiet", "--"])
+        .args([
+            "split",
+            "10.0.0.0/8",
+            "-p",
+            "32",
+            "--max",
+            "--format",
+            "text",
+        ])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn ipcalc");
+    let lines: Vec<String> = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .take(8)
+        .map(|l| l.unwrap())
+        .collect();
+    assert!(lines.contains(&"Generated 16777216 subnets:".to_string()));
+    assert!(lines[7].starts_with("  2. 10.0.0.1/32"));
+    // Closing the pipe ends the split without an error
+    assert!(child.wait().unwrap().success());
+
+    // Output files still honour the limit
+    let path = std::env::temp_dir().join("ipcalc_split_limit_test.txt");
+    let (_, stderr, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/8",
+        "-p",
+        "32",
+        "--max",
+        "--format",
+        "text",
+        "-o",
+        path.to_str().unwrap(),
+    ]);
+    assert!(!success);
+    assert!(stderr.contains("exceeds the limit"));
+    assert!(!path.exists());
+}
+
 #[test]
 fn test_split_requires_count_or_max() {
     // Neither --count nor --max should fail
@@ -168,6 +434,39 @@ fn test_split_requires_count_or_max() {
     assert!(stderr.contains("Error"));
 }
 
+#[test]
+fn test_verify_flags() {
+    let (verified, _, success) =
+        run_ipcalc(&["split", "10.0.0.0/24", "-p", "26", "--max", "--verify"]);
+    assert!(success);
+    let (plain, _, _) = run_ipcalc(&["split", "10.0.0.0/24", "-p", "26", "--max"]);
+    assert_eq!(verified, plain);
+
+    let (_, _, success) = run_ipcalc(&[
+        "split",
+        "2001:db8::/46",
+        "-p",
+        "48",
+        "--max",
+        "--verify",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+
+    // Only a full split can summarize back to the supernet
+    let (_, stderr, success) =
+        run_ipcalc(&["split", "10.0.0.0/24", "-p", "26", "-n", "2", "--verify"]);
+    assert!(!success);
+    assert!(stderr.contains("cannot be used with"));
+
+    let (stdout, _, success) =
+        run_ipcalc(&["from-range", "192.168.1.10", "192.168.1.20", "--verify"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["cidr_count"], 4);
+}
+
 #[test]
 fn test_direct_ipv4() {
     let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24"]);
@@ -199,57 +498,1453 @@ fn test_direct_ipv4_text_format() {
 }
 
 #[test]
-fn test_contains_ipv4_json() {
-    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.1.0/24", "192.168.1.100"]);
+fn test_contains_ipv4_json() {
+    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.1.0/24", "192.168.1.100"]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["cidr"], "192.168.1.0/24");
+    assert_eq!(json["address"], "192.168.1.100");
+    assert_eq!(json["contained"], true);
+    assert_eq!(json["network_address"], "192.168.1.0");
+    assert_eq!(json["broadcast_address"], "192.168.1.255");
+}
+
+#[test]
+fn test_contains_ipv4_not_contained() {
+    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.1.0/24", "10.0.0.1"]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["contained"], false);
+}
+
+#[test]
+fn test_contains_ipv6_json() {
+    let (stdout, _, success) = run_ipcalc(&["contains", "2001:db8::/32", "2001:db8::1"]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["contained"], true);
+    assert_eq!(json["address"], "2001:db8::1");
+}
+
+#[test]
+fn test_contains_range() {
+    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.1.0/24", "192.168.1.10-20"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["kind"], "contains");
+    assert_eq!(json["contained"], true);
+    assert_eq!(json["partial"], false);
+    assert!(json.get("contained_fraction").is_none());
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "contains",
+        "192.168.1.0/24",
+        "192.168.1.128-192.168.2.127",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("Contained:         No"), "{}", stdout);
+    assert!(
+        stdout.contains("Partial:           Yes (50.00% of the range)"),
+        "{}",
+        stdout
+    );
+
+    let (_, stderr, success) = run_ipcalc(&[
+        "contains",
+        "192.168.1.0/24",
+        "192.168.1.10-20",
+        "--translate",
+    ]);
+    assert!(!success);
+    assert!(stderr.contains("not a range"), "{}", stderr);
+}
+
+#[test]
+fn test_ipv4_mapped_ipv6() {
+    let (stdout, _, success) = run_ipcalc(&["::ffff:192.168.1.1"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["address_type"], "IPv4-Mapped (RFC 4291)");
+    assert_eq!(json["ipv4_mapped"], "192.168.1.1");
+
+    let (stdout, _, success) = run_ipcalc(&["2001:db8::/32"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert!(json.get("ipv4_mapped").is_none());
+}
+
+#[test]
+fn test_sixto4_ipv6() {
+    let (stdout, _, success) = run_ipcalc(&["2002:c000:0200::/48"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["address_type"], "6to4 (RFC 3056)");
+    assert_eq!(json["sixto4_ipv4"], "192.0.2.0");
+
+    let (stdout, _, success) = run_ipcalc(&["2002:c000:0200::/48", "--format", "text"]);
+    assert!(success);
+    assert!(
+        stdout.contains("6to4 IPv4:           192.0.2.0"),
+        "{}",
+        stdout
+    );
+
+    let (stdout, _, success) = run_ipcalc(&["2001:db8::/32"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert!(json.get("sixto4_ipv4").is_none());
+}
+
+#[test]
+fn test_rfc6890_flag() {
+    let (stdout, _, success) = run_ipcalc(&["192.0.0.9/32", "--rfc6890"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let entry = &json["special_purpose"];
+    assert_eq!(entry["name"], "Port Control Protocol Anycast");
+    assert_eq!(entry["allocation_attributes"]["globally_reachable"], true);
+
+    // Left out without the flag, and for blocks outside the registry
+    let (stdout, _, _) = run_ipcalc(&["192.0.0.9/32"]);
+    assert!(!stdout.contains("special_purpose"));
+    let (stdout, _, _) = run_ipcalc(&["8.8.8.0/24", "--rfc6890"]);
+    assert!(!stdout.contains("special_purpose"));
+}
+
+#[test]
+fn test_contains_subnet_mode() {
+    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.0.0/22", "192.168.1.0/26"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["kind"], "subnet_contains");
+    assert_eq!(json["fully_contained"], true);
+    assert_eq!(json["inner_prefix"], 26);
+
+    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.0.0/22", "192.168.0.0/21"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["fully_contained"], false);
+    assert_eq!(json["overlaps"], true);
+
+    let (_, _, success) = run_ipcalc(&["contains", "192.168.0.0/22", "10.0.0.0/8", "--translate"]);
+    assert!(!success);
+}
+
+#[test]
+fn test_subnet_contains_relationship() {
+    let (stdout, _, success) = run_ipcalc(&["subnet-contains", "10.0.0.0/8", "10.1.0.0/16"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["kind"], "subnet_contains");
+    assert_eq!(json["relationship"], "contains");
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "subnet-contains",
+        "10.0.0.0/8",
+        "11.0.0.0/8",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("Relationship:      disjoint"));
+}
+
+#[test]
+fn test_contains_ipv4_text() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "contains",
+        "192.168.1.0/24",
+        "192.168.1.100",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("Address Containment Check"));
+    assert!(stdout.contains("Contained:         Yes"));
+    assert!(stdout.contains("Network Address:   192.168.1.0"));
+}
+
+#[test]
+fn test_contains_invalid_address() {
+    let (_, stderr, success) = run_ipcalc(&["contains", "192.168.1.0/24", "not-an-ip"]);
+    assert!(!success);
+    assert!(stderr.contains("Error"));
+}
+
+#[test]
+fn test_contains_family_mismatch() {
+    let (_, stderr, success) = run_ipcalc(&["contains", "2002::/16", "192.0.2.1"]);
+    assert!(!success);
+    assert!(stderr.contains("Address family mismatch"));
+    assert!(!stderr.contains("--translate"));
+
+    let (_, stderr, success) =
+        run_ipcalc(&["contains", "2002::/16", "192.0.2.1", "--explain-errors"]);
+    assert!(!success);
+    assert!(stderr.contains("For contains, use --translate"));
+
+    let (_, stderr, success) =
+        run_ipcalc(&["contains", "10.0.0.0/8", "2001:db8::1", "--translate"]);
+    assert!(!success);
+    assert!(stderr.contains("No IPv4/IPv6 mapping applies to 2001:db8::1"));
+    assert!(!stderr.contains("use --translate"));
+}
+
+#[test]
+fn test_contains_translate_6to4() {
+    let (stdout, _, success) = run_ipcalc(&["contains", "2002::/16", "192.0.2.1", "--translate"]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["contained"], true);
+    assert_eq!(json["translation"], "6to4");
+    assert_eq!(json["translated_address"], "2002:c000:201::");
+}
+
+#[test]
+fn test_zonefile_split_24_text() {
+    let (stdout, _, success) =
+        run_ipcalc(&["zonefile", "10.1.0.0/16", "--split-24", "--format", "text"]);
+    assert!(success);
+    let origins = stdout.lines().filter(|l| l.starts_with("$ORIGIN ")).count();
+    assert_eq!(origins, 256);
+    assert!(stdout.contains("$ORIGIN 0.1.10.in-addr.arpa."));
+    assert!(stdout.contains("$ORIGIN 255.1.10.in-addr.arpa."));
+}
+
+#[test]
+fn test_split_shuffle_seed_reproducible() {
+    let args = [
+        "split",
+        "10.0.0.0/16",
+        "-p",
+        "24",
+        "--max",
+        "--shuffle",
+        "--seed",
+        "7",
+    ];
+    let (first, _, success) = run_ipcalc(&args);
+    assert!(success);
+    let (second, _, _) = run_ipcalc(&args);
+    assert_eq!(first, second);
+
+    let json: serde_json::Value = serde_json::from_str(&first).expect("Invalid JSON");
+    assert_eq!(json["shuffled"], true);
+    assert_eq!(json["seed"], 7);
+    assert_ne!(json["subnets"][0]["network_address"], "10.0.0.0");
+}
+
+#[test]
+fn test_ipv6_style_nibble() {
+    let (stdout, _, success) = run_ipcalc(&["2001:db8::/32", "--ipv6-style", "nibble"]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["network_address"], "2001:db8::");
+    assert_eq!(json["style"], "nibble");
+    assert_eq!(
+        json["network_address_styled"],
+        "2.0.0.1:0.d.b.8:0.0.0.0:0.0.0.0:0.0.0.0:0.0.0.0:0.0.0.0:0.0.0.0"
+    );
+}
+
+#[test]
+fn test_split_estimate_matches_generated() {
+    let (stdout, _, success) = run_ipcalc(&["split", "192.168.0.0/22", "-p", "27", "--estimate"]);
+    assert!(success);
+    let estimate: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+
+    let (stdout, _, _) = run_ipcalc(&["split", "192.168.0.0/22", "-p", "27", "--max"]);
+    let generated: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+
+    let count = generated["subnets"].as_array().unwrap().len();
+    assert_eq!(estimate["count"], count.to_string());
+    assert_eq!(estimate["within_limit"], true);
+    assert!(estimate.get("subnets").is_none());
+}
+
+#[test]
+fn test_contains_without_resolve_has_no_ptr_name() {
+    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.1.0/24", "192.168.1.10"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert!(json.get("ptr_name").is_none());
+}
+
+#[test]
+fn test_config_show_reports_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = dir.path().join("main.toml");
+    std::fs::write(&main, "include = [\"extra.toml\"]\nmax_batch_size = 10\n").unwrap();
+    std::fs::write(dir.path().join("extra.toml"), "max_batch_size = 20\n").unwrap();
+    let main = main.to_str().unwrap();
+
+    let (stdout, _, success) =
+        run_ipcalc(&["config", "show", "--config", main, "--format", "json"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["files"].as_array().unwrap().len(), 2);
+    let entries = json["entries"].as_array().unwrap();
+    let batch = entries
+        .iter()
+        .find(|e| e["key"] == "max_batch_size")
+        .unwrap();
+    assert_eq!(batch["value"], 20);
+    assert!(batch["source"].as_str().unwrap().ends_with("extra.toml"));
+
+    let (stdout, _, success) = run_ipcalc(&["config", "show", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("defaults only"));
+}
+
+#[test]
+fn test_config_show_missing_env_var() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.toml");
+    std::fs::write(&path, "ipam_db = \"${IPCALC_TEST_UNSET_DIR}/ipam.db\"\n").unwrap();
+    let (_, stderr, success) = run_ipcalc(&["config", "show", "--config", path.to_str().unwrap()]);
+    assert!(!success);
+    assert!(stderr.contains("IPCALC_TEST_UNSET_DIR"), "{}", stderr);
+}
+
+#[test]
+fn test_require_canonical_flag() {
+    let (stdout, _, success) = run_ipcalc(&["2001:0DB8::/32"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["canonical_input"], false);
+
+    let (_, stderr, success) = run_ipcalc(&["2001:0DB8::/32", "--require-canonical"]);
+    assert!(!success);
+    assert!(stderr.contains("use 2001:db8::/32"), "{}", stderr);
+
+    let (stdout, _, success) =
+        run_ipcalc(&["2001:0DB8::/32", "2001:db8::/48", "--require-canonical"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["non_canonical"], 1);
+}
+
+#[test]
+fn test_plan_split() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "plan-split",
+        "10.0.0.0/8",
+        "--subnets",
+        "10",
+        "--hosts",
+        "200",
+    ]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["new_prefix"], 24);
+    assert_eq!(json["hosts_per_subnet"], "254");
+    assert_eq!(json["available_subnets"], "65536");
+
+    let (_, stderr, success) = run_ipcalc(&[
+        "plan-split",
+        "10.0.0.0/20",
+        "--subnets",
+        "17",
+        "--hosts",
+        "200",
+    ]);
+    assert!(!success);
+    assert!(stderr.contains("only 16 available"), "{}", stderr);
+}
+
+#[test]
+fn test_tree_command() {
+    let (stdout, _, success) = run_ipcalc(&["tree", "10.0.0.0/16", "-p", "20", "-p", "24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["kind"], "split_tree");
+    assert_eq!(json["node_count"], 16 + 256);
+    assert_eq!(json["children"][1]["cidr"], "10.0.16.0/20");
+    assert_eq!(json["children"][1]["children"][15]["cidr"], "10.0.31.0/24");
+    assert!(
+        json["children"][1]["children"][15]
+            .get("children")
+            .is_none()
+    );
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "tree",
+        "10.0.0.0/22",
+        "-p",
+        "23",
+        "-p",
+        "24",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(
+        stdout.contains(
+            "10.0.0.0/22\n  10.0.0.0/23\n    10.0.0.0/24\n    10.0.1.0/24\n  10.0.2.0/23\n"
+        ),
+        "{}",
+        stdout
+    );
+
+    let (_, stderr, success) = run_ipcalc(&["tree", "10.0.0.0/16", "-p", "24", "-p", "20"]);
+    assert!(!success);
+    assert!(
+        stderr.contains("Tree level 2 prefix /20 must be longer than the level above it (/24)"),
+        "{}",
+        stderr
+    );
+
+    let (_, stderr, success) = run_ipcalc(&["tree", "10.0.0.0/8", "-p", "16", "-p", "32"]);
+    assert!(!success);
+    assert!(stderr.contains("exceeds the limit of 100000"), "{}", stderr);
+}
+
+#[test]
+fn test_split_offset_pages() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/8",
+        "-p",
+        "24",
+        "--offset",
+        "5000",
+        "-n",
+        "100",
+    ]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["offset"], 5000);
+    assert_eq!(json["total_available"], "65536");
+    assert_eq!(json["subnets"].as_array().unwrap().len(), 100);
+    assert_eq!(json["subnets"][0]["network_address"], "10.19.136.0");
+
+    // Streamed text numbers subnets by their place in the whole split
+    let (stdout, _, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/8",
+        "-p",
+        "24",
+        "--offset",
+        "5000",
+        "-n",
+        "2",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("Offset: 5000"));
+    assert!(stdout.contains("5001. 10.19.136.0/24"), "{}", stdout);
+
+    let (_, stderr, success) =
+        run_ipcalc(&["split", "10.0.0.0/24", "-p", "26", "--offset", "4", "--max"]);
+    assert!(!success);
+    assert!(stderr.contains("past the last"), "{}", stderr);
+}
+
+#[test]
+fn test_split_limit() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "split",
+        "192.168.0.0/22",
+        "-p",
+        "27",
+        "--offset",
+        "30",
+        "--limit",
+        "5",
+    ]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["returned"], 2);
+    assert_eq!(json["subnets"][1]["network_address"], "192.168.3.224");
+
+    let (_, _, success) = run_ipcalc(&[
+        "split",
+        "192.168.0.0/22",
+        "-p",
+        "27",
+        "--limit",
+        "5",
+        "-n",
+        "5",
+    ]);
+    assert!(!success);
+}
+
+#[test]
+fn test_split_index() {
+    let (stdout, _, success) = run_ipcalc(&["split", "10.0.0.0/16", "-p", "27", "--index", "1000"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["kind"], "ipv4_subnet");
+    assert_eq!(json["network_address"], "10.0.125.0");
+    assert_eq!(json["broadcast_address"], "10.0.125.31");
+
+    let (_, stderr, success) = run_ipcalc(&["split", "10.0.0.0/16", "-p", "27", "--index", "2048"]);
+    assert!(!success);
+    assert!(stderr.contains("out of range"), "{}", stderr);
+
+    let (_, _, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/16",
+        "-p",
+        "27",
+        "--index",
+        "1",
+        "-n",
+        "2",
+    ]);
+    assert!(!success);
+}
+
+#[test]
+fn test_split_by_hosts() {
+    let (stdout, _, success) = run_ipcalc(&["split", "10.0.0.0/22", "--hosts", "100", "--max"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["new_prefix"], 25);
+    assert_eq!(json["hosts_required"], 100);
+    assert_eq!(json["subnets"].as_array().unwrap().len(), 8);
+    assert_eq!(json["subnets"][0]["usable_hosts"], 126);
+
+    // 128 hosts no longer fit a /25 once network and broadcast are excluded
+    let (stdout, _, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/22",
+        "--hosts",
+        "128",
+        "--max",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("New Prefix: /24"));
+
+    let (_, stderr, success) = run_ipcalc(&["split", "10.0.0.0/24", "--hosts", "200", "--max"]);
+    assert!(!success);
+    assert!(stderr.contains("does not split"), "{}", stderr);
+
+    let (_, _, success) = run_ipcalc(&[
+        "split",
+        "10.0.0.0/22",
+        "-p",
+        "24",
+        "--hosts",
+        "100",
+        "--max",
+    ]);
+    assert!(!success);
+}
+
+#[test]
+fn test_summarize_explain() {
+    let cidrs = ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/24", "10.0.3.0/24"];
+    let mut args = vec!["summarize"];
+    args.extend(cidrs);
+    args.push("--explain");
+    let (stdout, _, success) = run_ipcalc(&args);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    let merges = json["explanation"]["merges"].as_array().unwrap();
+    assert_eq!(merges.len(), 3);
+    assert_eq!(merges[2]["parent"], "10.0.0.0/22");
+
+    args.extend(["--format", "text"]);
+    let (stdout, _, success) = run_ipcalc(&args);
+    assert!(success);
+    assert!(stdout.contains("10.0.0.0/23 + 10.0.2.0/23 -> 10.0.0.0/22"));
+
+    let (stdout, _, _) = run_ipcalc(&["summarize", "10.0.0.0/24", "10.0.1.0/24"]);
+    assert!(!stdout.contains("explanation"));
+}
+
+#[test]
+fn test_covers_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let list = dir.path().join("list.txt");
+    std::fs::write(&list, "# more-specifics\n10.0.0.0/17\n\n10.0.128.0/17\n").unwrap();
+    let list = list.to_str().unwrap();
+
+    let (stdout, _, success) = run_ipcalc(&["covers", "10.0.0.0/16", "--by", list]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["exact"], true);
+
+    // Gapped: the report is still printed, but the exit code fails
+    let (stdout, _, success) = run_ipcalc(&["covers", "10.0.0.0/15", "--by", list]);
+    assert!(!success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["gaps"][0], "10.1.0.0/16");
+
+    // Overflowing
+    let (stdout, _, success) = run_ipcalc(&["covers", "10.0.0.0/17", "--by", list]);
+    assert!(!success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["excess"][0], "10.0.128.0/17");
+}
+
+#[test]
+fn test_diff_json_patch() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = dir.path().join("old.txt");
+    let new = dir.path().join("new.txt");
+    std::fs::write(&old, "10.0.0.0/24\n10.0.1.0/24\n").unwrap();
+    std::fs::write(&new, "# planned\n10.0.1.0/24\n10.0.2.0/24\n").unwrap();
+    let (old, new) = (old.to_str().unwrap(), new.to_str().unwrap());
+
+    let (stdout, _, success) = run_ipcalc(&["diff", old, new]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["added"], serde_json::json!(["10.0.2.0/24"]));
+    assert_eq!(json["removed"], serde_json::json!(["10.0.0.0/24"]));
+
+    let (stdout, _, success) = run_ipcalc(&["diff", old, new, "--format", "json-patch"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"op": "remove", "path": "/cidrs/0"},
+            {"op": "add", "path": "/cidrs/1", "value": "10.0.2.0/24"}
+        ])
+    );
+
+    // Other commands reject the patch format
+    let (_, stderr, success) = run_ipcalc(&["10.0.0.0/24", "--format", "json-patch"]);
+    assert!(!success);
+    assert!(stderr.contains("only supported by the diff command"));
+}
+
+#[test]
+fn test_since_reports_only_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot = dir.path().join("split.json");
+    let snapshot = snapshot.to_str().unwrap();
+    let split = |count: &str| {
+        run_ipcalc(&[
+            "split",
+            "10.0.0.0/24",
+            "-p",
+            "26",
+            "-n",
+            count,
+            "--since",
+            snapshot,
+        ])
+    };
+
+    // No snapshot yet: everything is new
+    let (stdout, _, success) = split("2");
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "diff");
+    assert_eq!(
+        json["added"],
+        serde_json::json!(["10.0.0.0/26", "10.0.0.64/26"])
+    );
+
+    // The second run only reports the delta
+    let (stdout, _, success) = split("3");
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["added"], serde_json::json!(["10.0.0.128/26"]));
+    assert_eq!(json["removed"], serde_json::json!([]));
+    assert_eq!(json["unchanged"], 2);
+
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(snapshot).unwrap()).unwrap();
+    assert_eq!(saved["cidrs"].as_array().unwrap().len(), 3);
+
+    // Commands that do not list CIDRs reject it
+    let (_, stderr, success) = run_ipcalc(&["10.0.0.0/24", "--since", snapshot]);
+    assert!(!success);
+    assert!(stderr.contains("--since is only supported"));
+}
+
+#[test]
+fn test_summarize_incremental_update() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("base.json");
+    let add = dir.path().join("add.txt");
+    let remove = dir.path().join("remove.txt");
+    std::fs::write(&add, "10.0.1.0/24\n").unwrap();
+    std::fs::write(&remove, "# decommissioned\n10.0.2.0/25\n").unwrap();
+    let base = base.to_str().unwrap();
+
+    let (_, _, success) = run_ipcalc(&["summarize", "10.0.0.0/24", "10.0.2.0/24", "-o", base]);
+    assert!(success);
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "summarize",
+        "--base",
+        base,
+        "--add",
+        add.to_str().unwrap(),
+        "--remove",
+        remove.to_str().unwrap(),
+    ]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["output_count"], 2);
+    assert_eq!(json["cidrs"][0]["network_address"], "10.0.0.0");
+    assert_eq!(json["cidrs"][0]["prefix_length"], 23);
+    assert_eq!(
+        json["changes"]["added"],
+        serde_json::json!(["10.0.0.0/23", "10.0.2.128/25"])
+    );
+    assert_eq!(
+        json["changes"]["removed"],
+        serde_json::json!(["10.0.0.0/24", "10.0.2.0/24"])
+    );
+
+    // A base that is not a summary result is rejected
+    let (_, stderr, success) = run_ipcalc(&["summarize", "--base", add.to_str().unwrap()]);
+    assert!(!success);
+    assert!(stderr.contains("not a summary result"));
+}
+
+#[test]
+fn test_dualstack_command() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "dualstack",
+        "--v4",
+        "10.20.0.0/16",
+        "--v6",
+        "2001:db8:20::/48",
+        "--split-prefix-v4",
+        "24",
+        "--split-prefix-v6",
+        "64",
+        "-n",
+        "10",
+        "--format",
+        "csv",
+    ]);
+    assert!(success);
+    let rows: Vec<&str> = stdout
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect();
+    assert_eq!(rows.len(), 11);
+    assert!(rows[10].starts_with("10,10.20.9.0,24,"));
+    assert!(rows[10].contains(",2001:db8:20:9::,64,"));
+
+    let (_, stderr, success) = run_ipcalc(&[
+        "dualstack",
+        "--v4",
+        "10.20.0.0/16",
+        "--v6",
+        "2001:db8:20::/62",
+        "--split-prefix-v4",
+        "24",
+        "--split-prefix-v6",
+        "64",
+        "-n",
+        "10",
+    ]);
+    assert!(!success);
+    assert!(stderr.contains("IPv6 supernet ran out"));
+}
+
+#[test]
+fn test_decimal_totals_flag() {
+    let (stdout, _, success) = run_ipcalc(&["::/0"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["total_addresses"], "2^128");
+
+    let (stdout, _, success) = run_ipcalc(&["::/0", "--decimal-totals"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json["total_addresses"],
+        "340282366920938463463374607431768211456"
+    );
+
+    let (stdout, _, success) =
+        run_ipcalc(&["split", "::/0", "-p", "1", "-n", "1", "--decimal-totals"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json["subnets"][0]["total_addresses"],
+        "170141183460469231731687303715884105728"
+    );
+}
+
+#[test]
+fn test_netmask_and_wildcard_input() {
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0/255.255.255.192"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["prefix_length"], 26);
+
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0 0.0.0.255"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["prefix_length"], 24);
+
+    // Unquoted, the mask arrives as a second argument
+    let (stdout, _, success) = run_ipcalc(&["10.1.2.3", "255.255.255.255"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["prefix_length"], 32);
+
+    let (_, stderr, success) = run_ipcalc(&["192.168.1.0/255.0.255.0"]);
+    assert!(!success);
+    assert!(stderr.contains("Invalid netmask"));
+}
+
+#[test]
+fn test_is_usable() {
+    let (stdout, _, success) = run_ipcalc(&["is-usable", "192.168.1.255/24", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("broadcast (not usable)"));
+
+    let (stdout, _, success) = run_ipcalc(&["is-usable", "192.168.1.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["role"], "network");
+    assert_eq!(json["usable"], false);
+
+    let (stdout, _, success) = run_ipcalc(&["is-usable", "10.0.0.1/31", "--p2p-usable", "0"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["role"], "broadcast");
+}
+
+#[test]
+fn test_host_check() {
+    let (stdout, _, success) = run_ipcalc(&["host-check", "192.168.1.0", "192.168.1.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["role"], "network");
+    assert_eq!(json["usable"], false);
+
+    let (stdout, _, success) = run_ipcalc(&["host-check", "192.168.2.9", "192.168.1.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["role"], "outside");
+
+    let (stdout, _, success) = run_ipcalc(&["host-check", "2001:db8::5", "2001:db8::/64"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["usable"], true);
+    assert!(json.get("broadcast_address").is_none());
+}
+
+#[test]
+fn test_summarize_mixed_space_warning() {
+    let (stdout, stderr, success) = run_ipcalc(&["summarize", "10.1.0.0/16", "100.1.0.0/16"]);
+    assert!(success);
+    assert!(stderr.contains("Warning: output contains both private and public space"));
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json["warnings"][0],
+        "output contains both private and public space"
+    );
+
+    // --expect turns the warning into a failure
+    let (stdout, stderr, success) = run_ipcalc(&[
+        "summarize",
+        "10.1.0.0/16",
+        "100.1.0.0/16",
+        "--expect",
+        "private",
+    ]);
+    assert!(!success);
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("100.1.0.0/16 (Public)"));
+
+    let (_, stderr, success) = run_ipcalc(&[
+        "summarize",
+        "10.1.0.0/16",
+        "192.168.0.0/24",
+        "--expect",
+        "private",
+    ]);
+    assert!(success);
+    assert!(!stderr.contains("Warning"));
+}
+
+#[test]
+fn test_overlap_command() {
+    let (stdout, _, success) =
+        run_ipcalc(&["overlap", "10.0.0.0/8", "10.1.0.0/16", "192.168.0.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["overlap_count"], 1);
+    assert_eq!(json["pairs"][0]["first"], "10.0.0.0/8");
+    assert_eq!(json["pairs"][0]["relation"], "contains");
+
+    let (stdout, _, success) =
+        run_ipcalc(&["overlap", "10.1.0.0/16", "10.0.0.0/8", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("10.1.0.0/16 is contained by 10.0.0.0/8"));
+
+    let (stdout, _, success) = run_ipcalc(&["overlaps", "10.0.0.0/24", "10.0.0.9/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["pairs"][0]["relation"], "equal");
+}
+
+#[test]
+fn test_common_supernet_command() {
+    let (stdout, _, success) = run_ipcalc(&["common-supernet", "192.168.1.0/24", "192.168.3.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "ipv4_subnet");
+    assert_eq!(json["network_address"], "192.168.0.0");
+    assert_eq!(json["prefix_length"], 22);
+
+    let (_, stderr, success) = run_ipcalc(&["common-supernet", "10.0.0.0/8", "2001:db8::/32"]);
+    assert!(!success);
+    assert!(stderr.contains("Address family mismatch"));
+}
+
+#[test]
+fn test_supernet_command() {
+    let (stdout, _, success) = run_ipcalc(&["supernet", "10.1.4.0/24", "10.1.7.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["supernet"], "10.1.4.0/22");
+    assert_eq!(json["extra_addresses"], "512");
+
+    let (stdout, _, success) = run_ipcalc(&["supernet", "2001:db8::/32", "fd00::/8"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["supernet"], "::/0");
+
+    let (stdout, _, success) = run_ipcalc(&["supernet", "10.0.0.0/8", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("Supernet:        10.0.0.0/8"));
+
+    let (_, stderr, success) = run_ipcalc(&["supernet", "10.0.0.0/8", "2001:db8::/32"]);
+    assert!(!success);
+    assert!(stderr.contains("Address family mismatch"));
+}
+
+#[test]
+fn test_acl_wildcard_command() {
+    let (stdout, _, success) = run_ipcalc(&["acl-wildcard", "10.0.0.0/16"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "acl_wildcard");
+    assert_eq!(json["base"], "10.0.0.0");
+    assert_eq!(json["wildcard"], "0.0.255.255");
+    assert_eq!(json["snippet"], "permit ip 10.0.0.0 0.0.255.255");
+
+    let (stdout, _, success) = run_ipcalc(&["acl-wildcard", "192.168.1.64/26", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("Wildcard: 0.0.0.63"));
+    assert!(stdout.contains("permit ip 192.168.1.64 0.0.0.63"));
+}
+
+#[test]
+fn test_lookup_command() {
+    let cidrs = [
+        "10.0.0.0/8",
+        "10.1.1.0/24",
+        "10.1.0.0/16",
+        "10.1.0.0/24",
+        "10.2.0.0/16",
+    ];
+    let mut args = vec!["lookup", "10.1.0.0/16"];
+    args.extend(cidrs);
+    let (stdout, _, success) = run_ipcalc(&args);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "lookup");
+    assert_eq!(json["order"], "longest-first");
+    let matches: Vec<&str> = json["matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["cidr"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        matches,
+        ["10.1.0.0/24", "10.1.1.0/24", "10.1.0.0/16", "10.0.0.0/8"]
+    );
+
+    args.extend(["--shortest-first", "--max-results", "3", "--format", "text"]);
+    let (stdout, _, success) = run_ipcalc(&args);
+    assert!(success);
+    assert!(stdout.contains("Matches: 4 (showing 3)"));
+    assert!(stdout.contains("1. 10.0.0.0/8"));
+    assert!(stdout.contains("3. 10.1.0.0/24"));
+    assert!(!stdout.contains("10.1.1.0/24"));
+}
+
+#[test]
+fn test_env_format_evals_in_shell() {
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "--format", "env"]);
+    assert!(success);
+    assert!(stdout.contains("IPCALC_NETWORK_ADDRESS=192.168.1.0\n"));
+
+    // The assignments must survive a real shell, including quoted values
+    let script = format!(
+        "{}\nprintf '%s|%s|%s' \"$IPCALC_NETWORK_ADDRESS\" \"$IPCALC_BROADCAST_ADDRESS\" \"$IPCALC_ADDRESS_TYPE\"",
+        stdout
+    );
+    let output = Command::new("sh").args(["-c", &script]).output().unwrap();
+    assert!(output.status.success());
+    let printed = String::from_utf8_lossy(&output.stdout).to_string();
+    let fields: Vec<&str> = printed.split('|').collect();
+    assert_eq!(fields[0], "192.168.1.0");
+    assert_eq!(fields[1], "192.168.1.255");
+    assert!(fields[2].contains(' '));
+
+    let (stdout, _, success) = run_ipcalc(&["10.0.0.0/8", "bogus", "--format", "env"]);
+    assert!(success);
+    assert!(stdout.contains("IPCALC_COUNT=2\n"));
+    assert!(stdout.contains("IPCALC_0_NETWORK_ADDRESS=10.0.0.0\n"));
+    assert!(stdout.contains("IPCALC_1_ERROR="));
+}
+
+#[test]
+fn test_classic_format_matches_golden_files() {
+    let cases = [
+        (
+            "192.168.0.1/24",
+            include_str!("fixtures/classic/192.168.0.1_24.txt"),
+        ),
+        (
+            "10.0.0.77/26",
+            include_str!("fixtures/classic/10.0.0.77_26.txt"),
+        ),
+        (
+            "10.0.0.0/31",
+            include_str!("fixtures/classic/10.0.0.0_31.txt"),
+        ),
+        (
+            "172.16.5.4/32",
+            include_str!("fixtures/classic/172.16.5.4_32.txt"),
+        ),
+        (
+            "2001:db8::1/48",
+            include_str!("fixtures/classic/2001_db8__1_48.txt"),
+        ),
+    ];
+    for (cidr, expected) in cases {
+        let (stdout, stderr, success) = run_ipcalc(&[cidr, "--format", "classic"]);
+        assert!(success, "{}: {}", cidr, stderr);
+        assert_eq!(stdout, expected, "classic output for {}", cidr);
+    }
+
+    let (_, stderr, success) = run_ipcalc(&[
+        "summarize",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+        "--format",
+        "classic",
+    ]);
+    assert!(!success);
+    assert!(stderr.contains("only supported for subnet calculations"));
+}
+
+#[test]
+fn test_next_and_prev_subnet_commands() {
+    let (stdout, _, success) = run_ipcalc(&["next-subnet", "192.168.1.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "ipv4_subnet");
+    assert_eq!(json["network_address"], "192.168.2.0");
+
+    let (stdout, _, success) = run_ipcalc(&["prev-subnet", "2001:db8:1::/48", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("2001:db8::"));
+
+    let (_, stderr, success) = run_ipcalc(&["prev-subnet", "0.0.0.0/8"]);
+    assert!(!success);
+    assert!(stderr.contains("no previous subnet"));
+}
+
+#[test]
+fn test_lpm_command() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "lpm",
+        "10.1.2.3",
+        "10.0.0.0/8",
+        "10.1.0.0/16",
+        "10.1.2.0/24",
+    ]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "lpm");
+    assert_eq!(json["matched"], "10.1.2.0/24");
+    assert_eq!(json["prefix_length"], 24);
+
+    let (stdout, _, success) = run_ipcalc(&["lpm", "192.0.2.1", "10.0.0.0/8", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("Match:      no match"));
+
+    let (stdout, _, success) =
+        run_ipcalc(&["match", "2001:db8:1::5", "2001:db8::/32", "2001:db8:1::/48"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["matched"], "2001:db8:1::/48");
+
+    let (_, stderr, success) = run_ipcalc(&["match", "10.1.2.3", "10.0.0.0/8", "2001:db8::/32"]);
+    assert!(!success);
+    assert!(stderr.contains("Address family mismatch: 10.1.2.3 and 2001:db8::/32"));
+}
+
+#[test]
+fn test_expand_and_compress_commands() {
+    let (stdout, _, success) = run_ipcalc(&["expand", "2001:db8::1"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "ipv6_format");
+    assert_eq!(json["expanded"], "2001:0db8:0000:0000:0000:0000:0000:0001");
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "compress",
+        "2001:0DB8:0000:0000:0001:0000:0000:0001",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("Compressed: 2001:db8::1:0:0:1"));
+
+    let (_, _, success) = run_ipcalc(&["expand", "10.0.0.1"]);
+    assert!(!success);
+}
+
+#[test]
+fn test_eui64_command() {
+    let (stdout, _, success) = run_ipcalc(&["eui64", "2001:db8::/64", "00-1A-2B-3C-4D-5E"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "eui64");
+    assert_eq!(json["mac"], "00:1a:2b:3c:4d:5e");
+    assert_eq!(json["derived_address"], "2001:db8::21a:2bff:fe3c:4d5e");
+
+    let (_, stderr, success) = run_ipcalc(&["eui64", "2001:db8::/64", "00:1a:2b:3c:4d"]);
+    assert!(!success);
+    assert!(stderr.contains("Invalid MAC address"));
+}
+
+#[test]
+fn test_ifid_command() {
+    let (stdout, _, success) = run_ipcalc(&["ifid", "2001:db8::1234:5678:9abc:def0/64"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "ifid");
+    assert_eq!(json["network"], "2001:db8::/64");
+    assert_eq!(json["interface_id"], "1234:5678:9abc:def0");
+
+    let (stdout, _, success) = run_ipcalc(&["ifid", "2001:db8:0:12ab:1:2:3:4/56"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["network"], "2001:db8:0:1200::/56");
+    assert_eq!(json["interface_id"], "ab:1:2:3:4");
+
+    let (_, _, success) = run_ipcalc(&["ifid", "2001:db8::1"]);
+    assert!(!success);
+}
+
+#[test]
+fn test_exclude_command() {
+    let (stdout, _, success) = run_ipcalc(&["exclude", "10.0.0.0/8", "10.1.0.0/16", "10.2.0.0/16"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "exclude");
+    assert_eq!(json["cidr_count"], 8);
+    assert_eq!(json["cidrs"][1]["network_address"], "10.3.0.0");
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "exclude",
+        "2001:db8::/46",
+        "2001:db8:1::/48",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("2. 2001:db8:2::/47"));
+
+    let (stdout, _, success) = run_ipcalc(&["exclude", "10.0.0.0/8", "11.0.0.0/16"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["cidrs"][0]["network_address"], "10.0.0.0");
+    assert_eq!(json["cidrs"][0]["prefix_length"], 8);
+    assert_eq!(json["ignored"][0], "11.0.0.0/16");
+
+    let (_, stderr, success) = run_ipcalc(&["exclude", "10.0.0.0/24", "10.0.0.0/16"]);
+    assert!(!success);
+    assert!(stderr.contains("not contained"));
+}
+
+#[test]
+fn test_walk_command() {
+    let (stdout, _, success) = run_ipcalc(&["walk", "10.0.0.0/24", "10.0.5.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "walk");
+    assert_eq!(json["count"], 6);
+    assert_eq!(json["subnets"][5]["network_address"], "10.0.5.0");
+
+    let (_, stderr, success) = run_ipcalc(&["walk", "10.0.0.0/24", "10.0.5.0/23"]);
+    assert!(!success);
+    assert!(stderr.contains("different prefix lengths"));
+}
+
+#[test]
+fn test_add_command() {
+    let (stdout, _, success) = run_ipcalc(&["add", "10.0.0.0", "256", "--format", "text"]);
+    assert!(success);
+    assert_eq!(stdout.trim(), "10.0.1.0");
+
+    let (stdout, _, success) = run_ipcalc(&["add", "10.0.0.0", "-1"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "add");
+    assert_eq!(json["result"], "9.255.255.255");
+
+    let (stdout, _, success) = run_ipcalc(&["add", "2001:db8::", "-1", "--format", "text"]);
+    assert!(success);
+    assert_eq!(stdout.trim(), "2001:db7:ffff:ffff:ffff:ffff:ffff:ffff");
+
+    let (_, stderr, success) = run_ipcalc(&["add", "0.0.0.0", "-1"]);
+    assert!(!success);
+    assert!(stderr.contains("outside the address space"));
+}
+
+#[test]
+fn test_hosts_command() {
+    let (stdout, stderr, success) =
+        run_ipcalc(&["hosts", "192.168.1.0/29", "--max", "6", "--format", "text"]);
+    assert!(success);
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines.first(), Some(&"192.168.1.1"));
+    assert_eq!(lines.len(), 6);
+    assert!(stderr.is_empty());
+
+    let (stdout, stderr, success) = run_ipcalc(&["hosts", "10.0.0.0/24", "--max", "3"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "hosts");
+    assert_eq!(json["count"], 3);
+    assert_eq!(json["truncated"], true);
+    assert!(stderr.contains("first 3 hosts"));
+}
+
+#[test]
+fn test_nth_host_command() {
+    let (stdout, _, success) = run_ipcalc(&["nth-host", "10.0.0.0/24", "5"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "nth_host");
+    assert_eq!(json["cidr"], "10.0.0.0/24");
+    assert_eq!(json["n"], 5);
+    assert_eq!(json["address"], "10.0.0.6");
+
+    let (stdout, _, success) = run_ipcalc(&["nth-host", "2001:db8::/64", "7"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["n"], "7");
+    assert_eq!(json["address"], "2001:db8::8");
+
+    let (stdout, _, success) = run_ipcalc(&["nth-host", "2001:db8::/64", "0", "--format", "text"]);
+    assert!(success);
+    assert_eq!(stdout.trim(), "2001:db8::1");
+
+    let (_, stderr, success) = run_ipcalc(&["nth-host", "10.0.0.0/30", "2"]);
+    assert!(!success);
+    assert!(stderr.contains("out of range"));
+}
+
+#[test]
+fn test_vlsm_command() {
+    let (stdout, _, success) = run_ipcalc(&["vlsm", "10.0.0.0/24", "20", "100", "10", "50"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["allocations"][0]["hosts"], 100);
+    assert_eq!(
+        json["allocations"][1]["subnet"]["network_address"],
+        "10.0.0.128"
+    );
+    assert_eq!(json["remaining_addresses"], 16);
+
+    let (stdout, _, success) = run_ipcalc(&["vlsm", "10.0.0.0/24", "--hosts", "100,50,20,5"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json["allocations"][3]["subnet"]["network_address"],
+        "10.0.0.224"
+    );
+    assert_eq!(json["allocations"][3]["waste"], 1);
+    assert_eq!(json["free"][0], "10.0.0.232/29");
+
+    let (stdout, _, success) = run_ipcalc(&["vlsm", "10.0.0.0/24", "100", "50", "--format", "csv"]);
+    assert!(success);
+    assert!(stdout.contains("50,10.0.0.128/26,26,10.0.0.129,10.0.0.190,62,64,12"));
+
+    let (_, stderr, success) = run_ipcalc(&["vlsm", "10.0.0.0/25", "100", "50"]);
+    assert!(!success);
+    assert!(stderr.contains("cannot fit"));
+
+    // Two hosts get the same block `split --hosts 2` would pick
+    let (stdout, _, success) = run_ipcalc(&["vlsm", "10.0.0.0/29", "2", "--p2p-usable", "0"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["allocations"][0]["prefix"], 30);
+    let (stdout, _, success) = run_ipcalc(&["vlsm", "10.0.0.0/29", "2"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["allocations"][0]["prefix"], 31);
+}
+
+#[test]
+fn test_reverse_dns_command() {
+    let (stdout, _, success) = run_ipcalc(&["reverse-dns", "192.0.2.128/25"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "reverse_dns");
+    assert_eq!(
+        json["zones"],
+        serde_json::json!(["128/25.2.0.192.in-addr.arpa"])
+    );
+    assert_eq!(
+        json["records"][0]["name"],
+        "129.128/25.2.0.192.in-addr.arpa"
+    );
+
+    let (stdout, _, success) = run_ipcalc(&["reverse-dns", "2001:db8::/48", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("Zone:    0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"));
+
+    let (stdout, _, success) = run_ipcalc(&["reverse-dns", "2001:db8::/47", "--format", "text"]);
+    assert!(success);
+    assert!(stdout.contains("Zone:    0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"));
+    assert!(stdout.contains("Zone:    1.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"));
+
+    let (stdout, _, success) = run_ipcalc(&["reverse-dns", "192.0.2.0/30", "--format", "csv"]);
+    assert!(success);
+    assert!(stdout.contains("192.0.2.1,1.0/30.2.0.192.in-addr.arpa"));
+}
+
+#[test]
+fn test_result_kind_field() {
+    for (args, kind) in [
+        (&["192.168.1.0/24"][..], "ipv4_subnet"),
+        (&["2001:db8::/32"][..], "ipv6_subnet"),
+        (
+            &["split", "10.0.0.0/24", "-p", "26", "-n", "2"][..],
+            "split",
+        ),
+        (&["contains", "10.0.0.0/8", "10.1.2.3"][..], "contains"),
+        (&["summarize", "10.0.0.0/24", "10.0.1.0/24"][..], "summary"),
+        (&["from-range", "10.0.0.0", "10.0.0.255"][..], "from_range"),
+        (&["10.0.0.0/24", "10.0.1.0/24"][..], "batch"),
+    ] {
+        let (stdout, stderr, success) = run_ipcalc(args);
+        assert!(success, "{args:?}: {stderr}");
+        let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+        assert_eq!(json["kind"], kind, "{args:?}");
+        // Written first so line-oriented consumers can dispatch on it
+        assert!(
+            stdout.starts_with(&format!("{{\n  \"kind\": \"{kind}\"")),
+            "{stdout}"
+        );
+    }
+
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "--format", "yaml"]);
+    assert!(success);
+    assert!(stdout.starts_with("kind: ipv4_subnet\n"));
+
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "--no-kind"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert!(json.get("kind").is_none());
+    assert_eq!(json["network_address"], "192.168.1.0");
+}
+
+#[test]
+fn test_validate_output_needs_schema_feature() {
+    // The default build leaves out the `schema` feature
+    let (stdout, stderr, success) = run_ipcalc(&["192.168.1.0/24", "--validate-output"]);
+    assert!(!success);
+    assert!(stdout.is_empty());
+    assert!(
+        stderr.contains("Output validation is not available in this build"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_json_case_camel() {
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "--json-case", "camel"]);
     assert!(success);
-
     let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
-    assert_eq!(json["cidr"], "192.168.1.0/24");
-    assert_eq!(json["address"], "192.168.1.100");
-    assert_eq!(json["contained"], true);
-    assert_eq!(json["network_address"], "192.168.1.0");
-    assert_eq!(json["broadcast_address"], "192.168.1.255");
+    assert_eq!(json["networkAddress"], "192.168.1.0");
+    assert_eq!(json["prefixLength"], 24);
+    assert!(json.get("network_address").is_none());
+    // Field order is kept
+    assert!(stdout.starts_with("{\n  \"kind\": \"ipv4_subnet\",\n  \"input\""));
+
+    // Text output is unaffected
+    let (stdout, _, success) =
+        run_ipcalc(&["192.168.1.0/24", "--json-case", "camel", "--format", "text"]);
+    assert!(success);
+    assert!(!stdout.contains("networkAddress"));
 }
 
 #[test]
-fn test_contains_ipv4_not_contained() {
-    let (stdout, _, success) = run_ipcalc(&["contains", "192.168.1.0/24", "10.0.0.1"]);
+fn test_p2p_usable_flag() {
+    let (stdout, _, success) = run_ipcalc(&["10.0.0.0/31"]);
     assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["rfc3021"], true);
+    assert_eq!(json["usable_hosts"], 2);
 
+    let (stdout, _, success) = run_ipcalc(&["10.0.0.0/31", "--p2p-usable", "0"]);
+    assert!(success);
     let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
-    assert_eq!(json["contained"], false);
+    assert_eq!(json["usable_hosts"], 0);
+
+    let (stdout, _, _) = run_ipcalc(&["10.0.0.0/31", "--format", "text"]);
+    assert!(stdout.contains("(point-to-point, RFC 3021)"));
 }
 
 #[test]
-fn test_contains_ipv6_json() {
-    let (stdout, _, success) = run_ipcalc(&["contains", "2001:db8::/32", "2001:db8::1"]);
+fn test_display_flags_apply_to_every_command() {
+    let (stdout, _, success) =
+        run_ipcalc(&["from-range", "10.0.0.0", "10.0.0.1", "--p2p-usable", "0"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["cidrs"][0]["usable_hosts"], 0);
+
+    let (stdout, _, success) =
+        run_ipcalc(&["walk", "10.0.0.0/31", "10.0.0.2/31", "--p2p-usable", "0"]);
     assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["subnets"][1]["usable_hosts"], 0);
 
+    let (stdout, _, success) = run_ipcalc(&["next-subnet", "192.0.0.8/32", "--rfc6890"]);
+    assert!(success);
     let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
-    assert_eq!(json["contained"], true);
-    assert_eq!(json["address"], "2001:db8::1");
-}
+    assert!(json["special_purpose"].is_object(), "{}", stdout);
 
-#[test]
-fn test_contains_ipv4_text() {
     let (stdout, _, success) = run_ipcalc(&[
-        "contains",
-        "192.168.1.0/24",
-        "192.168.1.100",
-        "--format",
-        "text",
+        "from-range",
+        "::",
+        "::ffff:ffff:ffff:ffff:ffff:ffff:ffff",
+        "--decimal-totals",
     ]);
     assert!(success);
-    assert!(stdout.contains("Address Containment Check"));
-    assert!(stdout.contains("Contained:         Yes"));
-    assert!(stdout.contains("Network Address:   192.168.1.0"));
-}
-
-#[test]
-fn test_contains_invalid_address() {
-    let (_, stderr, success) = run_ipcalc(&["contains", "192.168.1.0/24", "not-an-ip"]);
-    assert!(!success);
-    assert!(stderr.contains("Error"));
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(
+        json["cidrs"][0]["total_addresses"],
+        "5192296858534827628530496329220096"
+    );
 }
 
 #[test]
@@ -334,6 +2029,40 @@ fn test_summarize_ipv6_json() {
     assert_eq!(json["cidrs"][0]["prefix_length"], 47);
 }
 
+#[test]
+fn test_summarize_file_extract_junos() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "summarize",
+        "--file",
+        "tests/fixtures/routes/junos.txt",
+        "--extract",
+    ]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["input_count"], 2);
+    assert_eq!(json["cidrs"][0]["network_address"], "10.2.0.0");
+    assert_eq!(json["cidrs"][0]["prefix_length"], 23);
+    assert_eq!(json["extraction"]["extracted_lines"], 2);
+    assert_eq!(json["extraction"]["skipped_lines"], 7);
+}
+
+#[test]
+fn test_addresses_overlapping_union() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "addresses",
+        "10.0.0.0/24",
+        "10.0.0.128/25",
+        "192.168.0.0/24",
+    ]);
+    assert!(success);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["input_count"], 3);
+    assert_eq!(json["block_count"], 2);
+    assert_eq!(json["address_count"], "512");
+}
+
 #[test]
 fn test_summarize_empty() {
     let (_, stderr, success) = run_ipcalc(&["summarize"]);
@@ -428,6 +2157,75 @@ fn test_batch_mixed_v4_v6() {
     assert_eq!(json["results"][1]["subnet"]["version"], "v6");
 }
 
+#[test]
+fn test_v6_case_upper_only_touches_v6() {
+    let args = ["192.168.1.0/24", "2001:db8:abcd::/48", "10.0.0.0/8"];
+    let (lower, _, success) = run_ipcalc(&args);
+    assert!(success);
+    let (upper, _, success) = run_ipcalc(&[&args[..], &["--v6-case", "upper"]].concat());
+    assert!(success);
+
+    let lower: serde_json::Value = serde_json::from_str(&lower).expect("Invalid JSON");
+    let upper: serde_json::Value = serde_json::from_str(&upper).expect("Invalid JSON");
+    // The IPv4 entries are untouched
+    assert_eq!(upper["results"][0], lower["results"][0]);
+    assert_eq!(upper["results"][2], lower["results"][2]);
+
+    let v6 = &upper["results"][1]["subnet"];
+    assert_ne!(*v6, lower["results"][1]["subnet"]);
+    assert_eq!(v6["case"], "upper");
+    assert_eq!(v6["network_address"], "2001:DB8:ABCD::");
+    assert_eq!(v6["last_address"], "2001:DB8:ABCD:FFFF:FFFF:FFFF:FFFF:FFFF");
+    assert_eq!(
+        v6["network_address_full"],
+        "2001:0DB8:ABCD:0000:0000:0000:0000:0000"
+    );
+    assert_eq!(v6["hextets"][2], "ABCD");
+    assert_eq!(v6["input"], "2001:DB8:ABCD::/48");
+    assert_eq!(v6["canonical_input"], true);
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "2001:db8:abcd::/48",
+        "--v6-case",
+        "upper",
+        "--format",
+        "csv",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("2001:0DB8:ABCD:FFFF:FFFF:FFFF:FFFF:FFFF"));
+    assert!(!stdout.contains("2001:db8"));
+}
+
+#[test]
+fn test_v6_case_upper_covers_derived_blocks() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "exclude",
+        "2001:db8:abcd::/48",
+        "2001:db8:abcd::/50",
+        "--v6-case",
+        "upper",
+        "--format",
+        "csv",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("2001:DB8:ABCD:4000::/50,2001:DB8:ABCD:4000::,"));
+    assert!(!stdout.contains("2001:db8"));
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "gaps",
+        "2001:db8::/48",
+        "--used",
+        "2001:db8::/50",
+        "--v6-case",
+        "upper",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("2001:DB8:0:4000::/50"));
+    assert!(!stdout.contains("2001:db8"));
+}
+
 #[test]
 fn test_batch_with_invalid_cidr() {
     let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "not-valid", "10.0.0.0/8"]);
@@ -441,6 +2239,169 @@ fn test_batch_with_invalid_cidr() {
     assert!(json["results"][2]["subnet"].is_object());
 }
 
+#[test]
+fn test_batch_only_errors_and_only_ok() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "192.168.1.0/24",
+        "not-valid",
+        "10.0.0.0/8",
+        "--only-errors",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines.len(), 1, "{}", stdout);
+    assert!(lines[0].starts_with("not-valid: "), "{}", stdout);
+
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "not-valid", "--only-ok"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["count"], 2);
+    assert_eq!(json["shown_count"], 1);
+    assert_eq!(json["filter"], "ok");
+    assert_eq!(json["results"][0]["cidr"], "192.168.1.0/24");
+
+    // A filter on a single CIDR still reports in batch form
+    let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "--only-errors"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["shown_count"], 0);
+
+    let (_, _, success) = run_ipcalc(&["192.168.1.0/24", "x", "--only-errors", "--only-ok"]);
+    assert!(!success);
+}
+
+#[test]
+fn test_batch_ignore() {
+    let dir = tempfile::tempdir().unwrap();
+    let ignore = dir.path().join("ignore.txt");
+    std::fs::write(
+        &ignore,
+        "# noise
+203.0.113.0/24
+",
+    )
+    .unwrap();
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "10.1.0.0/16",
+        "8.8.8.0/24",
+        "203.0.113.7",
+        "192.168.1.0/24",
+        "--ignore",
+        "10.0.0.0/8",
+        "--ignore-file",
+        ignore.to_str().unwrap(),
+    ]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["count"], 2);
+    assert_eq!(json["ignored_count"], 2);
+    assert_eq!(
+        json["ignored"],
+        serde_json::json!(["10.1.0.0/16", "203.0.113.7"])
+    );
+
+    // The special-purpose shorthand, on a single CIDR, still reports in batch form
+    let (stdout, _, success) = run_ipcalc(&["169.254.0.0/16", "--ignore-special"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["count"], 0);
+    assert_eq!(json["ignored_count"], 1);
+
+    let (_, stderr, success) = run_ipcalc(&["10.0.0.0/8", "8.8.8.8", "--ignore", "bogus"]);
+    assert!(!success);
+    assert!(stderr.contains("Error"));
+}
+
+#[test]
+fn test_batch_with_summary() {
+    let (stdout, _, success) = run_ipcalc(&["10.0.0.0/24", "10.0.1.0/24", "--with-summary"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["count"], 2);
+    assert_eq!(json["aggregate_summary"]["ipv4"][0], "10.0.0.0/23");
+    assert!(json["aggregate_summary"]["ipv6"].is_null());
+
+    // A single CIDR switches to batch output
+    let (stdout, _, success) = run_ipcalc(&["2001:db8::/48", "--with-summary"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["aggregate_summary"]["ipv6"][0], "2001:db8::/48");
+
+    // Without the flag there is no summary
+    let (stdout, _, _) = run_ipcalc(&["10.0.0.0/24", "10.0.1.0/24"]);
+    assert!(!stdout.contains("aggregate_summary"));
+}
+
+#[test]
+fn test_bare_address_input() {
+    let (stdout, _, success) = run_ipcalc(&["8.8.8.8"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["prefix_length"], 32);
+    assert_eq!(json["address_type"], "Public");
+
+    let (stdout, _, success) = run_ipcalc(&["2001:db8::1"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["prefix_length"], 128);
+
+    // Mixed bare addresses and CIDRs in a stdin batch
+    let (stdout, _, success) =
+        run_ipcalc_stdin(&["--stdin"], "10.0.0.1\n192.168.0.0/24\nfe80::1\n");
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["count"], 3);
+    assert_eq!(json["results"][0]["subnet"]["prefix_length"], 32);
+    assert_eq!(json["results"][1]["subnet"]["prefix_length"], 24);
+    assert_eq!(json["results"][2]["subnet"]["prefix_length"], 128);
+}
+
+#[test]
+fn test_single_cidr_keeps_tags() {
+    let (stdout, _, success) = run_ipcalc(&["10.0.0.0/24 # role=web bogus"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["count"], 1);
+    assert_eq!(json["results"][0]["tags"]["role"], "web");
+    assert!(
+        json["results"][0]["warnings"][0]
+            .as_str()
+            .unwrap()
+            .contains("'bogus'")
+    );
+
+    let (stdout, stderr, success) = run_ipcalc_stdin(&["--stdin"], "10.0.0.0/24 # role=web\n");
+    assert!(success);
+    assert!(stderr.is_empty(), "{}", stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["results"][0]["tags"]["role"], "web");
+
+    // Without tags, a single CIDR keeps its flat output
+    let (stdout, _, success) = run_ipcalc(&["10.0.0.0/24 #"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON");
+    assert_eq!(json["network_address"], "10.0.0.0");
+}
+
+#[test]
+fn test_batch_cache_hits() {
+    let input = "10.0.0.0/24\n10.0.0.0/24 # role=web\n10.0.1.0/24\n10.0.0.0/24\n";
+    let (cached, _, success) = run_ipcalc_stdin(&["--stdin"], input);
+    assert!(success);
+    let cached: serde_json::Value = serde_json::from_str(&cached).unwrap();
+    assert_eq!(cached["cache_hits"], 2);
+    assert_eq!(cached["results"][1]["tags"]["role"], "web");
+
+    let (uncached, _, success) = run_ipcalc_stdin(&["--stdin", "--no-batch-cache"], input);
+    assert!(success);
+    let uncached: serde_json::Value = serde_json::from_str(&uncached).unwrap();
+    assert_eq!(uncached["cache_hits"], 0);
+    assert_eq!(cached["results"], uncached["results"]);
+}
+
 #[test]
 fn test_batch_text_output() {
     let (stdout, _, success) = run_ipcalc(&["192.168.1.0/24", "10.0.0.0/8", "--format", "text"]);
@@ -752,6 +2713,39 @@ fn test_ipam_utilization_and_free_blocks() {
     let _ = std::fs::remove_file(db);
 }
 
+#[test]
+fn test_ipam_utilization_bar() {
+    let db = "/tmp/ipcalc-test-util-bar.db";
+    let _ = std::fs::remove_file(db);
+
+    let (stdout, _, _) = run_ipam(db, &["supernet", "create", "10.0.0.0/24"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let sn_id = json["id"].as_str().unwrap().to_string();
+
+    run_ipam(db, &["allocate", &sn_id, "10.0.0.0/26"]);
+
+    let (stdout, _, success) = run_ipam(
+        db,
+        &[
+            "utilization",
+            &sn_id,
+            "--bar",
+            "--bar-width",
+            "8",
+            "--format",
+            "text",
+        ],
+    );
+    assert!(success);
+    assert!(stdout.contains("[##------] 25%"));
+
+    // Without --bar the text report is unchanged
+    let (stdout, _, _) = run_ipam(db, &["utilization", &sn_id, "--format", "text"]);
+    assert!(!stdout.contains("Usage:"));
+
+    let _ = std::fs::remove_file(db);
+}
+
 #[test]
 fn test_ipam_find_ip() {
     let db = "/tmp/ipcalc-test-findip.db";
@@ -880,3 +2874,129 @@ fn test_ipam_csv_output() {
 
     let _ = std::fs::remove_file(db);
 }
+
+#[test]
+fn test_gaps_command() {
+    let (stdout, _, success) =
+        run_ipcalc(&["gaps", "10.0.0.0/16", "--used", "10.0.0.0/24,10.0.4.0/22"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "gaps");
+    assert_eq!(json["free_cidr_count"], 7);
+    assert_eq!(json["free_addresses"], "64256");
+    assert_eq!(json["largest_free_block"]["first"], "10.0.8.0");
+
+    let (stdout, _, success) = run_ipcalc(&[
+        "gaps",
+        "2001:db8::/46",
+        "--used",
+        "2001:db8:1::/48",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("2. 2001:db8:2::/47"));
+
+    let (_, stderr, success) = run_ipcalc(&["gaps", "10.0.0.0/16", "--used", "10.1.0.0/24"]);
+    assert!(!success);
+    assert!(stderr.contains("allocation 10.1.0.0/24 is not inside 10.0.0.0/16"));
+}
+
+#[test]
+fn test_free_space_command() {
+    let (stdout, _, success) =
+        run_ipcalc(&["free-space", "10.0.0.0/16", "10.0.1.0/24", "10.0.5.0/24"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "free_space");
+    assert_eq!(json["allocated_count"], 2);
+    assert_eq!(json["free_count"], 9);
+    assert_eq!(json["free_cidrs"][0], "10.0.0.0/24");
+    assert!(json.get("clamped").is_none());
+
+    // Allocations outside the parent are clamped, not rejected
+    let (stdout, _, success) = run_ipcalc(&[
+        "free-space",
+        "10.0.0.0/24",
+        "10.0.1.0/24",
+        "10.0.0.0/25",
+        "--format",
+        "text",
+    ]);
+    assert!(success);
+    assert!(stdout.contains("Clamped:    10.0.1.0/24"));
+    assert!(stdout.contains("1. 10.0.0.128/25"));
+}
+
+#[test]
+fn test_allocate_command() {
+    let (stdout, _, success) = run_ipcalc(&[
+        "allocate",
+        "10.0.0.0/16",
+        "-p",
+        "24",
+        "--used",
+        "10.0.0.0/24",
+        "--used",
+        "10.0.1.0/24",
+        "--count",
+        "3",
+    ]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "allocate");
+    assert_eq!(json["allocated"][0], "10.0.2.0/24");
+    assert_eq!(json["allocated"][2], "10.0.4.0/24");
+
+    let (_, stderr, success) = run_ipcalc(&[
+        "allocate",
+        "10.0.0.0/24",
+        "-p",
+        "24",
+        "--used",
+        "10.0.0.9/32",
+    ]);
+    assert!(!success);
+    assert!(stderr.contains("No free space in 10.0.0.0/24 for a /24 allocation"));
+}
+
+#[test]
+fn test_defaults_command() {
+    let (stdout, _, success) = run_ipcalc(&["defaults"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "defaults");
+    let batch = json["defaults"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["name"] == "max_batch_size")
+        .unwrap();
+    assert_eq!(batch["value"], 10_000);
+    assert_eq!(batch["module"], "batch");
+
+    let (stdout, _, success) = run_ipcalc(&["defaults", "--format", "env"]);
+    assert!(success);
+    assert!(stdout.contains("IPCALC_MAX_INPUT_LENGTH=256\n"));
+}
+
+#[test]
+fn test_serve_dry_run() {
+    let (stdout, _, success) = run_ipcalc(&["serve", "--port", "0", "--dry-run"]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["kind"], "serve");
+    assert!(json["address"].as_str().unwrap().starts_with("127.0.0.1:"));
+    assert_ne!(json["address"], "127.0.0.1:0");
+    assert!(json["endpoint_count"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn test_serve_dry_run_port_conflict() {
+    let held = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = held.local_addr().unwrap().port().to_string();
+    let (stdout, stderr, success) = run_ipcalc(&["serve", "--port", &port, "--dry-run"]);
+    assert!(!success);
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Server startup failed at bind"));
+}